@@ -7,7 +7,7 @@
 //! - With the Tauri app via **named pipe** (length-prefixed JSON) for fast IPC
 //!
 //! Environment variables:
-//! - `VOICE_MIRROR_DATA_DIR` — path to the MCP data directory (inbox.json, status.json, etc.)
+//! - `VOICE_MIRROR_DATA_DIR` — path to the MCP data directory (inbox.db, status.json, etc.)
 //! - `VOICE_MIRROR_PIPE` — named pipe path for fast IPC (optional; falls back to file-based)
 //! - `ENABLED_GROUPS` — comma-separated tool groups to load on startup
 
@@ -4,12 +4,16 @@
 //! They delegate to the `AiManager` held in Tauri's managed state.
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use std::time::Instant;
 
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 use crate::providers::cli::scan_available_providers;
 use crate::providers::manager::AiManager;
+use crate::providers::tool_calling::{ToolPlanPhase, ToolPlanStep};
+use crate::providers::turn_queue::{self, Admission, PendingTurn, TurnPolicy, TurnQueue};
 use crate::providers::{is_cli_provider, ProviderConfig};
 
 use super::IpcResponse;
@@ -93,6 +97,7 @@ pub fn start_ai(
         mcp_preferences: mcp_prefs,
     };
 
+    TURNS.clear();
     match manager.start(&provider_type, cols, rows, config) {
         Ok(()) => IpcResponse::ok(serde_json::json!({
             "provider": provider_type,
@@ -107,6 +112,7 @@ pub fn start_ai(
 pub fn stop_ai(state: State<'_, AiManagerState>) -> IpcResponse {
     let mut manager = lock_manager!(state);
     let stopped = manager.stop();
+    TURNS.clear();
     IpcResponse::ok(serde_json::json!({ "stopped": stopped }))
 }
 
@@ -135,22 +141,188 @@ pub fn ai_pty_input(
     image_data_url: Option<String>,
 ) -> IpcResponse {
     let mut manager = lock_manager!(state);
+    if send_to_provider(&mut manager, &data, image_path.as_deref(), image_data_url.as_deref()) {
+        IpcResponse::ok_empty()
+    } else {
+        IpcResponse::err("No active provider to send input to")
+    }
+}
+
+fn send_to_provider(
+    manager: &mut AiManager,
+    data: &str,
+    image_path: Option<&str>,
+    image_data_url: Option<&str>,
+) -> bool {
     // Prefer pre-encoded data URL over file path (avoids file I/O)
-    if let Some(ref data_url) = image_data_url {
-        if manager.send_input_with_image_data_url(&data, data_url) {
-            return IpcResponse::ok_empty();
+    if let Some(data_url) = image_data_url {
+        if manager.send_input_with_image_data_url(data, data_url) {
+            return true;
         }
     }
-    if let Some(ref path) = image_path {
-        if manager.send_input_with_image(&data, path) {
-            return IpcResponse::ok_empty();
+    if let Some(path) = image_path {
+        if manager.send_input_with_image(data, path) {
+            return true;
         }
     }
-    if manager.send_input(&data) {
-        IpcResponse::ok_empty()
-    } else {
-        IpcResponse::err("No active provider to send input to")
+    manager.send_input(data)
+}
+
+/// Whether replies to the latest user message are spoken. Set by
+/// `send_text_message`; read when a reply arrives (`ai-response`,
+/// `mcp-inbox-message`).
+static SPEAK_REPLIES: AtomicBool = AtomicBool::new(true);
+
+/// Whether the reply to the latest user message should be spoken. Always
+/// false during quiet hours.
+pub fn speak_replies() -> bool {
+    SPEAK_REPLIES.load(Ordering::Relaxed) && !crate::services::quiet_hours::active()
+}
+
+/// Speak a short "step 2: web search" notice when the model calls a tool,
+/// if `ai.narrateToolSteps` is on and the reply would be spoken.
+pub fn narrate_tool_step(app_handle: &AppHandle, step: &ToolPlanStep) {
+    if step.phase != ToolPlanPhase::Call
+        || !speak_replies()
+        || !super::config::get_config_snapshot().ai.narrate_tool_steps
+    {
+        return;
+    }
+    let notice = format!("Step {}: {}", step.iteration, step.tool.replace('_', " "));
+    super::voice::speak_notice(app_handle, &notice);
+}
+
+/// User messages waiting for the API provider to finish its current reply
+/// (see `providers::turn_queue`).
+static TURNS: TurnQueue = TurnQueue::new();
+
+/// Send a turn to the provider, setting whether its reply is spoken.
+fn send_turn(manager: &mut AiManager, turn: &PendingTurn) -> bool {
+    SPEAK_REPLIES.store(turn.speak, Ordering::Relaxed);
+    send_to_provider(
+        manager,
+        &turn.text,
+        turn.image_path.as_deref(),
+        turn.image_data_url.as_deref(),
+    )
+}
+
+/// End the API provider's current turn (reply, error or tool calls) and
+/// send the next waiting message, if any. Called from the provider event
+/// loop.
+pub(crate) fn end_turn(app_handle: &AppHandle) {
+    let Some(next) = TURNS.finish(Instant::now()) else {
+        return;
+    };
+    let Some(state) = app_handle.try_state::<AiManagerState>() else {
+        return;
+    };
+    let mut manager = state.0.lock().unwrap_or_else(|e| e.into_inner());
+    if !send_turn(&mut manager, &next) {
+        TURNS.clear();
+    }
+}
+
+/// Drop the turn in progress and any waiting messages (provider exited).
+pub(crate) fn drop_turns() {
+    TURNS.clear();
+}
+
+/// Send a user message (typed or transcribed) to the active AI provider.
+///
+/// The one entry point for the chat box and voice transcriptions, so both
+/// land in the same conversation: API providers get it as chat input, CLI
+/// providers through the MCP inbox (`write_user_message`). `speak: false`
+/// keeps the reply out of TTS; it applies until the next message.
+///
+/// While an API provider is still answering, the message waits its turn.
+/// Voice messages (`voice: true`) follow `voice.turnPolicy` instead and may
+/// replace the waiting one or be rejected with a spoken notice. `turn` in
+/// the result says which: "sent", "queued", "replaced" or "rejected".
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn send_text_message(
+    text: String,
+    image_path: Option<String>,
+    image_data_url: Option<String>,
+    speak: Option<bool>,
+    voice: Option<bool>,
+    app_handle: AppHandle,
+    state: State<'_, AiManagerState>,
+    pipe_state: State<'_, crate::ipc::pipe_server::PipeServerState>,
+) -> Result<IpcResponse, ()> {
+    if text.trim().is_empty() && image_path.is_none() && image_data_url.is_none() {
+        return Ok(IpcResponse::err("Message is empty"));
+    }
+    let speak = speak.unwrap_or(true);
+
+    let api = {
+        let manager = match state.0.lock() {
+            Ok(guard) => guard,
+            Err(e) => return Ok(IpcResponse::err(format!("Failed to lock AI manager: {}", e))),
+        };
+        match manager.mode() {
+            Some("dictation") => {
+                return Ok(IpcResponse::err("The dictation provider has no AI to send to"));
+            }
+            Some("api") => true,
+            // CLI providers (and no provider yet) read the inbox
+            _ => false,
+        }
+    };
+
+    if api {
+        // Typed messages always wait their turn
+        let policy = if voice.unwrap_or(false) {
+            TurnPolicy::parse(&super::config::get_config_snapshot().voice.turn_policy)
+        } else {
+            TurnPolicy::Queue
+        };
+        let turn = PendingTurn {
+            text,
+            image_path,
+            image_data_url,
+            speak,
+        };
+        let outcome = match TURNS.admit(turn, policy, Instant::now()) {
+            Admission::Send(turn) => {
+                let mut manager = match state.0.lock() {
+                    Ok(guard) => guard,
+                    Err(e) => return Ok(IpcResponse::err(format!("Failed to lock AI manager: {}", e))),
+                };
+                if !send_turn(&mut manager, &turn) {
+                    TURNS.clear();
+                    return Ok(IpcResponse::err("No active provider to send input to"));
+                }
+                "sent"
+            }
+            Admission::Queued(_) => "queued",
+            Admission::Replaced => "replaced",
+            Admission::Rejected => {
+                super::voice::speak_notice(&app_handle, turn_queue::BUSY_NOTICE);
+                "rejected"
+            }
+        };
+        return Ok(IpcResponse::ok(serde_json::json!({
+            "mode": "api",
+            "speak": speak,
+            "turn": outcome,
+            "pending": TURNS.pending(),
+        })));
     }
+
+    SPEAK_REPLIES.store(speak, Ordering::Relaxed);
+    let written =
+        write_user_message(text, None, None, image_path, image_data_url, pipe_state).await?;
+    if !written.success {
+        return Ok(written);
+    }
+
+    Ok(IpcResponse::ok(serde_json::json!({
+        "mode": "pty",
+        "speak": speak,
+        "turn": "sent",
+    })))
 }
 
 /// Send raw bytes to the active AI provider (PTY passthrough).
@@ -182,9 +354,163 @@ pub fn ai_pty_resize(state: State<'_, AiManagerState>, cols: u16, rows: u16) ->
 pub fn interrupt_ai(state: State<'_, AiManagerState>) -> IpcResponse {
     let mut manager = lock_manager!(state);
     let interrupted = manager.interrupt();
+    // What the user said while it was answering goes too
+    TURNS.clear();
+    if interrupted {
+        crate::services::runtime_context::note_interruption();
+    }
     IpcResponse::ok(serde_json::json!({ "interrupted": interrupted }))
 }
 
+/// Set a temporary system-prompt addendum for the current session ("act as
+/// a code reviewer") without touching the configured system prompt. It's
+/// sent with every API provider request from the next turn on, and cleared
+/// when the provider stops. `None` or blank clears it.
+///
+/// CLI providers manage their own prompt, so `applied` is false for them.
+#[tauri::command]
+pub fn set_session_prompt(state: State<'_, AiManagerState>, prompt: Option<String>) -> IpcResponse {
+    use crate::services::runtime_context::{self, MAX_SESSION_PROMPT_CHARS};
+
+    if let Some(ref p) = prompt {
+        if p.chars().count() > MAX_SESSION_PROMPT_CHARS {
+            return IpcResponse::err(format!(
+                "Session prompt is too long (max {} characters)",
+                MAX_SESSION_PROMPT_CHARS
+            ));
+        }
+    }
+    let manager = lock_manager!(state);
+    runtime_context::set_session_prompt(prompt);
+    let active = runtime_context::session_prompt();
+    IpcResponse::ok(serde_json::json!({
+        "prompt": active,
+        "applied": active.is_some() && manager.mode() == Some("api"),
+    }))
+}
+
+/// Get the current session prompt addendum (`null` when none is set).
+#[tauri::command]
+pub fn get_session_prompt() -> IpcResponse {
+    IpcResponse::ok(serde_json::json!({
+        "prompt": crate::services::runtime_context::session_prompt(),
+    }))
+}
+
+/// Save a session snapshot: provider conversation history, session prompt,
+/// pending timers, and the active tool profile with its groups. `path` must
+/// be an absolute `.json` path; without one the snapshot goes to
+/// `{data_dir}/snapshots/`. Returns `{ path, messages, timers }`.
+#[tauri::command]
+pub fn snapshot_session(state: State<'_, AiManagerState>, path: Option<String>) -> IpcResponse {
+    use crate::services::{platform, runtime_context, session_snapshot};
+
+    let created_at = session_snapshot::to_ms(std::time::SystemTime::now());
+    let path = match path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => session_snapshot::default_path(&platform::get_data_dir(), created_at),
+    };
+    if let Err(e) = session_snapshot::validate_path(&path) {
+        return IpcResponse::err(e);
+    }
+
+    let (provider, history) = {
+        let manager = lock_manager!(state);
+        let provider = manager
+            .is_running()
+            .then(|| manager.provider_type().map(String::from))
+            .flatten();
+        (provider, manager.history())
+    };
+    let ai = super::config::get_config_snapshot().ai;
+    let tool_groups = ai
+        .tool_profiles
+        .get(&ai.tool_profile)
+        .map(|p| p.groups.clone())
+        .unwrap_or_default();
+    let snapshot = session_snapshot::SessionSnapshot {
+        version: session_snapshot::VERSION,
+        created_at,
+        provider,
+        history,
+        session_prompt: runtime_context::session_prompt(),
+        tool_profile: ai.tool_profile,
+        tool_groups,
+        timers: session_snapshot::timers_from(&runtime_context::pending_timers()),
+    };
+
+    match session_snapshot::save(&path, &snapshot) {
+        Ok(()) => {
+            tracing::info!(path = %path.display(), messages = snapshot.history.len(), "Session snapshot saved");
+            IpcResponse::ok(serde_json::json!({
+                "path": path.to_string_lossy(),
+                "messages": snapshot.history.len(),
+                "timers": snapshot.timers.len(),
+            }))
+        }
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// Restore a snapshot saved by `snapshot_session`.
+///
+/// History replaces the running API provider's (`historyRestored` is false
+/// when none is running), the session prompt is replaced, timers still in
+/// the future replace the pending ones, and the tool profile is saved to
+/// config for the next CLI provider start. Queued turns are dropped.
+#[tauri::command]
+pub fn restore_session(state: State<'_, AiManagerState>, path: String) -> IpcResponse {
+    use crate::services::{runtime_context, session_snapshot};
+
+    let path = std::path::PathBuf::from(path);
+    if let Err(e) = session_snapshot::validate_path(&path) {
+        return IpcResponse::err(e);
+    }
+    let snapshot = match session_snapshot::load(&path) {
+        Ok(s) => s,
+        Err(e) => return IpcResponse::err(e),
+    };
+
+    let mut profile = serde_json::Map::new();
+    profile.insert(
+        snapshot.tool_profile.clone(),
+        serde_json::json!({ "groups": snapshot.tool_groups }),
+    );
+    let saved = super::config::set_config(serde_json::json!({
+        "ai": { "toolProfile": snapshot.tool_profile, "toolProfiles": profile },
+    }));
+    if !saved.success {
+        return saved;
+    }
+
+    let history_restored = {
+        let mut manager = lock_manager!(state);
+        manager.restore_history(snapshot.history.clone())
+    };
+    TURNS.clear();
+    runtime_context::set_session_prompt(snapshot.session_prompt.clone());
+    let timers = snapshot.pending_timers(std::time::SystemTime::now());
+    runtime_context::clear_timers();
+    for (label, due) in &timers {
+        runtime_context::add_timer(label, *due);
+    }
+
+    tracing::info!(
+        path = %path.display(),
+        history_restored,
+        timers = timers.len(),
+        "Session snapshot restored"
+    );
+    IpcResponse::ok(serde_json::json!({
+        "createdAt": snapshot.created_at,
+        "provider": snapshot.provider,
+        "historyRestored": history_restored,
+        "messages": snapshot.history.len(),
+        "toolProfile": snapshot.tool_profile,
+        "timers": timers.len(),
+    }))
+}
+
 /// Send the voice listen loop command to CLI agents.
 ///
 /// Instructs the CLI agent to use MCP tools for voice I/O in a loop.
@@ -397,6 +723,7 @@ pub fn set_provider(
         mcp_preferences,
     };
 
+    TURNS.clear();
     match manager.switch(&provider_id, cols, rows, config) {
         Ok(()) => IpcResponse::ok(serde_json::json!({
             "provider": provider_id,
@@ -409,7 +736,7 @@ pub fn set_provider(
 /// Write a user message to the MCP inbox.
 ///
 /// This bridges the chat UI to the AI provider. Prefers the named pipe for
-/// instant delivery to `voice_listen`, falls back to inbox.json file write.
+/// instant delivery to `voice_listen`, falls back to an inbox.db write.
 #[tauri::command]
 pub async fn write_user_message(
     message: String,
@@ -466,7 +793,7 @@ pub async fn write_user_message(
             image_data_url: image_data_url.clone(),
         };
         if pipe_state.send(pipe_msg).is_ok() {
-            // Also write to inbox.json for persistence/fallback
+            // Also write to the inbox for persistence/fallback
             let _ = crate::services::inbox_watcher::write_inbox_message_with_image(
                 &sender, &message, Some(&tid), image_path.as_deref(),
             );
@@ -67,6 +67,19 @@ pub fn get_config() -> IpcResponse {
         }
     }
 
+    let hook_count = val.get("webhooks").and_then(|w| w.as_array()).map_or(0, Vec::len);
+    let hook_secrets = (0..hook_count).map(|i| format!("/webhooks/{}/secret", i));
+    let pointers = ["/browser/braveApiKey", "/controlApi/token"].map(String::from);
+    for pointer in pointers.into_iter().chain(hook_secrets) {
+        let Some(v) = val.pointer_mut(&pointer) else { continue };
+        if let Some(key_str) = v.as_str() {
+            *v = match mask_api_key(key_str) {
+                Some(masked) => serde_json::Value::String(masked),
+                None => serde_json::Value::Null,
+            };
+        }
+    }
+
     IpcResponse::ok(val)
 }
 
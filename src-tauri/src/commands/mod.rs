@@ -6,6 +6,7 @@ pub mod files;
 pub mod screenshot;
 pub mod shortcuts;
 pub mod tools;
+pub mod transcript;
 pub mod voice;
 pub mod window;
 pub mod lens;
@@ -21,8 +22,10 @@ pub mod sandbox;
 
 use serde_json::Value;
 
+use crate::error::AppError;
+
 /// IPC response format matching Voice Mirror convention:
-/// { success: bool, data?: any, error?: string }
+/// { success: bool, data?: any, error?: string, code?: string }
 #[derive(serde::Serialize)]
 pub struct IpcResponse {
     pub success: bool,
@@ -30,6 +33,9 @@ pub struct IpcResponse {
     pub data: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Stable error code (see `crate::error`), when the failure has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<&'static str>,
 }
 
 impl IpcResponse {
@@ -38,6 +44,7 @@ impl IpcResponse {
             success: true,
             data: Some(data),
             error: None,
+            code: None,
         }
     }
 
@@ -46,6 +53,7 @@ impl IpcResponse {
             success: true,
             data: None,
             error: None,
+            code: None,
         }
     }
 
@@ -54,6 +62,17 @@ impl IpcResponse {
             success: false,
             data: None,
             error: Some(msg.into()),
+            code: None,
+        }
+    }
+
+    /// A failure carrying the error's code.
+    pub fn from_error(err: AppError) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(err.to_string()),
+            code: Some(err.code()),
         }
     }
 }
@@ -5,6 +5,10 @@
 //! known credential files only and never invokes the CLI (which could burn API
 //! quota or pop a browser). Phase 1 uses cheap file-existence checks; a later
 //! phase adds reliable live probes for the providers that need them.
+//!
+//! The setup steps after provider choice (models, mic/speaker loop, VAD
+//! calibration, provider connection) run in `services::onboarding`; the
+//! commands at the end of this file drive them.
 
 use std::path::PathBuf;
 
@@ -14,6 +18,7 @@ use tauri::{AppHandle, Emitter};
 use super::IpcResponse;
 use crate::commands::tools::detect_tool;
 use crate::providers::cli::{get_cli_config, is_cli_available};
+use crate::services::onboarding::{self, Step};
 
 /// Best-effort login state for a provider, derived from its credential files.
 #[derive(Serialize, Clone, Copy, PartialEq)]
@@ -205,7 +210,7 @@ fn run_capture(cmdline: &str) -> Option<(bool, String)> {
 /// subcommand (verified to exist + be non-interactive). Unlike the file
 /// heuristic, this catches expired/refreshed tokens accurately. Still safe:
 /// `status` never opens a browser or spends quota.
-fn probe_auth(provider_type: &str) -> AuthState {
+pub(crate) fn probe_auth(provider_type: &str) -> AuthState {
     match provider_type {
         "claude" => probe_claude(),
         "codex" => probe_codex(),
@@ -553,3 +558,49 @@ pub async fn install_provider(app_handle: AppHandle, params: InstallProviderPara
         "message": tail,
     }))
 }
+
+// ---------------------------------------------------------------------------
+// Setup steps (models, audio loop, VAD calibration, provider)
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStepParams {
+    /// `models`, `audio`, `vad` or `provider` (see `services::onboarding`).
+    pub step: String,
+    /// `models` only: download what's missing instead of just reporting it.
+    #[serde(default)]
+    pub download: bool,
+}
+
+fn parse_step(name: &str) -> Result<Step, IpcResponse> {
+    Step::parse(name).ok_or_else(|| IpcResponse::err(format!("Unknown onboarding step '{}'", name)))
+}
+
+/// Where each setup step stands: `{ steps: [StepState], complete }`.
+#[tauri::command]
+pub fn get_onboarding_status() -> IpcResponse {
+    IpcResponse::ok(serde_json::json!(onboarding::status()))
+}
+
+/// Run one setup step. Progress streams as `onboarding-step` events; the
+/// final `StepState` is also the response. A failed step is still a
+/// successful call (`status: "failed"` with the reason in `detail`).
+#[tauri::command]
+pub async fn run_onboarding_step(app_handle: AppHandle, params: OnboardingStepParams) -> IpcResponse {
+    match parse_step(&params.step) {
+        Ok(step) => IpcResponse::ok(serde_json::json!(
+            onboarding::run(&app_handle, step, params.download).await
+        )),
+        Err(response) => response,
+    }
+}
+
+/// Mark a setup step skipped.
+#[tauri::command]
+pub fn skip_onboarding_step(app_handle: AppHandle, params: OnboardingStepParams) -> IpcResponse {
+    match parse_step(&params.step) {
+        Ok(step) => IpcResponse::ok(serde_json::json!(onboarding::skip(&app_handle, step))),
+        Err(response) => response,
+    }
+}
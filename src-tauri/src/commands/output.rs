@@ -115,7 +115,8 @@ pub struct ExportDiagnosticsParams {
 /// process log (a separate process, file-only) — into one plain-text blob the
 /// user can copy from Settings and paste for debugging. This is the only place
 /// the MCP binary's logs surface in the app, since it doesn't share the
-/// in-memory OutputStore.
+/// in-memory OutputStore. A hardware summary and the whisper model it can
+/// run (see `voice::hardware`) come first.
 #[tauri::command]
 pub fn export_diagnostics(
     params: ExportDiagnosticsParams,
@@ -131,6 +132,34 @@ pub fn export_diagnostics(
     let _ = writeln!(out, "os: {} ({})", std::env::consts::OS, std::env::consts::ARCH);
     let _ = writeln!(out, "lines per channel: {}", last);
 
+    // Hardware, and the whisper model it can run within the latency target.
+    let hw = crate::voice::hardware::probe();
+    let voice_cfg = super::config::get_config_snapshot().voice;
+    let rec = crate::voice::hardware::recommend(&hw, voice_cfg.stt_target_latency_ms as u64);
+    let _ = writeln!(out, "\n----- hardware -----");
+    let _ = writeln!(
+        out,
+        "cpu: {} logical cores ({} whisper threads), simd: {:?}",
+        hw.cpu_cores, hw.inference_threads, hw.simd
+    );
+    let ram = hw.ram_mb.map_or("unknown".to_string(), |mb| format!("{} MB", mb));
+    let _ = writeln!(out, "ram: {}", ram);
+    let gpu = match &hw.gpu {
+        Some(gpu) => format!(
+            "{} ({} MB, driver {})",
+            gpu.name,
+            gpu.vram_mb.map_or("?".to_string(), |mb| mb.to_string()),
+            gpu.driver_version
+        ),
+        None => "no NVIDIA GPU".to_string(),
+    };
+    let _ = writeln!(out, "gpu: {}, cuda compiled: {}", gpu, hw.cuda_compiled);
+    let _ = writeln!(
+        out,
+        "stt model: {} (gpu: {}); recommended: {} (gpu: {}) - {}",
+        voice_cfg.stt_model_size, voice_cfg.stt_use_gpu, rec.model_size, rec.use_gpu, rec.reason
+    );
+
     // In-memory system channels (the app process).
     for channel in Channel::ALL {
         let (entries, total) = output_store.query(channel, None, Some(last), None);
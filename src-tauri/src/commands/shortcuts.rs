@@ -7,6 +7,7 @@ use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 use tracing::{error, info, warn};
 
 use super::IpcResponse;
+use crate::config::schema::HotkeyConfig;
 
 /// A registered shortcut entry stored in managed state.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -209,3 +210,23 @@ pub fn unregister_all_shortcuts(
     info!("Unregistered all global shortcuts");
     IpcResponse::ok_empty()
 }
+
+/// Save the voice hotkeys (`hotkeys` config) and register them. Returns one
+/// `{ action, keys, error }` per bound action; `error` is null when the
+/// hotkey is active.
+#[tauri::command]
+pub fn configure_hotkeys(app: AppHandle, hotkeys: HotkeyConfig) -> IpcResponse {
+    let patch = match serde_json::to_value(&hotkeys) {
+        Ok(v) => serde_json::json!({ "hotkeys": v }),
+        Err(e) => return IpcResponse::err(format!("Serialize error: {}", e)),
+    };
+    let saved = super::config::set_config(patch);
+    if !saved.success {
+        return saved;
+    }
+    let statuses = crate::services::hotkeys::apply(&app, &hotkeys);
+    match serde_json::to_value(&statuses) {
+        Ok(data) => IpcResponse::ok(data),
+        Err(e) => IpcResponse::err(format!("Serialize error: {}", e)),
+    }
+}
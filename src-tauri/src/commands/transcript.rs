@@ -0,0 +1,133 @@
+//! Tauri commands for the conversation transcript log and the turn audio
+//! archive.
+//!
+//! The voice pipeline writes the log (see `services::transcript_log`); these
+//! commands read it back by date, export it as Markdown, and purge it. Dates
+//! are `YYYY-MM-DD` (UTC) and ranges include both ends.
+//!
+//! The `turn_archive_*` commands list the recordings kept for recent
+//! transcriptions (see `services::turn_archive`), return one for replay, and
+//! re-transcribe one with cloud STT.
+
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::services::turn_archive::{self, CloudStt};
+use crate::services::{platform, transcript_log};
+use crate::voice::tts::crypto::base64_encode;
+
+use super::IpcResponse;
+
+/// Transcript entries between `from` and `to` (either may be omitted),
+/// oldest first: `{ timestamp, speaker: "user" | "assistant", text,
+/// language?, session }`.
+#[tauri::command]
+pub fn transcript_query(from: Option<String>, to: Option<String>) -> IpcResponse {
+    match transcript_log::date_range(from.as_deref(), to.as_deref()) {
+        Ok((start, end)) => {
+            let entries = transcript_log::query(&platform::get_data_dir(), start, end);
+            IpcResponse::ok(json!(entries))
+        }
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// The same range as Markdown, one section per session. Save it with
+/// `export_chat_to_file`.
+#[tauri::command]
+pub fn transcript_export_markdown(from: Option<String>, to: Option<String>) -> IpcResponse {
+    match transcript_log::date_range(from.as_deref(), to.as_deref()) {
+        Ok((start, end)) => {
+            let entries = transcript_log::query(&platform::get_data_dir(), start, end);
+            IpcResponse::ok(json!({
+                "markdown": transcript_log::to_markdown(&entries),
+                "entries": entries.len(),
+            }))
+        }
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// Delete entries from before `before`, or the whole log when it is
+/// omitted. Returns `{ removed }`.
+#[tauri::command]
+pub fn transcript_purge(before: Option<String>) -> IpcResponse {
+    let cutoff = match before.as_deref() {
+        Some(date) => match transcript_log::date_range(Some(date), None) {
+            Ok((start, _)) => Some(start),
+            Err(e) => return IpcResponse::err(e),
+        },
+        None => None,
+    };
+    match transcript_log::purge(&platform::get_data_dir(), cutoff) {
+        Ok(removed) => {
+            tracing::info!(removed, before = ?before, "Transcript log purged");
+            IpcResponse::ok(json!({ "removed": removed }))
+        }
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// Archived turns, newest first: `{ id, durationMs, text, format,
+/// language?, corrected? }`. `id` matches the `Transcription` event's.
+#[tauri::command]
+pub fn turn_archive_list() -> IpcResponse {
+    IpcResponse::ok(json!(turn_archive::list(&platform::get_data_dir())))
+}
+
+/// One archived turn with its recording as base64 (16 kHz mono, WAV, FLAC
+/// or Ogg Opus per `turn.format`): `{ turn, audio, mimeType }`.
+#[tauri::command]
+pub fn turn_archive_get(id: u64) -> IpcResponse {
+    match turn_archive::get(&platform::get_data_dir(), id) {
+        Ok((turn, clip)) => IpcResponse::ok(json!({
+            "mimeType": turn.format.mime_type(),
+            "turn": turn,
+            "audio": base64_encode(&clip),
+        })),
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// Transcribe an archived recording again with cloud STT (`voice.sttApiKey`,
+/// OpenAI-compatible `voice.sttEndpoint`) and store the result as the turn's
+/// `corrected` text. Returns the updated turn.
+#[tauri::command]
+pub async fn turn_archive_recheck(id: u64) -> IpcResponse {
+    let voice = super::config::get_config_snapshot().voice;
+    let Some(api_key) = voice.stt_api_key.filter(|k| !k.trim().is_empty()) else {
+        return IpcResponse::from_error(AppError::Config(
+            "Set a cloud STT API key to re-check recordings".into(),
+        ));
+    };
+    let data_dir = platform::get_data_dir();
+    let (turn, clip) = match turn_archive::get(&data_dir, id) {
+        Ok(archived) => archived,
+        Err(e) => return IpcResponse::err(e),
+    };
+    let cloud = CloudStt {
+        api_key,
+        endpoint: voice.stt_endpoint,
+        model: voice.stt_model_name,
+        language: crate::voice::stt::whisper_language(&voice.stt_language),
+    };
+    let text = match turn_archive::cloud_transcribe(&cloud, clip, turn.format).await {
+        Ok(text) => text,
+        Err(e) => return IpcResponse::from_error(e.context("Re-check failed")),
+    };
+    match turn_archive::set_corrected(&data_dir, id, &text) {
+        Ok(turn) => {
+            tracing::info!(id, "Turn re-checked with cloud STT");
+            IpcResponse::ok(json!(turn))
+        }
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// Delete every archived recording. Returns `{ removed }`.
+#[tauri::command]
+pub fn turn_archive_purge() -> IpcResponse {
+    let removed = turn_archive::purge(&platform::get_data_dir());
+    tracing::info!(removed, "Turn audio archive purged");
+    IpcResponse::ok(json!({ "removed": removed }))
+}
@@ -4,11 +4,18 @@
 //! They interact with the `VoiceEngine` stored in Tauri's managed state.
 
 use serde_json::json;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 use super::IpcResponse;
-use crate::voice::pipeline::{list_input_devices, list_output_devices};
-use crate::voice::{VoiceEngine, VoiceMode};
+use crate::config::schema::{AppConfig, QuietHoursConfig};
+use crate::config::voice_engine::{self, ConfigIssue};
+use crate::config::voice_profiles;
+use crate::error::AppError;
+use crate::services::{turn_archive, voice_stats};
+use crate::voice::{calibration, capabilities, latency_budget, speaker, vad_corpus};
+use crate::voice::pipeline::{list_input_devices, list_output_devices, VoiceEvent};
+use crate::voice::resample::ResamplerKind;
+use crate::voice::{EngineLoadMode, SpeechPriority, VoiceEngine, VoiceEngineConfig, VoiceMode};
 
 /// Tauri managed state wrapper for the voice engine.
 ///
@@ -17,38 +24,135 @@ use crate::voice::{VoiceEngine, VoiceMode};
 /// contention is minimal.
 pub type VoiceEngineState = std::sync::Mutex<VoiceEngine>;
 
-/// Start the voice pipeline.
+/// Link a provider reply to the open voice turn (see `voice::session`).
+/// `speak` is whether the reply goes to TTS.
+pub(crate) fn note_provider_reply(app_handle: &AppHandle, speak: bool) {
+    if let Some(state) = app_handle.try_state::<VoiceEngineState>() {
+        if let Ok(engine) = state.lock() {
+            engine.note_reply(speak);
+        }
+    }
+}
+
+/// Speak a short notice after any speech in progress, if the pipeline is
+/// running. Best-effort: without voice there's no one to tell out loud.
+pub(crate) fn speak_notice(app_handle: &AppHandle, text: &str) {
+    if let Some(state) = app_handle.try_state::<VoiceEngineState>() {
+        if let Ok(engine) = state.lock() {
+            if let Err(e) = engine.speak_with_priority(text.to_string(), SpeechPriority::Queue) {
+                tracing::debug!("Notice not spoken: {}", e);
+            }
+        }
+    }
+}
+
+/// Build the runtime `VoiceEngineConfig` from the saved app config.
 ///
-/// Initializes audio capture, VAD, STT, and TTS engines, then
-/// begins the audio processing loop on background threads.
-#[tauri::command]
-pub fn start_voice(
-    app_handle: AppHandle,
-    voice_state: State<'_, VoiceEngineState>,
-) -> IpcResponse {
-    // Read the saved config so the engine starts with user's settings
-    // (STT model, GPU toggle, TTS adapter, etc.) instead of hardcoded defaults.
-    let app_cfg = super::config::get_config_snapshot();
-    let voice_cfg = crate::voice::VoiceEngineConfig {
-        mode: crate::voice::VoiceMode::from_str_flexible(
-            &app_cfg.behavior.activation_mode,
-        )
-        .unwrap_or_default(),
+/// Shared by `start_voice` and `restart_voice` so both pick up exactly the
+/// same user settings (STT model/language, GPU toggle, TTS adapter, devices).
+pub(crate) fn engine_config_from_app(app_cfg: &AppConfig) -> VoiceEngineConfig {
+    VoiceEngineConfig {
+        mode: VoiceMode::from_str_flexible(&app_cfg.behavior.activation_mode).unwrap_or_default(),
         stt_adapter: app_cfg.voice.stt_adapter.clone(),
         stt_model_size: app_cfg.voice.stt_model_size.clone(),
         stt_use_gpu: app_cfg.voice.stt_use_gpu,
+        stt_language: crate::voice::stt::normalize_stt_language(&app_cfg.voice.stt_language),
+        stt_task: crate::voice::stt::SttTask::parse(&app_cfg.voice.stt_task),
+        stt_context_words: app_cfg.voice.stt_context_words as usize,
+        stt_initial_prompt: app_cfg.voice.stt_initial_prompt.clone(),
+        stt_vocabulary: app_cfg.voice.stt_vocabulary.clone(),
         tts_adapter: app_cfg.voice.tts_adapter.clone(),
         tts_voice: app_cfg.voice.tts_voice.clone(),
         tts_speed: app_cfg.voice.tts_speed as f32,
         tts_volume: app_cfg.voice.tts_volume as f32,
+        tts_effects: app_cfg.voice.tts_effects.clone(),
+        tts_phrase_gap_ms: app_cfg.voice.tts_phrase_gap_ms,
+        tts_sentence_pause_ms: app_cfg.voice.tts_sentence_pause_ms,
+        tts_lookahead: app_cfg.voice.tts_lookahead,
+        tts_blocked_terms: app_cfg.voice.tts_blocked_terms.clone(),
+        tts_filter_action: app_cfg.voice.tts_filter_action.clone(),
         input_device: app_cfg.voice.input_device.clone(),
+        capture_source: app_cfg.voice.capture_source.clone(),
         output_device: app_cfg.voice.output_device.clone(),
+        output_devices: app_cfg.voice.output_devices.clone(),
+        min_speech_coverage: app_cfg.voice.min_speech_coverage as f32,
+        vad_threshold: app_cfg.voice.vad_threshold as f32,
+        silence_timeout_secs: app_cfg.voice.silence_timeout_secs,
+        max_recording_secs: app_cfg.voice.max_recording_secs,
+        toggle_timeout_secs: app_cfg.voice.toggle_timeout_secs,
+        duck_while_recording: app_cfg.voice.duck_while_recording,
+        duck_level: app_cfg.voice.duck_level as f32,
+        noise_suppression: app_cfg.voice.noise_suppression,
+        listen_duty_cycle: app_cfg.voice.listen_duty_cycle.clone(),
+        resampler: app_cfg.voice.resampler.clone(),
+        audio_session: app_cfg.voice.audio_session.clone(),
+        sample_rate: app_cfg.voice.sample_rate,
+        chunk_ms: app_cfg.voice.chunk_ms,
+        transcript_log: app_cfg.voice.transcript_log,
+        turn_archive_size: app_cfg.voice.turn_archive_size as usize,
+        turn_archive_format: app_cfg.voice.turn_archive_format.clone(),
+        local_commands: app_cfg.voice.local_commands,
+        commands: app_cfg.voice.commands.clone(),
+        transcript_rules: app_cfg.transcript_rules.clone(),
+        transcript_routes: app_cfg.transcript_routes.clone(),
+        stt_load: EngineLoadMode::parse(&app_cfg.voice.stt_load),
+        tts_load: EngineLoadMode::parse(&app_cfg.voice.tts_load),
+        speaker_id: app_cfg.voice.speaker_id,
+        speaker_threshold: app_cfg.voice.speaker_threshold as f32,
+        ignore_unknown_speakers: app_cfg.voice.ignore_unknown_speakers,
+        session_timeout_secs: app_cfg.voice.session_timeout_secs,
+        latency_budget_ms: app_cfg.voice.latency_budget_ms,
+        watchdog_auto_restart: app_cfg.voice.watchdog_auto_restart,
         ..Default::default()
+    }
+}
+
+/// The config the engine starts with: the active voice profile
+/// (`voice.activeProfile`) when one is set, otherwise the Voice settings.
+pub(crate) fn active_engine_config(app_cfg: &AppConfig) -> Result<VoiceEngineConfig, AppError> {
+    match &app_cfg.voice.active_profile {
+        Some(name) => voice_profiles::load(&crate::services::platform::get_config_dir(), name),
+        None => Ok(engine_config_from_app(app_cfg)),
+    }
+}
+
+/// Refuse to start with settings `voice_engine::validate` rejected. The
+/// issues also go in `data` so the settings page can point at the fields.
+fn invalid_config(issues: &[ConfigIssue]) -> IpcResponse {
+    let list: Vec<String> = issues.iter().map(ToString::to_string).collect();
+    tracing::warn!(issues = %list.join("; "), "Voice settings are invalid");
+    let mut response = IpcResponse::from_error(AppError::Config(format!(
+        "Invalid voice settings: {}",
+        list.join("; ")
+    )));
+    response.data = Some(json!({ "issues": issues }));
+    response
+}
+
+/// Start the voice pipeline.
+///
+/// Initializes audio capture, VAD, STT, and TTS engines, then
+/// begins the audio processing loop on background threads.
+#[tauri::command]
+pub fn start_voice(
+    app_handle: AppHandle,
+    voice_state: State<'_, VoiceEngineState>,
+) -> IpcResponse {
+    // Read the saved config so the engine starts with user's settings
+    // (STT model, GPU toggle, TTS adapter, etc.) instead of hardcoded defaults.
+    let voice_cfg = match active_engine_config(&super::config::get_config_snapshot()) {
+        Ok(cfg) => cfg,
+        Err(e) => return IpcResponse::from_error(e),
     };
+    let issues = voice_engine::validate(&voice_cfg);
+    if !issues.is_empty() {
+        return invalid_config(&issues);
+    }
 
     tracing::info!(
         stt_model = %voice_cfg.stt_model_size,
         stt_adapter = %voice_cfg.stt_adapter,
+        stt_language = %voice_cfg.stt_language,
         use_gpu = voice_cfg.stt_use_gpu,
         tts_adapter = %voice_cfg.tts_adapter,
         mode = %voice_cfg.mode,
@@ -61,7 +165,7 @@ pub fn start_voice(
     };
 
     if engine.is_running() {
-        return IpcResponse::err("Voice engine is already running");
+        return IpcResponse::from_error(AppError::AlreadyRunning);
     }
 
     // Apply saved config before starting
@@ -70,6 +174,7 @@ pub fn start_voice(
     match engine.start(app_handle) {
         Ok(()) => {
             tracing::info!("Voice engine started");
+            voice_engine::record(&crate::services::platform::get_config_dir(), engine.config());
             IpcResponse::ok(json!({
                 "running": true,
                 "state": engine.state().to_string(),
@@ -77,7 +182,7 @@ pub fn start_voice(
         }
         Err(e) => {
             tracing::error!("Failed to start voice engine: {}", e);
-            IpcResponse::err(format!("Failed to start voice engine: {}", e))
+            IpcResponse::from_error(e.context("Failed to start voice engine"))
         }
     }
 }
@@ -86,7 +191,10 @@ pub fn start_voice(
 ///
 /// Stops audio capture, cancels any in-progress TTS, and shuts
 /// down all background processing threads.
-#[tauri::command]
+// `(async)` — off the UI thread. stop() waits for the STT sink and drain
+// (up to 6 s) while holding voice_state; like `restart_voice`, the body is
+// sync, so the lock is safe.
+#[tauri::command(async)]
 pub fn stop_voice(voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
     let mut engine = match voice_state.lock() {
         Ok(guard) => guard,
@@ -94,6 +202,8 @@ pub fn stop_voice(voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
     };
 
     if !engine.is_running() {
+        // A stalled pipeline still holds the mic
+        engine.stop();
         return IpcResponse::ok(json!({
             "running": false,
             "message": "Voice engine was not running",
@@ -108,6 +218,69 @@ pub fn stop_voice(voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
     }))
 }
 
+/// Release the microphone without stopping the pipeline: audio is no
+/// longer consumed and the capture stream is dropped, but the STT and TTS
+/// engines stay loaded so `resume_voice` is quick.
+// `(async)` — off the UI thread. pause() closes the capture stream while
+// holding voice_state, which a sync command would run on the main thread.
+#[tauri::command(async)]
+pub fn pause_voice(voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
+    let mut engine = match voice_state.lock() {
+        Ok(guard) => guard,
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+
+    match engine.pause() {
+        Ok(()) => IpcResponse::ok(json!({ "paused": true })),
+        Err(e) => IpcResponse::from_error(e),
+    }
+}
+
+/// Reopen the microphone released by `pause_voice`.
+// `(async)` — off the UI thread. resume() waits for the processing loop to
+// drop the pre-pause audio (up to 2 s) and opens the device while holding
+// voice_state; the body is sync, so the lock is safe.
+#[tauri::command(async)]
+pub fn resume_voice(voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
+    let mut engine = match voice_state.lock() {
+        Ok(guard) => guard,
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+
+    match engine.resume() {
+        Ok(()) => IpcResponse::ok(json!({
+            "paused": false,
+            "state": engine.state().to_string(),
+        })),
+        Err(e) => {
+            tracing::error!("Failed to resume voice engine: {}", e);
+            IpcResponse::from_error(e.context("Failed to reopen the microphone"))
+        }
+    }
+}
+
+/// Check the settings the engine would start with (the active voice
+/// profile when one is set) the way `start_voice` does, without starting
+/// anything. Returns `{ issues: [{ field, message }] }`, empty when the
+/// engine can start with them.
+#[tauri::command]
+pub fn validate_voice_config() -> IpcResponse {
+    let voice_cfg = active_engine_config(&super::config::get_config_snapshot());
+    IpcResponse::ok(json!({ "issues": config_issues(voice_cfg) }))
+}
+
+/// Issues with the config `active_engine_config` loaded. A profile that is
+/// missing or can't be read is an issue with `voice.activeProfile`.
+fn config_issues(voice_cfg: Result<VoiceEngineConfig, AppError>) -> Vec<ConfigIssue> {
+    match voice_cfg {
+        Ok(cfg) => voice_engine::validate(&cfg),
+        Err(e) => vec![ConfigIssue {
+            field: "voice.activeProfile",
+            message: format!("can't be loaded: {}", e),
+        }],
+    }
+}
+
 /// Get the current voice engine status.
 ///
 /// Returns the running state, current voice state, STT/TTS readiness,
@@ -125,12 +298,22 @@ pub fn get_voice_status(voice_state: State<'_, VoiceEngineState>) -> IpcResponse
 
     IpcResponse::ok(json!({
         "running": running,
+        "paused": engine.is_paused(),
         "state": state.to_string(),
         "sttAdapter": config.stt_adapter,
         "sttModelSize": config.stt_model_size,
+        "sttLanguage": config.stt_language,
+        "sttUseGpu": config.stt_use_gpu,
+        "sttGpuBackend": crate::voice::stt::whisper_gpu_backend(),
+        "onnxAvailable": cfg!(feature = "onnx"),
+        "noiseSuppressionAvailable": cfg!(feature = "noise-suppression"),
         "ttsAdapter": config.tts_adapter,
         "ttsVoice": config.tts_voice,
         "mode": format!("{}", config.mode),
+        "profile": super::config::get_config_snapshot().voice.active_profile,
+        "audioOverflowSamples": engine.audio_overflow_samples(),
+        "session": engine.session(),
+        "queuedSpeech": engine.queued_speech(),
         // Backwards-compatible fields matching voice-core events
         "sttReady": running,
         "ttsReady": running,
@@ -189,13 +372,18 @@ pub fn list_audio_devices() -> IpcResponse {
 ///
 /// Accepts text to synthesize and play via the voice pipeline's TTS engine.
 /// Requires the voice engine to be running. Spawns TTS on a background task
-/// and returns immediately.
+/// and returns immediately. `priority` ("interrupt" by default, "queue",
+/// "drop_if_busy") decides what happens if something is already being said.
+/// `voice` speaks this text in another TTS voice (an agent's own, see
+/// `services::instances`) without changing the selected one.
 // `(async)` keeps this off the UI thread: it locks the shared voice_state, and a
 // sync command that blocks on that lock during a TTS wedge/restart would freeze the
 // whole window. The body has no awaits, so holding the std Mutex guard is fine.
 #[tauri::command(async)]
 pub fn speak_text(
     text: String,
+    priority: Option<SpeechPriority>,
+    voice: Option<String>,
     voice_state: State<'_, VoiceEngineState>,
 ) -> IpcResponse {
     let engine = match voice_state.lock() {
@@ -204,15 +392,47 @@ pub fn speak_text(
     };
 
     if !engine.is_running() {
-        return IpcResponse::err("Voice engine is not running");
+        return IpcResponse::from_error(AppError::NotRunning);
     }
 
-    match engine.speak_blocking(text) {
+    let voice = voice.filter(|v| !v.trim().is_empty());
+    match engine.speak_as(text, priority.unwrap_or_default(), voice) {
         Ok(()) => IpcResponse::ok_empty(),
-        Err(e) => IpcResponse::err(e),
+        Err(e) => IpcResponse::from_error(e),
     }
 }
 
+/// Synthesize text with the configured TTS engine and write it to `path`
+/// (`.wav`, or `.mp3` via ffmpeg) instead of playing it.
+///
+/// Doesn't need the voice engine to be running: when it's stopped the saved
+/// config picks the engine and voice.
+#[tauri::command]
+pub async fn speak_to_file(
+    text: String,
+    path: String,
+    voice_state: State<'_, VoiceEngineState>,
+) -> Result<IpcResponse, ()> {
+    // Build the export while holding the lock, run it after releasing it
+    let export = {
+        let mut engine = match voice_state.lock() {
+            Ok(guard) => guard,
+            Err(e) => return Ok(IpcResponse::err(format!("Failed to lock voice state: {}", e))),
+        };
+        if !engine.is_running() {
+            match active_engine_config(&super::config::get_config_snapshot()) {
+                Ok(cfg) => engine.update_config(cfg),
+                Err(e) => return Ok(IpcResponse::from_error(e)),
+            }
+        }
+        engine.speak_to_file(text, std::path::PathBuf::from(path))
+    };
+    Ok(match export.await {
+        Ok(file) => IpcResponse::ok(json!(file)),
+        Err(e) => IpcResponse::from_error(e),
+    })
+}
+
 /// Interrupt in-progress TTS playback.
 ///
 /// Sets the cancellation flag on the TTS engine, causing any
@@ -236,6 +456,222 @@ pub fn stop_speaking(voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
     IpcResponse::ok_empty()
 }
 
+/// Switch the TTS voice of the running pipeline without restarting it.
+///
+/// If speech is playing, the new voice is used from the next utterance.
+/// Does not save the config; the Settings panel persists it separately.
+// `(async)` — off the UI thread (locks voice_state; must not freeze the window).
+#[tauri::command(async)]
+pub fn voice_set_tts_voice(voice: String, voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
+    let voice = voice.trim();
+    if voice.is_empty() {
+        return IpcResponse::err("Voice name must not be empty");
+    }
+
+    let mut engine = match voice_state.lock() {
+        Ok(guard) => guard,
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+
+    match engine.set_tts_voice(voice) {
+        Ok(()) => {
+            tracing::info!(voice = %voice, "TTS voice set");
+            IpcResponse::ok(json!({ "voice": voice }))
+        }
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// Set the TTS speed multiplier (0.5 - 2.0) without restarting the pipeline.
+// `(async)` — off the UI thread (locks voice_state; must not freeze the window).
+#[tauri::command(async)]
+pub fn voice_set_tts_speed(speed: f32, voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
+    if !(0.5..=2.0).contains(&speed) {
+        return IpcResponse::err(format!(
+            "TTS speed must be between 0.5 and 2.0, got {}",
+            speed
+        ));
+    }
+
+    let mut engine = match voice_state.lock() {
+        Ok(guard) => guard,
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+
+    match engine.set_tts_speed(speed) {
+        Ok(()) => {
+            tracing::info!(speed, "TTS speed set");
+            IpcResponse::ok(json!({ "speed": speed }))
+        }
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// Set the TTS playback volume (0.0 - 2.0, 1.0 = normal) without restarting
+/// the pipeline. Takes effect from the next utterance.
+// `(async)` — off the UI thread (locks voice_state; must not freeze the window).
+#[tauri::command(async)]
+pub fn voice_set_tts_volume(volume: f32, voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
+    if !(0.0..=2.0).contains(&volume) {
+        return IpcResponse::err(format!(
+            "TTS volume must be between 0.0 and 2.0, got {}",
+            volume
+        ));
+    }
+
+    let mut engine = match voice_state.lock() {
+        Ok(guard) => guard,
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+
+    engine.set_tts_volume(volume);
+    tracing::info!(volume, "TTS volume set");
+    IpcResponse::ok(json!({ "volume": volume }))
+}
+
+/// Set the voice effect chain (`["robot"]`, `["pitch:-5", "reverb:0.3"]`,
+/// `[]` for none) without restarting the pipeline. Takes effect from the
+/// next utterance.
+// `(async)` — off the UI thread (locks voice_state; must not freeze the window).
+#[tauri::command(async)]
+pub fn voice_set_tts_effects(
+    effects: Vec<String>,
+    voice_state: State<'_, VoiceEngineState>,
+) -> IpcResponse {
+    if let Some(bad) = effects
+        .iter()
+        .find(|e| crate::voice::tts::effects::EffectSpec::parse(e).is_none())
+    {
+        return IpcResponse::err(format!(
+            "Unknown voice effect '{}' (expected pitch:<semitones>, robot or reverb[:<mix>])",
+            bad
+        ));
+    }
+
+    let mut engine = match voice_state.lock() {
+        Ok(guard) => guard,
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+
+    engine.set_tts_effects(effects.clone());
+    tracing::info!(?effects, "Voice effects set");
+    IpcResponse::ok(json!({ "effects": effects }))
+}
+
+/// Delete every cached TTS clip (memory and disk).
+///
+/// Returns how many clip files were removed and the bytes freed.
+// `(async)` — deletes files; keep the disk I/O off the UI thread.
+#[tauri::command(async)]
+pub fn clear_tts_cache() -> IpcResponse {
+    let stats = crate::voice::tts::cache::clear();
+    tracing::info!(files = stats.files, bytes = stats.bytes, "TTS cache cleared");
+    IpcResponse::ok(json!({
+        "files": stats.files,
+        "bytes": stats.bytes,
+    }))
+}
+
+/// Get the localhost control API settings and whether it is listening.
+///
+/// Returns the plaintext token so the Settings UI can show it for copying
+/// into scripts (`get_config` masks it).
+#[tauri::command]
+pub fn control_api_status() -> IpcResponse {
+    let cfg = super::config::get_config_snapshot().control_api;
+    let running_port = crate::services::control_api::running_port();
+    IpcResponse::ok(json!({
+        "enabled": cfg.enabled,
+        "port": cfg.port,
+        "token": cfg.token.unwrap_or_default(),
+        "running": running_port.is_some(),
+        "url": running_port.map(|p| format!("http://127.0.0.1:{}", p)),
+    }))
+}
+
+/// Enable/disable the localhost control API, change its port, or rotate
+/// its token. Saves the config and (re)starts or stops the server.
+///
+/// A token is generated the first time the API is enabled.
+#[tauri::command]
+pub fn configure_control_api(
+    app_handle: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+    regenerate_token: Option<bool>,
+) -> IpcResponse {
+    use crate::services::control_api;
+
+    let current = super::config::get_config_snapshot().control_api;
+    let port = port.unwrap_or(current.port);
+    if port < 1024 {
+        return IpcResponse::err("Port must be between 1024 and 65535");
+    }
+    let token = match current.token.filter(|t| !t.is_empty()) {
+        Some(t) if !regenerate_token.unwrap_or(false) => t,
+        _ => control_api::generate_token(),
+    };
+
+    let saved = super::config::set_config(json!({
+        "controlApi": { "enabled": enabled, "port": port, "token": token },
+    }));
+    if !saved.success {
+        return saved;
+    }
+
+    if enabled {
+        if let Err(e) = control_api::start(&app_handle, port, token) {
+            return IpcResponse::err(e);
+        }
+    } else {
+        control_api::stop();
+    }
+    control_api_status()
+}
+
+fn quiet_hours_response(status: crate::services::quiet_hours::QuietHoursStatus) -> IpcResponse {
+    match serde_json::to_value(&status) {
+        Ok(data) => IpcResponse::ok(data),
+        Err(e) => IpcResponse::err(format!("Serialize error: {}", e)),
+    }
+}
+
+/// Whether quiet hours are in effect, and why.
+#[tauri::command]
+pub fn get_quiet_hours_status(app_handle: AppHandle) -> IpcResponse {
+    quiet_hours_response(crate::services::quiet_hours::refresh(&app_handle))
+}
+
+/// Save the quiet hours schedule (`quietHours` config) and re-evaluate it.
+#[tauri::command]
+pub fn configure_quiet_hours(app_handle: AppHandle, quiet_hours: QuietHoursConfig) -> IpcResponse {
+    use crate::services::quiet_hours;
+
+    if let Err(e) = quiet_hours::validate(&quiet_hours) {
+        return IpcResponse::err(e);
+    }
+    let patch = match serde_json::to_value(&quiet_hours) {
+        Ok(v) => json!({ "quietHours": v }),
+        Err(e) => return IpcResponse::err(format!("Serialize error: {}", e)),
+    };
+    let saved = super::config::set_config(patch);
+    if !saved.success {
+        return saved;
+    }
+    quiet_hours_response(quiet_hours::refresh(&app_handle))
+}
+
+/// Override the quiet hours schedule: `mode` "quiet" (quiet now), "off"
+/// (not now), or "auto" (follow the schedule). Without `minutes` the
+/// override holds until the schedule next changes.
+#[tauri::command]
+pub fn set_quiet_hours_override(app_handle: AppHandle, mode: String, minutes: Option<u32>) -> IpcResponse {
+    match crate::services::quiet_hours::set_override(&app_handle, &mode, minutes) {
+        Ok(status) => quiet_hours_response(status),
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
 /// Start recording (PTT press / Toggle start).
 ///
 /// Transitions Idle/Listening → Recording. Used by the frontend
@@ -249,7 +685,7 @@ pub fn ptt_press(voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
 
     match engine.start_recording() {
         Ok(()) => IpcResponse::ok_empty(),
-        Err(e) => IpcResponse::err(e),
+        Err(e) => IpcResponse::from_error(e),
     }
 }
 
@@ -266,7 +702,7 @@ pub fn ptt_release(voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
 
     match engine.stop_recording() {
         Ok(()) => IpcResponse::ok_empty(),
-        Err(e) => IpcResponse::err(e),
+        Err(e) => IpcResponse::from_error(e),
     }
 }
 
@@ -281,7 +717,7 @@ pub fn cancel_recording(voice_state: State<'_, VoiceEngineState>) -> IpcResponse
 
     match engine.cancel_recording() {
         Ok(()) => IpcResponse::ok_empty(),
-        Err(e) => IpcResponse::err(e),
+        Err(e) => IpcResponse::from_error(e),
     }
 }
 
@@ -299,7 +735,8 @@ pub fn clear_inbox() -> IpcResponse {
 /// Configure the PTT key binding in the global input hook.
 ///
 /// Accepts key specs like `"kb:52"` (keyboard vkey 52 = the "4" key),
-/// `"mouse:4"` (mouse button 4 / back), or legacy `"MouseButton4"`.
+/// `"mouse:4"` (mouse button 4 / back), `"pad:0"` (gamepad button A, standard
+/// gamepad numbering) or legacy `"MouseButton4"`.
 /// The configured key is suppressed at the OS level for keyboard bindings,
 /// preventing "4444" from appearing in text fields while holding PTT.
 #[tauri::command]
@@ -338,13 +775,48 @@ pub async fn inject_text(text: String) -> IpcResponse {
 /// Downloads the model from HuggingFace if it doesn't exist locally.
 /// Emits `stt-download-progress` events with percentage and byte counts.
 /// Returns immediately if the model is already present on disk.
+///
+/// `language` selects the English-only or multilingual weights; when omitted
+/// the saved `voice.sttLanguage` setting is used.
 #[tauri::command]
-pub async fn ensure_stt_model(app_handle: AppHandle, model_size: String) -> IpcResponse {
+pub async fn ensure_stt_model(
+    app_handle: AppHandle,
+    model_size: String,
+    language: Option<String>,
+) -> IpcResponse {
     let data_dir = crate::services::platform::get_data_dir();
-    match crate::voice::stt::ensure_model_exists(&data_dir, &model_size, Some(&app_handle)).await {
+    let language = crate::voice::stt::normalize_stt_language(
+        &language.unwrap_or_else(|| super::config::get_config_snapshot().voice.stt_language),
+    );
+    match crate::voice::stt::ensure_model_exists(&data_dir, &model_size, &language, Some(&app_handle)).await {
         Ok(path) => IpcResponse::ok(json!({
             "path": path.display().to_string(),
             "modelSize": model_size,
+            "language": language,
+        })),
+        Err(e) => IpcResponse::err(format!("{}", e)),
+    }
+}
+
+/// Ensure the local Parakeet STT model (ONNX) is downloaded and ready.
+///
+/// Downloads the preprocessor, encoder, decoder and vocab (~670 MB) from
+/// HuggingFace into `get_data_dir()/models/parakeet`, skipping files already
+/// present. Emits `stt-download-progress` events with `modelSize: "parakeet"`.
+///
+/// Fails without downloading in builds without the `onnx` feature, where
+/// Parakeet can't run and the engine would fall back to whisper anyway.
+#[tauri::command]
+pub async fn ensure_parakeet_model(app_handle: AppHandle) -> IpcResponse {
+    if !cfg!(feature = "onnx") {
+        return IpcResponse::err(
+            "Parakeet requires a build with the onnx feature enabled",
+        );
+    }
+    let data_dir = crate::services::platform::get_data_dir();
+    match crate::voice::stt::ensure_parakeet_model_exists(&data_dir, Some(&app_handle)).await {
+        Ok(path) => IpcResponse::ok(json!({
+            "path": path.display().to_string(),
         })),
         Err(e) => IpcResponse::err(format!("{}", e)),
     }
@@ -385,23 +857,14 @@ pub fn restart_voice(
     voice_state: State<'_, VoiceEngineState>,
 ) -> IpcResponse {
     // Read the latest saved config so the engine picks up new STT model etc.
-    let app_cfg = super::config::get_config_snapshot();
-    let voice_cfg = crate::voice::VoiceEngineConfig {
-        mode: crate::voice::VoiceMode::from_str_flexible(
-            &app_cfg.behavior.activation_mode,
-        )
-        .unwrap_or_default(),
-        stt_adapter: app_cfg.voice.stt_adapter.clone(),
-        stt_model_size: app_cfg.voice.stt_model_size.clone(),
-        stt_use_gpu: app_cfg.voice.stt_use_gpu,
-        tts_adapter: app_cfg.voice.tts_adapter.clone(),
-        tts_voice: app_cfg.voice.tts_voice.clone(),
-        tts_speed: app_cfg.voice.tts_speed as f32,
-        tts_volume: app_cfg.voice.tts_volume as f32,
-        input_device: app_cfg.voice.input_device.clone(),
-        output_device: app_cfg.voice.output_device.clone(),
-        ..Default::default()
+    let voice_cfg = match active_engine_config(&super::config::get_config_snapshot()) {
+        Ok(cfg) => cfg,
+        Err(e) => return IpcResponse::from_error(e),
     };
+    let issues = voice_engine::validate(&voice_cfg);
+    if !issues.is_empty() {
+        return invalid_config(&issues);
+    }
 
     let mut engine = match voice_state.lock() {
         Ok(guard) => guard,
@@ -420,6 +883,7 @@ pub fn restart_voice(
     match engine.start(app_handle) {
         Ok(()) => {
             tracing::info!("Voice engine restarted successfully");
+            voice_engine::record(&crate::services::platform::get_config_dir(), engine.config());
             IpcResponse::ok(json!({
                 "running": true,
                 "wasRunning": was_running,
@@ -427,11 +891,430 @@ pub fn restart_voice(
         }
         Err(e) => {
             tracing::error!("Failed to restart voice engine: {}", e);
-            IpcResponse::err(format!("Restart failed: {}", e))
+            IpcResponse::from_error(e.context("Restart failed"))
+        }
+    }
+}
+
+/// List the saved voice profiles. Returns `{ profiles: [{ name, mode,
+/// inputDevice, outputDevice, ttsVoice }], active }`, `active` being the
+/// profile the engine starts with (`null` = the Voice settings).
+#[tauri::command]
+pub fn list_voice_profiles() -> IpcResponse {
+    match voice_profiles::list(&crate::services::platform::get_config_dir()) {
+        Ok(profiles) => IpcResponse::ok(json!({
+            "profiles": profiles,
+            "active": super::config::get_config_snapshot().voice.active_profile,
+        })),
+        Err(e) => IpcResponse::from_error(e),
+    }
+}
+
+/// Save the current Voice settings as the profile `name`, replacing one
+/// with that name. Settings the engine couldn't start with are refused.
+#[tauri::command]
+pub fn save_voice_profile(name: String) -> IpcResponse {
+    let voice_cfg = engine_config_from_app(&super::config::get_config_snapshot());
+    let issues = voice_engine::validate(&voice_cfg);
+    if !issues.is_empty() {
+        return invalid_config(&issues);
+    }
+    match voice_profiles::save(&crate::services::platform::get_config_dir(), &name, &voice_cfg) {
+        Ok(()) => {
+            tracing::info!(profile = %name, mode = %voice_cfg.mode, "Voice profile saved");
+            IpcResponse::ok(json!({ "name": name }))
+        }
+        Err(e) => IpcResponse::from_error(e),
+    }
+}
+
+/// Make `name` the active voice profile (`null` = back to the Voice
+/// settings) and restart the pipeline with it if it is running. The choice
+/// is saved to `voice.activeProfile`, so later starts use it too.
+// `(async)` — off the UI thread; restarts the engine while holding
+// voice_state, like `restart_voice`.
+#[tauri::command(async)]
+pub fn activate_voice_profile(
+    name: Option<String>,
+    app_handle: AppHandle,
+    voice_state: State<'_, VoiceEngineState>,
+) -> IpcResponse {
+    let mut engine = match voice_state.lock() {
+        Ok(guard) => guard,
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+
+    let switched = match &name {
+        Some(name) => engine.switch_profile(name, app_handle),
+        None => {
+            let voice_cfg = engine_config_from_app(&super::config::get_config_snapshot());
+            let issues = voice_engine::validate(&voice_cfg);
+            if !issues.is_empty() {
+                return invalid_config(&issues);
+            }
+            let restarted = engine.restart_with(voice_cfg, app_handle);
+            if let Ok(true) = restarted {
+                voice_engine::record(&crate::services::platform::get_config_dir(), engine.config());
+            }
+            restarted
+        }
+    };
+    let restarted = match switched {
+        Ok(restarted) => restarted,
+        Err(e) => return IpcResponse::from_error(e.context("Failed to switch voice profile")),
+    };
+
+    let saved = super::config::set_config(json!({ "voice": { "activeProfile": name } }));
+    if !saved.success {
+        return saved;
+    }
+    IpcResponse::ok(json!({
+        "active": name,
+        "restarted": restarted,
+    }))
+}
+
+/// Delete the voice profile `name`. Deleting the active profile switches
+/// later starts back to the Voice settings; a running pipeline keeps its
+/// config until then.
+#[tauri::command]
+pub fn delete_voice_profile(name: String) -> IpcResponse {
+    let deleted = match voice_profiles::delete(&crate::services::platform::get_config_dir(), &name) {
+        Ok(deleted) => deleted,
+        Err(e) => return IpcResponse::from_error(e),
+    };
+    if super::config::get_config_snapshot().voice.active_profile.as_deref() == Some(name.as_str()) {
+        let saved = super::config::set_config(json!({ "voice": { "activeProfile": null } }));
+        if !saved.success {
+            return saved;
+        }
+    }
+    IpcResponse::ok(json!({ "deleted": deleted }))
+}
+
+/// Calibrate voice activity detection for the current mic and room.
+///
+/// Records `calibration::PHASE_SECS` of silence, then the same of speech,
+/// emitting a `voice-calibration` event (`{ phase: "silence" | "speech", secs }`)
+/// as each take starts so the UI can prompt the user. Returns the recommended
+/// `vadThreshold` and `silenceTimeoutSecs` plus the measured levels; the
+/// frontend writes the recommendation back to config.
+///
+/// Wake-word listening is switched to push-to-talk for the duration so the
+/// speech take doesn't start a real recording.
+#[tauri::command]
+pub async fn voice_calibrate(app_handle: AppHandle) -> IpcResponse {
+    let voice_cfg = super::config::get_config_snapshot().voice;
+    let device = voice_cfg.input_device;
+    let resampler = ResamplerKind::from_config(&voice_cfg.resampler);
+
+    let paused_wake_word = pause_wake_word(&app_handle);
+    let takes = async {
+        let silence = record_calibration_take(&app_handle, device.clone(), resampler, "silence").await?;
+        let speech = record_calibration_take(&app_handle, device, resampler, "speech").await?;
+        Ok::<_, String>((silence, speech))
+    }
+    .await;
+    resume_wake_word(&app_handle, paused_wake_word);
+
+    match takes.and_then(|(silence, speech)| calibration::analyze(&silence, &speech)) {
+        Ok(cal) => {
+            tracing::info!(
+                vad_threshold = cal.vad_threshold,
+                silence_timeout_secs = cal.silence_timeout_secs,
+                snr_db = cal.snr_db,
+                "Voice calibration complete"
+            );
+            IpcResponse::ok(json!(cal))
+        }
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// Check the voice setup without starting the pipeline (see
+/// `voice::pipeline::self_test`): the microphone and output device open, the
+/// TTS engine speaks a test phrase and the STT engine transcribes it back.
+/// Uses the saved voice settings and its own engines, so it works whether or
+/// not the pipeline is running. Returns `{ ok, checks: [{ name, status,
+/// detail, durationMs }] }`.
+#[tauri::command]
+pub async fn voice_self_test() -> IpcResponse {
+    let config = match active_engine_config(&super::config::get_config_snapshot()) {
+        Ok(cfg) => cfg,
+        Err(e) => return IpcResponse::from_error(e),
+    };
+    let report = crate::voice::pipeline::self_test::run(config).await;
+    tracing::info!(ok = report.ok, "Voice self-test finished");
+    IpcResponse::ok(json!(report))
+}
+
+/// Development: replay a labeled clip corpus through VAD and endpointing
+/// (see `voice::vad_corpus`) and report false-trigger, miss and clipping
+/// rates. Uses the saved voice settings unless `vad_threshold` /
+/// `silence_timeout_secs` are given, so a tuning change can be compared
+/// before it's saved. Not available in release builds.
+#[tauri::command]
+pub async fn run_vad_corpus(
+    dir: String,
+    vad_threshold: Option<f32>,
+    silence_timeout_secs: Option<f64>,
+) -> IpcResponse {
+    if !cfg!(debug_assertions) {
+        return IpcResponse::err("The VAD corpus runner is only available in development builds");
+    }
+    let voice_cfg = super::config::get_config_snapshot().voice;
+    let params = vad_corpus::EndpointParams {
+        vad_threshold: vad_threshold.unwrap_or(voice_cfg.vad_threshold as f32),
+        silence_timeout_secs: silence_timeout_secs.unwrap_or(voice_cfg.silence_timeout_secs),
+        max_recording_secs: voice_cfg.max_recording_secs,
+        min_speech_coverage: voice_cfg.min_speech_coverage as f32,
+    };
+    let resampler = ResamplerKind::from_config(&voice_cfg.resampler);
+    tokio::task::spawn_blocking(move || vad_corpus::run(std::path::Path::new(&dir), params, resampler))
+        .await
+        .map_err(|e| format!("VAD corpus run panicked: {}", e))
+        .and_then(|r| r)
+        .map_or_else(IpcResponse::err, |report| IpcResponse::ok(json!(report)))
+}
+
+/// Stage timings of the most recent voice turns (oldest first), for the
+/// diagnostics UI. Each entry has the same shape as the `pipeline_timing`
+/// voice event.
+#[tauri::command]
+pub fn get_pipeline_timings() -> IpcResponse {
+    IpcResponse::ok(json!(crate::voice::pipeline::timing::history()))
+}
+
+/// The most recent voice errors (see `voice::errors`), oldest first: `[{ id,
+/// at, code, message, turn }]`. `turn` matches the `turn` of a pipeline
+/// timing. `limit` keeps only the newest ones.
+#[tauri::command]
+pub fn voice_get_errors(limit: Option<usize>) -> IpcResponse {
+    IpcResponse::ok(json!(crate::voice::errors::recent(limit)))
+}
+
+/// Empty the voice error log.
+#[tauri::command]
+pub fn voice_clear_errors() -> IpcResponse {
+    crate::voice::errors::clear();
+    IpcResponse::ok_empty()
+}
+
+/// Counters and latency histograms since the app started (see
+/// `services::metrics`): `{ counters: { name: n }, histograms: { name:
+/// { count, sumMs, buckets: [[le, n]] } } }`.
+#[tauri::command]
+pub fn get_metrics() -> IpcResponse {
+    IpcResponse::ok(json!(crate::services::metrics::snapshot()))
+}
+
+/// Where the latency ladder stands (see `voice::latency_budget`). Returns
+/// `{ budgetMs, level, pinned, strikes, steps: [{ level, rung, detail }] }`,
+/// `steps` being what the current level changes in the voice settings.
+#[tauri::command]
+pub fn get_latency_ladder(voice_state: State<'_, VoiceEngineState>) -> IpcResponse {
+    let engine = match voice_state.lock() {
+        Ok(guard) => guard,
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+    let (level, pinned, strikes) = latency_budget::status();
+    IpcResponse::ok(json!({
+        "budgetMs": engine.config().latency_budget_ms,
+        "level": level,
+        "pinned": pinned,
+        "strikes": strikes,
+        "steps": latency_budget::applied_steps(engine.config()),
+    }))
+}
+
+/// Override the latency ladder: `level` pins it there (0 = full quality,
+/// no automatic steps), `null` returns to automatic stepping from the top.
+/// A running pipeline restarts with the new level.
+// `(async)` — off the UI thread; may restart the engine, like `restart_voice`.
+#[tauri::command(async)]
+pub fn set_latency_ladder(
+    level: Option<usize>,
+    app_handle: AppHandle,
+    voice_state: State<'_, VoiceEngineState>,
+) -> IpcResponse {
+    let mut engine = match voice_state.lock() {
+        Ok(guard) => guard,
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+    let (previous, _, _) = latency_budget::status();
+    let level = latency_budget::set_override(level);
+    let pinned = latency_budget::status().1;
+    tracing::info!(level, pinned, "Latency ladder set by hand");
+
+    let steps = latency_budget::applied_steps(engine.config());
+    let _ = app_handle.emit(
+        "voice-event",
+        VoiceEvent::LatencyLadder {
+            level,
+            rung: steps.last().map(|s| s.rung),
+            detail: steps
+                .iter()
+                .map(|s| s.detail.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            latency_ms: None,
+            budget_ms: engine.config().latency_budget_ms,
+            manual: true,
+        },
+    );
+
+    let restarted = if level != previous {
+        let config = engine.config().clone();
+        match engine.restart_with(config, app_handle) {
+            Ok(restarted) => restarted,
+            Err(e) => return IpcResponse::from_error(e.context("Restart failed")),
+        }
+    } else {
+        false
+    };
+    IpcResponse::ok(json!({
+        "level": level,
+        "pinned": pinned,
+        "restarted": restarted,
+    }))
+}
+
+/// Which voice features this build and machine support, each with a reason
+/// when it's unavailable, so Settings can hide or explain options instead of
+/// failing when the pipeline starts. See `voice::capabilities`.
+#[tauri::command]
+pub fn get_capabilities() -> IpcResponse {
+    let config = super::config::get_config_snapshot();
+    let data_dir = crate::services::platform::get_data_dir();
+    IpcResponse::ok(json!(capabilities::detect(&config.voice, &data_dir)))
+}
+
+/// Daily voice activity for the last `days` days (default 7, today
+/// included), oldest first, plus their totals. Days without activity are
+/// left out.
+#[tauri::command]
+pub fn get_voice_stats(days: Option<u32>) -> IpcResponse {
+    let today = voice_stats::today();
+    let stats = voice_stats::load(&crate::services::platform::get_data_dir());
+    let recent = stats.recent(&today, days.unwrap_or(7).clamp(1, 90));
+    let total = voice_stats::total(&recent, &today);
+    let day_json = |d: &voice_stats::DayStats| {
+        json!({
+            "date": d.date,
+            "talkMs": d.talk_ms,
+            "utterances": d.utterances,
+            "averageUtteranceMs": d.average_utterance_ms(),
+            "interruptions": d.interruptions,
+        })
+    };
+    IpcResponse::ok(json!({
+        "days": recent.iter().map(day_json).collect::<Vec<_>>(),
+        "today": day_json(&stats.day(&today)),
+        "total": day_json(&total),
+    }))
+}
+
+/// Enrolled speakers: `[{ name, samples }]`.
+#[tauri::command]
+pub fn list_speakers() -> IpcResponse {
+    let profiles = speaker::load_profiles(&crate::services::platform::get_data_dir());
+    IpcResponse::ok(json!(profiles
+        .iter()
+        .map(|p| json!({ "name": p.name, "samples": p.samples }))
+        .collect::<Vec<_>>()))
+}
+
+/// Enroll `name`'s voice from recordings in the turn archive (`turnIds`),
+/// adding to their profile if they're already enrolled. Recordings too
+/// short to embed are skipped. Returns `{ name, samples, used }`.
+#[tauri::command]
+pub async fn enroll_speaker(name: String, turn_ids: Vec<u64>) -> IpcResponse {
+    let data_dir = crate::services::platform::get_data_dir();
+    let result = tokio::task::spawn_blocking(move || {
+        let encoder = speaker::SpeakerEncoder::load(&data_dir)?;
+        let embeddings = turn_ids
+            .iter()
+            .filter_map(|id| turn_archive::get(&data_dir, *id).ok())
+            .filter_map(|(turn, clip)| turn_archive::decode_clip(&clip, turn.format).ok())
+            .filter(|audio| audio.len() >= speaker::MIN_SAMPLES)
+            .map(|audio| encoder.embed(&audio))
+            .collect::<Result<Vec<_>, _>>()?;
+        if embeddings.is_empty() {
+            return Err(AppError::Config(
+                "No usable recordings: pick turns at least half a second long".into(),
+            ));
+        }
+        let profile =
+            speaker::enroll(&data_dir, &name, &embeddings).map_err(AppError::Internal)?;
+        Ok((profile, embeddings.len()))
+    })
+    .await
+    .unwrap_or_else(|e| Err(AppError::Internal(format!("Enrollment task failed: {}", e))));
+
+    match result {
+        Ok((profile, used)) => {
+            tracing::info!(name = %profile.name, used, "Speaker enrolled");
+            IpcResponse::ok(json!({
+                "name": profile.name,
+                "samples": profile.samples,
+                "used": used,
+            }))
+        }
+        Err(e) => IpcResponse::from_error(e.context("Enrollment failed")),
+    }
+}
+
+/// Forget an enrolled speaker.
+#[tauri::command]
+pub fn delete_speaker(name: String) -> IpcResponse {
+    match speaker::delete(&crate::services::platform::get_data_dir(), &name) {
+        Ok(true) => IpcResponse::ok_empty(),
+        Ok(false) => IpcResponse::err(format!("No enrolled speaker named {}", name)),
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+/// Switch a running wake-word engine to push-to-talk while an offline
+/// recording (calibration, onboarding) has the mic, so what the user says
+/// doesn't start a real recording. Returns whether it switched; hand that to
+/// `resume_wake_word` afterwards.
+pub(crate) fn pause_wake_word(app_handle: &AppHandle) -> bool {
+    match app_handle.state::<VoiceEngineState>().lock() {
+        Ok(mut engine) if engine.is_running() && engine.config().mode == VoiceMode::WakeWord => {
+            engine.set_mode(VoiceMode::PushToTalk);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Undo `pause_wake_word`.
+pub(crate) fn resume_wake_word(app_handle: &AppHandle, paused: bool) {
+    if paused {
+        if let Ok(mut engine) = app_handle.state::<VoiceEngineState>().lock() {
+            engine.set_mode(VoiceMode::WakeWord);
         }
     }
 }
 
+/// Announce `phase` to the frontend and record one calibration take.
+async fn record_calibration_take(
+    app_handle: &AppHandle,
+    device: Option<String>,
+    resampler: ResamplerKind,
+    phase: &str,
+) -> Result<Vec<f32>, String> {
+    let _ = app_handle.emit(
+        "voice-calibration",
+        json!({ "phase": phase, "secs": calibration::PHASE_SECS }),
+    );
+    tokio::task::spawn_blocking(move || {
+        calibration::record(device.as_deref(), calibration::PHASE_SECS, resampler)
+    })
+    .await
+    .map_err(|e| format!("Calibration task failed: {}", e))?
+}
+
 /// Detect whether espeak-ng (required by the local Kokoro TTS voice to phonemize
 /// text) is available, and where. Mirrors `KokoroTts::find_espeak_ng`'s lookup
 /// (PATH → dev `tools/espeak-ng/` → packaged `{exe}/espeak-ng/`). Backs the TTS
@@ -493,37 +1376,23 @@ pub fn detect_gpu() -> IpcResponse {
     let cuda_compiled = cfg!(feature = "cuda");
 
     // Try NVIDIA first via nvidia-smi
-    let mut smi_cmd = std::process::Command::new("nvidia-smi");
-    smi_cmd.args(["--query-gpu=name,memory.total,driver_version", "--format=csv,noheader"]);
-    crate::util::hidden(&mut smi_cmd);
-    if let Ok(output) = smi_cmd.output() {
-        if output.status.success() {
-            let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            // Parse "NVIDIA GeForce RTX 5070 Ti, 16303 MiB, 581.80"
-            let parts: Vec<&str> = text.split(", ").collect();
-            let name = parts.first().unwrap_or(&"Unknown").to_string();
-            let vram_mb = parts
-                .get(1)
-                .and_then(|s| s.replace(" MiB", "").parse::<u64>().ok());
-            let driver = parts.get(2).unwrap_or(&"").to_string();
-
-            tracing::info!(
-                gpu = %name,
-                vram_mb = ?vram_mb,
-                driver = %driver,
-                cuda_compiled = cuda_compiled,
-                "NVIDIA GPU detected"
-            );
+    if let Some(gpu) = crate::voice::hardware::nvidia_gpu() {
+        tracing::info!(
+            gpu = %gpu.name,
+            vram_mb = ?gpu.vram_mb,
+            driver = %gpu.driver_version,
+            cuda_compiled = cuda_compiled,
+            "NVIDIA GPU detected"
+        );
 
-            return IpcResponse::ok(json!({
-                "available": true,
-                "vendor": "nvidia",
-                "name": name,
-                "vramMb": vram_mb,
-                "driverVersion": driver,
-                "cudaCompiled": cuda_compiled,
-            }));
-        }
+        return IpcResponse::ok(json!({
+            "available": true,
+            "vendor": "nvidia",
+            "name": gpu.name,
+            "vramMb": gpu.vram_mb,
+            "driverVersion": gpu.driver_version,
+            "cudaCompiled": cuda_compiled,
+        }));
     }
 
     // Fallback: detect non-NVIDIA GPUs via wmic (Windows) or lspci (Linux)
@@ -620,6 +1489,59 @@ fn detect_gpu_fallback() -> Option<(String, String)> {
     None
 }
 
+/// Probe the hardware (CPU cores, SIMD, RAM, NVIDIA GPU) and recommend the
+/// largest whisper model expected to transcribe within `target_latency_ms`
+/// (default `voice.sttTargetLatencyMs`). See `voice::hardware`.
+///
+/// With `apply`, saves the recommendation as `voice.sttModelSize` and
+/// `voice.sttUseGpu`; the model still has to be downloaded and the voice
+/// engine restarted. Returns `{ hardware, recommendation, applied }`.
+#[tauri::command(async)]
+pub fn recommend_stt_model(apply: Option<bool>, target_latency_ms: Option<u64>) -> IpcResponse {
+    use crate::voice::hardware;
+
+    let target = target_latency_ms.unwrap_or_else(|| {
+        super::config::get_config_snapshot().voice.stt_target_latency_ms as u64
+    });
+    let profile = hardware::probe();
+    let rec = hardware::recommend(&profile, target);
+    tracing::info!(
+        model = rec.model_size,
+        use_gpu = rec.use_gpu,
+        estimated_ms = rec.estimated_ms,
+        target_ms = target,
+        "Recommended whisper model"
+    );
+
+    let applied = apply.unwrap_or(false);
+    if applied {
+        let saved = super::config::set_config(json!({
+            "voice": { "sttModelSize": rec.model_size, "sttUseGpu": rec.use_gpu },
+        }));
+        if !saved.success {
+            return saved;
+        }
+    }
+    IpcResponse::ok(json!({
+        "hardware": profile,
+        "recommendation": rec,
+        "applied": applied,
+    }))
+}
+
+/// Whisper model files the settings UI can list and delete:
+/// (size, filename, multilingual).
+const KNOWN_STT_MODELS: &[(&str, &str, bool)] = &[
+    ("tiny", "ggml-tiny.en.bin", false),
+    ("tiny", "ggml-tiny.bin", true),
+    ("base", "ggml-base.en.bin", false),
+    ("base", "ggml-base.bin", true),
+    ("small", "ggml-small.en.bin", false),
+    ("small", "ggml-small.bin", true),
+    ("large-v3-turbo", "ggml-large-v3-turbo-q5_0.bin", true),
+    ("large-v3", "ggml-large-v3.bin", true),
+];
+
 /// List installed Whisper STT models on disk.
 ///
 /// Scans the models directory for known GGML model files and returns
@@ -629,22 +1551,15 @@ pub fn list_stt_models() -> IpcResponse {
     let data_dir = crate::services::platform::get_data_dir();
     let models_dir = data_dir.join("models");
 
-    let known_models: &[(&str, &str)] = &[
-        ("tiny", "ggml-tiny.en.bin"),
-        ("base", "ggml-base.en.bin"),
-        ("small", "ggml-small.en.bin"),
-        ("large-v3-turbo", "ggml-large-v3-turbo-q5_0.bin"),
-        ("large-v3", "ggml-large-v3.bin"),
-    ];
-
     let mut installed = Vec::new();
-    for (size, filename) in known_models {
+    for (size, filename, multilingual) in KNOWN_STT_MODELS {
         let path = models_dir.join(filename);
         if path.exists() {
             let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
             installed.push(json!({
                 "modelSize": size,
                 "filename": filename,
+                "multilingual": multilingual,
                 "sizeMb": (bytes as f64 / 1_048_576.0).round(),
             }));
         }
@@ -657,17 +1572,43 @@ pub fn list_stt_models() -> IpcResponse {
 ///
 /// Refuses to delete a model that is currently in use by the running
 /// voice engine. Returns the deleted model size on success.
+///
+/// `filename` is the exact file reported by `list_stt_models` (so the
+/// English-only and multilingual rows of one size delete the right file).
+/// When omitted, the file is resolved from `model_size` and the saved
+/// `voice.sttLanguage` setting.
 #[tauri::command]
 pub fn delete_stt_model(
     model_size: String,
+    filename: Option<String>,
     voice_state: State<'_, VoiceEngineState>,
 ) -> IpcResponse {
+    let target_file = match filename {
+        Some(name) => {
+            // Only files we list for this size -- never an arbitrary path
+            if !KNOWN_STT_MODELS
+                .iter()
+                .any(|(size, file, _)| *size == model_size && *file == name)
+            {
+                return IpcResponse::err(format!("Unknown model file: {}", name));
+            }
+            name
+        }
+        None => {
+            let language = crate::voice::stt::normalize_stt_language(
+                &super::config::get_config_snapshot().voice.stt_language,
+            );
+            crate::voice::stt::model_filename_for_language(&model_size, &language)
+        }
+    };
+
     // Safety: refuse to delete if voice engine is running with this model
     let engine = match voice_state.lock() {
         Ok(guard) => guard,
         Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
     };
     let active_model = engine.config().stt_model_size.clone();
+    let active_language = engine.config().stt_language.clone();
     let is_running = engine.is_running();
     tracing::info!(
         model_size = %model_size,
@@ -675,7 +1616,8 @@ pub fn delete_stt_model(
         is_running = is_running,
         "delete_stt_model requested"
     );
-    if is_running && active_model == model_size {
+    let active_file = crate::voice::stt::model_filename_for_language(&active_model, &active_language);
+    if is_running && active_file == target_file {
         return IpcResponse::err(
             "Cannot delete the active model. Stop the voice engine first.",
         );
@@ -683,7 +1625,7 @@ pub fn delete_stt_model(
     drop(engine); // release lock before file I/O
 
     let data_dir = crate::services::platform::get_data_dir();
-    let filename = crate::voice::stt::model_filename(&model_size);
+    let filename = target_file;
     let model_path = data_dir.join("models").join(&filename);
     tracing::info!(model_path = %model_path.display(), exists = model_path.exists(), "delete target");
 
@@ -705,7 +1647,7 @@ pub fn delete_stt_model(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::voice::{VoiceEngineConfig, VoiceState};
+    use crate::voice::VoiceState;
 
     #[test]
     fn test_voice_engine_creation() {
@@ -759,4 +1701,47 @@ mod tests {
         );
         assert_eq!(VoiceMode::from_str_flexible("invalid"), None);
     }
+
+    #[test]
+    fn test_engine_config_from_app() {
+        let mut app_cfg = AppConfig::default();
+        app_cfg.behavior.activation_mode = "toggle".into();
+        app_cfg.voice.stt_language = "auto".into();
+        app_cfg.voice.stt_model_size = "small".into();
+
+        let cfg = engine_config_from_app(&app_cfg);
+        assert_eq!(cfg.mode, VoiceMode::Toggle);
+        assert_eq!(cfg.stt_language, "auto");
+        assert_eq!(cfg.stt_model_size, "small");
+        assert_eq!(cfg.stt_task, crate::voice::stt::SttTask::Transcribe);
+
+        app_cfg.voice.stt_language = "de-AT".into();
+        assert_eq!(engine_config_from_app(&app_cfg).stt_language, "de");
+
+        app_cfg.voice.stt_task = "translate".into();
+        assert_eq!(engine_config_from_app(&app_cfg).stt_task, crate::voice::stt::SttTask::Translate);
+    }
+
+    #[test]
+    fn test_config_issues_reports_profile_errors() {
+        assert!(config_issues(Ok(VoiceEngineConfig::default())).is_empty());
+
+        let issues = config_issues(Err(AppError::Config(
+            "No voice profile named 'desk'".into(),
+        )));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].field, "voice.activeProfile");
+        assert!(issues[0].message.contains("'desk'"));
+    }
+
+    #[test]
+    fn test_known_stt_models_match_resolver() {
+        for (size, filename, multilingual) in KNOWN_STT_MODELS {
+            let language = if *multilingual { "auto" } else { "en" };
+            assert_eq!(
+                crate::voice::stt::model_filename_for_language(size, language),
+                *filename
+            );
+        }
+    }
 }
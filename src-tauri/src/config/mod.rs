@@ -1,3 +1,5 @@
 pub mod crypto;
 pub mod persistence;
 pub mod schema;
+pub mod voice_engine;
+pub mod voice_profiles;
@@ -160,6 +160,41 @@ fn decrypt_api_keys(config_dir: &Path, config: &mut AppConfig) {
         }
     }
 
+    // Decrypt browser.brave_api_key
+    if let Some(ref encrypted) = config.browser.brave_api_key {
+        if !encrypted.is_empty() {
+            if !crypto::is_encrypted(encrypted) {
+                needs_migration = true;
+            }
+            let plaintext = crypto::decrypt_value(encrypted, &key);
+            config.browser.brave_api_key = if plaintext.is_empty() { None } else { Some(plaintext) };
+        }
+    }
+
+    // Decrypt control_api.token
+    if let Some(ref encrypted) = config.control_api.token {
+        if !encrypted.is_empty() {
+            if !crypto::is_encrypted(encrypted) {
+                needs_migration = true;
+            }
+            let plaintext = crypto::decrypt_value(encrypted, &key);
+            config.control_api.token = if plaintext.is_empty() { None } else { Some(plaintext) };
+        }
+    }
+
+    // Decrypt webhooks[].secret
+    for hook in config.webhooks.iter_mut() {
+        if let Some(ref encrypted) = hook.secret {
+            if !encrypted.is_empty() {
+                if !crypto::is_encrypted(encrypted) {
+                    needs_migration = true;
+                }
+                let plaintext = crypto::decrypt_value(encrypted, &key);
+                hook.secret = if plaintext.is_empty() { None } else { Some(plaintext) };
+            }
+        }
+    }
+
     // If any keys were plaintext, re-save with encryption
     if needs_migration {
         info!("Migrating plaintext API keys to encrypted format");
@@ -201,6 +236,29 @@ fn encrypt_api_keys(config_dir: &Path, config: &mut AppConfig) {
             config.voice.stt_api_key = Some(crypto::encrypt_value(plaintext, &key));
         }
     }
+
+    // Encrypt browser.brave_api_key
+    if let Some(ref plaintext) = config.browser.brave_api_key {
+        if !plaintext.is_empty() && !crypto::is_encrypted(plaintext) {
+            config.browser.brave_api_key = Some(crypto::encrypt_value(plaintext, &key));
+        }
+    }
+
+    // Encrypt control_api.token
+    if let Some(ref plaintext) = config.control_api.token {
+        if !plaintext.is_empty() && !crypto::is_encrypted(plaintext) {
+            config.control_api.token = Some(crypto::encrypt_value(plaintext, &key));
+        }
+    }
+
+    // Encrypt webhooks[].secret
+    for hook in config.webhooks.iter_mut() {
+        if let Some(ref plaintext) = hook.secret {
+            if !plaintext.is_empty() && !crypto::is_encrypted(plaintext) {
+                hook.secret = Some(crypto::encrypt_value(plaintext, &key));
+            }
+        }
+    }
 }
 
 #[cfg(test)]
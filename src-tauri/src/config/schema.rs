@@ -42,6 +42,26 @@ pub struct AppConfig {
     #[serde(default)]
     pub browser: BrowserConfig,
     #[serde(default)]
+    pub control_api: ControlApiConfig,
+    #[serde(default)]
+    pub hotkeys: HotkeyConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub files: FilesConfig,
+    /// Outbound webhooks fired on voice events (home automation).
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+    /// Post-processing of STT text (see `voice::transcript_rules`).
+    #[serde(default)]
+    pub transcript_rules: TranscriptRulesConfig,
+    /// Where transcriptions go (see `voice::routing`). Empty = the AI
+    /// provider, or the text injector while dictating.
+    #[serde(default)]
+    pub transcript_routes: Vec<TranscriptRouteConfig>,
+    #[serde(default)]
     pub terminal_layout: Option<serde_json::Value>,
 }
 
@@ -81,6 +101,26 @@ pub struct VoiceConfig {
     pub tts_speed: f64,
     #[serde(default = "default_one")]
     pub tts_volume: f64,
+    /// Voice effect chain on TTS output, e.g. `["robot"]` or
+    /// `["pitch:-5", "reverb:0.3"]` (see `voice::tts::effects`).
+    #[serde(default)]
+    pub tts_effects: Vec<String>,
+    /// Silence (ms) inserted between streamed TTS phrases.
+    #[serde(default = "default_tts_phrase_gap_ms")]
+    pub tts_phrase_gap_ms: u32,
+    /// Silence (ms) after a phrase ending in `.`, `!` or `?` (replaces the gap).
+    #[serde(default = "default_tts_sentence_pause_ms")]
+    pub tts_sentence_pause_ms: u32,
+    /// Phrases synthesized concurrently ahead of playback (1 - 4; 1 =
+    /// one at a time).
+    #[serde(default = "default_tts_lookahead")]
+    pub tts_lookahead: u32,
+    /// Words/phrases never spoken aloud (still shown in the UI). Empty = off.
+    #[serde(default)]
+    pub tts_blocked_terms: Vec<String>,
+    /// What to do on a blocked term: "mask" (skip the words) or "stop".
+    #[serde(default = "default_tts_filter_action")]
+    pub tts_filter_action: String,
     #[serde(default)]
     pub tts_api_key: Option<String>,
     #[serde(default)]
@@ -101,10 +141,99 @@ pub struct VoiceConfig {
     pub stt_model_name: Option<String>,
     #[serde(default)]
     pub stt_use_gpu: bool,
+    /// Latency budget (ms) for transcribing a typical utterance. The model
+    /// recommendation picks the largest whisper model expected to meet it.
+    #[serde(default = "default_stt_target_latency_ms")]
+    pub stt_target_latency_ms: u32,
+    /// STT language: ISO 639-1 code ("en", "de", ...) or "auto" to detect.
+    /// Anything other than "en" uses the multilingual whisper model.
+    #[serde(default = "default_stt_language")]
+    pub stt_language: String,
+    /// Whisper task: "transcribe" (default) or "translate" to English text.
+    /// The source language is still reported with each transcription.
+    #[serde(default = "default_stt_task")]
+    pub stt_task: String,
+    /// Words of recent transcriptions fed back to whisper as context for
+    /// the next utterance (0 = off).
+    #[serde(default = "default_stt_context_words")]
+    pub stt_context_words: u32,
+    /// Fixed text that leads whisper's prompt on every decode, e.g. a
+    /// sentence setting the topic or style. Empty = none.
+    #[serde(default)]
+    pub stt_initial_prompt: String,
+    /// Names and jargon whisper should recognise ("Kokoro", "n8n"), passed
+    /// in the prompt after `stt_initial_prompt`.
+    #[serde(default)]
+    pub stt_vocabulary: Vec<String>,
+    /// Minimum fraction of a recording that must be speech before STT runs.
+    #[serde(default = "default_min_speech_coverage")]
+    pub min_speech_coverage: f64,
+    /// VAD energy threshold for speech. `voice_calibrate` recommends one.
+    #[serde(default = "default_vad_threshold")]
+    pub vad_threshold: f64,
+    /// Seconds of silence that end a recording (except in toggle mode).
+    #[serde(default = "default_silence_timeout_secs")]
+    pub silence_timeout_secs: f64,
+    /// Force-stop a recording after this many seconds (0 = unlimited).
+    #[serde(default = "default_max_recording_secs")]
+    pub max_recording_secs: f64,
+    /// Auto-stop a toggle-mode recording after this many seconds (0 = off),
+    /// in case the user forgets the mic is live.
+    #[serde(default = "default_toggle_timeout_secs")]
+    pub toggle_timeout_secs: f64,
     #[serde(default)]
     pub input_device: Option<String>,
+    /// What to record: "microphone" (`input_device`), "system" (what the
+    /// output device is playing; WASAPI loopback, Windows only) or "both"
+    /// (the mic as usual, plus system audio transcribed separately).
+    #[serde(default = "default_capture_source")]
+    pub capture_source: String,
     #[serde(default)]
     pub output_device: Option<String>,
+    /// Extra output devices TTS is mirrored to, alongside `output_device`.
+    #[serde(default)]
+    pub output_devices: Vec<String>,
+    /// Turn other apps' audio down while recording (Windows only for now).
+    #[serde(default)]
+    pub duck_while_recording: bool,
+    /// Fraction of their volume other apps keep while ducked (0.0 - 1.0).
+    #[serde(default = "default_duck_level")]
+    pub duck_level: f64,
+    /// Run RNNoise on mic audio before VAD/STT (`noise-suppression` builds).
+    #[serde(default)]
+    pub noise_suppression: bool,
+    /// Duty-cycle idle wake-word listening to save power: "off", "battery"
+    /// (only while unplugged), or "always".
+    #[serde(default = "default_listen_duty_cycle")]
+    pub listen_duty_cycle: String,
+    /// Sample rate converter for mic capture and TTS playback: "linear"
+    /// (cheap) or "sinc" (windowed-sinc, no aliasing).
+    #[serde(default = "default_resampler")]
+    pub resampler: String,
+    /// "communications" opens the mic the way call apps do, so OS echo
+    /// cancellation, noise suppression and ducking policies apply (see
+    /// `services::audio_session`); "default" doesn't.
+    #[serde(default = "default_audio_session")]
+    pub audio_session: String,
+    /// Rate the capture pipeline runs at: 8000, 16000, 24000 or 48000 Hz.
+    /// STT still gets 16 kHz; recordings are converted when transcribed.
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: u32,
+    /// Length of each captured chunk, which is also the live VAD frame
+    /// (10-200 ms).
+    #[serde(default = "default_chunk_ms")]
+    pub chunk_ms: u32,
+    /// Keep a transcript of what was said and spoken in
+    /// `{data_dir}/transcripts/` (one JSONL file per session).
+    #[serde(default = "default_true")]
+    pub transcript_log: bool,
+    /// How many recent recordings to keep in `{data_dir}/turn_audio/` for
+    /// replay next to their transcription. 0 = don't keep any.
+    #[serde(default = "default_turn_archive_size")]
+    pub turn_archive_size: u32,
+    /// Codec of archived recordings: "wav", "flac" (lossless) or "opus".
+    #[serde(default = "default_turn_archive_format")]
+    pub turn_archive_format: String,
     #[serde(default = "default_true")]
     pub announce_startup: bool,
     #[serde(default = "default_true")]
@@ -114,6 +243,67 @@ pub struct VoiceConfig {
     /// transcription. Empty by default.
     #[serde(default)]
     pub dictionary: Vec<DictionaryEntry>,
+    /// Handle spoken commands ("stop talking", "louder", ...) locally
+    /// instead of sending them to the AI provider.
+    #[serde(default = "default_true")]
+    pub local_commands: bool,
+    /// Extra phrases for the local voice commands.
+    #[serde(default)]
+    pub commands: Vec<VoiceCommandConfig>,
+    /// In wake-word mode, ask aloud before a destructive tool runs and
+    /// take a spoken yes or no (see `voice::confirm`).
+    #[serde(default = "default_true")]
+    pub spoken_confirmation: bool,
+    /// When the STT model is loaded: "lazy" (first recording), "background"
+    /// (after the engine starts) or "blocking" (before it reports ready).
+    #[serde(default = "default_engine_load")]
+    pub stt_load: String,
+    /// When the TTS model is loaded; same values as `stt_load`.
+    #[serde(default = "default_engine_load")]
+    pub tts_load: String,
+    /// Identify the speaker of each turn against the enrolled voices
+    /// (`{data_dir}/speakers.json`). Needs the speaker model.
+    #[serde(default)]
+    pub speaker_id: bool,
+    /// Cosine similarity a voice must reach to count as an enrolled speaker.
+    #[serde(default = "default_speaker_threshold")]
+    pub speaker_threshold: f64,
+    /// In wake-word mode, ignore turns whose voice matches no enrolled
+    /// speaker (TV, other people in the room).
+    #[serde(default = "default_true")]
+    pub ignore_unknown_speakers: bool,
+    /// Seconds without a voice turn or reply before the conversation
+    /// session ends (see `voice::session`). 0 = never.
+    #[serde(default = "default_session_timeout_secs")]
+    pub session_timeout_secs: u64,
+    /// What a voice turn does while an API provider is still answering the
+    /// last one: "queue" (send after), "replace" (keep only the newest
+    /// waiting turn) or "reject" (drop it with a spoken notice).
+    #[serde(default = "default_turn_policy")]
+    pub turn_policy: String,
+    /// Named voice profile the engine starts with instead of these
+    /// settings (see `config::voice_profiles`). `None` = use these.
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Pipeline latency per turn (STT plus time to first audio, provider
+    /// excluded) above which the engine steps down to cheaper settings
+    /// (see `voice::latency_budget`). 0 = off.
+    #[serde(default)]
+    pub latency_budget_ms: u64,
+    /// Restart the voice pipeline when its watchdog finds the audio loop
+    /// stalled (see `voice::pipeline::watchdog`).
+    #[serde(default = "default_true")]
+    pub watchdog_auto_restart: bool,
+}
+
+/// An extra phrase for a local voice command, e.g.
+/// `{ phrase: "hush", action: "stopSpeaking" }`. Actions: stopSpeaking,
+/// pushToTalkMode, toggleMode, wakeWordMode, louder, quieter, repeat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceCommandConfig {
+    pub phrase: String,
+    pub action: String,
 }
 
 /// A single transcription correction: replace `from` with `to`.
@@ -136,6 +326,12 @@ impl Default for VoiceConfig {
             tts_model_size: "0.6B".into(),
             tts_speed: 1.0,
             tts_volume: 1.0,
+            tts_effects: Vec::new(),
+            tts_phrase_gap_ms: 80,
+            tts_sentence_pause_ms: 250,
+            tts_lookahead: 2,
+            tts_blocked_terms: Vec::new(),
+            tts_filter_action: "mask".into(),
             tts_api_key: None,
             tts_endpoint: None,
             tts_model_path: None,
@@ -146,11 +342,48 @@ impl Default for VoiceConfig {
             stt_endpoint: None,
             stt_model_name: None,
             stt_use_gpu: false,
+            stt_target_latency_ms: default_stt_target_latency_ms(),
+            stt_language: "en".into(),
+            stt_task: "transcribe".into(),
+            stt_context_words: 32,
+            stt_initial_prompt: String::new(),
+            stt_vocabulary: Vec::new(),
+            min_speech_coverage: 0.05,
+            vad_threshold: 0.01,
+            silence_timeout_secs: 2.0,
+            max_recording_secs: 120.0,
+            toggle_timeout_secs: 60.0,
             input_device: None,
+            capture_source: default_capture_source(),
             output_device: None,
+            output_devices: Vec::new(),
+            duck_while_recording: false,
+            duck_level: 0.2,
+            noise_suppression: false,
+            listen_duty_cycle: "battery".into(),
+            resampler: "linear".into(),
+            audio_session: default_audio_session(),
+            sample_rate: default_sample_rate(),
+            chunk_ms: default_chunk_ms(),
+            transcript_log: true,
+            turn_archive_size: 20,
+            turn_archive_format: default_turn_archive_format(),
             announce_startup: true,
             announce_provider_switch: true,
             dictionary: Vec::new(),
+            local_commands: true,
+            commands: Vec::new(),
+            spoken_confirmation: true,
+            stt_load: "background".into(),
+            tts_load: "background".into(),
+            speaker_id: false,
+            speaker_threshold: 0.5,
+            ignore_unknown_speakers: true,
+            session_timeout_secs: 300,
+            turn_policy: "queue".into(),
+            active_profile: None,
+            latency_budget_ms: 0,
+            watchdog_auto_restart: true,
         }
     }
 }
@@ -254,6 +487,9 @@ pub struct BehaviorConfig {
     /// Whether to show toast notifications (default: true).
     #[serde(default = "default_true")]
     pub show_toasts: bool,
+    /// Speak the replies to typed chat messages (default: true).
+    #[serde(default = "default_true")]
+    pub speak_chat_replies: bool,
 }
 
 impl Default for BehaviorConfig {
@@ -267,6 +503,7 @@ impl Default for BehaviorConfig {
             dictation_key: "MouseButton5".into(),
             stats_hotkey: "CommandOrControl+Shift+M".into(),
             show_toasts: true,
+            speak_chat_replies: true,
         }
     }
 }
@@ -315,6 +552,38 @@ pub struct AdvancedConfig {
     pub debug_mode: bool,
     #[serde(default)]
     pub show_dependencies: bool,
+    /// Throttling of high-frequency frontend events.
+    #[serde(default)]
+    pub event_throttle: EventThrottleConfig,
+}
+
+/// How long high-frequency events are held back before reaching the UI.
+/// A window of 0 sends that event type straight through.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventThrottleConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Voice `state_change` events within this window collapse to the latest.
+    #[serde(default = "default_state_coalesce_ms")]
+    pub state_coalesce_ms: u64,
+    /// AI stream tokens within this window are sent as one event.
+    #[serde(default = "default_token_batch_ms")]
+    pub token_batch_ms: u64,
+    /// Voice `audio_level` events within this window collapse to the latest.
+    #[serde(default)]
+    pub level_interval_ms: u64,
+}
+
+impl Default for EventThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            state_coalesce_ms: 16,
+            token_batch_ms: 30,
+            level_interval_ms: 0,
+        }
+    }
 }
 
 /// Sidebar UI state.
@@ -409,10 +678,24 @@ pub struct AiConfig {
     pub auto_detect: bool,
     #[serde(default)]
     pub system_prompt: Option<String>,
+    /// Response style for API providers: "auto" (concise when replies are
+    /// spoken, detailed on screen), "concise", or "detailed".
+    #[serde(default = "default_response_style")]
+    pub response_style: String,
+    /// Speak a short notice for each tool the model calls during a reply
+    /// (API providers).
+    #[serde(default)]
+    pub narrate_tool_steps: bool,
     #[serde(default = "default_tool_profile")]
     pub tool_profile: String,
     #[serde(default = "default_tool_profiles")]
     pub tool_profiles: HashMap<String, ToolProfile>,
+    /// Max characters of an MCP tool result before it is truncated (0 = no limit).
+    #[serde(default = "default_tool_result_max_chars")]
+    pub tool_result_max_chars: usize,
+    /// Per-tool overrides of `tool_result_max_chars`, keyed by tool name.
+    #[serde(default = "default_tool_result_limits")]
+    pub tool_result_limits: HashMap<String, usize>,
     #[serde(default = "default_endpoints")]
     pub endpoints: HashMap<String, String>,
     #[serde(default = "default_api_keys")]
@@ -429,8 +712,12 @@ impl Default for AiConfig {
             context_length: 32768,
             auto_detect: true,
             system_prompt: None,
+            response_style: default_response_style(),
+            narrate_tool_steps: false,
             tool_profile: "voice-assistant".into(),
             tool_profiles: default_tool_profiles(),
+            tool_result_max_chars: default_tool_result_max_chars(),
+            tool_result_limits: default_tool_result_limits(),
             endpoints: default_endpoints(),
             api_keys: default_api_keys(),
         }
@@ -526,7 +813,7 @@ pub struct McpServerPref {
     pub enabled: bool,
 }
 
-/// Browser settings (download behavior).
+/// Browser settings (download behavior, web search backend).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BrowserConfig {
@@ -534,6 +821,18 @@ pub struct BrowserConfig {
     pub download_ask_location: bool,
     #[serde(default)]
     pub download_path: Option<String>,
+    /// Web search backend: "auto", "duckduckgo", "google", "brave", "searxng".
+    #[serde(default = "default_search_backend")]
+    pub search_backend: String,
+    /// Try the other available backends when the chosen one fails.
+    #[serde(default = "default_true")]
+    pub search_fallback: bool,
+    /// Base URL of a SearxNG instance (JSON format must be enabled).
+    #[serde(default)]
+    pub searxng_url: Option<String>,
+    /// Brave Search API subscription token (encrypted on disk).
+    #[serde(default)]
+    pub brave_api_key: Option<String>,
 }
 
 impl Default for BrowserConfig {
@@ -541,10 +840,190 @@ impl Default for BrowserConfig {
         Self {
             download_ask_location: false,
             download_path: None,
+            search_backend: default_search_backend(),
+            search_fallback: true,
+            searxng_url: None,
+            brave_api_key: None,
+        }
+    }
+}
+
+/// Localhost control API for scripts, Stream Deck plugins, and home automation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_control_api_port")]
+    pub port: u16,
+    /// Bearer token required on every request (encrypted on disk).
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+impl Default for ControlApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_control_api_port(),
+            token: None,
+        }
+    }
+}
+
+/// OS-level global hotkeys that drive the voice engine directly, even when
+/// the window isn't focused (see `services::hotkeys`). Values are
+/// accelerators like "CommandOrControl+Shift+Space"; empty = unbound.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyConfig {
+    /// Record while held.
+    #[serde(default)]
+    pub push_to_talk: String,
+    /// Start a recording, or stop the current one.
+    #[serde(default)]
+    pub toggle: String,
+    /// Interrupt TTS playback.
+    #[serde(default)]
+    pub stop_speaking: String,
+    /// Switch to the next activation mode.
+    #[serde(default)]
+    pub cycle_mode: String,
+}
+
+/// Named LAN devices for the `network` MCP tools ("wake up my desktop").
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConfig {
+    #[serde(default)]
+    pub devices: Vec<NetworkDevice>,
+}
+
+/// Folders the `files` MCP tools may read and write.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesConfig {
+    /// Absolute paths (`~` for the home folder). Empty = `{data_dir}/notes`.
+    #[serde(default)]
+    pub roots: Vec<String>,
+}
+
+/// A device the user can refer to by name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkDevice {
+    pub name: String,
+    /// MAC address for Wake-on-LAN.
+    #[serde(default)]
+    pub mac: Option<String>,
+    /// Hostname or IP for ping / port checks.
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Subnet broadcast address for Wake-on-LAN (default 255.255.255.255).
+    #[serde(default)]
+    pub broadcast: Option<String>,
+}
+
+/// An HTTP endpoint that receives selected voice events as JSON POSTs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Voice event names to send (e.g. "transcription", "speaking_start").
+    /// Empty = every event except the high-rate audio level / word progress.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// HMAC-SHA256 signing secret (encrypted on disk). None = unsigned.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// Weekly quiet hours: no TTS, no wake-word listening, silent toasts
+/// (see `services::quiet_hours`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHoursConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_quiet_hours_schedule")]
+    pub schedule: Vec<QuietHoursWindow>,
+    /// The user's UTC offset, written by the settings UI; the schedule is
+    /// in local time.
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+}
+
+/// Rules applied to every transcription before it is sent on. All off by
+/// default; changes apply when the voice engine restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptRulesConfig {
+    /// Regex replacements, applied in order.
+    #[serde(default)]
+    pub replacements: Vec<TranscriptReplacement>,
+    /// Mask swear words ("s***").
+    #[serde(default)]
+    pub mask_profanity: bool,
+    /// Extra words to mask, on top of the built-in list.
+    #[serde(default)]
+    pub profanity_words: Vec<String>,
+    /// Capitalize the start of each sentence and a standalone "i".
+    #[serde(default)]
+    pub auto_capitalize: bool,
+    /// Spelled-out numbers to digits ("twenty three" -> "23").
+    #[serde(default)]
+    pub normalize_numbers: bool,
+}
+
+/// A regex replacement on transcriptions, e.g.
+/// `{ pattern: "(?i)\\bvoice mirror\\b", replacement: "Voice Mirror" }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptReplacement {
+    pub pattern: String,
+    #[serde(default)]
+    pub replacement: String,
+}
+
+/// A transcription route, e.g. `{ prefix: "note", targets: ["notes"] }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptRouteConfig {
+    /// Keyword the utterance must open with; empty matches any utterance.
+    #[serde(default)]
+    pub prefix: String,
+    /// Consumers: "provider", "dictation", "webhook", "notes".
+    pub targets: Vec<String>,
+    /// Drop the keyword before passing the text on.
+    #[serde(default = "default_true")]
+    pub strip_prefix: bool,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: default_quiet_hours_schedule(),
+            utc_offset_minutes: 0,
         }
     }
 }
 
+/// One recurring quiet period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHoursWindow {
+    /// Days the period starts on ("mon".."sun"). Empty = every day.
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// "HH:MM", 24-hour.
+    pub start: String,
+    /// "HH:MM"; at or before `start` = the next day.
+    pub end: String,
+}
+
 // ============ Default value functions ============
 
 fn default_true() -> bool { true }
@@ -554,8 +1033,39 @@ fn default_one() -> f64 { 1.0 }
 fn default_tts_adapter() -> String { "kokoro".into() }
 fn default_tts_voice() -> String { "af_bella".into() }
 fn default_tts_model_size() -> String { "0.6B".into() }
+fn default_tts_phrase_gap_ms() -> u32 { 80 }
+fn default_tts_sentence_pause_ms() -> u32 { 250 }
+fn default_tts_lookahead() -> u32 { 2 }
+fn default_tts_filter_action() -> String { "mask".into() }
 fn default_stt_adapter() -> String { "whisper-local".into() }
 fn default_stt_model_size() -> String { "base".into() }
+fn default_stt_target_latency_ms() -> u32 { 1500 }
+fn default_capture_source() -> String { "microphone".into() }
+fn default_stt_language() -> String { "en".into() }
+fn default_stt_task() -> String { "transcribe".into() }
+fn default_stt_context_words() -> u32 { 32 }
+fn default_turn_archive_size() -> u32 { 20 }
+fn default_turn_archive_format() -> String { "wav".into() }
+fn default_engine_load() -> String { "background".into() }
+fn default_speaker_threshold() -> f64 { 0.5 }
+fn default_session_timeout_secs() -> u64 { 300 }
+fn default_turn_policy() -> String { "queue".into() }
+fn default_quiet_hours_schedule() -> Vec<QuietHoursWindow> {
+    vec![QuietHoursWindow { days: Vec::new(), start: "22:00".into(), end: "07:00".into() }]
+}
+fn default_min_speech_coverage() -> f64 { 0.05 }
+fn default_vad_threshold() -> f64 { 0.01 }
+fn default_silence_timeout_secs() -> f64 { 2.0 }
+fn default_duck_level() -> f64 { 0.2 }
+fn default_state_coalesce_ms() -> u64 { 16 }
+fn default_token_batch_ms() -> u64 { 30 }
+fn default_max_recording_secs() -> f64 { 120.0 }
+fn default_toggle_timeout_secs() -> f64 { 60.0 }
+fn default_listen_duty_cycle() -> String { "battery".into() }
+fn default_resampler() -> String { "linear".into() }
+fn default_audio_session() -> String { "default".into() }
+fn default_sample_rate() -> u32 { 16_000 }
+fn default_chunk_ms() -> u32 { 80 }
 fn default_orb_size() -> u32 { 80 }
 fn default_theme() -> String { "colorblind".into() }
 fn default_panel_width() -> u32 { 500 }
@@ -568,6 +1078,7 @@ fn default_dictation_key() -> String { "MouseButton5".into() }
 fn default_stats_hotkey() -> String { "CommandOrControl+Shift+M".into() }
 fn default_provider() -> String { "claude".into() }
 fn default_context_length() -> u32 { 32768 }
+fn default_response_style() -> String { "auto".into() }
 fn default_tool_profile() -> String { "voice-assistant".into() }
 
 fn default_tool_profiles() -> HashMap<String, ToolProfile> {
@@ -581,6 +1092,17 @@ fn default_tool_profiles() -> HashMap<String, ToolProfile> {
     m
 }
 
+fn default_tool_result_max_chars() -> usize { 24_000 }
+
+fn default_tool_result_limits() -> HashMap<String, usize> {
+    let mut m = HashMap::new();
+    m.insert("browser_action".into(), 16_000);
+    m.insert("sandbox_snapshot".into(), 16_000);
+    m.insert("n8n_get_execution".into(), 8_000);
+    m.insert("n8n_get_executions".into(), 8_000);
+    m
+}
+
 fn default_endpoints() -> HashMap<String, String> {
     let mut m = HashMap::new();
     m.insert("ollama".into(), "http://127.0.0.1:11434".into());
@@ -589,6 +1111,8 @@ fn default_endpoints() -> HashMap<String, String> {
     m
 }
 
+fn default_search_backend() -> String { "auto".into() }
+fn default_control_api_port() -> u16 { 47823 }
 fn default_api_keys() -> HashMap<String, Option<String>> {
     let mut m = HashMap::new();
     for key in &["openai", "anthropic", "gemini", "grok", "groq", "mistral", "openrouter", "deepseek", "kimi"] {
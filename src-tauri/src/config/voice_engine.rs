@@ -0,0 +1,459 @@
+//! Versioned on-disk copy of the voice engine config, and its validation.
+//!
+//! `VoiceEngineConfig` is rebuilt from `config.json` every time the
+//! pipeline starts. The config each start used is also written to
+//! `voice-engine.json` next to it, as `{ "version": N, "config": {...} }`,
+//! so the next start can log which settings changed and a bug report shows
+//! what the engine last ran with.
+//!
+//! The stored field names and values are `VoiceEngineConfig`'s. When one is
+//! renamed or a value retired, bump `VERSION` and add a step to
+//! `MIGRATIONS`; loading an older file runs the steps it hasn't had, in
+//! order, before deserializing. Fields the file lacks take their defaults.
+//!
+//! Named voice profiles (`config::voice_profiles`) are stored in the same
+//! layout and go through the same migrations.
+//!
+//! `validate` checks a config before the engine starts with it. Each issue
+//! names the `config.json` setting to fix; `start_voice` and
+//! `restart_voice` refuse to start while there are any, and
+//! `validate_voice_config` lets the settings page check a save.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::persistence::deep_merge;
+use crate::error::AppError;
+use crate::voice::pipeline::format::{CHUNK_MS_RANGE, SUPPORTED_RATES};
+use crate::voice::routing::RouteTarget;
+use crate::voice::VoiceEngineConfig;
+
+/// Current layout of `voice-engine.json`.
+pub const VERSION: u32 = 1;
+const FILE: &str = "voice-engine.json";
+
+/// One upgrade of a stored config, from version `from` to `from + 1`.
+struct Migration {
+    from: u32,
+    what: &'static str,
+    apply: fn(&mut Map<String, Value>),
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    what: "rename retired activation modes",
+    apply: rename_modes,
+}];
+
+/// 0 → 1: the mode was stored as typed in `activationMode`, including the
+/// retired "continuous" and "hybrid" modes (now wake word) and snake_case
+/// spellings.
+fn rename_modes(config: &mut Map<String, Value>) {
+    let renamed = match config.get("mode").and_then(Value::as_str) {
+        Some("continuous" | "hybrid" | "wake_word") => "wakeWord",
+        Some("push_to_talk" | "ptt") => "pushToTalk",
+        Some("toggleToTalk" | "toggle_to_talk") => "toggle",
+        _ => return,
+    };
+    config.insert("mode".into(), Value::from(renamed));
+}
+
+#[derive(Serialize)]
+struct Stored<'a> {
+    version: u32,
+    config: &'a VoiceEngineConfig,
+}
+
+/// Bring a stored file up to `VERSION`, returning the config object. A
+/// file without a version is version 0. `name` labels the file in errors.
+fn migrate(stored: Value, name: &str) -> Result<Map<String, Value>, AppError> {
+    let Value::Object(mut file) = stored else {
+        return Err(AppError::Config(format!("{} is not an object", name)));
+    };
+    let version = file.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if version > VERSION {
+        return Err(AppError::Config(format!(
+            "{} is version {}, newer than this build supports ({})",
+            name, version, VERSION
+        )));
+    }
+    let Some(Value::Object(mut config)) = file.remove("config") else {
+        return Err(AppError::Config(format!("{} has no config", name)));
+    };
+    for step in MIGRATIONS.iter().filter(|m| m.from >= version) {
+        tracing::info!(from = step.from, "Migrating {}: {}", name, step.what);
+        (step.apply)(&mut config);
+    }
+    Ok(config)
+}
+
+/// Parse the contents of a stored config file.
+fn parse(text: &str, name: &str) -> Result<VoiceEngineConfig, AppError> {
+    let stored: Value = serde_json::from_str(text)
+        .map_err(|e| AppError::Config(format!("Failed to parse {}: {}", name, e)))?;
+    let config = migrate(stored, name)?;
+    let defaults = serde_json::to_value(VoiceEngineConfig::default())
+        .map_err(|e| AppError::Internal(format!("Serialize error: {}", e)))?;
+    serde_json::from_value(deep_merge(defaults, Value::Object(config)))
+        .map_err(|e| AppError::Config(format!("Invalid {}: {}", name, e)))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Read a stored config file, migrated to `VERSION`. `None` when the file
+/// doesn't exist.
+pub(crate) fn read_file(path: &Path) -> Result<Option<VoiceEngineConfig>, AppError> {
+    let name = file_name(path);
+    match fs::read_to_string(path) {
+        Ok(text) => parse(&text, &name).map(Some),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AppError::Internal(format!(
+            "Failed to read {}: {}",
+            name, e
+        ))),
+    }
+}
+
+/// Store `config` at `path` at the current version. Written to a temp file
+/// and renamed, so a crash never leaves half a file.
+pub(crate) fn write_file(path: &Path, config: &VoiceEngineConfig) -> Result<(), AppError> {
+    let name = file_name(path);
+    let io = |e: std::io::Error| AppError::Internal(format!("Failed to write {}: {}", name, e));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io)?;
+    }
+    let json = serde_json::to_string_pretty(&Stored {
+        version: VERSION,
+        config,
+    })
+    .map_err(|e| AppError::Internal(format!("Serialize error: {}", e)))?;
+    let mut tmp_path = PathBuf::from(path);
+    tmp_path.as_mut_os_string().push(".tmp");
+    fs::write(&tmp_path, json).map_err(io)?;
+    fs::rename(&tmp_path, path).map_err(io)
+}
+
+/// The config the last start used, migrated to `VERSION`. `None` when
+/// nothing has been stored yet.
+pub fn load(config_dir: &Path) -> Result<Option<VoiceEngineConfig>, AppError> {
+    read_file(&config_dir.join(FILE))
+}
+
+/// Store `config` as the one the last start used.
+pub fn save(config_dir: &Path, config: &VoiceEngineConfig) -> Result<(), AppError> {
+    write_file(&config_dir.join(FILE), config)
+}
+
+/// Names of the fields that differ between two configs.
+pub fn changed_fields(old: &VoiceEngineConfig, new: &VoiceEngineConfig) -> Vec<String> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) =
+        (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    new.into_iter()
+        .filter(|(field, value)| old.get(field) != Some(value))
+        .map(|(field, _)| field)
+        .collect()
+}
+
+/// Store the config a start is using and log what changed since the last
+/// one. Best-effort: an unreadable or unwritable file only costs the log.
+pub fn record(config_dir: &Path, config: &VoiceEngineConfig) {
+    match load(config_dir) {
+        Ok(Some(previous)) => {
+            let changed = changed_fields(&previous, config);
+            if !changed.is_empty() {
+                tracing::info!(changed = %changed.join(", "), "Voice config changed since the last start");
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Ignoring stored voice engine config: {}", e),
+    }
+    if let Err(e) = save(config_dir, config) {
+        tracing::warn!("{}", e);
+    }
+}
+
+// ── Validation ──────────────────────────────────────────────────────
+
+/// A setting the voice engine can't start with.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigIssue {
+    /// The `config.json` setting, e.g. `voice.vadThreshold`.
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
+fn in_range<T: PartialOrd + Copy + fmt::Display>(
+    issues: &mut Vec<ConfigIssue>,
+    field: &'static str,
+    value: T,
+    min: T,
+    max: T,
+) {
+    // NaN is never in range
+    if !(min..=max).contains(&value) {
+        issues.push(ConfigIssue {
+            field,
+            message: format!("must be between {} and {}, got {}", min, max, value),
+        });
+    }
+}
+
+fn one_of(issues: &mut Vec<ConfigIssue>, field: &'static str, value: &str, allowed: &[&str]) {
+    if !allowed.contains(&value) {
+        issues.push(ConfigIssue {
+            field,
+            message: format!("must be one of {}, got \"{}\"", allowed.join(", "), value),
+        });
+    }
+}
+
+/// Everything wrong with `config`; empty when the engine can start with it.
+pub fn validate(config: &VoiceEngineConfig) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    in_range(
+        &mut issues,
+        "voice.vadThreshold",
+        config.vad_threshold,
+        0.0,
+        1.0,
+    );
+    in_range(
+        &mut issues,
+        "voice.minSpeechCoverage",
+        config.min_speech_coverage,
+        0.0,
+        1.0,
+    );
+    if config.silence_timeout_secs.is_nan() || config.silence_timeout_secs <= 0.0 {
+        issues.push(ConfigIssue {
+            field: "voice.silenceTimeoutSecs",
+            message: format!(
+                "must be greater than 0, got {}",
+                config.silence_timeout_secs
+            ),
+        });
+    }
+    for (field, secs) in [
+        ("voice.maxRecordingSecs", config.max_recording_secs),
+        ("voice.toggleTimeoutSecs", config.toggle_timeout_secs),
+    ] {
+        if secs.is_nan() || secs < 0.0 {
+            issues.push(ConfigIssue {
+                field,
+                message: format!("must be 0 (no limit) or more, got {}", secs),
+            });
+        }
+    }
+    in_range(&mut issues, "voice.ttsSpeed", config.tts_speed, 0.5, 2.0);
+    in_range(&mut issues, "voice.ttsVolume", config.tts_volume, 0.0, 2.0);
+    in_range(&mut issues, "voice.duckLevel", config.duck_level, 0.0, 1.0);
+    in_range(
+        &mut issues,
+        "voice.speakerThreshold",
+        config.speaker_threshold,
+        0.0,
+        1.0,
+    );
+    if !SUPPORTED_RATES.contains(&config.sample_rate) {
+        let rates: Vec<String> = SUPPORTED_RATES.iter().map(u32::to_string).collect();
+        issues.push(ConfigIssue {
+            field: "voice.sampleRate",
+            message: format!(
+                "must be one of {}, got {}",
+                rates.join(", "),
+                config.sample_rate
+            ),
+        });
+    }
+    in_range(
+        &mut issues,
+        "voice.chunkMs",
+        config.chunk_ms,
+        *CHUNK_MS_RANGE.start(),
+        *CHUNK_MS_RANGE.end(),
+    );
+    one_of(
+        &mut issues,
+        "voice.captureSource",
+        &config.capture_source,
+        &["microphone", "system", "both"],
+    );
+    one_of(
+        &mut issues,
+        "voice.listenDutyCycle",
+        &config.listen_duty_cycle,
+        &["off", "battery", "always"],
+    );
+    one_of(
+        &mut issues,
+        "voice.resampler",
+        &config.resampler,
+        &["linear", "sinc"],
+    );
+    one_of(
+        &mut issues,
+        "voice.turnArchiveFormat",
+        &config.turn_archive_format,
+        &["wav", "flac", "opus"],
+    );
+    one_of(
+        &mut issues,
+        "voice.audioSession",
+        &config.audio_session,
+        &crate::services::audio_session::CATEGORIES,
+    );
+    one_of(
+        &mut issues,
+        "voice.ttsFilterAction",
+        &config.tts_filter_action,
+        &["mask", "stop"],
+    );
+    for route in &config.transcript_routes {
+        let unknown = route
+            .targets
+            .iter()
+            .find(|t| RouteTarget::parse(t).is_none());
+        let message = match unknown {
+            Some(target) => format!(
+                "has unknown target '{}' (expected {})",
+                target,
+                RouteTarget::NAMES.join(", ")
+            ),
+            None if route.targets.is_empty() => {
+                format!("route '{}' has no targets", route.prefix)
+            }
+            None => continue,
+        };
+        issues.push(ConfigIssue {
+            field: "transcriptRoutes",
+            message,
+        });
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voice::VoiceMode;
+    use serde_json::json;
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let tmp = std::env::temp_dir().join("voice-mirror-test-voice-engine");
+        let _ = fs::remove_dir_all(&tmp);
+        assert!(load(&tmp).unwrap().is_none());
+
+        let config = VoiceEngineConfig {
+            mode: VoiceMode::Toggle,
+            stt_model_size: "small".into(),
+            sample_rate: 8_000,
+            ..Default::default()
+        };
+        save(&tmp, &config).unwrap();
+        let text = fs::read_to_string(tmp.join(FILE)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&text).unwrap()["version"],
+            VERSION
+        );
+
+        let loaded = load(&tmp).unwrap().unwrap();
+        assert!(changed_fields(&config, &loaded).is_empty());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_migrates_unversioned_file() {
+        let text = json!({ "config": { "mode": "continuous", "stt_model_size": "tiny" } });
+        let config = parse(&text.to_string(), FILE).unwrap();
+        assert_eq!(config.mode, VoiceMode::WakeWord);
+        assert_eq!(config.stt_model_size, "tiny");
+        // Missing fields take their defaults
+        assert_eq!(config.sample_rate, VoiceEngineConfig::default().sample_rate);
+
+        let text = json!({ "config": { "mode": "push_to_talk" } });
+        assert_eq!(
+            parse(&text.to_string(), FILE).unwrap().mode,
+            VoiceMode::PushToTalk
+        );
+    }
+
+    #[test]
+    fn test_rejects_newer_or_broken_files() {
+        let newer = json!({ "version": VERSION + 1, "config": {} });
+        assert!(parse(&newer.to_string(), FILE).is_err());
+        assert!(parse("[]", FILE).is_err());
+        assert!(parse("{ not json", FILE).is_err());
+        let bad_mode = json!({ "version": VERSION, "config": { "mode": "telepathy" } });
+        assert!(parse(&bad_mode.to_string(), FILE).is_err());
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let old = VoiceEngineConfig::default();
+        let new = VoiceEngineConfig {
+            vad_threshold: 0.02,
+            chunk_ms: 32,
+            ..Default::default()
+        };
+        let mut changed = changed_fields(&old, &new);
+        changed.sort();
+        assert_eq!(changed, ["chunk_ms", "vad_threshold"]);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&VoiceEngineConfig::default()).is_empty());
+
+        let config = VoiceEngineConfig {
+            vad_threshold: 1.5,
+            silence_timeout_secs: 0.0,
+            tts_speed: f32::NAN,
+            sample_rate: 44_100,
+            chunk_ms: 5,
+            capture_source: "network".into(),
+            transcript_routes: vec![crate::config::schema::TranscriptRouteConfig {
+                prefix: "note".into(),
+                targets: vec!["printer".into()],
+                strip_prefix: true,
+            }],
+            ..Default::default()
+        };
+        let issues = validate(&config);
+        let fields: Vec<_> = issues.iter().map(|i| i.field).collect();
+        assert_eq!(
+            fields,
+            [
+                "voice.vadThreshold",
+                "voice.silenceTimeoutSecs",
+                "voice.ttsSpeed",
+                "voice.sampleRate",
+                "voice.chunkMs",
+                "voice.captureSource",
+                "transcriptRoutes",
+            ]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "voice.vadThreshold must be between 0 and 1, got 1.5"
+        );
+    }
+}
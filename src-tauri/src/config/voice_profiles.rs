@@ -0,0 +1,186 @@
+//! Named voice engine profiles ("headset", "speakerphone", ...).
+//!
+//! A profile is a whole `VoiceEngineConfig` saved under a name, so a user
+//! can flip between setups (push-to-talk on a headset, wake word through
+//! the speakers) without re-entering a dozen settings. Each one is
+//! `{config_dir}/voice-profiles/<name>.json`, in the versioned layout of
+//! `voice-engine.json` (see `config::voice_engine`), so profiles saved by an
+//! older build are migrated when loaded.
+//!
+//! `voice.activeProfile` in `config.json` names the profile the engine
+//! starts with; unset, it starts with the Voice settings as before.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::voice_engine;
+use crate::error::AppError;
+use crate::voice::VoiceEngineConfig;
+
+const DIR_NAME: &str = "voice-profiles";
+
+/// Longest profile name accepted.
+pub const MAX_NAME_LEN: usize = 40;
+
+/// What the profile list shows for each profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSummary {
+    pub name: String,
+    pub mode: String,
+    pub input_device: Option<String>,
+    pub output_device: Option<String>,
+    pub tts_voice: String,
+}
+
+impl ProfileSummary {
+    fn new(name: String, config: &VoiceEngineConfig) -> Self {
+        Self {
+            name,
+            mode: config.mode.to_string(),
+            input_device: config.input_device.clone(),
+            output_device: config.output_device.clone(),
+            tts_voice: config.tts_voice.clone(),
+        }
+    }
+}
+
+/// `{config_dir}/voice-profiles`.
+pub fn profile_dir(config_dir: &Path) -> PathBuf {
+    config_dir.join(DIR_NAME)
+}
+
+/// Check a profile name: 1-40 letters, digits, `-`, `_` or spaces, so it
+/// is also a safe file name.
+pub fn validate_name(name: &str) -> Result<(), AppError> {
+    let invalid = |why: &str| AppError::Config(format!("Invalid profile name '{}': {}", name, why));
+    if name.trim().is_empty() {
+        return Err(invalid("it is empty"));
+    }
+    if name.trim() != name {
+        return Err(invalid("it starts or ends with a space"));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(invalid("it is too long"));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' '))
+    {
+        return Err(invalid("use letters, digits, '-', '_' or spaces"));
+    }
+    Ok(())
+}
+
+fn profile_path(config_dir: &Path, name: &str) -> Result<PathBuf, AppError> {
+    validate_name(name)?;
+    Ok(profile_dir(config_dir).join(format!("{}.json", name)))
+}
+
+/// Every stored profile, sorted by name. Files that fail to load are
+/// skipped with a warning.
+pub fn list(config_dir: &Path) -> Result<Vec<ProfileSummary>, AppError> {
+    let entries = match fs::read_dir(profile_dir(config_dir)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(AppError::Internal(format!(
+                "Failed to read {}: {}",
+                DIR_NAME, e
+            )))
+        }
+    };
+    let mut profiles: Vec<ProfileSummary> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?.to_string();
+            match voice_engine::read_file(&path) {
+                Ok(Some(config)) => Some(ProfileSummary::new(name, &config)),
+                Ok(None) => None,
+                Err(e) => {
+                    tracing::warn!(profile = %name, "Skipping voice profile: {}", e);
+                    None
+                }
+            }
+        })
+        .collect();
+    profiles.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    Ok(profiles)
+}
+
+/// Load the profile called `name`.
+pub fn load(config_dir: &Path, name: &str) -> Result<VoiceEngineConfig, AppError> {
+    voice_engine::read_file(&profile_path(config_dir, name)?)?
+        .ok_or_else(|| AppError::Config(format!("No voice profile named '{}'", name)))
+}
+
+/// Save `config` as the profile called `name`, replacing any existing one.
+pub fn save(config_dir: &Path, name: &str, config: &VoiceEngineConfig) -> Result<(), AppError> {
+    voice_engine::write_file(&profile_path(config_dir, name)?, config)
+}
+
+/// Delete the profile called `name`. Returns whether it existed.
+pub fn delete(config_dir: &Path, name: &str) -> Result<bool, AppError> {
+    match fs::remove_file(profile_path(config_dir, name)?) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(AppError::Internal(format!(
+            "Failed to delete voice profile '{}': {}",
+            name, e
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voice::VoiceMode;
+
+    #[test]
+    fn test_save_list_load_delete() {
+        let tmp = std::env::temp_dir().join(format!("vm-voice-profiles-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&tmp);
+        assert!(list(&tmp).unwrap().is_empty());
+
+        let speakerphone = VoiceEngineConfig {
+            mode: VoiceMode::WakeWord,
+            output_device: Some("Speakers".into()),
+            ..Default::default()
+        };
+        save(&tmp, "speakerphone", &speakerphone).unwrap();
+        save(&tmp, "Headset PTT", &VoiceEngineConfig::default()).unwrap();
+        fs::write(profile_dir(&tmp).join("broken.json"), "{").unwrap();
+
+        let names: Vec<String> = list(&tmp).unwrap().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["Headset PTT", "speakerphone"]);
+
+        let loaded = load(&tmp, "speakerphone").unwrap();
+        assert!(voice_engine::changed_fields(&speakerphone, &loaded).is_empty());
+
+        assert!(delete(&tmp, "speakerphone").unwrap());
+        assert!(!delete(&tmp, "speakerphone").unwrap());
+        assert!(matches!(
+            load(&tmp, "speakerphone"),
+            Err(AppError::Config(_))
+        ));
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("gaming").is_ok());
+        assert!(validate_name("work_2 headset").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name(" padded").is_err());
+        assert!(validate_name("../config").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name(&"x".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Typed errors with stable codes for the frontend.
+//!
+//! Most of the crate still reports errors as strings. Where the UI needs to
+//! tell failures apart ("mic missing" vs "model missing" vs "network down")
+//! they are raised as an [`AppError`], whose [`code`](AppError::code) rides
+//! along in `IpcResponse.code` and in the `error` voice event so the frontend
+//! can offer a fix instead of just showing the text.
+//!
+//! Codes are part of the IPC contract: add new ones freely, but don't rename
+//! or reuse existing ones.
+
+use std::fmt;
+
+use crate::voice::stt::SttError;
+use crate::voice::tts::TtsError;
+
+/// An error the frontend can act on. The payload is the human-readable
+/// message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// No usable microphone: none connected, the configured one is gone, or
+    /// the OS denied access.
+    MicUnavailable(String),
+    /// An audio device exists but couldn't be opened or started.
+    AudioDevice(String),
+    /// A model file (STT or TTS) hasn't been downloaded.
+    ModelMissing(String),
+    /// Speech recognition failed.
+    Stt(String),
+    /// Speech synthesis or playback failed.
+    Tts(String),
+    /// A download or cloud service couldn't be reached.
+    Network(String),
+    /// The voice engine has to be started first.
+    NotRunning,
+    /// The voice engine is already running.
+    AlreadyRunning,
+    /// Invalid settings.
+    Config(String),
+    /// Anything else.
+    Internal(String),
+}
+
+impl AppError {
+    /// The stable code sent to the frontend.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MicUnavailable(_) => "mic_unavailable",
+            Self::AudioDevice(_) => "audio_device",
+            Self::ModelMissing(_) => "model_missing",
+            Self::Stt(_) => "stt_failed",
+            Self::Tts(_) => "tts_failed",
+            Self::Network(_) => "network",
+            Self::NotRunning => "not_running",
+            Self::AlreadyRunning => "already_running",
+            Self::Config(_) => "config",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    /// Prefix the message with what was being done, keeping the code.
+    pub fn context(self, what: impl fmt::Display) -> Self {
+        let wrap = |msg: String| format!("{}: {}", what, msg);
+        match self {
+            Self::MicUnavailable(m) => Self::MicUnavailable(wrap(m)),
+            Self::AudioDevice(m) => Self::AudioDevice(wrap(m)),
+            Self::ModelMissing(m) => Self::ModelMissing(wrap(m)),
+            Self::Stt(m) => Self::Stt(wrap(m)),
+            Self::Tts(m) => Self::Tts(wrap(m)),
+            Self::Network(m) => Self::Network(wrap(m)),
+            Self::Config(m) => Self::Config(wrap(m)),
+            Self::Internal(m) => Self::Internal(wrap(m)),
+            unit @ (Self::NotRunning | Self::AlreadyRunning) => unit,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MicUnavailable(m)
+            | Self::AudioDevice(m)
+            | Self::ModelMissing(m)
+            | Self::Stt(m)
+            | Self::Tts(m)
+            | Self::Network(m)
+            | Self::Config(m)
+            | Self::Internal(m) => f.write_str(m),
+            Self::NotRunning => f.write_str("Voice engine is not running"),
+            Self::AlreadyRunning => f.write_str("Voice engine is already running"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// Lets `?` pass an `AppError` up through code that still returns
+/// `Result<_, String>`.
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
+
+impl From<SttError> for AppError {
+    fn from(err: SttError) -> Self {
+        match err {
+            SttError::ModelNotFound(_) => Self::ModelMissing(err.to_string()),
+            SttError::DownloadError(_) => Self::Network(err.to_string()),
+            _ => Self::Stt(err.to_string()),
+        }
+    }
+}
+
+impl From<TtsError> for AppError {
+    fn from(err: TtsError) -> Self {
+        match err {
+            TtsError::ModelNotFound(_) => Self::ModelMissing(err.to_string()),
+            TtsError::NetworkError(_) => Self::Network(err.to_string()),
+            _ => Self::Tts(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_codes_from_engine_errors() {
+        let missing = AppError::from(SttError::ModelNotFound(PathBuf::from("ggml-base.bin")));
+        assert_eq!(missing.code(), "model_missing");
+        assert_eq!(
+            AppError::from(SttError::DownloadError("timed out".into())).code(),
+            "network"
+        );
+        assert_eq!(AppError::from(SttError::NotReady).code(), "stt_failed");
+        assert_eq!(
+            AppError::from(TtsError::NetworkError("dns".into())).code(),
+            "network"
+        );
+        assert_eq!(AppError::from(TtsError::Cancelled).code(), "tts_failed");
+    }
+
+    #[test]
+    fn test_context_keeps_code() {
+        let err = AppError::MicUnavailable("No default input device available".into())
+            .context("Failed to start voice engine");
+        assert_eq!(err.code(), "mic_unavailable");
+        assert_eq!(
+            err.to_string(),
+            "Failed to start voice engine: No default input device available"
+        );
+        assert_eq!(
+            AppError::NotRunning.context("ignored"),
+            AppError::NotRunning
+        );
+        let as_string: String = AppError::NotRunning.into();
+        assert_eq!(as_string, "Voice engine is not running");
+    }
+}
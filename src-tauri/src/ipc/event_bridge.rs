@@ -0,0 +1,131 @@
+//! Voice events pushed to the MCP binary.
+//!
+//! The MCP binary sends `SubscribeEvents { events }` (from the
+//! `voice_subscribe` tool) and from then on gets every matching
+//! `voice-event` as an `AppToMcp::VoiceEvent` frame, instead of an agent
+//! having to poll `voice_listen`. An empty list means every event except the
+//! high-rate ones (`audio_level`, `speaking_progress`), which are only sent
+//! when named. The subscription ends with `UnsubscribeEvents` or when the
+//! connection drops.
+
+use std::sync::Mutex;
+
+use serde_json::Value;
+use tauri::{AppHandle, Listener, Manager};
+use tracing::{debug, info};
+
+use super::pipe_server::PipeServerState;
+use super::protocol::AppToMcp;
+
+/// Events that fire many times a second; only sent when asked for by name.
+const HIGH_RATE_EVENTS: &[&str] = &["audio_level", "speaking_progress"];
+
+/// What the connected MCP binary subscribed to.
+struct Subscription {
+    /// Event names; empty for all but `HIGH_RATE_EVENTS`.
+    events: Vec<String>,
+}
+
+impl Subscription {
+    fn wants(&self, event: &str) -> bool {
+        if self.events.is_empty() {
+            !HIGH_RATE_EVENTS.contains(&event)
+        } else {
+            self.events.iter().any(|e| e == event)
+        }
+    }
+}
+
+static SUBSCRIPTION: Mutex<Option<Subscription>> = Mutex::new(None);
+
+/// Forward subscribed voice events to the pipe for the app's lifetime.
+pub fn start(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any("voice-event", move |event| {
+        forward(&handle, event.payload());
+    });
+}
+
+/// Replace the subscription (`SubscribeEvents`).
+pub fn subscribe(events: Vec<String>) {
+    info!("[EventBridge] MCP subscribed to voice events: {:?}", events);
+    if let Ok(mut guard) = SUBSCRIPTION.lock() {
+        *guard = Some(Subscription { events });
+    }
+}
+
+/// End the subscription (`UnsubscribeEvents`, or the client disconnected).
+pub fn unsubscribe() {
+    if let Ok(mut guard) = SUBSCRIPTION.lock() {
+        if guard.take().is_some() {
+            info!("[EventBridge] MCP unsubscribed from voice events");
+        }
+    }
+}
+
+fn forward(app: &AppHandle, payload: &str) {
+    let Some(frame) = SUBSCRIPTION
+        .lock()
+        .ok()
+        .and_then(|guard| frame_for(guard.as_ref()?, payload))
+    else {
+        return;
+    };
+    if let Some(pipe) = app.try_state::<PipeServerState>() {
+        if let Err(e) = pipe.send(frame) {
+            debug!("[EventBridge] Dropped voice event: {}", e);
+        }
+    }
+}
+
+/// The frame for a `voice-event` payload (`{ "event": ..., "data": ... }`),
+/// if `subscription` wants it.
+fn frame_for(subscription: &Subscription, payload: &str) -> Option<AppToMcp> {
+    let mut payload: Value = serde_json::from_str(payload).ok()?;
+    let event = payload.get("event")?.as_str()?.to_string();
+    if !subscription.wants(&event) {
+        return None;
+    }
+    let data = payload.get_mut("data").map(Value::take).unwrap_or(Value::Null);
+    Some(AppToMcp::VoiceEvent { event, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(events: &[&str]) -> Subscription {
+        Subscription {
+            events: events.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_empty_subscription_skips_high_rate_events() {
+        let all = subscription(&[]);
+        assert!(all.wants("transcription"));
+        assert!(all.wants("state_change"));
+        assert!(!all.wants("audio_level"));
+    }
+
+    #[test]
+    fn test_named_events_only() {
+        let some = subscription(&["transcription", "audio_level"]);
+        assert!(some.wants("audio_level"));
+        assert!(!some.wants("state_change"));
+    }
+
+    #[test]
+    fn test_frame_for_payload() {
+        let payload = r#"{"event":"transcription","data":{"text":"hello"}}"#;
+        match frame_for(&subscription(&[]), payload) {
+            Some(AppToMcp::VoiceEvent { event, data }) => {
+                assert_eq!(event, "transcription");
+                assert_eq!(data["text"], "hello");
+            }
+            other => panic!("expected a voice event, got {:?}", other),
+        }
+        assert!(frame_for(&subscription(&["error"]), payload).is_none());
+        assert!(frame_for(&subscription(&[]), "not json").is_none());
+    }
+}
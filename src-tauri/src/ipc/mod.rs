@@ -1,8 +1,9 @@
 //! Named pipe IPC for fast communication between the MCP binary and the Tauri app.
 //!
-//! Replaces file-based IPC (inbox.json polling) with direct named pipe/UDS messaging
+//! Replaces file-based IPC (inbox polling) with direct named pipe/UDS messaging
 //! for sub-millisecond latency on the voice_send/voice_listen hot path.
 
+pub mod event_bridge;
 pub mod pipe_client;
 pub mod pipe_server;
 pub mod protocol;
@@ -9,6 +9,7 @@
 //! so browser/capture tools keep working across MCP binary restarts.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::{mpsc, Mutex};
@@ -161,6 +162,11 @@ async fn run_pipe_server_loop(
         if let Ok(mut guard) = tx_slot.lock() {
             *guard = None;
         }
+        super::event_bridge::unsubscribe();
+        // Nobody is left to receive the answer
+        if crate::voice::ask::cancel(None) {
+            info!("[PipeServer] Closed the open spoken question (client disconnected)");
+        }
 
         // Clear any listener lock the MCP binary held.
         {
@@ -225,10 +231,14 @@ fn dispatch_message(msg: McpToApp, app_handle: &AppHandle) {
             reply_to,
             message_id,
             timestamp,
+            priority,
+            request_id,
+            speak,
         } => {
             // voice_send is always called by an AI provider, never a user.
             // Use "ai_message" regardless of instance_id so all providers
             // (Claude Code, OpenCode, etc.) trigger TTS + chat card.
+            let spoken = crate::services::inbox_watcher::sender_voice(&from);
             let event = InboxEvent {
                 kind: "ai_message".to_string(),
                 text: message,
@@ -237,24 +247,47 @@ fn dispatch_message(msg: McpToApp, app_handle: &AppHandle) {
                 timestamp,
                 thread_id,
                 reply_to,
+                // Replies in a thread the MCP side hasn't focused stay silent
+                speak: crate::commands::ai::speak_replies() && speak != Some(false),
+                priority,
+                voice: spoken.voice,
+                announce: spoken.announce,
             };
 
+            crate::commands::voice::note_provider_reply(app_handle, event.speak);
             if let Err(e) = app_handle.emit("mcp-inbox-message", &event) {
                 warn!("[PipeServer] Failed to emit mcp-inbox-message: {}", e);
             }
+
+            if let Some(request_id) = request_id {
+                use tauri::Manager;
+                if let Some(pipe_state) = app_handle.try_state::<PipeServerState>() {
+                    if let Err(e) = pipe_state.send(AppToMcp::VoiceSendAck { request_id }) {
+                        warn!("[PipeServer] Failed to send voice_send ack: {}", e);
+                    }
+                }
+            }
         }
         McpToApp::ListenStart {
             instance_id,
             from_sender,
             thread_id,
+            request_id,
         } => {
             info!(
-                "[PipeServer] AI {} listening for messages from {} (thread: {:?})",
-                instance_id, from_sender, thread_id
+                "[PipeServer] AI {} listening for messages from {} (thread: {:?}, request: {:?})",
+                instance_id, from_sender, thread_id, request_id
             );
             // The pipe server doesn't need to act on this — it just means the MCP
             // binary is now waiting for AppToMcp::UserMessage on the pipe.
+            // Several listens can be open at once; the MCP side matches each
+            // UserMessage to one of them.
         }
+        McpToApp::ListenCancel { request_id } => {
+            info!("[PipeServer] Listen {} ended without a message", request_id);
+        }
+        McpToApp::SubscribeEvents { events } => super::event_bridge::subscribe(events),
+        McpToApp::UnsubscribeEvents => super::event_bridge::unsubscribe(),
         McpToApp::Ready => {
             info!("[PipeServer] MCP binary ready (pipe handshake complete)");
             // New Claude session — clear stale inbox messages and notify frontend
@@ -454,6 +487,76 @@ fn dispatch_message(msg: McpToApp, app_handle: &AppHandle) {
                 }
             });
         }
+        McpToApp::ConfirmRequest { request_id, tool, prompt } => {
+            info!("[PipeServer] Confirm request: id={}, tool={}", request_id, tool);
+            let app = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                use tauri::Manager;
+                let answer = crate::voice::confirm::ask(&app, &tool, &prompt).await;
+                let response = AppToMcp::ConfirmResponse {
+                    request_id,
+                    answer: answer.name().into(),
+                };
+                if let Some(pipe_state) = app.try_state::<PipeServerState>() {
+                    if let Err(e) = pipe_state.send(response) {
+                        warn!("[PipeServer] Failed to send confirm response: {}", e);
+                    }
+                }
+            });
+        }
+        McpToApp::AskRequest {
+            request_id,
+            question,
+            timeout_secs,
+        } => {
+            info!("[PipeServer] Ask request: id={}, timeout={}s", request_id, timeout_secs);
+            let app = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                use crate::voice::ask::{self, Outcome};
+                use tauri::Manager;
+                let timeout = Duration::from_secs(timeout_secs);
+                let outcome = ask::ask(&app, &request_id, &question, timeout).await;
+                let text = match &outcome {
+                    Outcome::Answered(text) => Some(text.clone()),
+                    Outcome::Unavailable(reason) => Some(reason.to_string()),
+                    Outcome::Timeout | Outcome::Cancelled => None,
+                };
+                let response = AppToMcp::AskResponse {
+                    request_id,
+                    outcome: outcome.name().into(),
+                    text,
+                };
+                if let Some(pipe_state) = app.try_state::<PipeServerState>() {
+                    if let Err(e) = pipe_state.send(response) {
+                        warn!("[PipeServer] Failed to send ask response: {}", e);
+                    }
+                }
+            });
+        }
+        McpToApp::AskCancel { request_id } => {
+            if crate::voice::ask::cancel(Some(&request_id)) {
+                info!("[PipeServer] Ask {} cancelled", request_id);
+            }
+        }
+        McpToApp::VoiceOutputRequest { request_id, action, args } => {
+            info!("[PipeServer] Voice output request: id={}, action={}", request_id, action);
+            let app = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                use tauri::Manager;
+                let result = crate::voice::output::handle(&app, &action, &args);
+                let response = AppToMcp::VoiceOutputResponse {
+                    request_id,
+                    success: result.is_ok(),
+                    error: result.as_ref().err().cloned(),
+                    result: result.ok(),
+                };
+                if let Some(pipe_state) = app.try_state::<PipeServerState>() {
+                    if let Err(e) = pipe_state.send(response) {
+                        warn!("[PipeServer] Failed to send voice output response: {}", e);
+                    }
+                }
+            });
+        }
     }
 }
 
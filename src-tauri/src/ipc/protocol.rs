@@ -24,6 +24,16 @@ pub enum McpToApp {
         reply_to: Option<String>,
         message_id: String,
         timestamp: String,
+        /// Speech priority: "interrupt" (default), "queue", "drop_if_busy".
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        priority: Option<String>,
+        /// Set when the sender waits for a `VoiceSendAck`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+        /// `Some(false)` when the message's thread isn't the focused one
+        /// (see `mcp::threads`) and it shouldn't be spoken.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        speak: Option<bool>,
     },
     /// AI started listening for messages (voice_listen was called).
     ListenStart {
@@ -31,7 +41,21 @@ pub enum McpToApp {
         from_sender: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         thread_id: Option<String>,
+        /// Identifies this listen among concurrent ones on the connection.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+    },
+    /// A `ListenStart` ended without a message (timeout or cancelled call).
+    ListenCancel { request_id: String },
+    /// Push matching `voice-event`s as `AppToMcp::VoiceEvent` (see
+    /// `ipc::event_bridge`). Empty: all but the high-rate ones. Replaces any
+    /// earlier subscription.
+    SubscribeEvents {
+        #[serde(default)]
+        events: Vec<String>,
     },
+    /// Stop pushing voice events.
+    UnsubscribeEvents,
     /// MCP binary connected and is ready.
     Ready,
     /// Browser tool request from MCP to be processed by the Tauri app's native webview.
@@ -72,6 +96,34 @@ pub enum McpToApp {
         #[serde(default, skip_serializing_if = "Option::is_none")]
         structured: Option<bool>,
     },
+    /// A destructive tool was called without `confirmed: true`; ask the
+    /// user aloud (see `voice::confirm`).
+    ConfirmRequest {
+        request_id: String,
+        tool: String,
+        /// The question to speak.
+        prompt: String,
+    },
+    /// `voice_ask`: speak `question` and record the answer (see
+    /// `voice::ask`).
+    AskRequest {
+        request_id: String,
+        question: String,
+        /// Covers speaking the question and waiting for the answer.
+        timeout_secs: u64,
+    },
+    /// An `AskRequest` whose `voice_ask` call ended before the answer
+    /// (cancelled call or MCP-side timeout): close the question.
+    AskCancel { request_id: String },
+    /// `voice_output` tools: speak, stop, list or set voices (see
+    /// `voice::output`).
+    VoiceOutputRequest {
+        request_id: String,
+        /// "speak", "stop", "list_voices" or "set_voice".
+        action: String,
+        /// Action-specific arguments.
+        args: serde_json::Value,
+    },
 }
 
 /// Messages sent FROM the Tauri app TO the MCP binary.
@@ -125,6 +177,39 @@ pub enum AppToMcp {
         /// Formatted log text (ready for MCP output).
         text: String,
     },
+    /// Response to a ConfirmRequest.
+    ConfirmResponse {
+        request_id: String,
+        /// "yes", "no", "unclear", "timeout" or "unavailable" (not asked).
+        answer: String,
+    },
+    /// Response to an AskRequest.
+    AskResponse {
+        request_id: String,
+        /// "answered", "timeout", "cancelled" or "unavailable" (not asked).
+        outcome: String,
+        /// The transcribed answer, or why the question wasn't asked.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+    },
+    /// Response to a VoiceOutputRequest.
+    VoiceOutputResponse {
+        request_id: String,
+        success: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        result: Option<serde_json::Value>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// A `VoiceSend` with a `request_id` was delivered to the app.
+    VoiceSendAck { request_id: String },
+    /// A `voice-event` the MCP binary subscribed to (`SubscribeEvents`).
+    VoiceEvent {
+        /// The event tag, e.g. "state_change", "transcription".
+        event: String,
+        #[serde(default)]
+        data: serde_json::Value,
+    },
 }
 
 // ---------------------------------------------------------------------------
@@ -196,6 +281,9 @@ mod tests {
             reply_to: None,
             message_id: "msg-123".into(),
             timestamp: "2025-01-01T00:00:00.000Z".into(),
+            priority: None,
+            request_id: None,
+            speak: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         let parsed: McpToApp = serde_json::from_str(&json).unwrap();
@@ -378,6 +466,97 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_confirm_roundtrip() {
+        let msg = McpToApp::ConfirmRequest {
+            request_id: "confirm-1".into(),
+            tool: "memory_forget".into(),
+            prompt: "Forget the memory? Say yes or no.".into(),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"ConfirmRequest\""));
+
+        let json = r#"{"type":"ConfirmResponse","request_id":"confirm-1","answer":"yes"}"#;
+        match serde_json::from_str::<AppToMcp>(json).unwrap() {
+            AppToMcp::ConfirmResponse { request_id, answer } => {
+                assert_eq!(request_id, "confirm-1");
+                assert_eq!(answer, "yes");
+            }
+            _ => panic!("wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_ask_roundtrip() {
+        let msg = McpToApp::AskRequest {
+            request_id: "ask-1".into(),
+            question: "Which branch?".into(),
+            timeout_secs: 60,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"AskRequest\""));
+
+        let json = r#"{"type":"AskResponse","request_id":"ask-1","outcome":"timeout"}"#;
+        match serde_json::from_str::<AppToMcp>(json).unwrap() {
+            AppToMcp::AskResponse { outcome, text, .. } => {
+                assert_eq!(outcome, "timeout");
+                assert_eq!(text, None);
+            }
+            _ => panic!("wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_request_id_is_optional() {
+        // Messages from an MCP binary that predates request IDs
+        let json = r#"{"type":"ListenStart","instance_id":"a","from_sender":"user"}"#;
+        match serde_json::from_str::<McpToApp>(json).unwrap() {
+            McpToApp::ListenStart { request_id, .. } => assert_eq!(request_id, None),
+            _ => panic!("wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_listen_cancel_and_ack_roundtrip() {
+        let json = serde_json::to_string(&McpToApp::ListenCancel {
+            request_id: "listen-1".into(),
+        })
+        .unwrap();
+        match serde_json::from_str::<McpToApp>(&json).unwrap() {
+            McpToApp::ListenCancel { request_id } => assert_eq!(request_id, "listen-1"),
+            _ => panic!("wrong variant"),
+        }
+        let json = serde_json::to_string(&AppToMcp::VoiceSendAck {
+            request_id: "send-1".into(),
+        })
+        .unwrap();
+        match serde_json::from_str::<AppToMcp>(&json).unwrap() {
+            AppToMcp::VoiceSendAck { request_id } => assert_eq!(request_id, "send-1"),
+            _ => panic!("wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_subscribe_events_roundtrip() {
+        let json = r#"{"type":"SubscribeEvents"}"#;
+        match serde_json::from_str::<McpToApp>(json).unwrap() {
+            McpToApp::SubscribeEvents { events } => assert!(events.is_empty()),
+            _ => panic!("wrong variant"),
+        }
+        let json = serde_json::to_string(&AppToMcp::VoiceEvent {
+            event: "transcription".into(),
+            data: serde_json::json!({ "text": "hi" }),
+        })
+        .unwrap();
+        match serde_json::from_str::<AppToMcp>(&json).unwrap() {
+            AppToMcp::VoiceEvent { event, data } => {
+                assert_eq!(event, "transcription");
+                assert_eq!(data["text"], "hi");
+            }
+            _ => panic!("wrong variant"),
+        }
+    }
+
     #[tokio::test]
     async fn test_framing_roundtrip() {
         let msg = McpToApp::VoiceSend {
@@ -387,6 +566,9 @@ mod tests {
             reply_to: None,
             message_id: "m-1".into(),
             timestamp: "t".into(),
+            priority: Some("queue".into()),
+            request_id: Some("req-1".into()),
+            speak: Some(false),
         };
 
         // Write to buffer
@@ -397,8 +579,9 @@ mod tests {
         let mut cursor = std::io::Cursor::new(buf);
         let parsed: Option<McpToApp> = read_message(&mut cursor).await.unwrap();
         match parsed.unwrap() {
-            McpToApp::VoiceSend { message, .. } => {
+            McpToApp::VoiceSend { message, speak, .. } => {
                 assert_eq!(message, "Multi\nline\nmessage");
+                assert_eq!(speak, Some(false));
             }
             _ => panic!("wrong variant"),
         }
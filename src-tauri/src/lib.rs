@@ -1,5 +1,6 @@
 pub mod commands;
 pub mod config;
+pub mod error;
 pub mod ipc;
 pub mod mcp;
 pub mod providers;
@@ -15,6 +16,7 @@ use commands::config as config_cmds;
 use commands::screenshot as screenshot_cmds;
 use commands::shortcuts as shortcut_cmds;
 use commands::tools as tools_cmds;
+use commands::transcript as transcript_cmds;
 use commands::voice as voice_cmds;
 use commands::window as window_cmds;
 use commands::files as files_cmds;
@@ -32,6 +34,7 @@ use commands::sandbox as sandbox_cmds;
 
 use providers::manager::AiManager;
 use providers::ProviderEvent;
+use services::event_throttle::EmitThrottled;
 use voice::VoiceEngine;
 
 use tauri::{Emitter, Manager};
@@ -39,6 +42,8 @@ use tracing::{info, warn};
 
 /// Pre-loaded TTS engine state. Populated during app startup in a background
 /// task so the voice pipeline can use it immediately without cold-start delay.
+/// The Kokoro engine built here carries its warm Edge standby
+/// ([`voice::tts::WarmStandby`]), so fallback needs no construction either.
 pub type PreloadedTtsState = std::sync::Mutex<Option<Box<dyn voice::tts::TtsEngine>>>;
 
 /// CDP remote-debugging port for Voice Mirror's OWN WebView2 host process.
@@ -149,8 +154,10 @@ fn install_panic_hook() {
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_secs())
             .unwrap_or(0);
+        // Voice errors leading up to the panic (see `voice::errors`)
+        let breadcrumbs = voice::errors::breadcrumbs();
         let text = format!(
-            "==== Voice Mirror PANIC ====\nepoch: {epoch}\nthread: {thread_name}\nlocation: {location}\nmessage: {payload}\n\nbacktrace:\n{backtrace}\n============================\n\n"
+            "==== Voice Mirror PANIC ====\nepoch: {epoch}\nthread: {thread_name}\nlocation: {location}\nmessage: {payload}\n\nrecent voice errors:\n{breadcrumbs}\nbacktrace:\n{backtrace}\n============================\n\n"
         );
         let dir = services::platform::get_log_dir();
         let _ = std::fs::create_dir_all(&dir);
@@ -195,9 +202,10 @@ pub fn run() {
         .plugin(tauri_plugin_decorum::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_single_instance::init(|_app, _argv, _cwd| {
-            // If user tries to launch a second instance, focus the existing window
-            info!("Second instance detected, focusing existing window");
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            // A second launch exits before setup; focus this window and run
+            // the commands on its command line
+            crate::services::second_instance::handle(app, argv);
         }))
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
@@ -343,6 +351,9 @@ pub fn run() {
             downloads: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
             devtools_label: std::sync::Mutex::new(None),
         })
+        .manage(services::model_manager::ModelManager::new(
+            services::platform::get_data_dir(),
+        ))
         .manage(services::file_watcher::FileWatcherState {
             handle: std::sync::Mutex::new(None),
         })
@@ -392,11 +403,24 @@ pub fn run() {
             // Voice
             voice_cmds::start_voice,
             voice_cmds::stop_voice,
+            voice_cmds::pause_voice,
+            voice_cmds::resume_voice,
             voice_cmds::get_voice_status,
             voice_cmds::set_voice_mode,
             voice_cmds::list_audio_devices,
             voice_cmds::stop_speaking,
             voice_cmds::speak_text,
+            voice_cmds::speak_to_file,
+            voice_cmds::voice_set_tts_voice,
+            voice_cmds::voice_set_tts_speed,
+            voice_cmds::voice_set_tts_volume,
+            voice_cmds::voice_set_tts_effects,
+            voice_cmds::clear_tts_cache,
+            voice_cmds::control_api_status,
+            voice_cmds::configure_control_api,
+            voice_cmds::get_quiet_hours_status,
+            voice_cmds::configure_quiet_hours,
+            voice_cmds::set_quiet_hours_override,
             voice_cmds::ptt_press,
             voice_cmds::ptt_release,
             voice_cmds::cancel_recording,
@@ -405,20 +429,50 @@ pub fn run() {
             voice_cmds::configure_dictation_key,
             voice_cmds::ensure_stt_model,
             voice_cmds::ensure_kokoro_model,
+            voice_cmds::ensure_parakeet_model,
+            // Model manager
+            services::model_manager::list_model_assets,
+            services::model_manager::download_model,
+            services::model_manager::delete_model,
             voice_cmds::restart_voice,
+            voice_cmds::list_voice_profiles,
+            voice_cmds::save_voice_profile,
+            voice_cmds::activate_voice_profile,
+            voice_cmds::delete_voice_profile,
+            voice_cmds::validate_voice_config,
             voice_cmds::detect_espeak,
             voice_cmds::detect_gpu,
+            voice_cmds::recommend_stt_model,
             voice_cmds::list_stt_models,
             voice_cmds::delete_stt_model,
             voice_cmds::inject_text,
+            voice_cmds::voice_calibrate,
+            voice_cmds::voice_self_test,
+            voice_cmds::run_vad_corpus,
+            voice_cmds::get_pipeline_timings,
+            voice_cmds::get_metrics,
+            voice_cmds::voice_get_errors,
+            voice_cmds::voice_clear_errors,
+            voice_cmds::get_latency_ladder,
+            voice_cmds::set_latency_ladder,
+            voice_cmds::get_voice_stats,
+            voice_cmds::list_speakers,
+            voice_cmds::enroll_speaker,
+            voice_cmds::delete_speaker,
+            voice_cmds::get_capabilities,
             // AI (real implementations)
             ai_cmds::start_ai,
             ai_cmds::stop_ai,
             ai_cmds::get_ai_status,
             ai_cmds::ai_pty_input,
+            ai_cmds::send_text_message,
             ai_cmds::ai_raw_input,
             ai_cmds::ai_pty_resize,
             ai_cmds::interrupt_ai,
+            ai_cmds::set_session_prompt,
+            ai_cmds::get_session_prompt,
+            ai_cmds::snapshot_session,
+            ai_cmds::restore_session,
             ai_cmds::send_voice_loop,
             ai_cmds::scan_providers,
             ai_cmds::list_models,
@@ -432,6 +486,15 @@ pub fn run() {
             chat_cmds::chat_delete,
             chat_cmds::chat_rename,
             chat_cmds::export_chat_to_file,
+            // Conversation transcript log
+            transcript_cmds::transcript_query,
+            transcript_cmds::transcript_export_markdown,
+            transcript_cmds::transcript_purge,
+            // Turn audio archive
+            transcript_cmds::turn_archive_list,
+            transcript_cmds::turn_archive_get,
+            transcript_cmds::turn_archive_recheck,
+            transcript_cmds::turn_archive_purge,
             // CLI tool detection
             tools_cmds::scan_cli_tools,
             tools_cmds::check_npm_versions,
@@ -441,6 +504,7 @@ pub fn run() {
             shortcut_cmds::unregister_shortcut,
             shortcut_cmds::list_shortcuts,
             shortcut_cmds::unregister_all_shortcuts,
+            shortcut_cmds::configure_hotkeys,
             // Performance stats
             window_cmds::get_process_stats,
             // Lens (embedded browser) — tabs
@@ -595,6 +659,9 @@ pub fn run() {
             onboarding_cmds::install_provider,
             onboarding_cmds::probe_provider_auth,
             onboarding_cmds::validate_api_key,
+            onboarding_cmds::get_onboarding_status,
+            onboarding_cmds::run_onboarding_step,
+            onboarding_cmds::skip_onboarding_step,
             output_cmds::get_output_logs,
             output_cmds::export_diagnostics,
             output_cmds::log_frontend_error,
@@ -638,6 +705,11 @@ pub fn run() {
                 }
             }
 
+            // Install emission policies before anything starts streaming events.
+            services::event_throttle::init(
+                commands::config::get_config_snapshot().advanced.event_throttle,
+            );
+
             // Take the event receiver from the AI manager and spawn a forwarding loop.
             // This bridges provider events (terminal output, stream tokens, errors, etc.)
             // to the frontend via Tauri's event system.
@@ -689,23 +761,44 @@ pub fn run() {
                                 vec![("ai-stream-end", serde_json::json!({ "text": text }))]
                             }
                             ProviderEvent::Response(text) => {
-                                vec![("ai-response", serde_json::json!({ "text": text }))]
+                                voice_cmds::note_provider_reply(
+                                    &app_handle,
+                                    ai_cmds::speak_replies(),
+                                );
+                                vec![("ai-response", serde_json::json!({
+                                    "text": text,
+                                    "speak": ai_cmds::speak_replies(),
+                                }))]
                             }
                             ProviderEvent::ToolCalls(calls) => {
                                 vec![("ai-tool-calls", serde_json::json!({ "calls": calls }))]
                             }
+                            ProviderEvent::ToolPlanStep(step) => {
+                                ai_cmds::narrate_tool_step(&app_handle, step);
+                                vec![("ai-tool-plan-step", serde_json::json!(step))]
+                            }
                         };
 
-                        // Best-effort emit — if the window is gone, stop the loop
+                        // Best-effort emit — if the window is gone, stop the loop.
+                        // Stream tokens are batched by the event throttle.
                         let mut failed = false;
                         for (event_name, payload) in emissions {
-                            if app_handle.emit(event_name, payload).is_err() {
+                            if app_handle.emit_throttled(event_name, payload).is_err() {
                                 warn!("Failed to emit AI event '{}', stopping forwarding loop", event_name);
                                 failed = true;
                                 break;
                             }
                         }
                         if failed { break; }
+
+                        // A finished reply lets the next waiting turn go
+                        match event {
+                            ProviderEvent::Response(_)
+                            | ProviderEvent::Error(_)
+                            | ProviderEvent::ToolCalls(_) => ai_cmds::end_turn(&app_handle),
+                            ProviderEvent::Exit(_) => ai_cmds::drop_turns(),
+                            _ => {}
+                        }
                     }
 
                     info!("AI provider event forwarding loop ended");
@@ -863,11 +956,17 @@ pub fn run() {
 
             // Pre-load TTS engine in background so it's ready for the first message.
             // This avoids the cold-start "No TTS engine available" error.
-            {
+            // Skipped when the user asked for the TTS engine to load lazily.
+            let voice_cfg = commands::config::get_config_snapshot().voice;
+            if voice::EngineLoadMode::parse(&voice_cfg.tts_load) != voice::EngineLoadMode::Lazy {
                 let app_handle_tts = app.handle().clone();
                 tauri::async_runtime::spawn(async move {
-                    match tokio::task::spawn_blocking(|| {
-                        voice::tts::create_tts_engine("kokoro", Some("af_bella"), Some(1.0))
+                    match tokio::task::spawn_blocking(move || {
+                        voice::tts::create_tts_engine(
+                            &voice_cfg.tts_adapter,
+                            Some(&voice_cfg.tts_voice),
+                            Some(voice_cfg.tts_speed as f32),
+                        )
                     })
                     .await
                     {
@@ -888,6 +987,18 @@ pub fn run() {
                 });
             }
 
+            // Start the localhost control API if the user enabled it.
+            services::control_api::start_from_config(app.handle());
+
+            // Register the OS-level voice hotkeys (PTT, toggle, stop speaking, mode).
+            services::hotkeys::start_from_config(app.handle());
+
+            // Forward voice events to any configured outbound webhooks.
+            services::webhooks::start(app.handle());
+
+            // Evaluate the quiet hours schedule (no TTS / wake word while active).
+            services::quiet_hours::start(app.handle());
+
             // Start unified input hook for PTT and dictation keybindings.
             // Installs both WH_KEYBOARD_LL and WH_MOUSE_LL hooks.
             // Keyboard keys from mouse side buttons are suppressed + emitted as events.
@@ -900,6 +1011,8 @@ pub fn run() {
                     info!("Named pipe server started: {}", pipe_name);
                     ipc::set_pipe_name(pipe_name.clone());
                     app.manage(state);
+                    // Push subscribed voice events to the MCP binary.
+                    ipc::event_bridge::start(app.handle());
                 }
                 Err(e) => {
                     warn!("Failed to start pipe server: {} — falling back to file IPC", e);
@@ -1,13 +1,14 @@
-//! Core MCP tool handlers: voice_send, voice_inbox, voice_listen, voice_status.
+//! Core MCP tool handlers: voice_send, voice_inbox, voice_listen, voice_status,
+//! voice_talk_time, voice_subscribe, voice_threads, voice_ask.
 //!
 //! These tools use file-based IPC:
-//! - Inbox:  `{data_dir}/inbox.json`  -- message store
-//! - Status: `{data_dir}/status.json` -- presence tracking
+//! - Inbox:  `{data_dir}/inbox.db`    -- message store (SQLite, see `services::inbox_db`)
+//! - Status: `{data_dir}/status.json` -- presence tracking and per-instance voices (see `services::instances`)
 //! - Lock:   `{data_dir}/listener_lock.json` -- exclusive listener lock
+//! - Stats:  `{data_dir}/voice_stats.json` -- daily talk time (written by the app)
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::time::{Duration, Instant};
@@ -16,6 +17,10 @@ use tracing::{info, warn};
 use super::{McpContent, McpToolResult};
 use crate::ipc::protocol::{AppToMcp, McpToApp};
 use crate::mcp::pipe_router::PipeRouter;
+use crate::mcp::threads::SharedThreads;
+use crate::services::inbox_db::{self, InboxDb, InboxMessage};
+use crate::services::instances::{self, InstanceStatus, StatusStore, STALE_TIMEOUT_MS};
+use crate::services::voice_stats;
 
 /// Extract base64 from a `data:image/png;base64,...` URL and build an MCP image content block.
 fn image_content_from_data_url(data_url: &str) -> Option<McpContent> {
@@ -48,53 +53,14 @@ fn text_with_optional_image(text: String, image_data_url: Option<&str>) -> McpTo
 // Constants
 // ---------------------------------------------------------------------------
 
-const STALE_TIMEOUT_MS: u64 = 2 * 60 * 1000; // 2 minutes
-const AUTO_CLEANUP_HOURS: u64 = 24;
 const LISTENER_LOCK_TIMEOUT_MS: u64 = 310 * 1000; // 310s (> 300s default listen timeout)
-const MAX_MESSAGES: usize = 100;
-const MAX_INBOX_TOTAL: usize = 500;
+/// How long voice_send waits for the app to acknowledge a piped message.
+const VOICE_SEND_ACK_TIMEOUT: Duration = Duration::from_secs(1);
 
 // ---------------------------------------------------------------------------
-// Data types for inbox and status files
+// Data types for status files
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct InboxStore {
-    messages: Vec<InboxMessage>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct InboxMessage {
-    id: String,
-    from: String,
-    message: String,
-    timestamp: String,
-    #[serde(default)]
-    read_by: Vec<String>,
-    #[serde(default)]
-    thread_id: Option<String>,
-    #[serde(default)]
-    reply_to: Option<String>,
-    #[serde(default)]
-    image_path: Option<String>,
-    #[serde(default)]
-    image_data_url: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct StatusStore {
-    statuses: Vec<InstanceStatus>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct InstanceStatus {
-    instance_id: String,
-    status: String,
-    #[serde(default)]
-    current_task: Option<String>,
-    last_heartbeat: String,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ListenerLock {
     instance_id: String,
@@ -115,14 +81,6 @@ struct MessageTrigger {
 // File helpers
 // ---------------------------------------------------------------------------
 
-fn inbox_path(data_dir: &Path) -> PathBuf {
-    data_dir.join("inbox.json")
-}
-
-fn status_path(data_dir: &Path) -> PathBuf {
-    data_dir.join("status.json")
-}
-
 fn lock_path(data_dir: &Path) -> PathBuf {
     data_dir.join("listener_lock.json")
 }
@@ -131,6 +89,18 @@ fn trigger_path(data_dir: &Path) -> PathBuf {
     data_dir.join("claude_message_trigger.json")
 }
 
+/// Run `f` on the inbox database on a blocking thread.
+async fn with_inbox<T, F>(data_dir: &Path, f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&InboxDb) -> Result<T, String> + Send + 'static,
+{
+    let data_dir = data_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || f(&InboxDb::open(&data_dir)?))
+        .await
+        .map_err(|e| format!("Inbox task failed: {}", e))?
+}
+
 /// Read and parse a JSON file, returning a default value if the file doesn't exist or is corrupt.
 async fn read_json_file<T: serde::de::DeserializeOwned>(path: &Path, default: T) -> T {
     match tokio::fs::read_to_string(path).await {
@@ -232,25 +202,45 @@ async fn update_heartbeat(
     status: &str,
     current_task: Option<&str>,
 ) {
-    let path = status_path(data_dir);
-    let mut store: StatusStore = read_json_file(&path, StatusStore { statuses: vec![] }).await;
-    let now = now_iso();
+    set_instance_status(data_dir, instance_id, status, current_task, None).await;
+}
 
-    let new_status = InstanceStatus {
-        instance_id: instance_id.to_string(),
-        status: status.to_string(),
-        current_task: current_task.map(|s| s.to_string()),
-        last_heartbeat: now,
-    };
+/// Record an instance's heartbeat, and its TTS voice when `voice` is given
+/// (`Some("")` clears it). Otherwise the voice it registered is kept.
+async fn set_instance_status(
+    data_dir: &Path,
+    instance_id: &str,
+    status: &str,
+    current_task: Option<&str>,
+    voice: Option<&str>,
+) {
+    let path = instances::status_path(data_dir);
+    let mut store: StatusStore = read_json_file(&path, StatusStore::default()).await;
+    let now = now_iso();
 
-    if let Some(idx) = store
+    let idx = match store
         .statuses
         .iter()
         .position(|s| s.instance_id == instance_id)
     {
-        store.statuses[idx] = new_status;
-    } else {
-        store.statuses.push(new_status);
+        Some(idx) => idx,
+        None => {
+            store.statuses.push(InstanceStatus {
+                instance_id: instance_id.to_string(),
+                status: String::new(),
+                current_task: None,
+                last_heartbeat: String::new(),
+                voice: None,
+            });
+            store.statuses.len() - 1
+        }
+    };
+    let entry = &mut store.statuses[idx];
+    entry.status = status.to_string();
+    entry.current_task = current_task.map(|s| s.to_string());
+    entry.last_heartbeat = now;
+    if let Some(voice) = voice {
+        entry.voice = Some(voice.trim().to_string()).filter(|v| !v.is_empty());
     }
 
     if let Err(e) = atomic_write_json(&path, &store).await {
@@ -327,6 +317,7 @@ pub async fn handle_voice_send(
     args: &Value,
     data_dir: &Path,
     router: Option<&Arc<PipeRouter>>,
+    threads: &SharedThreads,
 ) -> McpToolResult {
     let instance_id = match args.get("instance_id").and_then(|v| v.as_str()) {
         Some(id) => id,
@@ -338,36 +329,50 @@ pub async fn handle_voice_send(
     };
     let thread_id = args.get("thread_id").and_then(|v| v.as_str());
     let reply_to = args.get("reply_to").and_then(|v| v.as_str());
+    let priority = match args.get("priority").and_then(|v| v.as_str()) {
+        Some(p @ ("interrupt" | "queue" | "drop_if_busy")) => Some(p.to_string()),
+        Some(other) => {
+            return McpToolResult::error(format!(
+                "Error: priority must be interrupt, queue or drop_if_busy (got \"{}\")",
+                other
+            ))
+        }
+        None => None,
+    };
 
     update_heartbeat(data_dir, instance_id, "active", Some("Sending message")).await;
 
-    // Load existing messages
-    let path = inbox_path(data_dir);
-    let mut store: InboxStore = read_json_file(&path, InboxStore { messages: vec![] }).await;
+    // Find the parent message's thread
+    let parent_thread = match reply_to {
+        Some(reply) if thread_id.is_none() => {
+            let reply = reply.to_string();
+            with_inbox(data_dir, move |db| db.thread_of(&reply))
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("[voice_send] {}", e);
+                    None
+                })
+        }
+        _ => None,
+    };
 
     // Resolve thread ID
     let resolved_thread_id = if let Some(tid) = thread_id {
         tid.to_string()
-    } else if let Some(reply) = reply_to {
-        // Find parent message's thread
-        store
-            .messages
-            .iter()
-            .find(|m| m.id == reply)
-            .and_then(|m| m.thread_id.clone())
-            .unwrap_or_else(|| {
-                if instance_id == "voice-claude" {
-                    "voice-mirror".to_string()
-                } else {
-                    format!("thread_{}", now_ms())
-                }
-            })
+    } else if let Some(parent) = parent_thread {
+        parent
     } else if instance_id == "voice-claude" {
         "voice-mirror".to_string()
     } else {
         format!("thread_{}", now_ms())
     };
 
+    // Only the focused thread is spoken
+    let speak = threads
+        .lock()
+        .map(|mut t| t.record(&resolved_thread_id, instance_id, message, now_ms()))
+        .unwrap_or(true);
+
     // Create new message
     let new_message = InboxMessage {
         id: generate_msg_id(),
@@ -379,17 +384,16 @@ pub async fn handle_voice_send(
         reply_to: reply_to.map(|s| s.to_string()),
         image_path: None,
         image_data_url: None,
+        priority: priority.clone(),
     };
 
-    store.messages.push(new_message.clone());
-
-    // Keep last MAX_MESSAGES messages
-    if store.messages.len() > MAX_MESSAGES {
-        let start = store.messages.len() - MAX_MESSAGES;
-        store.messages = store.messages[start..].to_vec();
-    }
-
-    if let Err(e) = atomic_write_json(&path, &store).await {
+    let stored = new_message.clone();
+    let saved = with_inbox(data_dir, move |db| {
+        db.insert(&stored)?;
+        db.cleanup(inbox_db::MESSAGE_TTL, inbox_db::MAX_MESSAGES)
+    })
+    .await;
+    if let Err(e) = saved {
         return McpToolResult::error(format!("Error: {}", e));
     }
 
@@ -406,6 +410,8 @@ pub async fn handle_voice_send(
     // Fast path: also send via named pipe for instant delivery to the Tauri app.
     // This bypasses the file watcher debounce (~100ms) for sub-ms event delivery.
     if let Some(router) = router {
+        let request_id = router.next_request_id("send");
+        let ack = router.wait_for_browser_response(&request_id).await;
         let pipe_msg = McpToApp::VoiceSend {
             from: instance_id.to_string(),
             message: message.to_string(),
@@ -413,9 +419,17 @@ pub async fn handle_voice_send(
             reply_to: reply_to.map(|s| s.to_string()),
             message_id: new_message.id.clone(),
             timestamp: new_message.timestamp.clone(),
+            priority,
+            request_id: Some(request_id.clone()),
+            speak: (!speak).then_some(false),
         };
         if let Err(e) = router.send(&pipe_msg).await {
             warn!("[voice_send] Pipe send failed (file fallback still active): {}", e);
+            router.remove_waiter(&request_id).await;
+        } else if tokio::time::timeout(VOICE_SEND_ACK_TIMEOUT, ack).await.is_err() {
+            // An app that predates acks never answers; the message still went out
+            warn!("[voice_send] No ack for {} (file fallback still active)", request_id);
+            router.remove_waiter(&request_id).await;
         }
     }
 
@@ -425,10 +439,14 @@ pub async fn handle_voice_send(
         message.to_string()
     };
 
-    McpToolResult::text(format!(
+    let mut text = format!(
         "Message sent in thread [{}]:\n\"{}\"",
         resolved_thread_id, preview
-    ))
+    );
+    if !speak {
+        text.push_str("\n(Not spoken: this thread isn't the focused one. See voice_threads.)");
+    }
+    McpToolResult::text(text)
 }
 
 /// `voice_inbox` -- Read messages from inbox.
@@ -453,69 +471,24 @@ pub async fn handle_voice_inbox(args: &Value, data_dir: &Path) -> McpToolResult
 
     update_heartbeat(data_dir, instance_id, "active", Some("Checking inbox")).await;
 
-    let path = inbox_path(data_dir);
-    if !path.exists() {
-        return McpToolResult::text("No messages in inbox.");
-    }
-
-    let mut store: InboxStore = read_json_file(&path, InboxStore { messages: vec![] }).await;
-
-    // Auto-cleanup old messages (24h cutoff)
-    let cutoff_ms = now_ms() - (AUTO_CLEANUP_HOURS * 60 * 60 * 1000);
-    store.messages.retain(|m| {
-        // Parse ISO timestamp to epoch ms (best-effort)
-        parse_iso_to_ms(&m.timestamp).unwrap_or(0) > cutoff_ms
-    });
-
-    // Cap at MAX_INBOX_TOTAL
-    if store.messages.len() > MAX_INBOX_TOTAL {
-        let start = store.messages.len() - MAX_INBOX_TOTAL;
-        store.messages = store.messages[start..].to_vec();
-    }
-
-    // Mark as read if requested (do this BEFORE filtering to avoid borrow issues)
-    if mark_as_read {
-        let id_str = instance_id.to_string();
-        for msg in &mut store.messages {
-            if msg.from == instance_id {
-                continue;
-            }
-            if !msg.read_by.contains(&id_str) {
-                msg.read_by.push(id_str.clone());
+    let instance = instance_id.to_string();
+    let inbox = with_inbox(data_dir, move |db| {
+        // Auto-cleanup old messages (24h cutoff, capped count)
+        db.cleanup(inbox_db::MESSAGE_TTL, inbox_db::MAX_MESSAGES)?;
+        let inbox = db.messages_for(&instance, include_read, limit)?;
+        // Read before marking, so the ones just marked still show this time
+        if mark_as_read {
+            if let Err(e) = db.mark_read(&instance) {
+                warn!("[MCP Core] Failed to mark messages as read: {}", e);
             }
         }
-        if let Err(e) = atomic_write_json(&path, &store).await {
-            warn!("[MCP Core] Failed to mark messages as read: {}", e);
-        }
-    }
-
-    // Filter out own messages
-    let mut inbox: Vec<&InboxMessage> = store
-        .messages
-        .iter()
-        .filter(|m| m.from != instance_id)
-        .collect();
-
-    // Filter by read status (use original read_by before mark_as_read for consistency)
-    if !include_read {
-        inbox.retain(|m| {
-            // If mark_as_read was true, we just marked them -- still show them this time
-            if mark_as_read {
-                // Show all messages this time (they were just marked)
-                true
-            } else {
-                !m.read_by.contains(&instance_id.to_string())
-            }
-        });
-    }
-
-    // Apply limit (take last N)
-    let start = if inbox.len() > limit {
-        inbox.len() - limit
-    } else {
-        0
+        Ok(inbox)
+    })
+    .await;
+    let inbox = match inbox {
+        Ok(inbox) => inbox,
+        Err(e) => return McpToolResult::error(format!("Error: {}", e)),
     };
-    let inbox = &inbox[start..];
 
     if inbox.is_empty() {
         return McpToolResult::text("No new messages.");
@@ -554,14 +527,42 @@ pub async fn handle_voice_inbox(args: &Value, data_dir: &Path) -> McpToolResult
     }
 }
 
+/// A listen opened on the pipe router by `voice_listen`. Unless it got its
+/// message (`done`), dropping it closes the listen and sends `ListenCancel`:
+/// on timeout, and when the tool call is cancelled and its task dropped.
+struct OpenListen {
+    router: Arc<PipeRouter>,
+    request_id: String,
+    done: bool,
+}
+
+impl Drop for OpenListen {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let router = Arc::clone(&self.router);
+        let request_id = std::mem::take(&mut self.request_id);
+        tokio::spawn(async move { router.cancel_listen(&request_id).await });
+    }
+}
+
+/// Note a received message in its thread, if it has one.
+fn record_in_thread(threads: &SharedThreads, thread_id: Option<&str>, from: &str, message: &str) {
+    if let (Some(thread_id), Ok(mut threads)) = (thread_id, threads.lock()) {
+        threads.record(thread_id, from, message, now_ms());
+    }
+}
+
 /// `voice_listen` -- Wait for new messages from a specific sender.
 ///
 /// When a pipe is available, listens for instant delivery via named pipe.
-/// Falls back to polling inbox.json every 5 seconds when no pipe is connected.
+/// Falls back to polling the inbox database every 5 seconds when no pipe is connected.
 pub async fn handle_voice_listen(
     args: &Value,
     data_dir: &Path,
     router: Option<&Arc<PipeRouter>>,
+    threads: &SharedThreads,
 ) -> McpToolResult {
     let instance_id = match args.get("instance_id").and_then(|v| v.as_str()) {
         Some(id) => id,
@@ -596,31 +597,38 @@ pub async fn handle_voice_listen(
     let lock_refresh_interval = Duration::from_secs(30);
     let mut last_lock_refresh = Instant::now();
 
-    // Capture existing message IDs
-    let path = inbox_path(data_dir);
-    let existing_ids: HashSet<String> = {
-        let store: InboxStore = read_json_file(&path, InboxStore { messages: vec![] }).await;
-        store.messages.iter().map(|m| m.id.clone()).collect()
-    };
+    // Only messages added from here on count
+    let start_seq = with_inbox(data_dir, |db| db.last_seq()).await.unwrap_or(0);
 
-    // Fast path: if pipe router is available, listen for instant message delivery
-    // via the router's user_messages_rx channel (already dispatched by PipeRouter).
+    // Fast path: if pipe router is available, open a listen on it for instant
+    // delivery. Each call has its own request_id, so concurrent voice_listen
+    // calls on the one connection each get only their own messages.
     if let Some(router) = router {
+        let request_id = router.next_request_id("listen");
+        let mut rx = router
+            .listen(&request_id, from_sender, thread_filter)
+            .await;
+        let mut open = OpenListen {
+            router: Arc::clone(router),
+            request_id: request_id.clone(),
+            done: false,
+        };
+
         // Notify the Tauri app that we're listening
         let _ = router
             .send(&McpToApp::ListenStart {
                 instance_id: instance_id.to_string(),
                 from_sender: from_sender.to_string(),
                 thread_id: thread_filter.map(|s| s.to_string()),
+                request_id: Some(request_id.clone()),
             })
             .await;
 
         info!(
-            "[voice_listen] Waiting for routed message from '{}' (timeout: {}s)",
-            from_sender, timeout_seconds
+            "[voice_listen] {} waiting for routed message from '{}' (timeout: {}s)",
+            request_id, from_sender, timeout_seconds
         );
 
-        let mut rx_guard = router.user_messages_rx.lock().await;
         let mut pipe_ok = true;
         loop {
             let remaining = timeout.saturating_sub(start.elapsed());
@@ -634,8 +642,8 @@ pub async fn handle_voice_listen(
                 last_lock_refresh = Instant::now();
             }
 
-            match tokio::time::timeout(remaining.min(Duration::from_secs(30)), rx_guard.recv()).await {
-                Ok(Some(AppToMcp::UserMessage {
+            match tokio::time::timeout(remaining.min(Duration::from_secs(30)), &mut rx).await {
+                Ok(Ok(AppToMcp::UserMessage {
                     id,
                     from,
                     message,
@@ -644,20 +652,10 @@ pub async fn handle_voice_listen(
                     image_path: _,
                     image_data_url,
                 })) => {
-                    // Check sender match
-                    if from.to_lowercase() != from_sender.to_lowercase() {
-                        continue;
-                    }
-                    // Check thread filter
-                    if let Some(filter) = thread_filter {
-                        if msg_thread.as_deref() != Some(filter) {
-                            continue;
-                        }
-                    }
-
+                    open.done = true;
                     let wait_secs = start.elapsed().as_secs();
-                    drop(rx_guard);
                     release_listener_lock(data_dir, instance_id).await;
+                    record_in_thread(threads, msg_thread.as_deref(), &from, &message);
 
                     info!(
                         "[voice_listen] Routed message received from '{}', has_image: {}, image_data_url_len: {}",
@@ -680,31 +678,29 @@ pub async fn handle_voice_listen(
                     );
                     return text_with_optional_image(text, image_data_url.as_deref());
                 }
-                Ok(Some(AppToMcp::Shutdown)) => {
-                    drop(rx_guard);
+                Ok(Ok(_)) => {
+                    // Shutdown (the router only hands listens user messages
+                    // and Shutdown)
+                    open.done = true;
                     release_listener_lock(data_dir, instance_id).await;
                     return McpToolResult::text("Shutdown requested, stopping listener.");
                 }
-                Ok(Some(_)) => {
-                    // BrowserResponse shouldn't arrive here (routed by PipeRouter), skip
-                    continue;
-                }
-                Ok(None) => {
-                    // Channel closed — fall back to file polling
-                    warn!("[voice_listen] User message channel closed, falling back to file polling");
+                Ok(Err(_)) => {
+                    // Pipe closed — fall back to file polling
+                    warn!("[voice_listen] Pipe closed, falling back to file polling");
+                    open.done = true;
                     pipe_ok = false;
                     break;
                 }
                 Err(_) => {
-                    // Timeout on this recv — loop to check overall timeout / refresh lock
+                    // Timeout on this wait — loop to check overall timeout / refresh lock
                     continue;
                 }
             }
         }
 
-        drop(rx_guard);
-
         // If pipe was healthy and we just timed out, return timeout
+        // (dropping `open` cancels the listen)
         if pipe_ok {
             release_listener_lock(data_dir, instance_id).await;
             return McpToolResult::text(format!(
@@ -728,26 +724,28 @@ pub async fn handle_voice_listen(
         }
 
         // Check for new messages
-        let store: InboxStore = read_json_file(&path, InboxStore { messages: vec![] }).await;
+        let new_messages = with_inbox(data_dir, move |db| db.newer_than(start_seq))
+            .await
+            .unwrap_or_default();
 
-        let new_msg = store
-            .messages
+        let new_msg = new_messages
             .iter()
+            .map(|(_, m)| m)
             .filter(|m| m.from.to_lowercase() == from_sender.to_lowercase())
-            .filter(|m| {
+            .rfind(|m| {
                 if let Some(filter) = thread_filter {
                     m.thread_id.as_deref() == Some(filter)
                 } else {
                     true
                 }
-            })
-            .rfind(|m| !existing_ids.contains(&m.id));
+            });
 
         if let Some(msg) = new_msg {
             let wait_secs = start.elapsed().as_secs();
 
             // Release lock before returning
             release_listener_lock(data_dir, instance_id).await;
+            record_in_thread(threads, msg.thread_id.as_deref(), &msg.from, &msg.message);
 
             info!(
                 "[voice_listen] Inbox message found from '{}', has_image: {}, image_data_url_len: {}",
@@ -804,14 +802,15 @@ pub async fn handle_voice_status(args: &Value, data_dir: &Path) -> McpToolResult
         .and_then(|v| v.as_str())
         .unwrap_or("active");
     let current_task = args.get("current_task").and_then(|v| v.as_str());
+    let voice = args.get("voice").and_then(|v| v.as_str());
 
     if action == "list" {
-        let path = status_path(data_dir);
+        let path = instances::status_path(data_dir);
         if !path.exists() {
             return McpToolResult::text("No active instances.");
         }
 
-        let store: StatusStore = read_json_file(&path, StatusStore { statuses: vec![] }).await;
+        let store: StatusStore = read_json_file(&path, StatusStore::default()).await;
         let now = now_ms();
 
         let formatted: Vec<String> = store
@@ -821,12 +820,18 @@ pub async fn handle_voice_status(args: &Value, data_dir: &Path) -> McpToolResult
                 let last_hb = parse_iso_to_ms(&s.last_heartbeat).unwrap_or(0);
                 let is_stale = (now - last_hb) > STALE_TIMEOUT_MS;
                 let stale_indicator = if is_stale { " [STALE]" } else { "" };
+                let voice_info = s
+                    .voice
+                    .as_deref()
+                    .map(|v| format!(" (voice: {})", v))
+                    .unwrap_or_default();
                 format!(
-                    "[{}] {}{} - {}",
+                    "[{}] {}{} - {}{}",
                     s.instance_id,
                     s.status,
                     stale_indicator,
-                    s.current_task.as_deref().unwrap_or("idle")
+                    s.current_task.as_deref().unwrap_or("idle"),
+                    voice_info
                 )
             })
             .collect();
@@ -838,17 +843,266 @@ pub async fn handle_voice_status(args: &Value, data_dir: &Path) -> McpToolResult
     }
 
     // Update status
-    update_heartbeat(data_dir, instance_id, status, current_task).await;
+    set_instance_status(data_dir, instance_id, status, current_task, voice).await;
 
     let task_info = current_task
         .map(|t| format!(" - {}", t))
         .unwrap_or_default();
+    let voice_info = match voice.map(str::trim) {
+        Some("") => " (voice reset to the default)".to_string(),
+        Some(v) => format!(" (replies spoken with voice {})", v),
+        None => String::new(),
+    };
     McpToolResult::text(format!(
-        "Status updated: [{}] {}{}",
-        instance_id, status, task_info
+        "Status updated: [{}] {}{}{}",
+        instance_id, status, task_info, voice_info
     ))
 }
 
+// ---------------------------------------------------------------------------
+// voice_subscribe
+// ---------------------------------------------------------------------------
+
+/// Handle `voice_subscribe` -- have the app push voice events (state
+/// changes, transcriptions, errors) as `notifications/voice_event`.
+pub async fn handle_voice_subscribe(
+    args: &Value,
+    router: Option<&Arc<PipeRouter>>,
+) -> McpToolResult {
+    let Some(router) = router else {
+        return McpToolResult::error(
+            "Error: voice events need the pipe connection to Voice Mirror, which is not available",
+        );
+    };
+    let unsubscribe = args.get("action").and_then(|v| v.as_str()) == Some("unsubscribe");
+    let events: Vec<String> = args
+        .get("events")
+        .and_then(|v| v.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|e| e.as_str())
+                .map(|e| e.trim().to_string())
+                .filter(|e| !e.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let msg = if unsubscribe {
+        McpToApp::UnsubscribeEvents
+    } else {
+        McpToApp::SubscribeEvents {
+            events: events.clone(),
+        }
+    };
+    if let Err(e) = router.send(&msg).await {
+        return McpToolResult::error(format!("Error: {}", e));
+    }
+
+    if unsubscribe {
+        McpToolResult::text("Unsubscribed from voice events.")
+    } else if events.is_empty() {
+        McpToolResult::text(
+            "Subscribed to all voice events except audio_level and speaking_progress. \
+             They arrive as notifications/voice_event with {event, data} params.",
+        )
+    } else {
+        McpToolResult::text(format!(
+            "Subscribed to voice events: {}. \
+             They arrive as notifications/voice_event with {{event, data}} params.",
+            events.join(", ")
+        ))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// voice_threads
+// ---------------------------------------------------------------------------
+
+/// Handle `voice_threads` -- list the conversation threads, or pick the one
+/// whose replies are spoken (`switch`, `unfocus`, `close`).
+pub async fn handle_voice_threads(args: &Value, threads: &SharedThreads) -> McpToolResult {
+    let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("list");
+    let thread_id = args.get("thread_id").and_then(|v| v.as_str());
+    let Ok(mut threads) = threads.lock() else {
+        return McpToolResult::error("Error: thread state is unavailable");
+    };
+
+    let changed = match (action, thread_id) {
+        ("list", _) => Ok(None),
+        ("unfocus", _) => {
+            threads.unfocus();
+            Ok(Some("No thread is focused; replies in every open thread are spoken.".to_string()))
+        }
+        ("switch" | "close", None) => Err(format!("Error: thread_id is required for {}", action)),
+        ("switch", Some(id)) => threads.switch(id).map(|()| {
+            Some(format!("Focused thread [{}]; only its replies are spoken now.", id))
+        }),
+        ("close", Some(id)) => threads
+            .close(id)
+            .map(|()| Some(format!("Closed thread [{}]; its replies are no longer spoken.", id))),
+        (other, _) => Err(format!(
+            "Error: action must be list, switch, unfocus or close (got \"{}\")",
+            other
+        )),
+    };
+    let changed = match changed {
+        Ok(changed) => changed,
+        Err(e) => return McpToolResult::error(e),
+    };
+
+    let list = threads.list();
+    if list.is_empty() {
+        return McpToolResult::text(
+            changed.unwrap_or_else(|| "No conversation threads yet.".to_string()),
+        );
+    }
+    let now = now_ms();
+    let mut lines: Vec<String> = changed.into_iter().collect();
+    lines.push(match threads.focused() {
+        Some(focused) => format!("Focused thread: {}", focused),
+        None => "No focused thread (every open thread is spoken).".to_string(),
+    });
+    lines.push(String::new());
+    for thread in list {
+        let state = if thread.closed {
+            "closed"
+        } else if threads.should_speak(&thread.id) {
+            "spoken"
+        } else {
+            "silent"
+        };
+        lines.push(format!(
+            "[{}] {} -- {} message(s), last {}s ago, with {}\n  \"{}\"",
+            thread.id,
+            state,
+            thread.message_count,
+            now.saturating_sub(thread.last_activity_ms) / 1000,
+            thread.participants.iter().cloned().collect::<Vec<_>>().join(", "),
+            thread.last_message,
+        ));
+    }
+    McpToolResult::text(lines.join("\n"))
+}
+
+// ---------------------------------------------------------------------------
+// voice_ask
+// ---------------------------------------------------------------------------
+
+/// Default wait for a spoken answer.
+const VOICE_ASK_DEFAULT_TIMEOUT_SECS: u64 = 60;
+/// Longest wait; matches `voice::ask::MAX_TIMEOUT` in the app.
+const VOICE_ASK_MAX_TIMEOUT_SECS: u64 = 300;
+/// Extra wait for the app's own timeout reply to arrive.
+const VOICE_ASK_SLACK: Duration = Duration::from_secs(5);
+
+/// A question opened in the app by `voice_ask`. Unless its answer arrived
+/// (`done`), dropping it sends `AskCancel`: on timeout, and when the tool
+/// call is cancelled and its task dropped. The app would otherwise keep the
+/// question open and take the user's next utterance as its answer.
+struct OpenAsk {
+    router: Arc<PipeRouter>,
+    request_id: String,
+    done: bool,
+}
+
+impl Drop for OpenAsk {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let router = Arc::clone(&self.router);
+        let request_id = std::mem::take(&mut self.request_id);
+        tokio::spawn(async move { router.cancel_ask(&request_id).await });
+    }
+}
+
+/// Handle `voice_ask` -- speak a question to the user and return what they
+/// say back. The app records the answer by itself once the question has
+/// been spoken.
+pub async fn handle_voice_ask(args: &Value, router: Option<&Arc<PipeRouter>>) -> McpToolResult {
+    let question = match args.get("question").and_then(|v| v.as_str()) {
+        Some(q) if !q.trim().is_empty() => q.trim(),
+        _ => return McpToolResult::error("Error: question is required"),
+    };
+    let Some(router) = router else {
+        return McpToolResult::error(
+            "Error: voice_ask needs the pipe connection to Voice Mirror, which is not available",
+        );
+    };
+    let timeout_secs = args
+        .get("timeout_seconds")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(VOICE_ASK_DEFAULT_TIMEOUT_SECS)
+        .clamp(1, VOICE_ASK_MAX_TIMEOUT_SECS);
+
+    let request_id = router.next_request_id("ask");
+    let rx = router.wait_for_browser_response(&request_id).await;
+    let msg = McpToApp::AskRequest {
+        request_id: request_id.clone(),
+        question: question.to_string(),
+        timeout_secs,
+    };
+    if let Err(e) = router.send(&msg).await {
+        router.remove_waiter(&request_id).await;
+        return McpToolResult::error(format!("Error: {}", e));
+    }
+    let mut open = OpenAsk {
+        router: Arc::clone(router),
+        request_id: request_id.clone(),
+        done: false,
+    };
+
+    let wait = Duration::from_secs(timeout_secs) + VOICE_ASK_SLACK;
+    let (outcome, text) = match tokio::time::timeout(wait, rx).await {
+        Ok(Ok(AppToMcp::AskResponse { outcome, text, .. })) => {
+            open.done = true;
+            (outcome, text)
+        }
+        // `open` cancels the question when dropped
+        _ => ("timeout".to_string(), None),
+    };
+    info!("[voice_ask] {} -> {}", request_id, outcome);
+    format_ask_outcome(&outcome, text.as_deref(), timeout_secs)
+}
+
+fn format_ask_outcome(outcome: &str, text: Option<&str>, timeout_secs: u64) -> McpToolResult {
+    match (outcome, text) {
+        ("answered", Some(answer)) => McpToolResult::text(format!("User answered: \"{}\"", answer)),
+        ("unavailable", reason) => McpToolResult::error(format!(
+            "Error: couldn't ask by voice ({})",
+            reason.unwrap_or("the voice engine is unavailable")
+        )),
+        _ => McpToolResult::text(format!("No answer within {}s.", timeout_secs)),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// voice_talk_time
+// ---------------------------------------------------------------------------
+
+/// Handle `voice_talk_time` -- the user's voice activity, summed over the
+/// last `days` days. Recorded by the app in `{data_dir}/voice_stats.json`.
+pub async fn handle_voice_talk_time(args: &Value, data_dir: &Path) -> McpToolResult {
+    let days = args
+        .get("days")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(1)
+        .clamp(1, 90) as u32;
+
+    let stats = voice_stats::load(data_dir);
+    let today = voice_stats::today();
+    let (total, period) = if days == 1 {
+        (stats.day(&today), "today".to_string())
+    } else {
+        let recent = stats.recent(&today, days);
+        (
+            voice_stats::total(&recent, &today),
+            format!("over the last {} days", days),
+        )
+    };
+    McpToolResult::text(voice_stats::describe(&total, &period))
+}
+
 // ---------------------------------------------------------------------------
 // Utility functions
 // ---------------------------------------------------------------------------
@@ -1191,11 +1445,44 @@ mod tests {
         let args = serde_json::json!({});
         let data_dir = std::env::temp_dir().join("mcp_test_send");
         let _ = tokio::fs::create_dir_all(&data_dir).await;
-        let result = handle_voice_send(&args, &data_dir, None).await;
+        let result = handle_voice_send(&args, &data_dir, None, &SharedThreads::default()).await;
         assert!(result.is_error);
         let _ = tokio::fs::remove_dir_all(&data_dir).await;
     }
 
+    #[tokio::test]
+    async fn test_handle_voice_threads() {
+        let threads = SharedThreads::default();
+        threads.lock().unwrap().record("a", "voice-claude", "hi", now_ms());
+
+        let result = handle_voice_threads(&serde_json::json!({}), &threads).await;
+        assert!(!result.is_error);
+
+        let args = serde_json::json!({ "action": "switch", "thread_id": "a" });
+        assert!(!handle_voice_threads(&args, &threads).await.is_error);
+        assert_eq!(threads.lock().unwrap().focused(), Some("a"));
+
+        let args = serde_json::json!({ "action": "close" });
+        assert!(handle_voice_threads(&args, &threads).await.is_error);
+        let args = serde_json::json!({ "action": "switch", "thread_id": "missing" });
+        assert!(handle_voice_threads(&args, &threads).await.is_error);
+    }
+
+    #[tokio::test]
+    async fn test_handle_voice_ask_needs_question_and_pipe() {
+        let result = handle_voice_ask(&serde_json::json!({ "question": " " }), None).await;
+        assert!(result.is_error);
+        let result = handle_voice_ask(&serde_json::json!({ "question": "Ready?" }), None).await;
+        assert!(result.is_error);
+    }
+
+    #[test]
+    fn test_format_ask_outcome() {
+        assert!(!format_ask_outcome("answered", Some("blue"), 60).is_error);
+        assert!(!format_ask_outcome("timeout", None, 60).is_error);
+        assert!(format_ask_outcome("unavailable", Some("quiet hours are on"), 60).is_error);
+    }
+
     #[tokio::test]
     async fn test_handle_voice_status_update() {
         let data_dir = std::env::temp_dir().join("mcp_test_status");
@@ -1221,4 +1508,23 @@ mod tests {
 
         let _ = tokio::fs::remove_dir_all(&data_dir).await;
     }
+
+    #[tokio::test]
+    async fn test_voice_status_keeps_registered_voice() {
+        let data_dir = std::env::temp_dir().join("mcp_test_status_voice");
+        let _ = tokio::fs::create_dir_all(&data_dir).await;
+
+        let args = serde_json::json!({ "instance_id": "coder-claude", "voice": "am_adam" });
+        assert!(!handle_voice_status(&args, &data_dir).await.is_error);
+        // A heartbeat without a voice keeps it
+        update_heartbeat(&data_dir, "coder-claude", "active", Some("Sending message")).await;
+        let store = instances::load(&data_dir);
+        assert_eq!(store.statuses[0].voice.as_deref(), Some("am_adam"));
+
+        let args = serde_json::json!({ "instance_id": "coder-claude", "voice": "" });
+        assert!(!handle_voice_status(&args, &data_dir).await.is_error);
+        assert_eq!(instances::load(&data_dir).statuses[0].voice, None);
+
+        let _ = tokio::fs::remove_dir_all(&data_dir).await;
+    }
 }
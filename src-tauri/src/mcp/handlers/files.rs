@@ -0,0 +1,460 @@
+//! Sandboxed file tools: read_file, write_file, list_dir, search_files.
+//!
+//! Agents can only reach files under the roots in `files.roots` of the app
+//! config (read from disk; the MCP binary runs out of process). With none
+//! configured the only root is `{data_dir}/notes`, created on first use.
+//! Paths are relative to the first root, or absolute inside any root.
+//!
+//! `..` is rejected outright, and every path is canonicalized (symlinks
+//! resolved) before it is checked against the canonical roots, so links
+//! can't lead outside either. `search_files` doesn't follow links.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::Value;
+use tracing::{info, warn};
+
+use super::McpToolResult;
+use crate::services::system_stats::format_bytes;
+
+/// Default root, under the data dir.
+const NOTES_DIR: &str = "notes";
+/// Largest file `read_file` returns or `search_files` looks inside.
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+const MAX_WRITE_BYTES: usize = 1024 * 1024;
+const MAX_LIST_ENTRIES: usize = 200;
+const MAX_SEARCH_RESULTS: usize = 50;
+const MAX_SEARCH_DEPTH: usize = 8;
+const MAX_LINE_CHARS: usize = 200;
+
+type FileTool = fn(&[PathBuf], &Value) -> Result<String, String>;
+
+/// Run a tool against the configured roots, off the async runtime.
+async fn run(args: &Value, data_dir: &Path, tool: FileTool) -> McpToolResult {
+    let args = args.clone();
+    let data_dir = data_dir.to_path_buf();
+    let result = tokio::task::spawn_blocking(move || {
+        let config_dir = crate::services::platform::get_config_dir();
+        let configured = crate::config::persistence::load_config(&config_dir)
+            .files
+            .roots;
+        tool(&allowed_roots(&configured, &data_dir), &args)
+    })
+    .await;
+    match result {
+        Ok(Ok(text)) => McpToolResult::text(text),
+        Ok(Err(e)) => McpToolResult::error(format!("Error: {}", e)),
+        Err(e) => McpToolResult::error(format!("Error: file task failed: {}", e)),
+    }
+}
+
+/// The canonical roots. Configured roots that are missing or relative are
+/// skipped.
+fn allowed_roots(configured: &[String], data_dir: &Path) -> Vec<PathBuf> {
+    let configured: Vec<&str> = configured
+        .iter()
+        .map(|r| r.trim())
+        .filter(|r| !r.is_empty())
+        .collect();
+    if configured.is_empty() {
+        let notes = data_dir.join(NOTES_DIR);
+        let _ = fs::create_dir_all(&notes);
+        return notes.canonicalize().into_iter().collect();
+    }
+    configured
+        .into_iter()
+        .filter_map(|root| {
+            let path = expand_home(root)?;
+            match path.canonicalize() {
+                Ok(p) if path.is_absolute() && p.is_dir() => Some(p),
+                _ => {
+                    warn!("[files] Skipping root {} (missing or not absolute)", root);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// `~/notes` -> `{home}/notes`.
+fn expand_home(path: &str) -> Option<PathBuf> {
+    match path.strip_prefix('~') {
+        Some("") => dirs::home_dir(),
+        Some(rest) if rest.starts_with(['/', '\\']) => Some(dirs::home_dir()?.join(&rest[1..])),
+        _ => Some(PathBuf::from(path)),
+    }
+}
+
+/// Resolve `path` to a location inside one of `roots`. The target may not
+/// exist yet (`write_file`): its nearest existing ancestor is canonicalized
+/// and the missing rest appended.
+fn resolve(roots: &[PathBuf], path: &str) -> Result<PathBuf, String> {
+    let first = roots
+        .first()
+        .ok_or("no file roots are available (check files.roots in the Voice Mirror config)")?;
+    let requested = Path::new(path.trim());
+    if requested.components().any(|c| c == Component::ParentDir) {
+        return Err(format!("\"..\" is not allowed in paths ({})", path));
+    }
+    let joined = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        first.join(requested)
+    };
+
+    let invalid = || format!("invalid path: {}", path);
+    let mut existing = joined.as_path();
+    let mut missing = Vec::new();
+    while fs::symlink_metadata(existing).is_err() {
+        missing.push(existing.file_name().ok_or_else(invalid)?);
+        existing = existing.parent().ok_or_else(invalid)?;
+    }
+    let mut resolved = existing
+        .canonicalize()
+        .map_err(|e| format!("can't resolve {}: {}", path, e))?;
+    resolved.extend(missing.iter().rev());
+
+    if roots.iter().any(|root| resolved.starts_with(root)) {
+        Ok(resolved)
+    } else {
+        warn!("[files] Path traversal blocked: {}", resolved.display());
+        Err(format!(
+            "{} is outside the folders the file tools may use",
+            path
+        ))
+    }
+}
+
+/// `path` as shown to the agent: relative to the first root when inside it.
+fn display(roots: &[PathBuf], path: &Path) -> String {
+    roots
+        .first()
+        .and_then(|root| path.strip_prefix(root).ok())
+        .filter(|rel| !rel.as_os_str().is_empty())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key).and_then(|v| v.as_str())
+}
+
+fn read_file(roots: &[PathBuf], args: &Value) -> Result<String, String> {
+    let path = str_arg(args, "path").ok_or("path is required")?;
+    let target = resolve(roots, path)?;
+    let meta = fs::metadata(&target).map_err(|e| format!("can't read {}: {}", path, e))?;
+    if meta.is_dir() {
+        return Err(format!("{} is a folder (use list_dir)", path));
+    }
+    if meta.len() > MAX_READ_BYTES {
+        return Err(format!(
+            "{} is too large ({}, max {})",
+            path,
+            format_bytes(meta.len()),
+            format_bytes(MAX_READ_BYTES)
+        ));
+    }
+    let bytes = fs::read(&target).map_err(|e| format!("can't read {}: {}", path, e))?;
+    let text = String::from_utf8(bytes).map_err(|_| format!("{} is not a text file", path))?;
+    Ok(format!(
+        "{} ({}):\n\n{}",
+        display(roots, &target),
+        format_bytes(meta.len()),
+        text
+    ))
+}
+
+fn write_file(roots: &[PathBuf], args: &Value) -> Result<String, String> {
+    let path = str_arg(args, "path").ok_or("path is required")?;
+    let content = str_arg(args, "content").ok_or("content is required")?;
+    let mode = str_arg(args, "mode").unwrap_or("overwrite");
+    if !matches!(mode, "overwrite" | "append" | "create") {
+        return Err(format!(
+            "mode must be overwrite, append or create (got \"{}\")",
+            mode
+        ));
+    }
+    if content.len() > MAX_WRITE_BYTES {
+        return Err(format!(
+            "content is too large ({}, max {})",
+            format_bytes(content.len() as u64),
+            format_bytes(MAX_WRITE_BYTES as u64)
+        ));
+    }
+    let target = resolve(roots, path)?;
+    if target.is_dir() {
+        return Err(format!("{} is a folder", path));
+    }
+    let existed = target.exists();
+    if existed && mode == "create" {
+        return Err(format!(
+            "{} already exists (use mode overwrite or append)",
+            path
+        ));
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("can't create folders: {}", e))?;
+    }
+
+    let written = if mode == "append" {
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&target)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+    } else {
+        // Write to a temp file, then rename over the target
+        let name = target.file_name().unwrap_or_default().to_string_lossy();
+        let tmp = target.with_file_name(format!(".{}.tmp", name));
+        fs::write(&tmp, content)
+            .and_then(|()| fs::rename(&tmp, &target))
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&tmp);
+            })
+    };
+    written.map_err(|e| format!("can't write {}: {}", path, e))?;
+
+    let shown = display(roots, &target);
+    info!("[files] {} {} ({} bytes)", mode, shown, content.len());
+    let verb = match (mode, existed) {
+        ("append", true) => "Appended to",
+        (_, true) => "Overwrote",
+        _ => "Created",
+    };
+    Ok(format!(
+        "{} {} ({}).",
+        verb,
+        shown,
+        format_bytes(content.len() as u64)
+    ))
+}
+
+fn list_dir(roots: &[PathBuf], args: &Value) -> Result<String, String> {
+    let path = str_arg(args, "path").map(str::trim).unwrap_or("");
+    if path.is_empty() && roots.len() > 1 {
+        let mut lines = vec!["Folders the file tools may use:".to_string()];
+        lines.extend(roots.iter().map(|r| format!("  {}/", r.display())));
+        return Ok(lines.join("\n"));
+    }
+    let dir = resolve(roots, path)?;
+    let read = fs::read_dir(&dir).map_err(|e| format!("can't list {}: {}", path, e))?;
+
+    let mut entries: Vec<(bool, String, u64)> = read
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                return None;
+            }
+            let meta = entry.metadata().ok()?;
+            Some((meta.is_dir(), name, meta.len()))
+        })
+        .collect();
+    // Folders first, then by name
+    entries.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| a.1.to_lowercase().cmp(&b.1.to_lowercase()))
+    });
+
+    let shown = if path.is_empty() {
+        dir.to_string_lossy().replace('\\', "/")
+    } else {
+        display(roots, &dir)
+    };
+    if entries.is_empty() {
+        return Ok(format!("{} is empty.", shown));
+    }
+    let mut lines = vec![format!("{} ({} entries):", shown, entries.len())];
+    for (is_dir, name, size) in entries.iter().take(MAX_LIST_ENTRIES) {
+        lines.push(if *is_dir {
+            format!("  {}/", name)
+        } else {
+            format!("  {} ({})", name, format_bytes(*size))
+        });
+    }
+    if entries.len() > MAX_LIST_ENTRIES {
+        lines.push(format!(
+            "  ... and {} more",
+            entries.len() - MAX_LIST_ENTRIES
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn search_files(roots: &[PathBuf], args: &Value) -> Result<String, String> {
+    let query = str_arg(args, "query").map(str::trim).unwrap_or("");
+    if query.is_empty() {
+        return Err("query is required".into());
+    }
+    let search_contents = args
+        .get("contents")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let bases = match str_arg(args, "path")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        Some(path) => vec![resolve(roots, path)?],
+        None => roots.to_vec(),
+    };
+    let needle = query.to_lowercase();
+
+    let mut results = Vec::new();
+    let mut truncated = false;
+    'walk: for base in &bases {
+        let walker = ignore::WalkBuilder::new(base)
+            .max_depth(Some(MAX_SEARCH_DEPTH))
+            .build();
+        for entry in walker.flatten() {
+            // Links aren't followed, so only real files inside the root
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            let shown = display(roots, entry.path());
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            if name.contains(&needle) {
+                results.push(shown.clone());
+            }
+            if search_contents && entry.metadata().is_ok_and(|m| m.len() <= MAX_READ_BYTES) {
+                let Ok(text) = fs::read_to_string(entry.path()) else {
+                    continue;
+                };
+                for (i, line) in text.lines().enumerate() {
+                    if results.len() >= MAX_SEARCH_RESULTS {
+                        break;
+                    }
+                    if line.to_lowercase().contains(&needle) {
+                        let line: String = line.trim().chars().take(MAX_LINE_CHARS).collect();
+                        results.push(format!("{}:{}: {}", shown, i + 1, line));
+                    }
+                }
+            }
+            if results.len() >= MAX_SEARCH_RESULTS {
+                truncated = true;
+                break 'walk;
+            }
+        }
+    }
+
+    if results.is_empty() {
+        return Ok(format!("Nothing matches \"{}\".", query));
+    }
+    let mut lines = vec![format!("Matches for \"{}\":", query)];
+    lines.extend(results);
+    if truncated {
+        lines.push(format!(
+            "(stopped at {} matches; narrow the query or path)",
+            MAX_SEARCH_RESULTS
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Handle `read_file` -- a text file inside the allowed folders.
+pub async fn handle_read_file(args: &Value, data_dir: &Path) -> McpToolResult {
+    run(args, data_dir, read_file).await
+}
+
+/// Handle `write_file` -- create, overwrite or append to a text file.
+pub async fn handle_write_file(args: &Value, data_dir: &Path) -> McpToolResult {
+    run(args, data_dir, write_file).await
+}
+
+/// Handle `list_dir` -- a folder's entries, or the roots.
+pub async fn handle_list_dir(args: &Value, data_dir: &Path) -> McpToolResult {
+    run(args, data_dir, list_dir).await
+}
+
+/// Handle `search_files` -- file names and lines containing a query.
+pub async fn handle_search_files(args: &Value, data_dir: &Path) -> McpToolResult {
+    run(args, data_dir, search_files).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A fresh data dir whose notes folder is the only root.
+    fn sandbox(name: &str) -> (PathBuf, Vec<PathBuf>) {
+        let data_dir = std::env::temp_dir().join(format!("mcp_test_files_{}", name));
+        let _ = fs::remove_dir_all(&data_dir);
+        fs::create_dir_all(&data_dir).unwrap();
+        let roots = allowed_roots(&[], &data_dir);
+        assert_eq!(roots.len(), 1);
+        (data_dir, roots)
+    }
+
+    #[test]
+    fn test_paths_stay_inside_roots() {
+        let (data_dir, roots) = sandbox("resolve");
+        fs::write(data_dir.join("secret.txt"), "outside").unwrap();
+
+        assert!(resolve(&roots, "../secret.txt").is_err());
+        assert!(resolve(&roots, "ideas/../../secret.txt").is_err());
+        let outside = data_dir.join("secret.txt");
+        assert!(resolve(&roots, &outside.to_string_lossy()).is_err());
+
+        let new_file = resolve(&roots, "ideas/2026/plan.md").unwrap();
+        assert!(new_file.starts_with(&roots[0]));
+        assert_eq!(resolve(&roots, "").unwrap(), roots[0]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_cannot_escape() {
+        let (data_dir, roots) = sandbox("symlink");
+        fs::write(data_dir.join("secret.txt"), "outside").unwrap();
+        std::os::unix::fs::symlink(&data_dir, roots[0].join("escape")).unwrap();
+        std::os::unix::fs::symlink(data_dir.join("missing.txt"), roots[0].join("dangling"))
+            .unwrap();
+
+        assert!(resolve(&roots, "escape/secret.txt").is_err());
+        assert!(write_file(&roots, &json!({ "path": "escape/new.txt", "content": "x" })).is_err());
+        assert!(write_file(&roots, &json!({ "path": "dangling", "content": "x" })).is_err());
+        assert!(!data_dir.join("missing.txt").exists());
+    }
+
+    #[test]
+    fn test_write_read_append() {
+        let (_, roots) = sandbox("write");
+        let create = json!({ "path": "todo/today.md", "content": "milk\n", "mode": "create" });
+        assert_eq!(
+            write_file(&roots, &create).unwrap(),
+            "Created todo/today.md (5 B)."
+        );
+        assert!(write_file(&roots, &create).is_err());
+
+        let append = json!({ "path": "todo/today.md", "content": "eggs\n", "mode": "append" });
+        write_file(&roots, &append).unwrap();
+        let text = read_file(&roots, &json!({ "path": "todo/today.md" })).unwrap();
+        assert!(text.ends_with("milk\neggs\n"));
+
+        let bad_mode = json!({ "path": "a.md", "content": "x", "mode": "delete" });
+        assert!(write_file(&roots, &bad_mode).is_err());
+        assert!(read_file(&roots, &json!({ "path": "todo" })).is_err());
+    }
+
+    #[test]
+    fn test_list_and_search() {
+        let (_, roots) = sandbox("search");
+        fs::create_dir_all(roots[0].join("projects")).unwrap();
+        fs::write(roots[0].join("groceries.md"), "Buy oat milk\nbread").unwrap();
+        fs::write(roots[0].join("projects/milkshake.txt"), "banana").unwrap();
+
+        let listing = list_dir(&roots, &json!({})).unwrap();
+        assert!(listing.contains("  projects/\n  groceries.md"));
+
+        let found = search_files(&roots, &json!({ "query": "MILK" })).unwrap();
+        assert!(found.contains("groceries.md:1: Buy oat milk"));
+        assert!(found.contains("projects/milkshake.txt"));
+
+        let names_only =
+            search_files(&roots, &json!({ "query": "bread", "contents": false })).unwrap();
+        assert!(names_only.starts_with("Nothing matches"));
+        assert!(search_files(&roots, &json!({ "query": "x", "path": "../" })).is_err());
+    }
+}
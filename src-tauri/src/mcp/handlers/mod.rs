@@ -1,18 +1,28 @@
 //! MCP tool handler implementations.
 //!
 //! Each module implements a group of related tools:
-//! - `core`        -- Voice I/O tools (voice_send, voice_inbox, voice_listen, voice_status)
+//! - `core`        -- Voice I/O tools (voice_send, voice_inbox, voice_listen, voice_status, voice_talk_time, voice_subscribe, voice_threads, voice_ask)
 //! - `memory`      -- Memory system (search, remember, forget, get, stats, flush)
-//! - `browser`     -- Browser control (1 unified tool, pipe IPC)
+//! - `browser`     -- Browser control (browser_action + browser_profile, pipe IPC)
+//! - `web_search`  -- Search backends behind browser_action `search`
 //! - `capture`     -- Window capture and screenshots (2 tools, pipe IPC)
 //! - `n8n`         -- n8n REST API integration (22 tools)
+//! - `network`     -- LAN tools (Wake-on-LAN, ping, port check, mDNS discovery)
+//! - `system`      -- System monitoring (CPU, memory, disks, processes, battery, throughput)
+//! - `voice_output` -- Speech channel (speak, stop_speaking, list_voices, set_voice, pipe IPC)
+//! - `files`       -- Sandboxed notes folders (read_file, write_file, list_dir, search_files)
 
 pub mod core;
 pub mod memory;
 pub mod browser;
+pub mod web_search;
 pub mod capture;
 pub mod sandbox;
 pub mod n8n;
+pub mod network;
+pub mod system;
+pub mod voice_output;
+pub mod files;
 
 use serde::{Deserialize, Serialize};
 
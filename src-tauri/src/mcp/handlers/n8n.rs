@@ -8,9 +8,13 @@
 //! Key patterns:
 //! - Node type formats differ: `nodes-base.*` (search) vs `n8n-nodes-base.*` (workflows)
 //! - Connections use node NAMES not IDs
-//! - n8n API runs at `http://localhost:5678`
-//! - API key from `~/.config/n8n/api_key` or `N8N_API_KEY` env var
+//! - Every API tool takes an optional `instance` naming a server from
+//!   `~/.config/n8n/instances.json`; without it the `default` instance
+//!   (`http://localhost:5678`) is used
+//! - Default API key from `~/.config/n8n/api_key` or `N8N_API_KEY` env var
+//! - List endpoints are paginated via `nextCursor`; transient failures are retried
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
@@ -18,7 +22,7 @@ use std::time::{Duration, Instant};
 
 use std::sync::LazyLock;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::warn;
 
@@ -28,13 +32,41 @@ use super::McpToolResult;
 // Configuration
 // ============================================
 
-const N8N_API_URL: &str = "http://localhost:5678";
+const DEFAULT_INSTANCE: &str = "default";
+const DEFAULT_N8N_API_URL: &str = "http://localhost:5678";
 const API_KEY_CACHE_TTL_SECS: u64 = 300; // 5 minutes
 
+/// Retries after the first attempt for transient failures.
+const MAX_RETRIES: u32 = 3;
+const RETRY_BASE_DELAY_MS: u64 = 500;
+/// Upper bound on a server-requested `Retry-After` wait.
+const MAX_RETRY_AFTER_SECS: u64 = 10;
+
+/// Page size requested from list endpoints (n8n caps this at 250).
+const PAGE_LIMIT: usize = 100;
+/// Safety stop so a misbehaving cursor can't loop forever.
+const MAX_PAGES: usize = 50;
+
 /// Cached API key with TTL.
 static API_KEY_CACHE: LazyLock<Mutex<(Option<String>, Instant)>> =
     LazyLock::new(|| Mutex::new((None, Instant::now())));
 
+/// A named n8n server the tools can target.
+#[derive(Debug, Clone, PartialEq)]
+struct N8nInstance {
+    name: String,
+    url: String,
+    api_key: Option<String>,
+}
+
+/// One entry in `instances.json`.
+#[derive(Debug, Deserialize)]
+struct InstanceEntry {
+    url: String,
+    #[serde(default, rename = "apiKey")]
+    api_key: Option<String>,
+}
+
 /// Get the n8n API key file path.
 fn api_key_file_path() -> PathBuf {
     dirs::home_dir()
@@ -44,6 +76,11 @@ fn api_key_file_path() -> PathBuf {
         .join("api_key")
 }
 
+/// Get the named-instances file path.
+fn instances_file_path() -> PathBuf {
+    api_key_file_path().with_file_name("instances.json")
+}
+
 /// Get the n8n API key from file or environment variable, with caching.
 fn get_api_key() -> Option<String> {
     let mut cache = API_KEY_CACHE.lock().unwrap_or_else(|e| e.into_inner());
@@ -75,64 +112,255 @@ fn get_api_key() -> Option<String> {
     None
 }
 
+/// Parse `instances.json`: `{ "work": { "url": "https://...", "apiKey": "..." } }`.
+fn parse_instances(content: &str) -> Result<Vec<N8nInstance>, String> {
+    let entries: BTreeMap<String, InstanceEntry> =
+        serde_json::from_str(content).map_err(|e| format!("Invalid instances.json: {}", e))?;
+    Ok(entries
+        .into_iter()
+        .map(|(name, entry)| N8nInstance {
+            name,
+            url: entry.url.trim().trim_end_matches('/').to_string(),
+            api_key: entry.api_key.map(|k| k.trim().to_string()).filter(|k| !k.is_empty()),
+        })
+        .collect())
+}
+
+/// Load configured instances. A missing file means none; a broken one is logged.
+fn load_instances() -> Vec<N8nInstance> {
+    let content = match fs::read_to_string(instances_file_path()) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+    parse_instances(&content).unwrap_or_else(|e| {
+        warn!("[n8n] {}", e);
+        Vec::new()
+    })
+}
+
+/// Pick the instance named by the `instance` argument from a list of configured ones.
+///
+/// `default` always exists: it points at localhost unless overridden in the
+/// file, and falls back to the legacy API key sources when no key is given.
+fn select_instance(args: &Value, configured: Vec<N8nInstance>) -> Result<N8nInstance, String> {
+    let name = args
+        .get("instance")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(DEFAULT_INSTANCE);
+
+    let names: Vec<String> = configured.iter().map(|i| i.name.clone()).collect();
+    let found = configured.into_iter().find(|i| i.name == name);
+
+    match found {
+        Some(mut inst) => {
+            if inst.name == DEFAULT_INSTANCE && inst.api_key.is_none() {
+                inst.api_key = get_api_key();
+            }
+            Ok(inst)
+        }
+        None if name == DEFAULT_INSTANCE => Ok(N8nInstance {
+            name: DEFAULT_INSTANCE.into(),
+            url: DEFAULT_N8N_API_URL.into(),
+            api_key: get_api_key(),
+        }),
+        None => {
+            let mut known = vec![DEFAULT_INSTANCE.to_string()];
+            known.extend(names.into_iter().filter(|n| n != DEFAULT_INSTANCE));
+            Err(format!(
+                "Unknown n8n instance '{}'. Available: {}",
+                name,
+                known.join(", ")
+            ))
+        }
+    }
+}
+
+/// Resolve the instance a tool call targets.
+fn resolve_instance(args: &Value) -> Result<N8nInstance, String> {
+    select_instance(args, load_instances())
+}
+
 // ============================================
 // HTTP Client
 // ============================================
 
-/// Make an API request to the n8n REST API.
-async fn api_request(endpoint: &str, method: &str, body: Option<Value>) -> Result<Value, String> {
-    let api_key = get_api_key()
-        .ok_or_else(|| "n8n API key not configured. Set in ~/.config/n8n/api_key or N8N_API_KEY env var.".to_string())?;
+/// Whether a failed attempt is worth repeating.
+///
+/// Connection failures never reached the server and 429 means it refused the
+/// request, so both are safe for any method. Timeouts and gateway errors may
+/// have been applied already, so only idempotent methods retry on those.
+fn is_retryable(method: &str, status: Option<u16>, connect_failed: bool) -> bool {
+    if connect_failed || status == Some(429) {
+        return true;
+    }
+    let idempotent = matches!(method, "GET" | "PUT" | "DELETE");
+    match status {
+        Some(code) => idempotent && matches!(code, 500 | 502 | 503 | 504),
+        // No status and not a connect error: timed out
+        None => idempotent,
+    }
+}
 
-    let url = format!("{}/api/v1{}", N8N_API_URL, endpoint);
+/// Exponential backoff for the given retry (0-based).
+fn retry_delay(retry: u32) -> Duration {
+    Duration::from_millis(RETRY_BASE_DELAY_MS << retry.min(4))
+}
+
+/// Make an API request to an n8n instance's REST API, retrying transient failures.
+async fn api_request(
+    inst: &N8nInstance,
+    endpoint: &str,
+    method: &str,
+    body: Option<Value>,
+) -> Result<Value, String> {
+    let api_key = inst.api_key.clone().ok_or_else(|| {
+        if inst.name == DEFAULT_INSTANCE {
+            "n8n API key not configured. Set in ~/.config/n8n/api_key or N8N_API_KEY env var.".to_string()
+        } else {
+            format!("n8n instance '{}' has no apiKey in ~/.config/n8n/instances.json", inst.name)
+        }
+    })?;
+
+    let url = format!("{}/api/v1{}", inst.url, endpoint);
 
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(30))
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
-    let mut req_builder = match method {
-        "POST" => client.post(&url),
-        "PUT" => client.put(&url),
-        "DELETE" => client.delete(&url),
-        "PATCH" => client.patch(&url),
-        _ => client.get(&url),
-    };
+    let mut retry = 0;
+    loop {
+        let mut req_builder = match method {
+            "POST" => client.post(&url),
+            "PUT" => client.put(&url),
+            "DELETE" => client.delete(&url),
+            "PATCH" => client.patch(&url),
+            _ => client.get(&url),
+        };
 
-    req_builder = req_builder
-        .header("X-N8N-API-KEY", &api_key)
-        .header("Content-Type", "application/json");
+        req_builder = req_builder
+            .header("X-N8N-API-KEY", &api_key)
+            .header("Content-Type", "application/json");
 
-    if let Some(data) = body {
-        req_builder = req_builder.json(&data);
-    }
+        if let Some(data) = &body {
+            req_builder = req_builder.json(data);
+        }
 
-    let response = req_builder
-        .send()
-        .await
-        .map_err(|e| {
-            if e.is_connect() {
-                "Cannot connect to n8n. Is it running?".to_string()
-            } else if e.is_timeout() {
-                "Request timed out".to_string()
-            } else {
-                format!("HTTP request failed: {}", e)
+        let response = match req_builder.send().await {
+            Ok(r) => r,
+            Err(e) => {
+                let transient = e.is_connect() || e.is_timeout();
+                if transient && retry < MAX_RETRIES && is_retryable(method, None, e.is_connect()) {
+                    tokio::time::sleep(retry_delay(retry)).await;
+                    retry += 1;
+                    continue;
+                }
+                return Err(if e.is_connect() {
+                    format!("Cannot connect to n8n instance '{}' at {}. Is it running?", inst.name, inst.url)
+                } else if e.is_timeout() {
+                    "Request timed out".to_string()
+                } else {
+                    format!("HTTP request failed: {}", e)
+                });
             }
-        })?;
+        };
 
-    let status = response.status();
+        let status = response.status();
 
-    if status.is_success() {
-        let text = response.text().await.map_err(|e| format!("Failed to read response: {}", e))?;
-        if text.is_empty() {
-            Ok(Value::Null)
-        } else {
-            serde_json::from_str(&text).map_err(|_| text)
+        if status.is_success() {
+            let text = response.text().await.map_err(|e| format!("Failed to read response: {}", e))?;
+            return if text.is_empty() {
+                Ok(Value::Null)
+            } else {
+                serde_json::from_str(&text).map_err(|_| text)
+            };
         }
-    } else {
+
+        if retry < MAX_RETRIES && is_retryable(method, Some(status.as_u16()), false) {
+            let delay = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(|secs| Duration::from_secs(secs.min(MAX_RETRY_AFTER_SECS)))
+                .unwrap_or_else(|| retry_delay(retry));
+            warn!(
+                "[n8n] {} {} returned {}, retrying in {:?}",
+                method, endpoint, status.as_u16(), delay
+            );
+            tokio::time::sleep(delay).await;
+            retry += 1;
+            continue;
+        }
+
         let body_text = response.text().await.unwrap_or_default();
-        Err(format!("API error: {} - {}", status.as_u16(), body_text))
+        return Err(format!("API error: {} - {}", status.as_u16(), body_text));
+    }
+}
+
+/// Build the URL for one page of a list endpoint.
+fn page_endpoint(endpoint: &str, limit: usize, cursor: Option<&str>) -> String {
+    let sep = if endpoint.contains('?') { '&' } else { '?' };
+    let mut out = format!("{}{}limit={}", endpoint, sep, limit);
+    if let Some(c) = cursor {
+        out.push_str("&cursor=");
+        out.extend(url::form_urlencoded::byte_serialize(c.as_bytes()));
     }
+    out
+}
+
+/// Split a list response into its items and the cursor for the next page.
+///
+/// Paginated endpoints return `{ data, nextCursor }`; some older ones
+/// (tags) return a bare array.
+fn split_page(result: Value) -> (Vec<Value>, Option<String>) {
+    if let Value::Array(items) = result {
+        return (items, None);
+    }
+    let next = result
+        .get("nextCursor")
+        .and_then(|c| c.as_str())
+        .filter(|c| !c.is_empty())
+        .map(|c| c.to_string());
+    let items = result
+        .get("data")
+        .and_then(|d| d.as_array())
+        .cloned()
+        .unwrap_or_default();
+    (items, next)
+}
+
+/// GET every page of a list endpoint, stopping early once `max_items` are collected.
+async fn api_list(
+    inst: &N8nInstance,
+    endpoint: &str,
+    max_items: Option<usize>,
+) -> Result<Vec<Value>, String> {
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    for _ in 0..MAX_PAGES {
+        let want = max_items.map_or(PAGE_LIMIT, |m| (m - items.len()).min(PAGE_LIMIT));
+        let result = api_request(inst, &page_endpoint(endpoint, want, cursor.as_deref()), "GET", None).await?;
+        let (page, next) = split_page(result);
+        items.extend(page);
+
+        if let Some(max) = max_items {
+            if items.len() >= max {
+                items.truncate(max);
+                return Ok(items);
+            }
+        }
+        match next {
+            Some(c) => cursor = Some(c),
+            None => return Ok(items),
+        }
+    }
+
+    warn!("[n8n] {} exceeded {} pages, returning partial list", endpoint, MAX_PAGES);
+    Ok(items)
 }
 
 /// Make a raw HTTP request (for webhooks / external URLs).
@@ -376,20 +604,18 @@ pub async fn handle_n8n_get_node(args: &Value, _data_dir: &Path) -> McpToolResul
 // ============================================
 
 pub async fn handle_n8n_list_workflows(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let active_only = args_val
         .get("active_only")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
-    match api_request("/workflows", "GET", None).await {
-        Ok(result) => {
-            let workflows = result
-                .get("data")
-                .and_then(|d| d.as_array())
-                .cloned()
-                .unwrap_or_default();
-
+    match api_list(&inst, "/workflows", None).await {
+        Ok(workflows) => {
             let filtered: Vec<Value> = workflows
                 .into_iter()
                 .filter(|w| {
@@ -421,6 +647,10 @@ pub async fn handle_n8n_list_workflows(args: &Value, _data_dir: &Path) -> McpToo
 }
 
 pub async fn handle_n8n_get_workflow(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let workflow_id = match args_val.get("workflow_id") {
         Some(v) => v.to_string().trim_matches('"').to_string(),
@@ -430,7 +660,7 @@ pub async fn handle_n8n_get_workflow(args: &Value, _data_dir: &Path) -> McpToolR
         return err_result("workflow_id required");
     }
 
-    match api_request(&format!("/workflows/{}", workflow_id), "GET", None).await {
+    match api_request(&inst, &format!("/workflows/{}", workflow_id), "GET", None).await {
         Ok(result) => {
             ok_result(json!({
                 "success": true,
@@ -455,6 +685,10 @@ pub async fn handle_n8n_get_workflow(args: &Value, _data_dir: &Path) -> McpToolR
 }
 
 pub async fn handle_n8n_create_workflow(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
 
     let name = match args_val.get("name").and_then(|v| v.as_str()) {
@@ -476,7 +710,7 @@ pub async fn handle_n8n_create_workflow(args: &Value, _data_dir: &Path) -> McpTo
         "settings": { "executionOrder": "v1" },
     });
 
-    match api_request("/workflows", "POST", Some(body)).await {
+    match api_request(&inst, "/workflows", "POST", Some(body)).await {
         Ok(result) => {
             ok_result(json!({
                 "success": true,
@@ -490,6 +724,10 @@ pub async fn handle_n8n_create_workflow(args: &Value, _data_dir: &Path) -> McpTo
 }
 
 pub async fn handle_n8n_update_workflow(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
 
     let workflow_id = match extract_string_or_number(&args_val, "workflow_id") {
@@ -500,7 +738,7 @@ pub async fn handle_n8n_update_workflow(args: &Value, _data_dir: &Path) -> McpTo
     // Mode 1: Full workflow update
     if let Some(workflow_data) = args_val.get("workflow_data") {
         // Fetch existing workflow first
-        let existing = match api_request(&format!("/workflows/{}", workflow_id), "GET", None).await {
+        let existing = match api_request(&inst, &format!("/workflows/{}", workflow_id), "GET", None).await {
             Ok(e) => e,
             Err(e) => return err_result(&format!("Cannot fetch workflow: {}", e)),
         };
@@ -512,7 +750,7 @@ pub async fn handle_n8n_update_workflow(args: &Value, _data_dir: &Path) -> McpTo
             "settings": workflow_data.get("settings").or_else(|| existing.get("settings")).unwrap_or(&json!({})),
         });
 
-        return match api_request(&format!("/workflows/{}", workflow_id), "PUT", Some(body)).await {
+        return match api_request(&inst, &format!("/workflows/{}", workflow_id), "PUT", Some(body)).await {
             Ok(result) => {
                 let node_count = result.get("nodes").and_then(|n| n.as_array()).map(|a| a.len()).unwrap_or(0);
                 ok_result(json!({
@@ -534,7 +772,7 @@ pub async fn handle_n8n_update_workflow(args: &Value, _data_dir: &Path) -> McpTo
     };
 
     // Fetch existing workflow
-    let existing = match api_request(&format!("/workflows/{}", workflow_id), "GET", None).await {
+    let existing = match api_request(&inst, &format!("/workflows/{}", workflow_id), "GET", None).await {
         Ok(e) => e,
         Err(e) => return err_result(&format!("Cannot fetch workflow: {}", e)),
     };
@@ -557,13 +795,13 @@ pub async fn handle_n8n_update_workflow(args: &Value, _data_dir: &Path) -> McpTo
 
         match op_type {
             "activateWorkflow" => {
-                return match api_request(&format!("/workflows/{}/activate", workflow_id), "POST", None).await {
+                return match api_request(&inst, &format!("/workflows/{}/activate", workflow_id), "POST", None).await {
                     Ok(_) => ok_result(json!({ "success": true, "message": "Workflow activated", "active": true })),
                     Err(e) => err_result(&format!("Activation failed: {}", e)),
                 };
             }
             "deactivateWorkflow" => {
-                return match api_request(&format!("/workflows/{}/deactivate", workflow_id), "POST", None).await {
+                return match api_request(&inst, &format!("/workflows/{}/deactivate", workflow_id), "POST", None).await {
                     Ok(_) => ok_result(json!({ "success": true, "message": "Workflow deactivated", "active": false })),
                     Err(e) => err_result(&format!("Deactivation failed: {}", e)),
                 };
@@ -703,7 +941,7 @@ pub async fn handle_n8n_update_workflow(args: &Value, _data_dir: &Path) -> McpTo
             "settings": existing.get("settings").unwrap_or(&json!({})),
         });
 
-        match api_request(&format!("/workflows/{}", workflow_id), "PUT", Some(body)).await {
+        match api_request(&inst, &format!("/workflows/{}", workflow_id), "PUT", Some(body)).await {
             Ok(result) => {
                 let node_count = result.get("nodes").and_then(|n| n.as_array()).map(|a| a.len()).unwrap_or(0);
                 ok_result(json!({
@@ -721,13 +959,17 @@ pub async fn handle_n8n_update_workflow(args: &Value, _data_dir: &Path) -> McpTo
 }
 
 pub async fn handle_n8n_delete_workflow(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let workflow_id = match extract_string_or_number(&args_val, "workflow_id") {
         Some(id) => id,
         None => return err_result("workflow_id required"),
     };
 
-    match api_request(&format!("/workflows/{}", workflow_id), "DELETE", None).await {
+    match api_request(&inst, &format!("/workflows/{}", workflow_id), "DELETE", None).await {
         Ok(result) => {
             ok_result(json!({
                 "success": true,
@@ -749,10 +991,14 @@ pub async fn handle_n8n_delete_workflow(args: &Value, _data_dir: &Path) -> McpTo
 }
 
 pub async fn handle_n8n_validate_workflow(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
 
     let (nodes, connections) = if let Some(id) = extract_string_or_number(&args_val, "workflow_id") {
-        match api_request(&format!("/workflows/{}", id), "GET", None).await {
+        match api_request(&inst, &format!("/workflows/{}", id), "GET", None).await {
             Ok(result) => {
                 let n = result.get("nodes").and_then(|v| v.as_array()).cloned().unwrap_or_default();
                 let c = result.get("connections").cloned().unwrap_or(json!({}));
@@ -822,6 +1068,10 @@ pub async fn handle_n8n_validate_workflow(args: &Value, _data_dir: &Path) -> Mcp
 }
 
 pub async fn handle_n8n_trigger_workflow(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
 
     let workflow_id = extract_string_or_number(&args_val, "workflow_id");
@@ -838,7 +1088,7 @@ pub async fn handle_n8n_trigger_workflow(args: &Value, _data_dir: &Path) -> McpT
             Some(id) => id,
             None => return err_result("workflow_id required when webhook_path is not provided"),
         };
-        match api_request(&format!("/workflows/{}", wf_id), "GET", None).await {
+        match api_request(&inst, &format!("/workflows/{}", wf_id), "GET", None).await {
             Ok(result) => {
                 let nodes = result.get("nodes").and_then(|n| n.as_array()).cloned().unwrap_or_default();
                 let webhook_nodes: Vec<&Value> = nodes
@@ -873,7 +1123,7 @@ pub async fn handle_n8n_trigger_workflow(args: &Value, _data_dir: &Path) -> McpT
         Some(p) => p,
         None => return err_result("No webhook path resolved"),
     };
-    let url = format!("{}/webhook/{}", N8N_API_URL, resolved_path);
+    let url = format!("{}/webhook/{}", inst.url, resolved_path);
 
     match raw_request(&url, "POST", Some(data), 60).await {
         Ok(result) => ok_result(json!({ "success": true, "response": result })),
@@ -888,6 +1138,10 @@ pub async fn handle_n8n_trigger_workflow(args: &Value, _data_dir: &Path) -> McpT
 }
 
 pub async fn handle_n8n_deploy_template(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
 
     let template_id = match extract_string_or_number(&args_val, "template_id") {
@@ -920,6 +1174,7 @@ pub async fn handle_n8n_deploy_template(args: &Value, _data_dir: &Path) -> McpTo
         "name": workflow_name,
         "nodes": workflow_data.get("nodes").unwrap_or(&json!([])),
         "connections": workflow_data.get("connections").unwrap_or(&json!({})),
+        "instance": inst.name,
     });
 
     handle_n8n_create_workflow(&create_args, _data_dir).await
@@ -930,15 +1185,19 @@ pub async fn handle_n8n_deploy_template(args: &Value, _data_dir: &Path) -> McpTo
 // ============================================
 
 pub async fn handle_n8n_get_executions(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
 
     let limit = args_val
         .get("limit")
         .and_then(|v| v.as_u64())
         .unwrap_or(10)
-        .clamp(1, 100);
+        .clamp(1, 1000) as usize;
 
-    let mut params = vec![format!("limit={}", limit)];
+    let mut params = Vec::new();
     if let Some(wf_id) = extract_string_or_number(&args_val, "workflow_id") {
         params.push(format!("workflowId={}", wf_id));
     }
@@ -946,15 +1205,14 @@ pub async fn handle_n8n_get_executions(args: &Value, _data_dir: &Path) -> McpToo
         params.push(format!("status={}", status));
     }
 
-    let endpoint = format!("/executions?{}", params.join("&"));
+    let endpoint = if params.is_empty() {
+        "/executions".to_string()
+    } else {
+        format!("/executions?{}", params.join("&"))
+    };
 
-    match api_request(&endpoint, "GET", None).await {
-        Ok(result) => {
-            let executions = result
-                .get("data")
-                .and_then(|d| d.as_array())
-                .cloned()
-                .unwrap_or_default();
+    match api_list(&inst, &endpoint, Some(limit)).await {
+        Ok(executions) => {
 
             let mapped: Vec<Value> = executions
                 .iter()
@@ -981,6 +1239,10 @@ pub async fn handle_n8n_get_executions(args: &Value, _data_dir: &Path) -> McpToo
 }
 
 pub async fn handle_n8n_get_execution(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let execution_id = match extract_string_or_number(&args_val, "execution_id") {
         Some(id) => id,
@@ -998,7 +1260,7 @@ pub async fn handle_n8n_get_execution(args: &Value, _data_dir: &Path) -> McpTool
         format!("/executions/{}", execution_id)
     };
 
-    match api_request(&endpoint, "GET", None).await {
+    match api_request(&inst, &endpoint, "GET", None).await {
         Ok(result) => {
             let mut execution = json!({
                 "id": result.get("id"),
@@ -1031,13 +1293,17 @@ pub async fn handle_n8n_get_execution(args: &Value, _data_dir: &Path) -> McpTool
 }
 
 pub async fn handle_n8n_delete_execution(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let execution_id = match extract_string_or_number(&args_val, "execution_id") {
         Some(id) => id,
         None => return err_result("execution_id required"),
     };
 
-    match api_request(&format!("/executions/{}", execution_id), "DELETE", None).await {
+    match api_request(&inst, &format!("/executions/{}", execution_id), "DELETE", None).await {
         Ok(_) => ok_result(json!({ "success": true, "message": format!("Execution {} deleted", execution_id) })),
         Err(e) => {
             if e.contains("404") {
@@ -1050,6 +1316,10 @@ pub async fn handle_n8n_delete_execution(args: &Value, _data_dir: &Path) -> McpT
 }
 
 pub async fn handle_n8n_retry_execution(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let execution_id = match extract_string_or_number(&args_val, "execution_id") {
         Some(id) => id,
@@ -1063,7 +1333,7 @@ pub async fn handle_n8n_retry_execution(args: &Value, _data_dir: &Path) -> McpTo
 
     let body = json!({ "loadWorkflow": load_workflow });
 
-    match api_request(&format!("/executions/{}/retry", execution_id), "POST", Some(body)).await {
+    match api_request(&inst, &format!("/executions/{}/retry", execution_id), "POST", Some(body)).await {
         Ok(result) => ok_result(json!({
             "success": true,
             "message": format!("Execution {} retried", execution_id),
@@ -1083,11 +1353,15 @@ pub async fn handle_n8n_retry_execution(args: &Value, _data_dir: &Path) -> McpTo
 // Credentials Management Handlers
 // ============================================
 
-pub async fn handle_n8n_list_credentials(_args: &Value, _data_dir: &Path) -> McpToolResult {
+pub async fn handle_n8n_list_credentials(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     ok_result(json!({
         "success": false,
         "error": "n8n public API does not support listing credentials",
-        "hint": "Use the n8n UI at http://localhost:5678 to view credentials.",
+        "hint": format!("Use the n8n UI at {} to view credentials.", inst.url),
         "available_operations": [
             "n8n_create_credential - Create a new credential",
             "n8n_delete_credential - Delete by ID",
@@ -1097,6 +1371,10 @@ pub async fn handle_n8n_list_credentials(_args: &Value, _data_dir: &Path) -> Mcp
 }
 
 pub async fn handle_n8n_create_credential(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
 
     let name = match args_val.get("name").and_then(|v| v.as_str()) {
@@ -1117,7 +1395,7 @@ pub async fn handle_n8n_create_credential(args: &Value, _data_dir: &Path) -> Mcp
         "data": data,
     });
 
-    match api_request("/credentials", "POST", Some(body)).await {
+    match api_request(&inst, "/credentials", "POST", Some(body)).await {
         Ok(result) => {
             ok_result(json!({
                 "success": true,
@@ -1132,13 +1410,17 @@ pub async fn handle_n8n_create_credential(args: &Value, _data_dir: &Path) -> Mcp
 }
 
 pub async fn handle_n8n_delete_credential(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let credential_id = match extract_string_or_number(&args_val, "credential_id") {
         Some(id) => id,
         None => return err_result("credential_id required"),
     };
 
-    match api_request(&format!("/credentials/{}", credential_id), "DELETE", None).await {
+    match api_request(&inst, &format!("/credentials/{}", credential_id), "DELETE", None).await {
         Ok(_) => ok_result(json!({ "success": true, "message": format!("Credential {} deleted", credential_id) })),
         Err(e) => {
             if e.contains("404") {
@@ -1151,13 +1433,17 @@ pub async fn handle_n8n_delete_credential(args: &Value, _data_dir: &Path) -> Mcp
 }
 
 pub async fn handle_n8n_get_credential_schema(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let credential_type = match args_val.get("credential_type").and_then(|v| v.as_str()) {
         Some(t) => t.to_string(),
         None => return err_result("credential_type required (e.g., 'gmailOAuth2', 'slackApi')"),
     };
 
-    match api_request(&format!("/credentials/schema/{}", credential_type), "GET", None).await {
+    match api_request(&inst, &format!("/credentials/schema/{}", credential_type), "GET", None).await {
         Ok(result) => {
             let required = result.get("required").cloned().unwrap_or(json!([]));
             ok_result(json!({
@@ -1181,19 +1467,13 @@ pub async fn handle_n8n_get_credential_schema(args: &Value, _data_dir: &Path) ->
 // Tags Management Handlers
 // ============================================
 
-pub async fn handle_n8n_list_tags(_args: &Value, _data_dir: &Path) -> McpToolResult {
-    match api_request("/tags", "GET", None).await {
-        Ok(result) => {
-            let tags = if result.is_array() {
-                result.as_array().cloned().unwrap_or_default()
-            } else {
-                result
-                    .get("data")
-                    .and_then(|d| d.as_array())
-                    .cloned()
-                    .unwrap_or_default()
-            };
-
+pub async fn handle_n8n_list_tags(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
+    match api_list(&inst, "/tags", None).await {
+        Ok(tags) => {
             let mapped: Vec<Value> = tags
                 .iter()
                 .map(|t| {
@@ -1217,6 +1497,10 @@ pub async fn handle_n8n_list_tags(_args: &Value, _data_dir: &Path) -> McpToolRes
 }
 
 pub async fn handle_n8n_create_tag(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let name = match args_val.get("name").and_then(|v| v.as_str()) {
         Some(n) => n.to_string(),
@@ -1225,7 +1509,7 @@ pub async fn handle_n8n_create_tag(args: &Value, _data_dir: &Path) -> McpToolRes
 
     let body = json!({ "name": name });
 
-    match api_request("/tags", "POST", Some(body)).await {
+    match api_request(&inst, "/tags", "POST", Some(body)).await {
         Ok(result) => {
             ok_result(json!({
                 "success": true,
@@ -1238,13 +1522,17 @@ pub async fn handle_n8n_create_tag(args: &Value, _data_dir: &Path) -> McpToolRes
 }
 
 pub async fn handle_n8n_delete_tag(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let inst = match resolve_instance(args) {
+        Ok(i) => i,
+        Err(e) => return err_result(&e),
+    };
     let args_val = args.clone();
     let tag_id = match extract_string_or_number(&args_val, "tag_id") {
         Some(id) => id,
         None => return err_result("tag_id required"),
     };
 
-    match api_request(&format!("/tags/{}", tag_id), "DELETE", None).await {
+    match api_request(&inst, &format!("/tags/{}", tag_id), "DELETE", None).await {
         Ok(_) => ok_result(json!({ "success": true, "message": format!("Tag {} deleted", tag_id) })),
         Err(e) => {
             if e.contains("404") {
@@ -1260,7 +1548,10 @@ pub async fn handle_n8n_delete_tag(args: &Value, _data_dir: &Path) -> McpToolRes
 // Variables Handler
 // ============================================
 
-pub async fn handle_n8n_list_variables(_args: &Value, _data_dir: &Path) -> McpToolResult {
+pub async fn handle_n8n_list_variables(args: &Value, _data_dir: &Path) -> McpToolResult {
+    if let Err(e) = resolve_instance(args) {
+        return err_result(&e);
+    }
     ok_result(json!({
         "success": false,
         "error": "Variables require n8n Enterprise license",
@@ -1326,6 +1617,76 @@ mod tests {
         assert_eq!(extract_string_or_number(&val, "id"), None);
     }
 
+    #[test]
+    fn test_parse_instances() {
+        let list = parse_instances(
+            r#"{ "work": { "url": "https://n8n.example.com/", "apiKey": " k1 " }, "lab": { "url": "http://lab:5678" } }"#,
+        )
+        .unwrap();
+        assert_eq!(list.len(), 2);
+        let work = list.iter().find(|i| i.name == "work").unwrap();
+        assert_eq!(work.url, "https://n8n.example.com");
+        assert_eq!(work.api_key.as_deref(), Some("k1"));
+        assert!(list.iter().find(|i| i.name == "lab").unwrap().api_key.is_none());
+        assert!(parse_instances("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_select_instance() {
+        let configured = || {
+            vec![N8nInstance {
+                name: "work".into(),
+                url: "https://n8n.example.com".into(),
+                api_key: Some("k1".into()),
+            }]
+        };
+
+        let default = select_instance(&json!({}), configured()).unwrap();
+        assert_eq!(default.name, DEFAULT_INSTANCE);
+        assert_eq!(default.url, DEFAULT_N8N_API_URL);
+
+        let work = select_instance(&json!({ "instance": "work" }), configured()).unwrap();
+        assert_eq!(work.url, "https://n8n.example.com");
+
+        let err = select_instance(&json!({ "instance": "nope" }), configured()).unwrap_err();
+        assert!(err.contains("default, work"));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable("POST", None, true));
+        assert!(is_retryable("POST", Some(429), false));
+        assert!(is_retryable("GET", Some(503), false));
+        assert!(is_retryable("GET", None, false));
+        assert!(!is_retryable("POST", Some(503), false));
+        assert!(!is_retryable("POST", None, false));
+        assert!(!is_retryable("GET", Some(404), false));
+        assert!(retry_delay(1) > retry_delay(0));
+    }
+
+    #[test]
+    fn test_page_endpoint() {
+        assert_eq!(page_endpoint("/workflows", 100, None), "/workflows?limit=100");
+        assert_eq!(
+            page_endpoint("/executions?status=error", 10, Some("a+b=")),
+            "/executions?status=error&limit=10&cursor=a%2Bb%3D"
+        );
+    }
+
+    #[test]
+    fn test_split_page() {
+        let (items, next) = split_page(json!({ "data": [{ "id": 1 }], "nextCursor": "abc" }));
+        assert_eq!(items.len(), 1);
+        assert_eq!(next.as_deref(), Some("abc"));
+
+        let (_, next) = split_page(json!({ "data": [], "nextCursor": null }));
+        assert!(next.is_none());
+
+        let (items, next) = split_page(json!([{ "id": 1 }, { "id": 2 }]));
+        assert_eq!(items.len(), 2);
+        assert!(next.is_none());
+    }
+
     #[test]
     fn test_common_nodes_not_empty() {
         let nodes = common_nodes();
@@ -0,0 +1,319 @@
+//! Network tools: wake_on_lan, ping, port_check, discover_services.
+//!
+//! Devices can be named ("desktop", "NAS") via `network.devices` in the app
+//! config, or addressed directly by MAC / host. The LAN logic itself lives
+//! in `services::network`; this module parses arguments and formats replies.
+
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::McpToolResult;
+use crate::config::schema::NetworkDevice;
+use crate::services::network;
+
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+const PORT_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long to collect mDNS answers per query.
+const DISCOVERY_WAIT: Duration = Duration::from_millis(1500);
+/// Max service types to expand into instances when no type is given.
+const MAX_TYPES_EXPANDED: usize = 8;
+const MAX_PORTS: usize = 20;
+
+/// Named devices from the app config on disk (the MCP binary runs out of process).
+fn load_devices() -> Vec<NetworkDevice> {
+    let config_dir = crate::services::platform::get_config_dir();
+    crate::config::persistence::load_config(&config_dir)
+        .network
+        .devices
+}
+
+/// Find a device by name: exact (case-insensitive) first, then a device
+/// whose name appears in the query ("my desktop" → "Desktop").
+fn find_device<'a>(devices: &'a [NetworkDevice], query: &str) -> Option<&'a NetworkDevice> {
+    let q = query.trim().to_lowercase();
+    devices
+        .iter()
+        .find(|d| d.name.to_lowercase() == q)
+        .or_else(|| devices.iter().find(|d| q.contains(&d.name.to_lowercase())))
+}
+
+fn unknown_device(devices: &[NetworkDevice], name: &str) -> McpToolResult {
+    if devices.is_empty() {
+        return McpToolResult::error(format!(
+            "Unknown device '{}'. No devices are configured — add them under \
+             network.devices in the Voice Mirror config, or pass a MAC/host directly.",
+            name
+        ));
+    }
+    let names: Vec<&str> = devices.iter().map(|d| d.name.as_str()).collect();
+    McpToolResult::error(format!(
+        "Unknown device '{}'. Known devices: {}",
+        name,
+        names.join(", ")
+    ))
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Resolve `host` or `device` arguments to (label, host).
+fn resolve_host(
+    args: &Value,
+    devices: &[NetworkDevice],
+) -> Result<(String, String), McpToolResult> {
+    if let Some(host) = str_arg(args, "host") {
+        return Ok((host.to_string(), host.to_string()));
+    }
+    let Some(name) = str_arg(args, "device") else {
+        return Err(McpToolResult::error(
+            "Either 'host' or 'device' is required",
+        ));
+    };
+    let device = find_device(devices, name).ok_or_else(|| unknown_device(devices, name))?;
+    match &device.host {
+        Some(host) => Ok((format!("{} ({})", device.name, host), host.clone())),
+        None => Err(McpToolResult::error(format!(
+            "Device '{}' has no host configured",
+            device.name
+        ))),
+    }
+}
+
+pub async fn handle_wake_on_lan(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let devices = load_devices();
+    let (label, mac_str, broadcast) = if let Some(mac) = str_arg(args, "mac") {
+        (
+            mac.to_string(),
+            mac.to_string(),
+            str_arg(args, "broadcast").map(String::from),
+        )
+    } else if let Some(name) = str_arg(args, "device") {
+        let Some(device) = find_device(&devices, name) else {
+            return unknown_device(&devices, name);
+        };
+        let Some(mac) = device.mac.clone() else {
+            return McpToolResult::error(format!(
+                "Device '{}' has no MAC address configured",
+                device.name
+            ));
+        };
+        let broadcast = str_arg(args, "broadcast")
+            .map(String::from)
+            .or_else(|| device.broadcast.clone());
+        (device.name.clone(), mac, broadcast)
+    } else {
+        return McpToolResult::error("Either 'mac' or 'device' is required");
+    };
+
+    let mac = match network::parse_mac(&mac_str) {
+        Ok(mac) => mac,
+        Err(e) => return McpToolResult::error(e),
+    };
+    let broadcast = match broadcast.as_deref().map(str::parse::<Ipv4Addr>) {
+        Some(Ok(addr)) => Some(addr),
+        Some(Err(_)) => return McpToolResult::error("'broadcast' must be an IPv4 address"),
+        None => None,
+    };
+
+    match network::wake_on_lan(&mac, broadcast).await {
+        Ok(()) => McpToolResult::text(format!(
+            "Sent Wake-on-LAN packet to {}. Booting can take up to a minute; \
+             use ping to check when it is online.",
+            label
+        )),
+        Err(e) => McpToolResult::error(e),
+    }
+}
+
+pub async fn handle_ping(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let (label, host) = match resolve_host(args, &load_devices()) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    match network::ping(&host, PING_TIMEOUT).await {
+        Ok(r) if r.reachable => McpToolResult::text(format!(
+            "{} is online ({:.1} ms).",
+            label,
+            r.rtt_ms.unwrap_or_default()
+        )),
+        Ok(_) => McpToolResult::text(format!(
+            "{} is not responding to ping. It may be off, asleep, or blocking ICMP \
+             — try port_check on a service it runs.",
+            label
+        )),
+        Err(e) => McpToolResult::error(e),
+    }
+}
+
+pub async fn handle_port_check(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let (label, host) = match resolve_host(args, &load_devices()) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let mut ports: Vec<u16> = args
+        .get("ports")
+        .and_then(|v| v.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|p| p.as_u64())
+                .map(|p| p as u16)
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(p) = args.get("port").and_then(|v| v.as_u64()) {
+        ports.push(p as u16);
+    }
+    ports.sort_unstable();
+    ports.dedup();
+    if ports.is_empty() {
+        return McpToolResult::error("'port' or 'ports' is required");
+    }
+    if ports.len() > MAX_PORTS {
+        return McpToolResult::error(format!("At most {} ports per check", MAX_PORTS));
+    }
+
+    let checks = ports
+        .iter()
+        .map(|&port| network::check_port(&host, port, PORT_TIMEOUT));
+    let results = futures_util::future::join_all(checks).await;
+
+    let mut out = format!("TCP ports on {}:\n", label);
+    for (port, result) in ports.iter().zip(results) {
+        match result {
+            Ok(elapsed) => out.push_str(&format!("  {} open ({} ms)\n", port, elapsed.as_millis())),
+            Err(e) => out.push_str(&format!("  {} closed ({})\n", port, e)),
+        }
+    }
+    McpToolResult::text(out.trim_end())
+}
+
+pub async fn handle_discover_services(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let types = match str_arg(args, "service") {
+        Some(service) => vec![service.to_string()],
+        None => match network::discover_service_types(DISCOVERY_WAIT).await {
+            Ok(types) if types.is_empty() => {
+                return McpToolResult::text("No mDNS services answered on the local network.");
+            }
+            Ok(types) => types,
+            Err(e) => return McpToolResult::error(e),
+        },
+    };
+
+    let expanded = &types[..types.len().min(MAX_TYPES_EXPANDED)];
+    let lookups = expanded
+        .iter()
+        .map(|t| network::discover_services(t, DISCOVERY_WAIT));
+    let results = futures_util::future::join_all(lookups).await;
+
+    let mut out = String::new();
+    for (service_type, result) in expanded.iter().zip(results) {
+        let services = match result {
+            Ok(s) => s,
+            Err(e) => return McpToolResult::error(e),
+        };
+        out.push_str(&format!("{}:\n", network::service_query_name(service_type)));
+        if services.is_empty() {
+            out.push_str("  (no instances answered)\n");
+        }
+        for svc in services {
+            let addr = match (&svc.host, svc.port) {
+                (Some(host), Some(port)) => format!(" — {}:{}", host, port),
+                _ => String::new(),
+            };
+            let ips: Vec<String> = svc.addresses.iter().map(|a| a.to_string()).collect();
+            let ips = if ips.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", ips.join(", "))
+            };
+            out.push_str(&format!("  {}{}{}\n", svc.instance, addr, ips));
+        }
+    }
+    if types.len() > expanded.len() {
+        out.push_str(&format!(
+            "\nAlso advertised (pass one as `service` to list instances): {}\n",
+            types[expanded.len()..].join(", ")
+        ));
+    }
+    McpToolResult::text(out.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::McpContent;
+    use super::*;
+
+    fn devices() -> Vec<NetworkDevice> {
+        vec![
+            NetworkDevice {
+                name: "Desktop".into(),
+                mac: Some("aa:bb:cc:dd:ee:ff".into()),
+                host: Some("192.168.1.10".into()),
+                broadcast: None,
+            },
+            NetworkDevice {
+                name: "NAS".into(),
+                mac: None,
+                host: None,
+                broadcast: None,
+            },
+        ]
+    }
+
+    fn text(result: &McpToolResult) -> &str {
+        match &result.content[0] {
+            McpContent::Text { text } => text,
+            _ => panic!("expected text"),
+        }
+    }
+
+    #[test]
+    fn test_find_device() {
+        let devices = devices();
+        assert_eq!(find_device(&devices, "desktop").unwrap().name, "Desktop");
+        assert_eq!(find_device(&devices, "my desktop").unwrap().name, "Desktop");
+        assert_eq!(find_device(&devices, "the nas").unwrap().name, "NAS");
+        assert!(find_device(&devices, "laptop").is_none());
+    }
+
+    #[test]
+    fn test_resolve_host() {
+        let devices = devices();
+        let (label, host) =
+            resolve_host(&serde_json::json!({"device": "desktop"}), &devices).unwrap();
+        assert_eq!(host, "192.168.1.10");
+        assert_eq!(label, "Desktop (192.168.1.10)");
+
+        let (_, host) = resolve_host(&serde_json::json!({"host": "nas.local"}), &devices).unwrap();
+        assert_eq!(host, "nas.local");
+
+        let err = resolve_host(&serde_json::json!({"device": "NAS"}), &devices).unwrap_err();
+        assert!(text(&err).contains("no host configured"));
+
+        let err = resolve_host(&serde_json::json!({"device": "laptop"}), &devices).unwrap_err();
+        assert!(text(&err).contains("Known devices: Desktop, NAS"));
+    }
+
+    #[tokio::test]
+    async fn test_wake_on_lan_requires_target() {
+        let result = handle_wake_on_lan(&serde_json::json!({}), Path::new("/tmp")).await;
+        assert!(result.is_error);
+        let result =
+            handle_wake_on_lan(&serde_json::json!({"mac": "not-a-mac"}), Path::new("/tmp")).await;
+        assert!(result.is_error);
+    }
+
+    #[tokio::test]
+    async fn test_port_check_requires_port() {
+        let args = serde_json::json!({"host": "127.0.0.1"});
+        let result = handle_port_check(&args, Path::new("/tmp")).await;
+        assert!(result.is_error);
+    }
+}
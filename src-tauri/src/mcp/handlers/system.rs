@@ -0,0 +1,234 @@
+//! System monitoring tools: system_stats, top_processes, disk_usage,
+//! battery_status, network_throughput.
+//!
+//! Sampling lives in `services::system_stats` and blocks for up to a second
+//! (CPU and throughput are deltas), so every call runs on `spawn_blocking`.
+//! Replies are short, speakable summaries rather than raw JSON.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::McpToolResult;
+use crate::services::system_stats::{self, format_bytes, format_duration, ProcessSort};
+
+const DEFAULT_PROCESS_LIMIT: usize = 10;
+const MAX_PROCESS_LIMIT: usize = 50;
+
+/// Run a blocking sampler off the async runtime.
+async fn sample<T, F>(f: F) -> Result<T, McpToolResult>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| McpToolResult::error(format!("System sampling failed: {}", e)))
+}
+
+fn percent(used: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f64 / total as f64 * 100.0
+    }
+}
+
+pub async fn handle_system_stats(_args: &Value, _data_dir: &Path) -> McpToolResult {
+    let snap = match sample(system_stats::snapshot).await {
+        Ok(s) => s,
+        Err(e) => return e,
+    };
+
+    let mut out = String::new();
+    if let Some(host) = &snap.host_name {
+        out.push_str(&format!("Host: {}", host));
+        if let Some(os) = &snap.os {
+            out.push_str(&format!(" ({})", os));
+        }
+        out.push('\n');
+    }
+    out.push_str(&format!("Uptime: {}\n", format_duration(snap.uptime_secs)));
+
+    let busiest = snap.per_cpu_percent.iter().copied().fold(0.0f32, f32::max);
+    out.push_str(&format!(
+        "CPU: {:.0}% across {} cores (busiest core {:.0}%)\n",
+        snap.cpu_percent, snap.cpu_count, busiest
+    ));
+    if snap.load_average.iter().any(|l| *l > 0.0) {
+        let [one, five, fifteen] = snap.load_average;
+        out.push_str(&format!(
+            "Load average: {:.2} / {:.2} / {:.2} (1 / 5 / 15 min)\n",
+            one, five, fifteen
+        ));
+    }
+    out.push_str(&format!(
+        "Memory: {} of {} used ({:.0}%)\n",
+        format_bytes(snap.memory_used),
+        format_bytes(snap.memory_total),
+        percent(snap.memory_used, snap.memory_total)
+    ));
+    if snap.swap_total > 0 {
+        out.push_str(&format!(
+            "Swap: {} of {} used ({:.0}%)\n",
+            format_bytes(snap.swap_used),
+            format_bytes(snap.swap_total),
+            percent(snap.swap_used, snap.swap_total)
+        ));
+    }
+    McpToolResult::text(out.trim_end())
+}
+
+pub async fn handle_top_processes(args: &Value, _data_dir: &Path) -> McpToolResult {
+    let sort = match args.get("sort_by").and_then(|v| v.as_str()) {
+        None => ProcessSort::Cpu,
+        Some(s) => match ProcessSort::parse(s) {
+            Some(sort) => sort,
+            None => return McpToolResult::error("'sort_by' must be 'cpu' or 'memory'"),
+        },
+    };
+    let limit = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|l| (l as usize).clamp(1, MAX_PROCESS_LIMIT))
+        .unwrap_or(DEFAULT_PROCESS_LIMIT);
+    let group = args.get("group").and_then(|v| v.as_bool()).unwrap_or(true);
+
+    let processes = match sample(move || {
+        let all = system_stats::top_processes(sort, usize::MAX);
+        let mut ranked = if group {
+            system_stats::group_by_name(all, sort)
+        } else {
+            all
+        };
+        ranked.truncate(limit);
+        ranked
+    })
+    .await
+    {
+        Ok(p) => p,
+        Err(e) => return e,
+    };
+
+    let by = match sort {
+        ProcessSort::Cpu => "CPU",
+        ProcessSort::Memory => "memory",
+    };
+    let mut out = format!(
+        "Top {} processes by {} (CPU % is per core, so can exceed 100):\n",
+        processes.len(),
+        by
+    );
+    for p in &processes {
+        out.push_str(&format!(
+            "  {} — {:.1}% CPU, {} (pid {})\n",
+            p.name,
+            p.cpu_percent,
+            format_bytes(p.memory),
+            p.pid
+        ));
+    }
+    McpToolResult::text(out.trim_end())
+}
+
+pub async fn handle_disk_usage(_args: &Value, _data_dir: &Path) -> McpToolResult {
+    let disks = match sample(system_stats::disks).await {
+        Ok(d) => d,
+        Err(e) => return e,
+    };
+    if disks.is_empty() {
+        return McpToolResult::text("No disks found.");
+    }
+
+    let mut out = String::from("Disks:\n");
+    for d in &disks {
+        let used = d.total.saturating_sub(d.available);
+        out.push_str(&format!(
+            "  {} ({}, {}) — {} free of {} ({:.0}% used){}\n",
+            d.mount_point,
+            d.name,
+            d.file_system,
+            format_bytes(d.available),
+            format_bytes(d.total),
+            percent(used, d.total),
+            if d.removable { ", removable" } else { "" }
+        ));
+    }
+    McpToolResult::text(out.trim_end())
+}
+
+pub async fn handle_battery_status(_args: &Value, _data_dir: &Path) -> McpToolResult {
+    let status = match sample(system_stats::battery).await {
+        Ok(Ok(Some(s))) => s,
+        Ok(Ok(None)) => return McpToolResult::text("This machine has no battery."),
+        Ok(Err(e)) => return McpToolResult::error(e),
+        Err(e) => return e,
+    };
+
+    let mut out = format!("Battery: {:.0}%, {}", status.percent, status.state);
+    if let Some(secs) = status.time_remaining_secs {
+        let until = if status.state == "charging" {
+            "until full"
+        } else {
+            "remaining"
+        };
+        out.push_str(&format!(", about {} {}", format_duration(secs), until));
+    }
+    match status.on_ac_power {
+        Some(true) => out.push_str(". Plugged in."),
+        Some(false) => out.push_str(". On battery power."),
+        None => out.push('.'),
+    }
+    McpToolResult::text(out)
+}
+
+pub async fn handle_network_throughput(_args: &Value, _data_dir: &Path) -> McpToolResult {
+    let interfaces = match sample(system_stats::network_throughput).await {
+        Ok(i) => i,
+        Err(e) => return e,
+    };
+    if interfaces.is_empty() {
+        return McpToolResult::text("No active network interfaces found.");
+    }
+
+    let mut out = String::from("Network throughput (1 s sample):\n");
+    for i in &interfaces {
+        out.push_str(&format!(
+            "  {} — down {}/s, up {}/s (since boot: {} down, {} up)\n",
+            i.name,
+            format_bytes(i.rx_per_sec),
+            format_bytes(i.tx_per_sec),
+            format_bytes(i.total_rx),
+            format_bytes(i.total_tx)
+        ));
+    }
+    McpToolResult::text(out.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::McpContent;
+    use super::*;
+
+    fn text(result: &McpToolResult) -> &str {
+        match &result.content[0] {
+            McpContent::Text { text } => text,
+            _ => panic!("expected text"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_top_processes_rejects_bad_sort() {
+        let args = serde_json::json!({"sort_by": "disk"});
+        let result = handle_top_processes(&args, Path::new("/tmp")).await;
+        assert!(result.is_error);
+        assert!(text(&result).contains("'cpu' or 'memory'"));
+    }
+
+    #[tokio::test]
+    async fn test_system_stats_reports_memory() {
+        let result = handle_system_stats(&serde_json::json!({}), Path::new("/tmp")).await;
+        assert!(!result.is_error);
+        assert!(text(&result).contains("Memory:"));
+    }
+}
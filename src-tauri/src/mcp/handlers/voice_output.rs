@@ -0,0 +1,232 @@
+//! Speech channel tools: speak, stop_speaking, list_voices, set_voice.
+//!
+//! Each call is a `VoiceOutputRequest` over the named pipe, run by
+//! `voice::output` in the app. `speak` talks directly, without an inbox
+//! message or chat card. `set_voice` with `persist: true` changes the saved
+//! config, so the server confirms it like a destructive tool (see
+//! `ToolRegistry::needs_confirmation`).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use super::McpToolResult;
+use crate::ipc::protocol::{AppToMcp, McpToApp};
+use crate::mcp::pipe_router::PipeRouter;
+
+/// The app answers at once; speech itself plays in the background.
+const VOICE_OUTPUT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Send one action to the app and return its result.
+async fn request(
+    router: Option<&Arc<PipeRouter>>,
+    action: &str,
+    args: Value,
+) -> Result<Value, McpToolResult> {
+    let router = router.ok_or_else(|| {
+        McpToolResult::error(
+            "Error: voice output needs the pipe connection to Voice Mirror, which is not available",
+        )
+    })?;
+    let request_id = router.next_request_id("tts");
+    let rx = router.wait_for_browser_response(&request_id).await;
+    let msg = McpToApp::VoiceOutputRequest {
+        request_id: request_id.clone(),
+        action: action.to_string(),
+        args,
+    };
+    if let Err(e) = router.send(&msg).await {
+        router.remove_waiter(&request_id).await;
+        return Err(McpToolResult::error(format!("Error: {}", e)));
+    }
+    match tokio::time::timeout(VOICE_OUTPUT_TIMEOUT, rx).await {
+        Ok(Ok(AppToMcp::VoiceOutputResponse {
+            success,
+            result,
+            error,
+            ..
+        })) => {
+            if success {
+                Ok(result.unwrap_or(Value::Null))
+            } else {
+                Err(McpToolResult::error(format!(
+                    "Error: {}",
+                    error.unwrap_or_else(|| "unknown voice output error".into())
+                )))
+            }
+        }
+        _ => {
+            router.remove_waiter(&request_id).await;
+            Err(McpToolResult::error(format!(
+                "Error: Voice Mirror didn't answer the {} request",
+                action
+            )))
+        }
+    }
+}
+
+fn flag(result: &Value, key: &str) -> bool {
+    result.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// `speak` -- say `text` now, or after current speech (`priority`).
+pub async fn handle_speak(args: &Value, router: Option<&Arc<PipeRouter>>) -> McpToolResult {
+    let text = args
+        .get("text")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim();
+    if text.is_empty() {
+        return McpToolResult::error("Error: text is required");
+    }
+    let priority = args
+        .get("priority")
+        .and_then(|v| v.as_str())
+        .unwrap_or("interrupt");
+    let mut forwarded = json!({ "text": text, "priority": priority });
+    if let Some(voice) = args.get("voice").and_then(|v| v.as_str()) {
+        forwarded["voice"] = json!(voice);
+    }
+    let result = match request(router, "speak", forwarded).await {
+        Ok(r) => r,
+        Err(e) => return e,
+    };
+    McpToolResult::text(describe_speak(priority, &result))
+}
+
+fn describe_speak(priority: &str, result: &Value) -> &'static str {
+    let busy = flag(result, "was_speaking");
+    if flag(result, "quiet_hours") {
+        "Not spoken: quiet hours are on."
+    } else if busy && priority == "drop_if_busy" {
+        "Not spoken: something else was being said."
+    } else if busy && priority == "queue" {
+        "Queued; it will be spoken after the current speech."
+    } else {
+        "Speaking."
+    }
+}
+
+/// `stop_speaking` -- cut off current speech.
+pub async fn handle_stop_speaking(
+    _args: &Value,
+    router: Option<&Arc<PipeRouter>>,
+) -> McpToolResult {
+    match request(router, "stop", json!({})).await {
+        Ok(r) if flag(&r, "was_speaking") => McpToolResult::text("Stopped speaking."),
+        Ok(_) => McpToolResult::text("Nothing was being spoken."),
+        Err(e) => e,
+    }
+}
+
+/// `list_voices` -- the current TTS voice and the others on offer.
+pub async fn handle_list_voices(_args: &Value, router: Option<&Arc<PipeRouter>>) -> McpToolResult {
+    match request(router, "list_voices", json!({})).await {
+        Ok(r) => McpToolResult::text(format_voices(&r)),
+        Err(e) => e,
+    }
+}
+
+fn format_voices(result: &Value) -> String {
+    let text = |key: &str| result.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    let current = text("voice");
+    let mut lines = vec![format!("TTS engine: {}", text("adapter"))];
+    lines.push(format!("Current voice: {}", current));
+    if text("saved_voice") != current {
+        lines.push(format!("Saved voice: {}", text("saved_voice")));
+    }
+    let options = result
+        .get("voices")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    if options.is_empty() {
+        lines.push("This engine has no voice list; pass any voice it knows.".into());
+    } else {
+        lines.push(String::new());
+        lines.push("Voices:".into());
+        for option in &options {
+            let value = option.get("value").and_then(|v| v.as_str()).unwrap_or("");
+            let label = option.get("label").and_then(|v| v.as_str()).unwrap_or("");
+            let marker = if value == current { " (current)" } else { "" };
+            lines.push(format!("  {} -- {}{}", value, label, marker));
+        }
+    }
+    lines.join("\n")
+}
+
+/// `set_voice` -- switch the TTS voice; `persist` also saves it.
+pub async fn handle_set_voice(args: &Value, router: Option<&Arc<PipeRouter>>) -> McpToolResult {
+    let voice = args
+        .get("voice")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim();
+    if voice.is_empty() {
+        return McpToolResult::error("Error: voice is required");
+    }
+    let persist = args
+        .get("persist")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let forwarded = json!({ "voice": voice, "persist": persist });
+    match request(router, "set_voice", forwarded).await {
+        Ok(_) if persist => {
+            McpToolResult::text(format!("Voice set to {} and saved as the default.", voice))
+        }
+        Ok(_) => McpToolResult::text(format!(
+            "Voice set to {} until Voice Mirror restarts (persist: true saves it).",
+            voice
+        )),
+        Err(e) => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requires_arguments_and_pipe() {
+        assert!(handle_speak(&json!({ "text": " " }), None).await.is_error);
+        assert!(handle_speak(&json!({ "text": "Hi" }), None).await.is_error);
+        assert!(handle_set_voice(&json!({}), None).await.is_error);
+        assert!(handle_stop_speaking(&json!({}), None).await.is_error);
+    }
+
+    #[test]
+    fn test_describe_speak() {
+        let busy = json!({ "was_speaking": true, "quiet_hours": false });
+        assert_eq!(
+            describe_speak("queue", &busy),
+            "Queued; it will be spoken after the current speech."
+        );
+        assert_eq!(
+            describe_speak("drop_if_busy", &busy),
+            "Not spoken: something else was being said."
+        );
+        assert_eq!(describe_speak("interrupt", &busy), "Speaking.");
+        let quiet = json!({ "was_speaking": false, "quiet_hours": true });
+        assert_eq!(
+            describe_speak("interrupt", &quiet),
+            "Not spoken: quiet hours are on."
+        );
+    }
+
+    #[test]
+    fn test_format_voices_marks_current() {
+        let result = json!({
+            "adapter": "kokoro",
+            "voice": "am_adam",
+            "saved_voice": "af_bella",
+            "voices": [
+                { "value": "af_bella", "label": "Bella (Female)" },
+                { "value": "am_adam", "label": "Adam (Male)" },
+            ],
+        });
+        let text = format_voices(&result);
+        assert!(text.contains("Saved voice: af_bella"));
+        assert!(text.contains("am_adam -- Adam (Male) (current)"));
+    }
+}
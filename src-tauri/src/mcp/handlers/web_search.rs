@@ -0,0 +1,493 @@
+//! Pluggable web search backends for `browser_action` `search`.
+//!
+//! Backends: DuckDuckGo Lite (HTML), Google (HTML), Brave Search API, and a
+//! SearxNG instance (JSON API). All return the same [`SearchResult`] shape.
+//! The configured backend is tried first; when it is blocked, errors, or
+//! finds nothing, the remaining available backends are tried in order
+//! (unless fallback is disabled).
+
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::config::schema::BrowserConfig;
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/124.0 Safari/537.36";
+const SEARCH_TIMEOUT_SECS: u64 = 15;
+
+/// One search hit, identical across backends.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchBackend {
+    DuckDuckGo,
+    Google,
+    Brave,
+    Searxng,
+}
+
+impl SearchBackend {
+    /// Order used for "auto" and for fallback: keyed APIs first, scrapers last.
+    const ALL: [SearchBackend; 4] = [Self::Brave, Self::Searxng, Self::DuckDuckGo, Self::Google];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "duckduckgo" | "ddg" => Some(Self::DuckDuckGo),
+            "google" => Some(Self::Google),
+            "brave" => Some(Self::Brave),
+            "searxng" | "searx" => Some(Self::Searxng),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::DuckDuckGo => "duckduckgo",
+            Self::Google => "google",
+            Self::Brave => "brave",
+            Self::Searxng => "searxng",
+        }
+    }
+}
+
+/// Search settings, resolved from `BrowserConfig`.
+#[derive(Debug, Clone, Default)]
+pub struct SearchSettings {
+    /// Preferred backend; `None` means "auto".
+    pub backend: Option<SearchBackend>,
+    pub fallback: bool,
+    pub searxng_url: Option<String>,
+    pub brave_api_key: Option<String>,
+}
+
+impl SearchSettings {
+    pub fn from_config(cfg: &BrowserConfig) -> Self {
+        let backend = match cfg.search_backend.as_str() {
+            "" | "auto" => None,
+            name => {
+                let parsed = SearchBackend::from_name(name);
+                if parsed.is_none() {
+                    warn!("[web_search] Unknown search backend '{}', using auto", name);
+                }
+                parsed
+            }
+        };
+        Self {
+            backend,
+            fallback: cfg.search_fallback,
+            searxng_url: cfg.searxng_url.clone().filter(|u| !u.trim().is_empty()),
+            brave_api_key: cfg.brave_api_key.clone().filter(|k| !k.trim().is_empty()),
+        }
+    }
+
+    /// Load from the app config on disk (the MCP binary runs out of process).
+    pub fn load() -> Self {
+        let config_dir = crate::services::platform::get_config_dir();
+        Self::from_config(&crate::config::persistence::load_config(&config_dir).browser)
+    }
+
+    fn is_available(&self, backend: SearchBackend) -> bool {
+        match backend {
+            SearchBackend::Brave => self.brave_api_key.is_some(),
+            SearchBackend::Searxng => self.searxng_url.is_some(),
+            SearchBackend::DuckDuckGo | SearchBackend::Google => true,
+        }
+    }
+
+    /// Backends to try, in order.
+    pub fn plan(&self) -> Vec<SearchBackend> {
+        let mut order = Vec::new();
+        if let Some(preferred) = self.backend {
+            order.push(preferred);
+            if !self.fallback {
+                return order;
+            }
+        }
+        for backend in SearchBackend::ALL {
+            if !order.contains(&backend) && self.is_available(backend) {
+                order.push(backend);
+            }
+        }
+        order
+    }
+}
+
+/// Results plus which backend produced them and which ones failed first.
+#[derive(Debug)]
+pub struct SearchOutcome {
+    pub backend: Option<SearchBackend>,
+    pub results: Vec<SearchResult>,
+    pub failures: Vec<String>,
+}
+
+/// Run a search, falling back across backends per `settings`.
+pub async fn search(query: &str, max_results: usize, settings: &SearchSettings) -> SearchOutcome {
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(SEARCH_TIMEOUT_SECS))
+        .user_agent(USER_AGENT)
+        .build()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            return SearchOutcome {
+                backend: None,
+                results: Vec::new(),
+                failures: vec![format!("HTTP client error: {}", e)],
+            }
+        }
+    };
+
+    let mut failures = Vec::new();
+    for backend in settings.plan() {
+        let attempt = match backend {
+            SearchBackend::DuckDuckGo => search_duckduckgo(&client, query).await,
+            SearchBackend::Google => search_google(&client, query, max_results).await,
+            SearchBackend::Brave => search_brave(&client, query, max_results, settings).await,
+            SearchBackend::Searxng => search_searxng(&client, query, settings).await,
+        };
+        match attempt {
+            Ok(mut results) if !results.is_empty() => {
+                results.truncate(max_results);
+                info!("[web_search] {} returned {} results", backend.name(), results.len());
+                return SearchOutcome { backend: Some(backend), results, failures };
+            }
+            Ok(_) => failures.push(format!("{}: no results", backend.name())),
+            Err(e) => {
+                warn!("[web_search] {} failed: {}", backend.name(), e);
+                failures.push(format!("{}: {}", backend.name(), e));
+            }
+        }
+    }
+
+    SearchOutcome { backend: None, results: Vec::new(), failures }
+}
+
+/// Fetch a page, mapping rate limits and bot walls to a "blocked" error.
+async fn get_text(req: reqwest::RequestBuilder) -> Result<String, String> {
+    let response = req.send().await.map_err(|e| format!("request failed: {}", e))?;
+    let status = response.status().as_u16();
+    if status == 429 || status == 403 {
+        return Err(format!("blocked (HTTP {})", status));
+    }
+    if !(200..300).contains(&status) {
+        return Err(format!("HTTP {}", status));
+    }
+    response.text().await.map_err(|e| format!("failed to read response: {}", e))
+}
+
+// ---------------------------------------------------------------------------
+// Backends
+// ---------------------------------------------------------------------------
+
+async fn search_duckduckgo(client: &reqwest::Client, query: &str) -> Result<Vec<SearchResult>, String> {
+    let html = get_text(client.get("https://lite.duckduckgo.com/lite/").query(&[("q", query)])).await?;
+    if html.contains("anomaly-modal") || html.contains("If this error persists") {
+        return Err("blocked (bot check)".into());
+    }
+    Ok(parse_duckduckgo_lite(&html))
+}
+
+async fn search_google(
+    client: &reqwest::Client,
+    query: &str,
+    max_results: usize,
+) -> Result<Vec<SearchResult>, String> {
+    let num = max_results.to_string();
+    let html = get_text(
+        client
+            .get("https://www.google.com/search")
+            .query(&[("q", query), ("num", num.as_str()), ("hl", "en"), ("gbv", "1")]),
+    )
+    .await?;
+    if html.contains("/sorry/") || html.contains("unusual traffic") {
+        return Err("blocked (captcha)".into());
+    }
+    Ok(parse_google_html(&html))
+}
+
+async fn search_brave(
+    client: &reqwest::Client,
+    query: &str,
+    max_results: usize,
+    settings: &SearchSettings,
+) -> Result<Vec<SearchResult>, String> {
+    let key = settings.brave_api_key.as_deref().ok_or("no API key configured")?;
+    let count = max_results.to_string();
+    let body = get_text(
+        client
+            .get("https://api.search.brave.com/res/v1/web/search")
+            .query(&[("q", query), ("count", count.as_str())])
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", key),
+    )
+    .await?;
+    let json: Value = serde_json::from_str(&body).map_err(|e| format!("invalid JSON: {}", e))?;
+    Ok(parse_json_results(json.pointer("/web/results"), "description"))
+}
+
+async fn search_searxng(
+    client: &reqwest::Client,
+    query: &str,
+    settings: &SearchSettings,
+) -> Result<Vec<SearchResult>, String> {
+    let base = settings.searxng_url.as_deref().ok_or("no instance URL configured")?;
+    let url = format!("{}/search", base.trim().trim_end_matches('/'));
+    let body = get_text(client.get(&url).query(&[("q", query), ("format", "json")]))
+        .await
+        .map_err(|e| {
+            if e.contains("403") {
+                format!("{} (enable the json format in the instance's settings.yml)", e)
+            } else {
+                e
+            }
+        })?;
+    let json: Value = serde_json::from_str(&body).map_err(|e| format!("invalid JSON: {}", e))?;
+    Ok(parse_json_results(json.get("results"), "content"))
+}
+
+// ---------------------------------------------------------------------------
+// Parsers
+// ---------------------------------------------------------------------------
+
+/// Map a JSON array of `{ title, url, <snippet_key> }` objects.
+fn parse_json_results(items: Option<&Value>, snippet_key: &str) -> Vec<SearchResult> {
+    items
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|r| {
+                    let url = r.get("url")?.as_str()?;
+                    let title = r.get("title").and_then(|t| t.as_str()).unwrap_or(url);
+                    let snippet = r.get(snippet_key).and_then(|s| s.as_str()).unwrap_or("");
+                    Some(SearchResult {
+                        title: clean_text(title),
+                        url: url.to_string(),
+                        snippet: clean_text(snippet),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// DuckDuckGo Lite: `<a rel="nofollow" href="...">title</a>` followed by a
+/// `result-snippet` cell.
+fn parse_duckduckgo_lite(html: &str) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = Vec::new();
+    let mut in_snippet = false;
+    let mut snippet = String::new();
+
+    for line in html.lines() {
+        let trimmed = line.trim();
+        if trimmed.contains("rel=\"nofollow\"") && trimmed.contains("href=\"") {
+            let Some(href_start) = trimmed.find("href=\"") else { continue };
+            let rest = &trimmed[href_start + 6..];
+            let Some(href_end) = rest.find('"') else { continue };
+            let url = decode_entities(&rest[..href_end]);
+            let title = rest
+                .find('>')
+                .map(|gt| &rest[gt + 1..])
+                .and_then(|after| after.find("</a>").map(|lt| clean_text(&after[..lt])))
+                .unwrap_or_default();
+            if !url.is_empty() && !title.is_empty() {
+                results.push(SearchResult { title, url, snippet: String::new() });
+            }
+        } else if trimmed.contains("result-snippet") {
+            in_snippet = true;
+            snippet.clear();
+            snippet.push_str(trimmed.split_once('>').map(|(_, s)| s).unwrap_or(""));
+        } else if in_snippet {
+            snippet.push(' ');
+            snippet.push_str(trimmed);
+        }
+
+        if in_snippet && snippet.contains("</td>") {
+            in_snippet = false;
+            let text = snippet.split("</td>").next().unwrap_or("");
+            if let Some(last) = results.last_mut() {
+                if last.snippet.is_empty() {
+                    last.snippet = clean_text(text);
+                }
+            }
+        }
+    }
+    results
+}
+
+/// Google basic-HTML results: `<a href="/url?q=TARGET&amp;...">` wrapping an
+/// `<h3>` title; the snippet is the next text block after the link.
+fn parse_google_html(html: &str) -> Vec<SearchResult> {
+    const MARKER: &str = "href=\"/url?q=";
+    let mut results: Vec<SearchResult> = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = html[pos..].find(MARKER) {
+        let start = pos + found + MARKER.len();
+        pos = start;
+        let Some(end) = html[start..].find('"') else { break };
+        let raw = &html[start..start + end];
+        let target = raw.split("&amp;").next().unwrap_or(raw);
+        let url = url::form_urlencoded::parse(format!("u={}", target).as_bytes())
+            .next()
+            .map(|(_, v)| v.into_owned())
+            .unwrap_or_default();
+        if !url.starts_with("http") || results.iter().any(|r| r.url == url) {
+            continue;
+        }
+
+        let tail = &html[start + end..];
+        let link_end = tail.find("</a>").unwrap_or(tail.len());
+        let title = match (tail[..link_end].find("<h3"), tail[..link_end].find("</h3>")) {
+            (Some(h), Some(e)) if h < e => clean_text(&tail[h..e]),
+            _ => continue,
+        };
+        let after = &tail[link_end..];
+        let mut cut = after.len().min(1200);
+        while !after.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let snippet_src = &after[..cut];
+        let snippet_src = snippet_src.split(MARKER).next().unwrap_or(snippet_src);
+        let snippet = clean_text(snippet_src);
+
+        if !title.is_empty() {
+            results.push(SearchResult { title, url, snippet });
+        }
+    }
+    results
+}
+
+/// Strip tags, decode common entities, and collapse whitespace.
+///
+/// `html` must start outside a tag.
+fn clean_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    let joined = decode_entities(&out).split_whitespace().collect::<Vec<_>>().join(" ");
+    // Tags become spaces, which strands punctuation after inline markup.
+    joined
+        .replace(" .", ".")
+        .replace(" ,", ",")
+        .replace(" ;", ";")
+        .replace(" :", ":")
+        .replace(" !", "!")
+        .replace(" ?", "?")
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings(backend: Option<SearchBackend>, fallback: bool) -> SearchSettings {
+        SearchSettings { backend, fallback, ..Default::default() }
+    }
+
+    #[test]
+    fn test_plan_auto_skips_unconfigured() {
+        let plan = settings(None, true).plan();
+        assert_eq!(plan, vec![SearchBackend::DuckDuckGo, SearchBackend::Google]);
+
+        let mut s = settings(None, true);
+        s.brave_api_key = Some("k".into());
+        s.searxng_url = Some("https://searx.example".into());
+        assert_eq!(s.plan()[..2], [SearchBackend::Brave, SearchBackend::Searxng]);
+    }
+
+    #[test]
+    fn test_plan_preferred_first_and_no_fallback() {
+        let plan = settings(Some(SearchBackend::Google), true).plan();
+        assert_eq!(plan, vec![SearchBackend::Google, SearchBackend::DuckDuckGo]);
+
+        let plan = settings(Some(SearchBackend::Google), false).plan();
+        assert_eq!(plan, vec![SearchBackend::Google]);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let cfg = BrowserConfig {
+            search_backend: "SearXNG".into(),
+            searxng_url: Some("  ".into()),
+            ..Default::default()
+        };
+        let s = SearchSettings::from_config(&cfg);
+        assert_eq!(s.backend, Some(SearchBackend::Searxng));
+        assert!(s.searxng_url.is_none());
+        assert!(s.fallback);
+
+        let cfg = BrowserConfig { search_backend: "bing".into(), ..Default::default() };
+        assert_eq!(SearchSettings::from_config(&cfg).backend, None);
+    }
+
+    #[test]
+    fn test_parse_duckduckgo_lite() {
+        let html = r#"
+            <td valign="top">1.&nbsp;</td>
+            <td><a rel="nofollow" href="https://www.rust-lang.org/" class='result-link'>Rust &amp; Cargo</a></td>
+            </tr><tr>
+            <td class='result-snippet'>
+              A language empowering <b>everyone</b>.
+            </td>
+            <a rel="nofollow" href="https://doc.rust-lang.org/book/">The Book</a>
+        "#;
+        let results = parse_duckduckgo_lite(html);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Rust & Cargo");
+        assert_eq!(results[0].snippet, "A language empowering everyone.");
+        assert_eq!(results[1].url, "https://doc.rust-lang.org/book/");
+        assert!(results[1].snippet.is_empty());
+    }
+
+    #[test]
+    fn test_parse_google_html() {
+        let html = r#"<div><a href="/url?q=https://example.com/a%3Fx%3D1&amp;sa=U&amp;ved=2"><h3 class="zBAuLc"><div>Example A</div></h3></a><div class="BNeawe">First snippet.</div></div>
+            <div><a href="/url?q=https://example.com/b&amp;sa=U"><h3>Example B</h3></a><div>Second.</div></div>
+            <a href="/url?q=https://example.com/b&amp;sa=U"><h3>Dup</h3></a>"#;
+        let results = parse_google_html(html);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://example.com/a?x=1");
+        assert_eq!(results[0].title, "Example A");
+        assert_eq!(results[0].snippet, "First snippet.");
+        assert_eq!(results[1].title, "Example B");
+    }
+
+    #[test]
+    fn test_parse_json_results() {
+        let brave = json!({ "web": { "results": [
+            { "title": "T <strong>1</strong>", "url": "https://a", "description": "d1" },
+            { "title": "no url" }
+        ] } });
+        let results = parse_json_results(brave.pointer("/web/results"), "description");
+        assert_eq!(results, vec![SearchResult { title: "T 1".into(), url: "https://a".into(), snippet: "d1".into() }]);
+        assert!(parse_json_results(None, "content").is_empty());
+    }
+}
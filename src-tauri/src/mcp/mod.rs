@@ -8,8 +8,12 @@
 //! - `server.rs` -- JSON-RPC protocol handler (stdin/stdout)
 //! - `tools.rs`  -- Tool registry with dynamic group loading/unloading
 //! - `handlers/` -- Tool handler implementations (core, memory, ...)
+//! - `result_limit.rs` -- Per-tool result size limits (truncation + summary)
+//! - `threads.rs` -- Conversation threads and which one is spoken (`voice_threads`)
 
 pub mod handlers;
 pub mod pipe_router;
+pub mod result_limit;
 pub mod server;
+pub mod threads;
 pub mod tools;
@@ -1,14 +1,24 @@
 //! Pipe message router for the MCP binary.
 //!
 //! Routes incoming `AppToMcp` messages from the pipe to the appropriate handler:
-//! - `BrowserResponse` -> oneshot channel matched by request_id
-//! - `UserMessage` -> mpsc channel consumed by voice_listen
-//! - `Shutdown` -> mpsc channel consumed by voice_listen
+//! - `BrowserResponse` / `CaptureResponse` / `LogEntries` / `ConfirmResponse` /
+//!   `AskResponse` / `VoiceOutputResponse` / `VoiceSendAck` -> oneshot channel
+//!   matched by request_id
+//! - `UserMessage` -> the oldest open `voice_listen` whose sender and thread
+//!   filter match it
+//! - `Shutdown` -> every open `voice_listen`
+//! - `VoiceEvent` -> bounded channel the server turns into
+//!   `notifications/voice_event` (see `voice_subscribe`)
 //!
 //! This solves the concurrency issue where both `voice_listen` and
-//! `pipe_browser_request` need to receive from the same pipe connection.
+//! `pipe_browser_request` need to receive from the same pipe connection, and
+//! lets several `voice_listen` calls wait on one connection at once: each
+//! registers under its own request_id and gets only the messages meant for
+//! it. Messages no listen wants yet are held (up to `MAX_UNCLAIMED`) for the
+//! next one.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use tokio::sync::{mpsc, oneshot, Mutex};
@@ -17,28 +27,45 @@ use tracing::{info, warn};
 use crate::ipc::pipe_client::PipeClient;
 use crate::ipc::protocol::{AppToMcp, McpToApp};
 
+/// Unclaimed user messages kept for the next `voice_listen`.
+const MAX_UNCLAIMED: usize = 32;
+
+/// Voice events waiting to be written to stdout before new ones are dropped.
+const VOICE_EVENT_QUEUE: usize = 256;
+
 /// Routes pipe messages to the appropriate handler.
 pub struct PipeRouter {
     pipe: Arc<PipeClient>,
     /// Pending browser requests waiting for responses, keyed by request_id.
     browser_waiters: Arc<Mutex<HashMap<String, oneshot::Sender<AppToMcp>>>>,
-    /// Channel for UserMessage/Shutdown delivery (voice_listen consumes from here).
-    user_messages_tx: mpsc::UnboundedSender<AppToMcp>,
-    /// Receiver for user messages. Protected by mutex for single-consumer use.
-    pub user_messages_rx: Mutex<mpsc::UnboundedReceiver<AppToMcp>>,
+    /// Open `voice_listen` calls and the messages none of them wanted yet.
+    listeners: Mutex<Listeners>,
+    /// Counter for `next_request_id`.
+    request_counter: AtomicU64,
+    /// Subscribed voice events, for the server to push to the client.
+    voice_events_tx: mpsc::Sender<AppToMcp>,
+    voice_events_rx: std::sync::Mutex<Option<mpsc::Receiver<AppToMcp>>>,
 }
 
 impl PipeRouter {
     pub fn new(pipe: Arc<PipeClient>) -> Arc<Self> {
-        let (tx, rx) = mpsc::unbounded_channel();
+        let (voice_events_tx, voice_events_rx) = mpsc::channel(VOICE_EVENT_QUEUE);
         Arc::new(Self {
             pipe,
             browser_waiters: Arc::new(Mutex::new(HashMap::new())),
-            user_messages_tx: tx,
-            user_messages_rx: Mutex::new(rx),
+            listeners: Mutex::new(Listeners::default()),
+            request_counter: AtomicU64::new(0),
+            voice_events_tx,
+            voice_events_rx: std::sync::Mutex::new(Some(voice_events_rx)),
         })
     }
 
+    /// The receiving end of the voice event channel. Only the first call
+    /// gets it.
+    pub fn take_voice_events(&self) -> Option<mpsc::Receiver<AppToMcp>> {
+        self.voice_events_rx.lock().ok()?.take()
+    }
+
     /// Start the background message dispatch loop.
     ///
     /// Spawns a tokio task that reads from the pipe and routes each message
@@ -60,6 +87,10 @@ impl PipeRouter {
                     }
                 }
             }
+            // Nothing more will arrive: fail the waits now instead of
+            // letting each one run into its timeout.
+            router.listeners.lock().await.close();
+            router.browser_waiters.lock().await.clear();
             info!("[PipeRouter] Dispatch loop ended");
         });
     }
@@ -69,7 +100,11 @@ impl PipeRouter {
         match &msg {
             AppToMcp::BrowserResponse { request_id, .. }
             | AppToMcp::CaptureResponse { request_id, .. }
-            | AppToMcp::LogEntries { request_id, .. } => {
+            | AppToMcp::LogEntries { request_id, .. }
+            | AppToMcp::ConfirmResponse { request_id, .. }
+            | AppToMcp::AskResponse { request_id, .. }
+            | AppToMcp::VoiceOutputResponse { request_id, .. }
+            | AppToMcp::VoiceSendAck { request_id } => {
                 let mut waiters = self.browser_waiters.lock().await;
                 if let Some(tx) = waiters.remove(request_id) {
                     let _ = tx.send(msg);
@@ -81,13 +116,22 @@ impl PipeRouter {
                 }
             }
             AppToMcp::UserMessage { .. } | AppToMcp::Shutdown => {
-                if self.user_messages_tx.send(msg).is_err() {
-                    warn!("[PipeRouter] User message channel closed");
+                self.listeners.lock().await.deliver(msg);
+            }
+            AppToMcp::VoiceEvent { event, .. } => {
+                if self.voice_events_tx.try_send(msg.clone()).is_err() {
+                    warn!("[PipeRouter] Voice event queue full, dropping {}", event);
                 }
             }
         }
     }
 
+    /// A request_id unique to this connection, e.g. `listen-3`.
+    pub fn next_request_id(&self, kind: &str) -> String {
+        let n = self.request_counter.fetch_add(1, Ordering::Relaxed);
+        format!("{}-{}", kind, n)
+    }
+
     /// Register a waiter for a browser response and return the receiver.
     pub async fn wait_for_browser_response(
         &self,
@@ -109,8 +153,270 @@ impl PipeRouter {
         self.browser_waiters.lock().await.remove(request_id);
     }
 
+    /// Open a listen for the next `UserMessage` from `from_sender` (and in
+    /// `thread_id`, if set). The receiver gets that message or `Shutdown`;
+    /// it errors when the pipe closes.
+    pub async fn listen(
+        &self,
+        request_id: &str,
+        from_sender: &str,
+        thread_id: Option<&str>,
+    ) -> oneshot::Receiver<AppToMcp> {
+        self.listeners
+            .lock()
+            .await
+            .register(request_id, from_sender, thread_id)
+    }
+
+    /// Close a listen that ended without a message and tell the app.
+    pub async fn cancel_listen(&self, request_id: &str) {
+        if self.listeners.lock().await.cancel(request_id) {
+            let _ = self
+                .send(&McpToApp::ListenCancel {
+                    request_id: request_id.to_string(),
+                })
+                .await;
+        }
+    }
+
+    /// Give up on a `voice_ask` and tell the app to close the question.
+    pub async fn cancel_ask(&self, request_id: &str) {
+        self.remove_waiter(request_id).await;
+        let _ = self
+            .send(&McpToApp::AskCancel {
+                request_id: request_id.to_string(),
+            })
+            .await;
+    }
+
     /// Send a message through the pipe to the Tauri app.
     pub async fn send(&self, msg: &McpToApp) -> Result<(), std::io::Error> {
         self.pipe.send(msg).await
     }
 }
+
+/// An open `voice_listen`.
+struct Listener {
+    request_id: String,
+    /// Lowercased.
+    from_sender: String,
+    thread_id: Option<String>,
+    tx: oneshot::Sender<AppToMcp>,
+}
+
+impl Listener {
+    fn wants(&self, msg: &AppToMcp) -> bool {
+        match msg {
+            AppToMcp::UserMessage {
+                from, thread_id, ..
+            } => {
+                from.to_lowercase() == self.from_sender
+                    && self
+                        .thread_id
+                        .as_ref()
+                        .is_none_or(|filter| thread_id.as_ref() == Some(filter))
+            }
+            AppToMcp::Shutdown => true,
+            _ => false,
+        }
+    }
+}
+
+/// Matches user messages to open listens, oldest listen first.
+#[derive(Default)]
+struct Listeners {
+    open: Vec<Listener>,
+    unclaimed: VecDeque<AppToMcp>,
+    /// The pipe is gone; new listens fail straight away.
+    closed: bool,
+}
+
+impl Listeners {
+    fn register(
+        &mut self,
+        request_id: &str,
+        from_sender: &str,
+        thread_id: Option<&str>,
+    ) -> oneshot::Receiver<AppToMcp> {
+        let (tx, rx) = oneshot::channel();
+        if self.closed {
+            return rx;
+        }
+        let listener = Listener {
+            request_id: request_id.to_string(),
+            from_sender: from_sender.to_lowercase(),
+            thread_id: thread_id.map(str::to_string),
+            tx,
+        };
+        match self.unclaimed.iter().position(|m| listener.wants(m)) {
+            Some(i) => {
+                let msg = self.unclaimed.remove(i).expect("position is in range");
+                let _ = listener.tx.send(msg);
+            }
+            None => self.open.push(listener),
+        }
+        rx
+    }
+
+    /// Whether `request_id` was still open.
+    fn cancel(&mut self, request_id: &str) -> bool {
+        let before = self.open.len();
+        self.open.retain(|l| l.request_id != request_id);
+        self.open.len() != before
+    }
+
+    fn deliver(&mut self, msg: AppToMcp) {
+        // Listens whose call was dropped without cancelling
+        self.open.retain(|l| !l.tx.is_closed());
+
+        if matches!(msg, AppToMcp::Shutdown) && !self.open.is_empty() {
+            for listener in self.open.drain(..) {
+                let _ = listener.tx.send(AppToMcp::Shutdown);
+            }
+            return;
+        }
+        match self.open.iter().position(|l| l.wants(&msg)) {
+            Some(i) => {
+                let _ = self.open.remove(i).tx.send(msg);
+            }
+            None => {
+                if self.unclaimed.len() == MAX_UNCLAIMED {
+                    self.unclaimed.pop_front();
+                }
+                self.unclaimed.push_back(msg);
+            }
+        }
+    }
+
+    /// Drop every open listen; their receivers error.
+    fn close(&mut self) {
+        self.closed = true;
+        self.open.clear();
+        self.unclaimed.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_message(from: &str, thread_id: Option<&str>, id: &str) -> AppToMcp {
+        AppToMcp::UserMessage {
+            id: id.into(),
+            from: from.into(),
+            message: "hi".into(),
+            thread_id: thread_id.map(str::to_string),
+            timestamp: "t".into(),
+            image_path: None,
+            image_data_url: None,
+        }
+    }
+
+    fn message_id(msg: AppToMcp) -> String {
+        match msg {
+            AppToMcp::UserMessage { id, .. } => id,
+            other => panic!("expected a user message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_messages_go_to_the_matching_listen() {
+        let mut listeners = Listeners::default();
+        let mut alice = listeners.register("listen-0", "Alice", None);
+        let mut bob = listeners.register("listen-1", "bob", Some("t1"));
+
+        listeners.deliver(user_message("bob", Some("t1"), "m1"));
+        listeners.deliver(user_message("alice", None, "m2"));
+
+        assert_eq!(message_id(bob.try_recv().unwrap()), "m1");
+        assert_eq!(message_id(alice.try_recv().unwrap()), "m2");
+        assert!(listeners.open.is_empty());
+    }
+
+    #[test]
+    fn test_oldest_listen_wins() {
+        let mut listeners = Listeners::default();
+        let mut first = listeners.register("listen-0", "alice", None);
+        let mut second = listeners.register("listen-1", "alice", None);
+
+        listeners.deliver(user_message("alice", None, "m1"));
+
+        assert_eq!(message_id(first.try_recv().unwrap()), "m1");
+        assert!(second.try_recv().is_err());
+        assert_eq!(listeners.open.len(), 1);
+    }
+
+    #[test]
+    fn test_unclaimed_message_waits_for_next_listen() {
+        let mut listeners = Listeners::default();
+        listeners.deliver(user_message("alice", Some("t2"), "m1"));
+
+        let mut other_thread = listeners.register("listen-0", "alice", Some("t1"));
+        assert!(other_thread.try_recv().is_err());
+
+        let mut rx = listeners.register("listen-1", "alice", Some("t2"));
+        assert_eq!(message_id(rx.try_recv().unwrap()), "m1");
+        assert!(listeners.unclaimed.is_empty());
+    }
+
+    #[test]
+    fn test_unclaimed_is_bounded() {
+        let mut listeners = Listeners::default();
+        for i in 0..MAX_UNCLAIMED + 5 {
+            listeners.deliver(user_message("alice", None, &format!("m{}", i)));
+        }
+        assert_eq!(listeners.unclaimed.len(), MAX_UNCLAIMED);
+        let mut rx = listeners.register("listen-0", "alice", None);
+        assert_eq!(message_id(rx.try_recv().unwrap()), "m5");
+    }
+
+    #[test]
+    fn test_cancelled_listen_gets_nothing() {
+        let mut listeners = Listeners::default();
+        let _rx = listeners.register("listen-0", "alice", None);
+        assert!(listeners.cancel("listen-0"));
+        assert!(!listeners.cancel("listen-0"));
+
+        listeners.deliver(user_message("alice", None, "m1"));
+        assert_eq!(listeners.unclaimed.len(), 1);
+    }
+
+    #[test]
+    fn test_dropped_listen_is_skipped() {
+        let mut listeners = Listeners::default();
+        drop(listeners.register("listen-0", "alice", None));
+        let mut rx = listeners.register("listen-1", "alice", None);
+
+        listeners.deliver(user_message("alice", None, "m1"));
+        assert_eq!(message_id(rx.try_recv().unwrap()), "m1");
+    }
+
+    #[test]
+    fn test_shutdown_reaches_every_listen() {
+        let mut listeners = Listeners::default();
+        let mut a = listeners.register("listen-0", "alice", None);
+        let mut b = listeners.register("listen-1", "bob", None);
+
+        listeners.deliver(AppToMcp::Shutdown);
+
+        assert!(matches!(a.try_recv(), Ok(AppToMcp::Shutdown)));
+        assert!(matches!(b.try_recv(), Ok(AppToMcp::Shutdown)));
+    }
+
+    #[test]
+    fn test_closed_fails_listens() {
+        let mut listeners = Listeners::default();
+        let mut open = listeners.register("listen-0", "alice", None);
+        listeners.close();
+        assert!(matches!(
+            open.try_recv(),
+            Err(oneshot::error::TryRecvError::Closed)
+        ));
+
+        let mut late = listeners.register("listen-1", "alice", None);
+        assert!(matches!(
+            late.try_recv(),
+            Err(oneshot::error::TryRecvError::Closed)
+        ));
+    }
+}
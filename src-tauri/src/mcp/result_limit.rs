@@ -0,0 +1,279 @@
+//! Per-tool size limits for MCP tool results.
+//!
+//! Some tools (browser snapshots, n8n execution data) can return hundreds of
+//! kilobytes, which blows the model's context in one call. Results over the
+//! configured limit (`ai.toolResultMaxChars`, overridden per tool by
+//! `ai.toolResultLimits`) are cut down before being returned:
+//!
+//! - JSON is shrunk structurally — every key is kept, but long arrays keep
+//!   their first items, long strings are clipped, and deep nesting collapses
+//!   to a key count — so the model still sees the shape of the data.
+//! - Plain text keeps its head and tail around an omission marker.
+//!
+//! Either way a note is appended saying how much was cut and how to fetch
+//! more (a tool-specific hint where we have one).
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use super::handlers::{McpContent, McpToolResult};
+
+/// Shrink passes for JSON, from gentle to aggressive:
+/// (array items kept, max string chars, object depth kept).
+const JSON_PASSES: &[(usize, usize, usize)] = &[(10, 500, 6), (5, 200, 4), (3, 100, 3), (1, 60, 2)];
+
+/// Configured result limits, in characters.
+#[derive(Debug, Clone)]
+pub struct ResultLimits {
+    default_max: usize,
+    per_tool: HashMap<String, usize>,
+}
+
+impl ResultLimits {
+    pub fn new(default_max: usize, per_tool: HashMap<String, usize>) -> Self {
+        Self {
+            default_max,
+            per_tool,
+        }
+    }
+
+    /// Load from the app config on disk (the MCP binary runs out of process).
+    pub fn load() -> Self {
+        let config_dir = crate::services::platform::get_config_dir();
+        let ai = crate::config::persistence::load_config(&config_dir).ai;
+        Self::new(ai.tool_result_max_chars, ai.tool_result_limits)
+    }
+
+    /// Limit for a tool, or None if unlimited (configured as 0).
+    pub fn limit_for(&self, tool: &str) -> Option<usize> {
+        let limit = self.per_tool.get(tool).copied().unwrap_or(self.default_max);
+        (limit > 0).then_some(limit)
+    }
+
+    /// Apply the tool's limit to every text item in `result`. Images pass through.
+    pub fn apply(&self, tool: &str, mut result: McpToolResult) -> McpToolResult {
+        let Some(limit) = self.limit_for(tool) else {
+            return result;
+        };
+        for item in &mut result.content {
+            if let McpContent::Text { text } = item {
+                if text.chars().count() > limit {
+                    *text = truncate_result(tool, text, limit);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// How the model can get at what was cut, per tool.
+fn fetch_more_hint(tool: &str) -> &'static str {
+    match tool {
+        "n8n_get_execution" => {
+            "Call n8n_get_execution with include_data: false for a summary, \
+             or ask the user which node's output they need."
+        }
+        "n8n_get_executions" => {
+            "Call n8n_get_executions again with a smaller limit, or filter by \
+             status or workflow_id."
+        }
+        "n8n_get_workflow" | "n8n_list_workflows" | "n8n_search_nodes" => {
+            "Narrow the request (a specific id or search term) to see the rest."
+        }
+        "browser_action" | "sandbox_snapshot" => {
+            "Act on what is shown, or fetch a more specific page or element \
+             instead of the whole page."
+        }
+        _ => "Call the tool again with narrower arguments to see the rest.",
+    }
+}
+
+/// Cut `text` down to about `limit` chars and append the truncation note.
+fn truncate_result(tool: &str, text: &str, limit: usize) -> String {
+    let original = text.chars().count();
+    let body = shrink_json_text(text, limit).unwrap_or_else(|| head_and_tail(text, limit));
+    format!(
+        "{}\n\n[Result truncated: showing {} of {} characters ({} limit). {}]",
+        body,
+        body.chars().count(),
+        original,
+        tool,
+        fetch_more_hint(tool)
+    )
+}
+
+/// Shrink JSON (optionally preceded by a text prefix) to fit `limit`.
+/// Returns None if `text` isn't JSON.
+fn shrink_json_text(text: &str, limit: usize) -> Option<String> {
+    let start = text.find(['{', '['])?;
+    let (prefix, json) = text.split_at(start);
+    let value: Value = serde_json::from_str(json.trim_end()).ok()?;
+    let pretty = json.contains('\n');
+
+    let mut last = String::new();
+    for &(items, str_max, depth) in JSON_PASSES {
+        let shrunk = shrink_value(&value, items, str_max, depth);
+        let rendered = if pretty {
+            serde_json::to_string_pretty(&shrunk).ok()?
+        } else {
+            serde_json::to_string(&shrunk).ok()?
+        };
+        last = format!("{}{}", prefix, rendered);
+        if last.chars().count() <= limit {
+            return Some(last);
+        }
+    }
+    // Even the most aggressive pass is too big (e.g. a huge object); fall
+    // back to cutting its text.
+    Some(head_and_tail(&last, limit))
+}
+
+/// Structurally shrink a JSON value. Keys are always preserved down to
+/// `depth`; below that objects and arrays collapse to a size summary.
+fn shrink_value(value: &Value, items: usize, str_max: usize, depth: usize) -> Value {
+    match value {
+        Value::String(s) => {
+            let len = s.chars().count();
+            if len > str_max {
+                let head: String = s.chars().take(str_max).collect();
+                Value::String(format!("{}… ({} chars)", head, len))
+            } else {
+                value.clone()
+            }
+        }
+        Value::Array(arr) if depth == 0 => Value::String(format!("[… {} items]", arr.len())),
+        Value::Object(obj) if depth == 0 => Value::String(format!("{{… {} keys}}", obj.len())),
+        Value::Array(arr) => {
+            let mut out: Vec<Value> = arr
+                .iter()
+                .take(items)
+                .map(|v| shrink_value(v, items, str_max, depth - 1))
+                .collect();
+            if arr.len() > items {
+                out.push(Value::String(format!("… {} more items", arr.len() - items)));
+            }
+            Value::Array(out)
+        }
+        Value::Object(obj) => {
+            let out: Map<String, Value> = obj
+                .iter()
+                .map(|(k, v)| (k.clone(), shrink_value(v, items, str_max, depth - 1)))
+                .collect();
+            Value::Object(out)
+        }
+        _ => value.clone(),
+    }
+}
+
+/// Keep roughly the first three quarters and last quarter of `text`,
+/// preferring line breaks as cut points.
+fn head_and_tail(text: &str, limit: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= limit {
+        return text.to_string();
+    }
+    let head_len = limit * 3 / 4;
+    let tail_len = limit - head_len;
+
+    let mut head: String = chars[..head_len].iter().collect();
+    if let Some(nl) = head.rfind('\n') {
+        if head.len() - nl < 200 {
+            head.truncate(nl);
+        }
+    }
+    let mut tail: String = chars[chars.len() - tail_len..].iter().collect();
+    if let Some(nl) = tail.find('\n') {
+        if nl < 200 {
+            tail = tail[nl + 1..].to_string();
+        }
+    }
+
+    let omitted = chars.len() - head.chars().count() - tail.chars().count();
+    format!("{}\n[… {} characters omitted …]\n{}", head, omitted, tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(default_max: usize) -> ResultLimits {
+        let mut per_tool = HashMap::new();
+        per_tool.insert("small".to_string(), 100);
+        per_tool.insert("unlimited".to_string(), 0);
+        ResultLimits::new(default_max, per_tool)
+    }
+
+    fn text_of(result: &McpToolResult) -> &str {
+        match &result.content[0] {
+            McpContent::Text { text } => text,
+            _ => panic!("expected text"),
+        }
+    }
+
+    #[test]
+    fn test_limit_for() {
+        let l = limits(5000);
+        assert_eq!(l.limit_for("small"), Some(100));
+        assert_eq!(l.limit_for("unlimited"), None);
+        assert_eq!(l.limit_for("other"), Some(5000));
+        assert_eq!(limits(0).limit_for("other"), None);
+    }
+
+    #[test]
+    fn test_small_results_untouched() {
+        let result = limits(5000).apply("other", McpToolResult::text("short"));
+        assert_eq!(text_of(&result), "short");
+    }
+
+    #[test]
+    fn test_json_keeps_structure() {
+        let rows: Vec<Value> = (0..500)
+            .map(|i| serde_json::json!({ "id": i, "status": "success", "data": "x".repeat(300) }))
+            .collect();
+        let text = format!(
+            "Executions:\n{}",
+            serde_json::json!({ "count": 500, "rows": rows })
+        );
+        let result = limits(2000).apply("n8n_get_executions", McpToolResult::text(text));
+        let out = text_of(&result);
+
+        let (body, note) = out.split_once("\n\n[Result truncated").unwrap();
+        assert!(body.chars().count() <= 2000);
+        assert!(body.starts_with("Executions:\n"));
+        let value: Value = serde_json::from_str(&body["Executions:\n".len()..]).unwrap();
+        assert_eq!(value["count"], 500);
+        assert_eq!(value["rows"][0]["status"], "success");
+        assert!(value["rows"]
+            .as_array()
+            .unwrap()
+            .last()
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .contains("more items"));
+        assert!(note.contains("smaller limit"));
+    }
+
+    #[test]
+    fn test_plain_text_head_and_tail() {
+        let text: String = (0..400).map(|i| format!("line {}\n", i)).collect();
+        let result = limits(5000).apply("small", McpToolResult::text(text));
+        let out = text_of(&result);
+        assert!(out.starts_with("line 0\n"));
+        assert!(out.contains("characters omitted"));
+        assert!(out.contains("line 399"));
+        assert!(out.contains("of 3490 characters (small limit)"));
+        assert!(out.contains("narrower arguments"));
+    }
+
+    #[test]
+    fn test_images_pass_through() {
+        let result = McpToolResult::image("A".repeat(10_000), "image/png".into());
+        let result = limits(5000).apply("small", result);
+        match &result.content[0] {
+            McpContent::Image { data, .. } => assert_eq!(data.len(), 10_000),
+            _ => panic!("expected image"),
+        }
+    }
+}
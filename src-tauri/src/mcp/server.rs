@@ -3,20 +3,47 @@
 //! Reads JSON-RPC requests from stdin, routes tool calls to the appropriate
 //! handler, and sends JSON-RPC responses to stdout. Implements the MCP protocol
 //! methods: `initialize`, `initialized`, `tools/list`, `tools/call`.
+//!
+//! Tool calls run as their own tasks, so a `voice_listen` waiting minutes for
+//! the user doesn't hold up a `voice_send` or a browser call behind it, and
+//! `notifications/cancelled` aborts the call it names.
+//!
+//! Voice events the client subscribed to with `voice_subscribe` are pushed
+//! as `notifications/voice_event` with `{ event, data }` params.
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
 use tracing::{error, info};
 
 use super::handlers;
 use super::handlers::McpToolResult;
+use super::result_limit::ResultLimits;
+use super::threads::SharedThreads;
 use super::tools::ToolRegistry;
 
+use crate::ipc::protocol::{AppToMcp, McpToApp};
 use crate::mcp::pipe_router::PipeRouter;
 
+/// How long to wait for a spoken confirmation: the app's own 30 s timeout
+/// plus time to speak the question.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// Arguments that name what a destructive tool acts on, for the spoken
+/// question.
+const CONFIRM_TARGET_ARGS: &[&str] = &[
+    "content_or_id",
+    "workflow_id",
+    "execution_id",
+    "credential_id",
+    "tag_id",
+    "voice",
+];
+
 // ---------------------------------------------------------------------------
 // JSON-RPC message types
 // ---------------------------------------------------------------------------
@@ -89,6 +116,13 @@ impl JsonRpcResponse {
 // MCP Server
 // ---------------------------------------------------------------------------
 
+/// Stdout, shared by the tool call tasks.
+type SharedWriter = Arc<Mutex<tokio::io::Stdout>>;
+
+/// Tool calls still running, keyed by their JSON-RPC id (as JSON text), for
+/// `notifications/cancelled`.
+type InFlight = Arc<std::sync::Mutex<HashMap<String, tokio::task::AbortHandle>>>;
+
 /// Shared server state.
 pub struct McpServerState {
     registry: ToolRegistry,
@@ -99,6 +133,10 @@ pub struct McpServerState {
     /// Flag set when tool list changes (load/unload/auto-unload).
     /// The main loop checks this after each request to send notifications.
     tools_changed: bool,
+    /// Conversation threads and the focused one (whose replies are spoken).
+    threads: SharedThreads,
+    /// Tool result size limits, read from the config once at startup.
+    result_limits: Arc<ResultLimits>,
 }
 
 /// Run the MCP server on stdin/stdout.
@@ -108,7 +146,7 @@ pub struct McpServerState {
 /// to stderr.
 ///
 /// The optional `router` parameter enables fast named-pipe IPC for voice_send/voice_listen
-/// and browser tool requests. When `None`, the server falls back to file-based IPC (inbox.json).
+/// and browser tool requests. When `None`, the server falls back to file-based IPC (inbox.db).
 ///
 /// The optional `enabled_groups` parameter (comma-separated group names from
 /// `ENABLED_GROUPS` env var) pre-loads tool groups at startup so they appear
@@ -134,14 +172,26 @@ pub async fn run_server(
         data_dir,
         router,
         tools_changed: false,
+        threads: SharedThreads::default(),
+        result_limits: Arc::new(ResultLimits::load()),
     }));
 
     let stdin = tokio::io::stdin();
-    let stdout = tokio::io::stdout();
     let reader = BufReader::new(stdin);
-    let mut writer = stdout;
+    let writer: SharedWriter = Arc::new(Mutex::new(tokio::io::stdout()));
+    let in_flight: InFlight = Arc::default();
     let mut lines = reader.lines();
 
+    let voice_events = state
+        .lock()
+        .await
+        .router
+        .as_ref()
+        .and_then(|router| router.take_voice_events());
+    if let Some(voice_events) = voice_events {
+        tokio::spawn(push_voice_events(voice_events, writer.clone()));
+    }
+
     eprintln!("Voice Mirror MCP server (Rust) running");
 
     while let Ok(Some(line)) = lines.next_line().await {
@@ -159,7 +209,7 @@ pub async fn run_server(
                     -32700, // Parse error
                     format!("Invalid JSON: {}", e),
                 );
-                write_response(&mut writer, &resp).await;
+                write_response(&mut *writer.lock().await, &resp).await;
                 continue;
             }
         };
@@ -168,12 +218,19 @@ pub async fn run_server(
         if request.jsonrpc != "2.0" {
             if let Some(id) = request.id {
                 let resp = JsonRpcResponse::error(id, -32600, "Invalid JSON-RPC version");
-                write_response(&mut writer, &resp).await;
+                write_response(&mut *writer.lock().await, &resp).await;
             }
             continue;
         }
 
-        let _id = request.id.clone().unwrap_or(Value::Null);
+        if request.method == "tools/call" && request.id.is_some() {
+            spawn_tool_call(state.clone(), writer.clone(), in_flight.clone(), request);
+            continue;
+        }
+        if request.method == "notifications/cancelled" {
+            cancel_tool_call(&in_flight, &request.params);
+        }
+
         let response = handle_request(state.clone(), &request).await;
 
         // Notifications (no id) don't get a response
@@ -181,35 +238,108 @@ pub async fn run_server(
             continue;
         }
 
+        let mut writer = writer.lock().await;
         match response {
             Some(resp) => {
-                write_response(&mut writer, &resp).await;
+                write_response(&mut *writer, &resp).await;
             }
             None => {
                 // Method handled as notification, no response needed
             }
         }
-
-        // Send tools/list_changed notification if tool list was modified
-        // (BUG-005 Fix 2). This tells the MCP client to re-fetch tools/list.
-        {
-            let mut st = state.lock().await;
-            if st.tools_changed {
-                st.tools_changed = false;
-                let notification = JsonRpcNotification {
-                    jsonrpc: "2.0".into(),
-                    method: "notifications/tools/list_changed".into(),
-                    params: None,
-                };
-                write_notification(&mut writer, &notification).await;
-            }
-        }
+        notify_tools_changed(&state, &mut *writer).await;
     }
 
     eprintln!("MCP server stdin closed, shutting down");
     Ok(())
 }
 
+/// Run a `tools/call` as its own task, writing its response when done.
+fn spawn_tool_call(
+    state: Arc<Mutex<McpServerState>>,
+    writer: SharedWriter,
+    in_flight: InFlight,
+    request: JsonRpcRequest,
+) {
+    let id = request.id.clone().unwrap_or(Value::Null);
+    let key = id.to_string();
+    // Held across the spawn so the task can't finish (and remove its
+    // entry) before the entry is inserted
+    let mut calls = in_flight.lock().unwrap_or_else(|e| e.into_inner());
+    let task_in_flight = Arc::clone(&in_flight);
+    let task_key = key.clone();
+    let handle = tokio::spawn(async move {
+        let response = handle_tools_call(state.clone(), id, &request.params).await;
+        task_in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&task_key);
+
+        let mut writer = writer.lock().await;
+        write_response(&mut *writer, &response).await;
+        notify_tools_changed(&state, &mut *writer).await;
+    });
+    calls.insert(key, handle.abort_handle());
+}
+
+/// Write each subscribed voice event as a `notifications/voice_event`, a
+/// Voice Mirror extension (not an MCP spec method) that clients may ignore.
+async fn push_voice_events(mut events: mpsc::Receiver<AppToMcp>, writer: SharedWriter) {
+    while let Some(msg) = events.recv().await {
+        let AppToMcp::VoiceEvent { event, data } = msg else {
+            continue;
+        };
+        let notification = JsonRpcNotification {
+            jsonrpc: "2.0".into(),
+            method: "notifications/voice_event".into(),
+            params: Some(json!({ "event": event, "data": data })),
+        };
+        write_notification(&mut *writer.lock().await, &notification).await;
+    }
+}
+
+/// Abort the tool call named by a `notifications/cancelled`. Per the MCP
+/// spec it gets no response. A `voice_listen` closes its listen on the pipe
+/// as it is dropped.
+fn cancel_tool_call(in_flight: &InFlight, params: &Value) {
+    let Some(request_id) = params.get("requestId") else {
+        return;
+    };
+    let call = in_flight
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&request_id.to_string());
+    match call {
+        Some(call) => {
+            call.abort();
+            info!(
+                "[MCP] Cancelled tool call {} ({:?})",
+                request_id,
+                params.get("reason")
+            );
+        }
+        None => info!("[MCP] Cancel for {} which already finished", request_id),
+    }
+}
+
+/// Send tools/list_changed notification if tool list was modified
+/// (BUG-005 Fix 2). This tells the MCP client to re-fetch tools/list.
+async fn notify_tools_changed<W: AsyncWriteExt + Unpin>(
+    state: &Arc<Mutex<McpServerState>>,
+    writer: &mut W,
+) {
+    let mut st = state.lock().await;
+    if st.tools_changed {
+        st.tools_changed = false;
+        let notification = JsonRpcNotification {
+            jsonrpc: "2.0".into(),
+            method: "notifications/tools/list_changed".into(),
+            params: None,
+        };
+        write_notification(writer, &notification).await;
+    }
+}
+
 /// Handle a single JSON-RPC request and return a response.
 async fn handle_request(
     state: Arc<Mutex<McpServerState>>,
@@ -232,7 +362,7 @@ async fn handle_request(
             Some(response)
         }
         "notifications/cancelled" => {
-            // Client cancelled a request -- just log it
+            // The call itself is aborted by the read loop (cancel_tool_call)
             info!("[MCP] Request cancelled: {:?}", request.params);
             None
         }
@@ -291,39 +421,69 @@ async fn handle_tools_call(
         .and_then(|v| v.as_str())
         .unwrap_or("")
         .to_string();
-    let args = params.get("arguments").cloned().unwrap_or(json!({}));
+    let mut args = params.get("arguments").cloned().unwrap_or(json!({}));
 
     if tool_name.is_empty() {
         return JsonRpcResponse::error(id, -32602, "Missing tool name in params");
     }
 
-    // Record tool call and get data_dir + router
-    let (data_dir, is_destructive, router) = {
+    // Record tool call and get data_dir + router + threads + limits
+    let (data_dir, needs_confirmation, router, threads, result_limits) = {
         let mut state = state.lock().await;
         state.registry.record_tool_call(&tool_name);
         (
             state.data_dir.clone(),
-            state.registry.is_destructive(&tool_name),
+            state.registry.needs_confirmation(&tool_name, &args),
             state.router.clone(),
+            state.threads.clone(),
+            state.result_limits.clone(),
         )
     };
 
-    // Check destructive tool confirmation
-    if is_destructive {
-        let confirmed = args.get("confirmed").and_then(|v| v.as_bool()).unwrap_or(false);
-        if !confirmed {
-            let result = McpToolResult::text(format!(
-                "CONFIRMATION REQUIRED: \"{}\" is a destructive operation.\n\
-                 Ask the user for voice confirmation before proceeding.\n\
-                 To execute, call {} again with confirmed: true in the arguments.",
-                tool_name, tool_name
-            ));
-            return JsonRpcResponse::success(id, serde_json::to_value(&result).unwrap());
+    // Check destructive tool confirmation (also persistent voice changes).
+    // In hands-free mode the app asks the user aloud; otherwise the AI has to.
+    let confirmed = args.get("confirmed").and_then(|v| v.as_bool()).unwrap_or(false);
+    if needs_confirmation && !confirmed {
+        match confirm_by_voice(router.as_ref(), &tool_name, &args).await.as_deref() {
+            Some("yes") => {
+                if let Some(obj) = args.as_object_mut() {
+                    obj.insert("confirmed".into(), json!(true));
+                }
+            }
+            answer => {
+                let text = if answer == Some("no") {
+                    format!(
+                        "DECLINED: the user said no to \"{}\" when asked by voice. It was not run.\n\
+                         Do not call it again unless they ask for it.",
+                        tool_name
+                    )
+                } else {
+                    format!(
+                        "CONFIRMATION REQUIRED: \"{}\" is a destructive operation.\n\
+                         Ask the user for voice confirmation before proceeding.\n\
+                         To execute, call {} again with confirmed: true in the arguments.",
+                        tool_name, tool_name
+                    )
+                };
+                let result = McpToolResult::text(text);
+                return JsonRpcResponse::success(id, serde_json::to_value(&result).unwrap());
+            }
         }
     }
 
     // Route to handler
-    let result = route_tool_call(&tool_name, &args, &data_dir, state.clone(), router.as_ref()).await;
+    let result = route_tool_call(
+        &tool_name,
+        &args,
+        &data_dir,
+        state.clone(),
+        router.as_ref(),
+        &threads,
+    )
+    .await;
+
+    // Keep oversized results (snapshots, execution data) from flooding the context
+    let result = result_limits.apply(&tool_name, result);
 
     // After tool execution, check for idle groups
     {
@@ -338,6 +498,71 @@ async fn handle_tools_call(
     JsonRpcResponse::success(id, serde_json::to_value(&result).unwrap())
 }
 
+/// Ask the user to confirm `tool` aloud through the app. The answer ("yes",
+/// "no", "unclear", "timeout" or "unavailable"), or `None` without a pipe
+/// or a reply.
+async fn confirm_by_voice(
+    router: Option<&Arc<PipeRouter>>,
+    tool: &str,
+    args: &Value,
+) -> Option<String> {
+    let router = router?;
+    let request_id = handlers::capture::generate_request_id();
+    let rx = router.wait_for_browser_response(&request_id).await;
+    let msg = McpToApp::ConfirmRequest {
+        request_id: request_id.clone(),
+        tool: tool.to_string(),
+        prompt: confirm_prompt(tool, args),
+    };
+    if let Err(e) = router.send(&msg).await {
+        error!("[MCP] Failed to send confirm request: {}", e);
+        router.remove_waiter(&request_id).await;
+        return None;
+    }
+    match tokio::time::timeout(CONFIRM_TIMEOUT, rx).await {
+        Ok(Ok(AppToMcp::ConfirmResponse { answer, .. })) => {
+            info!("[MCP] Spoken confirmation for {}: {}", tool, answer);
+            Some(answer)
+        }
+        _ => {
+            router.remove_waiter(&request_id).await;
+            None
+        }
+    }
+}
+
+/// The question spoken before a destructive tool runs, e.g. "Delete n8n
+/// workflow 42? Say yes or no."
+fn confirm_prompt(tool: &str, args: &Value) -> String {
+    let action = match tool {
+        "memory_forget" => "Forget the memory".to_string(),
+        "set_voice" => "Save as the default voice".to_string(),
+        _ => {
+            let (service, rest) = match tool.strip_prefix("n8n_") {
+                Some(rest) => ("n8n ", rest),
+                None => ("", tool),
+            };
+            let mut words = rest.split('_');
+            let verb = words.next().unwrap_or_default();
+            let object: Vec<&str> = words.collect();
+            let mut verb_chars = verb.chars();
+            let verb = match verb_chars.next() {
+                Some(first) => first.to_uppercase().chain(verb_chars).collect(),
+                None => String::new(),
+            };
+            format!("{} {}{}", verb, service, object.join(" "))
+        }
+    };
+    let target = CONFIRM_TARGET_ARGS
+        .iter()
+        .find_map(|key| args.get(*key).and_then(|v| v.as_str()))
+        .map(|t| t.chars().take(60).collect::<String>());
+    match target {
+        Some(target) => format!("{} {}? Say yes or no.", action, target),
+        None => format!("{}? Say yes or no.", action),
+    }
+}
+
 /// Route a tool call to the appropriate handler module.
 async fn route_tool_call(
     name: &str,
@@ -345,10 +570,11 @@ async fn route_tool_call(
     data_dir: &std::path::Path,
     _state: Arc<Mutex<McpServerState>>,
     router: Option<&Arc<PipeRouter>>,
+    threads: &SharedThreads,
 ) -> McpToolResult {
     match name {
         // ---- Core tools ----
-        "voice_send" => handlers::core::handle_voice_send(args, data_dir, router).await,
+        "voice_send" => handlers::core::handle_voice_send(args, data_dir, router, threads).await,
         "voice_inbox" => {
             let result = handlers::core::handle_voice_inbox(args, data_dir).await;
             // Auto-load by intent based on inbox messages
@@ -356,9 +582,15 @@ async fn route_tool_call(
             //  The Node.js version does it inline.)
             result
         }
-        "voice_listen" => handlers::core::handle_voice_listen(args, data_dir, router).await,
+        "voice_listen" => {
+            handlers::core::handle_voice_listen(args, data_dir, router, threads).await
+        }
         "voice_status" => handlers::core::handle_voice_status(args, data_dir).await,
         "get_logs" => handlers::core::handle_get_logs(args, data_dir, router).await,
+        "voice_talk_time" => handlers::core::handle_voice_talk_time(args, data_dir).await,
+        "voice_subscribe" => handlers::core::handle_voice_subscribe(args, router).await,
+        "voice_threads" => handlers::core::handle_voice_threads(args, threads).await,
+        "voice_ask" => handlers::core::handle_voice_ask(args, router).await,
 
         // ---- Memory tools ----
         "memory_search" => handlers::memory::handle_memory_search(args, data_dir).await,
@@ -382,6 +614,8 @@ async fn route_tool_call(
             }
         }
 
+        "browser_profile" => handlers::browser::handle_browser_profile(args, data_dir, router).await,
+
         // ---- Capture tools ----
         "capture_list_windows" => handlers::capture::handle_capture_list_windows(args, data_dir, router).await,
         "capture_window" => handlers::capture::handle_capture_window(args, data_dir, router).await,
@@ -397,6 +631,31 @@ async fn route_tool_call(
         "sandbox_type" => handlers::sandbox::handle_sandbox_type(args, data_dir, router).await,
         "sandbox_close_window" => handlers::sandbox::handle_sandbox_close_window(args, data_dir, router).await,
 
+        // ---- Network tools ----
+        "wake_on_lan" => handlers::network::handle_wake_on_lan(args, data_dir).await,
+        "ping" => handlers::network::handle_ping(args, data_dir).await,
+        "port_check" => handlers::network::handle_port_check(args, data_dir).await,
+        "discover_services" => handlers::network::handle_discover_services(args, data_dir).await,
+
+        // ---- System monitoring tools ----
+        "system_stats" => handlers::system::handle_system_stats(args, data_dir).await,
+        "top_processes" => handlers::system::handle_top_processes(args, data_dir).await,
+        "disk_usage" => handlers::system::handle_disk_usage(args, data_dir).await,
+        "battery_status" => handlers::system::handle_battery_status(args, data_dir).await,
+        "network_throughput" => handlers::system::handle_network_throughput(args, data_dir).await,
+
+        // ---- Speech output tools ----
+        "speak" => handlers::voice_output::handle_speak(args, router).await,
+        "stop_speaking" => handlers::voice_output::handle_stop_speaking(args, router).await,
+        "list_voices" => handlers::voice_output::handle_list_voices(args, router).await,
+        "set_voice" => handlers::voice_output::handle_set_voice(args, router).await,
+
+        // ---- File tools ----
+        "read_file" => handlers::files::handle_read_file(args, data_dir).await,
+        "write_file" => handlers::files::handle_write_file(args, data_dir).await,
+        "list_dir" => handlers::files::handle_list_dir(args, data_dir).await,
+        "search_files" => handlers::files::handle_search_files(args, data_dir).await,
+
         // ---- n8n tools ----
         "n8n_list_workflows" => handlers::n8n::handle_n8n_list_workflows(args, data_dir).await,
         "n8n_get_workflow" => handlers::n8n::handle_n8n_get_workflow(args, data_dir).await,
@@ -457,7 +716,9 @@ async fn write_notification<W: AsyncWriteExt + Unpin>(
             if let Err(e) = writer.flush().await {
                 error!("[MCP] Failed to flush stdout: {}", e);
             }
-            info!("[MCP] Sent tools/list_changed notification");
+            if notification.method != "notifications/voice_event" {
+                info!("[MCP] Sent {} notification", notification.method);
+            }
         }
         Err(e) => {
             error!("[MCP] Failed to serialize notification: {}", e);
@@ -500,6 +761,8 @@ mod tests {
             data_dir: std::path::PathBuf::from("/tmp/test"),
             router: None,
             tools_changed: false,
+            threads: SharedThreads::default(),
+            result_limits: Arc::new(ResultLimits::new(0, HashMap::new())),
         };
         let resp = handle_tools_list(json!(1), &state);
         let result = resp.result.unwrap();
@@ -508,6 +771,26 @@ mod tests {
         assert_eq!(tools.len(), 16);
     }
 
+    #[test]
+    fn test_confirm_prompt() {
+        assert_eq!(
+            confirm_prompt("n8n_delete_workflow", &json!({ "workflow_id": "42" })),
+            "Delete n8n workflow 42? Say yes or no."
+        );
+        assert_eq!(
+            confirm_prompt("memory_forget", &json!({ "content_or_id": "chunk_7" })),
+            "Forget the memory chunk_7? Say yes or no."
+        );
+        assert_eq!(
+            confirm_prompt("n8n_delete_tag", &json!({})),
+            "Delete n8n tag? Say yes or no."
+        );
+        assert_eq!(
+            confirm_prompt("set_voice", &json!({ "voice": "am_adam", "persist": true })),
+            "Save as the default voice am_adam? Say yes or no."
+        );
+    }
+
     #[test]
     fn test_parse_json_rpc_request() {
         let json = r#"{"jsonrpc":"2.0","id":1,"method":"tools/list","params":{}}"#;
@@ -546,14 +829,17 @@ mod tests {
             data_dir: std::path::PathBuf::from("/tmp/test"),
             router: None,
             tools_changed: false,
+            threads: SharedThreads::default(),
+            result_limits: Arc::new(ResultLimits::new(0, HashMap::new())),
         };
         let resp = handle_tools_list(json!(1), &state);
         let result = resp.result.unwrap();
         let tools = result["tools"].as_array().unwrap();
-        // core (5) + capture (11) + browser (1) = 17
+        // core (5) + capture (11) + browser (2) = 18
         assert!(tools.len() > 7, "Should have more than default 7 tools");
         let names: Vec<&str> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
         assert!(names.contains(&"browser_action"));
+        assert!(names.contains(&"browser_profile"));
     }
 
     #[test]
@@ -570,4 +856,19 @@ mod tests {
         // params should be omitted (skip_serializing_if)
         assert!(!json.contains("\"params\""));
     }
+
+    #[tokio::test]
+    async fn test_cancel_tool_call_aborts_it() {
+        let in_flight: InFlight = Arc::default();
+        let call = tokio::spawn(std::future::pending::<()>());
+        in_flight
+            .lock()
+            .unwrap()
+            .insert(json!(7).to_string(), call.abort_handle());
+
+        cancel_tool_call(&in_flight, &json!({ "requestId": 7, "reason": "user" }));
+
+        assert!(call.await.unwrap_err().is_cancelled());
+        assert!(in_flight.lock().unwrap().is_empty());
+    }
 }
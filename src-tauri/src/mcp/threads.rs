@@ -0,0 +1,168 @@
+//! Conversation threads seen by this MCP server, and which one has focus.
+//!
+//! `voice_send` and `voice_listen` report each message's thread here. The
+//! user can only follow one conversation by ear, so once a thread is focused
+//! (`voice_threads` `switch`) only its replies are spoken; the others still
+//! reach the chat and inbox silently. With no focus every open thread is
+//! spoken, as before threads were tracked. Closed threads stay silent until
+//! switched back to.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Longest `last_message` kept per thread.
+const PREVIEW_CHARS: usize = 80;
+
+/// The thread manager shared by the tool calls.
+pub type SharedThreads = Arc<Mutex<ThreadManager>>;
+
+/// One conversation thread.
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    pub id: String,
+    /// Senders seen in the thread (instance IDs and the user's name).
+    pub participants: BTreeSet<String>,
+    pub message_count: usize,
+    /// Unix time (ms) of the last message.
+    pub last_activity_ms: u64,
+    /// Start of the last message.
+    pub last_message: String,
+    pub closed: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ThreadManager {
+    threads: HashMap<String, ThreadInfo>,
+    focused: Option<String>,
+}
+
+impl ThreadManager {
+    /// Record a message from `from` in `thread_id`. Returns whether a reply
+    /// in the thread should be spoken.
+    pub fn record(&mut self, thread_id: &str, from: &str, message: &str, now_ms: u64) -> bool {
+        let thread = self
+            .threads
+            .entry(thread_id.to_string())
+            .or_insert_with(|| ThreadInfo {
+                id: thread_id.to_string(),
+                participants: BTreeSet::new(),
+                message_count: 0,
+                last_activity_ms: now_ms,
+                last_message: String::new(),
+                closed: false,
+            });
+        thread.participants.insert(from.to_string());
+        thread.message_count += 1;
+        thread.last_activity_ms = now_ms;
+        thread.last_message = message.chars().take(PREVIEW_CHARS).collect();
+        self.should_speak(thread_id)
+    }
+
+    /// Whether replies in `thread_id` are spoken: it isn't closed, and it
+    /// is the focused thread (or nothing is focused).
+    pub fn should_speak(&self, thread_id: &str) -> bool {
+        if self.threads.get(thread_id).is_some_and(|t| t.closed) {
+            return false;
+        }
+        self.focused.as_deref().is_none_or(|f| f == thread_id)
+    }
+
+    pub fn focused(&self) -> Option<&str> {
+        self.focused.as_deref()
+    }
+
+    /// Focus `thread_id`, reopening it if it was closed.
+    pub fn switch(&mut self, thread_id: &str) -> Result<(), String> {
+        let thread = self
+            .threads
+            .get_mut(thread_id)
+            .ok_or_else(|| format!("Unknown thread \"{}\"", thread_id))?;
+        thread.closed = false;
+        self.focused = Some(thread_id.to_string());
+        Ok(())
+    }
+
+    /// Clear the focus, so every open thread is spoken again.
+    pub fn unfocus(&mut self) {
+        self.focused = None;
+    }
+
+    /// Close `thread_id`: its replies are no longer spoken. Closing the
+    /// focused thread clears the focus.
+    pub fn close(&mut self, thread_id: &str) -> Result<(), String> {
+        let thread = self
+            .threads
+            .get_mut(thread_id)
+            .ok_or_else(|| format!("Unknown thread \"{}\"", thread_id))?;
+        thread.closed = true;
+        if self.focused.as_deref() == Some(thread_id) {
+            self.focused = None;
+        }
+        Ok(())
+    }
+
+    /// Threads, most recently active first.
+    pub fn list(&self) -> Vec<&ThreadInfo> {
+        let mut threads: Vec<&ThreadInfo> = self.threads.values().collect();
+        threads.sort_by(|a, b| {
+            b.last_activity_ms
+                .cmp(&a.last_activity_ms)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        threads
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_everything_spoken_without_focus() {
+        let mut threads = ThreadManager::default();
+        assert!(threads.record("a", "voice-claude", "hi", 1));
+        assert!(threads.record("b", "coder-claude", "hello", 2));
+        assert!(threads.should_speak("never-seen"));
+    }
+
+    #[test]
+    fn test_only_focused_thread_spoken() {
+        let mut threads = ThreadManager::default();
+        threads.record("a", "voice-claude", "hi", 1);
+        threads.record("b", "coder-claude", "hello", 2);
+        threads.switch("a").unwrap();
+        assert!(threads.record("a", "voice-claude", "more", 3));
+        assert!(!threads.record("b", "coder-claude", "more", 4));
+        assert!(!threads.should_speak("new-thread"));
+        threads.unfocus();
+        assert!(threads.should_speak("b"));
+    }
+
+    #[test]
+    fn test_close_silences_and_switch_reopens() {
+        let mut threads = ThreadManager::default();
+        threads.record("a", "voice-claude", "hi", 1);
+        threads.switch("a").unwrap();
+        threads.close("a").unwrap();
+        assert_eq!(threads.focused(), None);
+        assert!(!threads.record("a", "voice-claude", "still here", 2));
+        threads.switch("a").unwrap();
+        assert!(threads.should_speak("a"));
+        assert!(threads.switch("missing").is_err());
+        assert!(threads.close("missing").is_err());
+    }
+
+    #[test]
+    fn test_list_most_recent_first() {
+        let mut threads = ThreadManager::default();
+        threads.record("old", "user", "first", 1);
+        threads.record("new", "user", "second", 5);
+        threads.record("old", "voice-claude", &"x".repeat(200), 3);
+        let list = threads.list();
+        assert_eq!(list[0].id, "new");
+        assert_eq!(list[1].id, "old");
+        assert_eq!(list[1].message_count, 2);
+        assert_eq!(list[1].participants.len(), 2);
+        assert_eq!(list[1].last_message.len(), PREVIEW_CHARS);
+    }
+}
@@ -193,6 +193,14 @@ impl ToolRegistry {
         self.destructive_tools.contains(tool_name)
     }
 
+    /// Check if a call needs the user's confirmation: a destructive tool, or
+    /// a `set_voice` that saves the voice to the config.
+    pub fn needs_confirmation(&self, tool_name: &str, args: &Value) -> bool {
+        self.is_destructive(tool_name)
+            || (tool_name == "set_voice"
+                && args.get("persist").and_then(|v| v.as_bool()) == Some(true))
+    }
+
     /// Record that a tool was called (for idle tracking).
     pub fn record_tool_call(&mut self, tool_name: &str) {
         let count = TOTAL_CALL_COUNT.fetch_add(1, Ordering::Relaxed) + 1;
@@ -464,7 +472,7 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
         "core".into(),
         ToolGroupDef {
             name: "core".into(),
-            description: "Core voice communication (send, inbox, listen, status)".into(),
+            description: "Core voice communication (send, inbox, listen, status, talk time)".into(),
             always_loaded: true,
             keywords: vec![],
             dependencies: vec![],
@@ -478,7 +486,12 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
                             "instance_id": { "type": "string", "description": "Your instance ID (use \"voice-claude\" for Voice Mirror)" },
                             "message": { "type": "string", "description": "The message to send (will be spoken via TTS)" },
                             "thread_id": { "type": "string", "description": "Optional thread ID for grouping messages" },
-                            "reply_to": { "type": "string", "description": "Optional message ID this replies to" }
+                            "reply_to": { "type": "string", "description": "Optional message ID this replies to" },
+                            "priority": {
+                                "type": "string",
+                                "enum": ["interrupt", "queue", "drop_if_busy"],
+                                "description": "If something is already being spoken: interrupt it (default, for replies), queue after it (status updates), or drop this message (low-value notices)"
+                            }
                         },
                         "required": ["instance_id", "message"]
                     }),
@@ -513,14 +526,15 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
                 },
                 ToolDef {
                     name: "voice_status".into(),
-                    description: "Update or list Claude instance status for presence tracking.".into(),
+                    description: "Update or list Claude instance status for presence tracking. Several agents can be registered at once; give each a different TTS voice so the user can tell them apart by ear. While another agent is active and shares your voice, your spoken replies start with your instance name.".into(),
                     input_schema: json!({
                         "type": "object",
                         "properties": {
                             "instance_id": { "type": "string", "description": "Your instance ID" },
                             "action": { "type": "string", "enum": ["update", "list"], "description": "Action to perform" },
                             "status": { "type": "string", "enum": ["active", "idle"], "description": "Your current status" },
-                            "current_task": { "type": "string", "description": "What you are working on" }
+                            "current_task": { "type": "string", "description": "What you are working on" },
+                            "voice": { "type": "string", "description": "TTS voice to speak your replies in (e.g. am_adam, or an Edge voice like en-GB-RyanNeural). Empty string resets to the configured voice; omit to keep the current one." }
                         },
                         "required": ["instance_id"]
                     }),
@@ -559,6 +573,54 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
                         }
                     }),
                 },
+                ToolDef {
+                    name: "voice_talk_time".into(),
+                    description: "How much the user has talked to Voice Mirror: talk time, number of utterances, average utterance length, and how often they interrupted a reply. Use for questions like \"how much have I talked to you today?\"".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "days": { "type": "number", "description": "Number of days to cover, ending today (default: 1 = today only, max: 90)" }
+                        }
+                    }),
+                },
+                ToolDef {
+                    name: "voice_subscribe".into(),
+                    description: "Subscribe to Voice Mirror's live voice events (state_change, recording_start, transcription, speaking_start, speaking_end, error, ...). Matching events are pushed as notifications/voice_event with {event, data} params, so you can react without polling voice_listen. notifications/voice_event is a Voice Mirror extension, not part of the MCP spec; if your client doesn't surface custom notifications, use voice_listen instead. A new subscription replaces the previous one.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "action": { "type": "string", "enum": ["subscribe", "unsubscribe"], "description": "Subscribe (default) or stop receiving events" },
+                            "events": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Event names to receive. Omit for all events except the high-rate audio_level and speaking_progress."
+                            }
+                        }
+                    }),
+                },
+                ToolDef {
+                    name: "voice_threads".into(),
+                    description: "List the conversation threads seen by voice_send/voice_listen, or choose which one is spoken. After switch, only replies in the focused thread are spoken aloud; replies in other threads still reach the chat silently. unfocus speaks every open thread again; close silences a thread until it is switched back to.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "action": { "type": "string", "enum": ["list", "switch", "unfocus", "close"], "description": "List threads (default), focus one, clear the focus, or close one" },
+                            "thread_id": { "type": "string", "description": "Thread to switch to or close" }
+                        }
+                    }),
+                },
+                ToolDef {
+                    name: "voice_ask".into(),
+                    description: "Ask the user a question aloud and wait for the spoken answer. The question is spoken, recording starts by itself once it has been said, and the transcription is returned. Use for a quick clarification mid-task instead of voice_send + voice_listen. Returns \"No answer\" on timeout.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "question": { "type": "string", "description": "Question to speak; keep it short and answerable in a few words" },
+                            "timeout_seconds": { "type": "number", "description": "How long to wait for the answer, including speaking the question (default: 60, max: 300)" }
+                        },
+                        "required": ["question"]
+                    }),
+                },
             ],
         },
     );
@@ -654,7 +716,7 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
         "browser".into(),
         ToolGroupDef {
             name: "browser".into(),
-            description: "Browser control with element refs and annotated screenshots (1 tool)".into(),
+            description: "Browser control with element refs, annotated screenshots, and persistent profiles (2 tools)".into(),
             always_loaded: false,
             keywords: vec![
                 "search".into(), "browse".into(), "website".into(), "web".into(),
@@ -662,7 +724,8 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
                 "look up".into(), "find online".into(), "what is".into(),
                 "who is".into(), "latest news".into(),
                 "ref".into(), "annotate".into(), "auth".into(), "login".into(),
-                "cookie".into(), "snapshot".into(),
+                "cookie".into(), "snapshot".into(), "profile".into(),
+                "session".into(),
             ],
             dependencies: vec![],
             tools: vec![
@@ -726,6 +789,11 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
                                 "type": "string",
                                 "description": "Search query for search action"
                             },
+                            "backend": {
+                                "type": "string",
+                                "enum": ["duckduckgo", "google", "brave", "searxng"],
+                                "description": "For search: override the configured search backend (others are still tried if it fails)"
+                            },
                             "name": {
                                 "type": "string",
                                 "description": "Profile name for auth actions"
@@ -743,6 +811,25 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
                         "required": ["action"]
                     }),
                 },
+                ToolDef {
+                    name: "browser_profile".into(),
+                    description: "Manage persistent browser profiles so logins survive restarts. A profile stores the browser's cookies and per-site localStorage, encrypted at rest. Actions: list | create (empty profile) | save (capture the current session into a profile; defaults to the active one) | use (restore a profile into the browser) | clear (forget stored cookies/storage) | delete.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "action": {
+                                "type": "string",
+                                "enum": ["list", "create", "save", "use", "clear", "delete"],
+                                "description": "Profile operation to perform"
+                            },
+                            "name": {
+                                "type": "string",
+                                "description": "Profile name (letters, digits, '-' or '_'). Required except for list; optional for save when a profile is active."
+                            }
+                        },
+                        "required": ["action"]
+                    }),
+                },
             ],
         },
     );
@@ -892,6 +979,239 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
         },
     );
 
+    // ---- Network (LAN devices) ----
+    groups.insert(
+        "network".into(),
+        ToolGroupDef {
+            name: "network".into(),
+            description: "Wake-on-LAN, ping, port checks, and mDNS service discovery (4 tools)".into(),
+            always_loaded: false,
+            keywords: vec![
+                "wake up".into(), "wake on lan".into(), "ping".into(), "online".into(),
+                "offline".into(), "reachable".into(), "nas".into(), "network".into(),
+                "port open".into(), "devices on".into(),
+            ],
+            dependencies: vec![],
+            tools: vec![
+                ToolDef {
+                    name: "wake_on_lan".into(),
+                    description: "Wake a computer on the local network by sending a Wake-on-LAN magic packet. Pass a configured device name (e.g. 'desktop') or a MAC address.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "device": { "type": "string", "description": "Name of a device configured in Voice Mirror (network.devices)." },
+                            "mac": { "type": "string", "description": "MAC address, e.g. 'aa:bb:cc:dd:ee:ff'. Use instead of `device`." },
+                            "broadcast": { "type": "string", "description": "Subnet broadcast address (e.g. '192.168.1.255'). Default 255.255.255.255." }
+                        }
+                    }),
+                },
+                ToolDef {
+                    name: "ping".into(),
+                    description: "Check whether a device is online by pinging it once. Pass a configured device name or a host/IP.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "device": { "type": "string", "description": "Name of a device configured in Voice Mirror (network.devices)." },
+                            "host": { "type": "string", "description": "Hostname or IP address. Use instead of `device`." }
+                        }
+                    }),
+                },
+                ToolDef {
+                    name: "port_check".into(),
+                    description: "Check whether TCP ports are open on a device (e.g. 445 for file sharing, 22 for SSH, 8123 for Home Assistant). Useful when a device blocks ping.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "device": { "type": "string", "description": "Name of a device configured in Voice Mirror (network.devices)." },
+                            "host": { "type": "string", "description": "Hostname or IP address. Use instead of `device`." },
+                            "port": { "type": "number", "description": "A single TCP port." },
+                            "ports": { "type": "array", "items": { "type": "number" }, "description": "Several TCP ports (max 20)." }
+                        }
+                    }),
+                },
+                ToolDef {
+                    name: "discover_services".into(),
+                    description: "Discover devices and services advertised on the local network via mDNS/Bonjour (printers, NAS shares, Home Assistant, Chromecasts, ...). Omit `service` to list everything.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "service": { "type": "string", "description": "DNS-SD service type to look for, e.g. '_smb._tcp' or '_http._tcp'. Omit to enumerate all types." }
+                        }
+                    }),
+                },
+            ],
+        },
+    );
+
+    // ---- System monitoring ----
+    groups.insert(
+        "sysinfo".into(),
+        ToolGroupDef {
+            name: "sysinfo".into(),
+            description: "CPU, memory, disk, process, battery, and network usage (5 tools)".into(),
+            always_loaded: false,
+            keywords: vec![
+                "cpu".into(), "memory usage".into(), "ram usage".into(), "disk space".into(),
+                "storage space".into(), "battery".into(), "processes".into(), "the fan".into(),
+                "laptop fan".into(), "running slow".into(), "bandwidth".into(), "system stats".into(),
+            ],
+            dependencies: vec![],
+            tools: vec![
+                ToolDef {
+                    name: "system_stats".into(),
+                    description: "Overall CPU load, memory and swap usage, load average, and uptime of this computer.".into(),
+                    input_schema: json!({ "type": "object", "properties": {} }),
+                },
+                ToolDef {
+                    name: "top_processes".into(),
+                    description: "The processes using the most CPU or memory right now. Use to find out why the computer is slow, hot, or loud.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "sort_by": { "type": "string", "enum": ["cpu", "memory"], "description": "Rank by CPU (default) or memory." },
+                            "limit": { "type": "number", "description": "How many to return (default 10, max 50)." },
+                            "group": { "type": "boolean", "description": "Merge processes with the same name, e.g. browser helpers (default true)." }
+                        }
+                    }),
+                },
+                ToolDef {
+                    name: "disk_usage".into(),
+                    description: "Free and used space on each mounted disk.".into(),
+                    input_schema: json!({ "type": "object", "properties": {} }),
+                },
+                ToolDef {
+                    name: "battery_status".into(),
+                    description: "Battery charge, charging state, time remaining, and whether the computer is plugged in.".into(),
+                    input_schema: json!({ "type": "object", "properties": {} }),
+                },
+                ToolDef {
+                    name: "network_throughput".into(),
+                    description: "Current download/upload rate per network interface (sampled over one second).".into(),
+                    input_schema: json!({ "type": "object", "properties": {} }),
+                },
+            ],
+        },
+    );
+
+    // ---- Speech output ----
+    groups.insert(
+        "voice_output".into(),
+        ToolGroupDef {
+            name: "voice_output".into(),
+            description: "Direct control of spoken output: speak, stop, list and set TTS voices (4 tools)".into(),
+            always_loaded: false,
+            keywords: vec![
+                "say it out loud".into(), "read it aloud".into(), "read aloud".into(),
+                "stop talking".into(), "stop speaking".into(), "your voice".into(),
+                "different voice".into(), "change voice".into(), "which voices".into(),
+            ],
+            dependencies: vec![],
+            tools: vec![
+                ToolDef {
+                    name: "speak".into(),
+                    description: "Say text aloud right away through Voice Mirror's TTS, without sending an inbox message or adding a chat card. Use voice_send for normal replies; use this for announcements, readouts, or short asides.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "text": { "type": "string", "description": "Text to speak" },
+                            "priority": { "type": "string", "enum": ["interrupt", "queue", "drop_if_busy"], "description": "interrupt (default) cuts off current speech; queue waits for it; drop_if_busy skips the text if something is being said" },
+                            "voice": { "type": "string", "description": "Speak this text in another voice without changing the selected one" }
+                        },
+                        "required": ["text"]
+                    }),
+                },
+                ToolDef {
+                    name: "stop_speaking".into(),
+                    description: "Stop the speech that is playing now.".into(),
+                    input_schema: json!({ "type": "object", "properties": {} }),
+                },
+                ToolDef {
+                    name: "list_voices".into(),
+                    description: "The current TTS engine and voice, and the voices it offers.".into(),
+                    input_schema: json!({ "type": "object", "properties": {} }),
+                },
+                ToolDef {
+                    name: "set_voice".into(),
+                    description: "Switch the TTS voice. Lasts until Voice Mirror restarts unless persist is true, which saves it as the default and requires confirmed: true (ask the user first).".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "voice": { "type": "string", "description": "Voice name from list_voices (Kokoro also accepts blends like af_bella:0.6+am_adam:0.4)" },
+                            "persist": { "type": "boolean", "description": "Save as the default voice (default: false)" },
+                            "confirmed": { "type": "boolean", "description": "Set to true after getting user confirmation (only needed with persist)" }
+                        },
+                        "required": ["voice"]
+                    }),
+                },
+            ],
+        },
+    );
+
+    // ---- Files (sandboxed notes folders) ----
+    groups.insert(
+        "files".into(),
+        ToolGroupDef {
+            name: "files".into(),
+            description: "Read, write, list and search text files in the allowed notes folders (4 tools)".into(),
+            always_loaded: false,
+            keywords: vec![
+                "my notes".into(), "save a note".into(), "take a note".into(), "notes folder".into(),
+                "text file".into(), "read the file".into(), "save it to a file".into(),
+                "write it to a file".into(), "my files".into(), "shopping list".into(),
+            ],
+            dependencies: vec![],
+            tools: vec![
+                ToolDef {
+                    name: "read_file".into(),
+                    description: "Read a text file (up to 1 MB) from the folders Voice Mirror allows (files.roots; by default its notes folder).".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "path": { "type": "string", "description": "Path relative to the notes folder (e.g. 'todo.md'), or an absolute path inside an allowed folder" }
+                        },
+                        "required": ["path"]
+                    }),
+                },
+                ToolDef {
+                    name: "write_file".into(),
+                    description: "Save a text file in the allowed folders, creating parent folders as needed. Overwrites by default; use mode append to add to a note.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "path": { "type": "string", "description": "Path relative to the notes folder, or an absolute path inside an allowed folder" },
+                            "content": { "type": "string", "description": "Text to write (max 1 MB)" },
+                            "mode": { "type": "string", "enum": ["overwrite", "append", "create"], "description": "overwrite (default) replaces the file; append adds to the end; create fails if the file exists" }
+                        },
+                        "required": ["path", "content"]
+                    }),
+                },
+                ToolDef {
+                    name: "list_dir".into(),
+                    description: "List a folder's files and subfolders. Omit path for the notes folder (or the allowed folders, when there are several).".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "path": { "type": "string", "description": "Folder to list (default: the notes folder)" }
+                        }
+                    }),
+                },
+                ToolDef {
+                    name: "search_files".into(),
+                    description: "Find files in the allowed folders whose name or text contains a query (case-insensitive). Returns matching files and lines.".into(),
+                    input_schema: json!({
+                        "type": "object",
+                        "properties": {
+                            "query": { "type": "string", "description": "Text to look for" },
+                            "path": { "type": "string", "description": "Only search this folder (default: all allowed folders)" },
+                            "contents": { "type": "boolean", "description": "Search inside files too, not just names (default: true)" }
+                        },
+                        "required": ["query"]
+                    }),
+                },
+            ],
+        },
+    );
+
     // ---- n8n ----
     groups.insert(
         "n8n".into(),
@@ -907,26 +1227,26 @@ fn build_all_groups() -> HashMap<String, ToolGroupDef> {
             tools: vec![
                 ToolDef { name: "n8n_search_nodes".into(), description: "Search for n8n nodes by keyword.".into(), input_schema: json!({ "type": "object", "properties": { "query": { "type": "string" }, "limit": { "type": "number" } }, "required": ["query"] }) },
                 ToolDef { name: "n8n_get_node".into(), description: "Get detailed node info.".into(), input_schema: json!({ "type": "object", "properties": { "node_type": { "type": "string" }, "detail": { "type": "string", "enum": ["minimal", "standard", "full"] } }, "required": ["node_type"] }) },
-                ToolDef { name: "n8n_list_workflows".into(), description: "List all workflows.".into(), input_schema: json!({ "type": "object", "properties": { "active_only": { "type": "boolean" } } }) },
-                ToolDef { name: "n8n_get_workflow".into(), description: "Get workflow details.".into(), input_schema: json!({ "type": "object", "properties": { "workflow_id": { "type": "string" } }, "required": ["workflow_id"] }) },
-                ToolDef { name: "n8n_create_workflow".into(), description: "Create a new workflow.".into(), input_schema: json!({ "type": "object", "properties": { "name": { "type": "string" }, "nodes": { "type": "array", "items": { "type": "object" } }, "connections": { "type": "object" } }, "required": ["name", "nodes", "connections"] }) },
-                ToolDef { name: "n8n_update_workflow".into(), description: "Update workflow via operations.".into(), input_schema: json!({ "type": "object", "properties": { "workflow_id": { "type": "string" }, "operations": { "type": "array", "items": { "type": "object" } }, "workflow_data": { "type": "object" } }, "required": ["workflow_id"] }) },
-                ToolDef { name: "n8n_delete_workflow".into(), description: "Delete a workflow by ID.".into(), input_schema: json!({ "type": "object", "properties": { "workflow_id": { "type": "string" }, "confirmed": { "type": "boolean" } }, "required": ["workflow_id"] }) },
-                ToolDef { name: "n8n_validate_workflow".into(), description: "Validate a workflow configuration.".into(), input_schema: json!({ "type": "object", "properties": { "workflow_id": { "type": "string" }, "workflow_json": { "type": "object" } } }) },
-                ToolDef { name: "n8n_trigger_workflow".into(), description: "Trigger a workflow execution.".into(), input_schema: json!({ "type": "object", "properties": { "workflow_id": { "type": "string" }, "webhook_path": { "type": "string" }, "data": { "type": "object" } }, "required": ["workflow_id"] }) },
-                ToolDef { name: "n8n_deploy_template".into(), description: "Deploy a template from n8n.io.".into(), input_schema: json!({ "type": "object", "properties": { "template_id": { "type": "number" }, "name": { "type": "string" } }, "required": ["template_id"] }) },
-                ToolDef { name: "n8n_get_executions".into(), description: "Get recent executions.".into(), input_schema: json!({ "type": "object", "properties": { "workflow_id": { "type": "string" }, "status": { "type": "string", "enum": ["success", "error", "waiting"] }, "limit": { "type": "number" } } }) },
-                ToolDef { name: "n8n_get_execution".into(), description: "Get execution details.".into(), input_schema: json!({ "type": "object", "properties": { "execution_id": { "type": "string" }, "include_data": { "type": "boolean" } }, "required": ["execution_id"] }) },
-                ToolDef { name: "n8n_delete_execution".into(), description: "Delete an execution.".into(), input_schema: json!({ "type": "object", "properties": { "execution_id": { "type": "string" }, "confirmed": { "type": "boolean" } }, "required": ["execution_id"] }) },
-                ToolDef { name: "n8n_retry_execution".into(), description: "Retry a failed execution.".into(), input_schema: json!({ "type": "object", "properties": { "execution_id": { "type": "string" }, "load_workflow": { "type": "boolean" } }, "required": ["execution_id"] }) },
-                ToolDef { name: "n8n_list_credentials".into(), description: "List credentials.".into(), input_schema: json!({ "type": "object", "properties": {} }) },
-                ToolDef { name: "n8n_create_credential".into(), description: "Create a new credential.".into(), input_schema: json!({ "type": "object", "properties": { "name": { "type": "string" }, "type": { "type": "string" }, "data": { "type": "object" } }, "required": ["name", "type"] }) },
-                ToolDef { name: "n8n_delete_credential".into(), description: "Delete a credential.".into(), input_schema: json!({ "type": "object", "properties": { "credential_id": { "type": "string" }, "confirmed": { "type": "boolean" } }, "required": ["credential_id"] }) },
-                ToolDef { name: "n8n_get_credential_schema".into(), description: "Get schema for a credential type.".into(), input_schema: json!({ "type": "object", "properties": { "credential_type": { "type": "string" } }, "required": ["credential_type"] }) },
-                ToolDef { name: "n8n_list_tags".into(), description: "List all tags.".into(), input_schema: json!({ "type": "object", "properties": {} }) },
-                ToolDef { name: "n8n_create_tag".into(), description: "Create a new tag.".into(), input_schema: json!({ "type": "object", "properties": { "name": { "type": "string" } }, "required": ["name"] }) },
-                ToolDef { name: "n8n_delete_tag".into(), description: "Delete a tag.".into(), input_schema: json!({ "type": "object", "properties": { "tag_id": { "type": "string" }, "confirmed": { "type": "boolean" } }, "required": ["tag_id"] }) },
-                ToolDef { name: "n8n_list_variables".into(), description: "List global variables.".into(), input_schema: json!({ "type": "object", "properties": {} }) },
+                ToolDef { name: "n8n_list_workflows".into(), description: "List all workflows.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "active_only": { "type": "boolean" } } }) },
+                ToolDef { name: "n8n_get_workflow".into(), description: "Get workflow details.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "workflow_id": { "type": "string" } }, "required": ["workflow_id"] }) },
+                ToolDef { name: "n8n_create_workflow".into(), description: "Create a new workflow.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "name": { "type": "string" }, "nodes": { "type": "array", "items": { "type": "object" } }, "connections": { "type": "object" } }, "required": ["name", "nodes", "connections"] }) },
+                ToolDef { name: "n8n_update_workflow".into(), description: "Update workflow via operations.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "workflow_id": { "type": "string" }, "operations": { "type": "array", "items": { "type": "object" } }, "workflow_data": { "type": "object" } }, "required": ["workflow_id"] }) },
+                ToolDef { name: "n8n_delete_workflow".into(), description: "Delete a workflow by ID.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "workflow_id": { "type": "string" }, "confirmed": { "type": "boolean" } }, "required": ["workflow_id"] }) },
+                ToolDef { name: "n8n_validate_workflow".into(), description: "Validate a workflow configuration.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "workflow_id": { "type": "string" }, "workflow_json": { "type": "object" } } }) },
+                ToolDef { name: "n8n_trigger_workflow".into(), description: "Trigger a workflow execution.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "workflow_id": { "type": "string" }, "webhook_path": { "type": "string" }, "data": { "type": "object" } }, "required": ["workflow_id"] }) },
+                ToolDef { name: "n8n_deploy_template".into(), description: "Deploy a template from n8n.io.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "template_id": { "type": "number" }, "name": { "type": "string" } }, "required": ["template_id"] }) },
+                ToolDef { name: "n8n_get_executions".into(), description: "Get recent executions.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "workflow_id": { "type": "string" }, "status": { "type": "string", "enum": ["success", "error", "waiting"] }, "limit": { "type": "number" } } }) },
+                ToolDef { name: "n8n_get_execution".into(), description: "Get execution details.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "execution_id": { "type": "string" }, "include_data": { "type": "boolean" } }, "required": ["execution_id"] }) },
+                ToolDef { name: "n8n_delete_execution".into(), description: "Delete an execution.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "execution_id": { "type": "string" }, "confirmed": { "type": "boolean" } }, "required": ["execution_id"] }) },
+                ToolDef { name: "n8n_retry_execution".into(), description: "Retry a failed execution.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "execution_id": { "type": "string" }, "load_workflow": { "type": "boolean" } }, "required": ["execution_id"] }) },
+                ToolDef { name: "n8n_list_credentials".into(), description: "List credentials.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" } } }) },
+                ToolDef { name: "n8n_create_credential".into(), description: "Create a new credential.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "name": { "type": "string" }, "type": { "type": "string" }, "data": { "type": "object" } }, "required": ["name", "type"] }) },
+                ToolDef { name: "n8n_delete_credential".into(), description: "Delete a credential.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "credential_id": { "type": "string" }, "confirmed": { "type": "boolean" } }, "required": ["credential_id"] }) },
+                ToolDef { name: "n8n_get_credential_schema".into(), description: "Get schema for a credential type.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "credential_type": { "type": "string" } }, "required": ["credential_type"] }) },
+                ToolDef { name: "n8n_list_tags".into(), description: "List all tags.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" } } }) },
+                ToolDef { name: "n8n_create_tag".into(), description: "Create a new tag.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "name": { "type": "string" } }, "required": ["name"] }) },
+                ToolDef { name: "n8n_delete_tag".into(), description: "Delete a tag.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" }, "tag_id": { "type": "string" }, "confirmed": { "type": "boolean" } }, "required": ["tag_id"] }) },
+                ToolDef { name: "n8n_list_variables".into(), description: "List global variables.".into(), input_schema: json!({ "type": "object", "properties": { "instance": { "type": "string" } } }) },
             ],
         },
     );
@@ -953,8 +1273,8 @@ mod tests {
     fn test_list_tools_default() {
         let reg = ToolRegistry::new();
         let tools = reg.list_tools();
-        // Should have core (5) + capture (11) = 16 always-loaded tools
-        assert_eq!(tools.len(), 16);
+        // Should have core (9) + capture (11) = 20 always-loaded tools
+        assert_eq!(tools.len(), 20);
     }
 
     #[test]
@@ -1035,6 +1355,27 @@ mod tests {
         assert!(reg.is_tool_loaded("list_ports"));
     }
 
+    #[test]
+    fn test_network_group() {
+        let mut reg = ToolRegistry::new();
+        assert!(!reg.is_tool_loaded("wake_on_lan"));
+        let loaded = reg.auto_load_by_intent("wake up my desktop");
+        assert!(loaded.contains(&"network".to_string()));
+        assert!(reg.is_tool_loaded("wake_on_lan"));
+        assert!(reg.is_tool_loaded("ping"));
+        assert!(reg.is_tool_loaded("port_check"));
+        assert!(reg.is_tool_loaded("discover_services"));
+    }
+
+    #[test]
+    fn test_sysinfo_group() {
+        let mut reg = ToolRegistry::new();
+        let loaded = reg.auto_load_by_intent("why is my laptop fan screaming");
+        assert!(loaded.contains(&"sysinfo".to_string()));
+        assert!(reg.is_tool_loaded("top_processes"));
+        assert!(reg.is_tool_loaded("battery_status"));
+    }
+
     #[test]
     fn test_destructive_tool_check() {
         let reg = ToolRegistry::new();
@@ -1042,4 +1383,32 @@ mod tests {
         assert!(reg.is_destructive("n8n_delete_workflow"));
         assert!(!reg.is_destructive("voice_send"));
     }
+
+    #[test]
+    fn test_voice_output_group() {
+        let mut reg = ToolRegistry::new();
+        let loaded = reg.auto_load_by_intent("can you use a different voice");
+        assert!(loaded.contains(&"voice_output".to_string()));
+        assert!(reg.is_tool_loaded("speak"));
+        assert!(reg.is_tool_loaded("set_voice"));
+    }
+
+    #[test]
+    fn test_files_group() {
+        let mut reg = ToolRegistry::new();
+        assert!(!reg.is_tool_loaded("read_file"));
+        let loaded = reg.auto_load_by_intent("add eggs to my shopping list");
+        assert!(loaded.contains(&"files".to_string()));
+        assert!(reg.is_tool_loaded("write_file"));
+        assert!(reg.is_tool_loaded("search_files"));
+    }
+
+    #[test]
+    fn test_only_persistent_voice_change_needs_confirmation() {
+        let reg = ToolRegistry::new();
+        assert!(reg.needs_confirmation("memory_forget", &json!({})));
+        assert!(!reg.needs_confirmation("set_voice", &json!({ "voice": "am_adam" })));
+        assert!(reg.needs_confirmation("set_voice", &json!({ "voice": "am_adam", "persist": true })));
+        assert!(!reg.needs_confirmation("speak", &json!({ "text": "hi", "persist": true })));
+    }
 }
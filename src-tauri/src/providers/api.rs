@@ -21,6 +21,17 @@
 //! The actual tool execution is handled by the caller (MCP module). After
 //! execution, the caller calls `inject_tool_results()` to add the results
 //! to the conversation and trigger a follow-up API call.
+//!
+//! Each call and each injected result is also sent as a
+//! `ProviderEvent::ToolPlanStep`, with its round out of `MAX_TOOL_ITERATIONS`,
+//! so a long tool loop shows up as progress rather than silence.
+//!
+//! ## Offline Queue
+//!
+//! If the endpoint can't be reached at all, the request is held in an
+//! [`OfflineQueue`](super::offline_queue) instead of failing. The user hears
+//! that the message is queued, and it is sent automatically once the
+//! endpoint answers again.
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -29,13 +40,17 @@ use reqwest::Client;
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::{debug, info, warn};
 
+use super::offline_queue::{self, OfflineQueue};
+use super::response_style::{self, ResponseStyle};
 use super::tool_calling::{
-    self, ToolCallAccumulator, ToolCallRequest, ToolDefinition, ToolResult,
+    self, ToolCallAccumulator, ToolCallRequest, ToolDefinition, ToolPlanPhase, ToolPlanStep,
+    ToolResult,
 };
 use super::{Provider, ProviderConfig, ProviderEvent};
+use crate::services::metrics::{self, Counter};
 
 /// Default endpoints for known providers.
-fn default_endpoint(provider_type: &str) -> &'static str {
+pub(crate) fn default_endpoint(provider_type: &str) -> &'static str {
     match provider_type {
         "ollama" => "http://127.0.0.1:11434",
         "lmstudio" => "http://127.0.0.1:1234",
@@ -90,6 +105,32 @@ const MAX_HISTORY_MESSAGES: usize = 20;
 /// Prevents infinite loops if the model keeps calling tools.
 const MAX_TOOL_ITERATIONS: usize = 10;
 
+/// Number of `role: "system"` messages at the start of `messages`.
+fn leading_system_count(messages: &[serde_json::Value]) -> usize {
+    messages
+        .iter()
+        .take_while(|m| m.get("role").and_then(|r| r.as_str()) == Some("system"))
+        .count()
+}
+
+/// Copy of `history` with the runtime context block as a system message.
+///
+/// Inserted just before the latest user message: earlier history stays a
+/// stable prefix (server-side prompt caches keep working), and tool
+/// follow-ups never get a message between `tool_calls` and their results.
+/// The block is per-request and never enters the stored history.
+fn with_runtime_context(history: &[serde_json::Value], block: String) -> Vec<serde_json::Value> {
+    let at = history
+        .iter()
+        .rposition(|m| m["role"] == "user")
+        .unwrap_or(history.len());
+    let mut messages = Vec::with_capacity(history.len() + 1);
+    messages.extend_from_slice(&history[..at]);
+    messages.push(serde_json::json!({ "role": "system", "content": block }));
+    messages.extend_from_slice(&history[at..]);
+    messages
+}
+
 /// The result of a streaming HTTP request.
 ///
 /// Contains both the accumulated text response and any tool call data
@@ -102,6 +143,32 @@ struct StreamResult {
     tool_call_request: Option<ToolCallRequest>,
 }
 
+/// Why a streaming request failed.
+enum RequestError {
+    /// The endpoint couldn't be reached at all; the request can be queued.
+    Unreachable(String),
+    /// Any other failure, including HTTP error statuses.
+    Failed(String),
+}
+
+/// Everything a spawned request needs besides its body.
+#[derive(Clone)]
+struct RequestContext {
+    client: Client,
+    url: String,
+    base_url: String,
+    api_key: Option<String>,
+    event_tx: UnboundedSender<ProviderEvent>,
+    abort_flag: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+    offline: Arc<OfflineQueue>,
+    provider_type: String,
+    /// Tool round this request answers (1 = the user message itself).
+    tool_iteration: usize,
+    tools_enabled: bool,
+    native_tools: bool,
+}
+
 /// The API provider implementation.
 ///
 /// Communicates with OpenAI-compatible APIs using SSE streaming.
@@ -146,6 +213,10 @@ pub struct ApiProvider {
     tools: Vec<ToolDefinition>,
     /// Current tool iteration counter (reset on each user message).
     current_tool_iteration: usize,
+    /// Response style for the current turn (chosen on each user message).
+    response_style: ResponseStyle,
+    /// Request held back while the provider is unreachable.
+    offline: Arc<OfflineQueue>,
 }
 
 impl ApiProvider {
@@ -190,6 +261,8 @@ impl ApiProvider {
             _stream_handle: None,
             tools: Vec::new(),
             current_tool_iteration: 0,
+            response_style: ResponseStyle::Detailed,
+            offline: Arc::new(OfflineQueue::default()),
         }
     }
 
@@ -219,6 +292,20 @@ impl ApiProvider {
         tool_calling::supports_native_tools(&self.provider_type_id)
     }
 
+    /// Choose the response style for a new user turn from the
+    /// `ai.responseStyle` setting, voice state, and any length request in `text`.
+    fn select_response_style(&mut self, text: &str) {
+        let setting = crate::commands::config::get_config_snapshot()
+            .ai
+            .response_style;
+        self.response_style = response_style::resolve(
+            &setting,
+            crate::services::runtime_context::voice_active(),
+            response_style::requested_style(text),
+        );
+        debug!(style = ?self.response_style, "Response style selected");
+    }
+
     /// Check if tools are enabled and available.
     fn tools_enabled(&self) -> bool {
         !self.tools.is_empty()
@@ -234,6 +321,8 @@ impl ApiProvider {
     ///
     /// For native tool calling, results use the standard `role: "tool"` format.
     /// For text-parsing fallback, results are injected as `role: "user"` messages.
+    ///
+    /// Each result is also reported as a `Result` plan step.
     pub fn inject_tool_results(&mut self, results: Vec<ToolResult>) {
         if !self.running.load(Ordering::SeqCst) {
             let _ = self
@@ -242,6 +331,19 @@ impl ApiProvider {
             return;
         }
 
+        for result in &results {
+            let step = ToolPlanStep {
+                // The caller counted this round with `check_tool_iteration_limit`
+                iteration: self.current_tool_iteration.max(1),
+                max_iterations: MAX_TOOL_ITERATIONS,
+                tool: self.tool_name_for_call(&result.tool_call_id),
+                tool_call_id: result.tool_call_id.clone(),
+                phase: ToolPlanPhase::Result,
+                summary: tool_calling::summarize_for_plan(&result.content),
+            };
+            let _ = self.event_tx.send(ProviderEvent::ToolPlanStep(step));
+        }
+
         if self.supports_native_tools() {
             // Native path: add role:"tool" messages with tool_call_id
             for result in &results {
@@ -312,13 +414,14 @@ impl ApiProvider {
             }));
             // Reset tool iteration counter for new user input
             self.current_tool_iteration = 0;
+            self.select_response_style(&text);
         }
 
         self.send_message_internal(false);
     }
 
     /// Internal message sending — shared by `send_message()` and `inject_tool_results()`.
-    fn send_message_internal(&mut self, _is_tool_follow_up: bool) {
+    fn send_message_internal(&mut self, is_tool_follow_up: bool) {
         // Limit history to prevent context overflow
         self.limit_message_history();
 
@@ -342,9 +445,16 @@ impl ApiProvider {
             self.messages.len()
         );
 
+        let context = format!(
+            "{}\n{}",
+            crate::services::runtime_context::build_block(!is_tool_follow_up),
+            self.response_style.instruction()
+        );
+        let messages = with_runtime_context(&self.messages, context);
+
         let mut body = serde_json::json!({
             "model": model,
-            "messages": self.messages,
+            "messages": messages,
             "stream": true,
         });
 
@@ -353,6 +463,10 @@ impl ApiProvider {
             body["options"] = serde_json::json!({ "num_ctx": self.context_length });
         }
 
+        if let Some(max_tokens) = self.response_style.max_tokens() {
+            body["max_tokens"] = serde_json::json!(max_tokens);
+        }
+
         // Add native tool definitions for cloud providers
         let use_native_tools = self.tools_enabled() && self.supports_native_tools();
         if use_native_tools {
@@ -360,88 +474,187 @@ impl ApiProvider {
             body["tool_choice"] = serde_json::json!("auto");
         }
 
+        let ctx = RequestContext {
+            client: self.client.clone(),
+            url,
+            base_url: self.base_url.clone(),
+            api_key: self.api_key.clone(),
+            event_tx: self.event_tx.clone(),
+            abort_flag: self.abort_flag.clone(),
+            running: self.running.clone(),
+            offline: self.offline.clone(),
+            provider_type: self.provider_type_id.clone(),
+            tool_iteration: self.current_tool_iteration + 1,
+            tools_enabled: self.tools_enabled(),
+            native_tools: use_native_tools,
+        };
+
+        // Still offline: the new message joins the held request instead
+        if self.offline.is_offline() {
+            Self::hold_offline(&ctx, body, usize::from(!is_tool_follow_up));
+            return;
+        }
+
         // Reset abort flag
         self.abort_flag.store(false, Ordering::SeqCst);
-        let abort_flag = self.abort_flag.clone();
-        let event_tx = self.event_tx.clone();
-        let client = self.client.clone();
-        let api_key = self.api_key.clone();
-        let running = self.running.clone();
-        let provider_type = self.provider_type_id.clone();
-        let tools_enabled = self.tools_enabled();
-        let native_tools = use_native_tools;
 
         // Spawn an async task to handle the streaming response.
         // Use `tauri::async_runtime::spawn` instead of bare `tokio::spawn` —
         // Tauri's spawn uses a globally-stored runtime handle that works from
         // any thread, whereas `tokio::spawn` panics when called from Tauri's
         // sync command handlers (which run outside the tokio reactor context).
-        let handle = tauri::async_runtime::spawn(async move {
-            let result = Self::stream_request(
-                &client,
-                &url,
-                api_key.as_deref(),
-                body,
-                &event_tx,
-                &abort_flag,
-                native_tools,
-            )
-            .await;
-
-            match result {
-                Ok(stream_result) => {
-                    // --- Native tool calling path ---
-                    if let Some(tc_request) = stream_result.tool_call_request {
+        let new_messages = usize::from(!is_tool_follow_up);
+        let handle = tauri::async_runtime::spawn(Self::run_request(ctx, body, new_messages));
+
+        self._stream_handle = Some(handle);
+    }
+
+    /// Send one request and turn the outcome into provider events.
+    ///
+    /// `new_messages` is how many queued user messages `body` carries if it
+    /// has to be held for later.
+    async fn run_request(ctx: RequestContext, body: serde_json::Value, new_messages: usize) {
+        let RequestContext {
+            client,
+            url,
+            api_key,
+            event_tx,
+            abort_flag,
+            running,
+            provider_type,
+            tool_iteration,
+            tools_enabled,
+            native_tools,
+            ..
+        } = &ctx;
+        let result = Self::stream_request(
+            client,
+            url,
+            api_key.as_deref(),
+            &body,
+            event_tx,
+            abort_flag,
+            *native_tools,
+        )
+        .await;
+
+        match result {
+            Ok(stream_result) => {
+                // --- Native tool calling path ---
+                if let Some(tc_request) = stream_result.tool_call_request {
+                    info!(
+                        "Native tool calls detected: {} calls",
+                        tc_request.calls.len()
+                    );
+                    Self::send_plan_steps(event_tx, &tc_request, *tool_iteration);
+                    let _ = event_tx.send(ProviderEvent::ToolCalls(tc_request));
+                    return;
+                }
+
+                // --- Text-parsing fallback path (local providers) ---
+                if *tools_enabled
+                    && !tool_calling::supports_native_tools(provider_type)
+                    && !stream_result.full_response.is_empty()
+                {
+                    if let Some(parsed_call) =
+                        tool_calling::parse_tool_call_from_text(&stream_result.full_response)
+                    {
                         info!(
-                            "Native tool calls detected: {} calls",
-                            tc_request.calls.len()
+                            "Text-parsed tool call detected: {}",
+                            parsed_call.name
                         );
+                        let tc_request = ToolCallRequest {
+                            calls: vec![parsed_call],
+                            response_text: stream_result.full_response.clone(),
+                            raw_tool_calls: Vec::new(),
+                        };
+                        Self::send_plan_steps(event_tx, &tc_request, *tool_iteration);
                         let _ = event_tx.send(ProviderEvent::ToolCalls(tc_request));
                         return;
                     }
+                }
 
-                    // --- Text-parsing fallback path (local providers) ---
-                    if tools_enabled
-                        && !tool_calling::supports_native_tools(&provider_type)
-                        && !stream_result.full_response.is_empty()
-                    {
-                        if let Some(parsed_call) =
-                            tool_calling::parse_tool_call_from_text(&stream_result.full_response)
-                        {
-                            info!(
-                                "Text-parsed tool call detected: {}",
-                                parsed_call.name
-                            );
-                            let tc_request = ToolCallRequest {
-                                calls: vec![parsed_call],
-                                response_text: stream_result.full_response.clone(),
-                                raw_tool_calls: Vec::new(),
-                            };
-                            let _ = event_tx.send(ProviderEvent::ToolCalls(tc_request));
-                            return;
-                        }
-                    }
-
-                    // --- Normal response (no tool calls) ---
-                    if !stream_result.full_response.is_empty() {
-                        let _ = event_tx.send(ProviderEvent::StreamEnd(
-                            stream_result.full_response.clone(),
-                        ));
-                        let _ =
-                            event_tx.send(ProviderEvent::Response(stream_result.full_response));
-                    }
+                // --- Normal response (no tool calls) ---
+                if !stream_result.full_response.is_empty() {
+                    let _ = event_tx.send(ProviderEvent::StreamEnd(
+                        stream_result.full_response.clone(),
+                    ));
+                    let _ = event_tx.send(ProviderEvent::Response(stream_result.full_response));
                 }
-                Err(e) => {
-                    if abort_flag.load(Ordering::SeqCst) {
-                        let _ = event_tx.send(ProviderEvent::Output("[Cancelled]\n".to_string()));
-                    } else if running.load(Ordering::SeqCst) {
-                        let _ = event_tx.send(ProviderEvent::Error(e));
-                    }
+            }
+            Err(_) if abort_flag.load(Ordering::SeqCst) => {
+                let _ = event_tx.send(ProviderEvent::Output("[Cancelled]\n".to_string()));
+            }
+            Err(RequestError::Unreachable(e)) if running.load(Ordering::SeqCst) => {
+                warn!("Provider unreachable, queueing request: {}", e);
+                Self::hold_offline(&ctx, body, new_messages);
+            }
+            Err(RequestError::Unreachable(e) | RequestError::Failed(e)) => {
+                if running.load(Ordering::SeqCst) {
+                    let _ = event_tx.send(ProviderEvent::Error(e));
                 }
             }
-        });
+        }
+    }
 
-        self._stream_handle = Some(handle);
+    /// Report the calls in `request` as `Call` steps of tool round `iteration`.
+    fn send_plan_steps(
+        event_tx: &UnboundedSender<ProviderEvent>,
+        request: &ToolCallRequest,
+        iteration: usize,
+    ) {
+        for step in tool_calling::plan_call_steps(request, iteration, MAX_TOOL_ITERATIONS) {
+            let _ = event_tx.send(ProviderEvent::ToolPlanStep(step));
+        }
+    }
+
+    /// Queue `body` for when the provider is reachable again, tell the user
+    /// if it adds messages, and start probing if nothing is probing yet.
+    fn hold_offline(ctx: &RequestContext, body: serde_json::Value, new_messages: usize) {
+        let (queued, start_probe) = ctx.offline.hold(body, new_messages);
+        if new_messages > 0 {
+            let notice = offline_queue::queued_notice(queued);
+            let _ = ctx
+                .event_tx
+                .send(ProviderEvent::Output(format!("[Offline] {}\n", notice)));
+            // Spoken by the frontend like any other response
+            let _ = ctx.event_tx.send(ProviderEvent::Response(notice));
+        }
+        if start_probe {
+            tauri::async_runtime::spawn(Self::flush_when_online(ctx.clone()));
+        }
+    }
+
+    /// Probe the endpoint with backoff and send the held request once it
+    /// answers. Ends early if the provider stops or the queue is cleared.
+    async fn flush_when_online(ctx: RequestContext) {
+        let mut attempt = 0;
+        loop {
+            tokio::time::sleep(offline_queue::probe_delay(attempt)).await;
+            attempt += 1;
+            if !ctx.running.load(Ordering::SeqCst) || !ctx.offline.is_offline() {
+                return;
+            }
+            if !offline_queue::is_reachable(&ctx.client, &ctx.base_url).await {
+                debug!(attempt, "Provider still unreachable");
+                continue;
+            }
+            let Some((body, queued)) = ctx.offline.take() else {
+                return;
+            };
+            info!(queued, "Provider reachable again, sending queued request");
+            if queued > 0 {
+                let _ = ctx.event_tx.send(ProviderEvent::Output(format!(
+                    "[Online] Sending {} queued message{}\n",
+                    queued,
+                    if queued == 1 { "" } else { "s" }
+                )));
+            }
+            ctx.abort_flag.store(false, Ordering::SeqCst);
+            // If it fails to connect again, the body goes back in the queue
+            Self::run_request(ctx, body, queued).await;
+            return;
+        }
     }
 
     /// Execute the streaming HTTP request and parse SSE events.
@@ -452,11 +665,11 @@ impl ApiProvider {
         client: &Client,
         url: &str,
         api_key: Option<&str>,
-        body: serde_json::Value,
+        body: &serde_json::Value,
         event_tx: &UnboundedSender<ProviderEvent>,
         abort_flag: &AtomicBool,
         accumulate_tools: bool,
-    ) -> Result<StreamResult, String> {
+    ) -> Result<StreamResult, RequestError> {
         let mut request = client
             .post(url)
             .header("Content-Type", "application/json")
@@ -466,11 +679,14 @@ impl ApiProvider {
             request = request.header("Authorization", format!("Bearer {}", key));
         }
 
-        let response = request
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| format!("HTTP request failed: {}", e))?;
+        let response = request.json(body).send().await.map_err(|e| {
+            let msg = format!("HTTP request failed: {}", e);
+            if offline_queue::is_unreachable(&e) {
+                RequestError::Unreachable(msg)
+            } else {
+                RequestError::Failed(msg)
+            }
+        })?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -478,13 +694,18 @@ impl ApiProvider {
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("HTTP {}: {}", status, error_text));
+            return Err(RequestError::Failed(format!(
+                "HTTP {}: {}",
+                status, error_text
+            )));
         }
 
         // Read the streaming response
         let mut full_response = String::new();
         let mut tool_accumulator = ToolCallAccumulator::new();
         let mut finish_reason: Option<String> = None;
+        let mut streamed_deltas = 0u64;
+        let mut usage: Option<(u64, u64)> = None;
         let mut stream = response.bytes_stream();
 
         use futures_util::StreamExt;
@@ -493,10 +714,11 @@ impl ApiProvider {
         while let Some(chunk_result) = stream.next().await {
             // Check abort
             if abort_flag.load(Ordering::SeqCst) {
-                return Err("Aborted".to_string());
+                return Err(RequestError::Failed("Aborted".to_string()));
             }
 
-            let chunk = chunk_result.map_err(|e| format!("Stream read error: {}", e))?;
+            let chunk = chunk_result
+                .map_err(|e| RequestError::Failed(format!("Stream read error: {}", e)))?;
             let text = String::from_utf8_lossy(&chunk);
 
             // Prepend any leftover from the previous chunk
@@ -535,10 +757,17 @@ impl ApiProvider {
                             .and_then(|c| c.as_str())
                         {
                             full_response.push_str(content);
+                            streamed_deltas += 1;
                             let _ =
                                 event_tx.send(ProviderEvent::StreamToken(content.to_string()));
                         }
 
+                        // Token counts, sent by most servers in the last chunk
+                        if let Some(u) = parsed.get("usage").filter(|u| u.is_object()) {
+                            let count = |key: &str| u.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+                            usage = Some((count("prompt_tokens"), count("completion_tokens")));
+                        }
+
                         // Accumulate native tool call deltas
                         if accumulate_tools {
                             if let Some(delta_tool_calls) = choice
@@ -568,6 +797,10 @@ impl ApiProvider {
             }
         }
 
+        let (prompt_tokens, completion_tokens) = usage.unwrap_or((0, streamed_deltas));
+        metrics::add(Counter::ProviderPromptTokens, prompt_tokens);
+        metrics::add(Counter::ProviderCompletionTokens, completion_tokens);
+
         // Build tool call request if the model requested tool execution
         let has_native_tool_calls = tool_accumulator.has_calls()
             && matches!(
@@ -604,11 +837,7 @@ impl ApiProvider {
         }
 
         // Count system messages at the start
-        let system_end = self
-            .messages
-            .iter()
-            .take_while(|m| m.get("role").and_then(|r| r.as_str()) == Some("system"))
-            .count();
+        let system_end = leading_system_count(&self.messages);
 
         let non_system_count = self.messages.len() - system_end;
         let keep = MAX_HISTORY_MESSAGES.min(non_system_count);
@@ -667,6 +896,21 @@ impl ApiProvider {
         self.messages.push(msg);
     }
 
+    /// Name of the tool called as `tool_call_id` by the latest assistant
+    /// message. Text-parsed calls aren't in the history, so they come back
+    /// as `"tool"`.
+    fn tool_name_for_call(&self, tool_call_id: &str) -> String {
+        self.messages
+            .iter()
+            .rev()
+            .find(|m| m["role"] == "assistant")
+            .and_then(|m| m["tool_calls"].as_array())
+            .and_then(|calls| calls.iter().find(|c| c["id"] == tool_call_id))
+            .and_then(|c| c["function"]["name"].as_str())
+            .unwrap_or("tool")
+            .to_string()
+    }
+
     /// Check if the max tool iteration limit has been reached.
     pub fn check_tool_iteration_limit(&mut self) -> bool {
         self.current_tool_iteration += 1;
@@ -737,6 +981,7 @@ impl ApiProvider {
             "content": content_parts
         }));
         self.current_tool_iteration = 0;
+        self.select_response_style(&text);
 
         self.send_message_internal(false);
     }
@@ -779,6 +1024,7 @@ impl ApiProvider {
             "content": content_parts
         }));
         self.current_tool_iteration = 0;
+        self.select_response_style(&text);
 
         self.send_message_internal(false);
     }
@@ -821,6 +1067,7 @@ impl Provider for ApiProvider {
     fn stop(&mut self) {
         self.abort_flag.store(true, Ordering::SeqCst);
         self.running.store(false, Ordering::SeqCst);
+        self.offline.clear();
         self.messages.clear();
         self.tools.clear();
         self.current_tool_iteration = 0;
@@ -866,8 +1113,34 @@ impl Provider for ApiProvider {
 
     fn interrupt(&mut self) {
         self.abort_flag.store(true, Ordering::SeqCst);
+        // Queued messages stay in history but aren't sent on their own
+        self.offline.clear();
         if let Some(handle) = self._stream_handle.take() {
             handle.abort();
         }
     }
+
+    fn history(&self) -> Vec<serde_json::Value> {
+        // The leading system prompt comes from config, not the conversation
+        self.messages[leading_system_count(&self.messages)..].to_vec()
+    }
+
+    fn restore_history(&mut self, messages: Vec<serde_json::Value>) -> bool {
+        if !self.running.load(Ordering::SeqCst) {
+            return false;
+        }
+        // Drop whatever is in flight: its reply belongs to the old history
+        self.interrupt();
+        let system_end = leading_system_count(&self.messages);
+        self.messages.truncate(system_end);
+        let skip = leading_system_count(&messages);
+        self.messages.extend(messages.into_iter().skip(skip));
+        self.current_tool_iteration = 0;
+        self.limit_message_history();
+        info!(
+            messages = self.messages.len() - system_end,
+            "Restored conversation history for {}", self.display_name_str
+        );
+        true
+    }
 }
@@ -34,7 +34,7 @@ pub fn write_mcp_config(
     let mcp_binary = resolve_mcp_binary(project_root)?;
     let binary_path_str = mcp_binary.to_string_lossy().replace('\\', "/");
 
-    // Resolve the MCP data directory — this is where inbox.json, status.json, etc. live.
+    // Resolve the MCP data directory — this is where inbox.db, status.json, etc. live.
     let mcp_data_dir = get_mcp_data_dir_for_env();
     let mcp_data_dir_str = mcp_data_dir.to_string_lossy().replace('\\', "/");
 
@@ -242,7 +242,7 @@ pub fn write_mcp_config(
 /// Get the MCP data directory path that the Node.js MCP server should use.
 ///
 /// This matches the path used by `inbox_watcher.rs::get_mcp_data_dir()` —
-/// both must agree on where inbox.json lives.
+/// both must agree on where inbox.db lives.
 ///
 /// Currently: `{config_dir}/voice-mirror/data/`
 pub fn get_mcp_data_dir_for_env() -> PathBuf {
@@ -115,6 +115,8 @@ impl AiManager {
         // Bump generation FIRST — invalidates output from dying provider
         self.generation.fetch_add(1, Ordering::SeqCst);
         self.starting = false;
+        // The session prompt addendum only lasts for the session
+        crate::services::runtime_context::set_session_prompt(None);
 
         if let Some(ref mut provider) = self.provider {
             if provider.is_running() {
@@ -246,6 +248,25 @@ impl AiManager {
         })
     }
 
+    /// Conversation history of the running provider (empty when nothing is
+    /// running or the provider keeps none).
+    pub fn history(&self) -> Vec<serde_json::Value> {
+        match self.provider {
+            Some(ref provider) if provider.is_running() => provider.history(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replace the running provider's conversation history.
+    ///
+    /// Returns false if no provider is running or it keeps no history.
+    pub fn restore_history(&mut self, messages: Vec<serde_json::Value>) -> bool {
+        match self.provider {
+            Some(ref mut provider) if provider.is_running() => provider.restore_history(messages),
+            _ => false,
+        }
+    }
+
     /// Send the voice listen loop command to CLI agents.
     ///
     /// Delegates to the provider's `send_voice_loop` implementation,
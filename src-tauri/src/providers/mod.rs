@@ -2,7 +2,10 @@ pub mod api;
 pub mod cli;
 pub mod dictation;
 pub mod manager;
+pub mod offline_queue;
+pub mod response_style;
 pub mod tool_calling;
+pub mod turn_queue;
 
 use std::collections::HashMap;
 use std::fmt;
@@ -39,6 +42,9 @@ pub enum ProviderEvent {
     /// Contains the request payload with tool calls, assistant text, and
     /// raw tool call data needed for conversation history injection.
     ToolCalls(tool_calling::ToolCallRequest),
+    /// Progress through a tool loop: a call the model made or a result
+    /// sent back to it (API providers).
+    ToolPlanStep(tool_calling::ToolPlanStep),
 }
 
 impl fmt::Display for ProviderEvent {
@@ -52,6 +58,7 @@ impl fmt::Display for ProviderEvent {
             ProviderEvent::StreamEnd(s) => write!(f, "StreamEnd({} bytes)", s.len()),
             ProviderEvent::Response(s) => write!(f, "Response({} bytes)", s.len()),
             ProviderEvent::ToolCalls(req) => write!(f, "ToolCalls({} calls)", req.calls.len()),
+            ProviderEvent::ToolPlanStep(step) => write!(f, "ToolPlanStep({})", step.tool),
         }
     }
 }
@@ -120,6 +127,20 @@ pub trait Provider: Send {
         );
         self.send_input(&prompt);
     }
+
+    /// Conversation history, for session snapshots.
+    ///
+    /// Default: empty. CLI providers keep theirs inside the agent process.
+    fn history(&self) -> Vec<serde_json::Value> {
+        Vec::new()
+    }
+
+    /// Replace the conversation history with one from a session snapshot.
+    ///
+    /// Default: returns false (the provider keeps no history of its own).
+    fn restore_history(&mut self, _messages: Vec<serde_json::Value>) -> bool {
+        false
+    }
 }
 
 /// Create a provider instance based on the provider type.
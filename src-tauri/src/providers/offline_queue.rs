@@ -0,0 +1,146 @@
+//! Offline queue for API providers.
+//!
+//! When a request can't reach the provider at all (connection refused, DNS
+//! failure, no route), the user's message is kept instead of failing with an
+//! error. The provider tells the user out loud, probes the endpoint in the
+//! background, and sends the request as soon as the endpoint answers again.
+//!
+//! Messages sent while offline are already in the conversation history, so
+//! each one replaces the held request body with a newer one that carries all
+//! of them. Once the provider is back they go out together, in order, as a
+//! single request.
+//!
+//! HTTP error statuses (401, 429, 500, ...) mean the provider was reached and
+//! still surface as errors.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::Client;
+
+/// Delay before the first connectivity probe.
+const FIRST_PROBE_DELAY: Duration = Duration::from_secs(2);
+/// Probes back off to at most this interval.
+const MAX_PROBE_DELAY: Duration = Duration::from_secs(30);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct State {
+    /// Request body to send once the provider is reachable again.
+    body: Option<serde_json::Value>,
+    /// User messages waiting in `body`.
+    queued: usize,
+    /// Whether a probe loop is running.
+    probing: bool,
+}
+
+/// The request held back while the provider is unreachable.
+#[derive(Default)]
+pub(crate) struct OfflineQueue {
+    state: Mutex<State>,
+}
+
+impl OfflineQueue {
+    /// Whether a request is waiting for connectivity.
+    pub(crate) fn is_offline(&self) -> bool {
+        self.state.lock().map(|s| s.body.is_some()).unwrap_or(false)
+    }
+
+    /// Hold `body` until the provider is back, replacing any older body.
+    /// `new_messages` is how many user messages it adds to the queue (none
+    /// for tool follow-ups). Returns the queued count and whether the caller
+    /// should start a probe loop.
+    pub(crate) fn hold(&self, body: serde_json::Value, new_messages: usize) -> (usize, bool) {
+        let Ok(mut state) = self.state.lock() else {
+            return (0, false);
+        };
+        state.body = Some(body);
+        state.queued += new_messages;
+        let start_probe = !state.probing;
+        state.probing = true;
+        (state.queued, start_probe)
+    }
+
+    /// Take the held body and its queued message count, ending the probe
+    /// loop. `None` if the queue was cleared in the meantime.
+    pub(crate) fn take(&self) -> Option<(serde_json::Value, usize)> {
+        let mut state = self.state.lock().ok()?;
+        state.probing = false;
+        let queued = std::mem::take(&mut state.queued);
+        state.body.take().map(|body| (body, queued))
+    }
+
+    /// Drop the held request (provider stopped or interrupted).
+    pub(crate) fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = State::default();
+        }
+    }
+}
+
+/// Whether a failed `send()` means the endpoint couldn't be reached at all.
+pub(crate) fn is_unreachable(err: &reqwest::Error) -> bool {
+    err.is_connect()
+}
+
+/// Wait before probe number `attempt` (0-based): 2 s, doubling up to 30 s.
+pub(crate) fn probe_delay(attempt: u32) -> Duration {
+    FIRST_PROBE_DELAY
+        .saturating_mul(1 << attempt.min(5))
+        .min(MAX_PROBE_DELAY)
+}
+
+/// Any HTTP response at all, even a 404, means the endpoint is back.
+pub(crate) async fn is_reachable(client: &Client, base_url: &str) -> bool {
+    client
+        .get(base_url)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .is_ok()
+}
+
+/// What the user hears when a message is queued.
+pub(crate) fn queued_notice(queued: usize) -> String {
+    match queued {
+        0 | 1 => "I can't reach the AI provider right now. \
+                  I'll send your message as soon as the connection is back."
+            .to_string(),
+        n => format!("Still offline. {} messages are waiting to be sent.", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hold_and_take() {
+        let queue = OfflineQueue::default();
+        assert!(!queue.is_offline());
+
+        assert_eq!(queue.hold(json!({"n": 1}), 1), (1, true));
+        // Newer body replaces the old one; probe loop already running
+        assert_eq!(queue.hold(json!({"n": 2}), 1), (2, false));
+        assert!(queue.is_offline());
+
+        assert_eq!(queue.take(), Some((json!({"n": 2}), 2)));
+        assert!(!queue.is_offline());
+        assert_eq!(queue.take(), None);
+
+        // A fresh outage starts a new probe loop
+        assert_eq!(queue.hold(json!({"n": 3}), 0), (0, true));
+        queue.clear();
+        assert_eq!(queue.take(), None);
+    }
+
+    #[test]
+    fn test_probe_delay_and_notice() {
+        assert_eq!(probe_delay(0), Duration::from_secs(2));
+        assert_eq!(probe_delay(1), Duration::from_secs(4));
+        assert_eq!(probe_delay(10), MAX_PROBE_DELAY);
+        assert!(queued_notice(1).contains("as soon as the connection is back"));
+        assert!(queued_notice(3).contains("3 messages"));
+    }
+}
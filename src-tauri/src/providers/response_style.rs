@@ -0,0 +1,133 @@
+//! Response style presets for API providers.
+//!
+//! A reply that will be spoken should be short and free of markdown; a reply
+//! read on screen can be long and formatted. The `ai.responseStyle` setting
+//! picks a preset ("concise", "detailed") or "auto", which chooses per turn
+//! from whether the voice engine is running. A spoken request such as "give
+//! me the long version" overrides the preset for that turn.
+
+/// A response style preset: prompt instruction plus output token cap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseStyle {
+    /// "Concise voice": a few short sentences suitable for TTS.
+    Concise,
+    /// "Detailed on-screen": thorough answer, markdown allowed.
+    Detailed,
+}
+
+/// Token cap for concise replies. Leaves room for a tool call.
+const CONCISE_MAX_TOKENS: u32 = 400;
+
+/// Phrases asking for a longer answer this turn.
+const LONGER_PHRASES: &[&str] = &[
+    "long version",
+    "longer version",
+    "detailed version",
+    "full version",
+    "more detail",
+    "in detail",
+    "elaborate",
+    "tell me more",
+];
+
+/// Phrases asking for a shorter answer this turn.
+const SHORTER_PHRASES: &[&str] = &[
+    "short version",
+    "shorter version",
+    "keep it short",
+    "briefly",
+    "in short",
+    "tl;dr",
+    "tldr",
+];
+
+impl ResponseStyle {
+    /// Instruction appended to the per-request context block.
+    pub fn instruction(self) -> &'static str {
+        match self {
+            Self::Concise => {
+                "Response style: concise. Your reply will be spoken aloud. Answer in one to \
+                 three short sentences of plain text: no markdown, lists, tables, or code \
+                 blocks. Offer to go into detail if the topic needs it."
+            }
+            Self::Detailed => {
+                "Response style: detailed. Your reply will be read on screen. Be thorough; \
+                 markdown, lists, and code blocks are fine."
+            }
+        }
+    }
+
+    /// `max_tokens` for the request, if the preset caps output.
+    pub fn max_tokens(self) -> Option<u32> {
+        match self {
+            Self::Concise => Some(CONCISE_MAX_TOKENS),
+            Self::Detailed => None,
+        }
+    }
+}
+
+/// Detect a spoken/typed request for a different length ("give me the long version").
+pub fn requested_style(text: &str) -> Option<ResponseStyle> {
+    let lower = text.to_lowercase();
+    if LONGER_PHRASES.iter().any(|p| lower.contains(p)) {
+        Some(ResponseStyle::Detailed)
+    } else if SHORTER_PHRASES.iter().any(|p| lower.contains(p)) {
+        Some(ResponseStyle::Concise)
+    } else {
+        None
+    }
+}
+
+/// Pick the style for a turn from the `ai.responseStyle` setting, whether
+/// the reply will be spoken, and any per-turn request in the user's text.
+pub fn resolve(setting: &str, spoken: bool, requested: Option<ResponseStyle>) -> ResponseStyle {
+    if let Some(style) = requested {
+        return style;
+    }
+    match setting {
+        "concise" => ResponseStyle::Concise,
+        "detailed" => ResponseStyle::Detailed,
+        _ if spoken => ResponseStyle::Concise,
+        _ => ResponseStyle::Detailed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_follows_voice() {
+        assert_eq!(resolve("auto", true, None), ResponseStyle::Concise);
+        assert_eq!(resolve("auto", false, None), ResponseStyle::Detailed);
+        assert_eq!(resolve("bogus", false, None), ResponseStyle::Detailed);
+    }
+
+    #[test]
+    fn test_fixed_presets_ignore_voice() {
+        assert_eq!(resolve("concise", false, None), ResponseStyle::Concise);
+        assert_eq!(resolve("detailed", true, None), ResponseStyle::Detailed);
+    }
+
+    #[test]
+    fn test_voice_command_overrides_preset() {
+        let long = requested_style("Okay, give me the long version");
+        assert_eq!(long, Some(ResponseStyle::Detailed));
+        assert_eq!(resolve("concise", true, long), ResponseStyle::Detailed);
+
+        let short = requested_style("Just the short version please");
+        assert_eq!(short, Some(ResponseStyle::Concise));
+        assert_eq!(resolve("detailed", false, short), ResponseStyle::Concise);
+
+        assert_eq!(requested_style("What's the weather?"), None);
+    }
+
+    #[test]
+    fn test_max_tokens() {
+        assert_eq!(
+            ResponseStyle::Concise.max_tokens(),
+            Some(CONCISE_MAX_TOKENS)
+        );
+        assert_eq!(ResponseStyle::Detailed.max_tokens(), None);
+    }
+}
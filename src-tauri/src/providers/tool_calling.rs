@@ -76,6 +76,82 @@ pub struct ToolCallRequest {
     pub raw_tool_calls: Vec<serde_json::Value>,
 }
 
+/// Which half of a tool call a `ToolPlanStep` reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ToolPlanPhase {
+    /// The model asked for the tool.
+    Call,
+    /// The tool's result went back to the model.
+    Result,
+}
+
+/// One step of a multi-iteration tool loop, emitted via
+/// `ProviderEvent::ToolPlanStep` so the UI can show what the model is
+/// working on instead of silence.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolPlanStep {
+    /// Tool round for the current user message, from 1.
+    pub iteration: usize,
+    pub max_iterations: usize,
+    pub tool: String,
+    pub tool_call_id: String,
+    pub phase: ToolPlanPhase,
+    /// One-line summary of the arguments (`Call`) or result (`Result`).
+    pub summary: String,
+}
+
+/// Longest `ToolPlanStep::summary`, in characters.
+pub const PLAN_SUMMARY_MAX_CHARS: usize = 120;
+
+/// Collapse `text` to one line of at most `PLAN_SUMMARY_MAX_CHARS`.
+pub fn summarize_for_plan(text: &str) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= PLAN_SUMMARY_MAX_CHARS {
+        return line;
+    }
+    let cut: String = line.chars().take(PLAN_SUMMARY_MAX_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Summary of a call's arguments: `key=value` pairs for an object,
+/// otherwise the compact JSON.
+pub fn summarize_arguments(arguments: &serde_json::Value) -> String {
+    let text = match arguments.as_object() {
+        Some(map) => map
+            .iter()
+            .map(|(key, value)| match value.as_str() {
+                Some(s) => format!("{}={}", key, s),
+                None => format!("{}={}", key, value),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => arguments.to_string(),
+    };
+    summarize_for_plan(&text)
+}
+
+/// `Call` steps for every call in `request`.
+pub fn plan_call_steps(
+    request: &ToolCallRequest,
+    iteration: usize,
+    max_iterations: usize,
+) -> Vec<ToolPlanStep> {
+    request
+        .calls
+        .iter()
+        .map(|call| ToolPlanStep {
+            iteration,
+            max_iterations,
+            tool: call.name.clone(),
+            tool_call_id: call.id.clone(),
+            phase: ToolPlanPhase::Call,
+            summary: summarize_arguments(&call.arguments),
+        })
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // Schema conversion
 // ---------------------------------------------------------------------------
@@ -679,4 +755,27 @@ mod tests {
         assert!(!supports_native_tools("lmstudio"));
         assert!(!supports_native_tools("jan"));
     }
+
+    #[test]
+    fn test_plan_call_steps_summarize_arguments() {
+        let request = ToolCallRequest {
+            calls: vec![CompletedToolCall {
+                id: "call_1".to_string(),
+                name: "browser_goto".to_string(),
+                arguments: serde_json::json!({ "url": "https://example.com", "wait": 2 }),
+            }],
+            response_text: String::new(),
+            raw_tool_calls: Vec::new(),
+        };
+        let steps = plan_call_steps(&request, 2, 10);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].iteration, 2);
+        assert_eq!(steps[0].phase, ToolPlanPhase::Call);
+        assert_eq!(steps[0].summary, "url=https://example.com, wait=2");
+
+        let long = summarize_for_plan(&"word\n".repeat(100));
+        assert_eq!(long.chars().count(), PLAN_SUMMARY_MAX_CHARS);
+        assert!(long.ends_with('…'));
+        assert!(!long.contains('\n'));
+    }
 }
@@ -0,0 +1,217 @@
+//! Turn queue for API providers.
+//!
+//! An API provider answers one user message at a time. A message that
+//! arrived while the previous reply was still streaming used to start a
+//! second request next to the first: both replies streamed into the chat at
+//! once and the history got the turns out of order.
+//!
+//! Now a message that arrives mid-turn waits. Voice turns follow
+//! `voice.turnPolicy`:
+//! - `queue` (default): sent in order as each reply ends.
+//! - `replace`: only the newest waiting turn is kept ("no, I meant...").
+//! - `reject`: dropped, and a short spoken notice says the assistant is busy.
+//!
+//! Typed messages always queue. A turn ends with the reply, an error, tool
+//! calls or the provider exiting; an interrupt or stop also drops whatever
+//! is waiting. A turn that never reports an end is given up on after
+//! `MAX_TURN`, so a lost event can't block input for good.
+//!
+//! CLI providers read the MCP inbox at their own pace and aren't gated.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A turn still "in progress" after this long is treated as finished.
+const MAX_TURN: Duration = Duration::from_secs(180);
+
+/// Spoken when a voice turn is rejected.
+pub const BUSY_NOTICE: &str = "Still working on your last request.";
+
+/// What to do with a voice turn while the provider is still responding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TurnPolicy {
+    #[default]
+    Queue,
+    Replace,
+    Reject,
+}
+
+impl TurnPolicy {
+    /// Parse a config value; unknown values fall back to `Queue`.
+    pub fn parse(s: &str) -> Self {
+        match s.trim() {
+            "replace" => Self::Replace,
+            "reject" => Self::Reject,
+            _ => Self::Queue,
+        }
+    }
+}
+
+/// A user message for the provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTurn {
+    pub text: String,
+    pub image_path: Option<String>,
+    pub image_data_url: Option<String>,
+    /// Whether the reply goes to TTS.
+    pub speak: bool,
+}
+
+/// What `admit` did with a turn.
+#[derive(Debug, PartialEq)]
+pub enum Admission {
+    /// The provider is idle: send it now. The turn is now in progress.
+    Send(PendingTurn),
+    /// Waiting behind the current turn, at this position (1 = next).
+    Queued(usize),
+    /// Waiting as the next turn, in place of the one that was waiting.
+    Replaced,
+    /// Dropped because the provider is busy.
+    Rejected,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// When the turn in progress was sent. `None` = idle.
+    started: Option<Instant>,
+    pending: VecDeque<PendingTurn>,
+}
+
+/// Turns waiting for the provider to finish its current reply.
+#[derive(Debug, Default)]
+pub struct TurnQueue {
+    state: Mutex<State>,
+}
+
+impl TurnQueue {
+    pub const fn new() -> Self {
+        Self {
+            state: Mutex::new(State {
+                started: None,
+                pending: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Take a new user turn, sending it now if the provider is idle and
+    /// otherwise handling it per `policy`.
+    pub fn admit(&self, turn: PendingTurn, policy: TurnPolicy, now: Instant) -> Admission {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let busy = matches!(state.started, Some(at) if now.duration_since(at) < MAX_TURN);
+        if !busy {
+            if state.started.is_some() {
+                tracing::warn!("Provider turn never reported an end, sending the next one");
+            }
+            // Whatever was waiting on a lost turn goes first
+            state.started = Some(now);
+            return match state.pending.pop_front() {
+                Some(next) => {
+                    state.pending.push_back(turn);
+                    Admission::Send(next)
+                }
+                None => Admission::Send(turn),
+            };
+        }
+        match policy {
+            TurnPolicy::Queue => {
+                state.pending.push_back(turn);
+                Admission::Queued(state.pending.len())
+            }
+            TurnPolicy::Replace => {
+                state.pending.clear();
+                state.pending.push_back(turn);
+                Admission::Replaced
+            }
+            TurnPolicy::Reject => Admission::Rejected,
+        }
+    }
+
+    /// End the turn in progress. Returns the next waiting turn, which is
+    /// then in progress, or `None` when the provider is idle again.
+    pub fn finish(&self, now: Instant) -> Option<PendingTurn> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let next = state.pending.pop_front();
+        state.started = next.as_ref().map(|_| now);
+        next
+    }
+
+    /// Drop the turn in progress and everything waiting (interrupt, stop).
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        *state = State::default();
+    }
+
+    /// Turns waiting behind the current one.
+    pub fn pending(&self) -> usize {
+        self.state
+            .lock()
+            .map(|s| s.pending.len())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(text: &str) -> PendingTurn {
+        PendingTurn {
+            text: text.into(),
+            image_path: None,
+            image_data_url: None,
+            speak: true,
+        }
+    }
+
+    #[test]
+    fn test_policy_parse() {
+        assert_eq!(TurnPolicy::parse("replace"), TurnPolicy::Replace);
+        assert_eq!(TurnPolicy::parse("reject"), TurnPolicy::Reject);
+        assert_eq!(TurnPolicy::parse("queue"), TurnPolicy::Queue);
+        assert_eq!(TurnPolicy::parse("whatever"), TurnPolicy::Queue);
+    }
+
+    #[test]
+    fn test_queue_sends_in_order() {
+        let q = TurnQueue::new();
+        let now = Instant::now();
+        assert_eq!(q.admit(turn("a"), TurnPolicy::Queue, now), Admission::Send(turn("a")));
+        assert_eq!(q.admit(turn("b"), TurnPolicy::Queue, now), Admission::Queued(1));
+        assert_eq!(q.admit(turn("c"), TurnPolicy::Queue, now), Admission::Queued(2));
+
+        assert_eq!(q.finish(now), Some(turn("b")));
+        assert_eq!(q.finish(now), Some(turn("c")));
+        assert_eq!(q.finish(now), None);
+        // Idle again
+        assert_eq!(q.admit(turn("d"), TurnPolicy::Queue, now), Admission::Send(turn("d")));
+    }
+
+    #[test]
+    fn test_replace_and_reject() {
+        let q = TurnQueue::new();
+        let now = Instant::now();
+        q.admit(turn("a"), TurnPolicy::Replace, now);
+        assert_eq!(q.admit(turn("b"), TurnPolicy::Replace, now), Admission::Replaced);
+        assert_eq!(q.admit(turn("c"), TurnPolicy::Replace, now), Admission::Replaced);
+        assert_eq!(q.admit(turn("d"), TurnPolicy::Reject, now), Admission::Rejected);
+        assert_eq!(q.pending(), 1);
+        assert_eq!(q.finish(now), Some(turn("c")));
+
+        q.clear();
+        assert_eq!(q.pending(), 0);
+        assert_eq!(q.admit(turn("e"), TurnPolicy::Reject, now), Admission::Send(turn("e")));
+    }
+
+    #[test]
+    fn test_lost_turn_times_out() {
+        let q = TurnQueue::new();
+        let now = Instant::now();
+        q.admit(turn("a"), TurnPolicy::Queue, now);
+        q.admit(turn("b"), TurnPolicy::Queue, now);
+        // The end of "a" never arrived: "b" goes first, "c" waits behind it
+        let later = now + MAX_TURN;
+        assert_eq!(q.admit(turn("c"), TurnPolicy::Queue, later), Admission::Send(turn("b")));
+        assert_eq!(q.finish(later), Some(turn("c")));
+    }
+}
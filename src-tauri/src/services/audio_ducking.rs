@@ -0,0 +1,231 @@
+//! Ducks other applications' audio while the microphone is recording.
+//!
+//! Music or a video playing through the speakers bleeds into the mic and
+//! wrecks transcription. While ducked, every other app's audio session on the
+//! default output device is turned down to a fraction of its volume, and put
+//! back when recording ends.
+//!
+//! Windows uses WASAPI sessions (`IAudioSessionManager2` → `ISimpleAudioVolume`)
+//! on a dedicated MTA worker thread, since the COM pointers aren't `Send`.
+//! Other platforms have no per-app volume API we can use yet; ducking is a
+//! logged no-op there.
+
+use std::sync::mpsc;
+
+// Only the Windows worker reads the level.
+#[cfg_attr(not(windows), allow(dead_code))]
+enum DuckCmd {
+    Duck(f32),
+    Restore,
+}
+
+/// Handle to the ducking worker. Dropping it restores any ducked sessions.
+pub struct AudioDucker {
+    tx: Option<mpsc::Sender<DuckCmd>>,
+    level: f32,
+    ducked: bool,
+}
+
+impl AudioDucker {
+    /// `level` is the fraction (0.0 - 1.0) of each app's volume kept while ducked.
+    pub fn new(level: f32) -> Self {
+        Self {
+            tx: spawn_worker(),
+            level: level.clamp(0.0, 1.0),
+            ducked: false,
+        }
+    }
+
+    /// Duck or restore. Only transitions reach the worker, so this is cheap
+    /// to call on every pipeline tick.
+    pub fn set_ducked(&mut self, ducked: bool) {
+        if ducked == self.ducked {
+            return;
+        }
+        self.ducked = ducked;
+        if let Some(tx) = &self.tx {
+            let cmd = if ducked {
+                DuckCmd::Duck(self.level)
+            } else {
+                DuckCmd::Restore
+            };
+            let _ = tx.send(cmd);
+        }
+    }
+}
+
+impl Drop for AudioDucker {
+    fn drop(&mut self) {
+        self.set_ducked(false);
+        // Dropping the sender ends the worker once it has restored.
+    }
+}
+
+/// Volume to restore a session to, or `None` if the user changed it while
+/// ducked (in which case their new setting wins).
+#[cfg(any(windows, test))]
+fn restore_target(original: f32, ducked_to: f32, current: f32) -> Option<f32> {
+    /// Volumes closer than this are treated as unchanged.
+    const VOLUME_EPSILON: f32 = 0.01;
+    ((current - ducked_to).abs() < VOLUME_EPSILON).then_some(original)
+}
+
+#[cfg(not(windows))]
+fn spawn_worker() -> Option<mpsc::Sender<DuckCmd>> {
+    tracing::debug!("Audio ducking is only supported on Windows; ignoring");
+    None
+}
+
+#[cfg(windows)]
+fn spawn_worker() -> Option<mpsc::Sender<DuckCmd>> {
+    let (tx, rx) = mpsc::channel();
+    match std::thread::Builder::new()
+        .name("audio-ducking".into())
+        .spawn(move || sys::worker_main(rx))
+    {
+        Ok(_) => Some(tx),
+        Err(e) => {
+            tracing::warn!("Failed to spawn audio ducking thread: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::sync::mpsc;
+
+    use windows::core::Interface;
+    use windows::Win32::Foundation::S_OK;
+    use windows::Win32::Media::Audio::{
+        eMultimedia, eRender, AudioSessionStateExpired, IAudioSessionControl2,
+        IAudioSessionManager2, IMMDeviceEnumerator, ISimpleAudioVolume, MMDeviceEnumerator,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    use super::{restore_target, DuckCmd};
+
+    /// A session we turned down: (volume control, original, value we set).
+    struct Ducked {
+        volume: ISimpleAudioVolume,
+        original: f32,
+        ducked_to: f32,
+    }
+
+    pub(super) fn worker_main(rx: mpsc::Receiver<DuckCmd>) {
+        // SAFETY: COM is initialized once for this thread, which owns every
+        // interface pointer created below.
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        }
+        let mut ducked: Vec<Ducked> = Vec::new();
+        while let Ok(cmd) = rx.recv() {
+            match cmd {
+                DuckCmd::Duck(level) => {
+                    restore(&mut ducked);
+                    match duck_sessions(level) {
+                        Ok(sessions) => {
+                            tracing::debug!(count = sessions.len(), level, "Ducked audio sessions");
+                            ducked = sessions;
+                        }
+                        Err(e) => tracing::warn!("Audio ducking failed: {}", e),
+                    }
+                }
+                DuckCmd::Restore => restore(&mut ducked),
+            }
+        }
+        restore(&mut ducked);
+    }
+
+    fn duck_sessions(level: f32) -> windows::core::Result<Vec<Ducked>> {
+        let own_pid = std::process::id();
+        let mut out = Vec::new();
+        // SAFETY: plain WASAPI calls on this COM-initialized thread.
+        unsafe {
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+            let device = enumerator.GetDefaultAudioEndpoint(eRender, eMultimedia)?;
+            let manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)?;
+            let sessions = manager.GetSessionEnumerator()?;
+            for i in 0..sessions.GetCount()? {
+                let Ok(control) = sessions.GetSession(i) else {
+                    continue;
+                };
+                let Ok(control2) = control.cast::<IAudioSessionControl2>() else {
+                    continue;
+                };
+                // System sounds are short and are how the app's own cues play.
+                if control2.IsSystemSoundsSession() == S_OK
+                    || control2.GetProcessId().unwrap_or(0) == own_pid
+                    || control.GetState().ok() == Some(AudioSessionStateExpired)
+                {
+                    continue;
+                }
+                let Ok(volume) = control.cast::<ISimpleAudioVolume>() else {
+                    continue;
+                };
+                let Ok(original) = volume.GetMasterVolume() else {
+                    continue;
+                };
+                let ducked_to = original * level;
+                if original <= 0.0 || volume.SetMasterVolume(ducked_to, std::ptr::null()).is_err() {
+                    continue;
+                }
+                out.push(Ducked {
+                    volume,
+                    original,
+                    ducked_to,
+                });
+            }
+        }
+        Ok(out)
+    }
+
+    fn restore(ducked: &mut Vec<Ducked>) {
+        for d in ducked.drain(..) {
+            // SAFETY: the interface was created on this thread.
+            unsafe {
+                let Ok(current) = d.volume.GetMasterVolume() else {
+                    continue;
+                };
+                if let Some(target) = restore_target(d.original, d.ducked_to, current) {
+                    let _ = d.volume.SetMasterVolume(target, std::ptr::null());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_target() {
+        assert_eq!(restore_target(0.8, 0.16, 0.16), Some(0.8));
+        assert_eq!(restore_target(0.8, 0.16, 0.165), Some(0.8));
+        // The user turned the app up or down while ducked: leave it.
+        assert_eq!(restore_target(0.8, 0.16, 0.5), None);
+    }
+
+    #[test]
+    fn test_set_ducked_only_on_transitions() {
+        let (tx, rx) = mpsc::channel();
+        let mut ducker = AudioDucker {
+            tx: Some(tx),
+            level: 0.2,
+            ducked: false,
+        };
+        ducker.set_ducked(false);
+        ducker.set_ducked(true);
+        ducker.set_ducked(true);
+        drop(ducker);
+
+        let cmds: Vec<DuckCmd> = rx.iter().collect();
+        assert_eq!(cmds.len(), 2);
+        assert!(matches!(cmds[0], DuckCmd::Duck(l) if l == 0.2));
+        assert!(matches!(cmds[1], DuckCmd::Restore));
+    }
+}
@@ -0,0 +1,130 @@
+//! Registers the mic stream with the OS as a voice call (`voice.audioSession`).
+//!
+//! With `"communications"`, the capture stream is opened the way call apps
+//! open theirs, so the OS applies its communications policies to it:
+//!
+//! - **Windows**: with no input device picked, capture opens on the *default
+//!   communications device* (Sound settings → Recording) rather than the
+//!   default device. Windows treats a stream there as a communications
+//!   stream: the "When Windows detects communications activity" ducking
+//!   setting applies, and so does any echo cancellation or noise
+//!   suppression the driver runs in communications mode.
+//! - **Linux**: the stream is tagged `media.role=phone` (`PULSE_PROP`), which
+//!   PulseAudio and pipewire-pulse use to load echo cancellation
+//!   (`module-filter-heuristics`) and to duck or cork other streams.
+//! - **macOS**: voice processing needs the VoiceProcessingIO audio unit,
+//!   which the capture backend doesn't use; the setting is a logged no-op.
+//!
+//! `"default"` leaves capture as it was.
+
+/// `voice.audioSession` values.
+pub const CATEGORIES: [&str; 2] = ["default", "communications"];
+
+/// How the capture stream presents itself to the OS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AudioSessionCategory {
+    #[default]
+    Default,
+    Communications,
+}
+
+impl AudioSessionCategory {
+    /// Parse a `voice.audioSession` value; unknown values are `Default`.
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "communications" => Self::Communications,
+            _ => Self::Default,
+        }
+    }
+}
+
+/// PulseAudio stream property set through the environment, read when the
+/// capture stream connects.
+#[cfg(target_os = "linux")]
+const PULSE_ROLE_VAR: &str = "PULSE_PROP_media.role";
+
+/// Prepare the process before a capture stream is opened with `category`.
+pub fn apply(category: AudioSessionCategory) {
+    #[cfg(target_os = "linux")]
+    match category {
+        AudioSessionCategory::Communications => std::env::set_var(PULSE_ROLE_VAR, "phone"),
+        AudioSessionCategory::Default => std::env::remove_var(PULSE_ROLE_VAR),
+    }
+    #[cfg(target_os = "macos")]
+    if category == AudioSessionCategory::Communications {
+        tracing::debug!("Communications audio session isn't supported on macOS; ignoring");
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let _ = category;
+}
+
+/// Name of the input device the OS uses for calls. Only Windows keeps one
+/// apart from the default input device.
+pub fn communications_input_device() -> Option<String> {
+    #[cfg(windows)]
+    {
+        match sys::default_capture_name() {
+            Ok(name) => Some(name),
+            Err(e) => {
+                tracing::warn!("No default communications device: {}", e);
+                None
+            }
+        }
+    }
+    #[cfg(not(windows))]
+    None
+}
+
+#[cfg(windows)]
+mod sys {
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Media::Audio::{
+        eCapture, eCommunications, IMMDeviceEnumerator, MMDeviceEnumerator,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED,
+        STGM_READ,
+    };
+
+    /// Friendly name of the default communications capture endpoint, which
+    /// is the name cpal lists the device under.
+    pub(super) fn default_capture_name() -> windows::core::Result<String> {
+        // SAFETY: COM is initialized for this call and every interface is
+        // released before it is uninitialized.
+        unsafe {
+            let initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+            let name = (|| -> windows::core::Result<String> {
+                let enumerator: IMMDeviceEnumerator =
+                    CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+                let device = enumerator.GetDefaultAudioEndpoint(eCapture, eCommunications)?;
+                let props = device.OpenPropertyStore(STGM_READ)?;
+                Ok(props.GetValue(&PKEY_Device_FriendlyName)?.to_string())
+            })();
+            if initialized {
+                CoUninitialize();
+            }
+            name
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_from_config() {
+        assert_eq!(
+            AudioSessionCategory::from_config("communications"),
+            AudioSessionCategory::Communications
+        );
+        assert_eq!(
+            AudioSessionCategory::from_config("default"),
+            AudioSessionCategory::Default
+        );
+        assert_eq!(
+            AudioSessionCategory::from_config("bogus"),
+            AudioSessionCategory::Default
+        );
+    }
+}
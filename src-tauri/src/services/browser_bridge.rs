@@ -18,7 +18,7 @@
 //! `CallDevToolsProtocolMethod()`.
 //! Results are routed back to the caller through oneshot channels.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::RwLock;
 
 use once_cell::sync::Lazy;
@@ -480,6 +480,178 @@ async fn handle_auth_action(
     }
 }
 
+// ---------------------------------------------------------------------------
+// Persistent browser profiles
+// ---------------------------------------------------------------------------
+
+/// JS that returns the current origin and its localStorage entries.
+const LOCAL_STORAGE_DUMP_JS: &str = "(function() { var o = {}; \
+    for (var i = 0; i < localStorage.length; i++) { var k = localStorage.key(i); o[k] = localStorage.getItem(k); } \
+    return JSON.stringify({ origin: location.origin, entries: o }); })()";
+
+/// Handle browser profile actions (list, create, delete, clear, save, use).
+async fn handle_profile_action(
+    app: &AppHandle,
+    action: &str,
+    args: &Value,
+) -> Result<Value, String> {
+    use crate::services::browser_profiles as profiles;
+
+    let dir = profiles::profiles_dir()?;
+    let key = profiles::ensure_key(&dir)?;
+    let name_arg = args.get("name").and_then(|v| v.as_str()).map(str::trim);
+
+    match action {
+        "profile_list" => Ok(json!({
+            "ok": true,
+            "active": profiles::active_profile(),
+            "profiles": profiles::list(&dir, &key),
+        })),
+        "profile_create" => {
+            let name = name_arg.ok_or("profile name is required")?;
+            profiles::create(&dir, name, &key)?;
+            Ok(json!({ "ok": true, "created": name }))
+        }
+        "profile_delete" => {
+            let name = name_arg.ok_or("profile name is required")?;
+            profiles::delete(&dir, name)?;
+            Ok(json!({ "ok": true, "deleted": name }))
+        }
+        "profile_clear" => {
+            let name = name_arg.ok_or("profile name is required")?;
+            profiles::clear(&dir, name, &key)?;
+            Ok(json!({ "ok": true, "cleared": name }))
+        }
+        "profile_save" => {
+            // Defaults to the active profile; saving to a new name creates it.
+            let name = name_arg
+                .map(str::to_string)
+                .or_else(profiles::active_profile)
+                .ok_or("profile name is required (no profile is active)")?;
+            let mut profile = if profiles::exists(&dir, &name) {
+                profiles::load(&dir, &name, &key)?
+            } else {
+                profiles::validate_name(&name)?;
+                profiles::BrowserProfile::new(&name)
+            };
+
+            let state = app.state::<LensState>();
+            let webview = get_webview(app, &state)?;
+            let cookies = call_cdp_method(&webview, "Network.getAllCookies", "{}")
+                .await?
+                .get("cookies")
+                .and_then(|c| c.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let page = unwrap_js_result(
+                evaluate_js_with_result(app, &webview, LOCAL_STORAGE_DUMP_JS, std::time::Duration::from_secs(10)).await?,
+            );
+            let origin = page.get("origin").and_then(|v| v.as_str());
+            let storage: BTreeMap<String, String> = page
+                .get("entries")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+
+            profile.record(cookies, origin, storage);
+            profiles::save(&dir, &profile, &key)?;
+            profiles::set_active_profile(Some(&name));
+            Ok(json!({
+                "ok": true,
+                "saved": name,
+                "cookies": profile.cookies.len(),
+                "origins": profile.local_storage.keys().collect::<Vec<_>>(),
+            }))
+        }
+        "profile_use" => {
+            let name = name_arg.ok_or("profile name is required")?;
+            let profile = profiles::load(&dir, name, &key)?;
+            let state = app.state::<LensState>();
+            let webview = get_webview(app, &state)?;
+
+            let cookies = profile.cookie_params();
+            if !cookies.is_empty() {
+                let params = json!({ "cookies": cookies }).to_string();
+                call_cdp_method(&webview, "Network.setCookies", &params).await?;
+            }
+            profiles::set_active_profile(Some(name));
+            let restored_storage = restore_profile_storage(app, &webview, &profile).await;
+
+            Ok(json!({
+                "ok": true,
+                "active": name,
+                "cookies": cookies.len(),
+                "localStorageRestored": restored_storage,
+                "hint": "Reload or navigate so the page picks up the restored session.",
+            }))
+        }
+        _ => Err(format!("Unknown profile action: {}", action)),
+    }
+}
+
+/// After a navigation, restore the active profile's localStorage once the
+/// new page has loaded, if the profile has entries for its origin.
+fn schedule_profile_storage_restore(app: &AppHandle, webview: tauri::Webview, target_origin: String) {
+    use crate::services::browser_profiles as profiles;
+
+    let Some(name) = profiles::active_profile() else { return };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let profile = match profiles::profiles_dir()
+            .and_then(|dir| profiles::ensure_key(&dir).and_then(|key| profiles::load(&dir, &name, &key)))
+        {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::warn!("[browser_bridge] Active profile '{}' unavailable: {}", name, e);
+                return;
+            }
+        };
+        if !profile.local_storage.contains_key(&target_origin) {
+            return;
+        }
+        // Poll until the new document is on the target origin and parsed.
+        let probe = "JSON.stringify({ origin: location.origin, ready: document.readyState !== 'loading' })";
+        for _ in 0..40 {
+            tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+            let Ok(raw) = evaluate_js_with_result(&app, &webview, probe, std::time::Duration::from_secs(5)).await else {
+                continue;
+            };
+            let page = unwrap_js_result(raw);
+            let on_target = page.get("origin").and_then(|v| v.as_str()) == Some(target_origin.as_str());
+            if on_target && page.get("ready").and_then(|v| v.as_bool()).unwrap_or(false) {
+                restore_profile_storage(&app, &webview, &profile).await;
+                return;
+            }
+        }
+    });
+}
+
+/// Write the active profile's localStorage for the page's current origin.
+///
+/// Returns whether anything was restored. localStorage can only be written
+/// from a page on the same origin, so navigation schedules this again.
+async fn restore_profile_storage(
+    app: &AppHandle,
+    webview: &tauri::Webview,
+    profile: &crate::services::browser_profiles::BrowserProfile,
+) -> bool {
+    let origin = match evaluate_js_with_result(app, webview, "location.origin", std::time::Duration::from_secs(5)).await {
+        Ok(Value::String(o)) => o,
+        _ => return false,
+    };
+    let entries = match profile.local_storage.get(&origin) {
+        Some(e) if !e.is_empty() => e,
+        _ => return false,
+    };
+    let sets: String = entries
+        .iter()
+        .map(|(k, v)| format!("localStorage.setItem('{}', '{}');", escape_js(k), escape_js(v)))
+        .collect();
+    let js = format!("(function() {{ {} return true; }})()", sets);
+    evaluate_js_with_result(app, webview, &js, std::time::Duration::from_secs(10))
+        .await
+        .is_ok()
+}
+
 // ---------------------------------------------------------------------------
 // Main dispatch
 // ---------------------------------------------------------------------------
@@ -506,9 +678,11 @@ pub async fn handle_browser_action(
             let parsed = url
                 .parse::<tauri::Url>()
                 .map_err(|e| format!("Invalid URL: {}", e))?;
+            let target_origin = parsed.origin().ascii_serialization();
             webview
                 .navigate(parsed)
                 .map_err(|e| format!("Navigation failed: {}", e))?;
+            schedule_profile_storage_restore(app, webview, target_origin);
             // Notify frontend so URL bar updates
             let _ = app.emit("lens-url-changed", json!({ "url": url }));
             Ok(json!({ "ok": true, "url": url }))
@@ -1589,6 +1763,15 @@ pub async fn handle_browser_action(
             handle_auth_action(app, action, args).await
         }
 
+        // -----------------------------------------------------------------
+        // Persistent profiles
+        // -----------------------------------------------------------------
+
+        "profile_list" | "profile_create" | "profile_delete" | "profile_clear"
+        | "profile_save" | "profile_use" => {
+            handle_profile_action(app, action, args).await
+        }
+
         // -----------------------------------------------------------------
         // HTTP actions (handled in server.rs, fallback error here)
         // -----------------------------------------------------------------
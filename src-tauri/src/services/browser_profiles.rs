@@ -0,0 +1,378 @@
+//! Named persistent browser profiles for the Lens webview.
+//!
+//! A profile is a snapshot of the webview's cookies (all of them, including
+//! HttpOnly session cookies, captured via CDP `Network.getAllCookies`) plus
+//! `localStorage` entries keyed by origin. Saving a profile after logging in
+//! and applying it next session restores the logins without re-entering
+//! credentials.
+//!
+//! Each profile is written to `{profiles_dir}/{name}.profile` as a single
+//! AES-256-GCM blob (the auth vault cipher) under its own DPAPI-wrapped key,
+//! so nothing about the sites or sessions is readable at rest.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::services::auth_vault;
+
+/// Extension of encrypted profile files.
+const PROFILE_EXT: &str = "profile";
+const MAX_NAME_LEN: usize = 64;
+
+/// Name of the profile last applied or saved this session.
+static ACTIVE_PROFILE: Lazy<RwLock<Option<String>>> = Lazy::new(|| RwLock::new(None));
+
+/// Decrypted profile contents.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BrowserProfile {
+    pub name: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+    /// CDP `Network.Cookie` objects as returned by `Network.getAllCookies`.
+    #[serde(default)]
+    pub cookies: Vec<Value>,
+    /// `localStorage` entries per origin (e.g. `https://github.com`).
+    #[serde(default)]
+    pub local_storage: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Listing entry for `browser_profile list`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSummary {
+    pub name: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub cookie_count: usize,
+    pub origins: Vec<String>,
+    pub active: bool,
+}
+
+impl BrowserProfile {
+    pub fn new(name: &str) -> Self {
+        let now = now_secs();
+        Self {
+            name: name.to_string(),
+            created_at: now,
+            updated_at: now,
+            ..Default::default()
+        }
+    }
+
+    /// Replace the stored cookies and one origin's localStorage with a fresh capture.
+    pub fn record(&mut self, cookies: Vec<Value>, origin: Option<&str>, storage: BTreeMap<String, String>) {
+        self.cookies = cookies;
+        if let Some(origin) = origin.filter(|o| is_storable_origin(o)) {
+            if storage.is_empty() {
+                self.local_storage.remove(origin);
+            } else {
+                self.local_storage.insert(origin.to_string(), storage);
+            }
+        }
+        self.updated_at = now_secs();
+    }
+
+    /// Cookies reshaped as CDP `Network.CookieParam` objects for `Network.setCookies`.
+    ///
+    /// Read-only fields from `Network.Cookie` (size, session, priority, ...)
+    /// are dropped, as are expired cookies.
+    pub fn cookie_params(&self) -> Vec<Value> {
+        let now = now_secs() as f64;
+        self.cookies
+            .iter()
+            .filter_map(|c| {
+                let name = c.get("name")?.as_str()?;
+                let value = c.get("value")?.as_str()?;
+                let domain = c.get("domain")?.as_str()?;
+                let mut param = json!({
+                    "name": name,
+                    "value": value,
+                    "domain": domain,
+                    "path": c.get("path").and_then(|p| p.as_str()).unwrap_or("/"),
+                    "secure": c.get("secure").and_then(|v| v.as_bool()).unwrap_or(false),
+                    "httpOnly": c.get("httpOnly").and_then(|v| v.as_bool()).unwrap_or(false),
+                });
+                if let Some(same_site) = c.get("sameSite").and_then(|v| v.as_str()) {
+                    param["sameSite"] = json!(same_site);
+                }
+                let session = c.get("session").and_then(|v| v.as_bool()).unwrap_or(false);
+                if let Some(expires) = c.get("expires").and_then(|v| v.as_f64()) {
+                    if !session && expires > 0.0 {
+                        if expires <= now {
+                            return None;
+                        }
+                        param["expires"] = json!(expires);
+                    }
+                }
+                Some(param)
+            })
+            .collect()
+    }
+
+    fn summary(&self, active: Option<&str>) -> ProfileSummary {
+        ProfileSummary {
+            name: self.name.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            cookie_count: self.cookies.len(),
+            origins: self.local_storage.keys().cloned().collect(),
+            active: active == Some(self.name.as_str()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Storage
+// ---------------------------------------------------------------------------
+
+/// Directory holding encrypted profiles and their key.
+pub fn profiles_dir() -> Result<PathBuf, String> {
+    Ok(dirs::data_dir()
+        .ok_or("Could not find app data directory")?
+        .join("voice-mirror")
+        .join("browser-profiles"))
+}
+
+/// Profile names become file names, so keep them to a safe character set.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!("Profile name must be 1-{} characters", MAX_NAME_LEN));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid profile name '{}': use letters, digits, '-' or '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn profile_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.{}", name, PROFILE_EXT))
+}
+
+pub fn exists(dir: &Path, name: &str) -> bool {
+    profile_path(dir, name).exists()
+}
+
+/// Encrypt and write a profile.
+pub fn save(dir: &Path, profile: &BrowserProfile, key: &[u8; 32]) -> Result<(), String> {
+    validate_name(&profile.name)?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create profiles dir: {e}"))?;
+    let json = serde_json::to_vec(profile).map_err(|e| format!("Failed to serialize profile: {e}"))?;
+    let encrypted = auth_vault::encrypt_data(&json, key)?;
+    fs::write(profile_path(dir, &profile.name), encrypted)
+        .map_err(|e| format!("Failed to write profile: {e}"))
+}
+
+/// Read and decrypt a profile.
+pub fn load(dir: &Path, name: &str, key: &[u8; 32]) -> Result<BrowserProfile, String> {
+    validate_name(name)?;
+    let bytes = fs::read(profile_path(dir, name))
+        .map_err(|_| format!("Browser profile '{}' not found", name))?;
+    let json = auth_vault::decrypt_data(&bytes, key)?;
+    serde_json::from_slice(&json).map_err(|e| format!("Failed to parse profile: {e}"))
+}
+
+/// Create an empty profile. Fails if one with that name already exists.
+pub fn create(dir: &Path, name: &str, key: &[u8; 32]) -> Result<BrowserProfile, String> {
+    validate_name(name)?;
+    if exists(dir, name) {
+        return Err(format!("Browser profile '{}' already exists", name));
+    }
+    let profile = BrowserProfile::new(name);
+    save(dir, &profile, key)?;
+    Ok(profile)
+}
+
+/// Forget a profile's cookies and storage but keep the profile itself.
+pub fn clear(dir: &Path, name: &str, key: &[u8; 32]) -> Result<(), String> {
+    let mut profile = load(dir, name, key)?;
+    profile.cookies.clear();
+    profile.local_storage.clear();
+    profile.updated_at = now_secs();
+    save(dir, &profile, key)
+}
+
+/// Delete a profile file.
+pub fn delete(dir: &Path, name: &str) -> Result<(), String> {
+    validate_name(name)?;
+    fs::remove_file(profile_path(dir, name))
+        .map_err(|_| format!("Browser profile '{}' not found", name))?;
+    let mut active = ACTIVE_PROFILE.write().unwrap_or_else(|e| e.into_inner());
+    if active.as_deref() == Some(name) {
+        *active = None;
+    }
+    Ok(())
+}
+
+/// Summaries of all profiles, sorted by name. Unreadable files are skipped.
+pub fn list(dir: &Path, key: &[u8; 32]) -> Vec<ProfileSummary> {
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+    let active = active_profile();
+    let mut out: Vec<ProfileSummary> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(PROFILE_EXT) {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?;
+            match load(dir, name, key) {
+                Ok(p) => Some(p.summary(active.as_deref())),
+                Err(e) => {
+                    tracing::warn!("[browser_profiles] Skipping {}: {}", name, e);
+                    None
+                }
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
+
+/// Load the profiles key, creating it on first use.
+pub fn ensure_key(dir: &Path) -> Result<[u8; 32], String> {
+    auth_vault::ensure_key(dir)
+}
+
+pub fn active_profile() -> Option<String> {
+    ACTIVE_PROFILE.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_active_profile(name: Option<&str>) {
+    *ACTIVE_PROFILE.write().unwrap_or_else(|e| e.into_inner()) = name.map(|n| n.to_string());
+}
+
+/// Only real web origins get localStorage snapshots (not `null`, `about:`, files).
+fn is_storable_origin(origin: &str) -> bool {
+    origin.starts_with("https://") || origin.starts_with("http://")
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(suffix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "vm-browser-profiles-{}-{}",
+            std::process::id(),
+            suffix
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work-github_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../evil").is_err());
+        assert!(validate_name("has space").is_err());
+        assert!(validate_name(&"a".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_roundtrip_is_encrypted() {
+        let dir = test_dir("roundtrip");
+        let key = auth_vault::generate_key();
+
+        let mut profile = create(&dir, "research", &key).unwrap();
+        let mut storage = BTreeMap::new();
+        storage.insert("token".to_string(), "secret-token-value".to_string());
+        profile.record(
+            vec![json!({ "name": "sid", "value": "abc", "domain": ".example.com" })],
+            Some("https://example.com"),
+            storage,
+        );
+        save(&dir, &profile, &key).unwrap();
+
+        let raw = fs::read(dir.join("research.profile")).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("secret-token-value"));
+        assert_eq!(load(&dir, "research", &key).unwrap(), profile);
+        assert!(load(&dir, "research", &auth_vault::generate_key()).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_create_list_clear_delete() {
+        let dir = test_dir("crud");
+        let key = auth_vault::generate_key();
+
+        create(&dir, "b", &key).unwrap();
+        let mut a = create(&dir, "a", &key).unwrap();
+        assert!(create(&dir, "a", &key).is_err());
+
+        a.record(vec![json!({ "name": "x", "value": "1", "domain": "a.com" })], None, BTreeMap::new());
+        save(&dir, &a, &key).unwrap();
+
+        let names: Vec<String> = list(&dir, &key).into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["a", "b"]);
+
+        clear(&dir, "a", &key).unwrap();
+        assert!(load(&dir, "a", &key).unwrap().cookies.is_empty());
+
+        delete(&dir, "a").unwrap();
+        assert!(delete(&dir, "a").is_err());
+        assert_eq!(list(&dir, &key).len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_record_ignores_opaque_origins() {
+        let mut profile = BrowserProfile::new("p");
+        let mut storage = BTreeMap::new();
+        storage.insert("k".to_string(), "v".to_string());
+        profile.record(Vec::new(), Some("null"), storage.clone());
+        profile.record(Vec::new(), Some("about:blank"), storage);
+        assert!(profile.local_storage.is_empty());
+    }
+
+    #[test]
+    fn test_cookie_params() {
+        let mut profile = BrowserProfile::new("p");
+        profile.cookies = vec![
+            json!({
+                "name": "sid", "value": "1", "domain": ".example.com", "path": "/",
+                "expires": -1, "size": 4, "httpOnly": true, "secure": true,
+                "session": true, "sameSite": "Lax", "priority": "Medium"
+            }),
+            json!({ "name": "old", "value": "2", "domain": "a.com", "expires": 1000.0, "session": false }),
+            json!({ "name": "keep", "value": "3", "domain": "a.com", "expires": 4_000_000_000.0, "session": false }),
+            json!({ "value": "no-name", "domain": "a.com" }),
+        ];
+
+        let params = profile.cookie_params();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0]["httpOnly"], true);
+        assert_eq!(params[0]["sameSite"], "Lax");
+        assert!(params[0].get("expires").is_none());
+        assert!(params[0].get("priority").is_none());
+        assert_eq!(params[1]["name"], "keep");
+        assert_eq!(params[1]["expires"], 4_000_000_000.0);
+    }
+}
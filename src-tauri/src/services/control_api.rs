@@ -0,0 +1,626 @@
+//! Localhost control API for scripts, Stream Deck plugins, and home automation.
+//!
+//! A small HTTP server bound to 127.0.0.1 that drives the voice pipeline
+//! without going through the Tauri frontend. Every request must carry the
+//! configured token, either as `Authorization: Bearer <token>` or as a
+//! `?token=` query parameter (for WebSocket clients that cannot set headers).
+//!
+//! Routes (JSON bodies and `{ success, data?, error? }` responses):
+//! - `GET  /api/state`          voice engine status
+//! - `POST /api/start`          start the voice pipeline
+//! - `POST /api/stop`           stop the voice pipeline
+//! - `POST /api/mode`           `{ "mode": "pushToTalk" | "toggle" | "wakeWord" | ... }`
+//! - `POST /api/speak`          `{ "text": "..." }`
+//! - `POST /api/stop-speaking`  interrupt TTS playback
+//! - `GET  /api/events`         WebSocket stream of `voice-event` payloads
+//! - `GET  /metrics`            `services::metrics` in the Prometheus text
+//!   format (point a scraper at it with the token as bearer credentials)
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Listener, Manager};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, watch, Semaphore};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use tracing::{info, warn};
+
+use crate::commands::voice::{self as voice_cmds, VoiceEngineState};
+use crate::commands::IpcResponse;
+use crate::voice::SpeechPriority;
+
+/// Largest request head or body we accept.
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// How long a client has to send its request head.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connections handled at once (event streams included); more are closed.
+const MAX_CONNECTIONS: usize = 32;
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+struct RunningServer {
+    port: u16,
+    shutdown: watch::Sender<bool>,
+    app: AppHandle,
+    listener_id: tauri::EventId,
+}
+
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+/// Generate a fresh random API token (32 hex chars).
+pub fn generate_token() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// Port the server is listening on, if running.
+pub fn running_port() -> Option<u16> {
+    SERVER.lock().ok()?.as_ref().map(|s| s.port)
+}
+
+/// Start (or restart) the server on `port`, requiring `token`.
+pub fn start(app: &AppHandle, port: u16, token: String) -> Result<(), String> {
+    if token.is_empty() {
+        return Err("Control API token is empty".into());
+    }
+    stop();
+
+    let std_listener = std::net::TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to bind control API on 127.0.0.1:{}: {}", port, e))?;
+    std_listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure control API socket: {}", e))?;
+
+    // Forward every voice-event to connected WebSocket clients.
+    let (events_tx, _) = broadcast::channel::<String>(256);
+    let forward_tx = events_tx.clone();
+    let listener_id = app.listen_any("voice-event", move |event| {
+        let _ = forward_tx.send(event.payload().to_string());
+    });
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let server_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(std_listener) {
+            Ok(l) => l,
+            Err(e) => {
+                warn!("Control API listener setup failed: {}", e);
+                return;
+            }
+        };
+        info!("Control API listening on http://127.0.0.1:{}/", port);
+        accept_loop(listener, server_app, token, events_tx, shutdown_rx).await;
+        info!("Control API stopped");
+    });
+
+    if let Ok(mut guard) = SERVER.lock() {
+        *guard = Some(RunningServer {
+            port,
+            shutdown: shutdown_tx,
+            app: app.clone(),
+            listener_id,
+        });
+    }
+    Ok(())
+}
+
+/// Stop the server if running. Open WebSocket streams are closed.
+pub fn stop() {
+    let Some(server) = SERVER.lock().ok().and_then(|mut g| g.take()) else {
+        return;
+    };
+    let _ = server.shutdown.send(true);
+    server.app.unlisten(server.listener_id);
+}
+
+/// Start the server at app launch when enabled in config.
+pub fn start_from_config(app: &AppHandle) {
+    let cfg = crate::commands::config::get_config_snapshot().control_api;
+    if !cfg.enabled {
+        return;
+    }
+    let Some(token) = cfg.token.filter(|t| !t.is_empty()) else {
+        warn!("Control API enabled but no token is configured; not starting");
+        return;
+    };
+    if let Err(e) = start(app, cfg.port, token) {
+        warn!("{}", e);
+    }
+}
+
+async fn accept_loop(
+    listener: tokio::net::TcpListener,
+    app: AppHandle,
+    token: String,
+    events: broadcast::Sender<String>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let permits = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+    loop {
+        tokio::select! {
+            _ = shutdown.changed() => break,
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else { continue };
+                let Ok(permit) = permits.clone().try_acquire_owned() else {
+                    warn!("Control API at {} connections, closing a new one", MAX_CONNECTIONS);
+                    continue;
+                };
+                let app = app.clone();
+                let token = token.clone();
+                let events = events.subscribe();
+                let shutdown = shutdown.clone();
+                tokio::spawn(async move {
+                    handle_connection(stream, app, token, events, shutdown).await;
+                    drop(permit);
+                });
+            }
+        }
+    }
+}
+
+// ── Request parsing ─────────────────────────────────────────────────
+
+#[derive(Debug)]
+struct RequestHead {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    /// Lowercased header names.
+    headers: HashMap<String, String>,
+    /// Length of the head including the blank line.
+    len: usize,
+}
+
+impl RequestHead {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    fn is_websocket_upgrade(&self) -> bool {
+        self.header("upgrade")
+            .is_some_and(|v| v.eq_ignore_ascii_case("websocket"))
+    }
+}
+
+/// Parse an HTTP/1.1 request head, or `None` if it is incomplete or malformed.
+fn parse_head(buf: &[u8]) -> Option<RequestHead> {
+    let end = buf.windows(4).position(|w| w == b"\r\n\r\n")? + 4;
+    let text = std::str::from_utf8(&buf[..end]).ok()?;
+    let mut lines = text.split("\r\n");
+
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_ascii_uppercase();
+    let target = request_line.next()?;
+    let (path, query) = match target.split_once('?') {
+        Some((p, q)) => (p, q),
+        None => (target, ""),
+    };
+    let query = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+
+    Some(RequestHead {
+        method,
+        path: path.to_string(),
+        query,
+        headers,
+        len: end,
+    })
+}
+
+/// Check the bearer header or `token` query parameter against `token`.
+fn is_authorized(head: &RequestHead, token: &str) -> bool {
+    let presented = head
+        .header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| head.query.get("token").map(String::as_str));
+    presented.is_some_and(|p| constant_time_eq(p.as_bytes(), token.as_bytes()))
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Debug, PartialEq)]
+enum Route {
+    State,
+    Start,
+    Stop,
+    Mode,
+    Speak,
+    StopSpeaking,
+    Events,
+    Metrics,
+    Preflight,
+    MethodNotAllowed,
+    NotFound,
+}
+
+fn route(method: &str, path: &str) -> Route {
+    let path = path.trim_end_matches('/');
+    let expected = match path {
+        "/api/state" | "/api/events" | "/metrics" => "GET",
+        "/api/start" | "/api/stop" | "/api/mode" | "/api/speak" | "/api/stop-speaking" => "POST",
+        _ => return Route::NotFound,
+    };
+    if method == "OPTIONS" {
+        return Route::Preflight;
+    }
+    if method != expected {
+        return Route::MethodNotAllowed;
+    }
+    match path {
+        "/api/state" => Route::State,
+        "/api/events" => Route::Events,
+        "/metrics" => Route::Metrics,
+        "/api/start" => Route::Start,
+        "/api/stop" => Route::Stop,
+        "/api/mode" => Route::Mode,
+        "/api/speak" => Route::Speak,
+        _ => Route::StopSpeaking,
+    }
+}
+
+// ── Connection handling ─────────────────────────────────────────────
+
+/// Read until the request head is complete, within one `HEAD_TIMEOUT`.
+/// Returns the head and any bytes the client sent after it.
+async fn read_head(stream: &mut (impl AsyncRead + Unpin)) -> Option<(RequestHead, Vec<u8>)> {
+    let mut buf = Vec::with_capacity(1024);
+    let read = async {
+        let mut chunk = [0u8; 1024];
+        loop {
+            let n = stream.read(&mut chunk).await.ok()?;
+            if n == 0 {
+                return None;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+            if let Some(head) = parse_head(&buf) {
+                return Some(head);
+            }
+            if buf.len() >= MAX_HEAD_BYTES {
+                return None;
+            }
+        }
+    };
+    let head = tokio::time::timeout(HEAD_TIMEOUT, read)
+        .await
+        .ok()
+        .flatten()?;
+    let rest = buf.split_off(head.len);
+    Some((head, rest))
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    app: AppHandle,
+    token: String,
+    events: broadcast::Receiver<String>,
+    shutdown: watch::Receiver<bool>,
+) {
+    let Some((head, rest)) = read_head(&mut stream).await else {
+        return;
+    };
+
+    let route = route(&head.method, &head.path);
+    if route == Route::Preflight {
+        let _ = write_response(&mut stream, 204, None).await;
+        return;
+    }
+    if !is_authorized(&head, &token) {
+        let body = IpcResponse::err("Missing or invalid token");
+        let _ = write_response(&mut stream, 401, Some(&body)).await;
+        return;
+    }
+
+    if route == Route::Events {
+        match head.header("sec-websocket-key") {
+            Some(key) if head.is_websocket_upgrade() => {
+                let accept = derive_accept_key(key.as_bytes());
+                stream_events(stream, &accept, rest, app, events, shutdown).await;
+            }
+            _ => {
+                let body = IpcResponse::err("Expected a WebSocket upgrade");
+                let _ = write_response(&mut stream, 400, Some(&body)).await;
+            }
+        }
+        return;
+    }
+
+    if route == Route::Metrics {
+        let text = crate::services::metrics::render_prometheus();
+        let _ = write_raw(&mut stream, 200, PROMETHEUS_CONTENT_TYPE, &text).await;
+        return;
+    }
+
+    let body = match read_body(&mut stream, &head, rest).await {
+        Ok(b) => b,
+        Err(e) => {
+            let _ = write_response(&mut stream, 400, Some(&IpcResponse::err(e))).await;
+            return;
+        }
+    };
+
+    let (status, response) = match route {
+        Route::NotFound => (404, IpcResponse::err("Not found")),
+        Route::MethodNotAllowed => (405, IpcResponse::err("Method not allowed")),
+        _ => (200, dispatch(route, body, app).await),
+    };
+    let _ = write_response(&mut stream, status, Some(&response)).await;
+}
+
+/// Read the body; `rest` is whatever arrived along with the head.
+async fn read_body(
+    stream: &mut TcpStream,
+    head: &RequestHead,
+    rest: Vec<u8>,
+) -> Result<Value, String> {
+    let len: usize = head
+        .header("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if len == 0 {
+        return Ok(Value::Null);
+    }
+    if len > MAX_BODY_BYTES {
+        return Err(format!("Body too large (max {} bytes)", MAX_BODY_BYTES));
+    }
+    let mut body = vec![0u8; len];
+    let have = rest.len().min(len);
+    body[..have].copy_from_slice(&rest[..have]);
+    tokio::time::timeout(HEAD_TIMEOUT, stream.read_exact(&mut body[have..]))
+        .await
+        .map_err(|_| "Timed out reading body".to_string())?
+        .map_err(|e| format!("Failed to read body: {}", e))?;
+    serde_json::from_slice(&body).map_err(|e| format!("Invalid JSON body: {}", e))
+}
+
+/// Run a voice command on a blocking thread (they lock the engine mutex).
+async fn dispatch(route: Route, body: Value, app: AppHandle) -> IpcResponse {
+    let string_field = |name: &str| body.get(name).and_then(Value::as_str).map(str::to_string);
+    let mode = string_field("mode");
+    let text = string_field("text");
+    let priority = match string_field("priority") {
+        Some(name) => match SpeechPriority::from_name(&name) {
+            Some(priority) => Some(priority),
+            None => return IpcResponse::err("\"priority\" must be interrupt, queue or drop_if_busy"),
+        },
+        None => None,
+    };
+
+    let result = tokio::task::spawn_blocking(move || {
+        let state = app.state::<VoiceEngineState>();
+        match route {
+            Route::State => voice_cmds::get_voice_status(state),
+            Route::Start => voice_cmds::start_voice(app.clone(), state),
+            Route::Stop => voice_cmds::stop_voice(state),
+            Route::StopSpeaking => voice_cmds::stop_speaking(state),
+            Route::Mode => match mode {
+                Some(mode) => voice_cmds::set_voice_mode(mode, state),
+                None => IpcResponse::err("Missing \"mode\""),
+            },
+            Route::Speak => match text.filter(|t| !t.trim().is_empty()) {
+                Some(text) => voice_cmds::speak_text(text, priority, state),
+                None => IpcResponse::err("Missing \"text\""),
+            },
+            _ => IpcResponse::err("Not found"),
+        }
+    })
+    .await;
+
+    result.unwrap_or_else(|e| IpcResponse::err(format!("Command failed: {}", e)))
+}
+
+async fn write_response(
+    stream: &mut TcpStream,
+    status: u16,
+    body: Option<&IpcResponse>,
+) -> std::io::Result<()> {
+    let payload = body
+        .and_then(|b| serde_json::to_string(b).ok())
+        .unwrap_or_default();
+    write_raw(stream, status, "application/json", &payload).await
+}
+
+async fn write_raw(
+    stream: &mut TcpStream,
+    status: u16,
+    content_type: &str,
+    payload: &str,
+) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Headers: Authorization, Content-Type\r\n\
+         Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+         Connection: close\r\n\r\n{payload}",
+        payload.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Answer the upgrade (the head is already read, so the handshake is done
+/// here rather than by tungstenite) and forward voice events until either
+/// side closes. `rest` holds any frames that arrived with the head.
+async fn stream_events(
+    mut stream: TcpStream,
+    accept_key: &str,
+    rest: Vec<u8>,
+    app: AppHandle,
+    mut events: broadcast::Receiver<String>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {accept_key}\r\n\r\n"
+    );
+    if let Err(e) = stream.write_all(response.as_bytes()).await {
+        warn!("Control API WebSocket handshake failed: {}", e);
+        return;
+    }
+    let ws = WebSocketStream::from_partially_read(stream, rest, Role::Server, None).await;
+    let (mut sink, mut incoming) = ws.split();
+
+    // Start with a snapshot so clients don't have to race a GET /api/state.
+    let snapshot = dispatch(Route::State, Value::Null, app).await;
+    let hello = json!({ "type": "state", "data": snapshot.data });
+    if sink.send(Message::text(hello.to_string())).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            _ = shutdown.changed() => break,
+            event = events.recv() => match event {
+                Ok(payload) => {
+                    let data: Value = serde_json::from_str(&payload).unwrap_or(Value::Null);
+                    let msg = json!({ "type": "voice-event", "data": data });
+                    if sink.send(Message::text(msg.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "Control API event stream lagged");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            msg = incoming.next() => match msg {
+                Some(Ok(Message::Ping(data))) => {
+                    let _ = sink.send(Message::Pong(data)).await;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+    let _ = sink.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(raw: &str) -> RequestHead {
+        parse_head(raw.as_bytes()).expect("valid head")
+    }
+
+    #[test]
+    fn test_parse_head_extracts_path_query_and_headers() {
+        let h =
+            head("GET /api/events?token=abc%20d HTTP/1.1\r\nHost: x\r\nUpgrade: WebSocket\r\n\r\n");
+        assert_eq!(h.method, "GET");
+        assert_eq!(h.path, "/api/events");
+        assert_eq!(h.query.get("token").map(String::as_str), Some("abc d"));
+        assert!(h.is_websocket_upgrade());
+        assert_eq!(h.len, 71);
+    }
+
+    #[test]
+    fn test_parse_head_needs_blank_line() {
+        assert!(parse_head(b"GET /api/state HTTP/1.1\r\nHost: x\r\n").is_none());
+    }
+
+    #[test]
+    fn test_authorization_header_or_query() {
+        let token = "s3cret";
+        assert!(is_authorized(
+            &head("GET /api/state HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n\r\n"),
+            token
+        ));
+        assert!(is_authorized(
+            &head("GET /api/state?token=s3cret HTTP/1.1\r\n\r\n"),
+            token
+        ));
+        assert!(!is_authorized(
+            &head("GET /api/state HTTP/1.1\r\nAuthorization: Bearer nope\r\n\r\n"),
+            token
+        ));
+        assert!(!is_authorized(
+            &head("GET /api/state HTTP/1.1\r\n\r\n"),
+            token
+        ));
+    }
+
+    #[test]
+    fn test_routes_check_method() {
+        assert_eq!(route("GET", "/api/state"), Route::State);
+        assert_eq!(route("GET", "/api/state/"), Route::State);
+        assert_eq!(route("POST", "/api/speak"), Route::Speak);
+        assert_eq!(route("POST", "/api/stop-speaking"), Route::StopSpeaking);
+        assert_eq!(route("GET", "/api/speak"), Route::MethodNotAllowed);
+        assert_eq!(route("OPTIONS", "/api/speak"), Route::Preflight);
+        assert_eq!(route("GET", "/metrics"), Route::Metrics);
+        assert_eq!(route("POST", "/metrics"), Route::MethodNotAllowed);
+        assert_eq!(route("GET", "/nope"), Route::NotFound);
+    }
+
+    #[test]
+    fn test_generated_tokens_are_unique_hex() {
+        let a = generate_token();
+        assert_eq!(a.len(), 32);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, generate_token());
+    }
+
+    #[tokio::test]
+    async fn test_read_head_across_writes_keeps_the_body_start() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        let writer = tokio::spawn(async move {
+            client
+                .write_all(b"POST /api/speak HTTP/1.1\r\n")
+                .await
+                .unwrap();
+            client.write_all(b"Content-Length: 13\r\n").await.unwrap();
+            client.write_all(b"\r\n{\"text\":").await.unwrap();
+            client
+        });
+        let (head, rest) = read_head(&mut server).await.expect("complete head");
+        assert_eq!(head.path, "/api/speak");
+        assert_eq!(head.header("content-length"), Some("13"));
+        assert_eq!(rest, b"{\"text\":");
+        drop(writer.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_read_head_gives_up_on_oversized_or_closed_heads() {
+        let (mut client, mut server) = tokio::io::duplex(MAX_HEAD_BYTES * 2);
+        client
+            .write_all(b"GET /api/state HTTP/1.1\r\n")
+            .await
+            .unwrap();
+        client.write_all(&vec![b'x'; MAX_HEAD_BYTES]).await.unwrap();
+        assert!(read_head(&mut server).await.is_none());
+
+        let (client, mut server) = tokio::io::duplex(64);
+        drop(client);
+        assert!(read_head(&mut server).await.is_none());
+    }
+}
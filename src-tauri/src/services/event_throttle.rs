@@ -0,0 +1,300 @@
+//! Central throttling and batching for high-frequency frontend events.
+//!
+//! Some events fire much faster than the UI can usefully repaint. AI stream
+//! tokens arrive one per token, and the voice state machine can pass through
+//! several states within a single frame. Each one is a separate trip across
+//! the Tauri event bridge, and on slow machines that shows up as stutter.
+//!
+//! [`EmitThrottled::emit_throttled`] looks up a policy for each event:
+//! - `Coalesce`: hold the event briefly. A newer event of the same kind
+//!   replaces it (latest wins).
+//! - `Batch`: hold the event briefly. Newer events append their text field
+//!   to it.
+//! - `Immediate`: emit right away, after flushing anything held, so the
+//!   frontend sees events in the order they happened.
+//!
+//! Voice events are keyed by their `event` tag, so `state_change` and
+//! `audio_level` each get their own policy within the one `voice-event`
+//! channel. Policies come from `advanced.eventThrottle` at startup.
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::config::schema::EventThrottleConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Policy {
+    Immediate,
+    Coalesce(Duration),
+    /// Append the named top-level string field to the held payload.
+    Batch(Duration, &'static str),
+}
+
+/// An event waiting for its window to close.
+struct Held {
+    event: String,
+    kind: String,
+    payload: Value,
+    due: Instant,
+}
+
+/// Events held back, in the order they were first emitted.
+#[derive(Default)]
+struct HeldQueue {
+    held: Vec<Held>,
+}
+
+impl HeldQueue {
+    /// Add an event. Returns the events to emit now, in order, and when to
+    /// flush again if the event was held.
+    fn push(
+        &mut self,
+        event: &str,
+        kind: &str,
+        payload: Value,
+        policy: Policy,
+        now: Instant,
+    ) -> (Vec<(String, Value)>, Option<Instant>) {
+        let window = match policy {
+            Policy::Immediate => None,
+            Policy::Coalesce(w) | Policy::Batch(w, _) => Some(w),
+        };
+        let Some(window) = window else {
+            let mut out = self.drain(self.held.len());
+            out.push((event.to_string(), payload));
+            return (out, None);
+        };
+
+        if let Some(held) = self
+            .held
+            .iter_mut()
+            .find(|h| h.event == event && h.kind == kind)
+        {
+            match policy {
+                Policy::Batch(_, field) => {
+                    if !append_field(&mut held.payload, &payload, field) {
+                        // Not text we can join: send everything as is
+                        let mut out = self.drain(self.held.len());
+                        out.push((event.to_string(), payload));
+                        return (out, None);
+                    }
+                }
+                _ => held.payload = payload,
+            }
+            return (Vec::new(), None);
+        }
+
+        let due = now + window;
+        self.held.push(Held {
+            event: event.to_string(),
+            kind: kind.to_string(),
+            payload,
+            due,
+        });
+        (Vec::new(), Some(due))
+    }
+
+    /// Events whose window has closed, plus everything held before them.
+    fn take_due(&mut self, now: Instant) -> Vec<(String, Value)> {
+        match self.held.iter().rposition(|h| h.due <= now) {
+            Some(last) => self.drain(last + 1),
+            None => Vec::new(),
+        }
+    }
+
+    fn drain(&mut self, count: usize) -> Vec<(String, Value)> {
+        self.held
+            .drain(..count)
+            .map(|h| (h.event, h.payload))
+            .collect()
+    }
+}
+
+/// Append `next[field]` to `held[field]`. False if either isn't a string.
+fn append_field(held: &mut Value, next: &Value, field: &str) -> bool {
+    let Some(extra) = next.get(field).and_then(|v| v.as_str()) else {
+        return false;
+    };
+    match held.get_mut(field) {
+        Some(Value::String(text)) => {
+            text.push_str(extra);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn policy_for(config: &EventThrottleConfig, event: &str, kind: &str) -> Policy {
+    if !config.enabled {
+        return Policy::Immediate;
+    }
+    let policy = match (event, kind) {
+        ("voice-event", "state_change") => {
+            Policy::Coalesce(Duration::from_millis(config.state_coalesce_ms))
+        }
+        ("voice-event", "audio_level") => {
+            Policy::Coalesce(Duration::from_millis(config.level_interval_ms))
+        }
+        ("ai-stream-token", _) => {
+            Policy::Batch(Duration::from_millis(config.token_batch_ms), "token")
+        }
+        _ => Policy::Immediate,
+    };
+    match policy {
+        Policy::Coalesce(w) | Policy::Batch(w, _) if w.is_zero() => Policy::Immediate,
+        other => other,
+    }
+}
+
+struct Throttle {
+    config: EventThrottleConfig,
+    queue: Mutex<HeldQueue>,
+}
+
+static THROTTLE: OnceLock<Throttle> = OnceLock::new();
+
+/// Install the emission policies. Until this runs, events go straight out.
+pub fn init(config: EventThrottleConfig) {
+    tracing::debug!(?config, "Event throttling configured");
+    let _ = THROTTLE.set(Throttle {
+        config,
+        queue: Mutex::new(HeldQueue::default()),
+    });
+}
+
+/// `emit` with the throttling policy for the event applied.
+pub trait EmitThrottled {
+    /// Returns the emit result for events sent now, or `Ok` for held ones.
+    fn emit_throttled<S: Serialize>(&self, event: &str, payload: S) -> tauri::Result<()>;
+}
+
+impl EmitThrottled for AppHandle {
+    fn emit_throttled<S: Serialize>(&self, event: &str, payload: S) -> tauri::Result<()> {
+        let payload = serde_json::to_value(payload)?;
+        let Some(throttle) = THROTTLE.get() else {
+            return self.emit(event, payload);
+        };
+        let kind = if event == "voice-event" {
+            payload.get("event").and_then(|e| e.as_str()).unwrap_or("")
+        } else {
+            ""
+        }
+        .to_string();
+        let policy = policy_for(&throttle.config, event, &kind);
+
+        let (ready, flush_at) = match throttle.queue.lock() {
+            Ok(mut queue) => queue.push(event, &kind, payload, policy, Instant::now()),
+            Err(_) => return self.emit(event, payload),
+        };
+        if let Some(at) = flush_at {
+            let app = self.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep_until(tokio::time::Instant::from_std(at)).await;
+                let due = match throttle.queue.lock() {
+                    Ok(mut queue) => queue.take_due(Instant::now()),
+                    Err(_) => return,
+                };
+                let _ = emit_all(&app, due);
+            });
+        }
+        // Emitted outside the lock: Rust-side listeners run inline and may
+        // emit themselves.
+        emit_all(self, ready)
+    }
+}
+
+fn emit_all(app: &AppHandle, events: Vec<(String, Value)>) -> tauri::Result<()> {
+    let mut result = Ok(());
+    for (event, payload) in events {
+        if let Err(e) = app.emit(&event, payload) {
+            result = Err(e);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WINDOW: Duration = Duration::from_millis(30);
+
+    #[test]
+    fn test_coalesce_keeps_latest_and_flushes_before_immediate() {
+        let now = Instant::now();
+        let mut queue = HeldQueue::default();
+        let coalesce = Policy::Coalesce(WINDOW);
+        let state = |s: &str| json!({"event": "state_change", "data": {"state": s}});
+
+        let (out, flush) = queue.push(
+            "voice-event",
+            "state_change",
+            state("recording"),
+            coalesce,
+            now,
+        );
+        assert!(out.is_empty());
+        assert_eq!(flush, Some(now + WINDOW));
+        let (out, flush) = queue.push(
+            "voice-event",
+            "state_change",
+            state("processing"),
+            coalesce,
+            now,
+        );
+        assert!(out.is_empty() && flush.is_none());
+
+        let done = json!({"event": "transcription", "data": {"text": "hi"}});
+        let (out, _) = queue.push("voice-event", "transcription", done, Policy::Immediate, now);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].1["data"]["state"], "processing");
+        assert_eq!(out[1].1["event"], "transcription");
+        assert!(queue.take_due(now + WINDOW).is_empty());
+    }
+
+    #[test]
+    fn test_batch_joins_tokens_until_due() {
+        let now = Instant::now();
+        let mut queue = HeldQueue::default();
+        let batch = Policy::Batch(WINDOW, "token");
+        for token in ["Hel", "lo", "!"] {
+            queue.push("ai-stream-token", "", json!({"token": token}), batch, now);
+        }
+        assert!(queue.take_due(now).is_empty());
+
+        let out = queue.take_due(now + WINDOW);
+        assert_eq!(
+            out,
+            vec![("ai-stream-token".to_string(), json!({"token": "Hello!"}))]
+        );
+    }
+
+    #[test]
+    fn test_policy_for() {
+        let config = EventThrottleConfig::default();
+        assert_eq!(
+            policy_for(&config, "voice-event", "state_change"),
+            Policy::Coalesce(Duration::from_millis(16))
+        );
+        // Levels default to a 0ms window, i.e. unthrottled
+        assert_eq!(
+            policy_for(&config, "voice-event", "audio_level"),
+            Policy::Immediate
+        );
+        assert_eq!(policy_for(&config, "ai-stream-end", ""), Policy::Immediate);
+
+        let disabled = EventThrottleConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            policy_for(&disabled, "ai-stream-token", ""),
+            Policy::Immediate
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! OS-level global hotkeys for voice control (`hotkeys` config).
+//!
+//! The frontend's shortcuts reach the voice engine through the webview. These
+//! act on the engine straight from the shortcut callback, so they work while
+//! the window is hidden or unfocused:
+//! - push-to-talk: record while held
+//! - toggle: start a recording, or stop the current one
+//! - stop speaking: interrupt TTS playback
+//! - cycle mode: push-to-talk -> toggle -> wake word -> push-to-talk. The new
+//!   mode is saved to `behavior.activationMode` and announced with a
+//!   `voice-mode-changed` event (`{ mode }`).
+//!
+//! Bindings are accelerators ("CommandOrControl+Shift+Space") registered
+//! with the global-shortcut plugin. An empty binding leaves the action
+//! unbound. Only this module's shortcuts are replaced when the config
+//! changes; the frontend's are left alone.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{info, warn};
+
+use crate::commands::voice::VoiceEngineState;
+use crate::config::schema::HotkeyConfig;
+use crate::error::AppError;
+use crate::voice::{VoiceMode, VoiceState};
+
+/// What a hotkey does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyAction {
+    PushToTalk,
+    Toggle,
+    StopSpeaking,
+    CycleMode,
+}
+
+/// Outcome of registering one binding.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyStatus {
+    pub action: HotkeyAction,
+    pub keys: String,
+    /// Why the hotkey isn't active, or `None` when it is.
+    pub error: Option<String>,
+}
+
+/// Shortcuts registered by this module.
+static REGISTERED: Mutex<Vec<Shortcut>> = Mutex::new(Vec::new());
+
+/// Register the configured hotkeys at app launch.
+pub fn start_from_config(app: &AppHandle) {
+    let cfg = crate::commands::config::get_config_snapshot().hotkeys;
+    apply(app, &cfg);
+}
+
+/// Replace the registered hotkeys with `cfg`'s. Returns one status per
+/// bound action; a binding that fails doesn't stop the others.
+pub fn apply(app: &AppHandle, cfg: &HotkeyConfig) -> Vec<HotkeyStatus> {
+    let mut registered = REGISTERED.lock().unwrap_or_else(|e| e.into_inner());
+    for shortcut in registered.drain(..) {
+        if let Err(e) = app.global_shortcut().unregister(shortcut) {
+            warn!("Failed to unregister voice hotkey: {}", e);
+        }
+    }
+
+    let mut statuses = Vec::new();
+    for (action, keys, parsed) in parse_bindings(cfg) {
+        let result = parsed.and_then(|shortcut| {
+            app.global_shortcut()
+                .on_shortcut(shortcut, move |app, _shortcut, event| {
+                    on_hotkey(app, action, event.state)
+                })
+                .map_err(|e| format!("Failed to register: {}", e))?;
+            registered.push(shortcut);
+            Ok(())
+        });
+        match &result {
+            Ok(()) => info!(?action, keys = %keys, "Voice hotkey registered"),
+            Err(e) => warn!(?action, keys = %keys, "Voice hotkey not registered: {}", e),
+        }
+        statuses.push(HotkeyStatus {
+            action,
+            keys,
+            error: result.err(),
+        });
+    }
+    statuses
+}
+
+/// The bound actions with their parsed shortcuts. A combination already
+/// taken by an earlier action is an error.
+fn parse_bindings(cfg: &HotkeyConfig) -> Vec<(HotkeyAction, String, Result<Shortcut, String>)> {
+    let bindings = [
+        (HotkeyAction::PushToTalk, &cfg.push_to_talk),
+        (HotkeyAction::Toggle, &cfg.toggle),
+        (HotkeyAction::StopSpeaking, &cfg.stop_speaking),
+        (HotkeyAction::CycleMode, &cfg.cycle_mode),
+    ];
+    let mut taken: Vec<(Shortcut, HotkeyAction)> = Vec::new();
+    bindings
+        .into_iter()
+        .map(|(action, keys)| (action, keys.trim()))
+        .filter(|(_, keys)| !keys.is_empty())
+        .map(|(action, keys)| {
+            let parsed = keys
+                .parse::<Shortcut>()
+                .map_err(|e| format!("Invalid key combination '{}': {}", keys, e))
+                .and_then(
+                    |shortcut| match taken.iter().find(|(s, _)| *s == shortcut) {
+                        Some((_, other)) => Err(format!("Already bound to {:?}", other)),
+                        None => {
+                            taken.push((shortcut, action));
+                            Ok(shortcut)
+                        }
+                    },
+                );
+            (action, keys.to_string(), parsed)
+        })
+        .collect()
+}
+
+fn on_hotkey(app: &AppHandle, action: HotkeyAction, state: ShortcutState) {
+    let pressed = matches!(state, ShortcutState::Pressed);
+    if !pressed && action != HotkeyAction::PushToTalk {
+        return;
+    }
+    let app = app.clone();
+    // Off the event loop: the engine lock can be held through a restart
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = run_action(&app, action, pressed) {
+            warn!(?action, "Voice hotkey failed: {}", e);
+        }
+    });
+}
+
+pub(crate) fn run_action(app: &AppHandle, action: HotkeyAction, pressed: bool) -> Result<(), AppError> {
+    let state = app.state::<VoiceEngineState>();
+    let mut engine = state
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock voice state: {}", e)))?;
+    match action {
+        HotkeyAction::CycleMode => {
+            let mode = next_mode(engine.config().mode);
+            engine.set_mode(mode);
+            drop(engine);
+            save_mode(app, mode);
+            Ok(())
+        }
+        _ if !engine.is_running() => Err(AppError::NotRunning),
+        HotkeyAction::PushToTalk if pressed => engine.start_recording(),
+        HotkeyAction::PushToTalk => engine.stop_recording(),
+        HotkeyAction::Toggle if engine.state() == VoiceState::Recording => engine.stop_recording(),
+        HotkeyAction::Toggle => engine.start_recording(),
+        HotkeyAction::StopSpeaking => {
+            engine.stop_speaking();
+            Ok(())
+        }
+    }
+}
+
+/// Persist a mode switch (hotkey or voice command) and tell the frontend,
+/// whose PTT handling and settings follow `behavior.activationMode`.
+pub(crate) fn save_mode(app: &AppHandle, mode: VoiceMode) {
+    let saved = crate::commands::config::set_config(json!({
+        "behavior": { "activationMode": mode.to_string() },
+    }));
+    if let Some(e) = saved.error {
+        warn!("Failed to save activation mode: {}", e);
+    }
+    info!(%mode, "Voice mode switched");
+    let _ = app.emit("voice-mode-changed", json!({ "mode": mode.to_string() }));
+}
+
+fn next_mode(mode: VoiceMode) -> VoiceMode {
+    match mode {
+        VoiceMode::PushToTalk => VoiceMode::Toggle,
+        VoiceMode::Toggle => VoiceMode::WakeWord,
+        VoiceMode::WakeWord => VoiceMode::PushToTalk,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bindings() {
+        let cfg = HotkeyConfig {
+            push_to_talk: "CommandOrControl+Shift+Space".into(),
+            toggle: String::new(),
+            stop_speaking: " Control+Shift+Space ".into(),
+            cycle_mode: "Ctrl+Shift+Nope".into(),
+        };
+        let parsed = parse_bindings(&cfg);
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[0].0, HotkeyAction::PushToTalk);
+        assert!(parsed[0].2.is_ok());
+        // Same combination spelled differently (on Windows/Linux)
+        #[cfg(not(target_os = "macos"))]
+        assert!(parsed[1].2.as_ref().unwrap_err().contains("PushToTalk"));
+        assert_eq!(parsed[1].1, "Control+Shift+Space");
+        assert!(parsed[2].2.is_err());
+    }
+
+    #[test]
+    fn test_next_mode_cycles() {
+        let mut mode = VoiceMode::PushToTalk;
+        for expected in [
+            VoiceMode::Toggle,
+            VoiceMode::WakeWord,
+            VoiceMode::PushToTalk,
+        ] {
+            mode = next_mode(mode);
+            assert_eq!(mode, expected);
+        }
+    }
+}
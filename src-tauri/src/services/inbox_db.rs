@@ -0,0 +1,523 @@
+//! SQLite message store behind the MCP voice inbox.
+//!
+//! `{data_dir}/inbox.db` holds the messages `voice_send` writes and the user
+//! messages the app writes for `voice_listen`. Both processes open it (WAL
+//! mode, so a reader never blocks the writer) instead of rewriting one
+//! `inbox.json` per message:
+//!
+//! - `messages`: one row per message. `seq` orders them and lets readers
+//!   ask for "everything after the last one I saw"; `created_ms` drives the
+//!   TTL. Indexed on `instance_id` (the sender) and `thread_id`.
+//! - `message_reads`: which instance has read which message (`voice_inbox`
+//!   with `mark_as_read`), indexed by reader.
+//!
+//! Messages older than `MESSAGE_TTL`, and all but the newest `MAX_MESSAGES`,
+//! are removed by `cleanup`. An `inbox.json` left by an older version is
+//! imported on open and renamed to `inbox.json.migrated`.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Database file name in the MCP data directory.
+pub const DB_FILE: &str = "inbox.db";
+
+/// Messages older than this are removed by `cleanup`.
+pub const MESSAGE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Messages kept at most; older ones go first.
+pub const MAX_MESSAGES: usize = 500;
+
+/// How long a write waits for the other process's transaction.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The JSON inbox this store replaces.
+const LEGACY_JSON_FILE: &str = "inbox.json";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS messages (
+        seq            INTEGER PRIMARY KEY AUTOINCREMENT,
+        id             TEXT NOT NULL UNIQUE,
+        instance_id    TEXT NOT NULL,
+        message        TEXT NOT NULL,
+        timestamp      TEXT NOT NULL,
+        created_ms     INTEGER NOT NULL,
+        thread_id      TEXT,
+        reply_to       TEXT,
+        image_path     TEXT,
+        image_data_url TEXT,
+        priority       TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_messages_instance ON messages(instance_id);
+    CREATE INDEX IF NOT EXISTS idx_messages_thread ON messages(thread_id);
+    CREATE INDEX IF NOT EXISTS idx_messages_created ON messages(created_ms);
+    CREATE TABLE IF NOT EXISTS message_reads (
+        message_id  TEXT NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+        instance_id TEXT NOT NULL,
+        PRIMARY KEY (message_id, instance_id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_reads_instance ON message_reads(instance_id, message_id);
+";
+
+/// Columns for `message_from_row`, in order.
+const COLUMNS: &str = "seq, id, instance_id, message, timestamp, thread_id, reply_to, \
+     image_path, image_data_url, priority, \
+     (SELECT group_concat(r.instance_id, char(31)) FROM message_reads r WHERE r.message_id = messages.id)";
+
+/// A message in the inbox (also the `inbox.json` format it migrates from).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxMessage {
+    pub id: String,
+    pub from: String,
+    pub message: String,
+    pub timestamp: String,
+    #[serde(default)]
+    pub read_by: Vec<String>,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    #[serde(default)]
+    pub image_path: Option<String>,
+    #[serde(default)]
+    pub image_data_url: Option<String>,
+    /// Speech priority requested by `voice_send`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+}
+
+/// The `inbox.json` layout.
+#[derive(Debug, Default, Deserialize)]
+struct LegacyInbox {
+    #[serde(default)]
+    messages: Vec<InboxMessage>,
+}
+
+/// An open inbox database.
+pub struct InboxDb {
+    conn: Connection,
+}
+
+impl InboxDb {
+    /// Open (creating if needed) the inbox in `data_dir`, importing a
+    /// leftover `inbox.json`.
+    pub fn open(data_dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Failed to create data dir: {}", e))?;
+        let conn = Connection::open(db_path(data_dir))
+            .map_err(|e| format!("Failed to open inbox database: {}", e))?;
+        let db = Self::init(conn)?;
+        db.migrate_json(data_dir);
+        Ok(db)
+    }
+
+    fn init(conn: Connection) -> Result<Self, String> {
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(db_err)?;
+        // journal_mode returns the mode, so it can't go through execute
+        conn.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))
+            .map_err(db_err)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")
+            .map_err(db_err)?;
+        conn.execute_batch(SCHEMA).map_err(db_err)?;
+        Ok(Self { conn })
+    }
+
+    /// Add a message. A message whose id is already stored is ignored.
+    pub fn insert(&self, msg: &InboxMessage) -> Result<(), String> {
+        self.insert_at(msg, now_ms())
+    }
+
+    fn insert_at(&self, msg: &InboxMessage, created_ms: u64) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO messages (id, instance_id, message, timestamp, created_ms, \
+                 thread_id, reply_to, image_path, image_data_url, priority) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    msg.id,
+                    msg.from,
+                    msg.message,
+                    msg.timestamp,
+                    created_ms as i64,
+                    msg.thread_id,
+                    msg.reply_to,
+                    msg.image_path,
+                    msg.image_data_url,
+                    msg.priority,
+                ],
+            )
+            .map_err(db_err)?;
+        for reader in &msg.read_by {
+            self.conn
+                .execute(
+                    "INSERT OR IGNORE INTO message_reads (message_id, instance_id) VALUES (?1, ?2)",
+                    params![msg.id, reader],
+                )
+                .map_err(db_err)?;
+        }
+        Ok(())
+    }
+
+    /// The thread of message `id`, if it is stored and has one.
+    pub fn thread_of(&self, id: &str) -> Result<Option<String>, String> {
+        self.conn
+            .query_row(
+                "SELECT thread_id FROM messages WHERE id = ?1",
+                params![id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()
+            .map(Option::flatten)
+            .map_err(db_err)
+    }
+
+    /// The newest `limit` messages not sent by `instance_id`, oldest first.
+    /// Without `include_read`, only those it hasn't read.
+    pub fn messages_for(
+        &self,
+        instance_id: &str,
+        include_read: bool,
+        limit: usize,
+    ) -> Result<Vec<InboxMessage>, String> {
+        let sql = format!(
+            "SELECT * FROM (SELECT {} FROM messages WHERE instance_id != ?1 \
+             AND (?2 OR NOT EXISTS (SELECT 1 FROM message_reads r \
+                  WHERE r.message_id = messages.id AND r.instance_id = ?1)) \
+             ORDER BY seq DESC LIMIT ?3) ORDER BY seq",
+            COLUMNS
+        );
+        let mut stmt = self.conn.prepare(&sql).map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![instance_id, include_read, limit as i64], |row| {
+                message_from_row(row).map(|(_, msg)| msg)
+            })
+            .map_err(db_err)?;
+        rows.collect::<Result<_, _>>().map_err(db_err)
+    }
+
+    /// Mark every message not sent by `instance_id` as read by it. Returns
+    /// how many were newly marked.
+    pub fn mark_read(&self, instance_id: &str) -> Result<usize, String> {
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO message_reads (message_id, instance_id) \
+                 SELECT id, ?1 FROM messages WHERE instance_id != ?1",
+                params![instance_id],
+            )
+            .map_err(db_err)
+    }
+
+    /// `seq` of the newest message, 0 when empty.
+    pub fn last_seq(&self) -> Result<i64, String> {
+        self.conn
+            .query_row("SELECT COALESCE(MAX(seq), 0) FROM messages", [], |row| row.get(0))
+            .map_err(db_err)
+    }
+
+    /// Messages added after `seq`, oldest first, with their `seq`.
+    pub fn newer_than(&self, seq: i64) -> Result<Vec<(i64, InboxMessage)>, String> {
+        let sql = format!("SELECT {} FROM messages WHERE seq > ?1 ORDER BY seq", COLUMNS);
+        let mut stmt = self.conn.prepare(&sql).map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![seq], message_from_row)
+            .map_err(db_err)?;
+        rows.collect::<Result<_, _>>().map_err(db_err)
+    }
+
+    /// Remove messages older than `ttl` and all but the newest `max`.
+    /// Returns how many were removed.
+    pub fn cleanup(&self, ttl: Duration, max: usize) -> Result<usize, String> {
+        let cutoff = now_ms().saturating_sub(ttl.as_millis() as u64) as i64;
+        let expired = self
+            .conn
+            .execute("DELETE FROM messages WHERE created_ms < ?1", params![cutoff])
+            .map_err(db_err)?;
+        let excess = self
+            .conn
+            .execute(
+                "DELETE FROM messages WHERE seq NOT IN \
+                 (SELECT seq FROM messages ORDER BY seq DESC LIMIT ?1)",
+                params![max as i64],
+            )
+            .map_err(db_err)?;
+        Ok(expired + excess)
+    }
+
+    /// Remove every message.
+    pub fn clear(&self) -> Result<(), String> {
+        self.conn
+            .execute_batch("DELETE FROM message_reads; DELETE FROM messages;")
+            .map_err(db_err)
+    }
+
+    /// Import `inbox.json` from `data_dir`, if there is one, and rename it
+    /// so it is imported once.
+    fn migrate_json(&self, data_dir: &Path) {
+        let json_path = data_dir.join(LEGACY_JSON_FILE);
+        let Ok(raw) = std::fs::read_to_string(&json_path) else {
+            return;
+        };
+        let legacy: LegacyInbox = serde_json::from_str(&raw).unwrap_or_else(|e| {
+            warn!("Unreadable {}, not importing it: {}", LEGACY_JSON_FILE, e);
+            LegacyInbox::default()
+        });
+        let count = legacy.messages.len();
+        let now = now_ms();
+        let imported = (|| -> Result<(), String> {
+            let tx = self.conn.unchecked_transaction().map_err(db_err)?;
+            for msg in &legacy.messages {
+                let created = parse_timestamp_ms(&msg.timestamp).unwrap_or(now);
+                self.insert_at(msg, created)?;
+            }
+            tx.commit().map_err(db_err)
+        })();
+        if let Err(e) = imported {
+            warn!("Failed to import {}: {}", LEGACY_JSON_FILE, e);
+            return;
+        }
+        let _ = std::fs::rename(&json_path, json_path.with_extension("json.migrated"));
+        info!("Imported {} message(s) from {} into {}", count, LEGACY_JSON_FILE, DB_FILE);
+    }
+}
+
+/// Path of the inbox database in `data_dir`.
+pub fn db_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(DB_FILE)
+}
+
+/// Whether `file_name` is the database or one of its journal files (for
+/// file watchers).
+pub fn is_db_file(file_name: &str) -> bool {
+    file_name
+        .strip_prefix(DB_FILE)
+        .is_some_and(|rest| matches!(rest, "" | "-wal" | "-journal"))
+}
+
+fn message_from_row(row: &Row<'_>) -> rusqlite::Result<(i64, InboxMessage)> {
+    let read_by: Option<String> = row.get(10)?;
+    Ok((
+        row.get(0)?,
+        InboxMessage {
+            id: row.get(1)?,
+            from: row.get(2)?,
+            message: row.get(3)?,
+            timestamp: row.get(4)?,
+            thread_id: row.get(5)?,
+            reply_to: row.get(6)?,
+            image_path: row.get(7)?,
+            image_data_url: row.get(8)?,
+            priority: row.get(9)?,
+            read_by: read_by
+                .map(|r| r.split('\u{1f}').map(str::to_string).collect())
+                .unwrap_or_default(),
+        },
+    ))
+}
+
+fn db_err(e: rusqlite::Error) -> String {
+    format!("Inbox database error: {}", e)
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Epoch ms of an inbox timestamp: ISO 8601 from `voice_send`
+/// (`2025-01-15T10:30:00.000Z`) or epoch seconds from the app
+/// (`1736937000.000Z`).
+pub(crate) fn parse_timestamp_ms(ts: &str) -> Option<u64> {
+    let ts = ts.trim_end_matches('Z');
+    if let Ok(secs) = ts.parse::<f64>() {
+        return Some((secs * 1000.0) as u64);
+    }
+    let (date, time) = ts.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|p| p.parse::<i64>());
+    let (y, m, d) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let (hms, millis) = time.split_once('.').unwrap_or((time, "0"));
+    let mut hms = hms.splitn(3, ':').map(|p| p.parse::<u64>());
+    let (hh, mm, ss) = (hms.next()?.ok()?, hms.next()?.ok()?, hms.next()?.ok()?);
+    let days = days_from_civil(y, m as u32, d as u32);
+    let secs = u64::try_from(days).ok()? * 86_400 + hh * 3600 + mm * 60 + ss;
+    Some(secs * 1000 + millis.get(..3).unwrap_or(millis).parse::<u64>().unwrap_or(0))
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    // Algorithm from https://howardhinnant.github.io/date_algorithms.html
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_db() -> InboxDb {
+        InboxDb::init(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn message(id: &str, from: &str, thread_id: Option<&str>) -> InboxMessage {
+        InboxMessage {
+            id: id.into(),
+            from: from.into(),
+            message: format!("text of {}", id),
+            timestamp: "2025-01-01T00:00:00.000Z".into(),
+            read_by: vec![],
+            thread_id: thread_id.map(str::to_string),
+            reply_to: None,
+            image_path: None,
+            image_data_url: None,
+            priority: None,
+        }
+    }
+
+    fn ids(messages: &[InboxMessage]) -> Vec<&str> {
+        messages.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_messages_for_skips_own_and_read() {
+        let db = memory_db();
+        db.insert(&message("m1", "user", None)).unwrap();
+        db.insert(&message("m2", "voice-claude", None)).unwrap();
+        db.insert(&message("m3", "user", None)).unwrap();
+
+        let unread = db.messages_for("voice-claude", false, 10).unwrap();
+        assert_eq!(ids(&unread), ["m1", "m3"]);
+
+        assert_eq!(db.mark_read("voice-claude").unwrap(), 2);
+        assert!(db.messages_for("voice-claude", false, 10).unwrap().is_empty());
+
+        let all = db.messages_for("voice-claude", true, 10).unwrap();
+        assert_eq!(all[0].read_by, ["voice-claude"]);
+    }
+
+    #[test]
+    fn test_messages_for_limit_keeps_newest() {
+        let db = memory_db();
+        for i in 0..5 {
+            db.insert(&message(&format!("m{}", i), "user", None)).unwrap();
+        }
+        let newest = db.messages_for("voice-claude", false, 2).unwrap();
+        assert_eq!(ids(&newest), ["m3", "m4"]);
+    }
+
+    #[test]
+    fn test_duplicate_id_is_ignored() {
+        let db = memory_db();
+        db.insert(&message("m1", "user", None)).unwrap();
+        db.insert(&message("m1", "user", Some("other"))).unwrap();
+        assert_eq!(db.newer_than(0).unwrap().len(), 1);
+        assert_eq!(db.thread_of("m1").unwrap(), None);
+    }
+
+    #[test]
+    fn test_thread_of() {
+        let db = memory_db();
+        db.insert(&message("m1", "user", Some("voice-mirror"))).unwrap();
+        assert_eq!(db.thread_of("m1").unwrap().as_deref(), Some("voice-mirror"));
+        assert_eq!(db.thread_of("missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_newer_than() {
+        let db = memory_db();
+        db.insert(&message("m1", "user", None)).unwrap();
+        let seq = db.last_seq().unwrap();
+        db.insert(&message("m2", "user", None)).unwrap();
+
+        let newer = db.newer_than(seq).unwrap();
+        assert_eq!(newer.len(), 1);
+        assert_eq!(newer[0].1.id, "m2");
+        assert_eq!(newer[0].0, db.last_seq().unwrap());
+    }
+
+    #[test]
+    fn test_cleanup_ttl_and_cap() {
+        let db = memory_db();
+        let old = now_ms() - 2 * MESSAGE_TTL.as_millis() as u64;
+        db.insert_at(&message("old", "user", None), old).unwrap();
+        for i in 0..4 {
+            db.insert(&message(&format!("m{}", i), "user", None)).unwrap();
+        }
+        db.mark_read("voice-claude").unwrap();
+
+        assert_eq!(db.cleanup(MESSAGE_TTL, 3).unwrap(), 2);
+        let left = db.messages_for("voice-claude", true, 10).unwrap();
+        assert_eq!(ids(&left), ["m1", "m2", "m3"]);
+        // Read marks went with their messages
+        let reads: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM message_reads", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(reads, 3);
+    }
+
+    #[test]
+    fn test_clear() {
+        let db = memory_db();
+        db.insert(&message("m1", "user", None)).unwrap();
+        db.mark_read("voice-claude").unwrap();
+        db.clear().unwrap();
+        assert_eq!(db.last_seq().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_migrates_json_inbox() {
+        let dir = std::env::temp_dir().join(format!("vm-inbox-db-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let json = r#"{"messages":[
+            {"id":"msg-1","from":"user","message":"test","timestamp":"2025-01-01T00:00:00Z","read_by":["voice-claude"],"thread_id":"voice-mirror"},
+            {"id":"msg-2","from":"voice-claude","message":"reply","timestamp":"1735689600.000Z","priority":"queue"}
+        ]}"#;
+        std::fs::write(dir.join(LEGACY_JSON_FILE), json).unwrap();
+
+        let db = InboxDb::open(&dir).unwrap();
+        let messages: Vec<InboxMessage> =
+            db.newer_than(0).unwrap().into_iter().map(|(_, m)| m).collect();
+        assert_eq!(ids(&messages), ["msg-1", "msg-2"]);
+        assert_eq!(messages[0].read_by, ["voice-claude"]);
+        assert_eq!(messages[0].thread_id.as_deref(), Some("voice-mirror"));
+        assert_eq!(messages[1].priority.as_deref(), Some("queue"));
+        assert!(!dir.join(LEGACY_JSON_FILE).exists());
+        assert!(dir.join("inbox.json.migrated").exists());
+
+        // Opening again doesn't import twice
+        drop(db);
+        let db = InboxDb::open(&dir).unwrap();
+        assert_eq!(db.newer_than(0).unwrap().len(), 2);
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_timestamp_ms() {
+        assert_eq!(parse_timestamp_ms("1970-01-01T00:00:01.500Z"), Some(1500));
+        assert_eq!(
+            parse_timestamp_ms("2025-01-01T00:00:00Z"),
+            Some(1_735_689_600_000)
+        );
+        assert_eq!(parse_timestamp_ms("1735689600.000Z"), Some(1_735_689_600_000));
+        assert_eq!(parse_timestamp_ms("not a time"), None);
+    }
+
+    #[test]
+    fn test_is_db_file() {
+        assert!(is_db_file("inbox.db"));
+        assert!(is_db_file("inbox.db-wal"));
+        assert!(!is_db_file("inbox.db-shm"));
+        assert!(!is_db_file("inbox.json"));
+    }
+}
@@ -1,43 +1,19 @@
 //! Inbox watcher service for MCP message bridge.
 //!
-//! Watches the MCP inbox JSON file for changes and forwards messages
-//! as Tauri events (`mcp-inbox-message`). This bridges the Node.js
-//! MCP server with the Tauri frontend.
+//! Watches the MCP inbox database (`services::inbox_db`) for changes and
+//! forwards new messages as Tauri events (`mcp-inbox-message`). This is the
+//! file-based fallback for when the named pipe isn't connected.
 
-use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 use tracing::{debug, error, info, warn};
 
-/// Inbox JSON structure matching the MCP server format.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct InboxData {
-    #[serde(default)]
-    pub messages: Vec<InboxMessage>,
-}
-
-/// A single message in the inbox.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct InboxMessage {
-    pub id: String,
-    pub from: String,
-    pub message: String,
-    pub timestamp: String,
-    #[serde(default)]
-    pub read_by: Vec<String>,
-    #[serde(default)]
-    pub thread_id: Option<String>,
-    #[serde(default)]
-    pub reply_to: Option<String>,
-    #[serde(default)]
-    pub image_path: Option<String>,
-    #[serde(default)]
-    pub image_data_url: Option<String>,
-}
+use super::inbox_db::{self, InboxDb, InboxMessage};
+use super::instances::{self, SenderVoice};
 
 /// Event payload emitted to the frontend.
 #[derive(Debug, Clone, Serialize)]
@@ -57,37 +33,46 @@ pub struct InboxEvent {
     pub thread_id: Option<String>,
     /// Reply-to ID if present
     pub reply_to: Option<String>,
+    /// Whether an AI reply should be spoken (`send_text_message`'s `speak`)
+    pub speak: bool,
+    /// Speech priority for `speak_text` ("interrupt", "queue", "drop_if_busy")
+    pub priority: Option<String>,
+    /// TTS voice the sender registered with `voice_status`
+    pub voice: Option<String>,
+    /// Sender name to say before the text, when another agent sounds the same
+    pub announce: Option<String>,
 }
 
 /// Shared state for the inbox watcher.
 struct WatcherState {
-    /// IDs of messages we've already emitted events for.
-    seen_ids: HashSet<String>,
+    /// `seq` of the newest message we've emitted an event for (or skipped
+    /// as already there at startup).
+    last_seq: i64,
 }
 
 impl WatcherState {
-    fn new() -> Self {
+    /// Start after the messages already in `db`, to avoid re-emitting old ones.
+    fn after_existing(db: &InboxDb) -> Self {
         Self {
-            seen_ids: HashSet::new(),
+            last_seq: db.last_seq().unwrap_or(0),
         }
     }
 
-    /// Seed seen IDs from existing messages to avoid re-emitting old ones.
-    fn seed_from_messages(&mut self, messages: &[InboxMessage]) {
-        for msg in messages {
-            self.seen_ids.insert(msg.id.clone());
+    /// Messages added since the last call.
+    fn take_new(&mut self, db: &InboxDb) -> Vec<InboxMessage> {
+        match db.newer_than(self.last_seq) {
+            Ok(new) => {
+                if let Some((seq, _)) = new.last() {
+                    self.last_seq = *seq;
+                }
+                new.into_iter().map(|(_, msg)| msg).collect()
+            }
+            Err(e) => {
+                debug!("Failed to read the inbox: {}", e);
+                vec![]
+            }
         }
     }
-
-    /// Check if we've already seen this message ID.
-    fn is_seen(&self, id: &str) -> bool {
-        self.seen_ids.contains(id)
-    }
-
-    /// Mark a message ID as seen.
-    fn mark_seen(&mut self, id: String) {
-        self.seen_ids.insert(id);
-    }
 }
 
 /// Get the MCP server data directory.
@@ -106,42 +91,28 @@ pub fn get_mcp_data_dir() -> PathBuf {
         .join("data")
 }
 
-/// Get the path to the MCP inbox file.
+/// Get the path to the MCP inbox database.
 pub fn get_inbox_path() -> PathBuf {
-    get_mcp_data_dir().join("inbox.json")
+    inbox_db::db_path(&get_mcp_data_dir())
+}
+
+/// How to speak a reply from AI instance `from` (see `services::instances`).
+pub fn sender_voice(from: &str) -> SenderVoice {
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    instances::sender_voice(&get_mcp_data_dir(), from, now_ms)
 }
 
-/// Clear the inbox file, resetting it to an empty message list.
+/// Clear the inbox, removing every message.
 ///
 /// Called on app startup and when a new MCP session begins (pipe Ready)
 /// to prevent stale messages from leaking across sessions.
 pub fn clear_inbox() {
-    let inbox_path = get_inbox_path();
-    if inbox_path.exists() {
-        match std::fs::write(&inbox_path, r#"{"messages":[]}"#) {
-            Ok(()) => info!("Cleared inbox.json for new session"),
-            Err(e) => warn!("Failed to clear inbox.json: {}", e),
-        }
-    }
-}
-
-/// Read and parse the inbox file.
-fn read_inbox(path: &std::path::Path) -> Option<InboxData> {
-    match std::fs::read_to_string(path) {
-        Ok(raw) => match serde_json::from_str::<InboxData>(&raw) {
-            Ok(data) => Some(data),
-            Err(e) => {
-                // SyntaxError is expected during atomic writes
-                debug!("Failed to parse inbox.json: {}", e);
-                None
-            }
-        },
-        Err(e) => {
-            if e.kind() != std::io::ErrorKind::NotFound {
-                debug!("Failed to read inbox.json: {}", e);
-            }
-            None
-        }
+    match InboxDb::open(&get_mcp_data_dir()).and_then(|db| db.clear()) {
+        Ok(()) => info!("Cleared inbox for new session"),
+        Err(e) => warn!("Failed to clear inbox: {}", e),
     }
 }
 
@@ -160,28 +131,14 @@ fn classify_sender(from: &str) -> &'static str {
 }
 
 /// Process the inbox: find new messages and emit events.
-fn process_inbox(
-    inbox_path: &std::path::Path,
-    state: &mut WatcherState,
-    app_handle: &AppHandle,
-) {
-    let data = match read_inbox(inbox_path) {
-        Some(d) => d,
-        None => return,
-    };
-
-    if data.messages.is_empty() {
-        return;
-    }
-
-    for msg in &data.messages {
-        if state.is_seen(&msg.id) {
-            continue;
-        }
-
-        state.mark_seen(msg.id.clone());
-
+fn process_inbox(db: &InboxDb, state: &mut WatcherState, app_handle: &AppHandle) {
+    for msg in state.take_new(db) {
         let kind = classify_sender(&msg.from);
+        let spoken = if kind == "ai_message" {
+            sender_voice(&msg.from)
+        } else {
+            SenderVoice::default()
+        };
 
         let event = InboxEvent {
             kind: kind.to_string(),
@@ -191,6 +148,10 @@ fn process_inbox(
             timestamp: msg.timestamp.clone(),
             thread_id: msg.thread_id.clone(),
             reply_to: msg.reply_to.clone(),
+            speak: crate::commands::ai::speak_replies(),
+            priority: msg.priority.clone(),
+            voice: spoken.voice,
+            announce: spoken.announce,
         };
 
         debug!(
@@ -230,15 +191,15 @@ impl InboxWatcherHandle {
     }
 }
 
-/// Write a new message to the MCP inbox file.
+/// Write a new message to the MCP inbox.
 ///
 /// Used to bridge voice transcriptions to the AI provider. The AI reads
-/// inbox.json via the `voice_listen` MCP tool.
+/// the inbox via the `voice_listen` MCP tool.
 pub fn write_inbox_message(from: &str, message: &str, thread_id: Option<&str>) -> Result<(), String> {
     write_inbox_message_with_image(from, message, thread_id, None)
 }
 
-/// Write a new message with an optional image attachment to the MCP inbox file.
+/// Write a new message with an optional image attachment to the MCP inbox.
 ///
 /// When `image_path` is provided, the image is base64-encoded and included
 /// as a data URL so the MCP tool consumer can access the image content.
@@ -248,15 +209,7 @@ pub fn write_inbox_message_with_image(
     thread_id: Option<&str>,
     image_path: Option<&str>,
 ) -> Result<(), String> {
-    let inbox_path = get_inbox_path();
-    let data_dir = get_mcp_data_dir();
-
-    // Ensure data directory exists
-    std::fs::create_dir_all(&data_dir)
-        .map_err(|e| format!("Failed to create data dir: {}", e))?;
-
-    // Read existing inbox or create empty
-    let mut data = read_inbox(&inbox_path).unwrap_or_default();
+    let db = InboxDb::open(&get_mcp_data_dir())?;
 
     // Generate RFC3339-like timestamp without chrono dependency
     let timestamp = {
@@ -297,25 +250,16 @@ pub fn write_inbox_message_with_image(
         reply_to: None,
         image_path: img_path,
         image_data_url: img_data_url,
+        priority: None,
     };
 
-    data.messages.push(msg);
+    db.insert(&msg)?;
 
-    // Keep inbox bounded (last 100 messages)
-    if data.messages.len() > 100 {
-        let excess = data.messages.len() - 100;
-        data.messages.drain(..excess);
+    // Keep inbox bounded
+    if let Err(e) = db.cleanup(inbox_db::MESSAGE_TTL, inbox_db::MAX_MESSAGES) {
+        warn!("Failed to clean up the inbox: {}", e);
     }
 
-    // Atomic write: write to .tmp, then rename
-    let tmp_path = inbox_path.with_extension("json.tmp");
-    let json = serde_json::to_string_pretty(&data)
-        .map_err(|e| format!("Failed to serialize inbox: {}", e))?;
-    std::fs::write(&tmp_path, &json)
-        .map_err(|e| format!("Failed to write inbox.tmp: {}", e))?;
-    std::fs::rename(&tmp_path, &inbox_path)
-        .map_err(|e| format!("Failed to rename inbox.tmp: {}", e))?;
-
     info!(
         "Wrote inbox message from '{}': {}...",
         from,
@@ -326,37 +270,29 @@ pub fn write_inbox_message_with_image(
 
 /// Start the inbox watcher.
 ///
-/// Watches the MCP inbox JSON file for changes and emits `mcp-inbox-message`
+/// Watches the MCP inbox database for changes and emits `mcp-inbox-message`
 /// events to the Tauri frontend when new messages appear.
 ///
 /// Returns a handle to control the watcher lifecycle.
 pub fn start_inbox_watcher(app_handle: AppHandle) -> Result<InboxWatcherHandle, String> {
     let data_dir = get_mcp_data_dir();
-    let inbox_path = data_dir.join("inbox.json");
-
-    // Ensure data directory exists
-    if let Err(e) = std::fs::create_dir_all(&data_dir) {
-        return Err(format!("Failed to create MCP data dir: {}", e));
-    }
+    let inbox_path = inbox_db::db_path(&data_dir);
 
-    // Initialize state and seed with existing messages
-    let state = Arc::new(Mutex::new(WatcherState::new()));
+    // Creates the data directory and the database if needed
+    let db = InboxDb::open(&data_dir)?;
 
-    if let Some(data) = read_inbox(&inbox_path) {
-        let mut s = state.lock().unwrap_or_else(|e| e.into_inner());
-        s.seed_from_messages(&data.messages);
-        info!(
-            "Inbox watcher seeded with {} existing message IDs",
-            s.seen_ids.len()
-        );
-    }
+    // Initialize state after the existing messages
+    let state = Arc::new(Mutex::new(WatcherState::after_existing(&db)));
+    info!(
+        "Inbox watcher starting after message {}",
+        state.lock().unwrap_or_else(|e| e.into_inner()).last_seq
+    );
 
     let running = Arc::new(Mutex::new(true));
 
     // Set up file watcher
     let state_clone = Arc::clone(&state);
     let running_clone = Arc::clone(&running);
-    let inbox_path_clone = inbox_path.clone();
     let app_handle_clone = app_handle.clone();
 
     // Debounce: use a channel to coalesce rapid file change events
@@ -374,10 +310,11 @@ pub fn start_inbox_watcher(app_handle: AppHandle) -> Result<InboxWatcherHandle,
                     return;
                 }
 
+                // Writes land in the WAL file first
                 let is_inbox = event.paths.iter().any(|p| {
                     p.file_name()
-                        .map(|f| f == "inbox.json" || f == "inbox.json.tmp")
-                        .unwrap_or(false)
+                        .and_then(|f| f.to_str())
+                        .is_some_and(inbox_db::is_db_file)
                 });
 
                 if is_inbox {
@@ -431,7 +368,7 @@ pub fn start_inbox_watcher(app_handle: AppHandle) -> Result<InboxWatcherHandle,
 
                 // Process inbox
                 let mut s = state_clone.lock().unwrap_or_else(|e| e.into_inner());
-                process_inbox(&inbox_path_clone, &mut s, &app_handle_clone);
+                process_inbox(&db, &mut s, &app_handle_clone);
             }
 
             info!("Inbox watcher thread exited");
@@ -464,7 +401,7 @@ mod tests {
     #[test]
     fn test_get_inbox_path() {
         let path = get_inbox_path();
-        assert!(path.to_string_lossy().ends_with("inbox.json"));
+        assert!(path.to_string_lossy().ends_with("inbox.db"));
     }
 
     #[test]
@@ -480,61 +417,38 @@ mod tests {
         assert_eq!(classify_sender("my-name"), "user_message");
     }
 
-    #[test]
-    fn test_watcher_state_seed_and_seen() {
-        let mut state = WatcherState::new();
-
-        let msgs = vec![
-            InboxMessage {
-                id: "msg-1".into(),
-                from: "user".into(),
-                message: "hello".into(),
-                timestamp: "2025-01-01T00:00:00Z".into(),
-                read_by: vec![],
-                thread_id: None,
-                reply_to: None,
-                image_path: None,
-                image_data_url: None,
-            },
-            InboxMessage {
-                id: "msg-2".into(),
-                from: "voice-claude".into(),
-                message: "hi there".into(),
-                timestamp: "2025-01-01T00:00:01Z".into(),
-                read_by: vec![],
-                thread_id: Some("voice-mirror".into()),
-                reply_to: None,
-                image_path: None,
-                image_data_url: None,
-            },
-        ];
-
-        state.seed_from_messages(&msgs);
-        assert!(state.is_seen("msg-1"));
-        assert!(state.is_seen("msg-2"));
-        assert!(!state.is_seen("msg-3"));
-    }
-
-    #[test]
-    fn test_watcher_state_mark_seen() {
-        let mut state = WatcherState::new();
-        assert!(!state.is_seen("msg-1"));
-        state.mark_seen("msg-1".into());
-        assert!(state.is_seen("msg-1"));
-    }
-
-    #[test]
-    fn test_read_inbox_missing_file() {
-        let result = read_inbox(std::path::Path::new("/nonexistent/inbox.json"));
-        assert!(result.is_none());
+    fn message(id: &str, from: &str) -> InboxMessage {
+        InboxMessage {
+            id: id.into(),
+            from: from.into(),
+            message: "hello".into(),
+            timestamp: "2025-01-01T00:00:00Z".into(),
+            read_by: vec![],
+            thread_id: None,
+            reply_to: None,
+            image_path: None,
+            image_data_url: None,
+            priority: None,
+        }
     }
 
     #[test]
-    fn test_parse_inbox_json() {
-        let json = r#"{"messages":[{"id":"msg-1","from":"user","message":"test","timestamp":"2025-01-01T00:00:00Z","read_by":[],"thread_id":"voice-mirror"}]}"#;
-        let data: InboxData = serde_json::from_str(json).unwrap();
-        assert_eq!(data.messages.len(), 1);
-        assert_eq!(data.messages[0].id, "msg-1");
-        assert_eq!(data.messages[0].from, "user");
+    fn test_watcher_state_skips_existing() {
+        let dir = std::env::temp_dir().join(format!("vm-inbox-watcher-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let db = InboxDb::open(&dir).unwrap();
+        db.insert(&message("msg-1", "user")).unwrap();
+
+        let mut state = WatcherState::after_existing(&db);
+        assert!(state.take_new(&db).is_empty());
+
+        db.insert(&message("msg-2", "voice-claude")).unwrap();
+        let new = state.take_new(&db);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].id, "msg-2");
+        assert!(state.take_new(&db).is_empty());
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
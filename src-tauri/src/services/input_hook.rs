@@ -6,6 +6,9 @@
 //!
 //! Configured keys are **suppressed** at the OS level (keyboard hooks only),
 //! preventing "44444" in text fields when holding a mouse side button for PTT.
+//!
+//! Gamepad buttons have no hook; a companion thread polls XInput controllers
+//! while a binding uses one and emits the same press/release events.
 
 // FFI type names match Win32 API conventions (HHOOK, POINT, MSG, etc.)
 #![allow(clippy::upper_case_acronyms)]
@@ -23,12 +26,37 @@ use tracing::{error, warn};
 const KEY_TYPE_NONE: u8 = 0;
 const KEY_TYPE_KEYBOARD: u8 = 1;
 const KEY_TYPE_MOUSE: u8 = 2;
+const KEY_TYPE_GAMEPAD: u8 = 3;
+
+/// XInput button masks, indexed by W3C standard gamepad button number
+/// (what the browser Gamepad API reports). 6/7 are the analog triggers.
+const PAD_BUTTON_MASKS: [u16; 16] = [
+    0x1000, // 0: A
+    0x2000, // 1: B
+    0x4000, // 2: X
+    0x8000, // 3: Y
+    0x0100, // 4: left bumper
+    0x0200, // 5: right bumper
+    0,      // 6: left trigger
+    0,      // 7: right trigger
+    0x0020, // 8: back / view
+    0x0010, // 9: start / menu
+    0x0040, // 10: left stick press
+    0x0080, // 11: right stick press
+    0x0001, // 12: d-pad up
+    0x0002, // 13: d-pad down
+    0x0004, // 14: d-pad left
+    0x0008, // 15: d-pad right
+];
+
+/// Trigger travel (0-255) that counts as pressed (XINPUT_GAMEPAD_TRIGGER_THRESHOLD).
+const PAD_TRIGGER_THRESHOLD: u8 = 30;
 
 /// A configurable key binding (PTT key, dictation key, etc.)
 struct KeyBinding {
-    /// 0=none, 1=keyboard vkey, 2=mouse button
+    /// 0=none, 1=keyboard vkey, 2=mouse button, 3=gamepad button
     key_type: AtomicU8,
-    /// Virtual key code (for keyboard) or button ID (for mouse)
+    /// Virtual key code (for keyboard) or button ID (for mouse/gamepad)
     key_code: AtomicU32,
     /// Whether the key is currently pressed (for repeat suppression)
     active: AtomicBool,
@@ -60,6 +88,12 @@ impl KeyBinding {
             && self.key_code.load(Ordering::Acquire) == button_id
     }
 
+    /// The bound gamepad button, if the binding is one.
+    fn gamepad_button(&self) -> Option<u32> {
+        (self.key_type.load(Ordering::Acquire) == KEY_TYPE_GAMEPAD)
+            .then(|| self.key_code.load(Ordering::Acquire))
+    }
+
     fn type_and_code(&self) -> (u8, u32) {
         (
             self.key_type.load(Ordering::Acquire),
@@ -84,6 +118,7 @@ static HOOK_APP_HANDLE: std::sync::OnceLock<AppHandle> = std::sync::OnceLock::ne
 /// - `"kb:52"` — keyboard virtual key code 52 (the "4" key)
 /// - `"mouse:4"` — mouse button 4 (XBUTTON1 / back)
 /// - `"MouseButton4"` — legacy format, equivalent to `"mouse:4"`
+/// - `"pad:0"` — gamepad button 0 (A), standard gamepad numbering
 pub fn configure_ptt(key_spec: &str) -> Result<String, String> {
     let (key_type, key_code) = parse_key_spec(key_spec)?;
     PTT_BINDING.configure(key_type, key_code);
@@ -118,6 +153,14 @@ fn parse_key_spec(spec: &str) -> Result<(u8, u32), String> {
             .map_err(|_| format!("Invalid button: {}", btn))?;
         return Ok((KEY_TYPE_MOUSE, code));
     }
+    if let Some(btn) = spec.strip_prefix("pad:") {
+        let code = btn
+            .parse::<u32>()
+            .ok()
+            .filter(|&b| (b as usize) < PAD_BUTTON_MASKS.len())
+            .ok_or_else(|| format!("Invalid gamepad button: {}", btn))?;
+        return Ok((KEY_TYPE_GAMEPAD, code));
+    }
     // Legacy format: "MouseButton4" → mouse button 4
     if let Some(rest) = spec.strip_prefix("MouseButton") {
         let id = rest
@@ -126,7 +169,7 @@ fn parse_key_spec(spec: &str) -> Result<(u8, u32), String> {
         return Ok((KEY_TYPE_MOUSE, id));
     }
     Err(format!(
-        "Unknown key spec: '{}'. Use 'kb:CODE' or 'mouse:ID'.",
+        "Unknown key spec: '{}'. Use 'kb:CODE', 'mouse:ID' or 'pad:ID'.",
         spec
     ))
 }
@@ -135,10 +178,23 @@ fn format_binding(key_type: u8, key_code: u32) -> String {
     match key_type {
         KEY_TYPE_KEYBOARD => format!("keyboard vkey {}", key_code),
         KEY_TYPE_MOUSE => format!("mouse button {}", key_code),
+        KEY_TYPE_GAMEPAD => format!("gamepad button {}", key_code),
         _ => "none".into(),
     }
 }
 
+/// Whether standard gamepad button `button` is held in an XInput state.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn pad_button_down(button: u32, buttons: u16, left_trigger: u8, right_trigger: u8) -> bool {
+    match button {
+        6 => left_trigger >= PAD_TRIGGER_THRESHOLD,
+        7 => right_trigger >= PAD_TRIGGER_THRESHOLD,
+        b => PAD_BUTTON_MASKS
+            .get(b as usize)
+            .is_some_and(|&mask| buttons & mask != 0),
+    }
+}
+
 // ---- Windows-specific hooks ----
 
 #[cfg(target_os = "windows")]
@@ -230,6 +286,34 @@ mod win32 {
         pub fn GetModuleHandleW(module_name: *const u16) -> isize;
         pub fn GetAsyncKeyState(vkey: i32) -> i16;
     }
+
+    pub const XUSER_MAX_COUNT: u32 = 4;
+    pub const ERROR_SUCCESS: u32 = 0;
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct XINPUT_GAMEPAD {
+        pub buttons: u16,
+        pub left_trigger: u8,
+        pub right_trigger: u8,
+        pub thumb_lx: i16,
+        pub thumb_ly: i16,
+        pub thumb_rx: i16,
+        pub thumb_ry: i16,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct XINPUT_STATE {
+        pub packet_number: u32,
+        pub gamepad: XINPUT_GAMEPAD,
+    }
+
+    // xinput9_1_0 ships with every Windows version since Vista
+    #[link(name = "xinput9_1_0")]
+    extern "system" {
+        pub fn XInputGetState(user_index: u32, state: *mut XINPUT_STATE) -> u32;
+    }
 }
 
 /// Check if any modifier key is currently held.
@@ -392,6 +476,82 @@ unsafe extern "system" fn low_level_mouse_proc(
     win32::CallNextHookEx(0, code, wparam, lparam)
 }
 
+// ---- Gamepad polling ----
+
+/// Poll interval while a binding uses a gamepad button.
+#[cfg(target_os = "windows")]
+const PAD_POLL_MS: u64 = 16;
+/// Poll interval while none does.
+#[cfg(target_os = "windows")]
+const PAD_IDLE_MS: u64 = 250;
+/// How often disconnected controller slots are re-checked. XInputGetState
+/// is slow for an empty slot, so those aren't polled every frame.
+#[cfg(target_os = "windows")]
+const PAD_RESCAN_MS: u64 = 2000;
+
+/// Poll XInput controllers for gamepad bindings. A button counts as held
+/// when it is held on any connected controller.
+#[cfg(target_os = "windows")]
+fn run_gamepad_poll() {
+    use std::time::{Duration, Instant};
+
+    let mut connected = [false; win32::XUSER_MAX_COUNT as usize];
+    let mut last_scan: Option<Instant> = None;
+    loop {
+        let bindings = [
+            (&PTT_BINDING, "ptt-key-pressed", "ptt-key-released"),
+            (
+                &DICTATION_BINDING,
+                "dictation-key-pressed",
+                "dictation-key-released",
+            ),
+        ];
+        if bindings
+            .iter()
+            .all(|(b, _, _)| b.gamepad_button().is_none())
+        {
+            std::thread::sleep(Duration::from_millis(PAD_IDLE_MS));
+            continue;
+        }
+
+        let rescan = last_scan.is_none_or(|t| t.elapsed() >= Duration::from_millis(PAD_RESCAN_MS));
+        if rescan {
+            last_scan = Some(Instant::now());
+        }
+        let mut states = Vec::new();
+        for (slot, is_connected) in connected.iter_mut().enumerate() {
+            if !*is_connected && !rescan {
+                continue;
+            }
+            let mut state = win32::XINPUT_STATE::default();
+            let ok =
+                unsafe { win32::XInputGetState(slot as u32, &mut state) } == win32::ERROR_SUCCESS;
+            if ok != *is_connected {
+                info!(slot, connected = ok, "Gamepad connection changed");
+                *is_connected = ok;
+            }
+            if ok {
+                states.push(state.gamepad);
+            }
+        }
+
+        for (binding, event_pressed, event_released) in bindings {
+            let Some(button) = binding.gamepad_button() else {
+                continue;
+            };
+            let down = states.iter().any(|pad| {
+                pad_button_down(button, pad.buttons, pad.left_trigger, pad.right_trigger)
+            });
+            // Only edges reach handle_binding_event; a controller unplugged
+            // mid-press counts as a release
+            if down != binding.active.load(Ordering::Relaxed) {
+                handle_binding_event(binding, event_pressed, event_released, down);
+            }
+        }
+        std::thread::sleep(Duration::from_millis(PAD_POLL_MS));
+    }
+}
+
 // ---- Start the unified hook ----
 
 /// Start the unified input hook on a background thread.
@@ -399,13 +559,21 @@ unsafe extern "system" fn low_level_mouse_proc(
 /// Installs both WH_KEYBOARD_LL and WH_MOUSE_LL hooks. The hooks check
 /// configured key bindings (set via `configure_ptt`/`configure_dictation`)
 /// and emit Tauri events when matched. Keyboard keys are suppressed at the
-/// OS level to prevent them from reaching other applications.
+/// OS level to prevent them from reaching other applications. Gamepad
+/// bindings are served by a separate polling thread.
 #[cfg(target_os = "windows")]
 pub fn start_input_hook(app_handle: AppHandle) {
     HOOK_APP_HANDLE
         .set(app_handle)
         .expect("Input hook AppHandle already set");
 
+    if let Err(e) = std::thread::Builder::new()
+        .name("gamepad-poll".into())
+        .spawn(run_gamepad_poll)
+    {
+        error!("Failed to spawn gamepad poll thread: {}", e);
+    }
+
     std::thread::Builder::new()
         .name("input-hook".into())
         .spawn(|| {
@@ -509,6 +677,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_gamepad_spec() {
+        assert_eq!(parse_key_spec("pad:0").unwrap(), (KEY_TYPE_GAMEPAD, 0));
+        assert_eq!(parse_key_spec("pad:15").unwrap(), (KEY_TYPE_GAMEPAD, 15));
+        assert!(parse_key_spec("pad:16").is_err());
+        assert!(parse_key_spec("pad:a").is_err());
+    }
+
+    #[test]
+    fn gamepad_button_state() {
+        // A held, nothing else
+        assert!(pad_button_down(0, 0x1000, 0, 0));
+        assert!(!pad_button_down(1, 0x1000, 0, 0));
+        // D-pad down
+        assert!(pad_button_down(13, 0x0002, 0, 0));
+        // Triggers use the analog threshold, not the button mask
+        assert!(!pad_button_down(6, 0xFFFF, PAD_TRIGGER_THRESHOLD - 1, 0));
+        assert!(pad_button_down(6, 0, PAD_TRIGGER_THRESHOLD, 0));
+        assert!(pad_button_down(7, 0, 0, 255));
+        assert!(!pad_button_down(16, 0xFFFF, 255, 255));
+    }
+
     #[test]
     fn parse_empty_spec() {
         assert_eq!(parse_key_spec("").unwrap(), (KEY_TYPE_NONE, 0));
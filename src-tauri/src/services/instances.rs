@@ -0,0 +1,171 @@
+//! Registry of connected AI instances, and the voice each one speaks in.
+//!
+//! The MCP `voice_status` tool (and every `voice_send`/`voice_listen`, as a
+//! heartbeat) records instances in `{data_dir}/status.json`. Several agents
+//! (say `voice-claude` and `coder-claude`) can be registered at once, each
+//! with an optional TTS voice. The app reads the same file when a reply
+//! arrives, to speak it in its sender's voice, and to say the sender's name
+//! first when the user couldn't otherwise tell who is talking: another agent
+//! is live and this one has no voice of its own.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::inbox_db;
+
+pub const FILE_NAME: &str = "status.json";
+
+/// An instance whose last heartbeat is older than this is stale.
+pub const STALE_TIMEOUT_MS: u64 = 2 * 60 * 1000;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusStore {
+    pub statuses: Vec<InstanceStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceStatus {
+    pub instance_id: String,
+    pub status: String,
+    #[serde(default)]
+    pub current_task: Option<String>,
+    pub last_heartbeat: String,
+    /// TTS voice for this instance's replies; the configured voice if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice: Option<String>,
+}
+
+impl InstanceStatus {
+    pub fn is_stale(&self, now_ms: u64) -> bool {
+        let last = inbox_db::parse_timestamp_ms(&self.last_heartbeat).unwrap_or(0);
+        now_ms.saturating_sub(last) > STALE_TIMEOUT_MS
+    }
+}
+
+/// How to speak a reply from one instance.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SenderVoice {
+    /// TTS voice to speak it in.
+    pub voice: Option<String>,
+    /// Name to say before it.
+    pub announce: Option<String>,
+}
+
+pub fn status_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(FILE_NAME)
+}
+
+/// Read the registry; empty when missing or unreadable.
+pub fn load(data_dir: &Path) -> StatusStore {
+    std::fs::read_to_string(status_path(data_dir))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// How to speak a reply from `from`, per the registry in `data_dir`.
+pub fn sender_voice(data_dir: &Path, from: &str, now_ms: u64) -> SenderVoice {
+    sender_voice_in(&load(data_dir), from, now_ms)
+}
+
+fn sender_voice_in(store: &StatusStore, from: &str, now_ms: u64) -> SenderVoice {
+    let live: Vec<&InstanceStatus> = store
+        .statuses
+        .iter()
+        .filter(|s| !s.is_stale(now_ms))
+        .collect();
+    let voice = store
+        .statuses
+        .iter()
+        .find(|s| s.instance_id == from)
+        .and_then(|s| s.voice.clone());
+    // Someone else is live, and sounds the same as this sender
+    let ambiguous = live
+        .iter()
+        .any(|s| s.instance_id != from && s.voice == voice);
+    SenderVoice {
+        announce: ambiguous.then(|| spoken_name(from)),
+        voice,
+    }
+}
+
+/// `coder-claude` -> `coder claude`.
+fn spoken_name(instance_id: &str) -> String {
+    instance_id.replace(['-', '_'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000_000;
+
+    fn instance(id: &str, voice: Option<&str>, heartbeat: &str) -> InstanceStatus {
+        InstanceStatus {
+            instance_id: id.into(),
+            status: "active".into(),
+            current_task: None,
+            last_heartbeat: heartbeat.into(),
+            voice: voice.map(String::from),
+        }
+    }
+
+    // 2023-11-14T22:13:20Z is NOW
+    const FRESH: &str = "2023-11-14T22:13:00.000Z";
+    const OLD: &str = "2023-11-14T21:00:00.000Z";
+
+    #[test]
+    fn test_single_agent_is_not_announced() {
+        let store = StatusStore {
+            statuses: vec![instance("voice-claude", None, FRESH)],
+        };
+        assert_eq!(sender_voice_in(&store, "voice-claude", NOW), SenderVoice::default());
+    }
+
+    #[test]
+    fn test_agents_sharing_a_voice_are_announced() {
+        let store = StatusStore {
+            statuses: vec![
+                instance("voice-claude", None, FRESH),
+                instance("coder-claude", None, FRESH),
+            ],
+        };
+        let spoken = sender_voice_in(&store, "coder-claude", NOW);
+        assert_eq!(spoken.announce.as_deref(), Some("coder claude"));
+        assert_eq!(spoken.voice, None);
+    }
+
+    #[test]
+    fn test_own_voice_needs_no_announcement() {
+        let store = StatusStore {
+            statuses: vec![
+                instance("voice-claude", None, FRESH),
+                instance("coder-claude", Some("am_adam"), FRESH),
+            ],
+        };
+        let spoken = sender_voice_in(&store, "coder-claude", NOW);
+        assert_eq!(spoken.voice.as_deref(), Some("am_adam"));
+        assert_eq!(spoken.announce, None);
+        // The other one still has the default voice, but nobody else does
+        assert_eq!(sender_voice_in(&store, "voice-claude", NOW).announce, None);
+    }
+
+    #[test]
+    fn test_stale_agents_are_ignored() {
+        let store = StatusStore {
+            statuses: vec![
+                instance("voice-claude", None, FRESH),
+                instance("coder-claude", None, OLD),
+            ],
+        };
+        assert_eq!(sender_voice_in(&store, "voice-claude", NOW).announce, None);
+    }
+
+    #[test]
+    fn test_old_status_file_parses() {
+        let json = r#"{"statuses":[{"instance_id":"a","status":"active","last_heartbeat":"x"}]}"#;
+        let store: StatusStore = serde_json::from_str(json).unwrap();
+        assert_eq!(store.statuses[0].voice, None);
+    }
+}
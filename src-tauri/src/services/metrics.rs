@@ -0,0 +1,265 @@
+//! In-process metrics: counters and latency histograms.
+//!
+//! Cheap enough to update on hot paths (one atomic add each), and kept for
+//! the life of the app, so they span pipeline restarts. `snapshot` feeds the
+//! `get_metrics` command; `render_prometheus` the control API's
+//! `GET /metrics` route, in the Prometheus text exposition format, for
+//! anyone who wants to scrape and graph them.
+//!
+//! Provider tokens come from the `usage` object OpenAI-compatible servers
+//! put in the last stream chunk. Servers that send none count each streamed
+//! content delta as one completion token, which is what most of them send.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+
+/// Prefix of every exported metric name.
+const PREFIX: &str = "voice_mirror_";
+
+/// A monotonically increasing count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    /// Voice turns that reached STT.
+    Utterances,
+    /// Capture samples lost to ring buffer overruns.
+    RingOverflowSamples,
+    /// WebSocket connections retried after a network error (Edge TTS).
+    WsReconnects,
+    /// Prompt tokens sent to API providers.
+    ProviderPromptTokens,
+    /// Completion tokens streamed back from API providers.
+    ProviderCompletionTokens,
+}
+
+impl Counter {
+    const ALL: [Counter; 5] = [
+        Counter::Utterances,
+        Counter::RingOverflowSamples,
+        Counter::WsReconnects,
+        Counter::ProviderPromptTokens,
+        Counter::ProviderCompletionTokens,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Counter::Utterances => "utterances_total",
+            Counter::RingOverflowSamples => "ring_buffer_overflow_samples_total",
+            Counter::WsReconnects => "ws_reconnects_total",
+            Counter::ProviderPromptTokens => "provider_prompt_tokens_total",
+            Counter::ProviderCompletionTokens => "provider_completion_tokens_total",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Counter::Utterances => "Voice turns that reached speech recognition",
+            Counter::RingOverflowSamples => "Capture samples lost to ring buffer overruns",
+            Counter::WsReconnects => "WebSocket connections retried after a network error",
+            Counter::ProviderPromptTokens => "Prompt tokens sent to API providers",
+            Counter::ProviderCompletionTokens => "Completion tokens received from API providers",
+        }
+    }
+}
+
+/// A latency distribution, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Histogram {
+    /// STT inference per turn.
+    SttLatency,
+    /// Reply handed to TTS -> first synthesized audio.
+    TtsLatency,
+}
+
+impl Histogram {
+    const ALL: [Histogram; 2] = [Histogram::SttLatency, Histogram::TtsLatency];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Histogram::SttLatency => "stt_latency_ms",
+            Histogram::TtsLatency => "tts_first_audio_latency_ms",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Histogram::SttLatency => "Speech recognition time per turn",
+            Histogram::TtsLatency => "Time from a reply reaching TTS to its first audio",
+        }
+    }
+}
+
+/// Upper bounds (ms) of the histogram buckets; `+Inf` is implied.
+pub const BUCKETS_MS: [u64; 10] = [50, 100, 250, 500, 750, 1000, 1500, 2500, 5000, 10000];
+
+struct HistogramCells {
+    /// Non-cumulative count per bucket, plus one for `+Inf`.
+    buckets: [AtomicU64; BUCKETS_MS.len() + 1],
+    count: AtomicU64,
+    sum: AtomicU64,
+}
+
+impl HistogramCells {
+    const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicU64::new(0) }; BUCKETS_MS.len() + 1],
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, ms: u64) {
+        let bucket = BUCKETS_MS
+            .iter()
+            .position(|&le| ms <= le)
+            .unwrap_or(BUCKETS_MS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(ms, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> HistogramSnapshot {
+        let mut cumulative = 0;
+        let buckets = self
+            .buckets
+            .iter()
+            .take(BUCKETS_MS.len())
+            .zip(BUCKETS_MS)
+            .map(|(cell, le)| {
+                cumulative += cell.load(Ordering::Relaxed);
+                (le, cumulative)
+            })
+            .collect();
+        HistogramSnapshot {
+            count: self.count.load(Ordering::Relaxed),
+            sum_ms: self.sum.load(Ordering::Relaxed),
+            buckets,
+        }
+    }
+}
+
+static COUNTERS: [AtomicU64; Counter::ALL.len()] =
+    [const { AtomicU64::new(0) }; Counter::ALL.len()];
+static HISTOGRAMS: [HistogramCells; Histogram::ALL.len()] =
+    [const { HistogramCells::new() }; Histogram::ALL.len()];
+
+/// Add `n` to `counter`.
+pub fn add(counter: Counter, n: u64) {
+    if n > 0 {
+        COUNTERS[counter as usize].fetch_add(n, Ordering::Relaxed);
+    }
+}
+
+/// Add one to `counter`.
+pub fn inc(counter: Counter) {
+    add(counter, 1);
+}
+
+/// Record one `ms` observation in `histogram`.
+pub fn observe(histogram: Histogram, ms: u64) {
+    HISTOGRAMS[histogram as usize].observe(ms);
+}
+
+/// A histogram's totals. `buckets` are cumulative `(upper bound ms, count)`
+/// pairs; observations above the last bound only show in `count`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub sum_ms: u64,
+    pub buckets: Vec<(u64, u64)>,
+}
+
+/// Every metric at one point in time, keyed by name.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSnapshot {
+    pub counters: std::collections::BTreeMap<&'static str, u64>,
+    pub histograms: std::collections::BTreeMap<&'static str, HistogramSnapshot>,
+}
+
+pub fn snapshot() -> MetricsSnapshot {
+    MetricsSnapshot {
+        counters: Counter::ALL
+            .iter()
+            .map(|c| (c.name(), COUNTERS[*c as usize].load(Ordering::Relaxed)))
+            .collect(),
+        histograms: Histogram::ALL
+            .iter()
+            .map(|h| (h.name(), HISTOGRAMS[*h as usize].snapshot()))
+            .collect(),
+    }
+}
+
+/// The current metrics in the Prometheus text exposition format (0.0.4).
+pub fn render_prometheus() -> String {
+    render(&snapshot())
+}
+
+fn render(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    for counter in Counter::ALL {
+        let name = format!("{}{}", PREFIX, counter.name());
+        let value = snapshot.counters.get(counter.name()).copied().unwrap_or(0);
+        let _ = writeln!(out, "# HELP {} {}", name, counter.help());
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+    for histogram in Histogram::ALL {
+        let Some(h) = snapshot.histograms.get(histogram.name()) else {
+            continue;
+        };
+        let name = format!("{}{}", PREFIX, histogram.name());
+        let _ = writeln!(out, "# HELP {} {}", name, histogram.help());
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (le, count) in &h.buckets {
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, le, count);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, h.count);
+        let _ = writeln!(out, "{}_sum {}", name, h.sum_ms);
+        let _ = writeln!(out, "{}_count {}", name, h.count);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let cells = HistogramCells::new();
+        for ms in [10, 50, 51, 900, 60_000] {
+            cells.observe(ms);
+        }
+        let snap = cells.snapshot();
+        assert_eq!(snap.count, 5);
+        assert_eq!(snap.sum_ms, 61_011);
+        assert_eq!(snap.buckets[0], (50, 2));
+        assert_eq!(snap.buckets[1], (100, 3));
+        assert_eq!(snap.buckets[5], (1000, 4));
+        assert_eq!(snap.buckets.last(), Some(&(10_000, 4)));
+    }
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let cells = HistogramCells::new();
+        cells.observe(120);
+        let snapshot = MetricsSnapshot {
+            counters: [("utterances_total", 7)].into_iter().collect(),
+            histograms: [("stt_latency_ms", cells.snapshot())].into_iter().collect(),
+        };
+        let text = render(&snapshot);
+        assert!(text.contains(
+            "# TYPE voice_mirror_utterances_total counter\nvoice_mirror_utterances_total 7\n"
+        ));
+        // Missing counters render as 0
+        assert!(text.contains("voice_mirror_ws_reconnects_total 0\n"));
+        assert!(text.contains("voice_mirror_stt_latency_ms_bucket{le=\"100\"} 0\n"));
+        assert!(text.contains("voice_mirror_stt_latency_ms_bucket{le=\"250\"} 1\n"));
+        assert!(text.contains("voice_mirror_stt_latency_ms_bucket{le=\"+Inf\"} 1\n"));
+        assert!(text.contains("voice_mirror_stt_latency_ms_sum 120\n"));
+        // Histograms absent from the snapshot are left out
+        assert!(!text.contains("tts_first_audio"));
+    }
+}
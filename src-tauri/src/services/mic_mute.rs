@@ -0,0 +1,112 @@
+//! OS mute state of the default input device.
+//!
+//! Polled by the pipeline (see `voice::pipeline::privacy`) so the UI can
+//! tell a live mic from one the OS or a hardware switch has muted:
+//!
+//! - **Windows**: `IAudioEndpointVolume::GetMute` on the default capture
+//!   endpoint. Headsets whose mute button goes through the driver show up
+//!   here.
+//! - **Linux**: `pactl get-source-mute @DEFAULT_SOURCE@` (PulseAudio and
+//!   pipewire-pulse).
+//! - **macOS**: an input volume of 0 in `osascript`'s volume settings.
+//!
+//! `None` when the state can't be read (tool missing, no device).
+
+/// Whether the default input device is muted.
+pub fn default_input_muted() -> Option<bool> {
+    #[cfg(windows)]
+    {
+        match sys::default_capture_muted() {
+            Ok(muted) => Some(muted),
+            Err(e) => {
+                tracing::debug!("Couldn't read the input mute state: {}", e);
+                None
+            }
+        }
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let output = std::process::Command::new("pactl")
+            .args(["get-source-mute", "@DEFAULT_SOURCE@"])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())?;
+        parse_pactl_mute(&String::from_utf8_lossy(&output.stdout))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("osascript")
+            .args(["-e", "input volume of (get volume settings)"])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())?;
+        parse_input_volume(&String::from_utf8_lossy(&output.stdout))
+    }
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// `pactl get-source-mute` prints `Mute: yes` or `Mute: no`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_pactl_mute(out: &str) -> Option<bool> {
+    match out.trim().strip_prefix("Mute:")?.trim() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Input volume 0-100; `missing value` when the device has no volume.
+#[cfg(any(target_os = "macos", test))]
+fn parse_input_volume(out: &str) -> Option<bool> {
+    out.trim().parse::<u32>().ok().map(|volume| volume == 0)
+}
+
+#[cfg(windows)]
+mod sys {
+    use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+    use windows::Win32::Media::Audio::{eCapture, eConsole, IMMDeviceEnumerator, MMDeviceEnumerator};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    pub(super) fn default_capture_muted() -> windows::core::Result<bool> {
+        // SAFETY: COM is initialized for this call and every interface is
+        // released before it is uninitialized.
+        unsafe {
+            let initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+            let muted = (|| -> windows::core::Result<bool> {
+                let enumerator: IMMDeviceEnumerator =
+                    CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+                let device = enumerator.GetDefaultAudioEndpoint(eCapture, eConsole)?;
+                let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)?;
+                Ok(volume.GetMute()?.as_bool())
+            })();
+            if initialized {
+                CoUninitialize();
+            }
+            muted
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pactl_mute() {
+        assert_eq!(parse_pactl_mute("Mute: yes\n"), Some(true));
+        assert_eq!(parse_pactl_mute("Mute: no\n"), Some(false));
+        assert_eq!(parse_pactl_mute("Connection failure"), None);
+    }
+
+    #[test]
+    fn test_parse_input_volume() {
+        assert_eq!(parse_input_volume("0\n"), Some(true));
+        assert_eq!(parse_input_volume("75\n"), Some(false));
+        assert_eq!(parse_input_volume("missing value\n"), None);
+    }
+}
@@ -1,19 +1,41 @@
+pub mod audio_ducking;
+pub mod audio_session;
 pub mod auth_vault;
 pub mod browser_bridge;
+pub mod browser_profiles;
 pub mod cdp;
+pub mod control_api;
 pub mod dev_server;
+pub mod event_throttle;
 pub mod file_watcher;
+pub mod hotkeys;
+pub mod inbox_db;
 pub mod inbox_watcher;
 pub mod input_hook;
+pub mod instances;
 pub mod crash_handler;
 pub mod hang_watchdog;
 pub mod logger;
+pub mod metrics;
+pub mod mic_mute;
+pub mod model_manager;
+pub mod network;
+pub mod onboarding;
 pub mod output;
 pub mod platform;
 pub mod ports;
+pub mod quiet_hours;
+pub mod runtime_context;
 pub mod sandbox;
 pub mod sandbox_stream;
+pub mod second_instance;
+pub mod session_snapshot;
+pub mod system_stats;
 pub mod text_injector;
+pub mod transcript_log;
+pub mod turn_archive;
 pub mod uia;
+pub mod voice_stats;
+pub mod webhooks;
 pub mod window_follow;
 pub mod window_stream;
@@ -0,0 +1,1056 @@
+//! Model manager: one place to list, download, and delete local ML assets.
+//!
+//! Tracks every downloadable model the app can use — Whisper GGML sizes
+//! (English-only and multilingual), Kokoro ONNX + voice embeddings, the
+//! Parakeet ONNX export, the speaker identification model, and Silero VAD
+//! — and exposes Tauri commands
+//! `list_model_assets` / `download_model` / `delete_model`. (The listing
+//! command isn't called `list_models` because the AI provider model list
+//! already owns that command name.)
+//!
+//! Silero VAD is download-only for now: nothing in the crate loads it yet
+//! (the pipeline uses the energy VAD in `voice::vad`), so it is fetched into
+//! `models/silero` ahead of a future loader. It is flagged `loaded: false`
+//! in the listing.
+//!
+//! This module owns the one downloader in the crate: the per-engine helpers
+//! (`stt::ensure_model_exists`, `stt::ensure_parakeet_model_exists`,
+//! `tts::ensure_kokoro_model_exists`) build their asset here and call
+//! [`download_asset`]. Downloads stream to a `.tmp` sibling, are size-checked
+//! and renamed into place, and emit `model-download-progress` events with
+//! percent and byte counts so any UI can show the same progress bar
+//! regardless of the asset. A process-wide in-flight set keeps two callers
+//! from writing the same asset at once.
+//!
+//! Every file is checked: against the bundled `model_checksums.json`
+//! manifest when it has an entry, else against the digest the host publishes
+//! (HuggingFace's `X-Linked-Etag`, the `digest` of a GitHub release asset, or
+//! the git blob id of a file in a GitHub repo). The verified SHA-256 is
+//! recorded next to the file (`<file>.sha256`), so files already on disk are
+//! re-checked at every download without asking the host again. A corrupt
+//! download is deleted and fetched again, and a corrupt file already on disk
+//! is replaced, instead of letting whisper/ONNX fail later with an opaque
+//! load error.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, warn};
+
+use crate::commands::voice::VoiceEngineState;
+use crate::commands::IpcResponse;
+use crate::voice::tts::crypto::{hex_encode_lower, Sha1, Sha256};
+use crate::voice::{speaker, stt, tts, VoiceEngineConfig};
+
+/// Silero VAD v5 ONNX model (download-only, see module docs).
+const SILERO_VAD_URL: &str =
+    "https://github.com/snakers4/silero-vad/raw/master/src/silero_vad/data/silero_vad.onnx";
+
+/// Attempts per file before a checksum/size mismatch is reported as an error.
+const MAX_DOWNLOAD_ATTEMPTS: usize = 3;
+
+// ---------------------------------------------------------------------------
+// Checksums
+// ---------------------------------------------------------------------------
+
+/// Bundled manifest of known-good SHA-256 digests.
+const CHECKSUM_MANIFEST: &str = include_str!("model_checksums.json");
+
+static CHECKSUMS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+fn parse_manifest(json: &str) -> HashMap<String, String> {
+    #[derive(serde::Deserialize)]
+    struct Manifest {
+        files: HashMap<String, String>,
+    }
+    match serde_json::from_str::<Manifest>(json) {
+        Ok(m) => m
+            .files
+            .into_iter()
+            .map(|(file, hash)| (file, hash.to_ascii_lowercase()))
+            .collect(),
+        Err(e) => {
+            warn!("Invalid model checksum manifest: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Bundled SHA-256 for `filename`, if the manifest has one.
+pub fn expected_sha256(filename: &str) -> Option<&'static str> {
+    CHECKSUMS
+        .get_or_init(|| parse_manifest(CHECKSUM_MANIFEST))
+        .get(filename)
+        .map(String::as_str)
+}
+
+/// Whether `s` looks like a hex SHA-256 digest.
+fn is_sha256_hex(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// A digest a file must match.
+#[derive(Debug, Clone, PartialEq)]
+enum Digest {
+    /// SHA-256 of the file (lowercase hex).
+    Sha256(String),
+    /// Git blob id: SHA-1 of `blob <size>\0` followed by the file.
+    GitBlob { sha1: String, size: u64 },
+}
+
+/// Where the host of a download URL publishes the file's digest.
+#[derive(Debug, PartialEq)]
+enum DigestSource {
+    /// HuggingFace `resolve/` URL: `X-Linked-Etag` on the redirect.
+    HuggingFace,
+    /// GitHub release asset: `digest` of the named asset in the release API.
+    GithubRelease { api: String, asset: String },
+    /// File in a GitHub repo: git blob `sha` and `size` from the contents API.
+    GithubFile { api: String },
+}
+
+fn digest_source(url: &str) -> Option<DigestSource> {
+    if url.starts_with("https://huggingface.co/") && url.contains("/resolve/") {
+        return Some(DigestSource::HuggingFace);
+    }
+    let path = url.strip_prefix("https://github.com/")?;
+    let mut parts = path.splitn(4, '/');
+    let (owner, repo, kind, rest) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    match kind {
+        "releases" => {
+            let (tag, asset) = rest.strip_prefix("download/")?.split_once('/')?;
+            Some(DigestSource::GithubRelease {
+                api: format!("https://api.github.com/repos/{owner}/{repo}/releases/tags/{tag}"),
+                asset: asset.to_string(),
+            })
+        }
+        "raw" => {
+            let (git_ref, file) = rest.split_once('/')?;
+            Some(DigestSource::GithubFile {
+                api: format!(
+                    "https://api.github.com/repos/{owner}/{repo}/contents/{file}?ref={git_ref}"
+                ),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Digest the host publishes for `url`, if any.
+async fn published_digest(url: &str) -> Option<Digest> {
+    match digest_source(url)? {
+        DigestSource::HuggingFace => huggingface_sha256(url).await.map(Digest::Sha256),
+        DigestSource::GithubRelease { api, asset } => {
+            let release = github_api(&api).await?;
+            let digest = release["assets"]
+                .as_array()?
+                .iter()
+                .find(|a| a["name"] == asset.as_str())?["digest"]
+                .as_str()?
+                .strip_prefix("sha256:")?
+                .to_ascii_lowercase();
+            is_sha256_hex(&digest).then_some(Digest::Sha256(digest))
+        }
+        DigestSource::GithubFile { api } => {
+            let file = github_api(&api).await?;
+            let sha1 = file["sha"].as_str()?.to_ascii_lowercase();
+            let size = file["size"].as_u64()?;
+            (sha1.len() == 40).then_some(Digest::GitBlob { sha1, size })
+        }
+    }
+}
+
+/// HuggingFace answers `resolve/` URLs for LFS files with a redirect whose
+/// `X-Linked-Etag` header is the file's SHA-256. Redirects are not followed
+/// so the header is read from that first response.
+async fn huggingface_sha256(url: &str) -> Option<String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .ok()?;
+    let resp = client.head(url).send().await.ok()?;
+    let etag = resp.headers().get("x-linked-etag")?.to_str().ok()?;
+    let etag = etag.trim_start_matches("W/").trim_matches('"').to_ascii_lowercase();
+    is_sha256_hex(&etag).then_some(etag)
+}
+
+async fn github_api(url: &str) -> Option<serde_json::Value> {
+    let resp = reqwest::Client::new()
+        .get(url)
+        .header("User-Agent", "voice-mirror")
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .ok()?;
+    if !resp.status().is_success() {
+        warn!(url, status = %resp.status(), "GitHub API request for a model digest failed");
+        return None;
+    }
+    resp.json().await.ok()
+}
+
+/// `<file>.sha256` sibling holding the digest the file was verified against.
+fn digest_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".sha256");
+    dest.with_file_name(name)
+}
+
+/// SHA-256 recorded when `dest` was last verified, if any.
+async fn recorded_sha256(dest: &Path) -> Option<String> {
+    let recorded = tokio::fs::read_to_string(digest_path(dest)).await.ok()?;
+    let recorded = recorded.trim().to_ascii_lowercase();
+    is_sha256_hex(&recorded).then_some(recorded)
+}
+
+/// Remember the verified digest of `dest` for the next check on disk.
+async fn record_sha256(dest: &Path, sha256: &str) {
+    if let Err(e) = tokio::fs::write(digest_path(dest), sha256).await {
+        warn!(file = %dest.display(), "Failed to record model digest: {}", e);
+    }
+}
+
+/// Git blob id of a file on disk (see [`Digest::GitBlob`]).
+async fn file_git_blob_sha1(path: &Path) -> Result<String, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let data = std::fs::read(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut hasher = Sha1::new();
+        hasher.update(format!("blob {}\0", data.len()).as_bytes());
+        hasher.update(&data);
+        Ok(hex_encode_lower(&hasher.finalize()))
+    })
+    .await
+    .map_err(|e| format!("Checksum task failed: {}", e))?
+}
+
+/// SHA-256 of a file on disk (lowercase hex), hashed off the async runtime.
+async fn file_sha256(path: &Path) -> Result<String, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        use std::io::Read;
+        let mut file = std::fs::File::open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 1 << 20];
+        loop {
+            let n = file
+                .read(&mut buf)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Ok(hex_encode_lower(&hasher.finalize()))
+    })
+    .await
+    .map_err(|e| format!("Checksum task failed: {}", e))?
+}
+
+// ---------------------------------------------------------------------------
+// Catalog
+// ---------------------------------------------------------------------------
+
+/// What an asset is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelKind {
+    Stt,
+    Tts,
+    Vad,
+    Speaker,
+}
+
+/// A single file belonging to an asset.
+#[derive(Debug, Clone)]
+pub struct ModelFile {
+    pub filename: String,
+    pub url: String,
+}
+
+/// A downloadable model (one or more files in one directory).
+#[derive(Debug, Clone)]
+pub struct ModelAsset {
+    /// Stable identifier used by the commands (e.g. "whisper-base.en", "kokoro").
+    pub id: String,
+    pub kind: ModelKind,
+    pub label: String,
+    /// Directory the files live in. For assets an engine loads this is the
+    /// exact directory it reads from.
+    pub dir: PathBuf,
+    pub files: Vec<ModelFile>,
+    /// Whether any engine in this build loads the asset (false = download-only).
+    pub loaded: bool,
+}
+
+impl ModelAsset {
+    fn paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.files.iter().map(|f| self.dir.join(&f.filename))
+    }
+
+    /// Whether every file is present on disk.
+    pub fn is_installed(&self) -> bool {
+        self.paths().all(|p| p.exists())
+    }
+
+    /// Total bytes of the files currently on disk.
+    pub fn size_on_disk(&self) -> u64 {
+        self.paths()
+            .filter_map(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum()
+    }
+}
+
+/// Asset for the whisper GGML file `size` + `language` resolve to.
+pub fn whisper_asset(data_dir: &Path, size: &str, language: &str) -> ModelAsset {
+    let filename = stt::model_filename_for_language(size, language);
+    let stem = filename
+        .trim_start_matches("ggml-")
+        .trim_end_matches(".bin")
+        .to_string();
+    let label = if stem.ends_with(".en") {
+        format!("Whisper {} (English)", size)
+    } else {
+        format!("Whisper {} (multilingual)", size)
+    };
+    ModelAsset {
+        id: format!("whisper-{}", stem),
+        kind: ModelKind::Stt,
+        label,
+        dir: data_dir.join("models"),
+        files: vec![ModelFile {
+            url: stt::model_download_url(size, language),
+            filename,
+        }],
+        loaded: true,
+    }
+}
+
+/// Asset for the Parakeet ONNX export.
+pub fn parakeet_asset(data_dir: &Path) -> ModelAsset {
+    ModelAsset {
+        id: "parakeet".into(),
+        kind: ModelKind::Stt,
+        label: "Parakeet TDT 0.6B (English, ONNX)".into(),
+        dir: stt::parakeet_model_dir(data_dir),
+        files: stt::PARAKEET_FILES
+            .iter()
+            .map(|f| ModelFile {
+                filename: f.to_string(),
+                url: stt::parakeet_file_url(f),
+            })
+            .collect(),
+        loaded: cfg!(feature = "onnx"),
+    }
+}
+
+/// Asset for the Kokoro model + voices, stored in `model_dir`.
+pub fn kokoro_asset(model_dir: &Path) -> ModelAsset {
+    ModelAsset {
+        id: "kokoro".into(),
+        kind: ModelKind::Tts,
+        label: "Kokoro v1.0 (ONNX + voices)".into(),
+        dir: model_dir.to_path_buf(),
+        files: tts::KOKORO_FILES
+            .iter()
+            .map(|(filename, url)| ModelFile {
+                filename: filename.to_string(),
+                url: url.to_string(),
+            })
+            .collect(),
+        loaded: cfg!(feature = "onnx"),
+    }
+}
+
+/// Build the full asset catalog rooted at `data_dir`.
+pub fn catalog(data_dir: &Path) -> Vec<ModelAsset> {
+    let models_dir = data_dir.join("models");
+    let mut assets = Vec::new();
+
+    // Whisper: one asset per distinct GGML file (English-only + multilingual)
+    let mut seen = HashSet::new();
+    for size in stt::WHISPER_MODEL_SIZES {
+        for language in ["en", "auto"] {
+            let asset = whisper_asset(data_dir, size, language);
+            if seen.insert(asset.id.clone()) {
+                assets.push(asset);
+            }
+        }
+    }
+
+    assets.push(parakeet_asset(data_dir));
+    assets.push(kokoro_asset(&models_dir.join("kokoro")));
+
+    assets.push(ModelAsset {
+        id: "speaker-ecapa".into(),
+        kind: ModelKind::Speaker,
+        label: "ECAPA-TDNN speaker model (ONNX)".into(),
+        dir: speaker::model_dir(data_dir),
+        files: vec![ModelFile {
+            filename: speaker::SPEAKER_MODEL_FILE.into(),
+            url: speaker::SPEAKER_MODEL_URL.into(),
+        }],
+        loaded: cfg!(feature = "onnx"),
+    });
+
+    assets.push(ModelAsset {
+        id: "silero-vad".into(),
+        kind: ModelKind::Vad,
+        label: "Silero VAD v5".into(),
+        dir: models_dir.join("silero"),
+        files: vec![ModelFile {
+            filename: "silero_vad.onnx".into(),
+            url: SILERO_VAD_URL.into(),
+        }],
+        loaded: false,
+    });
+
+    assets
+}
+
+// ---------------------------------------------------------------------------
+// Events
+// ---------------------------------------------------------------------------
+
+/// Emitted while downloading any managed model.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDownloadProgress {
+    pub model_id: String,
+    /// The file currently downloading.
+    pub file: String,
+    /// 1-based index of `file` and the asset's file count.
+    pub file_index: usize,
+    pub file_count: usize,
+    pub percent: u8,
+    pub downloaded_bytes: u64,
+    /// `None` when the server didn't report a content length.
+    pub total_bytes: Option<u64>,
+}
+
+// ---------------------------------------------------------------------------
+// Manager
+// ---------------------------------------------------------------------------
+
+/// Asset ids currently downloading, shared by the manager commands and the
+/// per-engine `ensure_*` helpers.
+static DOWNLOADING: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn downloading() -> &'static Mutex<HashSet<String>> {
+    DOWNLOADING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Whether `id` is downloading right now.
+pub fn is_downloading(id: &str) -> bool {
+    downloading()
+        .lock()
+        .map(|set| set.contains(id))
+        .unwrap_or(false)
+}
+
+/// Removes an id from the in-flight set when a download ends (ok or not).
+struct DownloadGuard {
+    id: String,
+}
+
+impl Drop for DownloadGuard {
+    fn drop(&mut self) {
+        if let Ok(mut set) = downloading().lock() {
+            set.remove(&self.id);
+        }
+    }
+}
+
+/// Download every missing file of `asset`. Returns the asset directory.
+///
+/// Emits `model-download-progress` for each file; `on_progress` sees the
+/// same events so callers can forward them to their legacy event names.
+pub async fn download_asset<F>(
+    asset: &ModelAsset,
+    app: Option<&AppHandle>,
+    mut on_progress: F,
+) -> Result<PathBuf, String>
+where
+    F: FnMut(&ModelDownloadProgress) + Send,
+{
+    {
+        let mut set = downloading()
+            .lock()
+            .map_err(|e| format!("Failed to lock download state: {}", e))?;
+        if !set.insert(asset.id.clone()) {
+            return Err(format!("Model {} is already downloading", asset.id));
+        }
+    }
+    let _guard = DownloadGuard {
+        id: asset.id.clone(),
+    };
+
+    tokio::fs::create_dir_all(&asset.dir)
+        .await
+        .map_err(|e| format!("Failed to create model dir: {}", e))?;
+
+    let file_count = asset.files.len();
+    for (i, file) in asset.files.iter().enumerate() {
+        let dest = asset.dir.join(&file.filename);
+        let manifest = expected_sha256(&file.filename).map(|h| Digest::Sha256(h.to_string()));
+        if dest.exists() {
+            // The manifest, else the digest recorded at download time. A file
+            // from before digests were recorded asks the host once.
+            let recorded = recorded_sha256(&dest).await;
+            let expected = match (&manifest, &recorded) {
+                (Some(digest), _) => Some(digest.clone()),
+                (None, Some(hash)) => Some(Digest::Sha256(hash.clone())),
+                (None, None) => published_digest(&file.url).await,
+            };
+            let Some(expected) = expected else {
+                continue;
+            };
+            let sha256 = file_sha256(&dest).await?;
+            let (expected_hex, actual) = match &expected {
+                Digest::Sha256(hash) => (hash, sha256.clone()),
+                Digest::GitBlob { sha1, .. } => (sha1, file_git_blob_sha1(&dest).await?),
+            };
+            if actual == *expected_hex {
+                if recorded.is_none() {
+                    record_sha256(&dest, &sha256).await;
+                }
+                continue;
+            }
+            warn!(
+                file = %dest.display(),
+                expected = %expected_hex,
+                actual = %actual,
+                "Model file on disk is corrupt, downloading it again"
+            );
+            tokio::fs::remove_file(&dest)
+                .await
+                .map_err(|e| format!("Failed to remove corrupt {}: {}", dest.display(), e))?;
+        }
+
+        let expected = match manifest {
+            Some(digest) => Some(digest),
+            None => published_digest(&file.url).await,
+        };
+        if expected.is_none() {
+            warn!(file = %file.filename, "No known digest for model file, skipping verification");
+        }
+
+        let mut attempt = 1;
+        loop {
+            info!(model = %asset.id, url = %file.url, dest = %dest.display(), attempt, "Downloading model file");
+            let result = download_file(
+                &asset.id,
+                file,
+                i + 1,
+                file_count,
+                &dest,
+                expected.as_ref(),
+                app,
+                &mut on_progress,
+            )
+            .await;
+            match result {
+                Ok(sha256) => {
+                    if expected.is_some() {
+                        record_sha256(&dest, &sha256).await;
+                    }
+                    break;
+                }
+                Err(FileError::Corrupt(msg)) if attempt < MAX_DOWNLOAD_ATTEMPTS => {
+                    warn!(file = %file.filename, attempt, "{}, retrying", msg);
+                    attempt += 1;
+                }
+                Err(FileError::Corrupt(msg)) | Err(FileError::Fatal(msg)) => return Err(msg),
+            }
+        }
+    }
+
+    info!(model = %asset.id, "Model ready");
+    Ok(asset.dir.clone())
+}
+
+/// Why a single file download failed.
+enum FileError {
+    /// The bytes arrived but don't match the expected size or checksum
+    /// (the partial file is already deleted); worth another attempt.
+    Corrupt(String),
+    /// Network, HTTP or filesystem error.
+    Fatal(String),
+}
+
+/// Managed Tauri state: the data dir the catalog is rooted at.
+pub struct ModelManager {
+    data_dir: PathBuf,
+}
+
+impl ModelManager {
+    pub fn new(data_dir: PathBuf) -> Self {
+        Self { data_dir }
+    }
+
+    pub fn catalog(&self) -> Vec<ModelAsset> {
+        catalog(&self.data_dir)
+    }
+
+    pub fn find(&self, id: &str) -> Option<ModelAsset> {
+        self.catalog().into_iter().find(|a| a.id == id)
+    }
+
+    /// Download every missing file of `id`. Returns the asset directory.
+    pub async fn download(&self, id: &str, app: Option<&AppHandle>) -> Result<PathBuf, String> {
+        let asset = self.find(id).ok_or_else(|| format!("Unknown model: {}", id))?;
+        download_asset(&asset, app, |_| {}).await
+    }
+
+    /// Ids of the assets a running engine with `config` has loaded.
+    ///
+    /// Mirrors `stt::create_stt_engine`: Parakeet may have fallen back to
+    /// whisper at the configured size, and the unimplemented cloud adapters
+    /// load the base whisper stub.
+    pub fn active_asset_ids(&self, config: &VoiceEngineConfig) -> Vec<String> {
+        let size = config.stt_model_size.as_str();
+        let language = config.stt_language.as_str();
+        let mut ids = Vec::new();
+        match config.stt_adapter.as_str() {
+            "parakeet" => {
+                ids.push(parakeet_asset(&self.data_dir).id);
+                ids.push(whisper_asset(&self.data_dir, size, language).id);
+            }
+            "openai" | "openai-cloud" | "custom-cloud" => {
+                ids.push(whisper_asset(&self.data_dir, "base", language).id);
+            }
+            _ => ids.push(whisper_asset(&self.data_dir, size, language).id),
+        }
+        if config.tts_adapter == "kokoro" {
+            ids.push("kokoro".into());
+        }
+        ids
+    }
+
+    /// Delete the files of `id`, returning the bytes freed.
+    ///
+    /// `active` lists assets the running voice engine has loaded (see
+    /// [`Self::active_asset_ids`]); those are refused. Leftover `.tmp`
+    /// partials are removed too. Shared directories (the whisper `models/`
+    /// root) are never removed, only the asset's files.
+    pub fn delete(&self, id: &str, active: &[String]) -> Result<u64, String> {
+        let asset = self.find(id).ok_or_else(|| format!("Unknown model: {}", id))?;
+        if is_downloading(id) {
+            return Err(format!("Model {} is downloading", id));
+        }
+        if active.iter().any(|a| a == id) {
+            return Err(format!(
+                "Model {} is in use by the voice engine. Stop the voice engine first.",
+                id
+            ));
+        }
+
+        let mut freed = 0u64;
+        for path in asset.paths() {
+            for candidate in [path.clone(), tmp_path(&path), digest_path(&path)] {
+                if let Ok(meta) = std::fs::metadata(&candidate) {
+                    std::fs::remove_file(&candidate).map_err(|e| {
+                        format!("Failed to delete {}: {}", candidate.display(), e)
+                    })?;
+                    freed += meta.len();
+                }
+            }
+        }
+        info!(model = %asset.id, freed_bytes = freed, "Model deleted");
+        Ok(freed)
+    }
+}
+
+/// `<file>.tmp` sibling used while downloading.
+fn tmp_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    dest.with_file_name(name)
+}
+
+/// Download one file to `dest` via its `.tmp` sibling, checking the size
+/// and (when known) the digest before the rename. Returns the SHA-256.
+#[allow(clippy::too_many_arguments)]
+async fn download_file<F>(
+    model_id: &str,
+    file: &ModelFile,
+    file_index: usize,
+    file_count: usize,
+    dest: &Path,
+    expected: Option<&Digest>,
+    app: Option<&AppHandle>,
+    on_progress: &mut F,
+) -> Result<String, FileError>
+where
+    F: FnMut(&ModelDownloadProgress) + Send,
+{
+    use futures_util::StreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    let fatal = FileError::Fatal;
+
+    let resp = reqwest::Client::new()
+        .get(&file.url)
+        .send()
+        .await
+        .map_err(|e| fatal(format!("HTTP request failed for {}: {}", file.filename, e)))?;
+    if !resp.status().is_success() {
+        return Err(fatal(format!("HTTP {} from {}", resp.status(), file.url)));
+    }
+
+    let total_bytes = resp.content_length();
+    let tmp = tmp_path(dest);
+    let mut out = tokio::fs::File::create(&tmp)
+        .await
+        .map_err(|e| fatal(format!("Failed to create temp file: {}", e)))?;
+    let mut hasher = Sha256::new();
+    let mut blob_hasher = match expected {
+        Some(Digest::GitBlob { size, .. }) => {
+            let mut blob = Sha1::new();
+            blob.update(format!("blob {}\0", size).as_bytes());
+            Some(blob)
+        }
+        _ => None,
+    };
+
+    let mut emit = |percent: u8, downloaded_bytes: u64| {
+        let progress = ModelDownloadProgress {
+            model_id: model_id.to_string(),
+            file: file.filename.clone(),
+            file_index,
+            file_count,
+            percent,
+            downloaded_bytes,
+            total_bytes,
+        };
+        if let Some(handle) = app {
+            let _ = handle.emit("model-download-progress", &progress);
+        }
+        on_progress(&progress);
+    };
+
+    let mut downloaded: u64 = 0;
+    let mut last_percent: u8 = 0;
+    let mut stream = resp.bytes_stream();
+    emit(0, 0);
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| fatal(format!("Download stream error: {}", e)))?;
+        out.write_all(&chunk)
+            .await
+            .map_err(|e| fatal(format!("Write error: {}", e)))?;
+        hasher.update(&chunk);
+        if let Some(blob) = &mut blob_hasher {
+            blob.update(&chunk);
+        }
+        downloaded += chunk.len() as u64;
+
+        if let Some(total) = total_bytes {
+            let percent = ((downloaded as f64 / total.max(1) as f64) * 100.0).min(100.0) as u8;
+            if percent > last_percent {
+                last_percent = percent;
+                if percent % 10 == 0 {
+                    info!(
+                        "Downloading {} {}... {}% ({:.1} MB / {:.1} MB)",
+                        model_id,
+                        file.filename,
+                        percent,
+                        downloaded as f64 / 1_048_576.0,
+                        total as f64 / 1_048_576.0
+                    );
+                }
+                emit(percent, downloaded);
+            }
+        }
+    }
+
+    out.flush().await.map_err(|e| fatal(format!("Flush error: {}", e)))?;
+    drop(out);
+
+    if let Some(total) = total_bytes {
+        if downloaded != total {
+            warn!(file = %file.filename, downloaded, total, "Download size mismatch");
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(FileError::Corrupt(format!(
+                "Incomplete download of {} ({} of {} bytes)",
+                file.filename, downloaded, total
+            )));
+        }
+    }
+
+    let sha256 = hex_encode_lower(&hasher.finalize());
+    let checked = match (expected, blob_hasher) {
+        (Some(Digest::Sha256(hash)), _) => Some((hash, sha256.clone())),
+        (Some(Digest::GitBlob { sha1, size }), Some(blob)) => {
+            let actual = if *size == downloaded {
+                hex_encode_lower(&blob.finalize())
+            } else {
+                format!("{} bytes instead of {}", downloaded, size)
+            };
+            Some((sha1, actual))
+        }
+        _ => None,
+    };
+    if let Some((expected, actual)) = checked {
+        if !actual.eq_ignore_ascii_case(expected) {
+            warn!(file = %file.filename, expected = %expected, actual = %actual, "Download checksum mismatch");
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(FileError::Corrupt(format!(
+                "Checksum mismatch for {} (expected {}, got {})",
+                file.filename, expected, actual
+            )));
+        }
+        info!(file = %file.filename, "Checksum verified");
+    }
+
+    tokio::fs::rename(&tmp, dest)
+        .await
+        .map_err(|e| fatal(format!("Rename failed: {}", e)))?;
+    emit(100, downloaded);
+    Ok(sha256)
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+/// List every managed model with its install status.
+#[tauri::command]
+pub fn list_model_assets(state: State<'_, ModelManager>) -> IpcResponse {
+    let models: Vec<_> = state
+        .catalog()
+        .iter()
+        .map(|asset| {
+            json!({
+                "id": asset.id,
+                "kind": asset.kind,
+                "label": asset.label,
+                "installed": asset.is_installed(),
+                "loaded": asset.loaded,
+                "downloading": is_downloading(&asset.id),
+                "sizeBytes": asset.size_on_disk(),
+                "files": asset.files.iter().map(|f| f.filename.clone()).collect::<Vec<_>>(),
+                "path": asset.dir.display().to_string(),
+            })
+        })
+        .collect();
+    IpcResponse::ok(json!({ "models": models }))
+}
+
+/// Download a model by id, emitting `model-download-progress` events.
+#[tauri::command]
+pub async fn download_model(
+    id: String,
+    state: State<'_, ModelManager>,
+    app: AppHandle,
+) -> Result<IpcResponse, ()> {
+    Ok(match state.download(&id, Some(&app)).await {
+        Ok(path) => IpcResponse::ok(json!({
+            "id": id,
+            "path": path.display().to_string(),
+        })),
+        Err(e) => IpcResponse::err(e),
+    })
+}
+
+/// Delete a downloaded model by id.
+///
+/// Refuses to delete a model the running voice engine has loaded, like
+/// `delete_stt_model`.
+#[tauri::command]
+pub fn delete_model(
+    id: String,
+    state: State<'_, ModelManager>,
+    voice_state: State<'_, VoiceEngineState>,
+) -> IpcResponse {
+    let active = match voice_state.lock() {
+        Ok(engine) if engine.is_running() => state.active_asset_ids(engine.config()),
+        Ok(_) => Vec::new(),
+        Err(e) => return IpcResponse::err(format!("Failed to lock voice state: {}", e)),
+    };
+    match state.delete(&id, &active) {
+        Ok(freed) => IpcResponse::ok(json!({ "id": id, "freedBytes": freed })),
+        Err(e) => IpcResponse::err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_covers_all_assets() {
+        let assets = catalog(Path::new("/tmp/vm-models"));
+        let ids: Vec<&str> = assets.iter().map(|a| a.id.as_str()).collect();
+        for id in [
+            "whisper-tiny.en",
+            "whisper-tiny",
+            "whisper-base.en",
+            "whisper-large-v3-turbo-q5_0",
+            "whisper-large-v3",
+            "parakeet",
+            "kokoro",
+            "silero-vad",
+        ] {
+            assert!(ids.contains(&id), "missing {}", id);
+        }
+        // Ids are unique
+        let unique: HashSet<_> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+    }
+
+    #[test]
+    fn test_asset_dirs_match_engine_loaders() {
+        let data_dir = Path::new("/tmp/vm-models");
+        let assets = catalog(data_dir);
+        let find = |id: &str| assets.iter().find(|a| a.id == id).unwrap();
+        assert_eq!(find("whisper-base.en").dir, data_dir.join("models"));
+        assert_eq!(find("kokoro").dir, data_dir.join("models").join("kokoro"));
+        assert_eq!(find("parakeet").dir, stt::parakeet_model_dir(data_dir));
+        // No engine loads Silero yet
+        assert!(!find("silero-vad").loaded);
+    }
+
+    #[test]
+    fn test_delete_and_status() {
+        let data_dir = std::env::temp_dir().join(format!("vm-model-mgr-{}", std::process::id()));
+        let manager = ModelManager::new(data_dir.clone());
+        let asset = manager.find("silero-vad").unwrap();
+        assert!(!asset.is_installed());
+
+        std::fs::create_dir_all(&asset.dir).unwrap();
+        std::fs::write(asset.dir.join("silero_vad.onnx"), [0u8; 16]).unwrap();
+        assert!(asset.is_installed());
+        assert_eq!(asset.size_on_disk(), 16);
+
+        // In use by the engine: refused, file kept
+        assert!(manager.delete("silero-vad", &["silero-vad".into()]).is_err());
+        assert!(asset.is_installed());
+
+        assert_eq!(manager.delete("silero-vad", &[]).unwrap(), 16);
+        assert!(!asset.is_installed());
+        assert!(manager.delete("nope", &[]).is_err());
+
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn test_whisper_asset_ids() {
+        let data_dir = Path::new("/tmp/vm-models");
+        assert_eq!(whisper_asset(data_dir, "base", "en").id, "whisper-base.en");
+        assert_eq!(whisper_asset(data_dir, "base", "de").id, "whisper-base");
+        assert_eq!(
+            whisper_asset(data_dir, "large-v3-turbo", "en").id,
+            "whisper-large-v3-turbo-q5_0"
+        );
+    }
+
+    #[test]
+    fn test_active_asset_ids() {
+        let manager = ModelManager::new(PathBuf::from("/tmp/vm-models"));
+        let mut config = VoiceEngineConfig::default();
+        config.stt_model_size = "small".into();
+        config.stt_language = "de".into();
+        assert_eq!(manager.active_asset_ids(&config), ["whisper-small", "kokoro"]);
+
+        config.stt_adapter = "parakeet".into();
+        config.tts_adapter = "edge".into();
+        assert_eq!(
+            manager.active_asset_ids(&config),
+            ["parakeet", "whisper-small"]
+        );
+    }
+
+    #[test]
+    fn test_every_catalog_file_can_be_verified() {
+        // Each file needs a bundled digest or a host that publishes one
+        let manifest = parse_manifest(CHECKSUM_MANIFEST);
+        for asset in catalog(Path::new("/tmp/vm-models")) {
+            for file in &asset.files {
+                assert!(
+                    manifest.contains_key(&file.filename) || digest_source(&file.url).is_some(),
+                    "{} ({}) has no manifest entry and no published digest",
+                    file.filename,
+                    file.url
+                );
+            }
+        }
+
+        let parsed = parse_manifest(r#"{"files": {"a.bin": "ABCDEF"}}"#);
+        assert_eq!(parsed.get("a.bin").map(String::as_str), Some("abcdef"));
+        assert!(parse_manifest("not json").is_empty());
+
+        assert!(is_sha256_hex(&"a".repeat(64)));
+        assert!(!is_sha256_hex("abc"));
+        assert!(!is_sha256_hex(&"g".repeat(64)));
+    }
+
+    #[test]
+    fn test_digest_sources() {
+        assert_eq!(
+            digest_source(&stt::model_download_url("base", "en")),
+            Some(DigestSource::HuggingFace)
+        );
+        assert_eq!(
+            digest_source(tts::KOKORO_FILES[0].1),
+            Some(DigestSource::GithubRelease {
+                api: "https://api.github.com/repos/thewh1teagle/kokoro-onnx/releases/tags/model-files-v1.0"
+                    .into(),
+                asset: "kokoro-v1.0.onnx".into(),
+            })
+        );
+        assert_eq!(
+            digest_source(SILERO_VAD_URL),
+            Some(DigestSource::GithubFile {
+                api: "https://api.github.com/repos/snakers4/silero-vad/contents/src/silero_vad/data/silero_vad.onnx?ref=master"
+                    .into(),
+            })
+        );
+        assert_eq!(digest_source("https://example.com/model.onnx"), None);
+        assert_eq!(digest_source("https://github.com/owner/repo"), None);
+    }
+
+    #[tokio::test]
+    async fn test_digests_on_disk() {
+        let path = std::env::temp_dir().join(format!("vm-blob-{}", std::process::id()));
+        std::fs::write(&path, b"abc").unwrap();
+        // `printf abc | git hash-object --stdin`
+        assert_eq!(
+            file_git_blob_sha1(&path).await.unwrap(),
+            "f2ba8f84ab5c1bce84a7b441cb1959cfc7093b7f"
+        );
+
+        assert_eq!(recorded_sha256(&path).await, None);
+        record_sha256(&path, &"AB".repeat(32)).await;
+        assert_eq!(recorded_sha256(&path).await, Some("ab".repeat(32)));
+
+        let _ = std::fs::remove_file(digest_path(&path));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_file_sha256() {
+        let path = std::env::temp_dir().join(format!("vm-sha-{}", std::process::id()));
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            file_sha256(&path).await.unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_tmp_path() {
+        assert_eq!(
+            tmp_path(Path::new("/m/ggml-base.en.bin")),
+            PathBuf::from("/m/ggml-base.en.bin.tmp")
+        );
+    }
+}
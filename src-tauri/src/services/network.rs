@@ -0,0 +1,500 @@
+//! LAN helpers behind the `network` MCP tool group.
+//!
+//! - Wake-on-LAN: UDP broadcast of the standard magic packet.
+//! - Ping: runs the system `ping` (ICMP needs raw sockets we don't have).
+//! - Port check: plain TCP connect with a timeout.
+//! - Service discovery: a one-shot mDNS / DNS-SD query (RFC 6762 "legacy
+//!   unicast" — sent from an ephemeral port, so responders answer us
+//!   directly) with a minimal DNS message parser.
+//!
+//! Everything here is stateless and runs directly in the MCP binary.
+
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use tokio::net::{TcpStream, UdpSocket};
+
+/// The DNS-SD meta-query that lists service types on the network.
+pub const SERVICE_TYPES_QUERY: &str = "_services._dns-sd._udp.local";
+
+const MDNS_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_SRV: u16 = 33;
+
+// ── Wake-on-LAN ─────────────────────────────────────────────────────
+
+/// Parse a MAC address written as `aa:bb:cc:dd:ee:ff`, `AA-BB-...`,
+/// `aabb.ccdd.eeff`, or 12 bare hex digits.
+pub fn parse_mac(s: &str) -> Result<[u8; 6], String> {
+    let hex: String = s
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.' | ' '))
+        .collect();
+    if hex.len() != 12 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid MAC address: '{}'", s));
+    }
+    let mut mac = [0u8; 6];
+    for (i, byte) in mac.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| format!("Invalid MAC address: '{}'", s))?;
+    }
+    Ok(mac)
+}
+
+/// The 102-byte magic packet: 6 × 0xFF, then the MAC 16 times.
+pub fn magic_packet(mac: &[u8; 6]) -> Vec<u8> {
+    let mut packet = vec![0xFF; 6];
+    for _ in 0..16 {
+        packet.extend_from_slice(mac);
+    }
+    packet
+}
+
+/// Broadcast a magic packet for `mac` (to UDP ports 9 and 7).
+/// `broadcast` defaults to 255.255.255.255; pass a subnet broadcast
+/// address (e.g. 192.168.1.255) if the limited broadcast isn't routed.
+pub async fn wake_on_lan(mac: &[u8; 6], broadcast: Option<Ipv4Addr>) -> Result<(), String> {
+    let target = broadcast.unwrap_or(Ipv4Addr::BROADCAST);
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(|e| format!("Failed to open UDP socket: {}", e))?;
+    socket
+        .set_broadcast(true)
+        .map_err(|e| format!("Failed to enable broadcast: {}", e))?;
+    let packet = magic_packet(mac);
+    for port in [9, 7] {
+        socket
+            .send_to(&packet, (target, port))
+            .await
+            .map_err(|e| format!("Failed to send magic packet: {}", e))?;
+    }
+    Ok(())
+}
+
+// ── Ping / port check ───────────────────────────────────────────────
+
+/// Outcome of a ping.
+#[derive(Debug, Clone)]
+pub struct PingResult {
+    pub reachable: bool,
+    /// Round-trip time of the reply, if one came back.
+    pub rtt_ms: Option<f64>,
+}
+
+/// Reject anything that isn't a plain host name or IP literal, so it can't
+/// be mistaken for a `ping` flag.
+pub fn validate_host(host: &str) -> Result<(), String> {
+    let ok = !host.is_empty()
+        && host.len() <= 253
+        && !host.starts_with('-')
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '_' | '%'));
+    if ok {
+        Ok(())
+    } else {
+        Err(format!("Invalid host: '{}'", host))
+    }
+}
+
+/// Send a single ping to `host` using the system `ping` command.
+pub async fn ping(host: &str, timeout: Duration) -> Result<PingResult, String> {
+    validate_host(host)?;
+    let mut cmd = tokio::process::Command::new("ping");
+    #[cfg(windows)]
+    {
+        cmd.args(["-n", "1", "-w", &timeout.as_millis().to_string()]);
+        cmd.creation_flags(crate::util::CREATE_NO_WINDOW);
+    }
+    #[cfg(target_os = "macos")]
+    cmd.args(["-c", "1", "-t", &timeout.as_secs().max(1).to_string()]);
+    #[cfg(all(unix, not(target_os = "macos")))]
+    cmd.args(["-c", "1", "-W", &timeout.as_secs().max(1).to_string()]);
+    cmd.arg(host);
+
+    let output = tokio::time::timeout(timeout + Duration::from_secs(2), cmd.output())
+        .await
+        .map_err(|_| "ping did not finish in time".to_string())?
+        .map_err(|e| format!("Failed to run ping: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rtt_ms = parse_ping_time(&stdout);
+    // Windows exits 0 for "Destination host unreachable", so also require a time.
+    Ok(PingResult {
+        reachable: output.status.success() && rtt_ms.is_some(),
+        rtt_ms,
+    })
+}
+
+/// Round-trip time from `ping` output: `time=12.3 ms`, `time<1ms`, and
+/// localized forms like `Zeit=12ms` all end in `=`/`<` + number + `ms`.
+pub fn parse_ping_time(output: &str) -> Option<f64> {
+    static RE: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
+        regex::Regex::new(r"[=<]\s*([0-9]+(?:[.,][0-9]+)?)\s*ms").unwrap()
+    });
+    let caps = RE.captures(output)?;
+    caps[1].replace(',', ".").parse().ok()
+}
+
+/// Try a TCP connection to `host:port`. Returns the connect time, or the
+/// reason it failed (refused, timed out, DNS failure).
+pub async fn check_port(host: &str, port: u16, timeout: Duration) -> Result<Duration, String> {
+    validate_host(host)?;
+    let start = Instant::now();
+    match tokio::time::timeout(timeout, TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => Ok(start.elapsed()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("timed out after {} ms", timeout.as_millis())),
+    }
+}
+
+// ── mDNS / DNS-SD discovery ─────────────────────────────────────────
+
+/// A service instance found via DNS-SD.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredService {
+    /// Full instance name, e.g. `NAS._smb._tcp.local`.
+    pub instance: String,
+    /// Target host from the SRV record, e.g. `nas.local`.
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    /// IPv4 addresses of `host` seen in the same responses.
+    pub addresses: Vec<Ipv4Addr>,
+}
+
+/// One parsed resource record.
+#[derive(Debug, Clone, PartialEq)]
+enum Record {
+    Ptr {
+        name: String,
+        target: String,
+    },
+    Srv {
+        name: String,
+        port: u16,
+        target: String,
+    },
+    A {
+        name: String,
+        addr: Ipv4Addr,
+    },
+}
+
+/// Normalize a service type (`_http._tcp`, `_http._tcp.local.`) to a
+/// fully-qualified name without the trailing dot.
+pub fn service_query_name(service: &str) -> String {
+    let s = service.trim().trim_end_matches('.');
+    if s.ends_with(".local") {
+        s.to_string()
+    } else {
+        format!("{}.local", s)
+    }
+}
+
+/// Query mDNS for `name` (a service type, or [`SERVICE_TYPES_QUERY`]) and
+/// collect answers for `wait`. Returns the PTR targets and all records seen.
+async fn mdns_query(name: &str, wait: Duration) -> Result<(Vec<String>, Vec<Record>), String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(|e| format!("Failed to open UDP socket: {}", e))?;
+    let dest = SocketAddr::from((MDNS_ADDR, MDNS_PORT));
+    socket
+        .send_to(&build_query(name), dest)
+        .await
+        .map_err(|e| format!("Failed to send mDNS query: {}", e))?;
+
+    let mut records = Vec::new();
+    let mut buf = vec![0u8; 9000];
+    let deadline = tokio::time::Instant::now() + wait;
+    while let Ok(Ok((len, _))) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await
+    {
+        records.extend(parse_records(&buf[..len]));
+    }
+
+    let mut targets: Vec<String> = records
+        .iter()
+        .filter_map(|r| match r {
+            Record::Ptr { name: n, target } if n.eq_ignore_ascii_case(name) => Some(target.clone()),
+            _ => None,
+        })
+        .collect();
+    targets.sort();
+    targets.dedup();
+    Ok((targets, records))
+}
+
+/// List service types advertised on the local network (`_http._tcp.local`, ...).
+pub async fn discover_service_types(wait: Duration) -> Result<Vec<String>, String> {
+    mdns_query(SERVICE_TYPES_QUERY, wait)
+        .await
+        .map(|(types, _)| types)
+}
+
+/// Find instances of `service` (e.g. `_smb._tcp`) with host, port, and address.
+pub async fn discover_services(
+    service: &str,
+    wait: Duration,
+) -> Result<Vec<DiscoveredService>, String> {
+    let name = service_query_name(service);
+    let (instances, records) = mdns_query(&name, wait).await?;
+    Ok(assemble_services(&instances, &records))
+}
+
+/// Join PTR → SRV → A records into one entry per instance.
+fn assemble_services(instances: &[String], records: &[Record]) -> Vec<DiscoveredService> {
+    let mut by_instance: BTreeMap<&str, DiscoveredService> = BTreeMap::new();
+    for instance in instances {
+        let mut svc = DiscoveredService {
+            instance: instance.clone(),
+            host: None,
+            port: None,
+            addresses: Vec::new(),
+        };
+        for r in records {
+            if let Record::Srv { name, port, target } = r {
+                if name.eq_ignore_ascii_case(instance) {
+                    svc.host = Some(target.clone());
+                    svc.port = Some(*port);
+                }
+            }
+        }
+        if let Some(host) = &svc.host {
+            for r in records {
+                if let Record::A { name, addr } = r {
+                    if name.eq_ignore_ascii_case(host) && !svc.addresses.contains(addr) {
+                        svc.addresses.push(*addr);
+                    }
+                }
+            }
+        }
+        by_instance.insert(instance, svc);
+    }
+    by_instance.into_values().collect()
+}
+
+/// Build a single-question PTR query (id 0, no flags).
+fn build_query(name: &str) -> Vec<u8> {
+    let mut msg = vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        let bytes = &label.as_bytes()[..label.len().min(63)];
+        msg.push(bytes.len() as u8);
+        msg.extend_from_slice(bytes);
+    }
+    msg.push(0);
+    msg.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    msg.extend_from_slice(&1u16.to_be_bytes()); // class IN
+    msg
+}
+
+/// Parse the PTR, SRV, and A records of a DNS response. Malformed input
+/// yields whatever parsed cleanly before the error.
+fn parse_records(msg: &[u8]) -> Vec<Record> {
+    let mut records = Vec::new();
+    if msg.len() < 12 || msg[2] & 0x80 == 0 {
+        return records; // too short, or a query rather than a response
+    }
+    let count = |i: usize| u16::from_be_bytes([msg[i], msg[i + 1]]) as usize;
+    let (questions, rrs) = (count(4), count(6) + count(8) + count(10));
+
+    let mut pos = 12;
+    for _ in 0..questions {
+        let Some((_, next)) = read_name(msg, pos) else {
+            return records;
+        };
+        pos = next + 4;
+    }
+    for _ in 0..rrs {
+        let Some((name, next)) = read_name(msg, pos) else {
+            break;
+        };
+        let Some(header) = msg.get(next..next + 10) else {
+            break;
+        };
+        let rtype = u16::from_be_bytes([header[0], header[1]]);
+        let rdlen = u16::from_be_bytes([header[8], header[9]]) as usize;
+        let rdata_start = next + 10;
+        let Some(rdata) = msg.get(rdata_start..rdata_start + rdlen) else {
+            break;
+        };
+        match rtype {
+            TYPE_PTR => {
+                if let Some((target, _)) = read_name(msg, rdata_start) {
+                    records.push(Record::Ptr { name, target });
+                }
+            }
+            TYPE_SRV if rdlen >= 7 => {
+                let port = u16::from_be_bytes([rdata[4], rdata[5]]);
+                if let Some((target, _)) = read_name(msg, rdata_start + 6) {
+                    records.push(Record::Srv { name, port, target });
+                }
+            }
+            TYPE_A if rdlen == 4 => {
+                let addr = Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]);
+                records.push(Record::A { name, addr });
+            }
+            _ => {}
+        }
+        pos = rdata_start + rdlen;
+    }
+    records
+}
+
+/// Read a (possibly compressed) domain name at `pos`. Returns the dotted
+/// name and the offset just past it in the original position.
+fn read_name(msg: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut end = None;
+    // Bound pointer chasing so a malicious loop can't spin forever.
+    for _ in 0..128 {
+        let len = *msg.get(pos)? as usize;
+        if len == 0 {
+            return Some((labels.join("."), end.unwrap_or(pos + 1)));
+        }
+        if len & 0xC0 == 0xC0 {
+            let ptr = ((len & 0x3F) << 8) | *msg.get(pos + 1)? as usize;
+            end.get_or_insert(pos + 2);
+            pos = ptr;
+            continue;
+        }
+        let label = msg.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + len;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_name(name: &str) -> Vec<u8> {
+        let mut out = Vec::new();
+        for label in name.split('.') {
+            out.push(label.len() as u8);
+            out.extend_from_slice(label.as_bytes());
+        }
+        out.push(0);
+        out
+    }
+
+    fn rr(name: &[u8], rtype: u16, rdata: &[u8]) -> Vec<u8> {
+        let mut out = name.to_vec();
+        out.extend_from_slice(&rtype.to_be_bytes());
+        out.extend_from_slice(&[0x80, 0x01, 0, 0, 0x11, 0x94]);
+        out.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        out.extend_from_slice(rdata);
+        out
+    }
+
+    #[test]
+    fn test_parse_mac() {
+        let mac = [0xAA, 0xBB, 0xCC, 0x01, 0x02, 0x03];
+        assert_eq!(parse_mac("aa:bb:cc:01:02:03").unwrap(), mac);
+        assert_eq!(parse_mac("AA-BB-CC-01-02-03").unwrap(), mac);
+        assert_eq!(parse_mac("aabb.cc01.0203").unwrap(), mac);
+        assert_eq!(parse_mac("aabbcc010203").unwrap(), mac);
+        assert!(parse_mac("aa:bb:cc").is_err());
+        assert!(parse_mac("zz:bb:cc:01:02:03").is_err());
+    }
+
+    #[test]
+    fn test_magic_packet() {
+        let mac = [1, 2, 3, 4, 5, 6];
+        let packet = magic_packet(&mac);
+        assert_eq!(packet.len(), 102);
+        assert_eq!(&packet[..6], &[0xFF; 6]);
+        assert_eq!(&packet[6..12], &mac);
+        assert_eq!(&packet[96..], &mac);
+    }
+
+    #[test]
+    fn test_parse_ping_time() {
+        assert_eq!(
+            parse_ping_time("64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms"),
+            Some(12.3)
+        );
+        assert_eq!(
+            parse_ping_time("Reply from 192.168.1.2: bytes=32 time<1ms TTL=64"),
+            Some(1.0)
+        );
+        assert_eq!(
+            parse_ping_time("Antwort von 10.0.0.2: Bytes=32 Zeit=4ms TTL=128"),
+            Some(4.0)
+        );
+        assert_eq!(parse_ping_time("Request timed out."), None);
+    }
+
+    #[test]
+    fn test_validate_host() {
+        assert!(validate_host("nas.local").is_ok());
+        assert!(validate_host("192.168.1.20").is_ok());
+        assert!(validate_host("fe80::1%eth0").is_ok());
+        assert!(validate_host("-c 100").is_err());
+        assert!(validate_host("host; rm -rf").is_err());
+        assert!(validate_host("").is_err());
+    }
+
+    #[test]
+    fn test_service_query_name() {
+        assert_eq!(service_query_name("_smb._tcp"), "_smb._tcp.local");
+        assert_eq!(service_query_name("_http._tcp.local."), "_http._tcp.local");
+    }
+
+    #[test]
+    fn test_build_query() {
+        let q = build_query("_smb._tcp.local");
+        assert_eq!(&q[4..6], &[0, 1]);
+        assert_eq!(&q[12..17], b"\x04_smb");
+        assert_eq!(&q[q.len() - 4..], &[0, TYPE_PTR as u8, 0, 1]);
+    }
+
+    #[test]
+    fn test_parse_response_and_assemble() {
+        // Header: response, 0 questions, 1 answer, 0 authority, 2 additional
+        let mut msg = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 2];
+        let service_off = msg.len();
+        let service = encode_name("_smb._tcp.local");
+        // PTR _smb._tcp.local -> NAS.<ptr to _smb._tcp.local>
+        let mut instance = vec![3];
+        instance.extend_from_slice(b"NAS");
+        instance.extend_from_slice(&[0xC0, service_off as u8]);
+        msg.extend(rr(&service, TYPE_PTR, &instance));
+        // SRV NAS._smb._tcp.local -> nas.local:445
+        let mut srv = vec![0, 0, 0, 0, 0x01, 0xBD];
+        srv.extend(encode_name("nas.local"));
+        msg.extend(rr(&encode_name("NAS._smb._tcp.local"), TYPE_SRV, &srv));
+        // A nas.local -> 192.168.1.20
+        msg.extend(rr(&encode_name("nas.local"), TYPE_A, &[192, 168, 1, 20]));
+
+        let records = parse_records(&msg);
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[0],
+            Record::Ptr {
+                name: "_smb._tcp.local".into(),
+                target: "NAS._smb._tcp.local".into()
+            }
+        );
+
+        let services = assemble_services(&["NAS._smb._tcp.local".into()], &records);
+        assert_eq!(
+            services,
+            vec![DiscoveredService {
+                instance: "NAS._smb._tcp.local".into(),
+                host: Some("nas.local".into()),
+                port: Some(445),
+                addresses: vec![Ipv4Addr::new(192, 168, 1, 20)],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_read_name_rejects_pointer_loop() {
+        let msg = [0xC0, 0x00];
+        assert!(read_name(&msg, 0).is_none());
+        assert!(parse_records(&[0, 0, 0x84]).is_empty());
+    }
+}
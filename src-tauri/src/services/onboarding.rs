@@ -0,0 +1,491 @@
+//! First-run onboarding: the checks behind the welcome wizard.
+//!
+//! Each step checks one part of the setup and can fix what it finds, so the
+//! wizard only has to show state and forward clicks:
+//!
+//! - `models`: the STT (and Kokoro TTS) models the saved voice settings
+//!   need are on disk. With `download`, missing ones are fetched through the
+//!   model manager.
+//! - `audio`: a record-and-playback loop. Records `AUDIO_TEST_SECS` from the
+//!   microphone, fails if nothing above the noise came in, then plays the
+//!   take back on the output device so the user hears both ends at once.
+//! - `vad`: VAD calibration (see `voice::calibration`); the recommended
+//!   `vadThreshold` and `silenceTimeoutSecs` are saved.
+//! - `provider`: the configured AI provider answers. CLI providers must be
+//!   installed and signed in (live `status` probe); local servers must serve
+//!   `/v1/models`; cloud APIs must accept the saved key.
+//!
+//! Every change of a step (started, phase, download progress, result) is
+//! emitted as an `onboarding-step` event carrying the whole `StepState`.
+//! States live for the session only; whether the wizard was finished is
+//! still `system.onboardingCompleted`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::config::{get_config_snapshot, set_config};
+use crate::commands::onboarding::{probe_auth, AuthState};
+use crate::commands::tools::detect_tool;
+use crate::commands::voice::{active_engine_config, pause_wake_word, resume_wake_word};
+use crate::providers::cli::get_cli_config;
+use crate::services::model_manager::ModelManager;
+use crate::voice::calibration;
+use crate::voice::pipeline::self_test;
+use crate::voice::pipeline::TARGET_SAMPLE_RATE;
+use crate::voice::resample::ResamplerKind;
+
+/// Event carrying a `StepState`.
+pub const EVENT: &str = "onboarding-step";
+/// Length of the record-and-playback take.
+pub const AUDIO_TEST_SECS: f64 = 4.0;
+/// Peak level below which the microphone counts as silent.
+const MIN_PEAK: f32 = 0.02;
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One onboarding step, in wizard order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Step {
+    Models,
+    Audio,
+    Vad,
+    Provider,
+}
+
+impl Step {
+    pub const ALL: [Step; 4] = [Step::Models, Step::Audio, Step::Vad, Step::Provider];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "models" => Some(Self::Models),
+            "audio" => Some(Self::Audio),
+            "vad" => Some(Self::Vad),
+            "provider" => Some(Self::Provider),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StepStatus {
+    Pending,
+    Running,
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// Where a step stands.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepState {
+    pub step: Step,
+    pub status: StepStatus,
+    /// What the step is doing, found or got wrong.
+    pub detail: String,
+    /// What the UI should prompt for while running: `download`, `record`,
+    /// `playback`, `silence` or `speech`.
+    pub phase: Option<&'static str>,
+    /// 0-100 while downloading.
+    pub percent: Option<u8>,
+    /// Step-specific results (missing models, levels, the calibration, the
+    /// provider's models).
+    pub data: Option<Value>,
+}
+
+impl StepState {
+    fn new(step: Step) -> Self {
+        Self {
+            step,
+            status: StepStatus::Pending,
+            detail: String::new(),
+            phase: None,
+            percent: None,
+            data: None,
+        }
+    }
+}
+
+/// All steps plus whether none is left to do.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStatus {
+    pub steps: Vec<StepState>,
+    /// Every step passed or was skipped.
+    pub complete: bool,
+}
+
+static STATE: Mutex<Vec<StepState>> = Mutex::new(Vec::new());
+
+fn with_state<R>(f: impl FnOnce(&mut Vec<StepState>) -> R) -> R {
+    let mut steps = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    if steps.is_empty() {
+        steps.extend(Step::ALL.map(StepState::new));
+    }
+    f(&mut steps)
+}
+
+pub fn status() -> OnboardingStatus {
+    with_state(|steps| OnboardingStatus {
+        complete: steps
+            .iter()
+            .all(|s| matches!(s.status, StepStatus::Passed | StepStatus::Skipped)),
+        steps: steps.clone(),
+    })
+}
+
+/// Store `state` and emit it.
+fn publish(app: &AppHandle, state: StepState) {
+    with_state(|steps| {
+        if let Some(slot) = steps.iter_mut().find(|s| s.step == state.step) {
+            *slot = state.clone();
+        }
+    });
+    let _ = app.emit(EVENT, &state);
+}
+
+/// Tell the UI `step` is running, in `phase`.
+fn progress(app: &AppHandle, step: Step, phase: &'static str, detail: String, percent: Option<u8>) {
+    publish(
+        app,
+        StepState {
+            status: StepStatus::Running,
+            detail,
+            phase: Some(phase),
+            percent,
+            ..StepState::new(step)
+        },
+    );
+}
+
+/// Mark `step` skipped; the wizard moves on without it.
+pub fn skip(app: &AppHandle, step: Step) -> StepState {
+    let state = StepState {
+        status: StepStatus::Skipped,
+        detail: "Skipped".into(),
+        ..StepState::new(step)
+    };
+    publish(app, state.clone());
+    state
+}
+
+/// Run `step` and return its result, which is also the last event emitted.
+pub async fn run(app: &AppHandle, step: Step, download: bool) -> StepState {
+    let started = StepState {
+        status: StepStatus::Running,
+        ..StepState::new(step)
+    };
+    // A second click while the step runs gets the run in progress
+    let running = with_state(|steps| {
+        let slot = steps.iter_mut().find(|s| s.step == step)?;
+        if slot.status == StepStatus::Running {
+            return Some(slot.clone());
+        }
+        *slot = started.clone();
+        None
+    });
+    if let Some(current) = running {
+        return current;
+    }
+    let _ = app.emit(EVENT, &started);
+
+    let result = match step {
+        Step::Models => check_models(app, download).await,
+        Step::Audio => check_audio(app).await,
+        Step::Vad => calibrate_vad(app).await,
+        Step::Provider => check_provider().await,
+    };
+    let (status, detail, data) = match result {
+        Ok((detail, data)) => (StepStatus::Passed, detail, data),
+        Err((detail, data)) => (StepStatus::Failed, detail, data),
+    };
+    if status == StepStatus::Failed {
+        tracing::warn!(?step, "Onboarding step failed: {}", detail);
+    } else {
+        tracing::info!(?step, "Onboarding step passed: {}", detail);
+    }
+    let state = StepState {
+        status,
+        detail,
+        data,
+        ..StepState::new(step)
+    };
+    publish(app, state.clone());
+    state
+}
+
+/// `Ok((detail, data))` passes the step, `Err` fails it.
+type StepResult = Result<(String, Option<Value>), (String, Option<Value>)>;
+
+fn fail(detail: impl Into<String>) -> (String, Option<Value>) {
+    (detail.into(), None)
+}
+
+async fn check_models(app: &AppHandle, download: bool) -> StepResult {
+    let config = active_engine_config(&get_config_snapshot()).map_err(|e| fail(e.to_string()))?;
+    let manager = app.state::<ModelManager>();
+    let needed: Vec<_> = manager
+        .active_asset_ids(&config)
+        .iter()
+        .filter_map(|id| manager.find(id))
+        .collect();
+    let missing: Vec<_> = needed.iter().filter(|a| !a.is_installed()).collect();
+    let labels: Vec<String> = missing.iter().map(|a| a.label.clone()).collect();
+    if missing.is_empty() {
+        let installed: Vec<String> = needed.iter().map(|a| a.label.clone()).collect();
+        return Ok((
+            format!("Installed: {}", installed.join(", ")),
+            Some(json!({ "missing": [] })),
+        ));
+    }
+    if !download {
+        return Err((
+            format!("Not downloaded yet: {}", labels.join(", ")),
+            Some(json!({ "missing": labels })),
+        ));
+    }
+    for asset in &missing {
+        let label = asset.label.clone();
+        let mut last = None;
+        crate::services::model_manager::download_asset(asset, Some(app), |p| {
+            // One event per percent, not per chunk
+            if last != Some(p.percent) {
+                last = Some(p.percent);
+                progress(
+                    app,
+                    Step::Models,
+                    "download",
+                    format!("Downloading {}", label),
+                    Some(p.percent),
+                );
+            }
+        })
+        .await
+        .map_err(|e| fail(format!("Failed to download {}: {}", asset.label, e)))?;
+    }
+    Ok((
+        format!("Downloaded: {}", labels.join(", ")),
+        Some(json!({ "missing": [] })),
+    ))
+}
+
+async fn check_audio(app: &AppHandle) -> StepResult {
+    let voice = get_config_snapshot().voice;
+    let input = voice.input_device.clone();
+    let output = voice.output_device.clone();
+    let resampler = ResamplerKind::from_config(&voice.resampler);
+
+    progress(
+        app,
+        Step::Audio,
+        "record",
+        format!("Say something for {} seconds", AUDIO_TEST_SECS),
+        None,
+    );
+    let paused = pause_wake_word(app);
+    let take = tokio::task::spawn_blocking(move || {
+        calibration::record(input.as_deref(), AUDIO_TEST_SECS, resampler)
+    })
+    .await
+    .map_err(|e| format!("Recording task failed: {}", e))
+    .and_then(|r| r);
+    resume_wake_word(app, paused);
+
+    let samples = take.map_err(fail)?;
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    let levels = json!({ "peak": peak, "secs": samples.len() as f64 / TARGET_SAMPLE_RATE as f64 });
+    if peak < MIN_PEAK {
+        return Err((
+            "The microphone picked up nothing. Check that it is plugged in, \
+             unmuted and allowed in the system privacy settings."
+                .into(),
+            Some(levels),
+        ));
+    }
+
+    progress(
+        app,
+        Step::Audio,
+        "playback",
+        "Playing your recording back".into(),
+        None,
+    );
+    tokio::task::spawn_blocking(move || {
+        self_test::play_clip(output.as_deref(), samples, TARGET_SAMPLE_RATE)
+    })
+    .await
+    .map_err(|e| format!("Playback task failed: {}", e))
+    .and_then(|r| r)
+    .map_err(|e| (e, Some(levels.clone())))?;
+
+    Ok((
+        format!("Recorded and played back (peak level {:.2})", peak),
+        Some(levels),
+    ))
+}
+
+async fn calibrate_vad(app: &AppHandle) -> StepResult {
+    let voice = get_config_snapshot().voice;
+    let device = voice.input_device;
+    let resampler = ResamplerKind::from_config(&voice.resampler);
+
+    let paused = pause_wake_word(app);
+    let mut takes = Vec::with_capacity(2);
+    for (phase, prompt) in [("silence", "Stay quiet"), ("speech", "Keep talking")] {
+        progress(
+            app,
+            Step::Vad,
+            phase,
+            format!("{} for {} seconds", prompt, calibration::PHASE_SECS),
+            None,
+        );
+        let device = device.clone();
+        let take = tokio::task::spawn_blocking(move || {
+            calibration::record(device.as_deref(), calibration::PHASE_SECS, resampler)
+        })
+        .await
+        .map_err(|e| format!("Calibration task failed: {}", e))
+        .and_then(|r| r);
+        match take {
+            Ok(samples) => takes.push(samples),
+            Err(e) => {
+                resume_wake_word(app, paused);
+                return Err(fail(e));
+            }
+        }
+    }
+    resume_wake_word(app, paused);
+
+    let cal = calibration::analyze(&takes[0], &takes[1]).map_err(fail)?;
+    let saved = set_config(json!({
+        "voice": {
+            "vadThreshold": cal.vad_threshold,
+            "silenceTimeoutSecs": cal.silence_timeout_secs,
+        }
+    }));
+    if let Some(e) = saved.error {
+        return Err((
+            format!("Failed to save the calibration: {}", e),
+            Some(json!(cal)),
+        ));
+    }
+    Ok((
+        format!(
+            "Threshold {:.4}, silence timeout {:.1} s ({:.0} dB above the room)",
+            cal.vad_threshold, cal.silence_timeout_secs, cal.snr_db
+        ),
+        Some(json!(cal)),
+    ))
+}
+
+async fn check_provider() -> StepResult {
+    let ai = get_config_snapshot().ai;
+    let provider = ai.provider.clone();
+    if provider == "dictation" {
+        return Ok(("Dictation only, no AI provider to check".into(), None));
+    }
+
+    if let Some(cli) = get_cli_config(&provider) {
+        let command = cli.command.to_string();
+        let name = cli.display_name.to_string();
+        let ptype = provider.clone();
+        let (installed, auth) = tokio::task::spawn_blocking(move || {
+            let installed = detect_tool(&command).available;
+            (installed, installed.then(|| probe_auth(&ptype)))
+        })
+        .await
+        .map_err(|e| fail(format!("Provider check failed: {}", e)))?;
+        return match (installed, auth) {
+            (false, _) => Err(fail(format!("{} is not installed", name))),
+            (true, Some(AuthState::LoggedIn)) => Ok((format!("{} is signed in", name), None)),
+            (true, Some(AuthState::Unknown)) | (true, None) => Ok((
+                format!(
+                    "{} is installed; its sign-in can't be checked from here",
+                    name
+                ),
+                None,
+            )),
+            (true, Some(AuthState::Expired)) => Err(fail(format!("{} sign-in has expired", name))),
+            (true, Some(AuthState::LoggedOut)) => Err(fail(format!("{} is not signed in", name))),
+        };
+    }
+
+    let base = ai
+        .endpoints
+        .get(&provider)
+        .cloned()
+        .unwrap_or_else(|| crate::providers::api::default_endpoint(&provider).to_string());
+    let key = ai.api_keys.get(&provider).cloned().flatten();
+    let local = matches!(provider.as_str(), "ollama" | "lmstudio" | "jan");
+    if !local && key.as_deref().map_or(true, str::is_empty) {
+        return Err(fail(format!("No API key saved for {}", provider)));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(PROVIDER_TIMEOUT)
+        .build()
+        .map_err(|e| fail(format!("HTTP client error: {}", e)))?;
+    let mut request = client.get(format!("{}/v1/models", base.trim_end_matches('/')));
+    if let Some(key) = key.filter(|k| !k.is_empty()) {
+        request = request.bearer_auth(key);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| fail(format!("Can't reach {} at {}: {}", provider, base, e)))?;
+    let status = response.status();
+    if status.as_u16() == 401 || status.as_u16() == 403 {
+        return Err(fail(format!("{} rejected the API key", provider)));
+    }
+    if !status.is_success() {
+        return Err(fail(format!(
+            "{} answered HTTP {}",
+            provider,
+            status.as_u16()
+        )));
+    }
+    let body: Value = response.json().await.unwrap_or_default();
+    let models: Vec<&str> = body
+        .get("data")
+        .or_else(|| body.get("models"))
+        .and_then(Value::as_array)
+        .map(|list| {
+            list.iter()
+                .filter_map(|m| {
+                    m.get("id")
+                        .or_else(|| m.get("name"))
+                        .and_then(Value::as_str)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if local && models.is_empty() {
+        return Err((
+            format!("{} is running but has no models installed", provider),
+            Some(json!({ "models": models })),
+        ));
+    }
+    Ok((
+        format!("{} is reachable ({} models)", provider, models.len()),
+        Some(json!({ "models": models })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_names_roundtrip() {
+        for step in Step::ALL {
+            let name = serde_json::to_value(step).unwrap();
+            assert_eq!(Step::parse(name.as_str().unwrap()), Some(step));
+        }
+        assert_eq!(Step::parse("gpu"), None);
+    }
+}
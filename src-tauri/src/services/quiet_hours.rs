@@ -0,0 +1,311 @@
+//! Scheduled quiet hours.
+//!
+//! While quiet hours are active, replies stay text-only (TTS is
+//! suppressed), wake-word mode stops starting recordings, and the frontend
+//! keeps non-error toasts out of view (they still land in the notification
+//! center). The weekly schedule lives in the `quietHours` config; an
+//! override ("quiet now" / "not tonight") wins until it expires or, without
+//! a duration, until the schedule next changes on its own.
+//!
+//! The schedule is evaluated in the user's local time via
+//! `utcOffsetMinutes`, which the settings UI refreshes on save. A ticker
+//! re-evaluates it every few seconds and emits `quiet-hours-changed` with
+//! the new [`QuietHoursStatus`] on every transition; hot paths (the audio
+//! loop, TTS) only read a cached flag.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::info;
+
+use crate::config::schema::{QuietHoursConfig, QuietHoursWindow};
+
+const TICK: Duration = Duration::from_secs(15);
+const MINUTES_PER_DAY: i64 = 24 * 60;
+const MINUTES_PER_WEEK: i64 = 7 * MINUTES_PER_DAY;
+const DAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Cached result of the last evaluation, read by the audio and TTS paths.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static OVERRIDE: Mutex<Option<Override>> = Mutex::new(None);
+
+/// A manual override of the schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Override {
+    /// true = force quiet, false = force normal.
+    quiet: bool,
+    /// Unix seconds the override lapses at; None = never.
+    until: Option<i64>,
+}
+
+/// Current quiet-hours state, as returned to the frontend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHoursStatus {
+    /// Whether quiet hours are in effect right now.
+    pub active: bool,
+    /// What the schedule alone says.
+    pub scheduled: bool,
+    /// "quiet" or "off" while an override is in effect.
+    pub override_mode: Option<&'static str>,
+    /// Unix ms the override lapses at.
+    pub override_until: Option<u64>,
+    /// Unix ms the schedule next flips (within a week).
+    pub next_change: Option<u64>,
+}
+
+/// Whether quiet hours are in effect. Cheap; safe to call per audio chunk.
+pub fn active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Evaluate the schedule now and keep it evaluated for the app's lifetime.
+pub fn start(app: &AppHandle) {
+    refresh(app);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            refresh(&app);
+        }
+    });
+}
+
+/// Re-evaluate against the saved config, emitting `quiet-hours-changed`
+/// when the active state flips. Returns the fresh status.
+pub fn refresh(app: &AppHandle) -> QuietHoursStatus {
+    let status = current_status();
+    if ACTIVE.swap(status.active, Ordering::Relaxed) != status.active {
+        info!(active = status.active, "Quiet hours changed");
+        let _ = app.emit("quiet-hours-changed", &status);
+    }
+    status
+}
+
+/// Override the schedule. `mode` is "quiet", "off", or "auto" (clear the
+/// override). Without `minutes`, the override lasts until the schedule next
+/// changes on its own (or indefinitely if it never does).
+pub fn set_override(app: &AppHandle, mode: &str, minutes: Option<u32>) -> Result<QuietHoursStatus, String> {
+    let quiet = match mode {
+        "quiet" => Some(true),
+        "off" => Some(false),
+        "auto" => None,
+        other => return Err(format!("Unknown quiet hours mode '{}' (expected quiet, off or auto)", other)),
+    };
+    let next = quiet.map(|quiet| {
+        let now = unix_secs();
+        let until = match minutes {
+            Some(m) => Some(now + i64::from(m) * 60),
+            None => next_change(&config(), now),
+        };
+        Override { quiet, until }
+    });
+    *OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()) = next;
+    Ok(refresh(app))
+}
+
+fn current_status() -> QuietHoursStatus {
+    let mut guard = OVERRIDE.lock().unwrap_or_else(|e| e.into_inner());
+    status_at(&config(), &mut guard, unix_secs())
+}
+
+/// Evaluate `config` plus the override at `now` (unix seconds), dropping
+/// the override once it has lapsed.
+fn status_at(config: &QuietHoursConfig, over: &mut Option<Override>, now: i64) -> QuietHoursStatus {
+    if over.and_then(|o| o.until).is_some_and(|until| now >= until) {
+        *over = None;
+    }
+    let scheduled = scheduled_at(config, now);
+    let to_ms = |secs: i64| (secs.max(0) as u64) * 1000;
+    QuietHoursStatus {
+        active: over.map_or(scheduled, |o| o.quiet),
+        scheduled,
+        override_mode: over.map(|o| if o.quiet { "quiet" } else { "off" }),
+        override_until: over.and_then(|o| o.until).map(to_ms),
+        next_change: next_change(config, now).map(to_ms),
+    }
+}
+
+/// Whether the schedule says quiet at `now` (unix seconds).
+fn scheduled_at(config: &QuietHoursConfig, now: i64) -> bool {
+    config.enabled && {
+        let minute = minute_of_week(now, config.utc_offset_minutes);
+        config.schedule.iter().any(|w| window_contains(w, minute))
+    }
+}
+
+/// Unix seconds (on a minute boundary) the schedule next flips, or None if
+/// it doesn't within a week.
+fn next_change(config: &QuietHoursConfig, now: i64) -> Option<i64> {
+    let current = scheduled_at(config, now);
+    let first = now - now.rem_euclid(60) + 60;
+    (0..MINUTES_PER_WEEK)
+        .map(|i| first + i * 60)
+        .find(|&t| scheduled_at(config, t) != current)
+}
+
+/// Local minute of the week, 0 = Monday 00:00.
+fn minute_of_week(unix_secs: i64, utc_offset_minutes: i32) -> i64 {
+    let local = unix_secs.div_euclid(60) + i64::from(utc_offset_minutes);
+    // Day 0 of the epoch was a Thursday.
+    (local + 3 * MINUTES_PER_DAY).rem_euclid(MINUTES_PER_WEEK)
+}
+
+/// Whether `window` covers `minute` (of the week). A window ending at or
+/// before its start runs past midnight; one starting and ending at the same
+/// time covers the whole day.
+fn window_contains(window: &QuietHoursWindow, minute: i64) -> bool {
+    let (Some(start), Some(end)) = (parse_time(&window.start), parse_time(&window.end)) else {
+        return false;
+    };
+    let len = match (end - start).rem_euclid(MINUTES_PER_DAY) {
+        0 => MINUTES_PER_DAY,
+        n => n,
+    };
+    let days: Vec<i64> = if window.days.is_empty() {
+        (0..7).collect()
+    } else {
+        window.days.iter().filter_map(|d| parse_day(d)).collect()
+    };
+    days.into_iter().any(|day| {
+        let from = day * MINUTES_PER_DAY + start;
+        (minute - from).rem_euclid(MINUTES_PER_WEEK) < len
+    })
+}
+
+/// "HH:MM" (24-hour) to minutes past midnight.
+pub(crate) fn parse_time(s: &str) -> Option<i64> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m): (i64, i64) = (h.parse().ok()?, m.parse().ok()?);
+    ((0..24).contains(&h) && (0..60).contains(&m)).then_some(h * 60 + m)
+}
+
+/// "mon".."sun" (or the full name, any case) to 0..7.
+pub(crate) fn parse_day(s: &str) -> Option<i64> {
+    let s = s.trim().to_ascii_lowercase();
+    let short = s.get(..3)?;
+    DAY_NAMES.iter().position(|d| *d == short).map(|i| i as i64)
+}
+
+/// Check a schedule before saving it.
+pub fn validate(config: &QuietHoursConfig) -> Result<(), String> {
+    for window in &config.schedule {
+        for t in [&window.start, &window.end] {
+            if parse_time(t).is_none() {
+                return Err(format!("Invalid quiet hours time '{}' (expected HH:MM)", t));
+            }
+        }
+        if let Some(day) = window.days.iter().find(|d| parse_day(d).is_none()) {
+            return Err(format!("Invalid quiet hours day '{}'", day));
+        }
+    }
+    Ok(())
+}
+
+fn config() -> QuietHoursConfig {
+    crate::commands::config::get_config_snapshot().quiet_hours
+}
+
+fn unix_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Monday 2026-03-16 00:00 UTC.
+    const MONDAY: i64 = 1_773_619_200;
+
+    fn at(day: i64, hour: i64, minute: i64) -> i64 {
+        MONDAY + day * 86_400 + hour * 3600 + minute * 60
+    }
+
+    fn window(days: &[&str], start: &str, end: &str) -> QuietHoursWindow {
+        QuietHoursWindow {
+            days: days.iter().map(|d| d.to_string()).collect(),
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+
+    fn schedule(windows: Vec<QuietHoursWindow>) -> QuietHoursConfig {
+        QuietHoursConfig { enabled: true, schedule: windows, utc_offset_minutes: 0 }
+    }
+
+    #[test]
+    fn test_minute_of_week_starts_monday() {
+        assert_eq!(minute_of_week(MONDAY, 0), 0);
+        assert_eq!(minute_of_week(at(6, 23, 59), 0), MINUTES_PER_WEEK - 1);
+        // UTC+2: Monday 00:00 UTC is already 02:00 local.
+        assert_eq!(minute_of_week(MONDAY, 120), 120);
+        // UTC-5: still Sunday evening locally.
+        assert_eq!(minute_of_week(MONDAY, -300), MINUTES_PER_WEEK - 300);
+    }
+
+    #[test]
+    fn test_overnight_window_wraps_into_next_day() {
+        let config = schedule(vec![window(&["fri"], "22:00", "07:00")]);
+        assert!(!scheduled_at(&config, at(4, 21, 59)));
+        assert!(scheduled_at(&config, at(4, 22, 0)));
+        assert!(scheduled_at(&config, at(5, 6, 59)));
+        assert!(!scheduled_at(&config, at(5, 7, 0)));
+        // Only Friday night, not Thursday night.
+        assert!(!scheduled_at(&config, at(3, 23, 0)));
+        // Sunday night wraps into Monday.
+        let config = schedule(vec![window(&["sun"], "23:00", "01:00")]);
+        assert!(scheduled_at(&config, at(0, 0, 30)));
+    }
+
+    #[test]
+    fn test_empty_days_means_every_day_and_disabled_never_quiet() {
+        let mut config = schedule(vec![window(&[], "12:00", "13:00")]);
+        for day in 0..7 {
+            assert!(scheduled_at(&config, at(day, 12, 30)));
+        }
+        config.enabled = false;
+        assert!(!scheduled_at(&config, at(0, 12, 30)));
+    }
+
+    #[test]
+    fn test_next_change_finds_window_edges() {
+        let config = schedule(vec![window(&["mon"], "22:00", "07:00")]);
+        assert_eq!(next_change(&config, at(0, 20, 0) + 15), Some(at(0, 22, 0)));
+        assert_eq!(next_change(&config, at(0, 23, 0)), Some(at(1, 7, 0)));
+        assert_eq!(next_change(&schedule(vec![]), MONDAY), None);
+    }
+
+    #[test]
+    fn test_override_wins_until_it_lapses() {
+        let config = schedule(vec![window(&[], "22:00", "07:00")]);
+        let mut over = Some(Override { quiet: false, until: Some(at(0, 23, 0)) });
+        let status = status_at(&config, &mut over, at(0, 22, 30));
+        assert!(status.scheduled && !status.active);
+        assert_eq!(status.override_mode, Some("off"));
+
+        let status = status_at(&config, &mut over, at(0, 23, 0));
+        assert!(status.active);
+        assert_eq!(over, None);
+
+        let mut forced = Some(Override { quiet: true, until: None });
+        assert!(status_at(&config, &mut forced, at(2, 12, 0)).active);
+    }
+
+    #[test]
+    fn test_parse_time_and_day() {
+        assert_eq!(parse_time("07:30"), Some(450));
+        assert_eq!(parse_time("24:00"), None);
+        assert_eq!(parse_time("7"), None);
+        assert_eq!(parse_day("Saturday"), Some(5));
+        assert_eq!(parse_day("xyz"), None);
+        assert!(validate(&schedule(vec![window(&["mon"], "25:00", "07:00")])).is_err());
+        assert!(validate(&QuietHoursConfig::default()).is_ok());
+    }
+}
@@ -0,0 +1,311 @@
+//! Runtime context for AI provider requests.
+//!
+//! Collects small pieces of live app state — the current time, the voice
+//! activation mode, whether the user cut off the previous answer, any
+//! pending timers, and a session prompt addendum — and renders them as a compact block that API providers
+//! send alongside each request. This lets the model adapt, e.g. keep the next
+//! answer short after an interruption.
+//!
+//! State is process-global and updated from the voice pipeline and AI
+//! commands; [`build_block`] reads it. The block is never stored in the
+//! conversation history, so it always reflects the moment of the request.
+
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::voice::VoiceMode;
+
+/// A timer the model should know about (e.g. a spoken "remind me in 5 minutes").
+#[derive(Debug, Clone)]
+struct PendingTimer {
+    id: u64,
+    label: String,
+    due: SystemTime,
+}
+
+#[derive(Debug)]
+struct ContextState {
+    /// Active voice mode, or None while the voice engine is stopped.
+    voice_mode: Option<VoiceMode>,
+    /// The user interrupted the most recent answer (barge-in / stop / abort).
+    interrupted: bool,
+    timers: Vec<PendingTimer>,
+    next_timer_id: u64,
+    /// Temporary system-prompt addendum for the current provider session
+    /// ("act as a code reviewer"). Cleared when the provider stops.
+    session_prompt: Option<String>,
+}
+
+static STATE: Mutex<ContextState> = Mutex::new(ContextState {
+    voice_mode: None,
+    interrupted: false,
+    timers: Vec::new(),
+    next_timer_id: 0,
+    session_prompt: None,
+});
+
+/// Longest accepted session prompt addendum, in chars.
+pub const MAX_SESSION_PROMPT_CHARS: usize = 2000;
+
+/// Record the voice mode (`None` when the voice engine stops).
+pub fn set_voice_mode(mode: Option<VoiceMode>) {
+    if let Ok(mut state) = STATE.lock() {
+        state.voice_mode = mode;
+    }
+}
+
+/// Whether the voice engine is running (replies to the user may be spoken).
+pub fn voice_active() -> bool {
+    STATE.lock().map(|s| s.voice_mode.is_some()).unwrap_or(false)
+}
+
+/// Record that the user interrupted the answer being spoken or streamed.
+pub fn note_interruption() {
+    if let Ok(mut state) = STATE.lock() {
+        state.interrupted = true;
+    }
+}
+
+/// Register a pending timer. Returns an id for [`cancel_timer`].
+/// Timers drop out of the context block on their own once due.
+pub fn add_timer(label: &str, due: SystemTime) -> u64 {
+    let Ok(mut state) = STATE.lock() else {
+        return 0;
+    };
+    state.next_timer_id += 1;
+    let id = state.next_timer_id;
+    state.timers.push(PendingTimer {
+        id,
+        label: label.to_string(),
+        due,
+    });
+    id
+}
+
+/// Remove a pending timer. Returns false if it was unknown or already due.
+pub fn cancel_timer(id: u64) -> bool {
+    let Ok(mut state) = STATE.lock() else {
+        return false;
+    };
+    let before = state.timers.len();
+    state.timers.retain(|t| t.id != id);
+    state.timers.len() < before
+}
+
+/// Remove every pending timer.
+pub fn clear_timers() {
+    if let Ok(mut state) = STATE.lock() {
+        state.timers.clear();
+    }
+}
+
+/// Label and due time of every timer that isn't due yet.
+pub fn pending_timers() -> Vec<(String, SystemTime)> {
+    let now = SystemTime::now();
+    let Ok(state) = STATE.lock() else {
+        return Vec::new();
+    };
+    state
+        .timers
+        .iter()
+        .filter(|t| t.due > now)
+        .map(|t| (t.label.clone(), t.due))
+        .collect()
+}
+
+/// Set (or with `None`, clear) the session prompt addendum. It applies from
+/// the next provider request until cleared or the provider stops.
+pub fn set_session_prompt(prompt: Option<String>) {
+    if let Ok(mut state) = STATE.lock() {
+        state.session_prompt = prompt.filter(|p| !p.trim().is_empty());
+    }
+}
+
+/// The active session prompt addendum, if any.
+pub fn session_prompt() -> Option<String> {
+    STATE.lock().ok().and_then(|s| s.session_prompt.clone())
+}
+
+/// Render the context block for a provider request.
+///
+/// `new_turn` is true for a request carrying fresh user input; it consumes
+/// the interruption flag so it only colors the reply that follows it. Tool
+/// follow-ups within the same turn pass false and still see the flag.
+pub fn build_block(new_turn: bool) -> String {
+    let now = SystemTime::now();
+    let Ok(mut state) = STATE.lock() else {
+        return render(now, None, false, &[], None);
+    };
+    state.timers.retain(|t| t.due > now);
+    let interrupted = state.interrupted;
+    if new_turn {
+        state.interrupted = false;
+    }
+    render(
+        now,
+        state.voice_mode,
+        interrupted,
+        &state.timers,
+        state.session_prompt.as_deref(),
+    )
+}
+
+fn render(
+    now: SystemTime,
+    voice_mode: Option<VoiceMode>,
+    interrupted: bool,
+    timers: &[PendingTimer],
+    session_prompt: Option<&str>,
+) -> String {
+    let mut lines = vec![
+        "[Runtime context]".to_string(),
+        format!("Time: {}", format_utc(now)),
+    ];
+
+    let mode = match voice_mode {
+        Some(VoiceMode::PushToTalk) => Some("push-to-talk"),
+        Some(VoiceMode::Toggle) => Some("toggle"),
+        Some(VoiceMode::WakeWord) => Some("wake word"),
+        None => None,
+    };
+    lines.push(match mode {
+        Some(mode) => format!("Voice: on ({}); replies may be spoken aloud", mode),
+        None => "Voice: off".to_string(),
+    });
+
+    if interrupted {
+        lines.push("The user interrupted your previous answer. Keep this reply short.".into());
+    }
+
+    if !timers.is_empty() {
+        let list: Vec<String> = timers
+            .iter()
+            .map(|t| {
+                let left = t.due.duration_since(now).unwrap_or_default();
+                format!("{} (in {})", t.label, format_remaining(left))
+            })
+            .collect();
+        lines.push(format!("Pending timers: {}", list.join(", ")));
+    }
+
+    if let Some(prompt) = session_prompt {
+        lines.push(format!(
+            "Session instructions from the user (this conversation only): {}",
+            prompt.trim()
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// Format as `2026-03-14 09:05 UTC (Saturday)`.
+fn format_utc(t: SystemTime) -> String {
+    const WEEKDAYS: [&str; 7] = [
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+    ];
+    let secs = t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let days = secs / 86400;
+    let time_of_day = secs % 86400;
+    let (year, month, day) = days_to_date(days as i64);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC ({})",
+        year,
+        month,
+        day,
+        time_of_day / 3600,
+        (time_of_day % 3600) / 60,
+        WEEKDAYS[(days % 7) as usize]
+    )
+}
+
+/// Format a countdown as `1h 5m`, `4m 10s`, or `30s`.
+fn format_remaining(d: Duration) -> String {
+    let secs = d.as_secs();
+    match (secs / 3600, (secs % 3600) / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Convert days since Unix epoch to (year, month, day).
+pub(crate) fn days_to_date(mut days: i64) -> (i64, u32, u32) {
+    // Algorithm from https://howardhinnant.github.io/date_algorithms.html
+    days += 719468;
+    let era = if days >= 0 { days } else { days - 146096 } / 146097;
+    let doe = (days - era * 146097) as u32;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = if m <= 2 { y + 1 } else { y };
+    (year, m, d)
+}
+
+/// Days since Unix epoch for a (year, month, day). Inverse of
+/// `days_to_date`, from the same source.
+pub(crate) fn date_to_days(year: i64, month: u32, day: u32) -> i64 {
+    let (m, d) = (month as i64, day as i64);
+    let y = if m <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_utc() {
+        // 2026-03-14 09:05:30 UTC
+        let t = UNIX_EPOCH + Duration::from_secs(1_773_479_130);
+        assert_eq!(format_utc(t), "2026-03-14 09:05 UTC (Saturday)");
+        assert_eq!(format_utc(UNIX_EPOCH), "1970-01-01 00:00 UTC (Thursday)");
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::from_secs(30)), "30s");
+        assert_eq!(format_remaining(Duration::from_secs(250)), "4m 10s");
+        assert_eq!(format_remaining(Duration::from_secs(3900)), "1h 5m");
+    }
+
+    #[test]
+    fn test_render_block() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_773_479_130);
+        let block = render(now, None, false, &[], None);
+        assert!(block.starts_with("[Runtime context]\nTime: 2026-03-14"));
+        assert!(block.contains("Voice: off"));
+        assert!(!block.contains("interrupted"));
+        assert!(!block.contains("Pending timers"));
+        assert!(!block.contains("Session instructions"));
+
+        let timers = [PendingTimer {
+            id: 1,
+            label: "tea".into(),
+            due: now + Duration::from_secs(250),
+        }];
+        let block = render(
+            now,
+            Some(VoiceMode::PushToTalk),
+            true,
+            &timers,
+            Some("act as a code reviewer "),
+        );
+        assert!(block.contains("Voice: on (push-to-talk)"));
+        assert!(block.contains("interrupted your previous answer"));
+        assert!(block.contains("Pending timers: tea (in 4m 10s)"));
+        assert!(block.ends_with("(this conversation only): act as a code reviewer"));
+    }
+}
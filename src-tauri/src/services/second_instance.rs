@@ -0,0 +1,123 @@
+//! Commands handed over by a second launch of Voice Mirror.
+//!
+//! `tauri_plugin_single_instance` keeps one instance per user: a second
+//! process exits before `setup` runs (so it never opens the mic, the hotkey
+//! hooks or a pipe) and its command line arrives in the running instance's
+//! plugin callback, which calls `handle`. The window comes forward, then
+//! each recognised flag runs like the matching voice hotkey:
+//!
+//! - `--toggle`: start a recording, or stop the current one
+//! - `--stop-speaking`: interrupt TTS playback
+//! - `--cycle-mode`: switch to the next activation mode
+//!
+//! So `voice-mirror --toggle` can be bound to a desktop shortcut or a
+//! script. Unknown arguments are logged and ignored.
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::error::AppError;
+use crate::services::hotkeys::{self, HotkeyAction};
+
+/// What a handed-over command acts on (the app; a recorder in tests).
+trait CommandTarget {
+    fn focus(&self);
+    fn run(&self, action: HotkeyAction) -> Result<(), AppError>;
+}
+
+impl CommandTarget for AppHandle {
+    fn focus(&self) {
+        if let Some(window) = self.get_webview_window("main") {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+
+    fn run(&self, action: HotkeyAction) -> Result<(), AppError> {
+        hotkeys::run_action(self, action, true)
+    }
+}
+
+/// Act on the command line of a second launch (`argv[0]` is its program).
+pub fn handle(app: &AppHandle, argv: Vec<String>) {
+    let app = app.clone();
+    // Off the event loop: the engine lock can be held through a restart
+    tauri::async_runtime::spawn_blocking(move || apply(&app, &argv));
+}
+
+fn apply(target: &impl CommandTarget, argv: &[String]) {
+    info!(?argv, "Second instance launched, handing over to this one");
+    target.focus();
+    for action in parse(argv) {
+        if let Err(e) = target.run(action) {
+            warn!(?action, "Handed-over command failed: {}", e);
+        }
+    }
+}
+
+/// The actions requested on a command line, in order.
+fn parse(argv: &[String]) -> Vec<HotkeyAction> {
+    argv.iter()
+        .skip(1)
+        .filter_map(|arg| match arg.as_str() {
+            "--toggle" => Some(HotkeyAction::Toggle),
+            "--stop-speaking" => Some(HotkeyAction::StopSpeaking),
+            "--cycle-mode" => Some(HotkeyAction::CycleMode),
+            other => {
+                warn!(arg = other, "Ignoring unknown second-instance argument");
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct Recorder {
+        focused: RefCell<bool>,
+        ran: RefCell<Vec<HotkeyAction>>,
+    }
+
+    impl CommandTarget for Recorder {
+        fn focus(&self) {
+            *self.focused.borrow_mut() = true;
+        }
+
+        fn run(&self, action: HotkeyAction) -> Result<(), AppError> {
+            self.ran.borrow_mut().push(action);
+            Err(AppError::NotRunning)
+        }
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_handed_over_commands_are_applied() {
+        let target = Recorder::default();
+        apply(
+            &target,
+            &argv(&["voice-mirror", "--stop-speaking", "--nope", "--toggle"]),
+        );
+        assert!(*target.focused.borrow());
+        // A failing command doesn't stop the next
+        assert_eq!(
+            *target.ran.borrow(),
+            vec![HotkeyAction::StopSpeaking, HotkeyAction::Toggle]
+        );
+    }
+
+    #[test]
+    fn test_plain_launch_only_focuses() {
+        let target = Recorder::default();
+        apply(&target, &argv(&["--toggle"]));
+        assert!(*target.focused.borrow());
+        assert!(target.ran.borrow().is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+//! Session snapshots and restore points.
+//!
+//! A snapshot captures the assistant state that `config.json` doesn't hold:
+//! the API provider's conversation history, the session prompt addendum,
+//! pending timers, and the active tool profile with the groups it loads.
+//! Taking one before a risky experiment gives a point to roll back to, and
+//! since it is a self-contained JSON file it can be copied to another
+//! machine to carry on the conversation there.
+//!
+//! Snapshots go to `{data_dir}/snapshots/` unless the caller picks a path.
+//! On restore, history replaces that of the running API provider (CLI
+//! providers keep theirs inside the agent process), timers that fell due in
+//! the meantime are dropped, and the tool profile is saved to config, which
+//! CLI providers pick up on their next start.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Snapshot format version. Files from a newer version are refused.
+pub const VERSION: u32 = 1;
+
+const DIR_NAME: &str = "snapshots";
+
+/// A pending timer, with its due time as Unix ms.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotTimer {
+    pub label: String,
+    pub due: u64,
+}
+
+/// Everything a snapshot file holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSnapshot {
+    pub version: u32,
+    /// Unix ms the snapshot was taken.
+    pub created_at: u64,
+    /// Provider the history came from (e.g. "ollama"); `None` when no
+    /// provider was running.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Conversation messages after the configured system prompt.
+    #[serde(default)]
+    pub history: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub session_prompt: Option<String>,
+    /// Name of the active tool profile (`ai.toolProfile`).
+    pub tool_profile: String,
+    /// Tool groups that profile loaded.
+    #[serde(default)]
+    pub tool_groups: Vec<String>,
+    #[serde(default)]
+    pub timers: Vec<SnapshotTimer>,
+}
+
+impl SessionSnapshot {
+    /// Timers from the snapshot that are still in the future at `now`.
+    pub fn pending_timers(&self, now: SystemTime) -> Vec<(String, SystemTime)> {
+        let now_ms = to_ms(now);
+        self.timers
+            .iter()
+            .filter(|t| t.due > now_ms)
+            .map(|t| (t.label.clone(), UNIX_EPOCH + Duration::from_millis(t.due)))
+            .collect()
+    }
+}
+
+/// Convert runtime timers to their snapshot form.
+pub fn timers_from(timers: &[(String, SystemTime)]) -> Vec<SnapshotTimer> {
+    timers
+        .iter()
+        .map(|(label, due)| SnapshotTimer {
+            label: label.clone(),
+            due: to_ms(*due),
+        })
+        .collect()
+}
+
+/// Unix ms of `t`.
+pub fn to_ms(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// `{data_dir}/snapshots`.
+pub fn snapshot_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(DIR_NAME)
+}
+
+/// Where a snapshot taken at `created_at` goes when no path is given.
+pub fn default_path(data_dir: &Path, created_at: u64) -> PathBuf {
+    snapshot_dir(data_dir).join(format!("session-{}.json", created_at))
+}
+
+/// Check a caller-chosen snapshot path: absolute, no `..`, a `.json` file.
+pub fn validate_path(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err("Snapshot path must be absolute".into());
+    }
+    if path.components().any(|c| matches!(c, Component::ParentDir)) {
+        return Err("Snapshot path must not contain '..' components".into());
+    }
+    if path.extension().and_then(|e| e.to_str()) != Some("json") {
+        return Err("Snapshot file must have a .json extension".into());
+    }
+    Ok(())
+}
+
+/// Write `snapshot` to `path`, creating the parent directory.
+pub fn save(path: &Path, snapshot: &SessionSnapshot) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Read a snapshot written by [`save`].
+pub fn load(path: &Path) -> Result<SessionSnapshot, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse(&text)
+}
+
+fn parse(text: &str) -> Result<SessionSnapshot, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| format!("Not a session snapshot: {}", e))?;
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > u64::from(VERSION) {
+        return Err(format!(
+            "Unsupported snapshot version {} (this build reads up to {})",
+            version, VERSION
+        ));
+    }
+    serde_json::from_value(value).map_err(|e| format!("Not a session snapshot: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn snapshot(timers: Vec<SnapshotTimer>) -> SessionSnapshot {
+        SessionSnapshot {
+            version: VERSION,
+            created_at: 1_700_000_000_000,
+            provider: Some("ollama".into()),
+            history: vec![
+                json!({ "role": "user", "content": "hi" }),
+                json!({ "role": "assistant", "content": "Hello!" }),
+            ],
+            session_prompt: Some("act as a code reviewer".into()),
+            tool_profile: "voice-assistant".into(),
+            tool_groups: vec!["core".into(), "memory".into()],
+            timers,
+        }
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("vm-snapshots-{}", std::process::id()));
+        let path = default_path(&dir, 1_700_000_000_000);
+        let original = snapshot(vec![SnapshotTimer {
+            label: "tea".into(),
+            due: 42,
+        }]);
+        save(&path, &original).unwrap();
+        assert_eq!(load(&path).unwrap(), original);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_rejects_other_versions() {
+        let mut newer = serde_json::to_value(snapshot(Vec::new())).unwrap();
+        newer["version"] = json!(VERSION + 1);
+        assert!(parse(&newer.to_string())
+            .unwrap_err()
+            .contains("Unsupported"));
+        assert!(parse(r#"{ "history": [] }"#).is_err());
+        assert!(parse("not json").is_err());
+        // Missing optional parts take defaults
+        let minimal = parse(r#"{ "version": 1, "createdAt": 5, "toolProfile": "x" }"#).unwrap();
+        assert!(minimal.history.is_empty() && minimal.timers.is_empty());
+    }
+
+    #[test]
+    fn test_pending_timers_drop_lapsed() {
+        let now = UNIX_EPOCH + Duration::from_secs(1000);
+        let snap = snapshot(vec![
+            SnapshotTimer {
+                label: "past".into(),
+                due: 999_000,
+            },
+            SnapshotTimer {
+                label: "future".into(),
+                due: 1_060_000,
+            },
+        ]);
+        let pending = snap.pending_timers(now);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].0, "future");
+        assert_eq!(timers_from(&pending), vec![snap.timers[1].clone()]);
+    }
+
+    #[test]
+    fn test_validate_path() {
+        let base = std::env::temp_dir();
+        assert!(validate_path(&base.join("s.json")).is_ok());
+        assert!(validate_path(Path::new("s.json")).is_err());
+        assert!(validate_path(&base.join("s.txt")).is_err());
+        assert!(validate_path(&base.join("..").join("s.json")).is_err());
+    }
+}
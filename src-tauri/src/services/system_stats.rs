@@ -0,0 +1,501 @@
+//! System resource snapshots for the `sysinfo` MCP tools.
+//!
+//! CPU usage and network throughput are deltas, so each sampler refreshes
+//! twice with a short sleep in between. Every function here blocks for at
+//! most about a second and should be called from `spawn_blocking`.
+//!
+//! Battery status isn't covered by the `sysinfo` crate and is read per OS:
+//! `/sys/class/power_supply` on Linux, `pmset -g batt` on macOS, and
+//! `GetSystemPowerStatus` on Windows.
+
+use std::time::Duration;
+
+use sysinfo::{Disks, Networks, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Interval between the two network samples used for throughput.
+const NETWORK_SAMPLE: Duration = Duration::from_secs(1);
+
+/// Machine-wide CPU and memory usage.
+#[derive(Debug, Clone)]
+pub struct SystemSnapshot {
+    pub host_name: Option<String>,
+    pub os: Option<String>,
+    pub uptime_secs: u64,
+    pub cpu_percent: f32,
+    pub cpu_count: usize,
+    pub per_cpu_percent: Vec<f32>,
+    /// 1/5/15-minute load averages (zero on Windows).
+    pub load_average: [f64; 3],
+    pub memory_total: u64,
+    pub memory_used: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+}
+
+/// Per-process usage. `cpu_percent` is relative to one core, so a busy
+/// multi-threaded process can exceed 100.
+#[derive(Debug, Clone)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_percent: f32,
+    pub memory: u64,
+}
+
+/// How to rank processes in [`top_processes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSort {
+    Cpu,
+    Memory,
+}
+
+impl ProcessSort {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "cpu" => Some(Self::Cpu),
+            "memory" | "mem" | "ram" => Some(Self::Memory),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskUsage {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total: u64,
+    pub available: u64,
+    pub removable: bool,
+}
+
+/// Bytes per second over the sample window, plus totals since boot.
+#[derive(Debug, Clone)]
+pub struct InterfaceThroughput {
+    pub name: String,
+    pub rx_per_sec: u64,
+    pub tx_per_sec: u64,
+    pub total_rx: u64,
+    pub total_tx: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatteryStatus {
+    pub percent: f32,
+    /// "charging", "discharging", "full", or "unknown".
+    pub state: String,
+    pub on_ac_power: Option<bool>,
+    /// Time to empty (discharging) or full (charging), when the OS knows it.
+    pub time_remaining_secs: Option<u64>,
+}
+
+/// CPU and memory usage, sampled over `sysinfo::MINIMUM_CPU_UPDATE_INTERVAL`.
+pub fn snapshot() -> SystemSnapshot {
+    let mut sys = System::new();
+    sys.refresh_cpu_usage();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    sys.refresh_cpu_usage();
+    sys.refresh_memory();
+
+    let load = System::load_average();
+    SystemSnapshot {
+        host_name: System::host_name(),
+        os: System::long_os_version(),
+        uptime_secs: System::uptime(),
+        cpu_percent: sys.global_cpu_usage(),
+        cpu_count: sys.cpus().len(),
+        per_cpu_percent: sys.cpus().iter().map(|c| c.cpu_usage()).collect(),
+        load_average: [load.one, load.five, load.fifteen],
+        memory_total: sys.total_memory(),
+        memory_used: sys.used_memory(),
+        swap_total: sys.total_swap(),
+        swap_used: sys.used_swap(),
+    }
+}
+
+/// The `limit` heaviest processes by CPU or memory.
+///
+/// Processes sharing a name (browser tabs, helper processes) are reported
+/// individually; [`group_by_name`] folds them together.
+pub fn top_processes(sort: ProcessSort, limit: usize) -> Vec<ProcessUsage> {
+    let kind = ProcessRefreshKind::nothing().with_cpu().with_memory();
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    sys.refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+
+    let own_pid = std::process::id();
+    let processes: Vec<ProcessUsage> = sys
+        .processes()
+        .iter()
+        .filter(|(pid, _)| pid.as_u32() != own_pid)
+        .map(|(pid, p)| ProcessUsage {
+            pid: pid.as_u32(),
+            name: p.name().to_string_lossy().into_owned(),
+            cpu_percent: p.cpu_usage(),
+            memory: p.memory(),
+        })
+        .collect();
+    rank(processes, sort, limit)
+}
+
+/// Merge processes with the same name, summing CPU and memory. The merged
+/// entry keeps the pid of the first member (the heaviest, if `processes`
+/// is already ranked).
+pub fn group_by_name(processes: Vec<ProcessUsage>, sort: ProcessSort) -> Vec<ProcessUsage> {
+    let mut groups: Vec<(ProcessUsage, usize)> = Vec::new();
+    for p in processes {
+        match groups.iter_mut().find(|(g, _)| g.name == p.name) {
+            Some((g, count)) => {
+                g.cpu_percent += p.cpu_percent;
+                g.memory += p.memory;
+                *count += 1;
+            }
+            None => groups.push((p, 1)),
+        }
+    }
+    let merged: Vec<ProcessUsage> = groups
+        .into_iter()
+        .map(|(mut g, count)| {
+            if count > 1 {
+                g.name = format!("{} ({} processes)", g.name, count);
+            }
+            g
+        })
+        .collect();
+    let len = merged.len();
+    rank(merged, sort, len)
+}
+
+fn rank(mut processes: Vec<ProcessUsage>, sort: ProcessSort, limit: usize) -> Vec<ProcessUsage> {
+    match sort {
+        ProcessSort::Cpu => processes.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent)),
+        ProcessSort::Memory => processes.sort_by_key(|p| std::cmp::Reverse(p.memory)),
+    }
+    processes.truncate(limit);
+    processes
+}
+
+/// Mounted disks, skipping pseudo filesystems with no capacity.
+pub fn disks() -> Vec<DiskUsage> {
+    Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .filter(|d| d.total_space() > 0)
+        .map(|d| DiskUsage {
+            name: d.name().to_string_lossy().into_owned(),
+            mount_point: d.mount_point().display().to_string(),
+            file_system: d.file_system().to_string_lossy().into_owned(),
+            total: d.total_space(),
+            available: d.available_space(),
+            removable: d.is_removable(),
+        })
+        .collect()
+}
+
+/// Per-interface throughput over one second, busiest first. Loopback and
+/// interfaces that have never carried traffic are skipped.
+pub fn network_throughput() -> Vec<InterfaceThroughput> {
+    let mut networks = Networks::new_with_refreshed_list();
+    std::thread::sleep(NETWORK_SAMPLE);
+    networks.refresh(true);
+
+    let secs = NETWORK_SAMPLE.as_secs_f64();
+    let mut out: Vec<InterfaceThroughput> = networks
+        .iter()
+        .filter(|(name, data)| {
+            !is_loopback(name) && data.total_received() + data.total_transmitted() > 0
+        })
+        .map(|(name, data)| InterfaceThroughput {
+            name: name.clone(),
+            rx_per_sec: (data.received() as f64 / secs) as u64,
+            tx_per_sec: (data.transmitted() as f64 / secs) as u64,
+            total_rx: data.total_received(),
+            total_tx: data.total_transmitted(),
+        })
+        .collect();
+    out.sort_by_key(|i| std::cmp::Reverse(i.rx_per_sec + i.tx_per_sec));
+    out
+}
+
+fn is_loopback(name: &str) -> bool {
+    name == "lo" || name.starts_with("lo0") || name.to_lowercase().contains("loopback")
+}
+
+/// Battery status, or `Ok(None)` on machines without a battery.
+pub fn battery() -> Result<Option<BatteryStatus>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        Ok(linux_battery(std::path::Path::new(
+            "/sys/class/power_supply",
+        )))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .map_err(|e| format!("Failed to run pmset: {}", e))?;
+        Ok(parse_pmset(&String::from_utf8_lossy(&output.stdout)))
+    }
+    #[cfg(windows)]
+    {
+        windows_battery()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    {
+        Err("Battery status is not supported on this platform".into())
+    }
+}
+
+/// Read the first `type == Battery` supply under `root` (normally
+/// `/sys/class/power_supply`), plus whether any `Mains` supply is online.
+#[cfg(any(target_os = "linux", test))]
+fn linux_battery(root: &std::path::Path) -> Option<BatteryStatus> {
+    let read = |dir: &std::path::Path, file: &str| {
+        std::fs::read_to_string(dir.join(file))
+            .ok()
+            .map(|s| s.trim().to_string())
+    };
+    let read_num = |dir: &std::path::Path, file: &str| -> Option<f64> {
+        read(dir, file).and_then(|s| s.parse().ok())
+    };
+
+    let mut supplies: Vec<std::path::PathBuf> = std::fs::read_dir(root)
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    supplies.sort();
+
+    let on_ac_power = supplies
+        .iter()
+        .filter(|d| read(d, "type").as_deref() == Some("Mains"))
+        .map(|d| read(d, "online").as_deref() == Some("1"))
+        .reduce(|a, b| a || b);
+
+    let dir = supplies
+        .iter()
+        .find(|d| read(d, "type").as_deref() == Some("Battery"))?;
+
+    let percent = match read_num(dir, "capacity") {
+        Some(c) => c,
+        None => {
+            let now = read_num(dir, "energy_now").or_else(|| read_num(dir, "charge_now"))?;
+            let full = read_num(dir, "energy_full").or_else(|| read_num(dir, "charge_full"))?;
+            if full <= 0.0 {
+                return None;
+            }
+            now / full * 100.0
+        }
+    };
+    let state = match read(dir, "status")
+        .unwrap_or_default()
+        .to_lowercase()
+        .as_str()
+    {
+        "charging" => "charging",
+        "discharging" => "discharging",
+        "full" | "not charging" => "full",
+        _ => "unknown",
+    }
+    .to_string();
+
+    // Energy in µWh with power in µW, or charge in µAh with current in µA.
+    let rate = read_num(dir, "power_now").or_else(|| read_num(dir, "current_now"));
+    let now = read_num(dir, "energy_now").or_else(|| read_num(dir, "charge_now"));
+    let full = read_num(dir, "energy_full").or_else(|| read_num(dir, "charge_full"));
+    let time_remaining_secs = match (rate, now, full, state.as_str()) {
+        (Some(rate), Some(now), _, "discharging") if rate > 0.0 => Some(now / rate * 3600.0),
+        (Some(rate), Some(now), Some(full), "charging") if rate > 0.0 && full > now => {
+            Some((full - now) / rate * 3600.0)
+        }
+        _ => None,
+    }
+    .map(|s| s as u64);
+
+    Some(BatteryStatus {
+        percent: percent.clamp(0.0, 100.0) as f32,
+        state,
+        on_ac_power,
+        time_remaining_secs,
+    })
+}
+
+/// Parse `pmset -g batt` output, e.g.
+/// ` -InternalBattery-0 (id=1234)<TAB>85%; discharging; 3:12 remaining present: true`.
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset(output: &str) -> Option<BatteryStatus> {
+    let on_ac_power = output.lines().next().map(|l| l.contains("'AC Power'"));
+    let line = output.lines().find(|l| l.contains("InternalBattery"))?;
+    let stats = line.split('\t').nth(1).unwrap_or(line);
+    let mut parts = stats.split(';').map(str::trim);
+
+    let percent: f32 = parts.next()?.trim_end_matches('%').parse().ok()?;
+    let state = match parts.next().unwrap_or_default() {
+        "charging" => "charging",
+        "discharging" => "discharging",
+        "charged" | "finishing charge" | "AC attached" => "full",
+        _ => "unknown",
+    }
+    .to_string();
+    let time_remaining_secs = parts
+        .next()
+        .and_then(|t| t.split_whitespace().next())
+        .and_then(|t| t.split_once(':'))
+        .and_then(|(h, m)| Some(h.parse::<u64>().ok()? * 3600 + m.parse::<u64>().ok()? * 60));
+
+    Some(BatteryStatus {
+        percent,
+        state,
+        on_ac_power,
+        time_remaining_secs,
+    })
+}
+
+#[cfg(windows)]
+fn windows_battery() -> Result<Option<BatteryStatus>, String> {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    // SAFETY: GetSystemPowerStatus only writes into the struct we pass.
+    unsafe { GetSystemPowerStatus(&mut status) }
+        .map_err(|e| format!("GetSystemPowerStatus failed: {}", e))?;
+
+    // BatteryFlag 128 = no system battery, 255 = unknown status.
+    if status.BatteryFlag == 128 || status.BatteryFlag == 255 {
+        return Ok(None);
+    }
+    let on_ac_power = match status.ACLineStatus {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
+    };
+    let state = if status.BatteryFlag & 8 != 0 {
+        "charging"
+    } else if on_ac_power == Some(true) {
+        "full"
+    } else if on_ac_power == Some(false) {
+        "discharging"
+    } else {
+        "unknown"
+    };
+    Ok(Some(BatteryStatus {
+        percent: if status.BatteryLifePercent == 255 {
+            0.0
+        } else {
+            status.BatteryLifePercent as f32
+        },
+        state: state.into(),
+        on_ac_power,
+        time_remaining_secs: (status.BatteryLifeTime != u32::MAX)
+            .then_some(status.BatteryLifeTime as u64),
+    }))
+}
+
+/// Human-readable byte count ("1.5 GB").
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Human-readable duration ("3h 12m").
+pub fn format_duration(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3600, secs % 3600 / 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", mins),
+        (0, _) => format!("{}h {}m", hours, mins),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proc(pid: u32, name: &str, cpu: f32, memory: u64) -> ProcessUsage {
+        ProcessUsage {
+            pid,
+            name: name.into(),
+            cpu_percent: cpu,
+            memory,
+        }
+    }
+
+    #[test]
+    fn test_rank_and_group() {
+        let procs = vec![
+            proc(1, "chrome", 20.0, 300),
+            proc(2, "code", 35.0, 500),
+            proc(3, "chrome", 30.0, 400),
+        ];
+        let by_cpu = rank(procs.clone(), ProcessSort::Cpu, 2);
+        assert_eq!(by_cpu[0].pid, 2);
+        assert_eq!(by_cpu[1].pid, 3);
+
+        let grouped = group_by_name(procs, ProcessSort::Memory);
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[0].name, "chrome (2 processes)");
+        assert_eq!(grouped[0].memory, 700);
+        assert_eq!(grouped[0].cpu_percent, 50.0);
+    }
+
+    #[test]
+    fn test_linux_battery() {
+        let root = std::env::temp_dir().join(format!("vm-battery-{}", std::process::id()));
+        let bat = root.join("BAT0");
+        let ac = root.join("AC");
+        std::fs::create_dir_all(&bat).unwrap();
+        std::fs::create_dir_all(&ac).unwrap();
+        for (file, value) in [
+            ("type", "Battery"),
+            ("capacity", "42"),
+            ("status", "Discharging"),
+            ("energy_now", "20000000"),
+            ("power_now", "10000000"),
+        ] {
+            std::fs::write(bat.join(file), value).unwrap();
+        }
+        std::fs::write(ac.join("type"), "Mains").unwrap();
+        std::fs::write(ac.join("online"), "0").unwrap();
+
+        let status = linux_battery(&root).unwrap();
+        let _ = std::fs::remove_dir_all(&root);
+        assert_eq!(status.percent, 42.0);
+        assert_eq!(status.state, "discharging");
+        assert_eq!(status.on_ac_power, Some(false));
+        assert_eq!(status.time_remaining_secs, Some(7200));
+    }
+
+    #[test]
+    fn test_parse_pmset() {
+        let out = "Now drawing from 'Battery Power'\n \
+                   -InternalBattery-0 (id=4653155)\t85%; discharging; 3:12 remaining present: true\n";
+        let status = parse_pmset(out).unwrap();
+        assert_eq!(status.percent, 85.0);
+        assert_eq!(status.state, "discharging");
+        assert_eq!(status.on_ac_power, Some(false));
+        assert_eq!(status.time_remaining_secs, Some(3 * 3600 + 12 * 60));
+
+        assert!(parse_pmset("Now drawing from 'AC Power'\n").is_none());
+    }
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+        assert_eq!(format_duration(45 * 60), "45m");
+        assert_eq!(format_duration(3 * 3600 + 12 * 60), "3h 12m");
+        assert_eq!(format_duration(2 * 86_400 + 5 * 3600), "2d 5h");
+    }
+}
@@ -0,0 +1,340 @@
+//! Conversation transcript log.
+//!
+//! Every transcription (what the user said) and every reply the pipeline
+//! speaks is appended as one JSON line to a per-session file in
+//! `{data_dir}/transcripts/`. A session is one app run, so the files read
+//! like separate conversations. The `transcript_*` commands query entries by
+//! date, export them as Markdown, and purge them.
+//!
+//! Logging is on by default and controlled by `voice.transcriptLog`. Dates
+//! are UTC, like the rest of the app's timestamps.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::platform;
+use super::runtime_context::{date_to_days, days_to_date};
+
+const DIR_NAME: &str = "transcripts";
+const MS_PER_DAY: u64 = 86_400_000;
+
+/// Serializes appends and purges.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+/// This run's file name, fixed by the first entry.
+static SESSION: OnceLock<String> = OnceLock::new();
+
+/// Who said it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Speaker {
+    User,
+    Assistant,
+    /// System audio recorded next to the mic (`captureSource: "both"`).
+    System,
+}
+
+/// One line of a transcript file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptEntry {
+    /// Unix time in milliseconds.
+    pub timestamp: u64,
+    pub speaker: Speaker,
+    pub text: String,
+    /// Language whisper detected (user entries only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Session the entry belongs to. Not stored in the line; filled in from
+    /// the file name when reading.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub session: String,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// `{data_dir}/transcripts`.
+pub fn transcripts_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(DIR_NAME)
+}
+
+/// `2026-03-14_090530`, from a Unix time in milliseconds.
+fn session_name(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (year, month, day) = days_to_date((secs / 86400) as i64);
+    let time = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02}_{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        (time % 3600) / 60,
+        time % 60
+    )
+}
+
+/// Append an entry to this session's file. Errors are logged, not returned:
+/// a full disk shouldn't interrupt the conversation.
+pub fn append(speaker: Speaker, text: &str, language: Option<&str>) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    let entry = TranscriptEntry {
+        timestamp: now_millis(),
+        speaker,
+        text: text.to_string(),
+        language: language.map(String::from),
+        session: String::new(),
+    };
+    let session = SESSION.get_or_init(|| session_name(entry.timestamp));
+    let dir = transcripts_dir(&platform::get_data_dir());
+    if let Err(e) = append_to(&dir, session, &entry) {
+        tracing::warn!("Failed to write transcript: {}", e);
+    }
+}
+
+fn append_to(dir: &Path, session: &str, entry: &TranscriptEntry) -> Result<(), String> {
+    let _guard = WRITE_LOCK.lock();
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let mut line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(format!("{}.jsonl", session)))
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| format!("Failed to append transcript: {}", e))
+}
+
+/// Transcript files in `dir`, with their session names.
+fn session_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(String, PathBuf)> = read_dir
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|p| Some((p.file_stem()?.to_str()?.to_string(), p)))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Entries of one file. Lines that don't parse (a write cut short) are
+/// skipped.
+fn read_session(session: &str, path: &Path) -> Vec<TranscriptEntry> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<TranscriptEntry>(line).ok())
+        .map(|mut entry| {
+            entry.session = session.to_string();
+            entry
+        })
+        .collect()
+}
+
+/// Millisecond bounds `[from, to)` for an inclusive range of `YYYY-MM-DD`
+/// dates. A missing end is open.
+pub fn date_range(from: Option<&str>, to: Option<&str>) -> Result<(u64, u64), String> {
+    let day = |date: &str| {
+        parse_date(date).ok_or_else(|| format!("Invalid date '{}', expected YYYY-MM-DD", date))
+    };
+    let start = match from {
+        Some(d) => day(d)? * MS_PER_DAY,
+        None => 0,
+    };
+    let end = match to {
+        Some(d) => (day(d)? + 1) * MS_PER_DAY,
+        None => u64::MAX,
+    };
+    Ok((start, end))
+}
+
+/// Days since the Unix epoch for a `YYYY-MM-DD` date on or after it.
+fn parse_date(date: &str) -> Option<u64> {
+    let mut parts = date.trim().splitn(3, '-').map(|p| p.parse::<u32>().ok());
+    let (y, m, d) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    let days = date_to_days(y as i64, m, d);
+    (days >= 0 && days_to_date(days) == (y as i64, m, d)).then_some(days as u64)
+}
+
+/// Entries with `from <= timestamp < to`, oldest first.
+pub fn query(data_dir: &Path, from: u64, to: u64) -> Vec<TranscriptEntry> {
+    let mut entries: Vec<TranscriptEntry> = session_files(&transcripts_dir(data_dir))
+        .iter()
+        .flat_map(|(session, path)| read_session(session, path))
+        .filter(|e| e.timestamp >= from && e.timestamp < to)
+        .collect();
+    entries.sort_by_key(|e| e.timestamp);
+    entries
+}
+
+/// `HH:MM:SS` (UTC) of a Unix time in milliseconds.
+fn format_time(ms: u64) -> String {
+    let time = (ms / 1000) % 86400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        time / 3600,
+        (time % 3600) / 60,
+        time % 60
+    )
+}
+
+/// Render entries as Markdown, one section per session.
+pub fn to_markdown(entries: &[TranscriptEntry]) -> String {
+    let mut out = String::from("# Voice Mirror Transcript\n");
+    if entries.is_empty() {
+        out.push_str("\nNo conversation in this range.\n");
+        return out;
+    }
+    let mut session = None;
+    for entry in entries {
+        if session != Some(entry.session.as_str()) {
+            session = Some(entry.session.as_str());
+            let (year, month, day) = days_to_date((entry.timestamp / MS_PER_DAY) as i64);
+            out.push_str(&format!(
+                "\n## {:04}-{:02}-{:02} {} UTC\n",
+                year,
+                month,
+                day,
+                format_time(entry.timestamp)
+            ));
+        }
+        let who = match entry.speaker {
+            Speaker::User => "You",
+            Speaker::Assistant => "Assistant",
+            Speaker::System => "System audio",
+        };
+        out.push_str(&format!(
+            "\n**{}** ({}): {}\n",
+            who,
+            format_time(entry.timestamp),
+            entry.text
+        ));
+    }
+    out
+}
+
+/// Delete entries older than `before` (Unix ms), or every entry for
+/// `None`. Files left empty are removed. Returns how many entries were
+/// deleted.
+pub fn purge(data_dir: &Path, before: Option<u64>) -> Result<usize, String> {
+    let _guard = WRITE_LOCK.lock();
+    let mut removed = 0;
+    for (session, path) in session_files(&transcripts_dir(data_dir)) {
+        let entries = read_session(&session, &path);
+        let kept: Vec<&TranscriptEntry> = match before {
+            Some(before) => entries.iter().filter(|e| e.timestamp >= before).collect(),
+            None => Vec::new(),
+        };
+        removed += entries.len() - kept.len();
+        if kept.is_empty() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+        } else if kept.len() < entries.len() {
+            let mut content = String::new();
+            for entry in kept {
+                let line = TranscriptEntry {
+                    session: String::new(),
+                    ..entry.clone()
+                };
+                content.push_str(&serde_json::to_string(&line).map_err(|e| e.to_string())?);
+                content.push('\n');
+            }
+            fs::write(&path, content)
+                .map_err(|e| format!("Failed to rewrite {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: u64, speaker: Speaker, text: &str) -> TranscriptEntry {
+        TranscriptEntry {
+            timestamp,
+            speaker,
+            text: text.into(),
+            language: None,
+            session: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_append_query_purge() {
+        let data_dir = std::env::temp_dir().join(format!("vm-transcripts-{}", now_millis()));
+        let dir = transcripts_dir(&data_dir);
+        // 2026-03-14 09:05:30 and the next day
+        let day1 = 1_773_479_130_000;
+        let day2 = day1 + MS_PER_DAY;
+        append_to(&dir, "a", &entry(day1, Speaker::User, "what time is it")).unwrap();
+        append_to(&dir, "a", &entry(day1 + 2000, Speaker::Assistant, "nine")).unwrap();
+        append_to(&dir, "b", &entry(day2, Speaker::User, "hello")).unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let all = query(&data_dir, 0, u64::MAX);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].session, "a");
+        assert_eq!(all[1].speaker, Speaker::Assistant);
+
+        let (from, to) = date_range(Some("2026-03-14"), Some("2026-03-14")).unwrap();
+        assert_eq!(query(&data_dir, from, to).len(), 2);
+        let (from, to) = date_range(Some("2026-03-15"), None).unwrap();
+        assert_eq!(query(&data_dir, from, to)[0].text, "hello");
+
+        assert_eq!(purge(&data_dir, Some(day1 + 1000)).unwrap(), 1);
+        let left = query(&data_dir, 0, u64::MAX);
+        assert_eq!(left.len(), 2);
+        assert_eq!(left[0].text, "nine");
+        assert_eq!(purge(&data_dir, None).unwrap(), 2);
+        assert!(session_files(&dir).is_empty());
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn test_date_range_validation() {
+        assert_eq!(date_range(None, None).unwrap(), (0, u64::MAX));
+        assert_eq!(
+            date_range(Some("1970-01-02"), Some("1970-01-02")).unwrap(),
+            (MS_PER_DAY, 2 * MS_PER_DAY)
+        );
+        assert!(date_range(Some("2026-02-30"), None).is_err());
+        assert!(date_range(None, Some("yesterday")).is_err());
+    }
+
+    #[test]
+    fn test_markdown() {
+        let mut first = entry(1_773_479_130_000, Speaker::User, "what time is it");
+        first.session = "a".into();
+        let mut reply = entry(1_773_479_132_000, Speaker::Assistant, "It's nine.");
+        reply.session = "a".into();
+        assert_eq!(
+            to_markdown(&[first, reply]),
+            "# Voice Mirror Transcript\n\
+             \n## 2026-03-14 09:05:30 UTC\n\
+             \n**You** (09:05:30): what time is it\n\
+             \n**Assistant** (09:05:32): It's nine.\n"
+        );
+        assert_eq!(session_name(1_773_479_130_000), "2026-03-14_090530");
+    }
+}
@@ -0,0 +1,756 @@
+//! Archive of recent utterance audio ("what did I actually say?").
+//!
+//! The recording behind each of the last few transcriptions is kept in
+//! `{data_dir}/turn_audio/` as a 16 kHz mono clip plus a JSON sidecar with
+//! the transcription. Both are named after the transcription ID sent in the
+//! `Transcription` voice event, so the frontend can replay a turn next to
+//! its text. When a cloud STT key is configured a clip can be
+//! transcribed again in the cloud to check a suspected mis-transcription;
+//! the result is stored next to the original text.
+//!
+//! `voice.turnArchiveSize` sets how many clips are kept (0 turns the archive
+//! off). The oldest clips are deleted as new ones arrive.
+//!
+//! `voice.turnArchiveFormat` picks the codec: `wav` (16-bit PCM), `flac`
+//! (lossless, about half the size) or `opus` (Ogg Opus at 24 kbit/s, about
+//! a twentieth). FLAC and Opus clips also carry the transcript, language,
+//! transcription time and duration as Vorbis comments, so a clip copied out
+//! of the archive still says what it is; the sidecar stays authoritative.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+const DIR_NAME: &str = "turn_audio";
+/// Sample rate of pipeline recordings.
+const SAMPLE_RATE: u32 = 16_000;
+/// Opus bitrate; plenty for 16 kHz speech.
+const OPUS_BITRATE: i32 = 24_000;
+/// Opus frames per Ogg page (20 ms frames, so one page per second).
+const OPUS_FRAMES_PER_PAGE: usize = 50;
+/// Opus granule positions and pre-skip always count 48 kHz samples.
+const OPUS_GRANULE_RATE: u32 = 48_000;
+/// The archive writes a single logical stream per file.
+const OGG_SERIAL: u32 = 1;
+/// Vendor string of the embedded Vorbis comments.
+const TAG_VENDOR: &str = "Voice Mirror";
+const DEFAULT_CLOUD_ENDPOINT: &str = "https://api.openai.com/v1";
+const DEFAULT_CLOUD_MODEL: &str = "whisper-1";
+const CLOUD_TIMEOUT: Duration = Duration::from_secs(60);
+/// Multipart boundary for the cloud upload. A clip containing this exact
+/// byte run is too unlikely to bother generating one per request.
+const BOUNDARY: &str = "voice-mirror-turn-audio-7f3a9c";
+
+/// Serializes writes and pruning.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+static LAST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Codec of an archived clip (`voice.turnArchiveFormat`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipFormat {
+    /// 16-bit PCM WAV.
+    #[default]
+    Wav,
+    /// Lossless FLAC.
+    Flac,
+    /// Opus in an Ogg container. Lossy.
+    Opus,
+}
+
+impl ClipFormat {
+    const ALL: [ClipFormat; 3] = [ClipFormat::Wav, ClipFormat::Flac, ClipFormat::Opus];
+
+    /// Parse "wav" / "flac" / "opus". Unknown values mean `Wav`.
+    pub fn from_config(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "flac" => Self::Flac,
+            "opus" => Self::Opus,
+            _ => Self::Wav,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+            Self::Opus => "opus",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            Self::Flac => "audio/flac",
+            Self::Opus => "audio/ogg",
+        }
+    }
+
+    /// File name for uploads (cloud STT picks the decoder by extension).
+    fn file_name(self) -> String {
+        format!("turn.{}", self.extension())
+    }
+}
+
+/// One archived turn (the JSON sidecar).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedTurn {
+    /// Transcription ID: the Unix time in milliseconds the transcription
+    /// finished, made unique within the run.
+    pub id: u64,
+    pub duration_ms: u64,
+    pub text: String,
+    /// Codec of the clip. Clips archived before the format was selectable
+    /// are WAV.
+    #[serde(default)]
+    pub format: ClipFormat,
+    /// Language whisper detected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// What cloud STT heard, once the clip has been re-checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corrected: Option<String>,
+}
+
+/// A fresh transcription ID. Never repeats within a run, even for two
+/// transcriptions in the same millisecond.
+pub fn next_id() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let prev = LAST_ID
+        .fetch_update(Ordering::AcqRel, Ordering::Acquire, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or_default();
+    now.max(prev + 1)
+}
+
+/// `{data_dir}/turn_audio`.
+pub fn archive_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(DIR_NAME)
+}
+
+fn clip_path(dir: &Path, id: u64, format: ClipFormat) -> PathBuf {
+    dir.join(format!("{}.{}", id, format.extension()))
+}
+
+fn meta_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+/// 16-bit PCM mono WAV of `samples` (-1.0..1.0).
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut out = Vec::with_capacity(44 + samples.len() * 2);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&1u16.to_le_bytes()); // mono
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        out.extend_from_slice(&to_pcm16(*s).to_le_bytes());
+    }
+    out
+}
+
+fn to_pcm16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+/// Samples of a WAV written by [`encode_wav`] (16-bit PCM mono, 44-byte
+/// header).
+pub fn decode_wav(wav: &[u8]) -> Vec<f32> {
+    wav.get(44..)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+        .collect()
+}
+
+/// Samples of an archived clip.
+pub fn decode_clip(bytes: &[u8], format: ClipFormat) -> Result<Vec<f32>, String> {
+    match format {
+        ClipFormat::Wav => Ok(decode_wav(bytes)),
+        ClipFormat::Flac => decode_flac(bytes),
+        ClipFormat::Opus => decode_opus(bytes, SAMPLE_RATE),
+    }
+}
+
+fn encode_clip(turn: &ArchivedTurn, audio: &[f32]) -> Result<Vec<u8>, String> {
+    match turn.format {
+        ClipFormat::Wav => Ok(encode_wav(audio, SAMPLE_RATE)),
+        ClipFormat::Flac => encode_flac(audio, SAMPLE_RATE, &clip_tags(turn)),
+        ClipFormat::Opus => encode_opus(audio, SAMPLE_RATE, &clip_tags(turn)),
+    }
+}
+
+/// Vorbis comments describing a turn.
+fn clip_tags(turn: &ArchivedTurn) -> Vec<String> {
+    let mut tags = vec![
+        format!("TITLE={}", turn.text),
+        // Unix time in milliseconds, as in the sidecar.
+        format!("TRANSCRIPTION_ID={}", turn.id),
+        format!("DURATION_MS={}", turn.duration_ms),
+    ];
+    if let Some(language) = &turn.language {
+        tags.push(format!("LANGUAGE={}", language));
+    }
+    tags
+}
+
+/// A Vorbis comment structure (shared by FLAC's VORBIS_COMMENT block and
+/// Opus's OpusTags packet).
+fn vorbis_comment(tags: &[String]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&(TAG_VENDOR.len() as u32).to_le_bytes());
+    out.extend_from_slice(TAG_VENDOR.as_bytes());
+    out.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+    for tag in tags {
+        out.extend_from_slice(&(tag.len() as u32).to_le_bytes());
+        out.extend_from_slice(tag.as_bytes());
+    }
+    out
+}
+
+/// 16-bit mono FLAC of `samples` with `tags` as a VORBIS_COMMENT block.
+fn encode_flac(samples: &[f32], sample_rate: u32, tags: &[String]) -> Result<Vec<u8>, String> {
+    use flacenc::component::BitRepr;
+    use flacenc::error::Verify;
+
+    let pcm: Vec<i32> = samples.iter().map(|s| to_pcm16(*s) as i32).collect();
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| format!("Invalid FLAC encoder config: {:?}", e))?;
+    let source = flacenc::source::MemSource::from_samples(&pcm, 1, 16, sample_rate as usize);
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| format!("FLAC encoding failed: {}", e))?;
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| format!("FLAC encoding failed: {:?}", e))?;
+    insert_flac_comment(sink.as_slice(), &vorbis_comment(tags))
+}
+
+/// Append a VORBIS_COMMENT block to a FLAC stream's metadata.
+fn insert_flac_comment(flac: &[u8], comment: &[u8]) -> Result<Vec<u8>, String> {
+    const LAST_BLOCK: u8 = 0x80;
+    const VORBIS_COMMENT: u8 = 4;
+
+    if !flac.starts_with(b"fLaC") {
+        return Err("Not a FLAC stream".into());
+    }
+    // Walk the metadata blocks to the one flagged last.
+    let mut pos = 4;
+    let end = loop {
+        let header = flac.get(pos..pos + 4).ok_or("Truncated FLAC metadata")?;
+        let end = pos + 4 + u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        if header[0] & LAST_BLOCK != 0 {
+            break end;
+        }
+        pos = end;
+    };
+    if end > flac.len() {
+        return Err("Truncated FLAC metadata".into());
+    }
+
+    let mut out = Vec::with_capacity(flac.len() + 4 + comment.len());
+    out.extend_from_slice(&flac[..end]);
+    out[pos] &= !LAST_BLOCK;
+    out.push(LAST_BLOCK | VORBIS_COMMENT);
+    out.extend_from_slice(&(comment.len() as u32).to_be_bytes()[1..]);
+    out.extend_from_slice(comment);
+    out.extend_from_slice(&flac[end..]);
+    Ok(out)
+}
+
+/// Samples of a mono FLAC clip.
+fn decode_flac(flac: &[u8]) -> Result<Vec<f32>, String> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    // MediaSourceStream needs an owned ('static) source.
+    let cursor = std::io::Cursor::new(flac.to_vec());
+    let mss = MediaSourceStream::new(Box::new(cursor), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension("flac");
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("FLAC probe failed: {}", e))?;
+    let mut format = probed.format;
+    let track = format.default_track().ok_or("No audio track in FLAC")?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("FLAC decoder init failed: {}", e))?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(p) => p,
+            Err(symphonia::core::errors::Error::IoError(ref e))
+                if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break;
+            }
+            Err(e) => return Err(format!("FLAC decode error: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = decoder
+            .decode(&packet)
+            .map_err(|e| format!("FLAC decode error: {}", e))?;
+        let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+        buf.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buf.samples());
+    }
+    Ok(samples)
+}
+
+/// Ogg Opus (RFC 7845) of mono `samples` with `tags` in the OpusTags
+/// header.
+fn encode_opus(samples: &[f32], sample_rate: u32, tags: &[String]) -> Result<Vec<u8>, String> {
+    use ogg::{PacketWriteEndInfo, PacketWriter};
+
+    let opus_err = |e: opus::Error| format!("Opus encoding failed: {}", e);
+    let mut encoder =
+        opus::Encoder::new(sample_rate, opus::Channels::Mono, opus::Application::Voip)
+            .map_err(opus_err)?;
+    encoder
+        .set_bitrate(opus::Bitrate::Bits(OPUS_BITRATE))
+        .map_err(opus_err)?;
+    let lookahead = encoder.get_lookahead().map_err(opus_err)?.max(0) as usize;
+    let scale = (OPUS_GRANULE_RATE / sample_rate) as u64;
+    let pre_skip = lookahead as u64 * scale;
+    // Granule position of the last real sample; the decoder trims the
+    // padding after it.
+    let end_granule = pre_skip + samples.len() as u64 * scale;
+
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(1); // channels
+    head.extend_from_slice(&(pre_skip as u16).to_le_bytes());
+    head.extend_from_slice(&sample_rate.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family
+    let mut opus_tags = b"OpusTags".to_vec();
+    opus_tags.extend_from_slice(&vorbis_comment(tags));
+
+    let io_err = |e: std::io::Error| format!("Ogg write failed: {}", e);
+    let mut writer = PacketWriter::new(Vec::new());
+    writer
+        .write_packet(head.into(), OGG_SERIAL, PacketWriteEndInfo::EndPage, 0)
+        .map_err(io_err)?;
+    writer
+        .write_packet(opus_tags.into(), OGG_SERIAL, PacketWriteEndInfo::EndPage, 0)
+        .map_err(io_err)?;
+
+    // Pad with the encoder's lookahead so the last samples get flushed out.
+    let frame = sample_rate as usize / 50;
+    let mut padded = samples.to_vec();
+    padded.resize(samples.len() + lookahead, 0.0);
+    let frames = padded.len().div_ceil(frame);
+    padded.resize(frames * frame, 0.0);
+    let mut packet = [0u8; 1500];
+    for (i, pcm) in padded.chunks(frame).enumerate() {
+        let len = encoder.encode_float(pcm, &mut packet).map_err(opus_err)?;
+        let granule = (pre_skip + ((i + 1) * frame) as u64 * scale).min(end_granule);
+        let end = if i + 1 == frames {
+            PacketWriteEndInfo::EndStream
+        } else if (i + 1) % OPUS_FRAMES_PER_PAGE == 0 {
+            PacketWriteEndInfo::EndPage
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        writer
+            .write_packet(packet[..len].to_vec().into(), OGG_SERIAL, end, granule)
+            .map_err(io_err)?;
+    }
+    Ok(writer.into_inner())
+}
+
+/// Samples of a mono Ogg Opus clip, decoded at `sample_rate`.
+fn decode_opus(bytes: &[u8], sample_rate: u32) -> Result<Vec<f32>, String> {
+    let ogg_err = |e: ogg::OggReadError| format!("Ogg read failed: {}", e);
+    let opus_err = |e: opus::Error| format!("Opus decoding failed: {}", e);
+    let mut reader = ogg::PacketReader::new(std::io::Cursor::new(bytes));
+    let head = reader
+        .read_packet()
+        .map_err(ogg_err)?
+        .ok_or("Empty Ogg stream")?;
+    if head.data.len() < 19 || !head.data.starts_with(b"OpusHead") {
+        return Err("Not an Ogg Opus stream".into());
+    }
+    let pre_skip = u16::from_le_bytes([head.data[10], head.data[11]]) as u64;
+    // OpusTags
+    reader.read_packet().map_err(ogg_err)?;
+
+    let mut decoder = opus::Decoder::new(sample_rate, opus::Channels::Mono).map_err(opus_err)?;
+    let scale = (OPUS_GRANULE_RATE / sample_rate) as u64;
+    // Longest Opus frame: 120 ms.
+    let mut frame = vec![0.0f32; sample_rate as usize * 120 / 1000];
+    let mut samples = Vec::new();
+    let mut end_granule = None;
+    while let Some(packet) = reader.read_packet().map_err(ogg_err)? {
+        let n = decoder
+            .decode_float(&packet.data, &mut frame, false)
+            .map_err(opus_err)?;
+        samples.extend_from_slice(&frame[..n]);
+        if packet.last_in_stream() {
+            end_granule = Some(packet.absgp_page());
+        }
+    }
+    samples.drain(..((pre_skip / scale) as usize).min(samples.len()));
+    if let Some(granule) = end_granule {
+        samples.truncate((granule.saturating_sub(pre_skip) / scale) as usize);
+    }
+    Ok(samples)
+}
+
+/// Archive a turn's recording (16 kHz) in `turn.format` and drop clips
+/// beyond the newest `keep`. Errors are logged, not returned: the archive
+/// is a convenience and shouldn't interrupt the conversation.
+pub fn store(data_dir: &Path, keep: usize, turn: ArchivedTurn, audio: &[f32]) {
+    if let Err(e) = store_in(&archive_dir(data_dir), keep, &turn, audio) {
+        tracing::warn!(id = turn.id, "Failed to archive turn audio: {}", e);
+    }
+}
+
+fn store_in(dir: &Path, keep: usize, turn: &ArchivedTurn, audio: &[f32]) -> Result<(), String> {
+    let clip = encode_clip(turn, audio)?;
+    let _guard = WRITE_LOCK.lock();
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    fs::write(clip_path(dir, turn.id, turn.format), clip)
+        .map_err(|e| format!("Failed to write clip: {}", e))?;
+    write_meta(dir, turn)?;
+    prune(dir, keep);
+    Ok(())
+}
+
+fn write_meta(dir: &Path, turn: &ArchivedTurn) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(turn).map_err(|e| e.to_string())?;
+    fs::write(meta_path(dir, turn.id), json).map_err(|e| format!("Failed to write turn: {}", e))
+}
+
+/// IDs of the archived clips, oldest first.
+fn clip_ids(dir: &Path) -> Vec<u64> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<u64> = read_dir
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ClipFormat::ALL.iter().any(|f| ext == f.extension()))
+        })
+        .filter_map(|p| p.file_stem()?.to_str()?.parse().ok())
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// Delete all but the newest `keep` clips.
+fn prune(dir: &Path, keep: usize) {
+    let ids = clip_ids(dir);
+    for id in &ids[..ids.len().saturating_sub(keep)] {
+        for format in ClipFormat::ALL {
+            let _ = fs::remove_file(clip_path(dir, *id, format));
+        }
+        let _ = fs::remove_file(meta_path(dir, *id));
+    }
+}
+
+fn read_meta(dir: &Path, id: u64) -> Option<ArchivedTurn> {
+    let json = fs::read_to_string(meta_path(dir, id)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Archived turns, newest first. Clips whose sidecar is missing or
+/// unreadable are skipped.
+pub fn list(data_dir: &Path) -> Vec<ArchivedTurn> {
+    let dir = archive_dir(data_dir);
+    clip_ids(&dir)
+        .into_iter()
+        .rev()
+        .filter_map(|id| read_meta(&dir, id))
+        .collect()
+}
+
+/// One archived turn and its clip bytes (encoded as `turn.format`).
+pub fn get(data_dir: &Path, id: u64) -> Result<(ArchivedTurn, Vec<u8>), String> {
+    let dir = archive_dir(data_dir);
+    let turn = read_meta(&dir, id).ok_or_else(|| format!("No archived turn {}", id))?;
+    let clip = fs::read(clip_path(&dir, id, turn.format))
+        .map_err(|e| format!("Failed to read clip {}: {}", id, e))?;
+    Ok((turn, clip))
+}
+
+/// Store the cloud transcription of a turn.
+pub fn set_corrected(data_dir: &Path, id: u64, text: &str) -> Result<ArchivedTurn, String> {
+    let dir = archive_dir(data_dir);
+    let _guard = WRITE_LOCK.lock();
+    let mut turn = read_meta(&dir, id).ok_or_else(|| format!("No archived turn {}", id))?;
+    turn.corrected = Some(text.trim().to_string());
+    write_meta(&dir, &turn)?;
+    Ok(turn)
+}
+
+/// Delete every clip. Returns how many were deleted.
+pub fn purge(data_dir: &Path) -> usize {
+    let dir = archive_dir(data_dir);
+    let _guard = WRITE_LOCK.lock();
+    let ids = clip_ids(&dir);
+    prune(&dir, 0);
+    ids.len()
+}
+
+/// Cloud STT settings for re-checking a clip (`voice.sttApiKey`,
+/// `voice.sttEndpoint`, `voice.sttModelName`).
+pub struct CloudStt {
+    pub api_key: String,
+    /// OpenAI-compatible API base; defaults to OpenAI's.
+    pub endpoint: Option<String>,
+    pub model: Option<String>,
+    /// ISO 639-1 code, or `None` to let the service detect it.
+    pub language: Option<String>,
+}
+
+/// Transcribe an archived clip with an OpenAI-compatible
+/// `/audio/transcriptions` endpoint.
+pub async fn cloud_transcribe(
+    cloud: &CloudStt,
+    clip: Vec<u8>,
+    format: ClipFormat,
+) -> Result<String, AppError> {
+    let base = cloud
+        .endpoint
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .unwrap_or(DEFAULT_CLOUD_ENDPOINT)
+        .trim_end_matches('/');
+    let model = cloud
+        .model
+        .as_deref()
+        .filter(|m| !m.is_empty())
+        .unwrap_or(DEFAULT_CLOUD_MODEL);
+
+    let mut fields = vec![("model", model), ("response_format", "json")];
+    if let Some(language) = cloud.language.as_deref() {
+        fields.push(("language", language));
+    }
+    let body = multipart_body(&fields, &clip, format);
+
+    let client = reqwest::Client::builder()
+        .timeout(CLOUD_TIMEOUT)
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+    let resp = client
+        .post(format!("{}/audio/transcriptions", base))
+        .bearer_auth(&cloud.api_key)
+        .header(
+            "Content-Type",
+            format!("multipart/form-data; boundary={}", BOUNDARY),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Cloud STT request failed: {}", e)))?;
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        let err = format!("Cloud STT returned HTTP {}: {}", status, body.trim());
+        return Err(match status.as_u16() {
+            401 | 403 => AppError::Config(err),
+            _ => AppError::Stt(err),
+        });
+    }
+    let body: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| AppError::Stt(format!("Invalid cloud STT response: {}", e)))?;
+    body.get("text")
+        .and_then(|t| t.as_str())
+        .map(|t| t.trim().to_string())
+        .ok_or_else(|| AppError::Stt("Cloud STT response has no text".into()))
+}
+
+/// A `multipart/form-data` body with text `fields` and the clip as `file`.
+fn multipart_body(fields: &[(&str, &str)], clip: &[u8], format: ClipFormat) -> Vec<u8> {
+    let mut body = Vec::with_capacity(clip.len() + 512);
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                BOUNDARY, name, value
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n\
+             Content-Type: {}\r\n\r\n",
+            BOUNDARY,
+            format.file_name(),
+            format.mime_type()
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(clip);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(id: u64, text: &str) -> ArchivedTurn {
+        ArchivedTurn {
+            id,
+            duration_ms: 500,
+            text: text.into(),
+            format: ClipFormat::Wav,
+            language: None,
+            corrected: None,
+        }
+    }
+
+    fn tone(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| 0.5 * (i as f32 * 440.0 * std::f32::consts::TAU / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn test_store_list_prune() {
+        let data_dir = std::env::temp_dir().join(format!("vm-turn-audio-{}", next_id()));
+        let dir = archive_dir(&data_dir);
+        let audio = vec![0.25f32; 8000];
+        for (id, text) in [(1, "one"), (2, "two"), (3, "three")] {
+            store_in(&dir, 2, &turn(id, text), &audio).unwrap();
+        }
+        let turns = list(&data_dir);
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].text, "three");
+        assert!(get(&data_dir, 1).is_err());
+
+        let (_, wav) = get(&data_dir, 2).unwrap();
+        assert_eq!(wav.len(), 44 + audio.len() * 2);
+        assert_eq!(&wav[..4], b"RIFF");
+        let decoded = decode_wav(&wav);
+        assert_eq!(decoded.len(), audio.len());
+        assert!((decoded[0] - 0.25).abs() < 1e-3);
+
+        let corrected = set_corrected(&data_dir, 2, " two! ").unwrap();
+        assert_eq!(corrected.corrected.as_deref(), Some("two!"));
+        assert_eq!(list(&data_dir)[1].corrected.as_deref(), Some("two!"));
+
+        assert_eq!(purge(&data_dir), 2);
+        assert!(list(&data_dir).is_empty());
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn test_flac_clip_lossless_with_tags() {
+        let data_dir = std::env::temp_dir().join(format!("vm-turn-audio-{}", next_id()));
+        let dir = archive_dir(&data_dir);
+        let audio = tone(12_345);
+        let mut flac_turn = turn(1, "hello there");
+        flac_turn.format = ClipFormat::Flac;
+        flac_turn.language = Some("en".into());
+        store_in(&dir, 2, &flac_turn, &audio).unwrap();
+        store_in(&dir, 2, &turn(2, "wav"), &audio).unwrap();
+
+        let (stored, flac) = get(&data_dir, 1).unwrap();
+        assert_eq!(stored.format, ClipFormat::Flac);
+        assert!(flac.starts_with(b"fLaC"));
+        assert!(flac.len() < audio.len() * 2);
+        assert!(contains(&flac, b"TITLE=hello there"));
+        assert!(contains(&flac, b"TRANSCRIPTION_ID=1"));
+        assert!(contains(&flac, b"LANGUAGE=en"));
+        let decoded = decode_clip(&flac, stored.format).unwrap();
+        assert_eq!(decoded.len(), audio.len());
+        for (d, a) in decoded.iter().zip(&audio) {
+            assert!((d - to_pcm16(*a) as f32 / 32768.0).abs() < 1e-4);
+        }
+
+        // Pruning removes clips of any format.
+        store_in(&dir, 1, &turn(3, "three"), &audio).unwrap();
+        assert!(get(&data_dir, 1).is_err());
+        assert!(!clip_path(&dir, 1, ClipFormat::Flac).exists());
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn test_opus_clip_round_trip() {
+        let audio = tone(SAMPLE_RATE as usize);
+        let mut opus_turn = turn(7, "speak up");
+        opus_turn.format = ClipFormat::Opus;
+        let clip = encode_clip(&opus_turn, &audio).unwrap();
+        assert!(clip.starts_with(b"OggS"));
+        assert!(contains(&clip, b"OpusHead"));
+        assert!(contains(&clip, b"TITLE=speak up"));
+        assert!(clip.len() < audio.len() / 4);
+
+        let decoded = decode_clip(&clip, ClipFormat::Opus).unwrap();
+        assert_eq!(decoded.len(), audio.len());
+        let rms = |s: &[f32]| (s.iter().map(|x| x * x).sum::<f32>() / s.len() as f32).sqrt();
+        let (original, round_trip) = (rms(&audio), rms(&decoded));
+        assert!((round_trip - original).abs() < original * 0.2);
+    }
+
+    #[test]
+    fn test_insert_flac_comment() {
+        let mut flac = b"fLaC".to_vec();
+        flac.extend_from_slice(&[0x80, 0, 0, 34]);
+        flac.extend_from_slice(&[0; 34]);
+        flac.extend_from_slice(b"frames");
+        let out = insert_flac_comment(&flac, b"comment").unwrap();
+        assert_eq!(out[4], 0x00);
+        assert_eq!(&out[42..46], &[0x84, 0, 0, 7]);
+        assert_eq!(&out[46..53], b"comment");
+        assert!(out.ends_with(b"frames"));
+        assert!(insert_flac_comment(b"RIFF", b"").is_err());
+    }
+
+    #[test]
+    fn test_next_id_unique() {
+        let first = next_id();
+        let second = next_id();
+        assert!(second > first);
+    }
+}
@@ -0,0 +1,289 @@
+//! Voice activity statistics.
+//!
+//! Per-day totals of how much the user talked to Voice Mirror: talk time,
+//! utterance count (recordings that produced a transcription), and how often
+//! they cut a reply short. The voice pipeline records into
+//! `{data_dir}/voice_stats.json`; the `get_voice_stats` command and the MCP
+//! `voice_talk_time` tool (a separate process) read the same file.
+//!
+//! Talk time is the recorded length of each transcribed utterance, so it
+//! includes the trailing silence that ended the recording. Days are UTC
+//! dates, like the rest of the app's timestamps.
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::platform;
+use super::runtime_context::{date_to_days, days_to_date};
+
+pub const FILE_NAME: &str = "voice_stats.json";
+/// Days kept in the file.
+const KEEP_DAYS: usize = 90;
+
+/// Serializes the app's read-modify-write of the stats file.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Totals for one day.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayStats {
+    /// `YYYY-MM-DD` (UTC).
+    pub date: String,
+    pub talk_ms: u64,
+    pub utterances: u32,
+    /// Replies the user cut short (barge-in or stop).
+    pub interruptions: u32,
+}
+
+impl DayStats {
+    fn empty(date: &str) -> Self {
+        Self {
+            date: date.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn average_utterance_ms(&self) -> u64 {
+        match self.utterances {
+            0 => 0,
+            n => self.talk_ms / n as u64,
+        }
+    }
+
+    fn add(&mut self, other: &DayStats) {
+        self.talk_ms += other.talk_ms;
+        self.utterances += other.utterances;
+        self.interruptions += other.interruptions;
+    }
+}
+
+/// All recorded days, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VoiceStats {
+    days: Vec<DayStats>,
+}
+
+impl VoiceStats {
+    fn day_mut(&mut self, date: &str) -> &mut DayStats {
+        if self.days.last().map(|d| d.date.as_str()) != Some(date) {
+            self.days.push(DayStats::empty(date));
+            if self.days.len() > KEEP_DAYS {
+                self.days.drain(..self.days.len() - KEEP_DAYS);
+            }
+        }
+        let last = self.days.len() - 1;
+        &mut self.days[last]
+    }
+
+    pub fn record_utterance(&mut self, date: &str, length: Duration) {
+        let day = self.day_mut(date);
+        day.talk_ms += length.as_millis() as u64;
+        day.utterances += 1;
+    }
+
+    pub fn record_interruption(&mut self, date: &str) {
+        self.day_mut(date).interruptions += 1;
+    }
+
+    /// Totals for `date` (zeroes if nothing was recorded).
+    pub fn day(&self, date: &str) -> DayStats {
+        self.days
+            .iter()
+            .rev()
+            .find(|d| d.date == date)
+            .cloned()
+            .unwrap_or_else(|| DayStats::empty(date))
+    }
+
+    /// Recorded days among the `count` days ending with `today`, oldest
+    /// first. Days without activity are left out.
+    pub fn recent(&self, today: &str, count: u32) -> Vec<DayStats> {
+        let first = shift_date(today, -(count.max(1) as i64 - 1));
+        self.days
+            .iter()
+            .filter(|d| d.date.as_str() >= first.as_str() && d.date.as_str() <= today)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Sum of `days`, dated `date`.
+pub fn total(days: &[DayStats], date: &str) -> DayStats {
+    let mut sum = DayStats::empty(date);
+    for day in days {
+        sum.add(day);
+    }
+    sum
+}
+
+/// Today's UTC date as `YYYY-MM-DD`.
+pub fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    format_day(secs as i64 / 86400)
+}
+
+fn format_day(days: i64) -> String {
+    let (year, month, day) = days_to_date(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// `date` moved by `offset` days. Unparseable dates are returned as-is.
+fn shift_date(date: &str, offset: i64) -> String {
+    match parse_day(date) {
+        Some(days) => format_day(days + offset),
+        None => date.to_string(),
+    }
+}
+
+/// Days since the Unix epoch for a `YYYY-MM-DD` date.
+fn parse_day(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-');
+    let y = parts.next()?.parse::<i64>().ok()?;
+    let m = parts.next()?.parse::<u32>().ok()?;
+    let d = parts.next()?.parse::<u32>().ok()?;
+    Some(date_to_days(y, m, d))
+}
+
+/// Read the stats file in `data_dir`. A missing or unreadable file reads as
+/// empty.
+pub fn load(data_dir: &Path) -> VoiceStats {
+    std::fs::read_to_string(data_dir.join(FILE_NAME))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn update(f: impl FnOnce(&mut VoiceStats, &str)) {
+    let _guard = WRITE_LOCK.lock();
+    let dir = platform::get_data_dir();
+    let mut stats = load(&dir);
+    f(&mut stats, &today());
+    let result = std::fs::create_dir_all(&dir).and_then(|_| {
+        let json = serde_json::to_string(&stats).map_err(std::io::Error::other)?;
+        std::fs::write(dir.join(FILE_NAME), json)
+    });
+    if let Err(e) = result {
+        tracing::warn!("Failed to save voice stats: {}", e);
+    }
+}
+
+/// A recording of `length` was transcribed.
+pub fn record_utterance(length: Duration) {
+    update(|stats, today| stats.record_utterance(today, length));
+}
+
+/// The user cut a reply short.
+pub fn record_interruption() {
+    update(|stats, today| stats.record_interruption(today));
+}
+
+/// Speakable summary of a day (or of several days summed).
+pub fn describe(stats: &DayStats, period: &str) -> String {
+    if stats.utterances == 0 {
+        return format!("You haven't talked to me {}.", period);
+    }
+    let mut out = format!(
+        "You've talked to me for {} {} across {} {}, {} on average.",
+        format_talk_time(stats.talk_ms),
+        period,
+        stats.utterances,
+        if stats.utterances == 1 {
+            "utterance"
+        } else {
+            "utterances"
+        },
+        format_talk_time(stats.average_utterance_ms()),
+    );
+    match stats.interruptions {
+        0 => {}
+        1 => out.push_str(" You interrupted me once."),
+        n => out.push_str(&format!(" You interrupted me {} times.", n)),
+    }
+    out
+}
+
+/// `1h 5m`, `4m 10s`, or `12s`.
+fn format_talk_time(ms: u64) -> String {
+    let secs = (ms + 500) / 1000;
+    match (secs / 3600, (secs % 3600) / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_query_days() {
+        let mut stats = VoiceStats::default();
+        stats.record_utterance("2026-03-13", Duration::from_secs(4));
+        stats.record_utterance("2026-03-14", Duration::from_millis(3000));
+        stats.record_utterance("2026-03-14", Duration::from_millis(5000));
+        stats.record_interruption("2026-03-14");
+
+        let day = stats.day("2026-03-14");
+        assert_eq!(day.talk_ms, 8000);
+        assert_eq!(day.utterances, 2);
+        assert_eq!(day.average_utterance_ms(), 4000);
+        assert_eq!(day.interruptions, 1);
+        assert_eq!(stats.day("2026-03-15"), DayStats::empty("2026-03-15"));
+
+        let both = total(&stats.days, "total");
+        assert_eq!((both.talk_ms, both.utterances), (12_000, 3));
+
+        for i in 0..KEEP_DAYS {
+            stats.record_interruption(&format_day(i as i64));
+        }
+        assert_eq!(stats.days.len(), KEEP_DAYS);
+        assert_eq!(stats.days[0].date, "1970-01-01");
+    }
+
+    #[test]
+    fn test_date_round_trip() {
+        for days in [0, 59, 60, 20_526, 20_527, -1] {
+            assert_eq!(parse_day(&format_day(days)), Some(days));
+        }
+        assert_eq!(shift_date("2026-03-01", -1), "2026-02-28");
+        assert_eq!(shift_date("2024-12-31", 1), "2025-01-01");
+        assert_eq!(parse_day("not a date"), None);
+    }
+
+    #[test]
+    fn test_recent_window() {
+        let today = today();
+        let mut stats = VoiceStats::default();
+        stats.record_utterance(&shift_date(&today, -8), Duration::from_secs(1));
+        stats.record_utterance(&shift_date(&today, -6), Duration::from_secs(1));
+        stats.record_utterance(&today, Duration::from_secs(1));
+        assert_eq!(stats.recent(&today, 7).len(), 2);
+        assert_eq!(stats.recent(&today, 1).len(), 1);
+        assert_eq!(stats.recent(&today, 0).len(), 1);
+    }
+
+    #[test]
+    fn test_describe() {
+        let empty = DayStats::empty("2026-03-14");
+        assert_eq!(describe(&empty, "today"), "You haven't talked to me today.");
+
+        let day = DayStats {
+            date: "2026-03-14".into(),
+            talk_ms: 754_000,
+            utterances: 18,
+            interruptions: 3,
+        };
+        assert_eq!(
+            describe(&day, "today"),
+            "You've talked to me for 12m 34s today across 18 utterances, \
+             42s on average. You interrupted me 3 times."
+        );
+    }
+}
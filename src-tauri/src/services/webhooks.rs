@@ -0,0 +1,375 @@
+//! Outbound webhooks for voice events.
+//!
+//! Each hook in the `webhooks` config list receives a JSON POST for the
+//! voice events it subscribes to (`transcription`, `speaking_start`,
+//! `error`, ...), so home-automation systems can react when the user starts
+//! talking to the assistant. The config is re-read per event, so edits apply
+//! without a restart.
+//!
+//! Body: `{ "event": "...", "data": { ... }, "timestamp": <unix ms> }`. With
+//! a `secret`, `X-VoiceMirror-Signature: sha256=<hex>` carries the
+//! HMAC-SHA256 of the exact body bytes. Network errors, 429 and 5xx are
+//! retried with backoff. Each hook has its own delivery task and queue, so
+//! it gets events in the order they happened and a slow or unreachable
+//! hook only holds up itself; once its queue is full, its new events are
+//! dropped.
+//! A transcription routed by `transcriptRoutes` is only sent when its route
+//! lists `webhook`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+use tauri::{AppHandle, Listener};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{debug, warn};
+
+use crate::config::schema::WebhookConfig;
+use crate::voice::tts::crypto::{hex_encode_lower, hmac_sha256};
+
+/// Events that fire many times a second; never sent.
+const HIGH_RATE_EVENTS: &[&str] = &["audio_level", "speaking_progress"];
+/// Events waiting to be fanned out to the hooks before new ones are dropped.
+const QUEUE_SIZE: usize = 64;
+/// Events waiting for one hook before its new ones are dropped.
+const HOOK_QUEUE_SIZE: usize = 64;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_ATTEMPTS: u32 = 3;
+
+const EVENT_HEADER: &str = "X-VoiceMirror-Event";
+const SIGNATURE_HEADER: &str = "X-VoiceMirror-Signature";
+
+/// Forward voice events to the configured webhooks for the app's lifetime.
+pub fn start(app: &AppHandle) {
+    let (tx, rx) = mpsc::channel::<(String, Value)>(QUEUE_SIZE);
+    app.listen_any("voice-event", move |event| {
+        let Ok(payload) = serde_json::from_str::<Value>(event.payload()) else {
+            return;
+        };
+        let Some(name) = payload.get("event").and_then(|e| e.as_str()) else {
+            return;
+        };
+        if HIGH_RATE_EVENTS.contains(&name) || routed_elsewhere(&payload) {
+            return;
+        }
+        let name = name.to_string();
+        if tx.try_send((name.clone(), payload)).is_err() {
+            warn!(event = %name, "Webhook queue full, dropping event");
+        }
+    });
+    tauri::async_runtime::spawn(dispatch_loop(rx));
+}
+
+async fn dispatch_loop(mut rx: mpsc::Receiver<(String, Value)>) {
+    let mut dispatcher = Dispatcher::new();
+    while let Some((name, payload)) = rx.recv().await {
+        let webhooks = crate::commands::config::get_config_snapshot().webhooks;
+        dispatcher.dispatch(webhooks, &name, payload);
+    }
+}
+
+/// One event for one hook.
+struct Delivery {
+    hook: WebhookConfig,
+    event: String,
+    body: Arc<str>,
+}
+
+/// Hands each event to the queues of the hooks that want it. Never waits
+/// on a delivery.
+struct Dispatcher {
+    client: reqwest::Client,
+    /// Each hook's queue, by URL. Its task exits once the sender is dropped
+    /// and the queue has drained.
+    queues: HashMap<String, mpsc::Sender<Delivery>>,
+}
+
+impl Dispatcher {
+    fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self {
+            client,
+            queues: HashMap::new(),
+        }
+    }
+
+    /// Queue `payload` for every hook in `webhooks` subscribed to `name`.
+    fn dispatch(&mut self, webhooks: Vec<WebhookConfig>, name: &str, payload: Value) {
+        // Hooks removed from the config finish what they have and stop
+        self.queues
+            .retain(|url, _| webhooks.iter().any(|h| h.url.trim() == url));
+        let hooks: Vec<WebhookConfig> = webhooks
+            .into_iter()
+            .filter(|h| subscribed(h, name))
+            .collect();
+        if hooks.is_empty() {
+            return;
+        }
+        let body: Arc<str> = build_body(payload, unix_millis()).into();
+        for hook in hooks {
+            let client = &self.client;
+            let queue = self
+                .queues
+                .entry(hook.url.trim().to_string())
+                .or_insert_with(|| spawn_hook_task(client.clone()));
+            let delivery = Delivery {
+                hook,
+                event: name.to_string(),
+                body: Arc::clone(&body),
+            };
+            if let Err(TrySendError::Full(d) | TrySendError::Closed(d)) = queue.try_send(delivery) {
+                warn!(
+                    event = name,
+                    target = %redact_url(&d.hook.url),
+                    "Webhook falling behind, dropping event for it"
+                );
+            }
+        }
+    }
+}
+
+/// Start the task that delivers one hook's events in order.
+fn spawn_hook_task(client: reqwest::Client) -> mpsc::Sender<Delivery> {
+    let (tx, mut rx) = mpsc::channel::<Delivery>(HOOK_QUEUE_SIZE);
+    tauri::async_runtime::spawn(async move {
+        while let Some(d) = rx.recv().await {
+            deliver(&client, &d.hook, &d.event, &d.body).await;
+        }
+    });
+    tx
+}
+
+/// POST `body` to one hook, retrying transient failures.
+async fn deliver(client: &reqwest::Client, hook: &WebhookConfig, event: &str, body: &str) {
+    let target = redact_url(&hook.url);
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = client
+            .post(hook.url.trim())
+            .header("Content-Type", "application/json")
+            .header(EVENT_HEADER, event)
+            .body(body.to_string());
+        if let Some(secret) = hook.secret.as_deref().filter(|s| !s.is_empty()) {
+            request = request.header(SIGNATURE_HEADER, sign(secret, body));
+        }
+
+        let retry_reason = match request.send().await {
+            Ok(resp) if resp.status().is_success() => {
+                debug!(event, target = %target, "Webhook delivered");
+                return;
+            }
+            Ok(resp) if is_retryable(resp.status().as_u16()) => format!("HTTP {}", resp.status()),
+            Ok(resp) => {
+                warn!(event, target = %target, "Webhook rejected: HTTP {}", resp.status());
+                return;
+            }
+            Err(e) => e.to_string(),
+        };
+
+        if attempt == MAX_ATTEMPTS {
+            warn!(
+                event,
+                target = %target,
+                "Webhook failed after {} attempts: {}",
+                attempt,
+                retry_reason
+            );
+            return;
+        }
+        let delay = retry_delay(attempt);
+        debug!(
+            event,
+            target = %target,
+            attempt,
+            "Webhook failed ({}), retrying in {:?}",
+            retry_reason,
+            delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Whether `hook` wants `event`. Names match case-insensitively and ignore
+/// `_`/`-`, so "speakingStart" and "speaking-start" both mean `speaking_start`.
+fn subscribed(hook: &WebhookConfig, event: &str) -> bool {
+    fn normalize(name: &str) -> String {
+        name.trim()
+            .chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .collect::<String>()
+            .to_ascii_lowercase()
+    }
+    if !hook.enabled || hook.url.trim().is_empty() {
+        return false;
+    }
+    let event = normalize(event);
+    hook.events.is_empty() || hook.events.iter().any(|e| normalize(e) == event)
+}
+
+/// A transcription whose `transcriptRoutes` entry doesn't list `webhook`
+/// (see `voice::routing`). Unrouted transcriptions go to every hook.
+fn routed_elsewhere(payload: &Value) -> bool {
+    payload
+        .pointer("/data/routes")
+        .and_then(Value::as_array)
+        .is_some_and(|routes| !routes.iter().any(|r| r == "webhook"))
+}
+
+/// The voice-event payload with a `timestamp` (unix ms) added.
+fn build_body(mut payload: Value, timestamp_ms: u64) -> String {
+    if let Some(obj) = payload.as_object_mut() {
+        obj.insert("timestamp".into(), timestamp_ms.into());
+    }
+    payload.to_string()
+}
+
+/// `X-VoiceMirror-Signature` value for `body`.
+fn sign(secret: &str, body: &str) -> String {
+    format!(
+        "sha256={}",
+        hex_encode_lower(&hmac_sha256(secret.as_bytes(), body.as_bytes()))
+    )
+}
+
+/// Rate limits and server errors may clear up; other statuses won't.
+fn is_retryable(status: u16) -> bool {
+    status == 408 || status == 429 || status >= 500
+}
+
+/// Backoff before attempt `attempt + 1`: 1 s, then 2 s.
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs(1 << (attempt - 1).min(4))
+}
+
+/// Scheme and host only: webhook paths and queries often embed tokens.
+fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url.trim()) {
+        Ok(u) => format!("{}://{}", u.scheme(), u.host_str().unwrap_or("?")),
+        Err(_) => "<invalid url>".into(),
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(events: &[&str]) -> WebhookConfig {
+        WebhookConfig {
+            url: "http://homeassistant.local:8123/api/webhook/abc123".into(),
+            enabled: true,
+            events: events.iter().map(|e| e.to_string()).collect(),
+            secret: None,
+        }
+    }
+
+    #[test]
+    fn test_subscribed() {
+        assert!(subscribed(&hook(&[]), "transcription"));
+        assert!(subscribed(&hook(&["speakingStart"]), "speaking_start"));
+        assert!(subscribed(&hook(&["Speaking-End"]), "speaking_end"));
+        assert!(!subscribed(&hook(&["transcription"]), "error"));
+
+        let mut disabled = hook(&[]);
+        disabled.enabled = false;
+        assert!(!subscribed(&disabled, "transcription"));
+    }
+
+    #[test]
+    fn test_routed_elsewhere() {
+        let event = |routes: Value| {
+            let data = serde_json::json!({ "text": "hi", "routes": routes });
+            serde_json::json!({ "event": "transcription", "data": data })
+        };
+        assert!(!routed_elsewhere(&event(Value::Null)));
+        let fan_out = serde_json::json!(["provider", "webhook"]);
+        assert!(!routed_elsewhere(&event(fan_out)));
+        assert!(routed_elsewhere(&event(serde_json::json!(["notes"]))));
+    }
+
+    #[test]
+    fn test_body_and_signature() {
+        let payload = serde_json::json!({"event": "transcription", "data": {"text": "hi"}});
+        let body = build_body(payload, 1_700_000_000_000);
+        let parsed: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["event"], "transcription");
+        assert_eq!(parsed["data"]["text"], "hi");
+        assert_eq!(parsed["timestamp"], 1_700_000_000_000u64);
+
+        let signature = sign("s3cret", &body);
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_ne!(signature, sign("other", &body));
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        assert!(is_retryable(503));
+        assert!(is_retryable(429));
+        assert!(!is_retryable(404));
+        assert!(!is_retryable(401));
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(2), Duration::from_secs(2));
+    }
+
+    /// A hook that accepts connections but never answers must not hold up
+    /// the others.
+    #[tokio::test]
+    async fn test_dead_hook_does_not_block_others() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dead = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let live = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let live_port = live.local_addr().unwrap().port();
+        let (got_tx, mut got_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = live.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await;
+                let _ = got_tx.send(());
+            }
+        });
+
+        let mut dead_hook = hook(&[]);
+        dead_hook.url = format!("http://{}/hook", dead.local_addr().unwrap());
+        let mut live_hook = hook(&[]);
+        live_hook.url = format!("http://127.0.0.1:{}/hook", live_port);
+
+        let mut dispatcher = Dispatcher::new();
+        for text in ["one", "two"] {
+            let payload = serde_json::json!({ "event": "transcription", "data": { "text": text } });
+            dispatcher.dispatch(
+                vec![dead_hook.clone(), live_hook.clone()],
+                "transcription",
+                payload,
+            );
+        }
+        for _ in 0..2 {
+            tokio::time::timeout(Duration::from_secs(2), got_rx.recv())
+                .await
+                .expect("live hook delivery held up by the dead one");
+        }
+        drop(dead);
+    }
+
+    #[test]
+    fn test_redact_url() {
+        assert_eq!(
+            redact_url("http://homeassistant.local:8123/api/webhook/abc123"),
+            "http://homeassistant.local"
+        );
+        assert_eq!(redact_url("not a url"), "<invalid url>");
+    }
+}
@@ -0,0 +1,243 @@
+//! Spoken questions from the AI (`voice_ask`).
+//!
+//! The MCP `voice_ask` tool sends `AskRequest` over the pipe. The question
+//! is spoken, recording starts by itself once it has been said (no PTT
+//! press or wake word needed), and the next transcription is the answer: it
+//! goes back to the MCP server as `AskResponse` instead of starting a new
+//! turn with the AI. Recording stops on silence even in toggle mode while a
+//! question is open.
+//!
+//! Only one question is open at a time, and not while a spoken confirmation
+//! (`voice::confirm`) is. The timeout covers speaking the question as well
+//! as waiting for the answer. A cancelled `voice_ask` call (`AskCancel`) or
+//! a dropped pipe closes the question early, so the next utterance goes to
+//! the AI as usual instead of to nobody.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+use super::pipeline::VoiceEvent;
+use super::{SpeechPriority, VoiceState};
+use crate::commands::voice::VoiceEngineState;
+use crate::services::event_throttle::EmitThrottled;
+
+/// Longest wait for an answer.
+pub const MAX_TIMEOUT: Duration = Duration::from_secs(300);
+/// How often the pipeline state is checked while the question is spoken.
+const SPEECH_POLL: Duration = Duration::from_millis(100);
+/// Speech that hasn't started by then never will (e.g. no TTS engine).
+const SPEECH_START_GRACE: Duration = Duration::from_secs(3);
+
+/// How a question ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answered(String),
+    Timeout,
+    /// Closed by `cancel` before an answer came.
+    Cancelled,
+    /// Not asked: the voice engine isn't running, quiet hours, or another
+    /// question is open.
+    Unavailable(&'static str),
+}
+
+impl Outcome {
+    /// The name sent back in `AskResponse`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Answered(_) => "answered",
+            Outcome::Timeout => "timeout",
+            Outcome::Cancelled => "cancelled",
+            Outcome::Unavailable(_) => "unavailable",
+        }
+    }
+}
+
+/// The open question.
+struct Pending {
+    /// The `AskRequest` it came from.
+    request_id: String,
+    tx: oneshot::Sender<String>,
+}
+
+static PENDING: Mutex<Option<Pending>> = Mutex::new(None);
+
+/// Whether a question is waiting for its answer.
+pub fn is_open() -> bool {
+    PENDING.lock().map(|p| p.is_some()).unwrap_or(false)
+}
+
+fn is_pending(request_id: &str) -> bool {
+    PENDING
+        .lock()
+        .map(|p| p.as_ref().is_some_and(|p| p.request_id == request_id))
+        .unwrap_or(false)
+}
+
+/// Close the open question without an answer: question `request_id`, or
+/// whichever is open for `None` (the MCP server disconnected). Returns
+/// whether one was closed.
+pub fn cancel(request_id: Option<&str>) -> bool {
+    let Ok(mut slot) = PENDING.lock() else {
+        return false;
+    };
+    let matches = slot
+        .as_ref()
+        .is_some_and(|p| request_id.is_none_or(|id| p.request_id == id));
+    if matches {
+        // Dropping the sender wakes `ask` with `Cancelled`
+        *slot = None;
+    }
+    matches
+}
+
+/// Take a transcription as the answer to the open question, if there is
+/// one. `false` means no question is open and the turn goes on as usual.
+pub(crate) fn hear(app: &AppHandle, text: &str) -> bool {
+    let Some(pending) = PENDING.lock().ok().and_then(|mut p| p.take()) else {
+        return false;
+    };
+    tracing::info!(text, "Spoken answer");
+    let _ = app.emit_throttled(
+        "voice-event",
+        VoiceEvent::AskAnswer {
+            answer: Some(text.to_string()),
+        },
+    );
+    let _ = pending.tx.send(text.to_string());
+    true
+}
+
+/// Speak `question`, record the reply and return its transcription.
+/// `request_id` identifies the question for `cancel`.
+pub async fn ask(app: &AppHandle, request_id: &str, question: &str, timeout: Duration) -> Outcome {
+    let deadline = Instant::now() + timeout.min(MAX_TIMEOUT);
+    if crate::services::quiet_hours::active() {
+        return Outcome::Unavailable("quiet hours are on");
+    }
+    if super::confirm::is_open() {
+        return Outcome::Unavailable("a spoken confirmation is open");
+    }
+    let rx = {
+        let Ok(mut slot) = PENDING.lock() else {
+            return Outcome::Unavailable("question state is unavailable");
+        };
+        if slot.is_some() {
+            return Outcome::Unavailable("another question is open");
+        }
+        let state = app.state::<VoiceEngineState>();
+        let Ok(engine) = state.lock() else {
+            return Outcome::Unavailable("the voice engine is unavailable");
+        };
+        if !engine.is_running() || engine.is_paused() {
+            return Outcome::Unavailable("the voice engine is not running");
+        }
+        let (tx, rx) = oneshot::channel();
+        *slot = Some(Pending {
+            request_id: request_id.to_string(),
+            tx,
+        });
+        let spoken = engine.speak_with_priority(question.to_string(), SpeechPriority::Interrupt);
+        if let Err(e) = spoken {
+            tracing::warn!("Failed to speak question: {}", e);
+            *slot = None;
+            return Outcome::Unavailable("the question couldn't be spoken");
+        }
+        rx
+    };
+    tracing::info!(question, "Asking the user");
+    let _ = app.emit_throttled(
+        "voice-event",
+        VoiceEvent::AskQuestion {
+            question: question.to_string(),
+        },
+    );
+
+    let answer = async {
+        wait_until_spoken(app, deadline).await;
+        if is_pending(request_id) {
+            start_recording(app);
+        }
+        rx.await.ok()
+    };
+    let outcome = match tokio::time::timeout_at(deadline, answer).await {
+        Ok(Some(text)) => return Outcome::Answered(text),
+        Ok(None) => {
+            tracing::info!(question, "Spoken question cancelled");
+            Outcome::Cancelled
+        }
+        Err(_) => {
+            cancel(Some(request_id));
+            tracing::info!(question, "Spoken question timed out");
+            Outcome::Timeout
+        }
+    };
+    let _ = app.emit_throttled("voice-event", VoiceEvent::AskAnswer { answer: None });
+    outcome
+}
+
+fn voice_state(app: &AppHandle) -> Option<VoiceState> {
+    let state = app.state::<VoiceEngineState>();
+    let engine = state.lock().ok()?;
+    Some(engine.state())
+}
+
+/// Wait for the question to finish playing (or never start).
+async fn wait_until_spoken(app: &AppHandle, deadline: Instant) {
+    let started = Instant::now();
+    let mut speaking = false;
+    while Instant::now() < deadline {
+        tokio::time::sleep(SPEECH_POLL).await;
+        match voice_state(app) {
+            Some(VoiceState::Speaking) => speaking = true,
+            _ if speaking || started.elapsed() >= SPEECH_START_GRACE => return,
+            _ => {}
+        }
+    }
+}
+
+fn start_recording(app: &AppHandle) {
+    let state = app.state::<VoiceEngineState>();
+    let Ok(engine) = state.lock() else {
+        return;
+    };
+    // The user may have started talking already
+    if engine.state() == VoiceState::Recording {
+        return;
+    }
+    if let Err(e) = engine.start_recording() {
+        tracing::warn!("Failed to start recording the answer: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_names() {
+        assert_eq!(Outcome::Answered("blue".into()).name(), "answered");
+        assert_eq!(Outcome::Timeout.name(), "timeout");
+        assert_eq!(Outcome::Cancelled.name(), "cancelled");
+        assert_eq!(Outcome::Unavailable("busy").name(), "unavailable");
+    }
+
+    #[test]
+    fn test_cancel_closes_only_the_matching_question() {
+        let (tx, mut rx) = oneshot::channel();
+        *PENDING.lock().unwrap() = Some(Pending {
+            request_id: "ask-1".into(),
+            tx,
+        });
+        assert!(!cancel(Some("ask-2")));
+        assert!(is_open());
+        assert!(cancel(Some("ask-1")));
+        assert!(!is_open());
+        // The waiting `ask` wakes up without an answer
+        assert!(rx.try_recv().is_err());
+        assert!(!cancel(None));
+    }
+}
@@ -0,0 +1,233 @@
+//! Voice activity calibration.
+//!
+//! The energy VAD needs a threshold that sits between the room's background
+//! noise and the user's speaking level, and both vary a lot between a laptop
+//! mic in a quiet room and a headset next to a fan. Calibration records a
+//! few seconds of silence and a few seconds of speech, measures the energy
+//! of each 80ms frame (the same frames the live VAD sees), and places the
+//! threshold between the two distributions.
+//!
+//! The silence timeout is derived from the longest pause inside the speech
+//! take, so natural mid-sentence pauses don't end a recording early.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use serde::Serialize;
+
+use super::pipeline::{find_input_device, CHUNK_SAMPLES, TARGET_SAMPLE_RATE};
+use super::resample::{ResamplerKind, StreamResampler};
+use super::vad::compute_energy;
+
+/// Length of each recording phase (silence, then speech).
+pub const PHASE_SECS: f64 = 3.0;
+/// Typical speech must be at least this many times louder than the noise
+/// peaks for a usable threshold.
+const MIN_SEPARATION: f32 = 1.5;
+/// Keeps a noisy room from pushing the threshold into the speech range, and a
+/// dead-silent one from making it trigger on breathing.
+const THRESHOLD_RANGE: (f32, f32) = (0.002, 0.2);
+/// Added to the longest pause measured in the speech take.
+const PAUSE_MARGIN_SECS: f64 = 0.7;
+const SILENCE_TIMEOUT_RANGE: (f64, f64) = (1.0, 3.0);
+
+/// Measured levels and the recommended settings.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Calibration {
+    /// Recommended `voice.vadThreshold`.
+    pub vad_threshold: f32,
+    /// Recommended `voice.silenceTimeoutSecs`.
+    pub silence_timeout_secs: f64,
+    /// Median frame energy while silent.
+    pub noise_floor: f32,
+    /// 95th percentile frame energy while silent.
+    pub noise_peak: f32,
+    /// 75th percentile frame energy while talking. The take includes short
+    /// pauses, so the median would understate it.
+    pub speech_level: f32,
+    /// `speech_level` over `noise_floor`, in dB.
+    pub snr_db: f32,
+}
+
+/// Record `secs` of 16kHz mono audio from `device` (system default for
+/// `None`), converted with the same resampler as live capture. Blocks for
+/// the duration; call from a blocking task.
+///
+/// Uses its own input stream, so it works whether or not the voice pipeline
+/// is running.
+pub fn record(
+    device: Option<&str>,
+    secs: f64,
+    resampler: ResamplerKind,
+) -> Result<Vec<f32>, String> {
+    let device = find_input_device(device)?;
+    let default_config = device
+        .default_input_config()
+        .map_err(|e| format!("Failed to get default input config: {}", e))?;
+    let native_rate = default_config.sample_rate().0;
+    let channels = default_config.channels() as usize;
+    let stream_config = cpal::StreamConfig {
+        channels: default_config.channels(),
+        sample_rate: cpal::SampleRate(native_rate),
+        buffer_size: cpal::BufferSize::Default,
+    };
+
+    let wanted = (secs * TARGET_SAMPLE_RATE as f64) as usize;
+    let captured = Arc::new(Mutex::new(Vec::with_capacity(wanted)));
+    let sink = Arc::clone(&captured);
+    let mut resampler = StreamResampler::new(resampler, native_rate, TARGET_SAMPLE_RATE);
+    let stream = device
+        .build_input_stream(
+            &stream_config,
+            move |data: &[f32], _info: &cpal::InputCallbackInfo| {
+                let mono: Vec<f32> = data
+                    .chunks_exact(channels.max(1))
+                    .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+                    .collect();
+                let resampled = resampler.process(mono);
+                if let Ok(mut buf) = sink.lock() {
+                    buf.extend_from_slice(&resampled);
+                }
+            },
+            move |err| {
+                tracing::error!("Calibration input stream error: {}", err);
+            },
+            None,
+        )
+        .map_err(|e| format!("Failed to build input stream: {}", e))?;
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start input stream: {}", e))?;
+    std::thread::sleep(Duration::from_secs_f64(secs));
+    drop(stream);
+
+    let mut samples = std::mem::take(
+        &mut *captured
+            .lock()
+            .map_err(|e| format!("Failed to lock capture buffer: {}", e))?,
+    );
+    samples.truncate(wanted);
+    Ok(samples)
+}
+
+/// Derive VAD settings from a silence take and a speech take.
+pub fn analyze(silence: &[f32], speech: &[f32]) -> Result<Calibration, String> {
+    let mut noise = frame_energies(silence);
+    let mut talk = frame_energies(speech);
+    if noise.len() < 5 || talk.len() < 5 {
+        return Err("Too little audio was captured to calibrate".into());
+    }
+    noise.sort_by(f32::total_cmp);
+    talk.sort_by(f32::total_cmp);
+
+    let noise_floor = percentile(&noise, 0.5);
+    let noise_peak = percentile(&noise, 0.95);
+    let speech_level = percentile(&talk, 0.75);
+    if speech_level < noise_peak * MIN_SEPARATION {
+        return Err("Couldn't tell speech apart from the background noise. \
+                    Speak closer to the microphone, or calibrate somewhere quieter."
+            .into());
+    }
+
+    // Geometric mean: energy ratios matter more than differences
+    let threshold = (noise_peak * speech_level).sqrt();
+    let vad_threshold = round_to(
+        threshold.clamp(THRESHOLD_RANGE.0, THRESHOLD_RANGE.1) as f64,
+        4,
+    ) as f32;
+
+    let pause = longest_pause_secs(&frame_energies(speech), vad_threshold);
+    let silence_timeout_secs = round_to(
+        (pause + PAUSE_MARGIN_SECS).clamp(SILENCE_TIMEOUT_RANGE.0, SILENCE_TIMEOUT_RANGE.1),
+        1,
+    );
+
+    Ok(Calibration {
+        vad_threshold,
+        silence_timeout_secs,
+        noise_floor,
+        noise_peak,
+        speech_level,
+        snr_db: 20.0 * (speech_level / noise_floor.max(1e-6)).log10(),
+    })
+}
+
+/// Energy of each whole VAD frame.
+fn frame_energies(samples: &[f32]) -> Vec<f32> {
+    samples
+        .chunks_exact(CHUNK_SAMPLES)
+        .map(compute_energy)
+        .collect()
+}
+
+/// `p` (0.0 - 1.0) percentile of an ascending, non-empty slice.
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    let idx = ((sorted.len() - 1) as f32 * p).round() as usize;
+    sorted[idx.min(sorted.len() - 1)]
+}
+
+/// Longest run of below-threshold frames with speech on both sides.
+fn longest_pause_secs(energies: &[f32], threshold: f32) -> f64 {
+    let mut longest = 0usize;
+    let mut run = 0usize;
+    let mut heard_speech = false;
+    for &energy in energies {
+        if energy > threshold {
+            if heard_speech {
+                longest = longest.max(run);
+            }
+            heard_speech = true;
+            run = 0;
+        } else {
+            run += 1;
+        }
+    }
+    (longest * CHUNK_SAMPLES) as f64 / TARGET_SAMPLE_RATE as f64
+}
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let scale = 10f64.powi(decimals);
+    (value * scale).round() / scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `frames` VAD frames of constant amplitude.
+    fn level(amplitude: f32, frames: usize) -> Vec<f32> {
+        vec![amplitude; frames * CHUNK_SAMPLES]
+    }
+
+    #[test]
+    fn test_analyze_places_threshold_between_noise_and_speech() {
+        let silence = level(0.002, 30);
+        // Speech with a 0.48s (6 frame) pause in the middle
+        let mut speech = level(0.05, 12);
+        speech.extend(level(0.002, 6));
+        speech.extend(level(0.05, 12));
+
+        let cal = analyze(&silence, &speech).unwrap();
+        assert!(cal.vad_threshold > 0.002 && cal.vad_threshold < 0.05);
+        assert!((cal.vad_threshold - 0.01).abs() < 1e-3);
+        assert_eq!(cal.silence_timeout_secs, 1.2);
+        assert!(cal.snr_db > 25.0);
+    }
+
+    #[test]
+    fn test_analyze_rejects_speech_at_noise_level() {
+        let silence = level(0.02, 30);
+        let speech = level(0.025, 30);
+        assert!(analyze(&silence, &speech).is_err());
+        assert!(analyze(&[], &speech).is_err());
+    }
+
+    #[test]
+    fn test_longest_pause_ignores_leading_and_trailing_silence() {
+        let energies = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+        assert!((longest_pause_secs(&energies, 0.5) - 0.16).abs() < 1e-9);
+        assert_eq!(longest_pause_secs(&[0.0; 10], 0.5), 0.0);
+    }
+}
@@ -0,0 +1,224 @@
+//! Feature availability for the settings UI.
+//!
+//! Several voice options depend on how the app was built (cargo features) or
+//! on what is installed (model files, OS speech). Settings lists them all, so
+//! without this report a choice that can't work only fails when the pipeline
+//! starts. Each entry says whether the feature works right now and, if not,
+//! why, so the UI can hide the option or explain it.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::stt;
+use super::tts::SystemTts;
+use crate::config::schema::VoiceConfig;
+use crate::services::model_manager;
+
+/// Whether one feature is usable, and why not.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Capability {
+    pub available: bool,
+    /// Shown to the user when `available` is false.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Capability {
+    fn yes() -> Self {
+        Self {
+            available: true,
+            reason: None,
+        }
+    }
+
+    fn no(reason: impl Into<String>) -> Self {
+        Self {
+            available: false,
+            reason: Some(reason.into()),
+        }
+    }
+
+    fn when(available: bool, reason: &str) -> Self {
+        if available {
+            Self::yes()
+        } else {
+            Self::no(reason)
+        }
+    }
+}
+
+/// Everything `get_capabilities` reports.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    /// Local whisper inference (`whisper` build feature).
+    pub whisper: Capability,
+    /// GPU backend compiled into whisper.cpp: "cuda", "metal", "vulkan".
+    pub whisper_gpu_backend: Option<&'static str>,
+    /// Model files are downloaded. Selecting the adapter in Settings
+    /// downloads them, so a missing model doesn't make an adapter
+    /// unavailable.
+    pub whisper_model: Capability,
+    pub parakeet_model: Capability,
+    pub kokoro_model: Capability,
+    /// ONNX Runtime engines: Parakeet, Kokoro (`onnx` build feature).
+    pub onnx: Capability,
+    pub noise_suppression: Capability,
+    /// Kokoro's embedded phonemizer (`builtin-g2p`); without it Kokoro
+    /// needs espeak-ng installed.
+    pub builtin_g2p: Capability,
+    /// A trained wake-word detector. Wake word mode works without one by
+    /// starting on voice activity.
+    pub wake_word_model: Capability,
+    /// Acoustic echo cancellation, for barge-in over speakers.
+    pub echo_cancellation: Capability,
+    /// Turning other apps down while recording.
+    pub audio_ducking: Capability,
+    /// An API key is saved for the cloud STT / TTS adapters.
+    pub stt_cloud_configured: Capability,
+    pub tts_cloud_configured: Capability,
+    /// Per STT adapter (keys match `voice.sttAdapter`): whether this build
+    /// can run it.
+    pub stt_adapters: BTreeMap<&'static str, Capability>,
+    /// Per TTS adapter (keys match `voice.ttsAdapter`).
+    pub tts_adapters: BTreeMap<&'static str, Capability>,
+}
+
+const NO_WHISPER: &str = "This build was compiled without the whisper feature, \
+                          so local speech recognition doesn't run.";
+const NO_ONNX: &str = "This build was compiled without the onnx feature.";
+const NOT_IMPLEMENTED: &str = "Not implemented yet.";
+
+/// Check every feature against the current voice settings.
+pub fn detect(voice: &VoiceConfig, data_dir: &Path) -> Capabilities {
+    let whisper = cfg!(feature = "whisper");
+    let onnx = cfg!(feature = "onnx");
+    let language = stt::normalize_stt_language(&voice.stt_language);
+    let whisper_installed =
+        model_manager::whisper_asset(data_dir, &voice.stt_model_size, &language).is_installed();
+    let parakeet_installed = model_manager::parakeet_asset(data_dir).is_installed();
+    let kokoro_installed =
+        model_manager::kokoro_asset(&data_dir.join("models").join("kokoro")).is_installed();
+    let system_tts = SystemTts::new(None, 1.0).is_ok();
+
+    Capabilities {
+        whisper: Capability::when(whisper, NO_WHISPER),
+        whisper_gpu_backend: stt::whisper_gpu_backend(),
+        whisper_model: Capability::when(
+            whisper_installed,
+            "The whisper model for this size and language isn't downloaded yet.",
+        ),
+        parakeet_model: Capability::when(
+            parakeet_installed,
+            "The Parakeet model isn't downloaded yet.",
+        ),
+        kokoro_model: Capability::when(
+            kokoro_installed,
+            "The Kokoro model isn't downloaded; Edge TTS is used until it is.",
+        ),
+        onnx: Capability::when(onnx, NO_ONNX),
+        noise_suppression: Capability::when(
+            cfg!(feature = "noise-suppression"),
+            "This build was compiled without the noise-suppression feature.",
+        ),
+        builtin_g2p: Capability::when(
+            cfg!(feature = "builtin-g2p"),
+            "Kokoro uses espeak-ng to pronounce text; install it if Kokoro stays silent.",
+        ),
+        wake_word_model: Capability::no(
+            "No wake-word model is included. Wake word mode starts recording \
+             when it hears speech instead of listening for the phrase.",
+        ),
+        echo_cancellation: Capability::no(
+            "Echo cancellation isn't available. Use headphones so replies \
+             don't trigger the microphone.",
+        ),
+        audio_ducking: Capability::when(cfg!(windows), "Audio ducking is Windows only."),
+        stt_cloud_configured: Capability::when(
+            has_key(&voice.stt_api_key),
+            "No API key is set for cloud speech recognition.",
+        ),
+        tts_cloud_configured: Capability::when(
+            has_key(&voice.tts_api_key),
+            "No API key is set for cloud text-to-speech.",
+        ),
+        stt_adapters: stt_adapters(whisper, onnx),
+        tts_adapters: tts_adapters(onnx, system_tts),
+    }
+}
+
+fn has_key(key: &Option<String>) -> bool {
+    key.as_deref().is_some_and(|k| !k.trim().is_empty())
+}
+
+fn stt_adapters(whisper: bool, onnx: bool) -> BTreeMap<&'static str, Capability> {
+    let mut adapters = BTreeMap::new();
+    adapters.insert("whisper-local", Capability::when(whisper, NO_WHISPER));
+    adapters.insert(
+        "parakeet",
+        Capability::when(
+            onnx,
+            "Parakeet needs the onnx build feature; whisper is used instead.",
+        ),
+    );
+    adapters.insert("openai-whisper-api", Capability::no(NOT_IMPLEMENTED));
+    adapters.insert("custom-api-stt", Capability::no(NOT_IMPLEMENTED));
+    adapters
+}
+
+fn tts_adapters(onnx: bool, system_tts: bool) -> BTreeMap<&'static str, Capability> {
+    let mut adapters = BTreeMap::new();
+    adapters.insert(
+        "kokoro",
+        Capability::when(
+            onnx,
+            "Kokoro needs the onnx build feature; Edge TTS is used instead.",
+        ),
+    );
+    adapters.insert("edge", Capability::yes());
+    adapters.insert(
+        "system",
+        Capability::when(system_tts, "No system speech synthesizer was found."),
+    );
+    let edge_fallback = "Not implemented yet; Edge TTS is used instead.";
+    adapters.insert("openai-tts", Capability::no(edge_fallback));
+    adapters.insert("elevenlabs", Capability::no(edge_fallback));
+    for adapter in ["qwen", "piper", "custom-api"] {
+        adapters.insert(adapter, Capability::no(NOT_IMPLEMENTED));
+    }
+    adapters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stt_adapters_follow_build_features() {
+        let adapters = stt_adapters(true, false);
+        assert!(adapters["whisper-local"].available);
+        assert!(!adapters["parakeet"].available);
+        assert!(!adapters["openai-whisper-api"].available);
+        assert!(stt_adapters(true, true)["parakeet"].available);
+        assert!(!stt_adapters(false, true)["whisper-local"].available);
+    }
+
+    #[test]
+    fn test_tts_adapters() {
+        let adapters = tts_adapters(false, false);
+        assert!(adapters["kokoro"]
+            .reason
+            .as_deref()
+            .unwrap()
+            .contains("onnx"));
+        assert!(adapters["edge"].available);
+        assert!(!adapters["system"].available);
+        assert!(!adapters["piper"].available);
+        assert!(tts_adapters(true, true)["kokoro"].available);
+
+        let json = serde_json::to_value(&adapters["edge"]).unwrap();
+        assert_eq!(json, serde_json::json!({ "available": true }));
+    }
+}
@@ -0,0 +1,222 @@
+//! Spoken confirmation of destructive tool calls.
+//!
+//! Destructive MCP tools (`memory_forget`, the n8n deletes) only run with
+//! `confirmed: true`. In wake-word mode nobody is at the keyboard to
+//! confirm, so when one is called without it the MCP server asks the app
+//! (`ConfirmRequest` over the pipe). The question is spoken, and the next
+//! utterance is matched against a small yes/no grammar instead of going to
+//! the AI. The answer goes back to the MCP server, which runs the tool on
+//! "yes" and reports the refusal on "no".
+//!
+//! Only one question is open at a time. Outside wake-word mode, with
+//! `voice.spokenConfirmation` off, during quiet hours, or when no clear
+//! answer comes within `TIMEOUT`, the server falls back to asking the AI to
+//! get confirmation itself.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+use super::intents::normalize;
+use super::pipeline::VoiceEvent;
+use super::{SpeechPriority, VoiceMode};
+use crate::commands::voice::VoiceEngineState;
+use crate::services::event_throttle::EmitThrottled;
+
+/// How long to wait for an answer after asking.
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+/// Unclear replies before giving up.
+const MAX_REPROMPTS: u8 = 1;
+/// Spoken after a reply that was neither yes nor no.
+pub(crate) const REPROMPT: &str = "Please say yes or no.";
+
+const YES: &[&str] = &[
+    "yes", "yeah", "yep", "yup", "sure", "confirm", "confirmed", "affirmative", "correct",
+    "proceed", "do it", "go ahead", "go for it",
+];
+const NO: &[&str] = &[
+    "no", "nope", "nah", "cancel", "dont", "do not", "stop", "abort", "negative", "never mind",
+    "nevermind", "keep it",
+];
+
+/// The outcome of a confirmation request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Yes,
+    No,
+    /// Two replies that were neither yes nor no.
+    Unclear,
+    Timeout,
+    /// Not asked: not in wake-word mode, turned off, or busy.
+    Unavailable,
+}
+
+impl Answer {
+    /// The name sent back in `ConfirmResponse`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Answer::Yes => "yes",
+            Answer::No => "no",
+            Answer::Unclear => "unclear",
+            Answer::Timeout => "timeout",
+            Answer::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// What `hear` did with an utterance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Heard {
+    /// It answered the open question.
+    Answered(Answer),
+    /// Neither yes nor no; ask again.
+    Reprompt,
+}
+
+struct Pending {
+    tool: String,
+    tx: oneshot::Sender<Answer>,
+    reprompts: u8,
+}
+
+static PENDING: Mutex<Option<Pending>> = Mutex::new(None);
+
+/// Whether a question is waiting for its yes or no.
+pub fn is_open() -> bool {
+    PENDING.lock().map(|p| p.is_some()).unwrap_or(false)
+}
+
+/// Yes (`Some(true)`), no (`Some(false)`), or neither. The whole reply has
+/// to be one of the phrases, or start with one ("yes, delete it").
+pub fn classify(text: &str) -> Option<bool> {
+    let text = normalize(text);
+    let matches = |phrases: &[&str]| {
+        phrases.iter().any(|p| {
+            text == *p || text.strip_prefix(p).is_some_and(|rest| rest.starts_with(' '))
+        })
+    };
+    match (matches(YES), matches(NO)) {
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        _ => None,
+    }
+}
+
+/// Offer a transcription to the open question, if there is one. `None`
+/// means no question is open and the turn goes on as usual.
+pub(crate) fn hear(app: &AppHandle, text: &str) -> Option<Heard> {
+    let mut slot = PENDING.lock().ok()?;
+    let pending = slot.as_mut()?;
+    let answer = match classify(text) {
+        Some(true) => Answer::Yes,
+        Some(false) => Answer::No,
+        None if pending.reprompts < MAX_REPROMPTS => {
+            pending.reprompts += 1;
+            return Some(Heard::Reprompt);
+        }
+        None => Answer::Unclear,
+    };
+    let pending = slot.take()?;
+    tracing::info!(tool = %pending.tool, answer = answer.name(), text, "Spoken confirmation");
+    let _ = app.emit_throttled(
+        "voice-event",
+        VoiceEvent::ConfirmAnswer {
+            tool: pending.tool,
+            answer: answer.name().into(),
+        },
+    );
+    let _ = pending.tx.send(answer);
+    Some(Heard::Answered(answer))
+}
+
+/// Speak `prompt` and wait for a yes or no to `tool`. Returns
+/// `Unavailable` right away when the question can't be asked by voice.
+pub async fn ask(app: &AppHandle, tool: &str, prompt: &str) -> Answer {
+    let enabled = crate::commands::config::get_config_snapshot()
+        .voice
+        .spoken_confirmation;
+    if !enabled || crate::services::quiet_hours::active() {
+        return Answer::Unavailable;
+    }
+    let rx = {
+        let Ok(mut slot) = PENDING.lock() else {
+            return Answer::Unavailable;
+        };
+        if slot.is_some() || super::ask::is_open() {
+            tracing::warn!(tool, "A spoken confirmation or question is already open");
+            return Answer::Unavailable;
+        }
+        let state = app.state::<VoiceEngineState>();
+        let Ok(engine) = state.lock() else {
+            return Answer::Unavailable;
+        };
+        if !engine.is_running() || engine.config().mode != VoiceMode::WakeWord {
+            return Answer::Unavailable;
+        }
+        let (tx, rx) = oneshot::channel();
+        *slot = Some(Pending {
+            tool: tool.to_string(),
+            tx,
+            reprompts: 0,
+        });
+        if let Err(e) = engine.speak_with_priority(prompt.to_string(), SpeechPriority::Interrupt) {
+            tracing::warn!("Failed to speak confirmation prompt: {}", e);
+            *slot = None;
+            return Answer::Unavailable;
+        }
+        rx
+    };
+    tracing::info!(tool, prompt, "Asking for spoken confirmation");
+    let _ = app.emit_throttled(
+        "voice-event",
+        VoiceEvent::ConfirmPrompt {
+            tool: tool.to_string(),
+            prompt: prompt.to_string(),
+        },
+    );
+
+    match tokio::time::timeout(TIMEOUT, rx).await {
+        Ok(Ok(answer)) => answer,
+        _ => {
+            if let Ok(mut slot) = PENDING.lock() {
+                *slot = None;
+            }
+            tracing::info!(tool, "Spoken confirmation timed out");
+            let _ = app.emit_throttled(
+                "voice-event",
+                VoiceEvent::ConfirmAnswer {
+                    tool: tool.to_string(),
+                    answer: Answer::Timeout.name().into(),
+                },
+            );
+            Answer::Timeout
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("Yes."), Some(true));
+        assert_eq!(classify("Yeah, go ahead"), Some(true));
+        assert_eq!(classify("Please do it"), Some(true));
+        assert_eq!(classify("No!"), Some(false));
+        assert_eq!(classify("Don't."), Some(false));
+        assert_eq!(classify("Never mind"), Some(false));
+        assert_eq!(classify("no wait, keep it"), Some(false));
+    }
+
+    #[test]
+    fn test_classify_rejects_other_speech() {
+        assert_eq!(classify("What's the weather?"), None);
+        // A word that merely starts with "no" isn't an answer
+        assert_eq!(classify("nothing"), None);
+        assert_eq!(classify("yesterday"), None);
+        assert_eq!(classify(""), None);
+    }
+}
@@ -0,0 +1,136 @@
+//! Noise suppression ahead of VAD and STT.
+//!
+//! Fans, keyboards and room hum push the energy VAD over its threshold and
+//! raise whisper's word error rate. With the `noise-suppression` build
+//! feature, each captured chunk goes through RNNoise (the `nnnoiseless` port)
+//! before VAD sees it and before it lands in the recording buffer.
+//!
+//! RNNoise works on 10ms frames of 48kHz audio in i16 sample scale. The
+//! pipeline runs at 16kHz by default, so each 160-sample frame is upsampled
+//! 3x, denoised, and averaged back down (8kHz: 80 samples, 6x; 48kHz: as
+//! is). Samples that don't fill a frame are carried over to the next chunk.
+
+/// RNNoise runs at 48kHz.
+#[cfg_attr(not(feature = "noise-suppression"), allow(dead_code))]
+const RNNOISE_RATE: u32 = 48_000;
+/// 10ms at 48kHz.
+#[cfg_attr(not(feature = "noise-suppression"), allow(dead_code))]
+const HI_FRAME: usize = 480;
+
+/// Streaming RNNoise denoiser for mono audio at the pipeline rate.
+pub struct NoiseSuppressor {
+    #[cfg(feature = "noise-suppression")]
+    state: Box<nnnoiseless::DenoiseState<'static>>,
+    /// 10ms at the pipeline rate.
+    #[cfg_attr(not(feature = "noise-suppression"), allow(dead_code))]
+    frame: usize,
+    /// Input samples that didn't fill a whole frame yet.
+    #[cfg_attr(not(feature = "noise-suppression"), allow(dead_code))]
+    carry: Vec<f32>,
+}
+
+impl NoiseSuppressor {
+    /// A denoiser for `sample_rate` audio (a divisor of 48kHz), or `None`
+    /// when this build doesn't include RNNoise.
+    pub fn new(sample_rate: u32) -> Option<Self> {
+        #[cfg(feature = "noise-suppression")]
+        {
+            if sample_rate == 0 || RNNOISE_RATE % sample_rate != 0 {
+                tracing::warn!(
+                    sample_rate,
+                    "Noise suppression needs a divisor of 48kHz, skipping"
+                );
+                return None;
+            }
+            let frame = (sample_rate / 100) as usize;
+            Some(Self {
+                state: nnnoiseless::DenoiseState::new(),
+                frame,
+                carry: Vec::with_capacity(frame),
+            })
+        }
+        #[cfg(not(feature = "noise-suppression"))]
+        {
+            let _ = sample_rate;
+            tracing::warn!(
+                "Noise suppression requested but this build lacks the \
+                 `noise-suppression` feature; audio is left as captured"
+            );
+            None
+        }
+    }
+
+    /// Denoise `input` into `out` (cleared first). Only whole frames are
+    /// emitted, so `out` can be up to one frame shorter or longer than
+    /// `input`.
+    #[cfg(feature = "noise-suppression")]
+    pub fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        debug_assert_eq!(HI_FRAME, nnnoiseless::DenoiseState::FRAME_SIZE);
+
+        out.clear();
+        self.carry.extend_from_slice(input);
+        let whole = self.carry.len() / self.frame * self.frame;
+        let mut hi_in = [0.0f32; HI_FRAME];
+        let mut hi_out = [0.0f32; HI_FRAME];
+        for frame in self.carry[..whole].chunks_exact(self.frame) {
+            upsample_frame(frame, &mut hi_in);
+            self.state.process_frame(&mut hi_out, &hi_in);
+            downsample_frame(&hi_out, self.frame, out);
+        }
+        self.carry.drain(..whole);
+    }
+
+    /// Without RNNoise compiled in, audio passes through unchanged.
+    #[cfg(not(feature = "noise-suppression"))]
+    pub fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        out.clear();
+        out.extend_from_slice(input);
+    }
+}
+
+/// 10ms frame (-1.0..1.0) to 48kHz in i16 scale, by linear interpolation.
+#[cfg_attr(not(feature = "noise-suppression"), allow(dead_code))]
+fn upsample_frame(frame: &[f32], hi: &mut [f32]) {
+    let factor = hi.len() / frame.len();
+    for (i, &s) in frame.iter().enumerate() {
+        let next = frame.get(i + 1).copied().unwrap_or(s);
+        for k in 0..factor {
+            let t = k as f32 / factor as f32;
+            hi[i * factor + k] = (s + (next - s) * t) * 32768.0;
+        }
+    }
+}
+
+/// 48kHz i16-scale frame back to `frame_len` samples (-1.0..1.0).
+/// Averaging each group doubles as a crude low-pass before decimating.
+#[cfg_attr(not(feature = "noise-suppression"), allow(dead_code))]
+fn downsample_frame(hi: &[f32], frame_len: usize, out: &mut Vec<f32>) {
+    let factor = hi.len() / frame_len;
+    out.extend(
+        hi.chunks_exact(factor)
+            .map(|g| (g.iter().sum::<f32>() / factor as f32 / 32768.0).clamp(-1.0, 1.0)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resampling_round_trip() {
+        for len in [160, 80] {
+            let frame: Vec<f32> = (0..len).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+            let mut hi = vec![0.0f32; HI_FRAME];
+            upsample_frame(&frame, &mut hi);
+            assert!((hi[0] - frame[0] * 32768.0).abs() < 1e-3);
+
+            let mut back = Vec::new();
+            downsample_frame(&hi, len, &mut back);
+            assert_eq!(back.len(), len);
+            // Averaging shifts each sample by a fraction of a step; stays close
+            for (a, b) in frame.iter().zip(&back) {
+                assert!((a - b).abs() < 0.03, "{} vs {}", a, b);
+            }
+        }
+    }
+}
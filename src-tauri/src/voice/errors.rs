@@ -0,0 +1,140 @@
+//! Recent voice errors, kept as breadcrumbs for diagnostics.
+//!
+//! Every `error` voice event is built by `VoiceEvent::error` (or
+//! `turn_error` inside the pipeline), which also records it here: its
+//! `AppError` code, message, a sequence id, and the voice turn it broke
+//! when there was one. The turn is the `turn` of that turn's
+//! `pipeline_timing`, so an error can be lined up with the stage timings
+//! of the same turn. The event carries the same `id` and `turn`.
+//!
+//! The last `CAPACITY` errors are kept for the life of the process (they
+//! span pipeline restarts). `voice_get_errors` returns them, and the panic
+//! hook appends them to the crash report so a crash comes with what went
+//! wrong just before it.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::error::AppError;
+
+/// Errors kept.
+pub const CAPACITY: usize = 100;
+
+/// One recorded error.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorRecord {
+    /// Increments per error since launch.
+    pub id: u64,
+    /// Unix time (ms).
+    pub at: u64,
+    /// Stable `AppError` code (`stt_failed`, `mic_unavailable`, ...).
+    pub code: &'static str,
+    pub message: String,
+    /// Pipeline turn the error happened in, if any.
+    pub turn: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct ErrorLog {
+    next_id: u64,
+    records: VecDeque<ErrorRecord>,
+}
+
+impl ErrorLog {
+    fn push(&mut self, err: &AppError, turn: Option<u64>, at: u64) -> ErrorRecord {
+        self.next_id += 1;
+        let record = ErrorRecord {
+            id: self.next_id,
+            at,
+            code: err.code(),
+            message: err.to_string(),
+            turn,
+        };
+        if self.records.len() == CAPACITY {
+            self.records.pop_front();
+        }
+        self.records.push_back(record.clone());
+        record
+    }
+
+    /// The newest `limit` records, oldest first.
+    fn recent(&self, limit: usize) -> Vec<ErrorRecord> {
+        let skip = self.records.len().saturating_sub(limit);
+        self.records.iter().skip(skip).cloned().collect()
+    }
+}
+
+static LOG: Mutex<ErrorLog> = Mutex::new(ErrorLog {
+    next_id: 0,
+    records: VecDeque::new(),
+});
+
+/// Record `err`, raised during pipeline turn `turn`.
+pub fn record(err: &AppError, turn: Option<u64>) -> ErrorRecord {
+    let at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let mut log = LOG.lock().unwrap_or_else(|e| e.into_inner());
+    log.push(err, turn, at)
+}
+
+/// The newest `limit` errors (all kept for `None`), oldest first.
+pub fn recent(limit: Option<usize>) -> Vec<ErrorRecord> {
+    LOG.lock()
+        .map(|log| log.recent(limit.unwrap_or(CAPACITY)))
+        .unwrap_or_default()
+}
+
+pub fn clear() {
+    if let Ok(mut log) = LOG.lock() {
+        log.records.clear();
+    }
+}
+
+/// The recent errors as text for a crash report. Doesn't wait for the
+/// lock: a panic while it is held must not deadlock the hook.
+pub fn breadcrumbs() -> String {
+    let Ok(log) = LOG.try_lock() else {
+        return "(error log busy)\n".into();
+    };
+    if log.records.is_empty() {
+        return "(none)\n".into();
+    }
+    log.records
+        .iter()
+        .map(|r| {
+            let turn = r.turn.map(|t| format!(" turn {}", t)).unwrap_or_default();
+            format!("#{} {}{} [{}] {}\n", r.id, r.at, turn, r.code, r.message)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_keeps_newest() {
+        let mut log = ErrorLog::default();
+        for i in 0..CAPACITY as u64 + 5 {
+            log.push(&AppError::Stt(format!("fail {}", i)), Some(i), i);
+        }
+        let all = log.recent(CAPACITY);
+        assert_eq!(all.len(), CAPACITY);
+        assert_eq!(all[0].id, 6);
+        assert_eq!(
+            all.last().unwrap().message,
+            format!("fail {}", CAPACITY + 4)
+        );
+
+        let last_two = log.recent(2);
+        assert_eq!(last_two.len(), 2);
+        assert_eq!(last_two[1].code, "stt_failed");
+        assert_eq!(last_two[1].turn, Some(CAPACITY as u64 + 4));
+    }
+}
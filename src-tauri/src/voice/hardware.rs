@@ -0,0 +1,315 @@
+//! Hardware probe and whisper model recommendation.
+//!
+//! Bigger whisper models transcribe better but slower, and how much slower
+//! depends on the machine: core count, SIMD support, and whether a CUDA GPU
+//! with enough memory is available. [`probe`] collects those facts and
+//! [`recommend`] estimates each model's latency for a typical utterance,
+//! then picks the largest one expected to stay under the target.
+//!
+//! The estimates are rough: relative model costs measured on a 4-thread
+//! AVX2 desktop, scaled for the probed machine. They're good enough to tell
+//! "tiny" machines from "large-v3-turbo" ones, not to predict milliseconds.
+
+use serde::Serialize;
+
+/// Widest SIMD instruction set whisper.cpp can use on this CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Simd {
+    Avx2,
+    Avx,
+    Neon,
+    None,
+}
+
+impl Simd {
+    fn detect() -> Self {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if std::arch::is_x86_feature_detected!("avx2") {
+                return Simd::Avx2;
+            }
+            if std::arch::is_x86_feature_detected!("avx") {
+                return Simd::Avx;
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") {
+                return Simd::Neon;
+            }
+        }
+        Simd::None
+    }
+
+    /// Inference time relative to AVX2.
+    fn slowdown(self) -> f64 {
+        match self {
+            Simd::Avx2 => 1.0,
+            Simd::Neon => 1.1,
+            Simd::Avx => 1.7,
+            Simd::None => 3.0,
+        }
+    }
+}
+
+/// An NVIDIA GPU as reported by `nvidia-smi`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NvidiaGpu {
+    pub name: String,
+    pub vram_mb: Option<u64>,
+    pub driver_version: String,
+}
+
+/// Query `nvidia-smi` for the first NVIDIA GPU. `None` without a driver.
+pub fn nvidia_gpu() -> Option<NvidiaGpu> {
+    let mut smi_cmd = std::process::Command::new("nvidia-smi");
+    smi_cmd.args(["--query-gpu=name,memory.total,driver_version", "--format=csv,noheader"]);
+    crate::util::hidden(&mut smi_cmd);
+    let output = smi_cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    // Several GPUs print one line each; the first is the one whisper uses.
+    parse_nvidia_smi(text.lines().next()?)
+}
+
+/// Parse "NVIDIA GeForce RTX 5070 Ti, 16303 MiB, 581.80".
+fn parse_nvidia_smi(line: &str) -> Option<NvidiaGpu> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let parts: Vec<&str> = line.split(", ").collect();
+    Some(NvidiaGpu {
+        name: parts.first().unwrap_or(&"Unknown").to_string(),
+        vram_mb: parts
+            .get(1)
+            .and_then(|s| s.replace(" MiB", "").trim().parse::<u64>().ok()),
+        driver_version: parts.get(2).unwrap_or(&"").to_string(),
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardwareProfile {
+    /// Logical CPUs.
+    pub cpu_cores: usize,
+    /// Threads whisper runs with (see `WhisperSttEngine::inference_threads`).
+    pub inference_threads: usize,
+    pub simd: Simd,
+    /// Total system memory. `None` if it couldn't be read.
+    pub ram_mb: Option<u64>,
+    pub gpu: Option<NvidiaGpu>,
+    /// Built with the `cuda` feature, so whisper can use the GPU.
+    pub cuda_compiled: bool,
+}
+
+impl HardwareProfile {
+    /// CUDA inference is possible: compiled in and an NVIDIA GPU present.
+    pub fn cuda_usable(&self) -> bool {
+        self.cuda_compiled && self.gpu.is_some()
+    }
+}
+
+/// Probe this machine. Runs `nvidia-smi`, so call off the async runtime.
+pub fn probe() -> HardwareProfile {
+    let cpu_cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    let total = sys.total_memory();
+    HardwareProfile {
+        cpu_cores,
+        inference_threads: (cpu_cores / 2).clamp(1, 8),
+        simd: Simd::detect(),
+        ram_mb: (total > 0).then(|| total / (1024 * 1024)),
+        gpu: nvidia_gpu(),
+        cuda_compiled: cfg!(feature = "cuda"),
+    }
+}
+
+/// Per-model cost table, smallest first.
+struct ModelCost {
+    size: &'static str,
+    /// ms for a 5 s utterance with 4 AVX2 threads.
+    cpu_ms: f64,
+    /// ms for a 5 s utterance on a mid-range CUDA GPU.
+    gpu_ms: f64,
+    /// Memory the loaded model needs (RAM on CPU, VRAM on GPU).
+    memory_mb: u64,
+}
+
+const MODEL_COSTS: &[ModelCost] = &[
+    ModelCost { size: "tiny", cpu_ms: 250.0, gpu_ms: 60.0, memory_mb: 400 },
+    ModelCost { size: "base", cpu_ms: 500.0, gpu_ms: 90.0, memory_mb: 550 },
+    ModelCost { size: "small", cpu_ms: 1600.0, gpu_ms: 200.0, memory_mb: 1100 },
+    ModelCost { size: "large-v3-turbo", cpu_ms: 2800.0, gpu_ms: 300.0, memory_mb: 1700 },
+    ModelCost { size: "large-v3", cpu_ms: 9000.0, gpu_ms: 700.0, memory_mb: 4200 },
+];
+
+/// Thread count the `cpu_ms` figures were measured with.
+const REFERENCE_THREADS: f64 = 4.0;
+/// Share of RAM a model may take; the rest is the OS, the app and the LLM.
+const RAM_BUDGET: f64 = 0.5;
+/// Share of VRAM a model may take (the driver and display keep some).
+const VRAM_BUDGET: f64 = 0.8;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelEstimate {
+    pub model_size: &'static str,
+    pub estimated_ms: u64,
+    /// Fits in the memory available to it.
+    pub fits: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SttRecommendation {
+    pub model_size: &'static str,
+    /// Run it on the GPU (`voice.sttUseGpu`).
+    pub use_gpu: bool,
+    pub estimated_ms: u64,
+    pub target_ms: u64,
+    /// One sentence for the UI.
+    pub reason: String,
+    /// Every model, smallest first, on the chosen device.
+    pub estimates: Vec<ModelEstimate>,
+}
+
+fn estimate(profile: &HardwareProfile, cost: &ModelCost, gpu: bool) -> ModelEstimate {
+    if gpu {
+        let vram = profile.gpu.as_ref().and_then(|g| g.vram_mb);
+        return ModelEstimate {
+            model_size: cost.size,
+            estimated_ms: cost.gpu_ms.round() as u64,
+            // Unknown VRAM: trust the model to fit, as the settings do.
+            fits: vram.is_none_or(|v| cost.memory_mb as f64 <= v as f64 * VRAM_BUDGET),
+        };
+    }
+    // More threads help, but less than linearly.
+    let threads = profile.inference_threads.max(1) as f64;
+    let speedup = (threads / REFERENCE_THREADS).powf(0.8);
+    let ms = cost.cpu_ms * profile.simd.slowdown() / speedup;
+    ModelEstimate {
+        model_size: cost.size,
+        estimated_ms: ms.round() as u64,
+        fits: profile
+            .ram_mb
+            .is_none_or(|r| cost.memory_mb as f64 <= r as f64 * RAM_BUDGET),
+    }
+}
+
+/// Pick the largest whisper model expected to transcribe a typical
+/// utterance within `target_ms`. Falls back to "tiny" when nothing does.
+pub fn recommend(profile: &HardwareProfile, target_ms: u64) -> SttRecommendation {
+    let use_gpu = profile.cuda_usable();
+    let estimates: Vec<ModelEstimate> = MODEL_COSTS
+        .iter()
+        .map(|cost| estimate(profile, cost, use_gpu))
+        .collect();
+    let pick = estimates
+        .iter()
+        .rev()
+        .find(|e| e.fits && e.estimated_ms <= target_ms);
+    let device = if use_gpu { "on the GPU" } else { "on the CPU" };
+    let (chosen, reason) = match pick {
+        Some(e) => (
+            e.clone(),
+            format!(
+                "{} should transcribe in about {:.1} s {}",
+                e.model_size,
+                e.estimated_ms as f64 / 1000.0,
+                device
+            ),
+        ),
+        None => (
+            estimates[0].clone(),
+            format!(
+                "No model meets the {:.1} s target {}; tiny is the fastest",
+                target_ms as f64 / 1000.0,
+                device
+            ),
+        ),
+    };
+    SttRecommendation {
+        model_size: chosen.model_size,
+        use_gpu,
+        estimated_ms: chosen.estimated_ms,
+        target_ms,
+        reason,
+        estimates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpu(threads: usize, simd: Simd, ram_mb: u64) -> HardwareProfile {
+        HardwareProfile {
+            cpu_cores: threads * 2,
+            inference_threads: threads,
+            simd,
+            ram_mb: Some(ram_mb),
+            gpu: None,
+            cuda_compiled: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_nvidia_smi() {
+        let gpu = parse_nvidia_smi("NVIDIA GeForce RTX 5070 Ti, 16303 MiB, 581.80").unwrap();
+        assert_eq!(gpu.name, "NVIDIA GeForce RTX 5070 Ti");
+        assert_eq!(gpu.vram_mb, Some(16303));
+        assert_eq!(gpu.driver_version, "581.80");
+        assert!(parse_nvidia_smi("  ").is_none());
+    }
+
+    #[test]
+    fn test_recommend_scales_with_cpu() {
+        let desktop = recommend(&cpu(8, Simd::Avx2, 32_000), 1500);
+        assert_eq!(desktop.model_size, "small");
+        assert!(!desktop.use_gpu);
+
+        let laptop = recommend(&cpu(2, Simd::Avx2, 8_000), 1500);
+        assert_eq!(laptop.model_size, "base");
+
+        let old = recommend(&cpu(1, Simd::None, 4_000), 1500);
+        assert_eq!(old.model_size, "tiny");
+        assert!(old.estimated_ms > 1500);
+        assert!(old.reason.contains("No model"));
+    }
+
+    #[test]
+    fn test_recommend_respects_memory() {
+        // Fast enough for large-v3 with a generous target, but 3 GB of RAM
+        // leaves room for small at most.
+        let rec = recommend(&cpu(8, Simd::Avx2, 3_000), 60_000);
+        assert_eq!(rec.model_size, "small");
+    }
+
+    #[test]
+    fn test_recommend_gpu() {
+        let mut profile = cpu(4, Simd::Avx2, 16_000);
+        profile.gpu = Some(NvidiaGpu {
+            name: "RTX".into(),
+            vram_mb: Some(12_000),
+            driver_version: String::new(),
+        });
+        // Not built with CUDA: the GPU doesn't count.
+        assert!(!recommend(&profile, 1500).use_gpu);
+
+        profile.cuda_compiled = true;
+        let rec = recommend(&profile, 1500);
+        assert!(rec.use_gpu);
+        assert_eq!(rec.model_size, "large-v3");
+
+        profile.gpu.as_mut().unwrap().vram_mb = Some(2_000);
+        assert_eq!(recommend(&profile, 1500).model_size, "small");
+    }
+}
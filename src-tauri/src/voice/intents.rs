@@ -0,0 +1,409 @@
+//! Local voice commands.
+//!
+//! Before a transcription goes to the AI provider, it is matched against a
+//! small command grammar. A match ("stop talking", "switch to wake word
+//! mode", "louder", "repeat that", "continue", "use the robot voice") is
+//! carried out here and emitted as a
+//! `LocalCommand` voice event instead of a `Transcription`, so it never
+//! reaches the LLM.
+//!
+//! Only a whole utterance counts: "stop talking about the weather" is a
+//! normal message. Case, punctuation and polite filler ("please", "okay")
+//! are ignored. `voice.commands` adds phrases for the same actions
+//! (`{ phrase, action }`) and `voice.localCommands: false` turns matching
+//! off.
+
+use tauri::{AppHandle, Manager};
+
+use super::pipeline::VoiceEvent;
+use super::VoiceMode;
+use crate::commands::voice::VoiceEngineState;
+use crate::config::schema::VoiceCommandConfig;
+use crate::error::AppError;
+use crate::services::event_throttle::EmitThrottled;
+
+/// Volume change per "louder" / "quieter".
+const VOLUME_STEP: f32 = 0.2;
+/// "Quieter" stops here so "louder" can still be heard.
+const MIN_VOLUME: f32 = 0.2;
+const MAX_VOLUME: f32 = 2.0;
+
+/// Words dropped from either end before matching.
+const FILLER: &[&str] = &["please", "okay", "ok", "hey", "um", "uh", "now"];
+
+/// What a local command does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intent {
+    StopSpeaking,
+    PushToTalkMode,
+    ToggleMode,
+    WakeWordMode,
+    Louder,
+    Quieter,
+    Repeat,
+    Continue,
+    RobotVoice,
+    ChipmunkVoice,
+    DeepVoice,
+    EchoVoice,
+    NormalVoice,
+}
+
+impl Intent {
+    const ALL: [Intent; 13] = [
+        Intent::StopSpeaking,
+        Intent::PushToTalkMode,
+        Intent::ToggleMode,
+        Intent::WakeWordMode,
+        Intent::Louder,
+        Intent::Quieter,
+        Intent::Repeat,
+        Intent::Continue,
+        Intent::RobotVoice,
+        Intent::ChipmunkVoice,
+        Intent::DeepVoice,
+        Intent::EchoVoice,
+        Intent::NormalVoice,
+    ];
+
+    /// The action name used in `voice.commands` and the `LocalCommand`
+    /// event.
+    pub fn name(self) -> &'static str {
+        match self {
+            Intent::StopSpeaking => "stopSpeaking",
+            Intent::PushToTalkMode => "pushToTalkMode",
+            Intent::ToggleMode => "toggleMode",
+            Intent::WakeWordMode => "wakeWordMode",
+            Intent::Louder => "louder",
+            Intent::Quieter => "quieter",
+            Intent::Repeat => "repeat",
+            Intent::Continue => "continue",
+            Intent::RobotVoice => "robotVoice",
+            Intent::ChipmunkVoice => "chipmunkVoice",
+            Intent::DeepVoice => "deepVoice",
+            Intent::EchoVoice => "echoVoice",
+            Intent::NormalVoice => "normalVoice",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Intent> {
+        Intent::ALL.into_iter().find(|i| i.name() == name.trim())
+    }
+
+    /// Built-in phrases, already normalized.
+    fn phrases(self) -> &'static [&'static str] {
+        match self {
+            Intent::StopSpeaking => &[
+                "stop",
+                "stop talking",
+                "stop speaking",
+                "be quiet",
+                "quiet",
+                "shush",
+                "shut up",
+                "thats enough",
+            ],
+            Intent::PushToTalkMode => &[
+                "push to talk mode",
+                "switch to push to talk",
+                "switch to push to talk mode",
+            ],
+            Intent::ToggleMode => &["toggle mode", "switch to toggle", "switch to toggle mode"],
+            Intent::WakeWordMode => &[
+                "wake word mode",
+                "switch to wake word",
+                "switch to wake word mode",
+            ],
+            Intent::Louder => &[
+                "louder",
+                "speak up",
+                "volume up",
+                "turn it up",
+                "increase volume",
+                "increase the volume",
+            ],
+            Intent::Quieter => &[
+                "quieter",
+                "softer",
+                "volume down",
+                "turn it down",
+                "decrease volume",
+                "lower the volume",
+            ],
+            Intent::Repeat => &[
+                "repeat",
+                "repeat that",
+                "say that again",
+                "come again",
+                "what did you say",
+            ],
+            Intent::Continue => &[
+                "continue",
+                "go on",
+                "keep going",
+                "carry on",
+                "resume",
+                "where were you",
+            ],
+            Intent::RobotVoice => &["robot voice", "use the robot voice", "use your robot voice"],
+            Intent::ChipmunkVoice => &[
+                "chipmunk voice",
+                "use the chipmunk voice",
+                "use your chipmunk voice",
+            ],
+            Intent::DeepVoice => &["deep voice", "use the deep voice", "use your deep voice"],
+            Intent::EchoVoice => &["echo voice", "use the echo voice", "add some echo"],
+            Intent::NormalVoice => &[
+                "normal voice",
+                "use your normal voice",
+                "use the normal voice",
+                "turn off the voice effect",
+                "no voice effect",
+            ],
+        }
+    }
+
+    fn mode(self) -> Option<VoiceMode> {
+        match self {
+            Intent::PushToTalkMode => Some(VoiceMode::PushToTalk),
+            Intent::ToggleMode => Some(VoiceMode::Toggle),
+            Intent::WakeWordMode => Some(VoiceMode::WakeWord),
+            _ => None,
+        }
+    }
+
+    /// The `tts::effects` preset a voice-effect command switches to.
+    fn effect_preset(self) -> Option<&'static str> {
+        match self {
+            Intent::RobotVoice => Some("robot"),
+            Intent::ChipmunkVoice => Some("chipmunk"),
+            Intent::DeepVoice => Some("deep"),
+            Intent::EchoVoice => Some("echo"),
+            Intent::NormalVoice => Some("normal"),
+            _ => None,
+        }
+    }
+}
+
+/// Lowercase words with punctuation and surrounding filler removed.
+/// Apostrophes are dropped ("that's" -> "thats"), other punctuation
+/// separates words ("push-to-talk" -> "push to talk").
+pub fn normalize(text: &str) -> String {
+    let cleaned: String = text
+        .to_lowercase()
+        .chars()
+        .filter(|c| *c != '\'' && *c != '’')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    while words.first().is_some_and(|w| FILLER.contains(w)) {
+        words.remove(0);
+    }
+    while words.last().is_some_and(|w| FILLER.contains(w)) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+/// Phrases to match, user phrases first.
+#[derive(Debug, Clone)]
+pub struct IntentGrammar {
+    phrases: Vec<(String, Intent)>,
+}
+
+impl IntentGrammar {
+    /// The built-in phrases plus `custom`. Entries with an unknown action
+    /// or an empty phrase are skipped with a warning.
+    pub fn new(custom: &[VoiceCommandConfig]) -> Self {
+        let mut phrases = Vec::new();
+        for entry in custom {
+            let phrase = normalize(&entry.phrase);
+            match Intent::from_name(&entry.action) {
+                Some(intent) if !phrase.is_empty() => phrases.push((phrase, intent)),
+                _ => tracing::warn!(
+                    phrase = %entry.phrase,
+                    action = %entry.action,
+                    "Ignoring voice command with an empty phrase or unknown action"
+                ),
+            }
+        }
+        for intent in Intent::ALL {
+            phrases.extend(intent.phrases().iter().map(|p| (p.to_string(), intent)));
+        }
+        Self { phrases }
+    }
+
+    /// The command `text` is, if any.
+    pub fn recognize(&self, text: &str) -> Option<Intent> {
+        let text = normalize(text);
+        if text.is_empty() {
+            return None;
+        }
+        self.phrases
+            .iter()
+            .find(|(phrase, _)| *phrase == text)
+            .map(|(_, intent)| *intent)
+    }
+}
+
+/// Carry out `intent` and emit `LocalCommand`. Locks the voice engine, so
+/// call it off the processing loop.
+pub(crate) fn run(app: &AppHandle, intent: Intent, text: String) {
+    match execute(app, intent) {
+        Ok(()) => {
+            tracing::info!(intent = intent.name(), text = %text, "Local voice command");
+            let _ = app.emit_throttled(
+                "voice-event",
+                VoiceEvent::LocalCommand {
+                    intent: intent.name().into(),
+                    text,
+                },
+            );
+        }
+        Err(e) => {
+            tracing::warn!(intent = intent.name(), "Local voice command failed: {}", e);
+            let _ = app.emit_throttled("voice-event", VoiceEvent::error(e));
+        }
+    }
+}
+
+fn execute(app: &AppHandle, intent: Intent) -> Result<(), AppError> {
+    let state = app.state::<VoiceEngineState>();
+    let mut engine = state
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock voice state: {}", e)))?;
+    if let Some(mode) = intent.mode() {
+        engine.set_mode(mode);
+        drop(engine);
+        crate::services::hotkeys::save_mode(app, mode);
+        return Ok(());
+    }
+    if let Some(name) = intent.effect_preset() {
+        let effects = super::tts::effects::preset(name).unwrap_or_default();
+        engine.set_tts_effects(effects.clone());
+        drop(engine);
+        let saved = crate::commands::config::set_config(serde_json::json!({
+            "voice": { "ttsEffects": effects },
+        }));
+        if let Some(e) = saved.error {
+            tracing::warn!("Failed to save voice effects: {}", e);
+        }
+        return Ok(());
+    }
+    match intent {
+        Intent::StopSpeaking => engine.stop_speaking(),
+        Intent::Louder | Intent::Quieter => {
+            let step = if intent == Intent::Louder {
+                VOLUME_STEP
+            } else {
+                -VOLUME_STEP
+            };
+            let volume = step_volume(engine.config().tts_volume, step);
+            engine.set_tts_volume(volume);
+            drop(engine);
+            let saved = crate::commands::config::set_config(serde_json::json!({
+                "voice": { "ttsVolume": volume },
+            }));
+            if let Some(e) = saved.error {
+                tracing::warn!("Failed to save TTS volume: {}", e);
+            }
+        }
+        Intent::Repeat => {
+            let text = engine
+                .last_spoken()
+                .ok_or_else(|| AppError::Tts("Nothing to repeat yet".into()))?;
+            engine.speak_blocking(text)?;
+        }
+        Intent::Continue => engine.resume_speaking()?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// `volume` moved by `step`, rounded to a tenth and kept in range.
+fn step_volume(volume: f32, step: f32) -> f32 {
+    ((volume + step) * 10.0)
+        .round()
+        .clamp(MIN_VOLUME * 10.0, MAX_VOLUME * 10.0)
+        / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recognize_builtin() {
+        let grammar = IntentGrammar::new(&[]);
+        assert_eq!(
+            grammar.recognize("Stop talking."),
+            Some(Intent::StopSpeaking)
+        );
+        assert_eq!(
+            grammar.recognize(" Okay, louder please! "),
+            Some(Intent::Louder)
+        );
+        assert_eq!(
+            grammar.recognize("Switch to push-to-talk mode."),
+            Some(Intent::PushToTalkMode)
+        );
+        assert_eq!(
+            grammar.recognize("That's enough"),
+            Some(Intent::StopSpeaking)
+        );
+        assert_eq!(grammar.recognize("Repeat that?"), Some(Intent::Repeat));
+        assert_eq!(grammar.recognize("OK, go on."), Some(Intent::Continue));
+        assert_eq!(
+            grammar.recognize("Use the robot voice."),
+            Some(Intent::RobotVoice)
+        );
+        assert_eq!(
+            grammar.recognize("Okay, use your normal voice"),
+            Some(Intent::NormalVoice)
+        );
+        assert_eq!(grammar.recognize("Stop talking about the weather"), None);
+        assert_eq!(grammar.recognize("..."), None);
+    }
+
+    #[test]
+    fn test_custom_commands() {
+        let custom = [
+            VoiceCommandConfig {
+                phrase: "Hush, computer".into(),
+                action: "stopSpeaking".into(),
+            },
+            VoiceCommandConfig {
+                phrase: "dance".into(),
+                action: "party".into(),
+            },
+        ];
+        let grammar = IntentGrammar::new(&custom);
+        assert_eq!(
+            grammar.recognize("hush computer"),
+            Some(Intent::StopSpeaking)
+        );
+        assert_eq!(grammar.recognize("dance"), None);
+        assert_eq!(
+            Intent::from_name("wakeWordMode"),
+            Some(Intent::WakeWordMode)
+        );
+    }
+
+    #[test]
+    fn test_effect_presets_exist() {
+        for intent in Intent::ALL {
+            if let Some(name) = intent.effect_preset() {
+                assert!(super::super::tts::effects::preset(name).is_some(), "{}", name);
+            }
+        }
+        assert_eq!(Intent::NormalVoice.effect_preset(), Some("normal"));
+        assert_eq!(Intent::Louder.effect_preset(), None);
+    }
+
+    #[test]
+    fn test_step_volume() {
+        assert_eq!(step_volume(1.0, VOLUME_STEP), 1.2);
+        assert_eq!(step_volume(0.3, -VOLUME_STEP), 0.2);
+        assert_eq!(step_volume(1.9, VOLUME_STEP), 2.0);
+    }
+}
@@ -0,0 +1,378 @@
+//! Latency budget and degradation ladder (`voice.latencyBudgetMs`).
+//!
+//! The budget covers the part of a turn the voice pipeline controls:
+//! recording stop -> STT done, plus reply -> first audio (the provider's own
+//! response time is left out, since no voice setting can shorten it). When
+//! `STRIKES` spoken turns in a row go over budget, the pipeline steps one
+//! rung down the ladder and restarts with the cheaper settings:
+//!
+//! 1. `stt_model`: the next smaller whisper model already downloaded
+//! 2. `stt_context`: no recent-transcript prompt for whisper
+//! 3. `tts_lookahead`: synthesize one phrase ahead instead of several
+//!
+//! A rung that wouldn't change anything (cloud STT, no smaller model on
+//! disk, lookahead already 1) is skipped. Each step is logged and emitted as
+//! `VoiceEvent::LatencyLadder`. The level survives pipeline restarts but not
+//! an app restart, and the user's settings are never rewritten: the engine
+//! applies the ladder to its config at each start.
+//!
+//! `set_latency_ladder` overrides it by hand: a level pins the ladder there
+//! (no automatic steps), `null` goes back to automatic from the top.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use tauri::{AppHandle, Manager};
+
+use super::pipeline::timing::{PipelineTiming, TurnOutcome};
+use super::pipeline::VoiceEvent;
+use super::stt;
+use super::VoiceEngineConfig;
+use crate::commands::voice::VoiceEngineState;
+use crate::config::voice_engine::changed_fields;
+use crate::services::event_throttle::EmitThrottled;
+
+/// Consecutive over-budget turns before stepping down.
+pub const STRIKES: u32 = 3;
+
+/// One step down the ladder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rung {
+    SttModel,
+    SttContext,
+    TtsLookahead,
+}
+
+/// The ladder, top rung first.
+pub const LADDER: [Rung; 3] = [Rung::SttModel, Rung::SttContext, Rung::TtsLookahead];
+
+impl Rung {
+    /// Apply this rung to `config`. `has_model` says whether a whisper size
+    /// is downloaded.
+    fn apply(self, config: &mut VoiceEngineConfig, has_model: &dyn Fn(&str) -> bool) {
+        match self {
+            Rung::SttModel => {
+                if !matches!(
+                    config.stt_adapter.as_str(),
+                    "whisper-local" | "whisper" | "faster-whisper"
+                ) {
+                    return;
+                }
+                let Some(current) = stt::WHISPER_MODEL_SIZES
+                    .iter()
+                    .position(|s| *s == config.stt_model_size)
+                else {
+                    return;
+                };
+                if let Some(smaller) = stt::WHISPER_MODEL_SIZES[..current]
+                    .iter()
+                    .rev()
+                    .find(|s| has_model(s))
+                {
+                    config.stt_model_size = smaller.to_string();
+                }
+            }
+            Rung::SttContext => config.stt_context_words = 0,
+            Rung::TtsLookahead => config.tts_lookahead = config.tts_lookahead.min(1),
+        }
+    }
+
+    /// `config` after this rung, or `None` when it changes nothing.
+    fn step(
+        self,
+        config: &VoiceEngineConfig,
+        has_model: &dyn Fn(&str) -> bool,
+    ) -> Option<VoiceEngineConfig> {
+        let mut next = config.clone();
+        self.apply(&mut next, has_model);
+        (!changed_fields(config, &next).is_empty()).then_some(next)
+    }
+
+    /// What this rung changed, for the event and the log.
+    fn describe(self, before: &VoiceEngineConfig, after: &VoiceEngineConfig) -> String {
+        match self {
+            Rung::SttModel => format!(
+                "STT model {} -> {}",
+                before.stt_model_size, after.stt_model_size
+            ),
+            Rung::SttContext => "STT prompt context off".into(),
+            Rung::TtsLookahead => format!(
+                "TTS lookahead {} -> {}",
+                before.tts_lookahead, after.tts_lookahead
+            ),
+        }
+    }
+}
+
+/// A rung the ladder stepped down to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Step {
+    /// Rungs applied after this step.
+    pub level: usize,
+    pub rung: Rung,
+    pub detail: String,
+}
+
+/// `config` with the first `level` rungs applied.
+pub fn apply(
+    config: &VoiceEngineConfig,
+    level: usize,
+    has_model: &dyn Fn(&str) -> bool,
+) -> VoiceEngineConfig {
+    let mut degraded = config.clone();
+    for rung in LADDER.iter().take(level) {
+        rung.apply(&mut degraded, has_model);
+    }
+    degraded
+}
+
+/// The rungs that change `config` at `level`, with what each changed.
+pub fn steps(
+    config: &VoiceEngineConfig,
+    level: usize,
+    has_model: &dyn Fn(&str) -> bool,
+) -> Vec<Step> {
+    let mut steps = Vec::new();
+    let mut current = config.clone();
+    for (i, rung) in LADDER.iter().enumerate().take(level) {
+        if let Some(next) = rung.step(&current, has_model) {
+            steps.push(Step {
+                level: i + 1,
+                rung: *rung,
+                detail: rung.describe(&current, &next),
+            });
+            current = next;
+        }
+    }
+    steps
+}
+
+/// The first rung below `level` that changes `current` (a config already
+/// degraded to `level`), or `None` at the bottom.
+fn next_step(
+    current: &VoiceEngineConfig,
+    level: usize,
+    has_model: &dyn Fn(&str) -> bool,
+) -> Option<Step> {
+    LADDER.iter().enumerate().skip(level).find_map(|(i, rung)| {
+        let next = rung.step(current, has_model)?;
+        Some(Step {
+            level: i + 1,
+            rung: *rung,
+            detail: rung.describe(current, &next),
+        })
+    })
+}
+
+/// The part of a spoken turn's latency the pipeline controls, in ms.
+/// `None` for turns whose reply didn't play to the end: an interrupted one
+/// closes when the next recording starts, which a restart would cut off.
+pub fn voice_latency_ms(timing: &PipelineTiming) -> Option<u64> {
+    if timing.outcome != TurnOutcome::Spoken {
+        return None;
+    }
+    Some(timing.stt_wait_ms? + timing.stt_ms? + timing.tts_first_audio_ms?)
+}
+
+/// Ladder position. Global so it survives pipeline restarts.
+#[derive(Debug, Default)]
+struct Ladder {
+    level: usize,
+    pinned: bool,
+    strikes: u32,
+}
+
+impl Ladder {
+    /// Count a measured turn. True when it was the `STRIKES`th over-budget
+    /// turn in a row and the ladder may step.
+    fn observe(&mut self, latency_ms: u64, budget_ms: u64) -> bool {
+        if latency_ms <= budget_ms {
+            self.strikes = 0;
+            return false;
+        }
+        self.strikes += 1;
+        if self.pinned || self.strikes < STRIKES {
+            return false;
+        }
+        self.strikes = 0;
+        true
+    }
+}
+
+static STATE: Mutex<Ladder> = Mutex::new(Ladder {
+    level: 0,
+    pinned: false,
+    strikes: 0,
+});
+
+/// Whether the whisper model `size` is downloaded for `language`.
+fn model_downloaded(size: &str, language: &str) -> bool {
+    crate::services::platform::get_data_dir()
+        .join("models")
+        .join(stt::model_filename_for_language(size, language))
+        .exists()
+}
+
+fn has_model_for(config: &VoiceEngineConfig) -> impl Fn(&str) -> bool + '_ {
+    move |size| model_downloaded(size, &config.stt_language)
+}
+
+/// The config a pipeline start should use: `config` with the current
+/// ladder level applied.
+pub fn effective_config(config: &VoiceEngineConfig) -> VoiceEngineConfig {
+    let level = STATE.lock().map(|s| s.level).unwrap_or(0);
+    if level == 0 {
+        return config.clone();
+    }
+    let has_model = has_model_for(config);
+    let degraded = apply(config, level, &has_model);
+    for step in steps(config, level, &has_model) {
+        tracing::info!(level = step.level, "Latency ladder: {}", step.detail);
+    }
+    degraded
+}
+
+/// Count a finished turn against `config.latency_budget_ms`, `config`
+/// being the running pipeline's (already degraded) config. Returns the step
+/// to take when the budget has been blown `STRIKES` times in a row and a
+/// rung is left; the ladder has then already moved to it.
+pub(crate) fn observe(config: &VoiceEngineConfig, timing: &PipelineTiming) -> Option<Step> {
+    if config.latency_budget_ms == 0 {
+        return None;
+    }
+    let latency = voice_latency_ms(timing)?;
+    let mut state = STATE.lock().ok()?;
+    if !state.observe(latency, config.latency_budget_ms) {
+        return None;
+    }
+    let step = next_step(config, state.level, &has_model_for(config));
+    match &step {
+        Some(step) => state.level = step.level,
+        None => tracing::warn!(
+            latency_ms = latency,
+            budget_ms = config.latency_budget_ms,
+            "Over the latency budget with no rung left to step down"
+        ),
+    }
+    step
+}
+
+/// Restart the running pipeline so it picks up the new level. Runs on its
+/// own thread: the caller may be one of the pipeline's.
+pub(crate) fn restart_pipeline(app: AppHandle) {
+    std::thread::spawn(move || {
+        let state = app.state::<VoiceEngineState>();
+        let Ok(mut engine) = state.lock() else {
+            return;
+        };
+        let config = engine.config().clone();
+        if let Err(e) = engine.restart_with(config, app.clone()) {
+            tracing::error!("Failed to restart voice after a latency step: {}", e);
+            let _ = app.emit_throttled("voice-event", VoiceEvent::error(e));
+        }
+    });
+}
+
+/// Ladder status for `get_latency_ladder`: `(level, pinned, strikes)`.
+pub fn status() -> (usize, bool, u32) {
+    STATE
+        .lock()
+        .map(|s| (s.level, s.pinned, s.strikes))
+        .unwrap_or((0, false, 0))
+}
+
+/// Pin the ladder at `level` (clamped to the ladder length), or with
+/// `None` go back to automatic stepping from the top. Returns the level.
+pub fn set_override(level: Option<usize>) -> usize {
+    let Ok(mut state) = STATE.lock() else {
+        return 0;
+    };
+    state.level = level.unwrap_or(0).min(LADDER.len());
+    state.pinned = level.is_some();
+    state.strikes = 0;
+    state.level
+}
+
+/// The rungs `config` is degraded by at the current level.
+pub fn applied_steps(config: &VoiceEngineConfig) -> Vec<Step> {
+    let (level, _, _) = status();
+    steps(config, level, &has_model_for(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(size: &str) -> VoiceEngineConfig {
+        VoiceEngineConfig {
+            stt_adapter: "whisper-local".into(),
+            stt_model_size: size.into(),
+            stt_context_words: 40,
+            tts_lookahead: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ladder_steps_skip_noops() {
+        let only_tiny = |size: &str| size == "tiny";
+        let small = config("small");
+
+        let first = next_step(&small, 0, &only_tiny).unwrap();
+        assert_eq!(first.rung, Rung::SttModel);
+        assert_eq!(first.detail, "STT model small -> tiny");
+        assert_eq!(apply(&small, 1, &only_tiny).stt_model_size, "tiny");
+
+        // Already on the smallest model: the first step is the context rung
+        let tiny = config("tiny");
+        let first = next_step(&tiny, 0, &only_tiny).unwrap();
+        assert_eq!((first.level, first.rung), (2, Rung::SttContext));
+
+        let bottom = apply(&small, LADDER.len(), &only_tiny);
+        assert_eq!(bottom.stt_context_words, 0);
+        assert_eq!(bottom.tts_lookahead, 1);
+        assert!(next_step(&bottom, LADDER.len(), &only_tiny).is_none());
+        assert_eq!(steps(&tiny, LADDER.len(), &only_tiny).len(), 2);
+    }
+
+    #[test]
+    fn test_strikes_must_be_consecutive() {
+        let mut ladder = Ladder::default();
+        assert!(!ladder.observe(900, 800));
+        assert!(!ladder.observe(900, 800));
+        assert!(!ladder.observe(700, 800));
+        assert!(!ladder.observe(900, 800));
+        assert!(!ladder.observe(900, 800));
+        assert!(ladder.observe(900, 800));
+        assert_eq!(ladder.strikes, 0);
+
+        ladder.pinned = true;
+        for _ in 0..STRIKES {
+            assert!(!ladder.observe(900, 800));
+        }
+    }
+
+    #[test]
+    fn test_voice_latency_leaves_out_provider_time() {
+        let timing = PipelineTiming {
+            turn: 1,
+            finished_at: 0,
+            outcome: TurnOutcome::Spoken,
+            stt_wait_ms: Some(20),
+            stt_ms: Some(500),
+            response_ms: Some(4000),
+            tts_first_audio_ms: Some(300),
+            playback_ms: Some(2000),
+            total_ms: 6820,
+        };
+        assert_eq!(voice_latency_ms(&timing), Some(820));
+        let unanswered = PipelineTiming {
+            outcome: TurnOutcome::NoResponse,
+            ..timing
+        };
+        assert_eq!(voice_latency_ms(&unanswered), None);
+    }
+}
@@ -8,13 +8,37 @@
 //! - Text-to-Speech (TTS) via Edge TTS HTTP API
 //! - Full voice pipeline orchestrating Mic -> VAD -> STT -> event -> TTS -> Speaker
 
+pub mod ask;
+pub mod calibration;
+pub mod capabilities;
+pub mod confirm;
+pub mod denoise;
+pub mod errors;
+pub mod hardware;
+pub mod intents;
+pub mod latency_budget;
+pub mod output;
 pub mod pipeline;
+pub mod resample;
+pub mod routing;
+pub mod session;
+pub mod speaker;
+pub mod streaming_stt;
 pub mod stt;
+pub mod stt_context;
+pub mod transcript_rules;
 pub mod tts;
 pub mod vad;
+pub mod vad_corpus;
 
+use std::sync::Arc;
+
+use crate::error::AppError;
 use serde::{Deserialize, Serialize};
 
+pub use pipeline::export::SpeechFile;
+pub use pipeline::speech_queue::SpeechPriority;
+
 // ── Voice State ─────────────────────────────────────────────────────
 
 /// Current state of the voice engine pipeline.
@@ -88,6 +112,32 @@ impl VoiceMode {
     }
 }
 
+// ── Engine Loading ──────────────────────────────────────────────────
+
+/// When an STT or TTS engine is loaded (`voice.sttLoad` / `voice.ttsLoad`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EngineLoadMode {
+    /// On first use: the first recording or reply waits for the load.
+    Lazy,
+    /// Right after the pipeline starts, without holding up the start.
+    #[default]
+    Background,
+    /// Before the pipeline reports ready.
+    Blocking,
+}
+
+impl EngineLoadMode {
+    /// Parse a config value; unknown values fall back to `Background`.
+    pub fn parse(s: &str) -> Self {
+        match s.trim() {
+            "lazy" => Self::Lazy,
+            "blocking" => Self::Blocking,
+            _ => Self::Background,
+        }
+    }
+}
+
 // ── Voice Config ────────────────────────────────────────────────────
 
 /// Runtime configuration for the voice engine.
@@ -105,9 +155,28 @@ pub struct VoiceEngineConfig {
     /// STT model size for local whisper (e.g., "tiny", "base", "small").
     pub stt_model_size: String,
 
-    /// Whether to use GPU acceleration for STT inference (CUDA).
+    /// Whether to use GPU acceleration for STT inference. Uses the whisper.cpp
+    /// backend compiled in (CUDA/Metal/Vulkan), falling back to CPU if none is
+    /// available or GPU initialization fails.
     pub stt_use_gpu: bool,
 
+    /// STT language as an ISO 639-1 code (e.g., "en", "de"), or "auto" to
+    /// let whisper detect it. Non-English selects the multilingual model.
+    pub stt_language: String,
+
+    /// Transcribe in the spoken language, or translate to English.
+    pub stt_task: stt::SttTask,
+
+    /// Words of recent transcriptions carried into the next whisper decode
+    /// as its initial prompt. 0 = off.
+    pub stt_context_words: usize,
+
+    /// Fixed text leading every whisper prompt. Empty = none.
+    pub stt_initial_prompt: String,
+
+    /// Domain terms (names, jargon) added to every whisper prompt.
+    pub stt_vocabulary: Vec<String>,
+
     /// TTS adapter name (e.g., "edge", "kokoro", "openai-tts").
     pub tts_adapter: String,
 
@@ -120,17 +189,129 @@ pub struct VoiceEngineConfig {
     /// TTS playback volume (0.0 - 1.0).
     pub tts_volume: f32,
 
+    /// Voice effect chain applied to TTS audio, e.g. `["pitch:-5",
+    /// "reverb"]` (see `tts::effects`). Empty = none.
+    pub tts_effects: Vec<String>,
+
+    /// Silence inserted between streamed TTS phrases, in milliseconds.
+    pub tts_phrase_gap_ms: u32,
+
+    /// Longer silence after sentence-final punctuation, in milliseconds.
+    pub tts_sentence_pause_ms: u32,
+
+    /// Phrases synthesized concurrently ahead of playback.
+    pub tts_lookahead: u32,
+
+    /// Terms that are never spoken aloud. Empty disables the output filter.
+    pub tts_blocked_terms: Vec<String>,
+
+    /// Output filter action on a blocked term: "mask" or "stop".
+    pub tts_filter_action: String,
+
     /// Preferred input device name. None = system default.
     pub input_device: Option<String>,
 
+    /// What to record: "microphone" (`input_device`), "system" (what
+    /// `output_device` is playing, via WASAPI loopback) or "both".
+    pub capture_source: String,
+
     /// Preferred output device name. None = system default.
     pub output_device: Option<String>,
 
+    /// Extra output devices that play the same TTS audio in parallel.
+    pub output_devices: Vec<String>,
+
     /// Silence timeout in seconds before auto-stopping recording.
     pub silence_timeout_secs: f64,
 
+    /// Longest a single recording may run, in seconds, before it is
+    /// force-stopped and sent to STT. 0 = unlimited.
+    pub max_recording_secs: f64,
+
+    /// Longest a toggle-mode recording may run before it is stopped and
+    /// sent to STT. 0 = only `max_recording_secs` applies.
+    pub toggle_timeout_secs: f64,
+
     /// VAD energy threshold for speech detection.
     pub vad_threshold: f32,
+
+    /// Minimum fraction of a recording (0.0 - 1.0) that must be speech
+    /// for STT to run. Below this a `NoSpeechDetected` event is emitted.
+    pub min_speech_coverage: f32,
+
+    /// Duck other applications' audio while recording.
+    pub duck_while_recording: bool,
+
+    /// Fraction of their volume other applications keep while ducked.
+    pub duck_level: f32,
+
+    /// Denoise mic audio before VAD and STT (needs the `noise-suppression`
+    /// build feature).
+    pub noise_suppression: bool,
+
+    /// When idle wake-word listening is duty cycled: "off", "battery"
+    /// (only while on battery power), or "always".
+    pub listen_duty_cycle: String,
+
+    /// Sample rate converter for capture and playback: "linear" or "sinc".
+    pub resampler: String,
+
+    /// How the mic stream presents itself to the OS: "default" or
+    /// "communications" (see `services::audio_session`).
+    pub audio_session: String,
+
+    /// Pipeline sample rate in Hz (see `pipeline::format`).
+    pub sample_rate: u32,
+
+    /// Captured chunk length in ms.
+    pub chunk_ms: u32,
+
+    /// Append transcriptions and spoken replies to the transcript log.
+    pub transcript_log: bool,
+
+    /// Recordings kept in the turn archive (0 = off).
+    pub turn_archive_size: usize,
+
+    /// Codec of archived recordings ("wav", "flac", "opus").
+    pub turn_archive_format: String,
+
+    /// Match transcriptions against the local command grammar.
+    pub local_commands: bool,
+
+    /// User phrases for local commands.
+    pub commands: Vec<crate::config::schema::VoiceCommandConfig>,
+
+    /// Post-processing of transcriptions (`transcriptRules`).
+    pub transcript_rules: crate::config::schema::TranscriptRulesConfig,
+
+    /// Transcription routing table (`transcriptRoutes`).
+    pub transcript_routes: Vec<crate::config::schema::TranscriptRouteConfig>,
+
+    /// When the STT engine is loaded.
+    pub stt_load: EngineLoadMode,
+
+    /// When the TTS engine is loaded.
+    pub tts_load: EngineLoadMode,
+
+    /// Tag transcriptions with the enrolled speaker they sound like.
+    pub speaker_id: bool,
+
+    /// Minimum similarity for a speaker match.
+    pub speaker_threshold: f32,
+
+    /// Drop wake-word turns from voices that match no enrolled speaker.
+    pub ignore_unknown_speakers: bool,
+
+    /// Idle seconds before the conversation session ends (0 = never).
+    pub session_timeout_secs: u64,
+
+    /// Pipeline latency budget per turn in ms (see `latency_budget`).
+    /// 0 = off.
+    pub latency_budget_ms: u64,
+
+    /// Restart the pipeline when the watchdog finds it stalled (see
+    /// `pipeline::watchdog`).
+    pub watchdog_auto_restart: bool,
 }
 
 impl Default for VoiceEngineConfig {
@@ -140,14 +321,53 @@ impl Default for VoiceEngineConfig {
             stt_adapter: "whisper-local".into(),
             stt_model_size: "base".into(),
             stt_use_gpu: false,
+            stt_language: "en".into(),
+            stt_task: stt::SttTask::Transcribe,
+            stt_context_words: 32,
+            stt_initial_prompt: String::new(),
+            stt_vocabulary: Vec::new(),
             tts_adapter: "kokoro".into(),
             tts_voice: "af_bella".into(),
             tts_speed: 1.0,
             tts_volume: 1.0,
+            tts_effects: Vec::new(),
+            tts_phrase_gap_ms: 80,
+            tts_sentence_pause_ms: 250,
+            tts_lookahead: 2,
+            tts_blocked_terms: Vec::new(),
+            tts_filter_action: "mask".into(),
             input_device: None,
+            capture_source: "microphone".into(),
             output_device: None,
+            output_devices: Vec::new(),
             silence_timeout_secs: 2.0,
+            max_recording_secs: 120.0,
+            toggle_timeout_secs: 60.0,
             vad_threshold: 0.01,
+            min_speech_coverage: 0.05,
+            duck_while_recording: false,
+            duck_level: 0.2,
+            noise_suppression: false,
+            listen_duty_cycle: "battery".into(),
+            resampler: "linear".into(),
+            audio_session: "default".into(),
+            sample_rate: 16_000,
+            chunk_ms: 80,
+            transcript_log: true,
+            turn_archive_size: 20,
+            turn_archive_format: "wav".into(),
+            local_commands: true,
+            commands: Vec::new(),
+            transcript_rules: Default::default(),
+            transcript_routes: Vec::new(),
+            stt_load: EngineLoadMode::Background,
+            tts_load: EngineLoadMode::Background,
+            speaker_id: false,
+            speaker_threshold: 0.5,
+            ignore_unknown_speakers: true,
+            session_timeout_secs: 300,
+            latency_budget_ms: 0,
+            watchdog_auto_restart: true,
         }
     }
 }
@@ -163,6 +383,8 @@ pub struct VoiceEngine {
     pipeline: Option<pipeline::VoicePipeline>,
     /// Current engine configuration.
     config: VoiceEngineConfig,
+    /// Conversation sessions; the running pipeline shares it.
+    session: Arc<session::SessionTracker>,
 }
 
 impl Default for VoiceEngine {
@@ -177,6 +399,7 @@ impl VoiceEngine {
         Self {
             pipeline: None,
             config: VoiceEngineConfig::default(),
+            session: Arc::default(),
         }
     }
 
@@ -185,17 +408,25 @@ impl VoiceEngine {
         Self {
             pipeline: None,
             config,
+            session: Arc::default(),
         }
     }
 
-    /// Start the voice pipeline. Returns an error if already running.
-    pub fn start(&mut self, app_handle: tauri::AppHandle) -> Result<(), String> {
-        if self.pipeline.is_some() {
-            return Err("Voice engine is already running".into());
+    /// Start the voice pipeline. Returns an error if already running. A
+    /// stalled pipeline is torn down first.
+    pub fn start(&mut self, app_handle: tauri::AppHandle) -> Result<(), AppError> {
+        if self.is_running() {
+            return Err(AppError::AlreadyRunning);
         }
+        self.stop();
 
-        let pipeline = pipeline::VoicePipeline::start(self.config.clone(), app_handle)?;
+        let pipeline = pipeline::VoicePipeline::start(
+            latency_budget::effective_config(&self.config),
+            app_handle,
+            Arc::clone(&self.session),
+        )?;
         self.pipeline = Some(pipeline);
+        crate::services::runtime_context::set_voice_mode(Some(self.config.mode));
         Ok(())
     }
 
@@ -203,6 +434,7 @@ impl VoiceEngine {
     pub fn stop(&mut self) {
         if let Some(pipeline) = self.pipeline.take() {
             pipeline.stop();
+            crate::services::runtime_context::set_voice_mode(None);
         }
     }
 
@@ -214,6 +446,46 @@ impl VoiceEngine {
             .unwrap_or(false)
     }
 
+    /// Whether the pipeline exists but the watchdog found it stalled.
+    pub fn is_stalled(&self) -> bool {
+        self.pipeline.as_ref().is_some_and(|p| p.is_stalled())
+    }
+
+    /// Whether the running pipeline has the mic released by `pause()`.
+    pub fn is_paused(&self) -> bool {
+        self.pipeline.as_ref().is_some_and(|p| p.is_paused())
+    }
+
+    /// Stop consuming audio and release the microphone without unloading
+    /// the STT/TTS engines (privacy mute, or to free the mic for another
+    /// app).
+    pub fn pause(&mut self) -> Result<(), AppError> {
+        match self.pipeline {
+            Some(ref mut pipeline) => {
+                pipeline.pause();
+                Ok(())
+            }
+            None => Err(AppError::NotRunning),
+        }
+    }
+
+    /// Reopen the microphone after `pause()`.
+    pub fn resume(&mut self) -> Result<(), AppError> {
+        match self.pipeline {
+            Some(ref mut pipeline) => pipeline.resume(),
+            None => Err(AppError::NotRunning),
+        }
+    }
+
+    /// Capture samples lost to ring buffer overruns in this pipeline run
+    /// (0 when stopped).
+    pub fn audio_overflow_samples(&self) -> u64 {
+        self.pipeline
+            .as_ref()
+            .map(|p| p.audio_overflow_samples())
+            .unwrap_or(0)
+    }
+
     /// Get the current voice state.
     pub fn state(&self) -> VoiceState {
         self.pipeline
@@ -227,40 +499,94 @@ impl VoiceEngine {
         self.config.mode = mode;
         if let Some(ref pipeline) = self.pipeline {
             pipeline.set_mode(mode);
+            crate::services::runtime_context::set_voice_mode(Some(mode));
         }
     }
 
     /// Start recording (for PTT press / Toggle start).
-    pub fn start_recording(&self) -> Result<(), String> {
+    pub fn start_recording(&self) -> Result<(), AppError> {
         match self.pipeline {
             Some(ref pipeline) => {
                 pipeline.start_recording();
                 Ok(())
             }
-            None => Err("Voice engine is not running".into()),
+            None => Err(AppError::NotRunning),
         }
     }
 
     /// Stop recording (for PTT release / Toggle stop).
-    pub fn stop_recording(&self) -> Result<(), String> {
+    pub fn stop_recording(&self) -> Result<(), AppError> {
         match self.pipeline {
             Some(ref pipeline) => {
                 pipeline.stop_recording();
                 Ok(())
             }
-            None => Err("Voice engine is not running".into()),
+            None => Err(AppError::NotRunning),
         }
     }
 
     /// Cancel the in-progress recording (discard audio, no transcription).
-    pub fn cancel_recording(&self) -> Result<(), String> {
+    pub fn cancel_recording(&self) -> Result<(), AppError> {
         match self.pipeline {
             Some(ref pipeline) => {
                 pipeline.cancel_recording();
                 Ok(())
             }
-            None => Err("Voice engine is not running".into()),
+            None => Err(AppError::NotRunning),
+        }
+    }
+
+    /// Switch the TTS voice. Applied to the running pipeline (if any) and
+    /// kept in the engine config for the next start.
+    pub fn set_tts_voice(&mut self, voice: &str) -> Result<(), String> {
+        if let Some(ref pipeline) = self.pipeline {
+            pipeline.set_tts_voice(voice)?;
+        }
+        self.config.tts_voice = voice.to_string();
+        Ok(())
+    }
+
+    /// Change the TTS speed multiplier (same semantics as `set_tts_voice`).
+    pub fn set_tts_speed(&mut self, speed: f32) -> Result<(), String> {
+        if let Some(ref pipeline) = self.pipeline {
+            pipeline.set_tts_speed(speed)?;
+        }
+        self.config.tts_speed = speed;
+        Ok(())
+    }
+
+    /// Change the TTS playback volume (same semantics as `set_tts_voice`).
+    pub fn set_tts_volume(&mut self, volume: f32) {
+        if let Some(ref pipeline) = self.pipeline {
+            pipeline.set_tts_volume(volume);
+        }
+        self.config.tts_volume = volume;
+    }
+
+    /// Change the voice effect chain (same semantics as `set_tts_volume`).
+    pub fn set_tts_effects(&mut self, effects: Vec<String>) {
+        if let Some(ref pipeline) = self.pipeline {
+            pipeline.set_tts_effects(effects.clone());
         }
+        self.config.tts_effects = effects;
+    }
+
+    /// The provider replied to the latest message. Links the reply to the
+    /// open voice turn (see `session`).
+    pub fn note_reply(&self, speak: bool) {
+        if let Some(ref pipeline) = self.pipeline {
+            pipeline.note_reply(speak);
+        }
+    }
+
+    /// The conversation session in progress, if any.
+    pub fn session(&self) -> Option<session::SessionInfo> {
+        self.session.current()
+    }
+
+    /// Text of the last utterance the running pipeline spoke.
+    pub fn last_spoken(&self) -> Option<String> {
+        self.pipeline.as_ref()?.last_spoken()
     }
 
     /// Interrupt any in-progress TTS playback.
@@ -270,25 +596,68 @@ impl VoiceEngine {
         }
     }
 
+    /// Pick up an interrupted reply where playback stopped. Requires a
+    /// running pipeline.
+    pub fn resume_speaking(&self) -> Result<(), AppError> {
+        match self.pipeline {
+            Some(ref pipeline) => pipeline.resume_speaking(),
+            None => Err(AppError::NotRunning),
+        }
+    }
+
     /// Speak text using the TTS engine. Requires a running pipeline.
-    pub async fn speak(&self, text: &str) -> Result<(), String> {
+    pub async fn speak(&self, text: &str) -> Result<(), AppError> {
         match self.pipeline {
-            Some(ref pipeline) => pipeline.speak(text).await,
-            None => Err("Voice engine is not running".into()),
+            Some(ref pipeline) => pipeline.speak(text).await.map_err(AppError::Tts),
+            None => Err(AppError::NotRunning),
         }
     }
 
     /// Speak text non-blocking (spawns a tokio task). Requires a running pipeline.
-    pub fn speak_blocking(&self, text: String) -> Result<(), String> {
+    pub fn speak_blocking(&self, text: String) -> Result<(), AppError> {
+        self.speak_with_priority(text, SpeechPriority::Interrupt)
+    }
+
+    /// Speak text non-blocking, interrupting, queueing behind, or yielding
+    /// to speech in progress per `priority`. Requires a running pipeline.
+    pub fn speak_with_priority(&self, text: String, priority: SpeechPriority) -> Result<(), AppError> {
+        self.speak_as(text, priority, None)
+    }
+
+    /// `speak_with_priority` in `voice` instead of the selected TTS voice.
+    /// Requires a running pipeline.
+    pub fn speak_as(
+        &self,
+        text: String,
+        priority: SpeechPriority,
+        voice: Option<String>,
+    ) -> Result<(), AppError> {
         match self.pipeline {
             Some(ref pipeline) => {
-                pipeline.speak_blocking(text);
+                pipeline.speak_as(text, priority, voice);
                 Ok(())
             }
-            None => Err("Voice engine is not running".into()),
+            None => Err(AppError::NotRunning),
         }
     }
 
+    /// Texts waiting in the speech queue.
+    pub fn queued_speech(&self) -> usize {
+        self.pipeline.as_ref().map_or(0, |p| p.queued_speech())
+    }
+
+    /// Synthesize `text` with the configured TTS engine and write it to
+    /// `path` (`.wav` or `.mp3`) instead of playing it. Works whether or not
+    /// the pipeline is running; the returned future doesn't borrow the
+    /// engine, so callers can release the voice state lock before awaiting.
+    pub fn speak_to_file(
+        &self,
+        text: String,
+        path: std::path::PathBuf,
+    ) -> impl std::future::Future<Output = Result<SpeechFile, AppError>> + Send + 'static {
+        pipeline::export::speak_to_file(self.config.clone(), text, path)
+    }
+
     /// Update the engine configuration. Pipeline must be restarted for
     /// changes to take effect.
     pub fn update_config(&mut self, config: VoiceEngineConfig) {
@@ -299,4 +668,50 @@ impl VoiceEngine {
     pub fn config(&self) -> &VoiceEngineConfig {
         &self.config
     }
+
+    /// Replace the configuration, restarting the pipeline if it is running
+    /// so the change takes effect. Returns whether it was restarted.
+    pub fn restart_with(
+        &mut self,
+        config: VoiceEngineConfig,
+        app_handle: tauri::AppHandle,
+    ) -> Result<bool, AppError> {
+        let was_running = self.is_running();
+        self.stop();
+        self.config = config;
+        if was_running {
+            self.start(app_handle)?;
+        }
+        Ok(was_running)
+    }
+
+    /// Switch to the voice profile called `name` (see
+    /// `config::voice_profiles`), restarting the pipeline if it is running.
+    /// Refuses a profile `config::voice_engine::validate` rejects. Returns
+    /// whether the pipeline was restarted.
+    pub fn switch_profile(
+        &mut self,
+        name: &str,
+        app_handle: tauri::AppHandle,
+    ) -> Result<bool, AppError> {
+        use crate::config::{voice_engine, voice_profiles};
+
+        let config_dir = crate::services::platform::get_config_dir();
+        let config = voice_profiles::load(&config_dir, name)?;
+        let issues = voice_engine::validate(&config);
+        if !issues.is_empty() {
+            let list: Vec<String> = issues.iter().map(ToString::to_string).collect();
+            return Err(AppError::Config(format!(
+                "Voice profile '{}' is invalid: {}",
+                name,
+                list.join("; ")
+            )));
+        }
+        let restarted = self.restart_with(config, app_handle)?;
+        tracing::info!(profile = %name, restarted, "Switched voice profile");
+        if restarted {
+            voice_engine::record(&config_dir, &self.config);
+        }
+        Ok(restarted)
+    }
 }
@@ -0,0 +1,95 @@
+//! The speech channel under direct AI control (MCP `voice_output` group).
+//!
+//! The MCP tools send `VoiceOutputRequest { action, args }` over the pipe;
+//! the reply is the JSON this module returns. Unlike `voice_send`, nothing
+//! goes through the inbox or shows up in the chat: `speak` only talks.
+//!
+//! `set_voice` switches the live TTS voice; with `persist` it is also saved
+//! as the configured voice. The MCP server asks the user to confirm that
+//! first, as it does for destructive tools.
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use super::tts::voices;
+use super::{SpeechPriority, VoiceState};
+use crate::commands::voice::VoiceEngineState;
+
+/// Run one `voice_output` action.
+pub fn handle(app: &AppHandle, action: &str, args: &Value) -> Result<Value, String> {
+    let state = app.state::<VoiceEngineState>();
+    let mut engine = state
+        .lock()
+        .map_err(|e| format!("Failed to lock voice state: {}", e))?;
+    let str_arg = |key: &str| {
+        args.get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+
+    match action {
+        "speak" => {
+            let text = str_arg("text").ok_or("text is required")?;
+            let priority = match str_arg("priority") {
+                Some(name) => SpeechPriority::from_name(name).ok_or_else(|| {
+                    format!(
+                        "priority must be interrupt, queue or drop_if_busy (got \"{}\")",
+                        name
+                    )
+                })?,
+                None => SpeechPriority::Interrupt,
+            };
+            let was_speaking = engine.state() == VoiceState::Speaking;
+            engine
+                .speak_as(
+                    text.to_string(),
+                    priority,
+                    str_arg("voice").map(String::from),
+                )
+                .map_err(|e| e.to_string())?;
+            Ok(json!({
+                "was_speaking": was_speaking,
+                "quiet_hours": crate::services::quiet_hours::active(),
+            }))
+        }
+        "stop" => {
+            let was_speaking = engine.state() == VoiceState::Speaking;
+            engine.stop_speaking();
+            Ok(json!({ "was_speaking": was_speaking }))
+        }
+        "list_voices" => {
+            let config = engine.config();
+            let options: Vec<Value> = voices::for_adapter(&config.tts_adapter)
+                .iter()
+                .map(|v| json!({ "value": v.value, "label": v.label }))
+                .collect();
+            Ok(json!({
+                "adapter": config.tts_adapter,
+                "voice": config.tts_voice,
+                "saved_voice": crate::commands::config::get_config_snapshot().voice.tts_voice,
+                "voices": options,
+            }))
+        }
+        "set_voice" => {
+            let voice = str_arg("voice").ok_or("voice is required")?;
+            let persist = args
+                .get("persist")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            engine.set_tts_voice(voice)?;
+            drop(engine);
+            tracing::info!(voice, persist, "TTS voice set over MCP");
+            if persist {
+                let saved = crate::commands::config::set_config(json!({
+                    "voice": { "ttsVoice": voice },
+                }));
+                if let Some(e) = saved.error {
+                    return Err(format!("Voice switched but not saved: {}", e));
+                }
+            }
+            Ok(json!({ "voice": voice, "persisted": persist }))
+        }
+        other => Err(format!("Unknown voice output action: {}", other)),
+    }
+}
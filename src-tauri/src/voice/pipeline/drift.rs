@@ -0,0 +1,198 @@
+//! Capture clock drift compensation.
+//!
+//! The mic's clock never runs at exactly the nominal rate, and the
+//! processing loop occasionally falls behind (e.g. while STT runs). Over
+//! hours of continuous listening the ring buffer backlog can creep up,
+//! which shows up as ever-growing end-to-end latency.
+//!
+//! `DriftCompensator` tracks a smoothed fill level of the ring buffer and,
+//! when the backlog stays above target, tells the consumer to discard a few
+//! milliseconds of audio per tick (micro drops). If the backlog gets far out
+//! of hand it resyncs to the target in one step.
+//!
+//! Drops are raw sample cuts with no crossfade, so they are never made while
+//! recording: only audio that feeds VAD onset detection is ever discarded,
+//! never audio that goes to STT. A backlog built up during a recording is
+//! worked off once it ends.
+
+use super::format::AudioFormat;
+
+/// Smoothing factor for the fill-level moving average.
+const FILL_EMA_ALPHA: f32 = 0.05;
+
+/// Compensation tuning. All sizes are mono samples at `sample_rate`.
+#[derive(Debug, Clone)]
+pub(crate) struct DriftConfig {
+    pub sample_rate: usize,
+    /// Backlog we aim to keep (one consumer read plus headroom).
+    pub target_fill: usize,
+    /// Smoothed backlog above which micro drops start.
+    pub high_water: usize,
+    /// Max samples discarded per tick while drifting.
+    pub max_drop_per_tick: usize,
+    /// Instantaneous backlog at which we resync straight to `target_fill`.
+    pub resync_fill: usize,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16_000,
+            target_fill: 2_560,       // 160ms
+            high_water: 8_000,        // 500ms
+            max_drop_per_tick: 160,   // 10ms
+            resync_fill: 32_000,      // 2s
+        }
+    }
+}
+
+impl DriftConfig {
+    /// The default timings at `format`'s rate. The target never drops below
+    /// two chunks, so long chunks aren't trimmed on every read.
+    pub(crate) fn for_format(format: &AudioFormat) -> Self {
+        let ms = |ms: f64| format.samples(ms / 1000.0);
+        Self {
+            sample_rate: format.sample_rate as usize,
+            target_fill: ms(160.0).max(format.chunk_samples * 2),
+            high_water: ms(500.0).max(format.chunk_samples * 4),
+            max_drop_per_tick: ms(10.0),
+            resync_fill: ms(2000.0),
+        }
+    }
+}
+
+pub(crate) struct DriftCompensator {
+    config: DriftConfig,
+    /// Smoothed ring buffer fill level.
+    avg_fill: f32,
+    /// Total samples discarded since start (for diagnostics).
+    dropped_total: u64,
+}
+
+impl DriftCompensator {
+    pub(crate) fn new(config: DriftConfig) -> Self {
+        Self {
+            config,
+            avg_fill: 0.0,
+            dropped_total: 0,
+        }
+    }
+
+    /// Record the backlog left after a read and return how many samples the
+    /// consumer should discard.
+    ///
+    /// `allow_drop` gates both micro drops and the one-step resync; pass
+    /// false while recording so no audio is ever cut from the user's speech.
+    /// The fill trend is still tracked so drops resume right after.
+    pub(crate) fn observe(&mut self, fill: usize, allow_drop: bool) -> usize {
+        self.avg_fill += FILL_EMA_ALPHA * (fill as f32 - self.avg_fill);
+        if !allow_drop {
+            return 0;
+        }
+
+        let excess = fill.saturating_sub(self.config.target_fill);
+        let drop = if fill >= self.config.resync_fill {
+            tracing::warn!(
+                fill,
+                dropped = excess,
+                "Audio capture backlog too large, resyncing"
+            );
+            // The backlog is gone, so forget the stale trend too
+            self.avg_fill = self.config.target_fill as f32;
+            excess
+        } else if self.avg_fill > self.config.high_water as f32 {
+            excess.min(self.config.max_drop_per_tick)
+        } else {
+            0
+        };
+
+        if drop > 0 {
+            let rate = self.config.sample_rate.max(1) as u64;
+            let before = self.dropped_total / rate;
+            self.dropped_total += drop as u64;
+            // Log once per second of cumulative compensation
+            if self.dropped_total / rate > before {
+                tracing::info!(
+                    avg_fill = self.avg_fill as usize,
+                    dropped_total_secs = self.dropped_total / rate,
+                    "Compensating capture drift"
+                );
+            }
+        }
+        drop
+    }
+
+    /// Total samples discarded since start.
+    #[cfg(test)]
+    pub(crate) fn dropped_total(&self) -> u64 {
+        self.dropped_total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_drop_at_steady_state() {
+        let mut drift = DriftCompensator::new(DriftConfig::default());
+        for _ in 0..1_000 {
+            assert_eq!(drift.observe(1_280, true), 0);
+        }
+        assert_eq!(drift.dropped_total(), 0);
+    }
+
+    #[test]
+    fn test_sustained_backlog_micro_drops() {
+        let mut drift = DriftCompensator::new(DriftConfig::default());
+        // A single spike doesn't move the average past high water
+        assert_eq!(drift.observe(20_000, true), 0);
+
+        // A sustained backlog does, and drops are capped per tick
+        let mut dropped = 0;
+        for _ in 0..200 {
+            dropped = drift.observe(12_000, true);
+        }
+        assert_eq!(dropped, 160);
+        assert!(drift.dropped_total() > 0);
+    }
+
+    #[test]
+    fn test_no_drops_while_recording() {
+        let mut drift = DriftCompensator::new(DriftConfig::default());
+        assert_eq!(drift.observe(40_000, false), 0);
+        // A sustained backlog doesn't cause micro drops either
+        for _ in 0..200 {
+            assert_eq!(drift.observe(12_000, false), 0);
+        }
+        assert_eq!(drift.dropped_total(), 0);
+        // Once recording ends the trend is already known, so drops resume
+        assert_eq!(drift.observe(12_000, true), 160);
+    }
+
+    #[test]
+    fn test_resync_on_huge_backlog() {
+        let mut drift = DriftCompensator::new(DriftConfig::default());
+        assert_eq!(drift.observe(40_000, true), 40_000 - 2_560);
+        // Trend was reset, so the next normal read doesn't trigger drops
+        assert_eq!(drift.observe(1_280, true), 0);
+    }
+
+    #[test]
+    fn test_config_for_format() {
+        let default = DriftConfig::for_format(&AudioFormat::default());
+        assert_eq!(default.target_fill, DriftConfig::default().target_fill);
+        assert_eq!(default.resync_fill, DriftConfig::default().resync_fill);
+
+        let config = crate::voice::VoiceEngineConfig {
+            sample_rate: 8_000,
+            chunk_ms: 200,
+            ..Default::default()
+        };
+        let tel = DriftConfig::for_format(&AudioFormat::from_config(&config));
+        // Two 200ms chunks outweigh the 160ms target
+        assert_eq!(tel.target_fill, 3_200);
+        assert_eq!(tel.max_drop_per_tick, 80);
+        assert_eq!(tel.resync_fill, 16_000);
+    }
+}
@@ -0,0 +1,146 @@
+//! Duty-cycled listening for wake-word mode.
+//!
+//! Waiting for speech onset normally wakes the processing loop for every
+//! chunk (80ms by default) to run VAD. While duty cycling, the loop instead sleeps for `SLEEP`
+//! between wakes and only analyzes the most recent `WINDOW_SECS` of audio. The
+//! first window with energy above a wake threshold (lower than the VAD
+//! threshold, so soft onsets count) switches back to full-rate listening,
+//! which then stays on for `HOLD` so follow-up speech and short pauses go
+//! through the normal VAD path.
+//!
+//! The audio captured while asleep is kept (up to `MAX_BACKLOG_SECS`) and
+//! handed to the Listening branch on wake, so a recording that starts right away
+//! still includes the beginning of the utterance.
+
+use std::time::{Duration, Instant};
+
+use crate::services::system_stats::{self, BatteryStatus};
+use crate::voice::vad::compute_energy;
+
+/// Sleep between analysis windows while duty cycling.
+pub(crate) const SLEEP: Duration = Duration::from_millis(500);
+/// Most recent audio analyzed on each wake, in seconds.
+pub(crate) const WINDOW_SECS: f64 = 0.25;
+/// Audio kept across a sleep for the recording pre-roll, in seconds.
+pub(crate) const MAX_BACKLOG_SECS: f64 = 1.0;
+/// How long full-rate listening lasts after the last energy or activity.
+const HOLD: Duration = Duration::from_secs(10);
+/// How often the power source is re-checked in `OnBattery` mode.
+pub(crate) const BATTERY_POLL: Duration = Duration::from_secs(60);
+
+/// When duty cycling is allowed (`voice.listenDutyCycle`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DutyCycleMode {
+    Off,
+    OnBattery,
+    Always,
+}
+
+impl DutyCycleMode {
+    /// Parse "off" / "battery" / "always". Unknown values mean `Off`.
+    pub(crate) fn from_config(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "battery" => Self::OnBattery,
+            "always" => Self::Always,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// Tracks whether listening is currently duty cycled or at full rate.
+pub(crate) struct DutyCycle {
+    wake_threshold: f32,
+    /// Full-rate listening continues until this instant.
+    full_rate_until: Option<Instant>,
+}
+
+impl DutyCycle {
+    pub(crate) fn new(vad_threshold: f32) -> Self {
+        Self {
+            wake_threshold: vad_threshold * 0.5,
+            full_rate_until: None,
+        }
+    }
+
+    /// Whether the loop should sleep between windows right now.
+    pub(crate) fn is_cycling(&self, now: Instant) -> bool {
+        !matches!(self.full_rate_until, Some(until) if now < until)
+    }
+
+    /// Keep full-rate listening on for `HOLD` from `now`. Called on wake and
+    /// while the pipeline is busy (recording, processing, speaking).
+    pub(crate) fn stay_awake(&mut self, now: Instant) {
+        if self.is_cycling(now) {
+            tracing::debug!("Listening at full rate");
+        }
+        self.full_rate_until = Some(now + HOLD);
+    }
+
+    /// Check one analysis window. Energy above the wake threshold switches
+    /// to full-rate listening and returns `true`.
+    pub(crate) fn observe(&mut self, window: &[f32], now: Instant) -> bool {
+        let woke = compute_energy(window) > self.wake_threshold;
+        if woke {
+            self.stay_awake(now);
+        }
+        woke
+    }
+}
+
+/// Whether the machine is running on battery power right now.
+pub(crate) fn on_battery() -> bool {
+    match system_stats::battery() {
+        Ok(Some(status)) => is_discharging(&status),
+        _ => false,
+    }
+}
+
+fn is_discharging(status: &BatteryStatus) -> bool {
+    match status.on_ac_power {
+        Some(ac) => !ac,
+        None => status.state == "discharging",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `WINDOW_SECS` at 16kHz.
+    const WINDOW: usize = 4_000;
+
+    #[test]
+    fn test_wake_and_hold() {
+        let start = Instant::now();
+        let mut duty = DutyCycle::new(0.01);
+        assert!(duty.is_cycling(start));
+
+        assert!(!duty.observe(&[0.001; WINDOW], start));
+        assert!(duty.is_cycling(start));
+
+        // Above half the VAD threshold wakes, even below the threshold itself
+        assert!(duty.observe(&[0.008; WINDOW], start));
+        assert!(!duty.is_cycling(start + Duration::from_secs(5)));
+        assert!(duty.is_cycling(start + HOLD));
+    }
+
+    #[test]
+    fn test_mode_and_power_source() {
+        assert_eq!(
+            DutyCycleMode::from_config("battery"),
+            DutyCycleMode::OnBattery
+        );
+        assert_eq!(DutyCycleMode::from_config("Always"), DutyCycleMode::Always);
+        assert_eq!(DutyCycleMode::from_config("bogus"), DutyCycleMode::Off);
+
+        let mut status = BatteryStatus {
+            percent: 80.0,
+            state: "discharging".into(),
+            on_ac_power: None,
+            time_remaining_secs: None,
+        };
+        assert!(is_discharging(&status));
+        status.on_ac_power = Some(true);
+        assert!(!is_discharging(&status));
+    }
+}
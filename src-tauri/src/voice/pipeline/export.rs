@@ -0,0 +1,225 @@
+//! Speech export: run the configured TTS engine and write the audio to a
+//! file instead of playing it.
+//!
+//! Exports build their own engine from the config, so they work without a
+//! running pipeline or any audio hardware (podcast-style export, automated
+//! tests) and never hold up live speech. Phrases are joined with the same
+//! phrase and sentence pauses as playback, after the blocked-term filter.
+//! Voice effects (`voice.ttsEffects`) are applied too, so the file sounds
+//! like the live voice.
+//!
+//! WAV is written directly (16-bit PCM mono at the engine's rate). MP3 is
+//! encoded by piping that WAV through `ffmpeg`, which must be on the PATH.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::Serialize;
+
+use super::playback::{pause_after_phrase, silence_samples, SYNTH_TIMEOUT};
+use super::recovery;
+use crate::error::AppError;
+use crate::services::turn_archive;
+use crate::voice::tts::effects::EffectChain;
+use crate::voice::tts::{self, TtsEngine};
+use crate::voice::VoiceEngineConfig;
+
+/// File format of an export, chosen by the path's extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeechFileFormat {
+    Wav,
+    Mp3,
+}
+
+impl SpeechFileFormat {
+    /// Format for `path`'s extension (case-insensitive), or None.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "wav" => Some(Self::Wav),
+            "mp3" => Some(Self::Mp3),
+            _ => None,
+        }
+    }
+}
+
+/// What an export wrote.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeechFile {
+    pub path: PathBuf,
+    pub format: SpeechFileFormat,
+    pub sample_rate: u32,
+    pub duration_ms: u64,
+    /// Phrases synthesized (after the blocked-term filter).
+    pub phrases: usize,
+}
+
+/// Synthesize `text` with the TTS engine `config` describes and write it to
+/// `path` (`.wav` or `.mp3`).
+pub async fn speak_to_file(
+    config: VoiceEngineConfig,
+    text: String,
+    path: PathBuf,
+) -> Result<SpeechFile, AppError> {
+    let format = SpeechFileFormat::from_path(&path).ok_or_else(|| {
+        AppError::Config(format!(
+            "Unsupported export file {} (use .wav or .mp3)",
+            path.display()
+        ))
+    })?;
+    let phrases = spoken_phrases(&config, &text);
+    if phrases.is_empty() {
+        return Err(AppError::Tts("Nothing to speak".into()));
+    }
+
+    let engine_config = config.clone();
+    let engine = tokio::task::spawn_blocking(move || recovery::tts_engine_from_config(&engine_config))
+        .await
+        .map_err(|e| AppError::Internal(format!("TTS engine creation panicked: {}", e)))??;
+    let sample_rate = engine.sample_rate();
+    let mut samples = render(
+        engine.as_ref(),
+        &phrases,
+        config.tts_phrase_gap_ms,
+        config.tts_sentence_pause_ms,
+    )
+    .await?;
+    if let Some(mut chain) = EffectChain::new(&config.tts_effects, sample_rate) {
+        chain.process(&mut samples);
+    }
+
+    let wav = turn_archive::encode_wav(&samples, sample_rate);
+    let out = path.clone();
+    tokio::task::spawn_blocking(move || match format {
+        SpeechFileFormat::Wav => std::fs::write(&out, &wav)
+            .map_err(|e| AppError::Internal(format!("Failed to write {}: {}", out.display(), e))),
+        SpeechFileFormat::Mp3 => encode_mp3(&wav, &out),
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Export task panicked: {}", e)))??;
+
+    let duration_ms = samples.len() as u64 * 1000 / sample_rate.max(1) as u64;
+    tracing::info!(path = %path.display(), duration_ms, "Exported speech to file");
+    Ok(SpeechFile {
+        path,
+        format,
+        sample_rate,
+        duration_ms,
+        phrases: phrases.len(),
+    })
+}
+
+/// The phrases playback would speak for `text`.
+fn spoken_phrases(config: &VoiceEngineConfig, text: &str) -> Vec<String> {
+    let phrases = tts::split_into_phrases(text);
+    match tts::OutputFilter::new(&config.tts_blocked_terms, &config.tts_filter_action) {
+        Some(filter) => filter.filter_phrases(phrases),
+        None => phrases,
+    }
+}
+
+/// Synthesize each phrase in turn and join them with playback's pauses.
+/// A phrase that fails or times out fails the export rather than leaving a
+/// silent gap in the file.
+async fn render(
+    engine: &dyn TtsEngine,
+    phrases: &[String],
+    phrase_gap_ms: u32,
+    sentence_pause_ms: u32,
+) -> Result<Vec<f32>, AppError> {
+    let sample_rate = engine.sample_rate();
+    let mut out = Vec::new();
+    for (i, phrase) in phrases.iter().enumerate() {
+        let samples = tokio::time::timeout(SYNTH_TIMEOUT, engine.synthesize(phrase))
+            .await
+            .map_err(|_| AppError::Tts(format!("Synthesis timed out on phrase {}", i + 1)))??;
+        out.extend_from_slice(&samples);
+        if i + 1 < phrases.len() {
+            let pause = pause_after_phrase(phrase, phrase_gap_ms, sentence_pause_ms);
+            out.resize(out.len() + silence_samples(pause, sample_rate), 0.0);
+        }
+    }
+    Ok(out)
+}
+
+/// Encode `wav` to MP3 at `path` with ffmpeg.
+fn encode_mp3(wav: &[u8], path: &Path) -> Result<(), AppError> {
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(["-hide_banner", "-loglevel", "error", "-y", "-f", "wav", "-i", "pipe:0"])
+        .args(["-codec:a", "libmp3lame", "-q:a", "2"])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    crate::util::hidden(&mut cmd);
+    let mut child = cmd.spawn().map_err(|e| {
+        AppError::Internal(format!("MP3 export needs ffmpeg on the PATH ({})", e))
+    })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(wav)
+            .map_err(|e| AppError::Internal(format!("Failed to pipe audio to ffmpeg: {}", e)))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| AppError::Internal(format!("ffmpeg failed: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::Internal(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voice::tts::TtsError;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    /// One 10 ms burst per phrase at 1 kHz.
+    struct Beeper;
+
+    impl TtsEngine for Beeper {
+        fn synthesize(
+            &self,
+            _text: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, TtsError>> + Send + '_>> {
+            Box::pin(async { Ok(vec![0.5; 10]) })
+        }
+        fn stop(&self) {}
+        fn set_voice(&mut self, _voice: &str) -> Result<(), TtsError> {
+            Ok(())
+        }
+        fn set_speed(&mut self, _speed: f32) {}
+        fn name(&self) -> String {
+            "Beeper".into()
+        }
+        fn sample_rate(&self) -> u32 {
+            1000
+        }
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(SpeechFileFormat::from_path(Path::new("out/reply.WAV")), Some(SpeechFileFormat::Wav));
+        assert_eq!(SpeechFileFormat::from_path(Path::new("episode.mp3")), Some(SpeechFileFormat::Mp3));
+        assert_eq!(SpeechFileFormat::from_path(Path::new("reply.ogg")), None);
+        assert_eq!(SpeechFileFormat::from_path(Path::new("reply")), None);
+    }
+
+    #[tokio::test]
+    async fn test_render_joins_phrases_with_pauses() {
+        let phrases = vec!["First,".to_string(), "then done.".to_string(), "Next".to_string()];
+        let samples = render(&Beeper, &phrases, 20, 100).await.unwrap();
+        // 3 bursts + phrase gap (20 ms) + sentence pause (100 ms), none after the last
+        assert_eq!(samples.len(), 30 + 20 + 100);
+        assert!(samples[10..30].iter().all(|&s| s == 0.0));
+        assert_eq!(samples[30], 0.5);
+    }
+}
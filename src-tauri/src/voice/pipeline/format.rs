@@ -0,0 +1,141 @@
+//! Sample rate and chunk size the live pipeline runs at.
+//!
+//! Capture resamples to `sample_rate` and pushes `chunk_samples` at a time;
+//! the ring buffer, VAD frames, drift compensation, duty cycling, noise
+//! suppression and recording limits are all sized from it. The default is
+//! 16 kHz in 80 ms chunks. An 8 kHz telephony source can run at its own
+//! rate, and a frame-based VAD can get the chunk length it needs (e.g.
+//! 32 ms).
+//!
+//! STT always gets 16 kHz: whisper and Parakeet are trained on it, so a
+//! recording at another rate is converted when it is handed to STT (see
+//! [`AudioFormat::to_stt_rate`]).
+
+use super::TARGET_SAMPLE_RATE;
+use crate::voice::resample::{self, ResamplerKind};
+use crate::voice::VoiceEngineConfig;
+
+/// Pipeline rates. All divide 48 kHz, which RNNoise runs at.
+pub const SUPPORTED_RATES: &[u32] = &[8_000, 16_000, 24_000, 48_000];
+/// Allowed chunk durations, in ms.
+pub const CHUNK_MS_RANGE: std::ops::RangeInclusive<u32> = 10..=200;
+/// Default chunk duration (1280 samples at 16 kHz).
+pub const DEFAULT_CHUNK_MS: u32 = 80;
+/// Ring buffer length.
+const RING_SECS: f64 = 10.0;
+/// Frame length of the pre-STT speech coverage check.
+const VAD_FRAME_MS: u32 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct AudioFormat {
+    pub(crate) sample_rate: u32,
+    /// Samples per chunk pushed by capture; also the live VAD frame.
+    pub(crate) chunk_samples: usize,
+}
+
+impl Default for AudioFormat {
+    fn default() -> Self {
+        Self::new(TARGET_SAMPLE_RATE, DEFAULT_CHUNK_MS)
+    }
+}
+
+impl AudioFormat {
+    fn new(sample_rate: u32, chunk_ms: u32) -> Self {
+        Self {
+            sample_rate,
+            chunk_samples: (sample_rate * chunk_ms / 1000) as usize,
+        }
+    }
+
+    /// From `voice.sampleRate` / `voice.chunkMs`. Unsupported values fall
+    /// back to the defaults (rate) or the nearest allowed value (chunk).
+    pub(crate) fn from_config(config: &VoiceEngineConfig) -> Self {
+        let rate = if SUPPORTED_RATES.contains(&config.sample_rate) {
+            config.sample_rate
+        } else {
+            tracing::warn!(
+                sample_rate = config.sample_rate,
+                "Unsupported pipeline sample rate, using 16 kHz"
+            );
+            TARGET_SAMPLE_RATE
+        };
+        let chunk_ms = config
+            .chunk_ms
+            .clamp(*CHUNK_MS_RANGE.start(), *CHUNK_MS_RANGE.end());
+        Self::new(rate, chunk_ms)
+    }
+
+    /// Samples in `secs` of audio.
+    pub(crate) fn samples(&self, secs: f64) -> usize {
+        (secs * self.sample_rate as f64) as usize
+    }
+
+    /// Length of `samples` in seconds.
+    pub(crate) fn secs(&self, samples: usize) -> f64 {
+        samples as f64 / self.sample_rate as f64
+    }
+
+    pub(crate) fn ring_capacity(&self) -> usize {
+        self.samples(RING_SECS)
+    }
+
+    /// Frame size for speech coverage checks (30 ms).
+    pub(crate) fn vad_frame(&self) -> usize {
+        (self.sample_rate * VAD_FRAME_MS / 1000) as usize
+    }
+
+    /// Convert a recording to the 16 kHz STT expects. A no-op at 16 kHz.
+    pub(crate) fn to_stt_rate(&self, kind: ResamplerKind, audio: Vec<f32>) -> Vec<f32> {
+        if self.sample_rate == TARGET_SAMPLE_RATE {
+            return audio;
+        }
+        resample::resample(kind, &audio, self.sample_rate, TARGET_SAMPLE_RATE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(sample_rate: u32, chunk_ms: u32) -> AudioFormat {
+        let config = VoiceEngineConfig {
+            sample_rate,
+            chunk_ms,
+            ..Default::default()
+        };
+        AudioFormat::from_config(&config)
+    }
+
+    #[test]
+    fn test_default_matches_constants() {
+        let f = AudioFormat::default();
+        assert_eq!(f, format(16_000, 80));
+        assert_eq!(f.chunk_samples, super::super::CHUNK_SAMPLES);
+        assert_eq!(f.vad_frame(), super::super::VAD_FRAME_SAMPLES);
+        assert_eq!(f.ring_capacity(), 160_000);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let tel = format(8_000, 32);
+        assert_eq!((tel.sample_rate, tel.chunk_samples), (8_000, 256));
+        assert_eq!(tel.vad_frame(), 240);
+        assert_eq!(tel.samples(1.5), 12_000);
+        assert_eq!(tel.secs(4_000), 0.5);
+
+        // Unsupported rate and out-of-range chunk
+        let f = format(44_100, 1000);
+        assert_eq!((f.sample_rate, f.chunk_samples), (16_000, 3_200));
+        assert_eq!(format(48_000, 1).chunk_samples, 480);
+    }
+
+    #[test]
+    fn test_to_stt_rate() {
+        let audio = vec![0.1f32; 8_000];
+        let f = format(8_000, 80);
+        let out = f.to_stt_rate(ResamplerKind::Linear, audio.clone());
+        assert!((out.len() as i64 - 16_000).abs() <= 2);
+        let same = AudioFormat::default().to_stt_rate(ResamplerKind::Linear, audio.clone());
+        assert_eq!(same, audio);
+    }
+}
@@ -0,0 +1,222 @@
+//! When the STT and TTS engines are loaded.
+//!
+//! Loading a whisper or Kokoro model can take seconds on a slow machine.
+//! Each engine has an [`EngineLoadMode`] (`voice.sttLoad`, `voice.ttsLoad`):
+//!
+//! - `blocking`: loaded in `VoicePipeline::start`, before `Ready`
+//! - `background`: loaded on a blocking thread right after start; a
+//!   recording or reply that needs the engine first waits for the load
+//! - `lazy`: loaded by the first recording or reply that needs it
+//!
+//! Every load is announced with `EngineLoad` events ("loading", then
+//! "ready" or "failed" with the time taken) so the UI can show that the
+//! first turn is waiting on a model. The first load runs once per pipeline;
+//! an engine lost after that is rebuilt by `recovery`.
+//!
+//! The speaker model (`voice.speakerId`) always loads in the background;
+//! turns before it is ready go untagged.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::{playback, recovery, PipelineShared, VoiceEvent};
+use crate::error::AppError;
+use crate::services::event_throttle::EmitThrottled;
+use crate::services::platform;
+use crate::voice::speaker::SpeakerEncoder;
+use crate::voice::EngineLoadMode;
+
+/// Serializes the first load of one engine.
+pub(crate) struct EngineLoader {
+    gate: tokio::sync::Mutex<()>,
+    attempted: AtomicBool,
+}
+
+impl EngineLoader {
+    /// A loader for an engine loaded with `mode`. `start` has already
+    /// loaded a `Blocking` one.
+    pub(super) fn new(mode: EngineLoadMode) -> Self {
+        Self {
+            gate: tokio::sync::Mutex::new(()),
+            attempted: AtomicBool::new(mode == EngineLoadMode::Blocking),
+        }
+    }
+
+    /// Whether the first load has run.
+    fn attempted(&self) -> bool {
+        self.attempted.load(Ordering::Acquire)
+    }
+}
+
+fn report(shared: &PipelineShared, engine: &str, status: &str, since: Option<Instant>) {
+    let _ = shared.app_handle.emit_throttled(
+        "voice-event",
+        VoiceEvent::EngineLoad {
+            engine: engine.into(),
+            status: status.into(),
+            elapsed_ms: since.map(|t| t.elapsed().as_millis() as u64),
+        },
+    );
+}
+
+/// Start the background loads `config` asks for.
+pub(super) fn spawn_background(shared: &Arc<PipelineShared>) {
+    if shared.config.stt_load == EngineLoadMode::Background {
+        let shared = Arc::clone(shared);
+        tauri::async_runtime::spawn(async move {
+            ensure_stt(&shared).await;
+        });
+    }
+    if shared.config.tts_load == EngineLoadMode::Background {
+        let shared = Arc::clone(shared);
+        tauri::async_runtime::spawn(async move {
+            ensure_tts(&shared).await;
+        });
+    }
+    if shared.config.speaker_id {
+        let shared = Arc::clone(shared);
+        tauri::async_runtime::spawn(async move {
+            load_speaker(&shared).await;
+        });
+    }
+}
+
+async fn load_speaker(shared: &Arc<PipelineShared>) {
+    let started = Instant::now();
+    report(shared, "speaker", "loading", None);
+    let result =
+        tokio::task::spawn_blocking(|| SpeakerEncoder::load(&platform::get_data_dir())).await;
+    match result {
+        Ok(Ok(encoder)) => {
+            *recovery::lock_slot(&shared.speaker_encoder).0 = Some(Arc::new(encoder));
+            report(shared, "speaker", "ready", Some(started));
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("Speaker model failed to load: {}", e);
+            report(shared, "speaker", "failed", Some(started));
+            let _ = shared.app_handle.emit_throttled(
+                "voice-event",
+                VoiceEvent::error(e.context("Speaker identification not available")),
+            );
+        }
+        Err(e) => {
+            tracing::error!("Speaker model load panicked: {}", e);
+            report(shared, "speaker", "failed", Some(started));
+        }
+    }
+}
+
+/// Run the first STT load if it hasn't run yet, waiting for one already in
+/// progress. Returns false when this call loaded the engine and failed.
+pub(super) async fn ensure_stt(shared: &Arc<PipelineShared>) -> bool {
+    let loader = &shared.stt_loader;
+    let _gate = loader.gate.lock().await;
+    if loader.attempted() {
+        return true;
+    }
+
+    let started = Instant::now();
+    report(shared, "stt", "loading", None);
+    let config = shared.config.clone();
+    let result =
+        tokio::task::spawn_blocking(move || recovery::stt_engine_from_config(&config)).await;
+    loader.attempted.store(true, Ordering::Release);
+    match result {
+        Ok(Ok(engine)) => {
+            tracing::info!(
+                adapter = %shared.config.stt_adapter,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "STT engine loaded"
+            );
+            recovery::restore_stt_engine(shared, engine);
+            report(shared, "stt", "ready", Some(started));
+            true
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("STT engine failed to load: {}", e);
+            report(shared, "stt", "failed", Some(started));
+            let _ = shared.app_handle.emit_throttled(
+                "voice-event",
+                VoiceEvent::error(AppError::from(e).context("STT not available")),
+            );
+            false
+        }
+        Err(e) => {
+            tracing::error!("STT engine load panicked: {}", e);
+            report(shared, "stt", "failed", Some(started));
+            false
+        }
+    }
+}
+
+/// Run the first TTS load if it hasn't run yet, waiting for one already in
+/// progress. Uses the engine pre-loaded at app launch when there is one.
+/// Returns false when this call loaded the engine and failed.
+pub(super) async fn ensure_tts(shared: &Arc<PipelineShared>) -> bool {
+    let loader = &shared.tts_loader;
+    let _gate = loader.gate.lock().await;
+    if loader.attempted() {
+        return true;
+    }
+
+    if let Some(engine) = super::take_preloaded_tts(&shared.app_handle) {
+        tracing::info!(name = %engine.name(), "Using pre-loaded TTS engine");
+        playback::restore_tts_engine(shared, engine);
+        loader.attempted.store(true, Ordering::Release);
+        report(shared, "tts", "ready", None);
+        return true;
+    }
+
+    let started = Instant::now();
+    report(shared, "tts", "loading", None);
+    let config = shared.config.clone();
+    let result =
+        tokio::task::spawn_blocking(move || recovery::tts_engine_from_config(&config)).await;
+    loader.attempted.store(true, Ordering::Release);
+    match result {
+        Ok(Ok(engine)) => {
+            tracing::info!(
+                adapter = %shared.config.tts_adapter,
+                name = %engine.name(),
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "TTS engine loaded"
+            );
+            playback::restore_tts_engine(shared, engine);
+            report(shared, "tts", "ready", Some(started));
+            true
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("TTS engine failed to load: {}", e);
+            report(shared, "tts", "failed", Some(started));
+            let _ = shared.app_handle.emit_throttled(
+                "voice-event",
+                VoiceEvent::error(AppError::from(e).context("TTS not available")),
+            );
+            false
+        }
+        Err(e) => {
+            tracing::error!("TTS engine load panicked: {}", e);
+            report(shared, "tts", "failed", Some(started));
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_mode() {
+        assert_eq!(EngineLoadMode::parse("lazy"), EngineLoadMode::Lazy);
+        assert_eq!(
+            EngineLoadMode::parse(" blocking "),
+            EngineLoadMode::Blocking
+        );
+        assert_eq!(EngineLoadMode::parse("eager"), EngineLoadMode::Background);
+        assert!(EngineLoader::new(EngineLoadMode::Blocking).attempted());
+        assert!(!EngineLoader::new(EngineLoadMode::Lazy).attempted());
+        assert!(!EngineLoader::new(EngineLoadMode::Background).attempted());
+    }
+}
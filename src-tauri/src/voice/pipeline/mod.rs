@@ -11,40 +11,105 @@
 //! - STT engine (Whisper stub) for transcription
 //! - TTS engine (Edge/Kokoro stub) for speech synthesis
 
+mod drift;
+mod duty_cycle;
+pub mod export;
+pub(crate) mod format;
+mod loading;
+mod network_input;
 mod playback;
+mod privacy;
+mod recovery;
+mod remote_output;
 mod ring_buffer;
-
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+pub mod self_test;
+mod shutdown;
+pub mod speech_queue;
+mod system_channel;
+pub mod timing;
+pub mod watchdog;
+mod word_progress;
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use serde::Serialize;
-use tauri::{AppHandle, Emitter};
-
-use super::stt::{self, SttAdapter};
-use super::tts::{self, TtsEngine};
-use super::vad::VadProcessor;
-use super::{VoiceEngineConfig, VoiceMode, VoiceState};
-
+use tauri::AppHandle;
+use tokio::sync::Notify;
+
+use super::stt::SttAdapter;
+use super::tts::TtsEngine;
+use super::denoise::NoiseSuppressor;
+use super::{ask, confirm};
+use super::intents::{self, IntentGrammar};
+use super::routing::{self, RouteTarget, TranscriptRouter};
+use super::transcript_rules::TranscriptRules;
+use super::speaker::{self, SpeakerEncoder};
+use super::resample::{ResamplerKind, StreamResampler};
+use super::session::SessionTracker;
+use super::vad::{self, VadProcessor};
+use super::{EngineLoadMode, VoiceEngineConfig, VoiceMode, VoiceState};
+use crate::error::AppError;
+use crate::services::audio_ducking::AudioDucker;
+use crate::services::audio_session::{self, AudioSessionCategory};
+use crate::services::event_throttle::EmitThrottled;
+use crate::services::platform;
+use crate::services::transcript_log::{self, Speaker};
+use crate::services::turn_archive::{self, ArchivedTurn, ClipFormat};
+use crate::services::voice_stats;
+
+use drift::{DriftCompensator, DriftConfig};
+use duty_cycle::{DutyCycle, DutyCycleMode};
+use format::AudioFormat;
 use ring_buffer::{create_ring_buffer, RingConsumer, RingProducer};
+use speech_queue::{SpeechPriority, SpeechQueue};
 
 // ── Constants ───────────────────────────────────────────────────────
 
-/// Target sample rate for the processing pipeline (16kHz mono).
-const TARGET_SAMPLE_RATE: u32 = 16_000;
+/// Sample rate STT expects (16kHz mono), and the pipeline's default rate.
+/// The live pipeline runs at `AudioFormat::sample_rate` (see `format`).
+pub(crate) const TARGET_SAMPLE_RATE: u32 = 16_000;
+
+/// Default audio chunk size in samples (80ms at 16kHz). Matches
+/// voice-core. Offline tools (calibration, the VAD corpus) use it; the live
+/// pipeline uses `AudioFormat::chunk_samples`.
+pub(crate) const CHUNK_SAMPLES: usize = 1280;
+
+/// Frame size for speech coverage checks on 16kHz audio (30ms).
+pub(crate) const VAD_FRAME_SAMPLES: usize = 480;
+
+/// Longest the processing loop waits for a chunk before re-checking state,
+/// so a stalled capture device can't park it forever.
+const CHUNK_WAIT_TIMEOUT: Duration = Duration::from_millis(500);
 
-/// Audio chunk size in samples (80ms at 16kHz). Matches voice-core.
-const CHUNK_SAMPLES: usize = 1280;
+/// Longest `resume()` waits for the processing loop to settle the pause.
+/// Longer only while it finishes a transcription that was already running.
+const RESUME_SETTLE_TIMEOUT: Duration = Duration::from_secs(2);
 
-/// Ring buffer capacity: ~10 seconds of 16kHz mono audio.
-const RING_BUFFER_CAPACITY: usize = 160_000;
+/// How often per chunk the processing loop checks the ring buffer while it
+/// waits for audio. Capture doesn't signal the loop (see `wait_for_chunk`).
+const CHUNK_POLLS: u32 = 4;
+
+/// `captureSource` value that records system output instead of the mic.
+pub(crate) const CAPTURE_SYSTEM: &str = "system";
 
 // ── Voice Events (emitted to frontend) ─────────────────────────────
 
+/// Which capture channel a transcription came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptSource {
+    /// The microphone: the user talking to the assistant.
+    User,
+    /// System audio with `captureSource: "both"`: a meeting, a video.
+    System,
+}
+
 /// Events emitted by the voice pipeline to the Tauri frontend.
 ///
-/// These are serialized as JSON and sent via `app_handle.emit()`.
+/// These are serialized as JSON and sent via `app_handle.emit_throttled()`.
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "event", content = "data")]
 #[serde(rename_all = "snake_case")]
@@ -57,16 +122,55 @@ pub enum VoiceEvent {
     StateChange { state: String },
     /// Recording started.
     RecordingStart { rec_type: String },
-    /// Recording stopped.
-    RecordingStop {},
-    /// Transcription result from STT.
-    Transcription { text: String },
+    /// Recording stopped. `reason` is "manual", "silence", "cancelled",
+    /// "paused" (discarded by `pause()`),
+    /// "max_duration" (hit `max_recording_secs`) or "toggle_timeout" (hit
+    /// `toggle_timeout_secs`). Audio stopped by a limit still goes to STT.
+    RecordingStop { reason: String },
+    /// Once per second of recorded audio. `limit_secs` is when the
+    /// recording will be auto-stopped (`None` = no limit), for a countdown.
+    RecordingElapsed { secs: u64, limit_secs: Option<u64> },
+    /// Transcription result from STT. `language` is the ISO 639-1 code of
+    /// the speech (detected when the STT language is "auto"); with the
+    /// translate task `text` is English but `language` stays the source.
+    /// `id` names the recording in the turn archive (see
+    /// `services::turn_archive`) when it is enabled. With speaker
+    /// identification on and a voice enrolled, `similarity` is how close the
+    /// voice is to the best-matching profile and `speaker` that profile's
+    /// name if it reached the threshold. `session_id` and `turn` place it
+    /// in the conversation (see `voice::session`). `source` is "system" for
+    /// speech recorded from system audio (see `system_channel`), which has
+    /// no speaker match, session or turn. `routes` lists the consumers a
+    /// `transcriptRoutes` entry picked (see `voice::routing`); `None` means
+    /// the default (the AI provider, or the injector while dictating).
+    Transcription {
+        id: u64,
+        text: String,
+        language: Option<String>,
+        source: TranscriptSource,
+        speaker: Option<String>,
+        similarity: Option<f32>,
+        session_id: Option<u64>,
+        turn: Option<u32>,
+        routes: Option<Vec<RouteTarget>>,
+    },
     /// TTS playback started.
     SpeakingStart { text: String },
+    /// A word started playing. `char_offset` is the word's position (in
+    /// chars) within the SpeakingStart text. Only for engines that report
+    /// word boundaries (Edge TTS).
+    SpeakingProgress { char_offset: usize, word: String },
     /// TTS playback ended.
     SpeakingEnd {},
-    /// An error occurred.
-    Error { message: String },
+    /// An error occurred. `code` is the stable `AppError` code the
+    /// frontend picks a remediation by. `id` is its entry in the error log
+    /// and `turn` the pipeline turn it broke, if any (see `voice::errors`).
+    Error {
+        id: u64,
+        code: &'static str,
+        message: String,
+        turn: Option<u64>,
+    },
     /// Audio devices enumerated.
     AudioDevices {
         input: Vec<AudioDeviceInfo>,
@@ -74,6 +178,18 @@ pub enum VoiceEvent {
     },
     /// Pipeline is shutting down.
     Stopping {},
+    /// Microphone released by `pause()`; STT/TTS stay loaded.
+    Paused {},
+    /// Microphone reopened by `resume()`.
+    Resumed {},
+    /// A capture stream on an input device was opened (see `privacy`).
+    /// `device` is its name, if the OS reports one.
+    MicrophoneOpen { device: Option<String> },
+    /// The input device's capture stream was dropped.
+    MicrophoneClosed {},
+    /// The mic was found muted, or unmuted again. `source` is "os" (the
+    /// OS mute state) or "signal" (the device delivers digital silence).
+    MicrophoneMuted { muted: bool, source: &'static str },
     /// Real-time audio levels for waveform visualization (emitted during recording).
     AudioLevel { levels: Vec<f32> },
     /// Pipeline has been sitting in a non-idle state for an abnormally long
@@ -81,6 +197,111 @@ pub enum VoiceEvent {
     /// recording the user forgot to stop). Lets the frontend surface a
     /// visible indicator + recovery action instead of silently hanging.
     Stuck { state: String, elapsed_secs: u64 },
+    /// A poisoned or emptied STT/TTS engine slot was recovered (see
+    /// `recovery`). `engine` is "stt" or "tts"; `reason` is "poisoned" or
+    /// "missing" (rebuilt from config).
+    EngineRecovered { engine: String, reason: String },
+    /// Progress of an engine's first load (see `loading`). `engine` is "stt",
+    /// "tts" or "speaker"; `status` is "loading", "ready" or "failed". `elapsed_ms`
+    /// is how long the load took (absent on "loading" and when the engine
+    /// was pre-loaded at app launch).
+    EngineLoad {
+        engine: String,
+        status: String,
+        elapsed_ms: Option<u64>,
+    },
+    /// A wake-word turn was dropped because the voice matched no enrolled
+    /// speaker (`ignore_unknown_speakers`). `similarity` is to the closest
+    /// profile.
+    UnknownSpeaker { similarity: f32 },
+    /// A recording was discarded without running STT because too little of
+    /// it contained speech (e.g. an accidental PTT press). `coverage` is the
+    /// fraction of VAD frames above the energy threshold.
+    NoSpeechDetected { coverage: f32 },
+    /// Stage durations of a finished turn (recording stop through playback
+    /// end). See `timing::PipelineTiming`.
+    PipelineTiming(timing::PipelineTiming),
+    /// A transcription was a local voice command and was carried out
+    /// instead of being sent on (see `voice::intents`). `intent` is the
+    /// action name ("stopSpeaking", "louder", ...), `text` what was said.
+    LocalCommand { intent: String, text: String },
+    /// A destructive tool call is waiting for a spoken yes or no (see
+    /// `voice::confirm`); `prompt` is the question being spoken.
+    ConfirmPrompt { tool: String, prompt: String },
+    /// The spoken confirmation for `tool` was settled. `answer` is "yes",
+    /// "no", "unclear" or "timeout".
+    ConfirmAnswer { tool: String, answer: String },
+    /// An AI asked the user a question (`voice_ask`, see `voice::ask`);
+    /// the answer is recorded once it has been spoken.
+    AskQuestion { question: String },
+    /// The question was answered (`answer`), or timed out (`None`).
+    AskAnswer { answer: Option<String> },
+    /// A conversation session opened with its first transcription (see
+    /// `voice::session`). `session_id` is the Unix time (ms) it started.
+    SessionStart { session_id: u64 },
+    /// A turn finished: `transcription_id` is its `Transcription` id,
+    /// `replied` whether the provider answered, `spoken` whether the answer
+    /// was played, and `interrupted` whether playback was cut short.
+    TurnComplete {
+        session_id: u64,
+        turn: u32,
+        transcription_id: u64,
+        replied: bool,
+        spoken: bool,
+        interrupted: bool,
+    },
+    /// A session ended. `reason` is "inactivity" (`session_timeout_secs`
+    /// passed) or "stopped" (the pipeline stopped).
+    SessionEnd {
+        session_id: u64,
+        turns: u32,
+        duration_secs: u64,
+        reason: String,
+    },
+    /// The latency ladder moved (see `voice::latency_budget`). `rung` is
+    /// the step taken (`None` when a manual override went back to the
+    /// top), `detail` what it changed; `latency_ms` is the last turn's
+    /// measured latency when `budget_ms` was exceeded. `manual` marks a
+    /// `set_latency_ladder` override.
+    LatencyLadder {
+        level: usize,
+        rung: Option<crate::voice::latency_budget::Rung>,
+        detail: String,
+        latency_ms: Option<u64>,
+        budget_ms: u64,
+        manual: bool,
+    },
+    /// The processing loop stopped consuming audio (see `watchdog`).
+    /// `reason` is "loop_exited", "lock_poisoned", "no_heartbeat" or
+    /// "not_draining"; `restarting` whether the pipeline is being restarted
+    /// (`voice.watchdogAutoRestart`, within the restart limit).
+    PipelineStalled {
+        reason: watchdog::StallReason,
+        detail: String,
+        restarting: bool,
+    },
+}
+
+impl VoiceEvent {
+    /// The `Error` event for `err`, recorded in the error log.
+    pub fn error(err: AppError) -> Self {
+        Self::recorded(&err, None)
+    }
+
+    /// Like `error`, for a failure during the turn in progress.
+    pub(crate) fn turn_error(shared: &PipelineShared, err: AppError) -> Self {
+        Self::recorded(&err, timing::current_turn(shared))
+    }
+
+    fn recorded(err: &AppError, turn: Option<u64>) -> Self {
+        let record = crate::voice::errors::record(err, turn);
+        Self::Error {
+            id: record.id,
+            code: record.code,
+            message: record.message,
+            turn: record.turn,
+        }
+    }
 }
 
 /// Audio device info for the frontend.
@@ -105,6 +326,13 @@ struct SendStream(cpal::Stream);
 // drop it, which is safe.
 unsafe impl Send for SendStream {}
 
+/// Whatever feeds the ring buffer; dropping it stops capture.
+#[allow(dead_code)]
+enum CaptureSource {
+    Device(SendStream),
+    Network(network_input::NetworkCapture),
+}
+
 /// The running voice pipeline.
 ///
 /// Manages background threads for audio capture and processing.
@@ -112,8 +340,10 @@ unsafe impl Send for SendStream {}
 pub struct VoicePipeline {
     /// Shared state (accessible from multiple threads).
     shared: Arc<PipelineShared>,
-    /// Handle to the cpal capture stream (must be kept alive).
-    _capture_stream: Option<SendStream>,
+    /// Handle to the capture stream (must be kept alive).
+    _capture_stream: Option<CaptureSource>,
+    /// Loopback stream of the system channel (`captureSource: "both"`).
+    _system_stream: Option<SendStream>,
     /// Handle to the audio processing task.
     processing_handle: Option<tauri::async_runtime::JoinHandle<()>>,
 }
@@ -126,6 +356,18 @@ pub(crate) struct PipelineShared {
     pub(crate) mode: std::sync::Mutex<VoiceMode>,
     /// Whether the pipeline is running.
     pub(crate) running: AtomicBool,
+    /// Set by the watchdog when the processing loop stopped consuming audio.
+    pub(crate) stalled: AtomicBool,
+    /// Mic released by `pause()`; the processing loop idles until `resume()`.
+    pub(crate) paused: AtomicBool,
+    /// Number of `pause()` calls, counted once the capture is released.
+    pauses: AtomicU32,
+    /// The `pauses` count the processing loop last settled: parked, with
+    /// the audio queued before the pause dropped. Only the loop reads the
+    /// ring buffer, so `resume()` waits for this before reattaching capture.
+    settled_pauses: AtomicU32,
+    /// Processing loop liveness, for the watchdog.
+    pub(crate) heartbeat: watchdog::Heartbeat,
     /// Cancellation flag for TTS playback.
     /// Used to signal the current speak() call to stop. Also checked by
     /// the synthesis loop. External callers (barge-in, stop_speaking) set
@@ -147,18 +389,71 @@ pub(crate) struct PipelineShared {
     pub(crate) app_handle: AppHandle,
     /// Audio ring buffer: producer side (written by capture callback).
     ring_producer: Mutex<Option<RingProducer>>,
-    /// Audio ring buffer: consumer side (read by processing thread).
-    ring_consumer: Mutex<Option<RingConsumer>>,
+    /// Audio ring buffer: consumer side. Only the processing loop reads it.
+    ring_consumer: RingConsumer,
+    /// System channel ring buffer, until the processing loop takes it.
+    system_audio: Mutex<Option<RingConsumer>>,
+    /// Rate and chunk size of the captured audio (`voice.sampleRate`,
+    /// `voice.chunkMs`).
+    pub(crate) format: AudioFormat,
+    /// Signalled when a recording is started, stopped, or cancelled, so the
+    /// processing loop handles it without waiting for the next chunk.
+    control: Notify,
     /// Accumulated recording buffer.
     recording_buf: Mutex<Vec<f32>>,
-    /// STT engine.
+    /// STT engine. Empty until its first load finishes.
     stt_engine: Mutex<Option<SttAdapter>>,
+    /// Runs the first STT load once (see `loading`).
+    stt_loader: loading::EngineLoader,
     /// TTS engine for speech synthesis output.
     pub(crate) tts_engine: Mutex<Option<Box<dyn TtsEngine>>>,
+    /// Runs the first TTS load once (see `loading`).
+    tts_loader: loading::EngineLoader,
+    /// `speak()` calls holding the TTS engine (see `recovery::TtsCheckout`).
+    pub(crate) tts_checkouts: AtomicUsize,
+    /// Stage timestamps of the current voice turn.
+    pub(crate) turn_timer: Mutex<timing::TurnTimer>,
+    /// Voice/speed changes waiting for `speak()` to return the TTS engine.
+    pub(crate) pending_tts: Mutex<playback::PendingTtsSettings>,
+    /// The selected TTS voice (`config.tts_voice`, then `set_tts_voice`).
+    /// Replies spoken in an agent's own voice switch back to it.
+    pub(crate) tts_voice: Mutex<String>,
+    /// TTS playback volume as `f32` bits. Starts at `config.tts_volume` and
+    /// can be changed while running; read at the start of each utterance.
+    pub(crate) tts_volume: AtomicU32,
+    /// Voice effect chain (`tts::effects` specs). Starts at
+    /// `config.tts_effects`; read at the start of each utterance.
+    pub(crate) tts_effects: Mutex<Vec<String>>,
+    /// Text of the last `speak()`, for the "repeat that" command.
+    pub(crate) last_spoken: Mutex<Option<String>>,
+    /// Phrases the last `speak()` didn't finish because it was interrupted
+    /// (barge-in, stop), for the "continue" command. Cleared by the next
+    /// `speak()`.
+    pub(crate) unspoken: Mutex<Vec<String>>,
+    /// Local voice command grammar (`None` when `local_commands` is off).
+    intents: Option<IntentGrammar>,
+    /// Transcription post-processing (`None` when no rule is enabled).
+    transcript_rules: Option<TranscriptRules>,
+    /// Transcription routing table (`None` when no route is configured).
+    transcript_router: Option<TranscriptRouter>,
+    /// Speaker model, once loaded (only with `speaker_id` on).
+    speaker_encoder: Mutex<Option<Arc<SpeakerEncoder>>>,
+    /// Conversation sessions, owned by the `VoiceEngine`.
+    pub(crate) session: Arc<SessionTracker>,
+    /// Speech waiting for the current utterance (see `speech_queue`).
+    pub(crate) speech_queue: Mutex<SpeechQueue>,
     /// Pipeline configuration.
     pub(crate) config: VoiceEngineConfig,
 }
 
+/// The TTS engine pre-loaded at app launch, if it is still unclaimed.
+pub(super) fn take_preloaded_tts(app_handle: &AppHandle) -> Option<Box<dyn TtsEngine>> {
+    use tauri::Manager;
+    app_handle
+        .try_state::<crate::PreloadedTtsState>()
+        .and_then(|state| state.lock().ok()?.take())
+}
+
 // ── State helpers ───────────────────────────────────────────────────
 
 pub(crate) fn state_from_u8(v: u8) -> VoiceState {
@@ -188,74 +483,74 @@ impl VoicePipeline {
     /// Start the voice pipeline with the given configuration.
     ///
     /// This initializes audio capture, VAD, STT, and TTS, then spawns
-    /// background processing tasks.
-    pub fn start(config: VoiceEngineConfig, app_handle: AppHandle) -> Result<Self, String> {
+    /// background processing tasks. Turns are grouped into `session`.
+    pub fn start(
+        config: VoiceEngineConfig,
+        app_handle: AppHandle,
+        session: Arc<SessionTracker>,
+    ) -> Result<Self, AppError> {
         tracing::info!("Starting voice pipeline");
 
         // Emit starting event
-        let _ = app_handle.emit("voice-event", VoiceEvent::Starting {});
+        let _ = app_handle.emit_throttled("voice-event", VoiceEvent::Starting {});
 
         // Create ring buffer for audio
-        let (producer, consumer) = create_ring_buffer(RING_BUFFER_CAPACITY);
-
-        let data_dir = crate::services::platform::get_data_dir();
-        let stt_engine = match stt::create_stt_engine(
-            &config.stt_adapter,
-            &data_dir,
-            Some(&config.stt_model_size),
-            config.stt_use_gpu,
-        ) {
-            Ok(engine) => {
-                tracing::info!(adapter = %config.stt_adapter, "STT engine initialized");
-                Some(engine)
-            }
-            Err(e) => {
-                tracing::warn!("STT engine failed to initialize: {}", e);
-                let _ = app_handle.emit(
-                    "voice-event",
-                    VoiceEvent::Error {
-                        message: format!("STT not available: {}", e),
-                    },
-                );
-                None
+        let format = AudioFormat::from_config(&config);
+        tracing::info!(
+            sample_rate = format.sample_rate,
+            chunk_samples = format.chunk_samples,
+            "Pipeline audio format"
+        );
+        let (producer, consumer) = create_ring_buffer(format.ring_capacity());
+
+        // Open network outputs now so listeners can connect before TTS plays
+        remote_output::sync(
+            config
+                .output_device
+                .iter()
+                .chain(&config.output_devices)
+                .map(String::as_str),
+        );
+
+        // Only `blocking` engines are loaded here; see `loading`
+        let stt_engine = if config.stt_load == EngineLoadMode::Blocking {
+            match recovery::stt_engine_from_config(&config) {
+                Ok(engine) => {
+                    tracing::info!(adapter = %config.stt_adapter, "STT engine initialized");
+                    Some(engine)
+                }
+                Err(e) => {
+                    tracing::warn!("STT engine failed to initialize: {}", e);
+                    let _ = app_handle.emit_throttled(
+                        "voice-event",
+                        VoiceEvent::error(AppError::from(e).context("STT not available")),
+                    );
+                    None
+                }
             }
+        } else {
+            None
         };
 
         // Initialize TTS engine — try pre-loaded first, then create a new one
-        let tts_engine = {
-            // Check for pre-loaded engine from app startup
-            use tauri::Manager;
-            let preloaded: Option<Box<dyn TtsEngine>> = app_handle
-                .try_state::<crate::PreloadedTtsState>()
-                .and_then(|state| state.lock().ok()?.take());
-
-            match preloaded {
-                Some(engine) => {
-                    tracing::info!(name = %engine.name(), "Using pre-loaded TTS engine");
+        let tts_engine = if config.tts_load != EngineLoadMode::Blocking {
+            None
+        } else if let Some(engine) = take_preloaded_tts(&app_handle) {
+            tracing::info!(name = %engine.name(), "Using pre-loaded TTS engine");
+            Some(engine)
+        } else {
+            match recovery::tts_engine_from_config(&config) {
+                Ok(engine) => {
+                    tracing::info!(adapter = %config.tts_adapter, name = %engine.name(), "TTS engine initialized");
                     Some(engine)
                 }
-                None => {
-                    // Fall back to creating a new engine
-                    match tts::create_tts_engine(
-                        &config.tts_adapter,
-                        Some(&config.tts_voice),
-                        Some(config.tts_speed),
-                    ) {
-                        Ok(engine) => {
-                            tracing::info!(adapter = %config.tts_adapter, name = %engine.name(), "TTS engine initialized");
-                            Some(engine)
-                        }
-                        Err(e) => {
-                            tracing::warn!("TTS engine failed to initialize: {}", e);
-                            let _ = app_handle.emit(
-                                "voice-event",
-                                VoiceEvent::Error {
-                                    message: format!("TTS not available: {}", e),
-                                },
-                            );
-                            None
-                        }
-                    }
+                Err(e) => {
+                    tracing::warn!("TTS engine failed to initialize: {}", e);
+                    let _ = app_handle.emit_throttled(
+                        "voice-event",
+                        VoiceEvent::error(AppError::from(e).context("TTS not available")),
+                    );
+                    None
                 }
             }
         };
@@ -265,28 +560,72 @@ impl VoicePipeline {
             state: AtomicU8::new(state_to_u8(VoiceState::Idle)),
             mode: std::sync::Mutex::new(config.mode),
             running: AtomicBool::new(true),
+            stalled: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            pauses: AtomicU32::new(0),
+            settled_pauses: AtomicU32::new(0),
+            heartbeat: watchdog::Heartbeat::new(),
             tts_cancel: AtomicBool::new(false),
             active_playback_cancel: Mutex::new(None),
             force_stop_recording: AtomicBool::new(false),
             force_cancel_recording: AtomicBool::new(false),
             app_handle: app_handle.clone(),
             ring_producer: Mutex::new(Some(producer)),
-            ring_consumer: Mutex::new(Some(consumer)),
+            ring_consumer: consumer,
+            system_audio: Mutex::new(None),
+            format,
+            control: Notify::new(),
             recording_buf: Mutex::new(Vec::new()),
             stt_engine: Mutex::new(stt_engine),
+            stt_loader: loading::EngineLoader::new(config.stt_load),
             tts_engine: Mutex::new(tts_engine),
+            tts_loader: loading::EngineLoader::new(config.tts_load),
+            tts_checkouts: AtomicUsize::new(0),
+            turn_timer: Mutex::new(timing::TurnTimer::default()),
+            pending_tts: Mutex::new(playback::PendingTtsSettings::default()),
+            tts_voice: Mutex::new(config.tts_voice.clone()),
+            tts_volume: AtomicU32::new(config.tts_volume.to_bits()),
+            tts_effects: Mutex::new(config.tts_effects.clone()),
+            last_spoken: Mutex::new(None),
+            unspoken: Mutex::new(Vec::new()),
+            intents: config
+                .local_commands
+                .then(|| IntentGrammar::new(&config.commands)),
+            transcript_rules: TranscriptRules::new(&config.transcript_rules),
+            transcript_router: TranscriptRouter::new(&config.transcript_routes),
+            speaker_encoder: Mutex::new(None),
+            session,
+            speech_queue: Mutex::new(SpeechQueue::default()),
             config,
         });
 
         // Start audio capture
         let capture_stream = start_audio_capture(&shared)?;
+        // The system channel is an extra; the mic works without it
+        let system_stream = system_channel::start(&shared).unwrap_or_else(|e| {
+            tracing::warn!("System audio capture unavailable: {}", e);
+            let _ = app_handle.emit_throttled(
+                "voice-event",
+                VoiceEvent::error(e.context("System audio capture unavailable")),
+            );
+            None
+        });
+
+        loading::spawn_background(&shared);
 
         // Spawn the audio processing loop
         let shared_clone = Arc::clone(&shared);
         let processing_handle = tauri::async_runtime::spawn(async move {
+            let _alive = watchdog::LoopGuard(Arc::clone(&shared_clone));
             audio_processing_loop(shared_clone).await;
         });
 
+        // Restarts the pipeline if the loop above dies or stops reading audio
+        let loop_watchdog_shared = Arc::clone(&shared);
+        tauri::async_runtime::spawn(async move {
+            watchdog::run(loop_watchdog_shared).await;
+        });
+
         // Spawn the stuck-state watchdog. Runs independently of the processing
         // loop (which can block on STT), so it can still notify the frontend
         // when the pipeline wedges. Exits when `running` is cleared in stop().
@@ -295,6 +634,21 @@ impl VoicePipeline {
             stuck_watchdog(watchdog_shared).await;
         });
 
+        // Tells the UI when the OS mutes the mic
+        tauri::async_runtime::spawn(privacy::poll_os_mute(Arc::clone(&shared)));
+
+        // Ends the conversation session once it has been idle too long
+        let session_shared = Arc::clone(&shared);
+        tauri::async_runtime::spawn(async move {
+            let timeout = Duration::from_secs(session_shared.config.session_timeout_secs);
+            while session_shared.running.load(Ordering::Relaxed) {
+                tokio::time::sleep(super::session::EXPIRY_POLL).await;
+                session_shared
+                    .session
+                    .expire(&session_shared.app_handle, timeout);
+            }
+        });
+
         // Set initial state based on mode
         {
             let mode = match shared.mode.lock() {
@@ -310,7 +664,7 @@ impl VoicePipeline {
                     shared
                         .state
                         .store(state_to_u8(VoiceState::Listening), Ordering::Release);
-                    let _ = app_handle.emit(
+                    let _ = app_handle.emit_throttled(
                         "voice-event",
                         VoiceEvent::StateChange {
                             state: "listening".into(),
@@ -324,20 +678,25 @@ impl VoicePipeline {
         }
 
         // Emit ready event
-        let _ = app_handle.emit("voice-event", VoiceEvent::Ready {});
+        let _ = app_handle.emit_throttled("voice-event", VoiceEvent::Ready {});
         tracing::info!("Voice pipeline ready");
 
         Ok(Self {
             shared,
-            _capture_stream: Some(SendStream(capture_stream)),
+            _capture_stream: Some(capture_stream),
+            _system_stream: system_stream,
             processing_handle: Some(processing_handle),
         })
     }
 
-    /// Stop the voice pipeline.
-    pub fn stop(self) {
+    /// Stop the voice pipeline, letting work in flight finish first (see
+    /// `shutdown`).
+    pub fn stop(mut self) {
         tracing::info!("Stopping voice pipeline");
+        let stalled = self.is_stalled();
         self.shared.running.store(false, Ordering::SeqCst);
+        // Wake the loop if it is waiting for audio
+        self.shared.control.notify_one();
         self.shared.tts_cancel.store(true, Ordering::SeqCst);
         if let Ok(guard) = self.shared.active_playback_cancel.lock() {
             if let Some(ref cancel) = *guard {
@@ -345,21 +704,147 @@ impl VoicePipeline {
             }
         }
 
+        self.shared.session.end(&self.shared.app_handle, "stopped");
+        remote_output::sync(std::iter::empty());
         let _ = self
             .shared
             .app_handle
-            .emit("voice-event", VoiceEvent::Stopping {});
-
-        // The capture stream and processing task will be dropped,
-        // which stops audio capture and aborts the processing loop.
-        if let Some(handle) = self.processing_handle {
+            .emit_throttled("voice-event", VoiceEvent::Stopping {});
+
+        if !stalled {
+            let shared = &self.shared;
+            if !shutdown::wait_until(shutdown::SINK_TIMEOUT, || {
+                shared.tts_checkouts.load(Ordering::Acquire) == 0
+            }) {
+                tracing::warn!("Speech playback didn't stop in time");
+            }
+            if !shutdown::wait_until(shutdown::DRAIN_TIMEOUT, || {
+                !shared.heartbeat.loop_alive()
+            }) {
+                tracing::warn!("Audio processing loop didn't exit in time; aborting it");
+            }
+        }
+        if let Some(handle) = self.processing_handle.take() {
+            // No-op once the loop has exited
             handle.abort();
         }
+
+        // Only now that nothing reads from them
+        self.release_capture();
+        tracing::info!("Voice pipeline stopped");
     }
 
-    /// Check if the pipeline is running.
+    /// Drop the capture streams, reporting a closed microphone.
+    fn release_capture(&mut self) {
+        drop(self._system_stream.take());
+        let device = matches!(self._capture_stream, Some(CaptureSource::Device(_)));
+        drop(self._capture_stream.take());
+        if device && self.shared.config.capture_source != CAPTURE_SYSTEM {
+            privacy::microphone_closed(&self.shared);
+        }
+    }
+
+    /// Check if the pipeline is running. False once the watchdog found it
+    /// stalled.
     pub fn is_running(&self) -> bool {
-        self.shared.running.load(Ordering::Relaxed)
+        self.shared.running.load(Ordering::Relaxed) && !self.is_stalled()
+    }
+
+    /// Whether the watchdog found the processing loop stalled.
+    pub fn is_stalled(&self) -> bool {
+        self.shared.stalled.load(Ordering::Relaxed)
+    }
+
+    /// Whether the mic is released by `pause()`.
+    pub fn is_paused(&self) -> bool {
+        self.shared.paused.load(Ordering::Acquire)
+    }
+
+    /// Stop consuming audio and release the microphone, keeping the STT and
+    /// TTS engines loaded. A recording in progress is discarded; speech
+    /// still plays. No-op if already paused.
+    pub fn pause(&mut self) {
+        if self.shared.paused.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        // The loop discards the recording and settles to Idle (see
+        // `settle_paused`) before it stops reading
+        self.shared.control.notify_one();
+        self.release_capture();
+        self.shared.pauses.fetch_add(1, Ordering::AcqRel);
+        tracing::info!("Voice pipeline paused, microphone released");
+        let _ = self
+            .shared
+            .app_handle
+            .emit_throttled("voice-event", VoiceEvent::Paused {});
+    }
+
+    /// Reopen the microphone after `pause()` and go back to the mode's
+    /// resting state. No-op if not paused. On error the pipeline stays
+    /// paused.
+    pub fn resume(&mut self) -> Result<(), AppError> {
+        if !self.is_paused() {
+            return Ok(());
+        }
+        // The parked loop drops the audio from before the pause; nothing
+        // may be written to the ring buffer until it has
+        if !self.wait_settled() {
+            return Err(AppError::Internal(
+                "Voice pipeline is still finishing the last turn; try again".into(),
+            ));
+        }
+        if let Ok(mut slot) = self.shared.ring_producer.lock() {
+            *slot = Some(self.shared.ring_consumer.reattach_producer());
+        }
+        let capture_stream = start_audio_capture(&self.shared)?;
+        let system_stream = system_channel::start(&self.shared).unwrap_or_else(|e| {
+            tracing::warn!("System audio capture unavailable: {}", e);
+            None
+        });
+        self._capture_stream = Some(capture_stream);
+        self._system_stream = system_stream;
+        self.shared.paused.store(false, Ordering::Release);
+        self.shared.control.notify_one();
+
+        let mode = self.shared.mode.lock().map(|g| *g).unwrap_or(VoiceMode::PushToTalk);
+        if mode == VoiceMode::WakeWord {
+            let resumed = self.shared.state.compare_exchange(
+                state_to_u8(VoiceState::Idle),
+                state_to_u8(VoiceState::Listening),
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            );
+            if resumed.is_ok() {
+                let _ = self.shared.app_handle.emit_throttled(
+                    "voice-event",
+                    VoiceEvent::StateChange {
+                        state: "listening".into(),
+                    },
+                );
+            }
+        }
+        self.shared.control.notify_one();
+        tracing::info!("Voice pipeline resumed");
+        let _ = self
+            .shared
+            .app_handle
+            .emit_throttled("voice-event", VoiceEvent::Resumed {});
+        Ok(())
+    }
+
+    /// Wait up to `RESUME_SETTLE_TIMEOUT` for the processing loop to settle
+    /// the latest pause (see `settled_pauses`). Returns whether it did.
+    fn wait_settled(&self) -> bool {
+        let pauses = self.shared.pauses.load(Ordering::Acquire);
+        let deadline = Instant::now() + RESUME_SETTLE_TIMEOUT;
+        self.shared.control.notify_one();
+        while self.shared.settled_pauses.load(Ordering::Acquire) != pauses {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        true
     }
 
     /// Get the current voice state.
@@ -384,7 +869,8 @@ impl VoicePipeline {
                     (VoiceState::Listening, VoiceMode::PushToTalk | VoiceMode::Toggle) => {
                         Some(VoiceState::Idle)
                     }
-                    (VoiceState::Idle, VoiceMode::WakeWord) => {
+                    // Paused: the mic is closed, nothing to listen to
+                    (VoiceState::Idle, VoiceMode::WakeWord) if !self.is_paused() => {
                         Some(VoiceState::Listening)
                     }
                     _ => None, // Don't interrupt recording/processing/speaking
@@ -392,7 +878,7 @@ impl VoicePipeline {
 
                 if let Some(state) = new_state {
                     self.shared.state.store(state_to_u8(state), Ordering::Release);
-                    let _ = self.shared.app_handle.emit(
+                    let _ = self.shared.app_handle.emit_throttled(
                         "voice-event",
                         VoiceEvent::StateChange {
                             state: state.to_string(),
@@ -411,6 +897,10 @@ impl VoicePipeline {
     /// Transitions Idle/Listening -> Recording. Also supports "barge-in":
     /// if TTS is currently speaking, it cancels playback and starts recording.
     pub fn start_recording(&self) {
+        if self.is_paused() {
+            tracing::debug!("Ignoring start_recording while paused");
+            return;
+        }
         let current = state_from_u8(self.shared.state.load(Ordering::Acquire));
         match current {
             VoiceState::Idle | VoiceState::Listening => {
@@ -419,6 +909,7 @@ impl VoicePipeline {
             VoiceState::Speaking => {
                 // Barge-in: interrupt TTS and start recording immediately
                 tracing::info!("Barge-in: interrupting TTS to start recording");
+                crate::services::runtime_context::note_interruption();
                 self.shared.tts_cancel.store(true, Ordering::SeqCst);
                 // Also cancel the per-request playback token
                 if let Ok(guard) = self.shared.active_playback_cancel.lock() {
@@ -444,13 +935,14 @@ impl VoicePipeline {
         self.shared
             .state
             .store(state_to_u8(VoiceState::Recording), Ordering::Release);
-        let _ = self.shared.app_handle.emit(
+        self.shared.control.notify_one();
+        let _ = self.shared.app_handle.emit_throttled(
             "voice-event",
             VoiceEvent::RecordingStart {
                 rec_type: "manual".into(),
             },
         );
-        let _ = self.shared.app_handle.emit(
+        let _ = self.shared.app_handle.emit_throttled(
             "voice-event",
             VoiceEvent::StateChange {
                 state: "recording".into(),
@@ -468,6 +960,7 @@ impl VoicePipeline {
         if current == VoiceState::Recording {
             tracing::info!("Force-stopping recording (manual release)");
             self.shared.force_stop_recording.store(true, Ordering::SeqCst);
+            self.shared.control.notify_one();
         } else {
             tracing::debug!(state = ?current, "Ignoring stop_recording in current state");
         }
@@ -480,13 +973,29 @@ impl VoicePipeline {
         if current == VoiceState::Recording {
             tracing::info!("Cancelling recording (user discarded)");
             self.shared.force_cancel_recording.store(true, Ordering::SeqCst);
+            self.shared.control.notify_one();
         } else {
             tracing::debug!(state = ?current, "Ignoring cancel_recording in current state");
         }
     }
 
-    /// Interrupt TTS playback.
+    /// Capture samples dropped because the processing loop fell more than
+    /// the ring buffer's length behind (e.g. blocked on a long STT run).
+    pub fn audio_overflow_samples(&self) -> u64 {
+        self.shared.ring_consumer.overflowed()
+    }
+
+    /// Interrupt TTS playback and drop any queued speech.
     pub fn stop_speaking(&self) {
+        if self.state() == VoiceState::Speaking {
+            crate::services::runtime_context::note_interruption();
+        }
+        if let Ok(mut queue) = self.shared.speech_queue.lock() {
+            let dropped = queue.clear();
+            if dropped > 0 {
+                tracing::info!(dropped, "Cleared queued speech");
+            }
+        }
         self.shared.tts_cancel.store(true, Ordering::SeqCst);
         // Also cancel the per-request playback token
         if let Ok(guard) = self.shared.active_playback_cancel.lock() {
@@ -497,19 +1006,85 @@ impl VoicePipeline {
         tracing::info!("TTS playback interrupted");
     }
 
-    /// Speak text using the TTS engine and play via rodio.
+    /// Switch the TTS voice without restarting the pipeline.
+    pub fn set_tts_voice(&self, voice: &str) -> Result<(), String> {
+        playback::set_tts_voice(&self.shared, voice)
+    }
+
+    /// Change the TTS speed multiplier without restarting the pipeline.
+    pub fn set_tts_speed(&self, speed: f32) -> Result<(), String> {
+        playback::set_tts_speed(&self.shared, speed)
+    }
+
+    /// Change the TTS playback volume (takes effect from the next utterance).
+    pub fn set_tts_volume(&self, volume: f32) {
+        self.shared.tts_volume.store(volume.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Change the voice effect chain (takes effect from the next utterance).
+    pub fn set_tts_effects(&self, effects: Vec<String>) {
+        *self.shared.tts_effects.lock().unwrap_or_else(|e| e.into_inner()) = effects;
+    }
+
+    /// The provider replied to the latest message; `speak` is whether the
+    /// reply goes to TTS.
+    pub fn note_reply(&self, speak: bool) {
+        self.shared.session.reply(&self.shared.app_handle, speak);
+    }
+
+    /// Text of the last utterance spoken.
+    pub fn last_spoken(&self) -> Option<String> {
+        self.shared.last_spoken.lock().ok()?.clone()
+    }
+
+    /// Speak the rest of an interrupted reply, starting with the phrase that
+    /// was cut off. Errors when nothing is left to say.
+    pub fn resume_speaking(&self) -> Result<(), AppError> {
+        let phrases = self
+            .shared
+            .unspoken
+            .lock()
+            .map(|mut unspoken| std::mem::take(&mut *unspoken))
+            .unwrap_or_default();
+        if phrases.is_empty() {
+            return Err(AppError::Tts("Nothing to continue".into()));
+        }
+        tracing::info!(phrases = phrases.len(), "Resuming interrupted speech");
+        self.speak_blocking(phrases.join(" "));
+        Ok(())
+    }
+
+    /// Speak text using the TTS engine and play via rodio, interrupting
+    /// anything already being said.
     ///
     /// This is the main entry point for TTS playback from external callers
     /// (e.g. Tauri commands, AI provider responses).
     pub async fn speak(&self, text: &str) -> Result<(), String> {
-        playback::speak(&self.shared, text).await
+        playback::speak_queued(&self.shared, text, None, SpeechPriority::Interrupt).await
     }
 
     /// Convenience method: spawn `speak()` on the tokio runtime (non-blocking).
     pub fn speak_blocking(&self, text: String) {
+        self.speak_with_priority(text, SpeechPriority::Interrupt);
+    }
+
+    /// Texts waiting in the speech queue.
+    pub fn queued_speech(&self) -> usize {
+        self.shared.speech_queue.lock().map(|q| q.len()).unwrap_or(0)
+    }
+
+    /// Spawn a speech request that treats speech in progress according to
+    /// `priority` (interrupt it, queue behind it, or drop if busy).
+    pub fn speak_with_priority(&self, text: String, priority: SpeechPriority) {
+        self.speak_as(text, priority, None);
+    }
+
+    /// `speak_with_priority` in `voice` instead of the selected TTS voice
+    /// (an agent's own voice, see `services::instances`).
+    pub fn speak_as(&self, text: String, priority: SpeechPriority, voice: Option<String>) {
         let shared = Arc::clone(&self.shared);
         tauri::async_runtime::spawn(async move {
-            if let Err(e) = playback::speak(&shared, &text).await {
+            if let Err(e) = playback::speak_queued(&shared, &text, voice.as_deref(), priority).await {
                 tracing::error!("speak_blocking failed: {}", e);
             }
         });
@@ -518,28 +1093,67 @@ impl VoicePipeline {
 
 // ── Audio Capture ───────────────────────────────────────────────────
 
-/// Start cpal audio capture, pushing samples into the ring buffer.
-fn start_audio_capture(shared: &Arc<PipelineShared>) -> Result<cpal::Stream, String> {
-    let host = cpal::default_host();
-
-    // Find the input device
-    let device = if let Some(ref name) = shared.config.input_device {
-        host.input_devices()
-            .map_err(|e| format!("Failed to enumerate input devices: {}", e))?
-            .find(|d| d.name().map(|n| n == *name).unwrap_or(false))
-            .ok_or_else(|| format!("Input device not found: {}", name))?
-    } else {
-        host.default_input_device()
-            .ok_or_else(|| "No default input device available".to_string())?
-    };
+/// Start audio capture from the configured input device (or network
+/// source, or system output), pushing samples into the ring buffer.
+fn start_audio_capture(shared: &Arc<PipelineShared>) -> Result<CaptureSource, AppError> {
+    let system = shared.config.capture_source == CAPTURE_SYSTEM;
+    if let Some(source) = shared
+        .config
+        .input_device
+        .as_deref()
+        .filter(|_| !system)
+        .and_then(network_input::NetworkSource::parse)
+    {
+        let producer = take_ring_producer(shared)?;
+        let resampler_kind = ResamplerKind::from_config(&shared.config.resampler);
+        let capture = network_input::start(source?, producer, resampler_kind, shared.format)?;
+        return Ok(CaptureSource::Network(capture));
+    }
+    start_device_capture(shared).map(|stream| CaptureSource::Device(SendStream(stream)))
+}
 
-    let dev_name = device.name().unwrap_or_else(|_| "unknown".into());
-    tracing::info!(device = %dev_name, "Selected input device");
+/// Take the ring buffer producer out of shared state for the capture side.
+fn take_ring_producer(shared: &PipelineShared) -> Result<RingProducer, AppError> {
+    shared
+        .ring_producer
+        .lock()
+        .map_err(|e| AppError::Internal(format!("Failed to lock ring_producer: {}", e)))?
+        .take()
+        .ok_or_else(|| AppError::Internal("Ring buffer producer already taken".into()))
+}
 
-    let default_config = device
-        .default_input_config()
-        .map_err(|e| format!("Failed to get default input config: {}", e))?;
+/// Start cpal audio capture, pushing samples into the ring buffer.
+fn start_device_capture(shared: &Arc<PipelineShared>) -> Result<cpal::Stream, AppError> {
+    let (device, default_config) = capture_device(&shared.config)?;
+    let resampler_kind = ResamplerKind::from_config(&shared.config.resampler);
+    // Take the producer out of shared state for the capture callback
+    let producer = take_ring_producer(shared)?;
+    let stream = build_capture_stream(
+        &device,
+        &default_config,
+        resampler_kind,
+        shared.format,
+        producer,
+    )?;
+    if shared.config.capture_source != CAPTURE_SYSTEM {
+        privacy::microphone_open(shared, device.name().ok());
+    }
+    Ok(stream)
+}
 
+/// Open and start an input stream on `device` that down-mixes, resamples
+/// to `format`'s rate and pushes chunks of `format.chunk_samples` into
+/// `producer`. The callback runs on the real-time audio thread, so it only
+/// writes the lock-free ring buffer and signals nobody; the processing loop
+/// polls for new chunks.
+pub(super) fn build_capture_stream(
+    device: &cpal::Device,
+    default_config: &cpal::SupportedStreamConfig,
+    resampler_kind: ResamplerKind,
+    format: AudioFormat,
+    producer: RingProducer,
+) -> Result<cpal::Stream, AppError> {
+    let chunk = format.chunk_samples;
     let native_rate = default_config.sample_rate().0;
     let channels = default_config.channels();
 
@@ -549,33 +1163,18 @@ fn start_audio_capture(shared: &Arc<PipelineShared>) -> Result<cpal::Stream, Str
         buffer_size: cpal::BufferSize::Default,
     };
 
-    let needs_resample = native_rate != TARGET_SAMPLE_RATE;
+    let mut resampler = StreamResampler::new(resampler_kind, native_rate, format.sample_rate);
     let needs_downmix = channels > 1;
 
     tracing::info!(
         native_rate,
         channels,
-        needs_resample,
+        resampler = ?resampler_kind,
         needs_downmix,
         "Audio input config"
     );
 
-    // Take the producer out of shared state for the capture callback
-    let producer_mutex = {
-        let mut guard = shared
-            .ring_producer
-            .lock()
-            .map_err(|e| format!("Failed to lock ring_producer: {}", e))?;
-        guard.take()
-    };
-
-    let Some(producer) = producer_mutex else {
-        return Err("Ring buffer producer already taken".into());
-    };
-
-    // Wrap producer in Arc<Mutex> for the callback (cpal callbacks need Send)
-    let producer = Arc::new(Mutex::new(producer));
-    let mut chunk_buf: Vec<f32> = Vec::with_capacity(CHUNK_SAMPLES * 2);
+    let mut chunk_buf: Vec<f32> = Vec::with_capacity(chunk * 2);
 
     let stream = device
         .build_input_stream(
@@ -591,22 +1190,14 @@ fn start_audio_capture(shared: &Arc<PipelineShared>) -> Result<cpal::Stream, Str
                     data.to_vec()
                 };
 
-                // Resample to 16kHz if needed
-                let resampled = if needs_resample {
-                    resample_linear(&mono, native_rate, TARGET_SAMPLE_RATE)
-                } else {
-                    mono
-                };
+                // Resample to the pipeline rate if needed
+                let resampled = resampler.process(mono);
 
                 // Accumulate and push full chunks
                 chunk_buf.extend_from_slice(&resampled);
-                while chunk_buf.len() >= CHUNK_SAMPLES {
-                    let chunk: Vec<f32> = chunk_buf.drain(..CHUNK_SAMPLES).collect();
-                    if let Ok(prod) = producer.lock() {
-                        if let Ok(mut ring) = prod.buffer.lock() {
-                            ring.push_slice(&chunk);
-                        }
-                    }
+                while chunk_buf.len() >= chunk {
+                    producer.push_slice(&chunk_buf[..chunk]);
+                    chunk_buf.drain(..chunk);
                 }
             },
             move |err| {
@@ -614,18 +1205,110 @@ fn start_audio_capture(shared: &Arc<PipelineShared>) -> Result<cpal::Stream, Str
             },
             None,
         )
-        .map_err(|e| format!("Failed to build input stream: {}", e))?;
+        .map_err(|e| AppError::AudioDevice(format!("Failed to build input stream: {}", e)))?;
 
     stream
         .play()
-        .map_err(|e| format!("Failed to start input stream: {}", e))?;
+        .map_err(|e| AppError::AudioDevice(format!("Failed to start input stream: {}", e)))?;
 
     tracing::info!("Audio capture started");
     Ok(stream)
 }
 
+/// The device to record and its native stream config: the input device,
+/// or with `captureSource: "system"` the output device in loopback mode.
+pub(crate) fn capture_device(
+    config: &VoiceEngineConfig,
+) -> Result<(cpal::Device, cpal::SupportedStreamConfig), AppError> {
+    if config.capture_source == CAPTURE_SYSTEM {
+        let device = find_loopback_device(config.output_device.as_deref())?;
+        let dev_name = device.name().unwrap_or_else(|_| "unknown".into());
+        tracing::info!(device = %dev_name, "Selected loopback device (system audio)");
+        // A loopback stream has the render device's format.
+        let default_config = device.default_output_config().map_err(|e| {
+            AppError::AudioDevice(format!("Failed to get loopback config: {}", e))
+        })?;
+        return Ok((device, default_config));
+    }
+
+    // As a call app would: see `services::audio_session`
+    let category = AudioSessionCategory::from_config(&config.audio_session);
+    audio_session::apply(category);
+    let comms_device = (config.input_device.is_none()
+        && category == AudioSessionCategory::Communications)
+        .then(audio_session::communications_input_device)
+        .flatten();
+    let device = match comms_device.as_deref().map(|name| find_input_device(Some(name))) {
+        Some(Ok(device)) => device,
+        _ => find_input_device(config.input_device.as_deref())?,
+    };
+    let dev_name = device.name().unwrap_or_else(|_| "unknown".into());
+    tracing::info!(device = %dev_name, audio_session = %config.audio_session, "Selected input device");
+    let default_config = device
+        .default_input_config()
+        .map_err(|e| AppError::AudioDevice(format!("Failed to get default input config: {}", e)))?;
+    Ok((device, default_config))
+}
+
+/// Look up the output device whose playback "system" capture records (by
+/// name, or the system default for `None` or a network target).
+///
+/// cpal turns an input stream on a WASAPI render device into a loopback
+/// capture. Other hosts have no equivalent, so this fails off Windows; on
+/// Linux a PulseAudio/PipeWire "Monitor of ..." input device does the same
+/// job as a regular microphone.
+pub(crate) fn find_loopback_device(name: Option<&str>) -> Result<cpal::Device, AppError> {
+    if !cfg!(target_os = "windows") {
+        return Err(AppError::AudioDevice(
+            "System audio capture (WASAPI loopback) is only available on Windows. \
+             On Linux, select a \"Monitor of ...\" input device instead."
+                .into(),
+        ));
+    }
+    let host = cpal::default_host();
+    let named = match name {
+        Some(name) => host
+            .output_devices()
+            .map_err(|e| {
+                AppError::AudioDevice(format!("Failed to enumerate output devices: {}", e))
+            })?
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false)),
+        None => None,
+    };
+    if named.is_none() {
+        if let Some(name) = name {
+            tracing::warn!(device = %name, "Loopback device not found, using the default output");
+        }
+    }
+    named
+        .or_else(|| host.default_output_device())
+        .ok_or_else(|| AppError::AudioDevice("No default output device to capture".into()))
+}
+
+/// Look up an input device by name, or the system default for `None`.
+pub(crate) fn find_input_device(name: Option<&str>) -> Result<cpal::Device, AppError> {
+    let host = cpal::default_host();
+    if let Some(name) = name.filter(|n| n.starts_with(network_input::RTP_SCHEME)) {
+        return Err(AppError::MicUnavailable(format!(
+            "{} is a network source, not a local microphone",
+            name
+        )));
+    }
+    if let Some(name) = name {
+        host.input_devices()
+            .map_err(|e| {
+                AppError::AudioDevice(format!("Failed to enumerate input devices: {}", e))
+            })?
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+            .ok_or_else(|| AppError::MicUnavailable(format!("Input device not found: {}", name)))
+    } else {
+        host.default_input_device()
+            .ok_or_else(|| AppError::MicUnavailable("No default input device available".into()))
+    }
+}
+
 /// Simple linear resampler from one rate to another.
-fn resample_linear(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+pub(crate) fn resample_linear(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     if from_rate == to_rate {
         return input.to_vec();
     }
@@ -736,11 +1419,15 @@ async fn stuck_watchdog(shared: Arc<PipelineShared>) {
                     // the state already moved and no-op its own finish_speaking,
                     // and will restore the TTS engine into shared.tts_engine.
                     playback::finish_speaking(&shared);
+                    // Don't hold queued speech behind the wedged request
+                    if let Ok(mut queue) = shared.speech_queue.lock() {
+                        queue.abandon();
+                    }
                     // Intentionally NO Stuck event here — silent recovery.
                 } else {
                     // Processing (wedged STT) / Recording (forgotten toggle) genuinely
                     // need the user, so surface the toast for those only.
-                    let _ = shared.app_handle.emit(
+                    let _ = shared.app_handle.emit_throttled(
                         "voice-event",
                         VoiceEvent::Stuck {
                             state: state.to_string(),
@@ -755,92 +1442,269 @@ async fn stuck_watchdog(shared: Arc<PipelineShared>) {
     tracing::info!("Stuck watchdog exiting");
 }
 
+/// Bring a paused pipeline to rest: a recording in progress is discarded
+/// (no STT) and Listening/Recording drop to Idle, since the mic is closed.
+/// Returns whether a recording was discarded.
+fn settle_paused(shared: &PipelineShared) -> bool {
+    let current = state_from_u8(shared.state.load(Ordering::Acquire));
+    if !matches!(current, VoiceState::Listening | VoiceState::Recording) {
+        return false;
+    }
+    let recording = current == VoiceState::Recording;
+    if recording {
+        tracing::info!("Discarding recording, voice paused");
+        shared.force_stop_recording.store(false, Ordering::SeqCst);
+        shared.force_cancel_recording.store(false, Ordering::SeqCst);
+        if let Ok(mut buf) = shared.recording_buf.lock() {
+            buf.clear();
+        }
+        let _ = shared.app_handle.emit_throttled(
+            "voice-event",
+            VoiceEvent::RecordingStop { reason: "paused".into() },
+        );
+    }
+    shared
+        .state
+        .store(state_to_u8(VoiceState::Idle), Ordering::Release);
+    let _ = shared.app_handle.emit_throttled(
+        "voice-event",
+        VoiceEvent::StateChange { state: "idle".into() },
+    );
+    recording
+}
+
+/// Wait until the ring buffer holds a whole chunk, a recording is started,
+/// stopped or cancelled, or `CHUNK_WAIT_TIMEOUT` passes. Capture never
+/// signals: waking a tokio waiter takes a lock, which the real-time audio
+/// callback must not. Instead the ring buffer's write counter (an atomic)
+/// is checked `CHUNK_POLLS` times per chunk, adding at most that fraction
+/// of a chunk to the latency.
+async fn wait_for_chunk(shared: &PipelineShared) {
+    let format = shared.format;
+    let poll = Duration::from_secs_f64(format.secs(format.chunk_samples) / CHUNK_POLLS as f64);
+    let deadline = Instant::now() + CHUNK_WAIT_TIMEOUT;
+    while shared.ring_consumer.available() < format.chunk_samples {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return;
+        }
+        tokio::select! {
+            _ = shared.control.notified() => return,
+            _ = tokio::time::sleep(poll.min(left)) => {}
+        }
+    }
+}
+
 async fn audio_processing_loop(shared: Arc<PipelineShared>) {
-    let mut read_buf = vec![0.0f32; CHUNK_SAMPLES];
+    let format = shared.format;
+    let mut read_buf = vec![0.0f32; format.chunk_samples];
     let mut vad = VadProcessor::new(shared.config.vad_threshold);
     let silence_timeout = Duration::from_secs_f64(shared.config.silence_timeout_secs);
+    // Last whole second reported by RecordingElapsed
+    let mut elapsed_secs = 0;
+    let mut drift = DriftCompensator::new(DriftConfig::for_format(&format));
+    // Turns other apps down while recording so they don't bleed into the mic.
+    // Never when recording system audio: their audio is what's being recorded.
+    let records_system = shared.config.capture_source == CAPTURE_SYSTEM
+        || shared.config.capture_source == system_channel::CAPTURE_BOTH;
+    let mut ducker = (shared.config.duck_while_recording && !records_system)
+        .then(|| AudioDucker::new(shared.config.duck_level));
+    // System audio transcribed next to the mic (`captureSource: "both"`)
+    let mut system = None;
+    // Idle wake-word listening sleeps between short windows to save power
+    let duty_mode = DutyCycleMode::from_config(&shared.config.listen_duty_cycle);
+    let mut duty = DutyCycle::new(shared.config.vad_threshold);
+    let duty_window = format.samples(duty_cycle::WINDOW_SECS);
+    let mut duty_buf = vec![0.0f32; format.samples(duty_cycle::MAX_BACKLOG_SECS)];
+    let mut on_battery = false;
+    let mut battery_checked: Option<Instant> = None;
+    // Cleans up the mic signal before VAD and the recording buffer see it
+    let mut denoiser = shared
+        .config
+        .noise_suppression
+        .then(|| NoiseSuppressor::new(format.sample_rate))
+        .flatten();
+    let mut denoised = Vec::with_capacity(duty_buf.len());
+    let mut reported_overflow = 0u64;
+    // A mic delivering digital silence is muted (network sources and system
+    // audio aren't a mic)
+    let mut silent_input = (shared.config.capture_source != CAPTURE_SYSTEM
+        && network_input::NetworkSource::parse(
+            shared.config.input_device.as_deref().unwrap_or_default(),
+        )
+        .is_none())
+    .then(|| privacy::SilentInput::new(&format));
 
     tracing::info!("Audio processing loop started");
 
     while shared.running.load(Ordering::Relaxed) {
-        // Sleep to avoid busy-waiting (40ms = roughly 2 chunks at 80ms each)
-        tokio::time::sleep(Duration::from_millis(40)).await;
+        shared.heartbeat.beat();
+
+        if shared.paused.load(Ordering::Acquire) {
+            if let Some(d) = ducker.as_mut() {
+                d.set_ducked(false);
+            }
+            if settle_paused(&shared) {
+                vad.reset();
+            }
+            // Once per pause, counted after the capture was released, so
+            // nothing is written after the discard until `resume()`
+            let pauses = shared.pauses.load(Ordering::Acquire);
+            if shared.settled_pauses.load(Ordering::Relaxed) != pauses {
+                shared.ring_consumer.discard(usize::MAX);
+                shared.settled_pauses.store(pauses, Ordering::Release);
+            }
+            tokio::select! {
+                _ = shared.control.notified() => {}
+                _ = tokio::time::sleep(CHUNK_WAIT_TIMEOUT) => {}
+            }
+            continue;
+        }
+        // Handed over at start, and again by each `resume()`
+        if let Some(ring) = shared.system_audio.lock().ok().and_then(|mut slot| slot.take()) {
+            system = Some(system_channel::SystemChannel::new(
+                ring,
+                &shared.config,
+                &shared.format,
+            ));
+        }
 
-        // Read from ring buffer
+        let now = Instant::now();
+        let battery_stale = !matches!(
+            battery_checked,
+            Some(t) if now.duration_since(t) < duty_cycle::BATTERY_POLL
+        );
+        if duty_mode == DutyCycleMode::OnBattery && battery_stale {
+            on_battery = tokio::task::spawn_blocking(duty_cycle::on_battery)
+                .await
+                .unwrap_or(false);
+            battery_checked = Some(now);
+        }
+        let duty_allowed = match duty_mode {
+            DutyCycleMode::Off => false,
+            DutyCycleMode::OnBattery => on_battery,
+            DutyCycleMode::Always => true,
+        };
+        let listening =
+            state_from_u8(shared.state.load(Ordering::Acquire)) == VoiceState::Listening;
+        if !listening {
+            // Stay at full rate through a conversation turn and just after it
+            duty.stay_awake(now);
+        }
+        let cycling = duty_allowed && listening && duty.is_cycling(now);
+
+        // Wake when capture has delivered a chunk, or right away for a
+        // recording start/stop/cancel. Duty cycling sleeps on purpose.
+        if cycling {
+            tokio::select! {
+                _ = tokio::time::sleep(duty_cycle::SLEEP) => {}
+                _ = shared.control.notified() => {}
+            }
+        } else {
+            wait_for_chunk(&shared).await;
+        }
+
+        // Never drop samples mid-recording: that would cut the user's speech
+        let current_state = state_from_u8(shared.state.load(Ordering::Acquire));
+        let recording = current_state == VoiceState::Recording;
+        if let Some(d) = ducker.as_mut() {
+            d.set_ducked(recording);
+        }
+        if let Some(system) = system.as_mut() {
+            system.poll(current_state);
+            if matches!(current_state, VoiceState::Idle | VoiceState::Listening) {
+                system.transcribe_pending(&shared).await;
+            }
+        }
+
+        if cycling && current_state == VoiceState::Listening {
+            // Keep the last second as pre-roll, but only look at the newest
+            // window; most wakes end right here.
+            let ring = &shared.ring_consumer;
+            let excess = ring.available().saturating_sub(duty_buf.len());
+            if excess > 0 {
+                ring.discard(excess);
+            }
+            let backlog_read = ring.pop_slice(&mut duty_buf);
+            let mut backlog = &duty_buf[..backlog_read];
+            if let Some(silent) = silent_input.as_mut() {
+                silent.check(&shared, backlog);
+            }
+            let window = &backlog[backlog.len().saturating_sub(duty_window)..];
+            if window.is_empty() || !duty.observe(window, Instant::now()) {
+                continue;
+            }
+            // Raw energy is enough to wake; VAD and the recording get clean audio
+            if let Some(ns) = denoiser.as_mut() {
+                ns.process(backlog, &mut denoised);
+                backlog = &denoised[..];
+            }
+            let window = &backlog[backlog.len().saturating_sub(duty_window)..];
+            process_listening_chunk(&shared, &mut vad, backlog, window);
+            continue;
+        }
+
+        // Read from ring buffer, then discard any backlog the drift
+        // compensator says has built up
         let samples_read = {
-            let guard = match shared.ring_consumer.lock() {
-                Ok(g) => g,
-                Err(e) => {
-                    tracing::error!("Failed to lock ring_consumer: {}", e);
-                    continue;
-                }
-            };
-            if let Some(ref consumer) = *guard {
-                if let Ok(mut ring) = consumer.buffer.lock() {
-                    ring.pop_slice(&mut read_buf)
-                } else {
-                    0
-                }
-            } else {
-                0
+            let ring = &shared.ring_consumer;
+            let read = ring.pop_slice(&mut read_buf);
+            let excess = drift.observe(ring.available(), !recording);
+            if excess > 0 {
+                ring.discard(excess);
             }
+            read
         };
+        if let (Some(silent), true) = (silent_input.as_mut(), samples_read > 0) {
+            silent.check(&shared, &read_buf[..samples_read]);
+        }
+
+        let overflowed = shared.ring_consumer.overflowed();
+        if overflowed > reported_overflow {
+            tracing::warn!(
+                dropped = overflowed - reported_overflow,
+                total = overflowed,
+                "Audio ring buffer overran, oldest samples dropped"
+            );
+            reported_overflow = overflowed;
+        }
 
-        if samples_read == 0 {
+        // A PTT release or cancel is handled even if no new audio came in
+        let stop_pending = recording
+            && (shared.force_stop_recording.load(Ordering::SeqCst)
+                || shared.force_cancel_recording.load(Ordering::SeqCst));
+        if samples_read == 0 && !stop_pending {
             continue;
         }
 
-        let chunk = &read_buf[..samples_read];
-        let current_state = state_from_u8(shared.state.load(Ordering::Acquire));
+        let mut chunk = &read_buf[..samples_read];
+        // Audio read in other states is thrown away; don't spend time on it
+        let wanted = matches!(current_state, VoiceState::Listening | VoiceState::Recording);
+        if let (Some(ns), true) = (denoiser.as_mut(), wanted && samples_read > 0) {
+            ns.process(chunk, &mut denoised);
+            if denoised.is_empty() {
+                continue;
+            }
+            chunk = &denoised[..];
+        }
 
         match current_state {
             VoiceState::Listening => {
-                // In listening mode, run VAD to detect speech onset.
-                let is_speech = vad.process_frame(chunk);
-
-                let mode = match shared.mode.lock() {
-                    Ok(g) => *g,
-                    Err(e) => {
-                        tracing::error!("Failed to lock mode: {}", e);
-                        VoiceMode::PushToTalk
-                    }
-                };
-                if is_speech && mode == VoiceMode::WakeWord {
-                    // Auto-start recording on speech detection (wake word / VAD mode)
-                    shared
-                        .state
-                        .store(state_to_u8(VoiceState::Recording), Ordering::Release);
-                    let _ = shared.app_handle.emit(
-                        "voice-event",
-                        VoiceEvent::RecordingStart {
-                            rec_type: "continuous".into(),
-                        },
-                    );
-                    match shared.recording_buf.lock() {
-                        Ok(mut buf) => {
-                            buf.clear();
-                            buf.extend_from_slice(chunk);
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to lock recording_buf: {}", e);
-                        }
-                    }
-                }
+                process_listening_chunk(&shared, &mut vad, chunk, chunk);
             }
 
             VoiceState::Recording => {
                 // Accumulate audio for STT
-                {
-                    match shared.recording_buf.lock() {
-                        Ok(mut buf) => {
-                            buf.extend_from_slice(chunk);
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to lock recording_buf: {}", e);
-                            continue;
-                        }
+                let recorded_samples = match shared.recording_buf.lock() {
+                    Ok(mut buf) => {
+                        buf.extend_from_slice(chunk);
+                        buf.len()
                     }
-                }
+                    Err(e) => {
+                        tracing::error!("Failed to lock recording_buf: {}", e);
+                        continue;
+                    }
+                };
 
                 // Emit audio levels for waveform visualization
                 {
@@ -866,29 +1730,52 @@ async fn audio_processing_loop(shared: Arc<PipelineShared>) {
                         }
                         let _ = shared
                             .app_handle
-                            .emit("voice-event", VoiceEvent::AudioLevel { levels });
+                            .emit_throttled("voice-event", VoiceEvent::AudioLevel { levels });
                     }
                 }
 
                 // Run VAD for silence detection
-                vad.process_frame(chunk);
+                if !chunk.is_empty() {
+                    vad.process_frame(chunk);
+                }
+
+                let current_mode = shared.mode.lock().map(|g| *g).unwrap_or(VoiceMode::PushToTalk);
+                // A stuck PTT key, a forgotten toggle or a noisy room would
+                // otherwise record forever
+                let limit = recording_limit(
+                    current_mode,
+                    shared.config.max_recording_secs,
+                    shared.config.toggle_timeout_secs,
+                    &format,
+                );
+
+                // Tick the UI's elapsed/countdown display
+                let secs = format.secs(recorded_samples) as u64;
+                if secs != elapsed_secs {
+                    elapsed_secs = secs;
+                    if secs > 0 {
+                        let _ = shared.app_handle.emit_throttled(
+                            "voice-event",
+                            VoiceEvent::RecordingElapsed {
+                                secs,
+                                limit_secs: limit.map(|(max, _)| format.secs(max) as u64),
+                            },
+                        );
+                    }
+                }
 
                 // Check for force-stop (PTT release / Toggle stop) OR silence timeout
                 // In toggle mode, only stop on manual press — never on silence
+                // (unless it's the answer to an AI's question)
                 let force_cancel = shared.force_cancel_recording.swap(false, Ordering::SeqCst);
                 let force_stop = shared.force_stop_recording.swap(false, Ordering::SeqCst);
-                let current_mode = shared.mode.lock().map(|g| *g).unwrap_or(VoiceMode::PushToTalk);
-                let silence_stop = current_mode != VoiceMode::Toggle && vad.silence_exceeded(silence_timeout);
+                let stops_on_silence = current_mode != VoiceMode::Toggle || ask::is_open();
+                let silence_stop = stops_on_silence && vad.silence_exceeded(silence_timeout);
+                let limit_hit = limit.filter(|&(max, _)| recorded_samples >= max);
                 if force_cancel {
                     // User discarded the recording — drop the audio, no STT.
                     tracing::info!("Discarding cancelled recording");
-                    if let Ok(guard) = shared.ring_consumer.lock() {
-                        if let Some(ref consumer) = *guard {
-                            if let Ok(mut ring) = consumer.buffer.lock() {
-                                let _ = ring.drain_all();
-                            }
-                        }
-                    }
+                    let _ = shared.ring_consumer.drain_all();
                     if let Ok(mut buf) = shared.recording_buf.lock() {
                         buf.clear();
                     }
@@ -898,57 +1785,55 @@ async fn audio_processing_loop(shared: Arc<PipelineShared>) {
                         VoiceMode::PushToTalk | VoiceMode::Toggle => VoiceState::Idle,
                     };
                     shared.state.store(state_to_u8(next_state), Ordering::Release);
-                    let _ = shared.app_handle.emit("voice-event", VoiceEvent::RecordingStop {});
-                    let _ = shared.app_handle.emit(
+                    let _ = shared.app_handle.emit_throttled(
+                        "voice-event",
+                        VoiceEvent::RecordingStop { reason: "cancelled".into() },
+                    );
+                    let _ = shared.app_handle.emit_throttled(
                         "voice-event",
                         VoiceEvent::StateChange { state: next_state.to_string() },
                     );
                     vad.reset();
-                } else if force_stop || silence_stop {
-                    tracing::info!(
-                        reason = if force_stop { "manual" } else { "silence" },
-                        "Stopping recording"
-                    );
+                } else if force_stop || silence_stop || limit_hit.is_some() {
+                    let reason = if force_stop {
+                        "manual"
+                    } else if let Some((_, reason)) = limit_hit {
+                        reason
+                    } else {
+                        "silence"
+                    };
+                    match limit_hit {
+                        Some((max, _)) if !force_stop => tracing::warn!(
+                            limit_secs = format.secs(max) as u64,
+                            reason,
+                            "Recording hit its time limit, forcing stop"
+                        ),
+                        _ => tracing::info!(reason, "Stopping recording"),
+                    }
 
                     shared
                         .state
                         .store(state_to_u8(VoiceState::Processing), Ordering::Release);
-                    let _ = shared
-                        .app_handle
-                        .emit("voice-event", VoiceEvent::RecordingStop {});
-                    let _ = shared.app_handle.emit(
+                    // Bring the volume back now rather than after STT finishes
+                    if let Some(d) = ducker.as_mut() {
+                        d.set_ducked(false);
+                    }
+                    let _ = shared.app_handle.emit_throttled(
+                        "voice-event",
+                        VoiceEvent::RecordingStop {
+                            reason: reason.into(),
+                        },
+                    );
+                    timing::begin(&shared);
+                    let _ = shared.app_handle.emit_throttled(
                         "voice-event",
                         VoiceEvent::StateChange {
                             state: "processing".into(),
                         },
                     );
 
-                    // Drain remaining audio from ring buffer.
-                    // The lock result must be fully resolved (not held) before
-                    // any .await, because MutexGuard is !Send.
-                    let drain_result: Result<Vec<f32>, String> = shared
-                        .ring_consumer
-                        .lock()
-                        .map(|guard| {
-                            if let Some(ref consumer) = *guard {
-                                if let Ok(mut ring) = consumer.buffer.lock() {
-                                    ring.drain_all()
-                                } else {
-                                    Vec::new()
-                                }
-                            } else {
-                                Vec::new()
-                            }
-                        })
-                        .map_err(|e| format!("{}", e));
-
-                    let remaining = match drain_result {
-                        Ok(v) => v,
-                        Err(e) => {
-                            tracing::error!("Failed to lock ring_consumer for drain: {}", e);
-                            Vec::new()
-                        }
-                    };
+                    // Drain remaining audio from ring buffer
+                    let remaining = shared.ring_consumer.drain_all();
 
                     let audio_for_stt = match shared.recording_buf.lock() {
                         Ok(mut buf) => {
@@ -975,7 +1860,7 @@ async fn audio_processing_loop(shared: Arc<PipelineShared>) {
                     shared
                         .state
                         .store(state_to_u8(next_state), Ordering::Release);
-                    let _ = shared.app_handle.emit(
+                    let _ = shared.app_handle.emit_throttled(
                         "voice-event",
                         VoiceEvent::StateChange {
                             state: next_state.to_string(),
@@ -996,109 +1881,309 @@ async fn audio_processing_loop(shared: Arc<PipelineShared>) {
     tracing::info!("Audio processing loop ended");
 }
 
+/// Recording length limit in samples at `format`'s rate, or `None` when
+/// unlimited.
+fn max_recording_samples(max_secs: f64, format: &AudioFormat) -> Option<usize> {
+    (max_secs > 0.0).then(|| format.samples(max_secs))
+}
+
+/// The limit that applies to a recording in `mode`, in samples, with the
+/// `RecordingStop` reason it produces. Toggle mode also has its own
+/// (usually shorter) timeout; the earlier of the two wins.
+fn recording_limit(
+    mode: VoiceMode,
+    max_secs: f64,
+    toggle_secs: f64,
+    format: &AudioFormat,
+) -> Option<(usize, &'static str)> {
+    let max = max_recording_samples(max_secs, format).map(|n| (n, "max_duration"));
+    let toggle = match mode {
+        VoiceMode::Toggle => {
+            max_recording_samples(toggle_secs, format).map(|n| (n, "toggle_timeout"))
+        }
+        VoiceMode::PushToTalk | VoiceMode::WakeWord => None,
+    };
+    match (max, toggle) {
+        (Some(max), Some(toggle)) if max.0 < toggle.0 => Some(max),
+        (max, toggle) => toggle.or(max),
+    }
+}
+
+/// Run VAD on `vad_frame` and, on speech in wake-word mode, start a
+/// recording seeded with `chunk` (which ends with `vad_frame`).
+fn process_listening_chunk(
+    shared: &Arc<PipelineShared>,
+    vad: &mut VadProcessor,
+    chunk: &[f32],
+    vad_frame: &[f32],
+) {
+    // In listening mode, run VAD to detect speech onset.
+    let is_speech = vad.process_frame(vad_frame);
+
+    let mode = match shared.mode.lock() {
+        Ok(g) => *g,
+        Err(e) => {
+            tracing::error!("Failed to lock mode: {}", e);
+            VoiceMode::PushToTalk
+        }
+    };
+    // Quiet hours keep wake-word mode from starting recordings; PTT and
+    // toggle still work.
+    if is_speech && mode == VoiceMode::WakeWord && !crate::services::quiet_hours::active() {
+        // Auto-start recording on speech detection (wake word / VAD mode)
+        shared
+            .state
+            .store(state_to_u8(VoiceState::Recording), Ordering::Release);
+        let _ = shared.app_handle.emit_throttled(
+            "voice-event",
+            VoiceEvent::RecordingStart {
+                rec_type: "continuous".into(),
+            },
+        );
+        match shared.recording_buf.lock() {
+            Ok(mut buf) => {
+                buf.clear();
+                buf.extend_from_slice(chunk);
+            }
+            Err(e) => {
+                tracing::error!("Failed to lock recording_buf: {}", e);
+            }
+        }
+    }
+}
+
 /// Run STT on recorded audio and emit the transcription as a Tauri event.
 async fn run_stt_and_emit(shared: &Arc<PipelineShared>, audio: Vec<f32>) {
     if audio.is_empty() {
+        timing::finish(shared, timing::TurnOutcome::NoSpeech);
         return;
     }
 
+    // STT always gets 16kHz; everything below counts in those samples
+    let audio = shared
+        .format
+        .to_stt_rate(ResamplerKind::from_config(&shared.config.resampler), audio);
     let duration_secs = audio.len() as f64 / 16000.0;
+
+    // Skip inference entirely when the buffer is (nearly) all silence —
+    // whisper would spend seconds on it and likely hallucinate.
+    let coverage = vad::speech_coverage(&audio, shared.config.vad_threshold, VAD_FRAME_SAMPLES);
+    if coverage < shared.config.min_speech_coverage {
+        tracing::info!(
+            samples = audio.len(),
+            duration_secs = format!("{:.2}", duration_secs),
+            coverage = format!("{:.3}", coverage),
+            min_coverage = shared.config.min_speech_coverage,
+            "No speech detected in recording, skipping STT"
+        );
+        let _ = shared
+            .app_handle
+            .emit_throttled("voice-event", VoiceEvent::NoSpeechDetected { coverage });
+        timing::finish(shared, timing::TurnOutcome::NoSpeech);
+        return;
+    }
+
     tracing::info!(
         samples = audio.len(),
         duration_secs = format!("{:.2}", duration_secs),
+        coverage = format!("{:.3}", coverage),
         "Running STT"
     );
 
     // Take the STT engine out so we don't hold the mutex during transcription
-    let engine = {
-        match shared.stt_engine.lock() {
-            Ok(mut guard) => guard.take(),
-            Err(e) => {
-                tracing::error!("Failed to lock stt_engine: {}", e);
-                let _ = shared.app_handle.emit(
-                    "voice-event",
-                    VoiceEvent::Error {
-                        message: format!("STT engine lock poisoned: {}", e),
-                    },
-                );
-                return;
-            }
-        }
-    };
-
-    let Some(engine) = engine else {
-        let _ = shared.app_handle.emit(
+    let Some(engine) = recovery::take_stt_engine(shared).await else {
+        let _ = shared.app_handle.emit_throttled(
             "voice-event",
-            VoiceEvent::Error {
-                message: "No STT engine available".into(),
-            },
+            VoiceEvent::turn_error(shared, AppError::Stt("No STT engine available".into())),
         );
+        timing::finish(shared, timing::TurnOutcome::SttFailed);
         return;
     };
 
-    // Run transcription (this is CPU-bound, use spawn_blocking)
+    // Keep the recording for the turn archive
+    let archive_audio = (shared.config.turn_archive_size > 0).then(|| audio.clone());
+    let encoder = shared
+        .speaker_encoder
+        .lock()
+        .ok()
+        .and_then(|slot| slot.clone());
+    let threshold = shared.config.speaker_threshold;
+
+    // Run transcription (this is CPU-bound, use spawn_blocking). The speaker
+    // is identified on the same thread, after STT.
+    timing::mark(shared, timing::Stage::SttStart);
     let transcription = tokio::task::spawn_blocking(move || {
         let result = engine.transcribe(&audio);
-        (engine, result)
+        let speaker = encoder.and_then(|encoder| {
+            speaker::identify(&encoder, &audio, &platform::get_data_dir(), threshold)
+        });
+        (engine, result, speaker)
     })
     .await;
+    timing::mark(shared, timing::Stage::SttDone);
 
     match transcription {
-        Ok((engine, Ok(text))) => {
+        Ok((engine, Ok(text), speaker)) => {
             let text = text.trim().to_string();
+            let language = engine.detected_language();
+            if !text.is_empty() {
+                engine.remember_transcript(&text);
+                let length = Duration::from_secs_f64(duration_secs);
+                tokio::task::spawn_blocking(move || voice_stats::record_utterance(length));
+            }
+
+            recovery::restore_stt_engine(shared, engine);
 
-            // Put engine back
-            match shared.stt_engine.lock() {
-                Ok(mut guard) => {
-                    *guard = Some(engine);
+            let text = match shared.transcript_rules.as_ref() {
+                Some(rules) if !text.is_empty() => rules.apply(&text),
+                _ => text,
+            };
+            if text.is_empty() {
+                timing::finish(shared, timing::TurnOutcome::NoSpeech);
+            } else {
+                let id = turn_archive::next_id();
+                tracing::info!(id, text = %text, language = ?language, "Transcription result");
+                if let Some(audio) = archive_audio {
+                    let turn = ArchivedTurn {
+                        id,
+                        duration_ms: (duration_secs * 1000.0) as u64,
+                        text: text.clone(),
+                        format: ClipFormat::from_config(&shared.config.turn_archive_format),
+                        language: language.clone(),
+                        corrected: None,
+                    };
+                    let keep = shared.config.turn_archive_size;
+                    tokio::task::spawn_blocking(move || {
+                        turn_archive::store(&platform::get_data_dir(), keep, turn, &audio)
+                    });
                 }
-                Err(e) => {
-                    tracing::error!("Failed to lock stt_engine to restore: {}", e);
-                    // Engine is lost, but we can still emit the transcription
+                if let Some(similarity) = unknown_speaker(shared, speaker.as_ref()) {
+                    tracing::info!(
+                        similarity = format!("{:.2}", similarity),
+                        "Ignoring turn from an unknown speaker"
+                    );
+                    let _ = shared
+                        .app_handle
+                        .emit_throttled("voice-event", VoiceEvent::UnknownSpeaker { similarity });
+                    timing::finish(shared, timing::TurnOutcome::UnknownSpeaker);
+                    return;
                 }
-            }
-
-            if !text.is_empty() {
-                tracing::info!(text = %text, "Transcription result");
-                let _ = shared.app_handle.emit(
+                // An open spoken confirmation takes the turn, answer or not
+                if let Some(heard) = confirm::hear(&shared.app_handle, &text) {
+                    timing::finish(shared, timing::TurnOutcome::LocalCommand);
+                    if heard == confirm::Heard::Reprompt {
+                        let shared = Arc::clone(shared);
+                        tauri::async_runtime::spawn(async move {
+                            let reprompt = confirm::REPROMPT;
+                            let spoken = playback::speak_queued(
+                                &shared,
+                                reprompt,
+                                None,
+                                SpeechPriority::Interrupt,
+                            );
+                            if let Err(e) = spoken.await {
+                                tracing::warn!("Failed to repeat the confirmation prompt: {}", e);
+                            }
+                        });
+                    }
+                    return;
+                }
+                // So does an open question from the AI
+                if ask::hear(&shared.app_handle, &text) {
+                    timing::finish(shared, timing::TurnOutcome::Answered);
+                    return;
+                }
+                if let Some(intent) = shared.intents.as_ref().and_then(|g| g.recognize(&text)) {
+                    timing::finish(shared, timing::TurnOutcome::LocalCommand);
+                    let app = shared.app_handle.clone();
+                    tokio::task::spawn_blocking(move || intents::run(&app, intent, text));
+                    return;
+                }
+                if shared.config.transcript_log {
+                    let (text, language) = (text.clone(), language.clone());
+                    tokio::task::spawn_blocking(move || {
+                        transcript_log::append(Speaker::User, &text, language.as_deref())
+                    });
+                }
+                let (speaker, similarity) = match speaker {
+                    Some(m) => (m.speaker, Some(m.similarity)),
+                    None => (None, None),
+                };
+                let route = shared.transcript_router.as_ref().and_then(|r| r.route(&text));
+                let (text, routes) = match route {
+                    Some(route) => {
+                        tracing::info!(id, targets = ?route.targets, "Transcription routed");
+                        if route.targets.contains(&RouteTarget::Notes) {
+                            let note = route.text.clone();
+                            tauri::async_runtime::spawn(async move { routing::save_note(&note).await });
+                        }
+                        (route.text, Some(route.targets))
+                    }
+                    None => (text, None),
+                };
+                // Only a turn the provider answers belongs to the conversation
+                let to_provider = routes
+                    .as_ref()
+                    .is_none_or(|targets| targets.contains(&RouteTarget::Provider));
+                let turn = if to_provider {
+                    shared.session.begin_turn(&shared.app_handle, id)
+                } else {
+                    timing::finish(shared, timing::TurnOutcome::Routed);
+                    None
+                };
+                let _ = shared.app_handle.emit_throttled(
                     "voice-event",
-                    VoiceEvent::Transcription { text },
+                    VoiceEvent::Transcription {
+                        id,
+                        text,
+                        language,
+                        source: TranscriptSource::User,
+                        speaker,
+                        similarity,
+                        session_id: turn.map(|t| t.session_id),
+                        turn: turn.map(|t| t.turn),
+                        routes,
+                    },
                 );
             }
         }
-        Ok((engine, Err(e))) => {
+        Ok((engine, Err(e), _)) => {
             tracing::error!("STT transcription failed: {}", e);
-            // Put engine back
-            match shared.stt_engine.lock() {
-                Ok(mut guard) => {
-                    *guard = Some(engine);
-                }
-                Err(e2) => {
-                    tracing::error!("Failed to lock stt_engine to restore: {}", e2);
-                }
-            }
-            let _ = shared.app_handle.emit(
+            recovery::restore_stt_engine(shared, engine);
+            let _ = shared.app_handle.emit_throttled(
                 "voice-event",
-                VoiceEvent::Error {
-                    message: format!("STT failed: {}", e),
-                },
+                VoiceEvent::turn_error(shared, AppError::from(e).context("STT failed")),
             );
+            timing::finish(shared, timing::TurnOutcome::SttFailed);
         }
         Err(e) => {
+            // The engine went down with the task; the next turn rebuilds it
             tracing::error!("STT task panicked: {}", e);
-            let _ = shared.app_handle.emit(
+            let _ = shared.app_handle.emit_throttled(
                 "voice-event",
-                VoiceEvent::Error {
-                    message: format!("STT task failed: {}", e),
-                },
+                VoiceEvent::turn_error(shared, AppError::Stt(format!("STT task failed: {}", e))),
             );
+            timing::finish(shared, timing::TurnOutcome::SttFailed);
         }
     }
 }
 
+/// The similarity of a turn to drop because its voice matched no enrolled
+/// speaker. Only wake-word turns are dropped: a PTT or toggle recording was
+/// started on purpose.
+fn unknown_speaker(
+    shared: &PipelineShared,
+    speaker: Option<&speaker::SpeakerMatch>,
+) -> Option<f32> {
+    let m = speaker.filter(|m| m.speaker.is_none())?;
+    let mode = shared.mode.lock().map(|m| *m).unwrap_or_default();
+    (shared.config.ignore_unknown_speakers && mode == VoiceMode::WakeWord).then_some(m.similarity)
+}
+
 // ── Audio Device Listing ────────────────────────────────────────────
 
-/// List available audio input devices.
+/// List available audio input devices. Network sources (`rtp://...`) are
+/// not enumerated; the settings UI offers them separately.
 pub fn list_input_devices() -> Vec<AudioDeviceInfo> {
     let host = cpal::default_host();
     let mut devices = Vec::new();
@@ -1115,7 +2200,9 @@ pub fn list_input_devices() -> Vec<AudioDeviceInfo> {
     devices
 }
 
-/// List available audio output devices.
+/// List available audio output devices. Network targets (`http://...`,
+/// `snapcast://...`) are not enumerated; the settings UI offers them
+/// separately.
 pub fn list_output_devices() -> Vec<AudioDeviceInfo> {
     let host = cpal::default_host();
     let mut devices = Vec::new();
@@ -1154,6 +2241,47 @@ mod tests {
         assert_eq!(output.len(), 16);
     }
 
+    #[test]
+    fn test_max_recording_samples() {
+        let f = AudioFormat::default();
+        assert_eq!(max_recording_samples(120.0, &f), Some(1_920_000));
+        assert_eq!(max_recording_samples(0.5, &f), Some(8_000));
+        assert_eq!(max_recording_samples(0.0, &f), None);
+
+        let config = VoiceEngineConfig {
+            sample_rate: 8_000,
+            ..Default::default()
+        };
+        let tel = AudioFormat::from_config(&config);
+        assert_eq!(max_recording_samples(120.0, &tel), Some(960_000));
+    }
+
+    #[test]
+    fn test_recording_limit() {
+        let f = AudioFormat::default();
+        assert_eq!(
+            recording_limit(VoiceMode::PushToTalk, 120.0, 60.0, &f),
+            Some((1_920_000, "max_duration"))
+        );
+        assert_eq!(
+            recording_limit(VoiceMode::Toggle, 120.0, 60.0, &f),
+            Some((960_000, "toggle_timeout"))
+        );
+        assert_eq!(
+            recording_limit(VoiceMode::Toggle, 30.0, 60.0, &f),
+            Some((480_000, "max_duration"))
+        );
+        assert_eq!(
+            recording_limit(VoiceMode::Toggle, 0.0, 60.0, &f),
+            Some((960_000, "toggle_timeout"))
+        );
+        assert_eq!(
+            recording_limit(VoiceMode::Toggle, 120.0, 0.0, &f),
+            Some((1_920_000, "max_duration"))
+        );
+        assert_eq!(recording_limit(VoiceMode::WakeWord, 0.0, 60.0, &f), None);
+    }
+
     #[test]
     fn test_state_roundtrip() {
         for state in [
@@ -0,0 +1,492 @@
+//! Network audio input: an RTP stream over UDP in place of a microphone.
+//!
+//! Selected with an input device of the form
+//! `rtp://[<bind-host>]:<port>[?from=<ip>,...&rate=<hz>&channels=<n>]`, e.g.
+//! `rtp://0.0.0.0:5004?from=192.168.1.20`. Any RTP sender works: a phone
+//! mic-streaming app,
+//! `ffmpeg -f rtp`, or a SIP softphone. Supported payloads are the static
+//! G.711 types (PCMU = 0, PCMA = 8) and L16 (10 = 44.1 kHz stereo, 11 =
+//! 44.1 kHz mono), plus any dynamic type (96-127) carrying L16 at the
+//! `rate` / `channels` given in the URL (default 16 kHz mono).
+//!
+//! Whatever arrives is spoken to the agent, so the source is locked down like
+//! the HTTP TTS stream: with no bind host it listens on 127.0.0.1 only, and
+//! binding any other address needs a `from` allow-list of sender IPs. Packets
+//! from anyone else are dropped.
+//!
+//! The first allowed sender to reach the port owns the stream until it has
+//! been silent for [`SENDER_IDLE`]; late and duplicate packets are dropped and
+//! short gaps are filled with silence so VAD timing stays honest.
+
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+
+use super::ring_buffer::RingProducer;
+use super::format::AudioFormat;
+use super::TARGET_SAMPLE_RATE;
+use crate::error::AppError;
+use crate::voice::resample::{ResamplerKind, StreamResampler};
+
+/// Input device prefix that selects a network source.
+pub(crate) const RTP_SCHEME: &str = "rtp://";
+
+/// How long `recv` blocks before re-checking the stop flag.
+const RECV_TIMEOUT: Duration = Duration::from_millis(200);
+/// After this long without packets, another sender may take over.
+const SENDER_IDLE: Duration = Duration::from_secs(2);
+/// Sequence jumps further back than this are a restarted sender, not a
+/// late packet.
+const MAX_MISORDER: i16 = 100;
+/// Most silence inserted for one gap of lost packets.
+const MAX_CONCEAL_SECS: f32 = 0.2;
+/// Larger than any RTP packet over UDP.
+const MAX_PACKET: usize = 65_536;
+
+/// A parsed `rtp://` input device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NetworkSource {
+    pub addr: SocketAddr,
+    /// Sender IPs accepted (`?from=`); empty only for a loopback bind,
+    /// where any local sender is accepted.
+    pub allowed: Vec<IpAddr>,
+    /// Sample rate of dynamic-payload L16.
+    pub rate: u32,
+    /// Channel count of dynamic-payload L16.
+    pub channels: u16,
+}
+
+impl NetworkSource {
+    /// Parse an input device name. `None` if it isn't an `rtp://` source;
+    /// `Some(Err)` if it is but is malformed.
+    pub(crate) fn parse(device: &str) -> Option<Result<Self, AppError>> {
+        let rest = device.strip_prefix(RTP_SCHEME)?;
+        Some(Self::parse_rest(rest).map_err(|e| {
+            AppError::AudioDevice(format!("Invalid network input '{}': {}", device, e))
+        }))
+    }
+
+    fn parse_rest(rest: &str) -> Result<Self, String> {
+        let (host_port, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let host_port = match host_port.strip_prefix(':') {
+            Some(port) => format!("127.0.0.1:{}", port),
+            None => host_port.to_string(),
+        };
+        let addr = host_port
+            .parse::<SocketAddr>()
+            .map_err(|_| "expected <ip>:<port>".to_string())?;
+        let mut source = Self { addr, allowed: Vec::new(), rate: TARGET_SAMPLE_RATE, channels: 1 };
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "from" => {
+                    for ip in value.split(',').filter(|ip| !ip.is_empty()) {
+                        let ip: IpAddr = ip.parse().map_err(|_| format!("'{}' is not an IP address", ip))?;
+                        source.allowed.push(ip.to_canonical());
+                    }
+                }
+                "rate" => source.rate = value.parse().map_err(|_| "rate must be a number")?,
+                "channels" => source.channels = value.parse().map_err(|_| "channels must be a number")?,
+                other => return Err(format!("unknown option '{}'", other)),
+            }
+        }
+        if !(8_000..=192_000).contains(&source.rate) || !(1..=8).contains(&source.channels) {
+            return Err("rate must be 8000-192000 and channels 1-8".into());
+        }
+        if !source.addr.ip().is_loopback() && source.allowed.is_empty() {
+            return Err("add ?from=<sender ip> to accept a stream from the network".into());
+        }
+        Ok(source)
+    }
+
+    /// Whether packets from `peer` may feed the stream.
+    fn accepts(&self, peer: SocketAddr) -> bool {
+        let ip = peer.ip().to_canonical();
+        if self.allowed.is_empty() {
+            return ip.is_loopback();
+        }
+        self.allowed.contains(&ip)
+    }
+}
+
+/// A running network capture. Dropping it stops the receive thread.
+pub(crate) struct NetworkCapture {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for NetworkCapture {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Bind the source's UDP port and feed decoded mono chunks at the
+/// pipeline's rate into `producer`, the same way the cpal capture callback
+/// does.
+pub(crate) fn start(
+    source: NetworkSource,
+    producer: RingProducer,
+    resampler_kind: ResamplerKind,
+    pipeline: AudioFormat,
+) -> Result<NetworkCapture, AppError> {
+    let socket = UdpSocket::bind(source.addr).map_err(|e| {
+        AppError::AudioDevice(format!("Failed to bind network input {}: {}", source.addr, e))
+    })?;
+    socket
+        .set_read_timeout(Some(RECV_TIMEOUT))
+        .map_err(|e| AppError::AudioDevice(format!("Failed to configure network input: {}", e)))?;
+    tracing::info!(addr = %source.addr, "Network audio input listening (RTP)");
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("voice-network-input".into())
+        .spawn(move || {
+            receive_loop(socket, source, producer, resampler_kind, pipeline, thread_stop)
+        })
+        .map_err(|e| AppError::Internal(format!("Failed to spawn network input thread: {}", e)))?;
+
+    Ok(NetworkCapture { stop, thread: Some(thread) })
+}
+
+fn receive_loop(
+    socket: UdpSocket,
+    source: NetworkSource,
+    producer: RingProducer,
+    resampler_kind: ResamplerKind,
+    pipeline: AudioFormat,
+    stop: Arc<AtomicBool>,
+) {
+    let chunk = pipeline.chunk_samples;
+    let mut buf = vec![0u8; MAX_PACKET];
+    let mut chunk_buf: Vec<f32> = Vec::with_capacity(chunk * 2);
+    let mut sender: Option<(SocketAddr, Instant)> = None;
+    let mut sequencer = Sequencer::default();
+    let mut format: Option<StreamFormat> = None;
+    let mut resampler = StreamResampler::new(resampler_kind, pipeline.sample_rate, pipeline.sample_rate);
+    let mut last_packet_samples = 0usize;
+    let mut last_rejected: Option<IpAddr> = None;
+
+    while !stop.load(Ordering::Relaxed) {
+        let (len, from) = match socket.recv_from(&mut buf) {
+            Ok(r) => r,
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                if sender.is_some_and(|(_, at)| at.elapsed() > SENDER_IDLE) {
+                    tracing::info!("Network audio stream idle");
+                    sender = None;
+                }
+                continue;
+            }
+            Err(e) => {
+                tracing::warn!("Network audio receive failed: {}", e);
+                std::thread::sleep(RECV_TIMEOUT);
+                continue;
+            }
+        };
+
+        if !source.accepts(from) {
+            if last_rejected != Some(from.ip()) {
+                tracing::warn!(from = %from, "Dropping network audio from a sender not in ?from=");
+                last_rejected = Some(from.ip());
+            }
+            continue;
+        }
+        match sender {
+            Some((addr, _)) if addr != from => continue,
+            None => {
+                tracing::info!(from = %from, "Network audio stream connected");
+                sequencer = Sequencer::default();
+            }
+            _ => {}
+        }
+        sender = Some((from, Instant::now()));
+
+        let Some(packet) = parse_rtp(&buf[..len]) else {
+            continue;
+        };
+        let Some(packet_format) = StreamFormat::for_payload(packet.payload_type, &source) else {
+            tracing::debug!(payload_type = packet.payload_type, "Unsupported RTP payload type");
+            continue;
+        };
+        let Some(lost) = sequencer.accept(packet.ssrc, packet.sequence) else {
+            continue;
+        };
+        if format != Some(packet_format) {
+            tracing::info!(
+                payload_type = packet.payload_type,
+                rate = packet_format.rate,
+                channels = packet_format.channels,
+                "Network audio format"
+            );
+            resampler = StreamResampler::new(resampler_kind, packet_format.rate, pipeline.sample_rate);
+            format = Some(packet_format);
+            last_packet_samples = 0;
+        }
+
+        let samples = packet_format.decode(packet.payload);
+        if lost > 0 && last_packet_samples > 0 {
+            let cap = (packet_format.rate as f32 * MAX_CONCEAL_SECS) as usize;
+            let gap = (lost as usize * last_packet_samples).min(cap);
+            chunk_buf.extend(resampler.process(vec![0.0; gap]));
+        }
+        last_packet_samples = samples.len();
+        chunk_buf.extend(resampler.process(samples));
+
+        while chunk_buf.len() >= chunk {
+            producer.push_slice(&chunk_buf[..chunk]);
+            chunk_buf.drain(..chunk);
+        }
+    }
+    tracing::info!("Network audio input stopped");
+}
+
+/// The parts of an RTP packet (RFC 3550) the decoder needs.
+#[derive(Debug, PartialEq, Eq)]
+struct RtpPacket<'a> {
+    payload_type: u8,
+    sequence: u16,
+    ssrc: u32,
+    payload: &'a [u8],
+}
+
+fn parse_rtp(buf: &[u8]) -> Option<RtpPacket<'_>> {
+    if buf.len() < 12 || buf[0] >> 6 != 2 {
+        return None;
+    }
+    let has_padding = buf[0] & 0x20 != 0;
+    let has_extension = buf[0] & 0x10 != 0;
+    let csrc_count = (buf[0] & 0x0f) as usize;
+    let mut start = 12 + 4 * csrc_count;
+    if has_extension {
+        let words = u16::from_be_bytes([*buf.get(start + 2)?, *buf.get(start + 3)?]) as usize;
+        start += 4 + 4 * words;
+    }
+    let mut end = buf.len();
+    if has_padding {
+        end = end.checked_sub(*buf.last()? as usize)?;
+    }
+    Some(RtpPacket {
+        payload_type: buf[1] & 0x7f,
+        sequence: u16::from_be_bytes([buf[2], buf[3]]),
+        ssrc: u32::from_be_bytes([buf[8], buf[9], buf[10], buf[11]]),
+        payload: buf.get(start..end)?,
+    })
+}
+
+/// Tracks RTP sequence numbers: drops late/duplicate packets and counts
+/// lost ones.
+#[derive(Debug, Default)]
+struct Sequencer {
+    ssrc: Option<u32>,
+    last: Option<u16>,
+}
+
+impl Sequencer {
+    /// Packets lost before this one, or `None` to drop it.
+    fn accept(&mut self, ssrc: u32, sequence: u16) -> Option<u16> {
+        let last = match self.last {
+            Some(last) if self.ssrc == Some(ssrc) => last,
+            _ => {
+                self.ssrc = Some(ssrc);
+                self.last = Some(sequence);
+                return Some(0);
+            }
+        };
+        let delta = sequence.wrapping_sub(last) as i16;
+        if delta < -MAX_MISORDER {
+            // Sender restarted its sequence
+            self.last = Some(sequence);
+            return Some(0);
+        }
+        if delta <= 0 {
+            return None;
+        }
+        self.last = Some(sequence);
+        Some(delta as u16 - 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    /// 16-bit big-endian PCM.
+    L16,
+    /// G.711 mu-law.
+    Pcmu,
+    /// G.711 A-law.
+    Pcma,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StreamFormat {
+    encoding: Encoding,
+    rate: u32,
+    channels: u16,
+}
+
+impl StreamFormat {
+    fn for_payload(payload_type: u8, source: &NetworkSource) -> Option<Self> {
+        let (encoding, rate, channels) = match payload_type {
+            0 => (Encoding::Pcmu, 8_000, 1),
+            8 => (Encoding::Pcma, 8_000, 1),
+            10 => (Encoding::L16, 44_100, 2),
+            11 => (Encoding::L16, 44_100, 1),
+            96..=127 => (Encoding::L16, source.rate, source.channels),
+            _ => return None,
+        };
+        Some(Self { encoding, rate, channels })
+    }
+
+    /// Decode a payload to mono f32 samples at `self.rate`.
+    fn decode(&self, payload: &[u8]) -> Vec<f32> {
+        let samples: Vec<f32> = match self.encoding {
+            Encoding::L16 => payload
+                .chunks_exact(2)
+                .map(|b| i16::from_be_bytes([b[0], b[1]]) as f32 / 32768.0)
+                .collect(),
+            Encoding::Pcmu => payload.iter().map(|&b| mulaw_to_linear(b) as f32 / 32768.0).collect(),
+            Encoding::Pcma => payload.iter().map(|&b| alaw_to_linear(b) as f32 / 32768.0).collect(),
+        };
+        let ch = self.channels.max(1) as usize;
+        if ch == 1 {
+            return samples;
+        }
+        samples
+            .chunks_exact(ch)
+            .map(|frame| frame.iter().sum::<f32>() / ch as f32)
+            .collect()
+    }
+}
+
+/// G.711 mu-law to 16-bit linear.
+fn mulaw_to_linear(byte: u8) -> i16 {
+    let u = !byte;
+    let exponent = (u >> 4) & 0x07;
+    let mantissa = (u & 0x0f) as i32;
+    let magnitude = (((mantissa << 3) + 0x84) << exponent) - 0x84;
+    (if u & 0x80 != 0 { -magnitude } else { magnitude }) as i16
+}
+
+/// G.711 A-law to 16-bit linear.
+fn alaw_to_linear(byte: u8) -> i16 {
+    let a = byte ^ 0x55;
+    let exponent = (a >> 4) & 0x07;
+    let mantissa = (a & 0x0f) as i32;
+    let magnitude = match exponent {
+        0 => (mantissa << 4) + 8,
+        e => ((mantissa << 4) + 0x108) << (e - 1),
+    };
+    (if a & 0x80 != 0 { magnitude } else { -magnitude }) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> NetworkSource {
+        NetworkSource::parse("rtp://:5004").unwrap().unwrap()
+    }
+
+    fn packet(pt: u8, seq: u16, payload: &[u8]) -> Vec<u8> {
+        let mut buf = vec![0x80, pt];
+        buf.extend_from_slice(&seq.to_be_bytes());
+        buf.extend_from_slice(&[0, 0, 0, 0]); // timestamp
+        buf.extend_from_slice(&0x1234_5678u32.to_be_bytes());
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    #[test]
+    fn test_parse_source() {
+        assert!(NetworkSource::parse("Microphone (USB)").is_none());
+        let s = source();
+        assert_eq!(s.addr, "127.0.0.1:5004".parse().unwrap(), "shorthand stays local");
+        assert_eq!((s.rate, s.channels), (16_000, 1));
+        let s = NetworkSource::parse("rtp://127.0.0.1:6000?rate=48000&channels=2").unwrap().unwrap();
+        assert_eq!((s.rate, s.channels), (48_000, 2));
+        assert!(NetworkSource::parse("rtp://nowhere").unwrap().is_err());
+        assert!(NetworkSource::parse("rtp://:5004?codec=opus").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_network_bind_needs_allowed_senders() {
+        assert!(NetworkSource::parse("rtp://0.0.0.0:5004").unwrap().is_err());
+        assert!(NetworkSource::parse("rtp://0.0.0.0:5004?from=phone").unwrap().is_err());
+        let s = NetworkSource::parse("rtp://0.0.0.0:5004?from=192.168.1.20,::ffff:10.0.0.5")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            s.allowed,
+            vec!["192.168.1.20".parse::<IpAddr>().unwrap(), "10.0.0.5".parse().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_packets_from_other_peers_are_rejected() {
+        let s = NetworkSource::parse("rtp://0.0.0.0:5004?from=192.168.1.20").unwrap().unwrap();
+        assert!(s.accepts("192.168.1.20:40000".parse().unwrap()));
+        assert!(s.accepts("[::ffff:192.168.1.20]:40000".parse().unwrap()));
+        assert!(!s.accepts("192.168.1.21:40000".parse().unwrap()));
+        assert!(!s.accepts("127.0.0.1:40000".parse().unwrap()));
+
+        let local = source();
+        assert!(local.accepts("127.0.0.1:40000".parse().unwrap()));
+        assert!(!local.accepts("192.168.1.20:40000".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_rtp_header() {
+        let buf = packet(11, 7, &[1, 2, 3, 4]);
+        let p = parse_rtp(&buf).unwrap();
+        assert_eq!((p.payload_type, p.sequence, p.ssrc), (11, 7, 0x1234_5678));
+        assert_eq!(p.payload, &[1, 2, 3, 4]);
+
+        // One CSRC, a one-word extension, and two bytes of padding
+        let mut buf = packet(0, 1, &[]);
+        buf[0] = 0x80 | 0x20 | 0x10 | 1;
+        buf.extend_from_slice(&[0; 4]); // CSRC
+        buf.extend_from_slice(&[0xbe, 0xde, 0, 1, 9, 9, 9, 9]); // extension
+        buf.extend_from_slice(&[0xaa, 0xbb, 0, 2]); // payload + padding
+        assert_eq!(parse_rtp(&buf).unwrap().payload, &[0xaa, 0xbb]);
+
+        assert!(parse_rtp(&[0x40; 20]).is_none(), "version 1");
+        assert!(parse_rtp(&[0x80; 8]).is_none(), "too short");
+    }
+
+    #[test]
+    fn test_sequencer_drops_late_and_counts_lost() {
+        let mut seq = Sequencer::default();
+        assert_eq!(seq.accept(1, 65_534), Some(0));
+        assert_eq!(seq.accept(1, 65_535), Some(0));
+        assert_eq!(seq.accept(1, 2), Some(2), "wraps, 0 and 1 lost");
+        assert_eq!(seq.accept(1, 1), None, "late");
+        assert_eq!(seq.accept(1, 2), None, "duplicate");
+        assert_eq!(seq.accept(2, 500), Some(0), "new SSRC resets");
+        assert_eq!(seq.accept(2, 100), Some(0), "restarted sequence");
+    }
+
+    #[test]
+    fn test_decode_payloads() {
+        let s = source();
+        let l16 = StreamFormat::for_payload(96, &s).unwrap();
+        assert_eq!(l16.decode(&[0x40, 0x00, 0xc0, 0x00]), vec![0.5, -0.5]);
+
+        let stereo = StreamFormat::for_payload(10, &s).unwrap();
+        assert_eq!(stereo.rate, 44_100);
+        assert_eq!(stereo.decode(&[0x40, 0x00, 0x00, 0x00]), vec![0.25]);
+
+        assert_eq!(mulaw_to_linear(0xff), 0);
+        assert_eq!(mulaw_to_linear(0x00), -32_124);
+        assert_eq!(mulaw_to_linear(0x80), 32_124);
+        assert_eq!(alaw_to_linear(0xd5), 8);
+        assert_eq!(alaw_to_linear(0x55), -8);
+        assert_eq!(alaw_to_linear(0xaa), 32_256);
+        assert!(StreamFormat::for_payload(9, &s).is_none(), "G.722 unsupported");
+    }
+}
@@ -1,18 +1,38 @@
 //! Rodio audio playback functions for TTS output.
 //!
 //! Provides both streaming (chunk-by-chunk) and one-shot playback
-//! strategies via rodio Sink.
+//! strategies via rodio Sink. Audio can be mirrored to several output
+//! devices at once (e.g. headset + speakers), one Sink per device, and any
+//! of them can be a network target instead (see `remote_output`).
+//!
+//! rodio converts engine audio to each device's rate with linear
+//! interpolation. With `voice.resampler = "sinc"` the samples are converted
+//! to the device rate first (see `voice::resample`), so rodio passes them
+//! through untouched.
+//!
+//! Voice effects (`voice.ttsEffects`, see `tts::effects`) run once per
+//! buffer, before the audio fans out to the devices.
 
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use cpal::traits::{DeviceTrait, HostTrait};
+use futures_util::StreamExt;
 use rodio::{OutputStream, Sink};
-use tauri::Emitter;
 
-use super::{PipelineShared, VoiceEvent};
-use crate::voice::tts::{self, TtsEngine};
+use super::recovery::{self, TtsCheckout};
+use super::remote_output::{self, RemoteTarget};
+use super::speech_queue::{SpeechPriority, Submitted};
+use super::timing::{self, Stage};
+use super::word_progress::{WordMark, WordProgress, WordScheduler};
+use super::{loading, PipelineShared, VoiceEvent};
+use crate::error::AppError;
+use crate::services::event_throttle::EmitThrottled;
+use crate::services::transcript_log::{self, Speaker};
+use crate::voice::resample::{self, ResamplerKind};
+use crate::voice::tts::effects::EffectChain;
+use crate::voice::tts::{self, TtsEngine, TtsError, WordBoundary};
 use crate::voice::VoiceState;
 
 use super::{state_to_u8, VoiceMode};
@@ -24,7 +44,13 @@ use super::{state_to_u8, VoiceMode};
 /// (even cloud TTS); 60s means the engine (or its network call) has wedged.
 /// On timeout we abort THIS phrase and continue, so the pipeline never hangs
 /// indefinitely in Speaking.
-const SYNTH_TIMEOUT: Duration = Duration::from_secs(60);
+pub(super) const SYNTH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Most phrases synthesized concurrently, whatever `voice.ttsLookahead` says.
+const MAX_LOOKAHEAD: u32 = 4;
+
+/// How often a pending synthesis checks the cancel flag.
+const CANCEL_POLL: Duration = Duration::from_millis(50);
 
 /// Compute a generous playback cap from the known audio length:
 /// `max(30s, expected * 3 + 10s)`. Used to bound the rodio drain loops so a
@@ -35,36 +61,172 @@ fn playback_cap(samples_len: usize, sample_rate: u32) -> Duration {
     Duration::from_secs_f64(cap_secs)
 }
 
+/// A synthesized phrase queued for streaming playback, with the pause that
+/// should follow it if another phrase comes after and its word timings.
+/// `index` is its position in the phrase list.
+struct PhraseAudio {
+    index: usize,
+    samples: Vec<f32>,
+    pause_after_ms: u32,
+    words: Vec<WordMark>,
+}
+
+/// Pause to insert after a phrase: the sentence pause after sentence-final
+/// punctuation (never shorter than the phrase gap), otherwise the phrase gap.
+pub(super) fn pause_after_phrase(phrase: &str, phrase_gap_ms: u32, sentence_pause_ms: u32) -> u32 {
+    if tts::ends_with_sentence_punctuation(phrase) {
+        sentence_pause_ms.max(phrase_gap_ms)
+    } else {
+        phrase_gap_ms
+    }
+}
+
+/// Number of mono samples covering `ms` milliseconds at `sample_rate`.
+pub(super) fn silence_samples(ms: u32, sample_rate: u32) -> usize {
+    (ms as u64 * sample_rate as u64 / 1000) as usize
+}
+
+/// Synthesize `phrase` and take its word timings.
+///
+/// Engines keep the timings of their last synthesis until they're taken.
+/// Lookahead phrases share one engine, but they are all polled by the same
+/// task, and the take runs in the same poll that finishes synthesis, so no
+/// other phrase can replace the timings in between.
+async fn synthesize_with_words(
+    engine: &dyn TtsEngine,
+    phrase: &str,
+) -> Result<(Vec<f32>, Vec<WordBoundary>), TtsError> {
+    let samples = engine.synthesize(phrase).await?;
+    Ok((samples, engine.take_word_boundaries()))
+}
+
+/// Resolves once `flag` is set.
+async fn wait_for_cancel(flag: &AtomicBool) {
+    while !flag.load(Ordering::SeqCst) {
+        tokio::time::sleep(CANCEL_POLL).await;
+    }
+}
+
 /// Transition to Speaking state and emit events.
 pub(crate) fn set_speaking_state(shared: &Arc<PipelineShared>, text: &str) {
     shared
         .state
         .store(state_to_u8(VoiceState::Speaking), Ordering::Release);
-    let _ = shared.app_handle.emit(
+    let _ = shared.app_handle.emit_throttled(
         "voice-event",
         VoiceEvent::StateChange {
             state: "speaking".into(),
         },
     );
-    let _ = shared.app_handle.emit(
+    let _ = shared.app_handle.emit_throttled(
         "voice-event",
         VoiceEvent::SpeakingStart {
             text: text.to_string(),
         },
     );
+    if let Ok(mut last) = shared.last_spoken.lock() {
+        *last = Some(text.to_string());
+    }
+    shared.session.speaking();
+    if shared.config.transcript_log {
+        let text = text.to_string();
+        tokio::task::spawn_blocking(move || transcript_log::append(Speaker::Assistant, &text, None));
+    }
 }
 
-/// Take the TTS engine from shared state. Returns None if unavailable.
-pub(crate) fn take_tts_engine(shared: &Arc<PipelineShared>) -> Option<Box<dyn TtsEngine>> {
-    match shared.tts_engine.lock() {
-        Ok(mut guard) => guard.take(),
+/// Take the TTS engine from shared state, checked out until the returned
+/// guard drops. Waits for the first load if it hasn't finished. After that,
+/// an empty slot that no other `speak()` has checked out means the engine
+/// was lost, and it is rebuilt from config. Returns None if the engine is in
+/// use or can't be loaded.
+pub(crate) async fn take_tts_engine(
+    shared: &Arc<PipelineShared>,
+) -> Option<(Box<dyn TtsEngine>, TtsCheckout<'_>)> {
+    if !loading::ensure_tts(shared).await {
+        return None;
+    }
+    let (engine, poisoned, in_use, checkout) = {
+        let (mut guard, poisoned) = recovery::lock_slot(&shared.tts_engine);
+        let in_use = shared.tts_checkouts.load(Ordering::Acquire) > 0;
+        let checkout = TtsCheckout::begin(&shared.tts_checkouts);
+        (guard.take(), poisoned, in_use, checkout)
+    };
+    if let Some(engine) = engine {
+        if poisoned {
+            recovery::report_recovery(shared, "tts", "poisoned");
+        }
+        return Some((engine, checkout));
+    }
+    if in_use {
+        return None;
+    }
+
+    // Still checked out while rebuilding, so a concurrent speak() waits
+    let config = shared.config.clone();
+    match tokio::task::spawn_blocking(move || recovery::tts_engine_from_config(&config)).await {
+        Ok(Ok(engine)) => {
+            recovery::report_recovery(shared, "tts", if poisoned { "poisoned" } else { "missing" });
+            Some((engine, checkout))
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("Could not re-create TTS engine: {}", e);
+            None
+        }
         Err(e) => {
-            tracing::error!("Failed to lock tts_engine: {}", e);
+            tracing::error!("TTS engine re-creation panicked: {}", e);
             None
         }
     }
 }
 
+/// Speak `text` according to `priority` (see `speech_queue`): now,
+/// after the speech in progress, or not at all. Whoever speaks then says
+/// the queued texts, unless it was cut off, stopped or barged in on.
+/// `voice` overrides the selected TTS voice for this text only.
+pub(super) async fn speak_queued(
+    shared: &Arc<PipelineShared>,
+    text: &str,
+    voice: Option<&str>,
+    priority: SpeechPriority,
+) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+    let submitted = shared
+        .speech_queue
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .submit(text, voice, priority);
+    let ticket = match submitted {
+        Submitted::Speak(ticket) => ticket,
+        Submitted::Queued => {
+            tracing::info!("Speech queued behind current speech");
+            return Ok(());
+        }
+        Submitted::Dropped => {
+            tracing::info!(?priority, "Already speaking, dropping speech request");
+            return Ok(());
+        }
+    };
+
+    let result = speak(shared, text, voice).await;
+    loop {
+        let may_continue = !shared.tts_cancel.load(Ordering::SeqCst);
+        let next = shared
+            .speech_queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .finished(ticket, may_continue);
+        let Some(next) = next else {
+            break;
+        };
+        if let Err(e) = speak(shared, &next.text, next.voice.as_deref()).await {
+            tracing::warn!("Queued speech failed: {}", e);
+        }
+    }
+    result
+}
+
 /// Speak text using streaming synthesis for low first-audio latency.
 ///
 /// Splits text into phrases, synthesizes each one individually, and streams
@@ -76,10 +238,15 @@ pub(crate) fn take_tts_engine(shared: &Arc<PipelineShared>) -> Option<Box<dyn Tt
 /// Uses a per-request cancel token so that when a new speak() call cancels
 /// the previous one, the old playback thread stays cancelled even after the
 /// new request resets the shared `tts_cancel` flag.
-pub(super) async fn speak(shared: &Arc<PipelineShared>, text: &str) -> Result<(), String> {
+async fn speak(shared: &Arc<PipelineShared>, text: &str, voice: Option<&str>) -> Result<(), String> {
     if text.trim().is_empty() {
         return Ok(());
     }
+    if crate::services::quiet_hours::active() {
+        tracing::info!("Quiet hours active, not speaking");
+        return Ok(());
+    }
+    timing::mark(shared, Stage::Response);
 
     // If already speaking, cancel current playback and wait for the TTS engine
     // to be restored before starting new synthesis (prevents overlapping audio).
@@ -122,26 +289,32 @@ pub(super) async fn speak(shared: &Arc<PipelineShared>, text: &str) -> Result<()
     if let Ok(mut guard) = shared.active_playback_cancel.lock() {
         *guard = Some(Arc::clone(&request_cancel));
     }
+    // New speech replaces whatever an interrupted reply left unsaid
+    if let Ok(mut unspoken) = shared.unspoken.lock() {
+        unspoken.clear();
+    }
 
     // Set state to Speaking + emit events
     set_speaking_state(shared, text);
 
     // Take the TTS engine
-    let engine = match take_tts_engine(shared) {
-        Some(e) => e,
+    let (mut engine, _checkout) = match take_tts_engine(shared).await {
+        Some(taken) => taken,
         None => {
             tracing::warn!("No TTS engine available, skipping speech");
-            let _ = shared.app_handle.emit(
+            let _ = shared.app_handle.emit_throttled(
                 "voice-event",
-                VoiceEvent::Error {
-                    message: "No TTS engine available".into(),
-                },
+                VoiceEvent::turn_error(shared, AppError::Tts("No TTS engine available".into())),
             );
             finish_speaking(shared);
             return Err("No TTS engine available".into());
         }
     };
 
+    if let Some(voice) = voice {
+        use_voice_once(shared, engine.as_mut(), voice);
+    }
+
     // Check cancellation before synthesis
     if shared.tts_cancel.load(Ordering::SeqCst) {
         tracing::info!("TTS cancelled before synthesis");
@@ -151,11 +324,40 @@ pub(super) async fn speak(shared: &Arc<PipelineShared>, text: &str) -> Result<()
     }
 
     let sample_rate = engine.sample_rate();
-    let volume = shared.config.tts_volume;
-    let output_device = shared.config.output_device.clone();
+    let output = PlaybackOutput {
+        targets: output_targets(
+            shared.config.output_device.as_deref(),
+            &shared.config.output_devices,
+        ),
+        volume: f32::from_bits(shared.tts_volume.load(Ordering::Relaxed)),
+        resampler: ResamplerKind::from_config(&shared.config.resampler),
+        effects: shared
+            .tts_effects
+            .lock()
+            .map(|e| e.clone())
+            .unwrap_or_default(),
+    };
+    let phrase_gap_ms = shared.config.tts_phrase_gap_ms;
+    let sentence_pause_ms = shared.config.tts_sentence_pause_ms;
 
     // Split into phrases for streaming
-    let phrases = tts::split_into_phrases(text);
+    let mut phrases = tts::split_into_phrases(text);
+
+    // Blocked-term filter: only affects what is spoken, not the UI text
+    if let Some(filter) = tts::OutputFilter::new(
+        &shared.config.tts_blocked_terms,
+        &shared.config.tts_filter_action,
+    ) {
+        let before = phrases.len();
+        phrases = filter.filter_phrases(phrases);
+        if phrases.len() < before && filter.action() == tts::FilterAction::Stop {
+            tracing::info!(
+                spoken = phrases.len(),
+                total = before,
+                "Blocked term in TTS text, stopping speech early"
+            );
+        }
+    }
 
     if phrases.is_empty() {
         restore_tts_engine(shared, engine);
@@ -163,9 +365,16 @@ pub(super) async fn speak(shared: &Arc<PipelineShared>, text: &str) -> Result<()
         return Ok(());
     }
 
+    // Word-level progress events (engines that report word boundaries).
+    let mut progress =
+        WordProgress::start(shared.app_handle.clone(), text, Arc::clone(&request_cancel));
+
     // For single phrase, use simpler non-streaming path (less overhead)
     if phrases.len() <= 1 {
-        let result = speak_oneshot(shared, engine, &phrases[0], sample_rate, volume, output_device, Arc::clone(&request_cancel)).await;
+        let result = speak_oneshot(shared, engine, &phrases[0], &mut progress, output, Arc::clone(&request_cancel)).await;
+        if is_cancelled(&request_cancel) {
+            remember_unspoken(shared, &request_cancel, &phrases);
+        }
         finish_speaking(shared);
         return result;
     }
@@ -177,8 +386,9 @@ pub(super) async fn speak(shared: &Arc<PipelineShared>, text: &str) -> Result<()
         phrases.len()
     );
 
-    let (chunk_tx, chunk_rx) = tokio::sync::mpsc::channel::<Vec<f32>>(4);
+    let (chunk_tx, chunk_rx) = tokio::sync::mpsc::channel::<PhraseAudio>(4);
     let playback_cancel = Arc::clone(&request_cancel);
+    let scheduler = progress.scheduler();
 
     // Spawn playback thread: creates Sink, receives chunks via channel.
     // Uses the per-request cancel token so it stays cancelled even if the
@@ -187,33 +397,58 @@ pub(super) async fn speak(shared: &Arc<PipelineShared>, text: &str) -> Result<()
         play_chunks_rodio(
             chunk_rx,
             sample_rate,
-            volume,
-            output_device.as_deref(),
+            &output,
             &playback_cancel,
+            &scheduler,
         )
     });
 
-    // Synthesize phrases and send to playback
-    for (i, phrase) in phrases.iter().enumerate() {
-        if shared.tts_cancel.load(Ordering::SeqCst) {
-            tracing::info!("TTS cancelled during streaming synthesis");
-            // Propagate to per-request token so playback thread also stops
-            request_cancel.store(true, Ordering::SeqCst);
+    // Synthesize up to `lookahead` phrases concurrently; results come out in
+    // phrase order. Playback appends chunks to its sink as they arrive, so the
+    // channel never holds synthesis back.
+    let lookahead = shared.config.tts_lookahead.clamp(1, MAX_LOOKAHEAD) as usize;
+    let engine_ref: &dyn TtsEngine = engine.as_ref();
+    let mut synthesized = futures_util::stream::iter(phrases.iter())
+        .map(|phrase| async move {
+            tokio::time::timeout(SYNTH_TIMEOUT, synthesize_with_words(engine_ref, phrase)).await
+        })
+        .buffered(lookahead)
+        .enumerate();
+
+    loop {
+        let next = tokio::select! {
+            biased;
+            _ = wait_for_cancel(&shared.tts_cancel) => {
+                tracing::info!("TTS cancelled during streaming synthesis");
+                // Propagate to per-request token so playback thread also stops
+                request_cancel.store(true, Ordering::SeqCst);
+                break;
+            }
+            next = synthesized.next() => next,
+        };
+        let Some((i, result)) = next else {
             break;
-        }
+        };
 
-        match tokio::time::timeout(SYNTH_TIMEOUT, engine.synthesize(phrase)).await {
-            Ok(Ok(samples)) if !samples.is_empty() => {
+        match result {
+            Ok(Ok((samples, words))) if !samples.is_empty() => {
                 tracing::debug!(
                     phrase = i + 1,
                     samples = samples.len(),
                     duration_secs = format!("{:.2}", samples.len() as f64 / sample_rate as f64),
                     "Phrase synthesized"
                 );
-                if chunk_tx.send(samples).await.is_err() {
+                let chunk = PhraseAudio {
+                    index: i,
+                    samples,
+                    pause_after_ms: pause_after_phrase(&phrases[i], phrase_gap_ms, sentence_pause_ms),
+                    words: progress.marks(words),
+                };
+                if chunk_tx.send(chunk).await.is_err() {
                     tracing::warn!("Playback channel closed, stopping synthesis");
                     break;
                 }
+                timing::mark(shared, Stage::FirstAudio);
             }
             Ok(Ok(_)) => {
                 tracing::debug!(phrase = i + 1, "Phrase produced no audio, skipping");
@@ -233,6 +468,8 @@ pub(super) async fn speak(shared: &Arc<PipelineShared>, text: &str) -> Result<()
             }
         }
     }
+    // Dropping the stream cancels lookahead phrases still synthesizing
+    drop(synthesized);
 
     // Drop sender to signal playback thread that no more chunks are coming
     drop(chunk_tx);
@@ -241,16 +478,18 @@ pub(super) async fn speak(shared: &Arc<PipelineShared>, text: &str) -> Result<()
     restore_tts_engine(shared, engine);
 
     match playback_handle.await {
-        Ok(Ok(())) => {
+        Ok(Ok(unplayed)) if is_cancelled(&request_cancel) => {
+            tracing::info!(unplayed, "Streaming TTS playback interrupted");
+            remember_unspoken(shared, &request_cancel, &phrases[unplayed.min(phrases.len())..]);
+        }
+        Ok(Ok(_)) => {
             tracing::info!("Streaming TTS playback complete");
         }
         Ok(Err(e)) => {
             tracing::error!("Streaming TTS playback error: {}", e);
-            let _ = shared.app_handle.emit(
+            let _ = shared.app_handle.emit_throttled(
                 "voice-event",
-                VoiceEvent::Error {
-                    message: format!("TTS playback error: {}", e),
-                },
+                VoiceEvent::turn_error(shared, AppError::AudioDevice(format!("TTS playback error: {}", e))),
             );
         }
         Err(e) => {
@@ -262,16 +501,32 @@ pub(super) async fn speak(shared: &Arc<PipelineShared>, text: &str) -> Result<()
     Ok(())
 }
 
+/// Keep the phrases an interrupted `speak()` didn't finish, for
+/// `resume_speaking()`. Skipped when a newer `speak()` has taken over.
+fn remember_unspoken(shared: &Arc<PipelineShared>, request_cancel: &Arc<AtomicBool>, phrases: &[String]) {
+    let current = shared
+        .active_playback_cancel
+        .lock()
+        .map(|guard| guard.as_ref().is_some_and(|c| Arc::ptr_eq(c, request_cancel)))
+        .unwrap_or(false);
+    if !current {
+        return;
+    }
+    if let Ok(mut unspoken) = shared.unspoken.lock() {
+        *unspoken = phrases.to_vec();
+    }
+}
+
 /// Single-shot (non-streaming) synthesis + playback for short text.
 async fn speak_oneshot(
     shared: &Arc<PipelineShared>,
     engine: Box<dyn TtsEngine>,
     text: &str,
-    sample_rate: u32,
-    volume: f32,
-    output_device: Option<String>,
+    progress: &mut WordProgress,
+    output: PlaybackOutput,
     request_cancel: Arc<AtomicBool>,
 ) -> Result<(), String> {
+    let sample_rate = engine.sample_rate();
     let synthesize_result = match tokio::time::timeout(SYNTH_TIMEOUT, engine.synthesize(text)).await
     {
         Ok(result) => result,
@@ -309,13 +564,17 @@ async fn speak_oneshot(
                 return Ok(());
             }
 
+            let words = progress.marks(engine.take_word_boundaries());
+            let scheduler = progress.scheduler();
+            timing::mark(shared, Stage::FirstAudio);
             let playback_result = tokio::task::spawn_blocking(move || {
                 play_samples_rodio(
                     samples,
+                    words,
                     sample_rate,
-                    volume,
-                    output_device.as_deref(),
+                    &output,
                     &request_cancel,
+                    &scheduler,
                 )
             })
             .await;
@@ -326,11 +585,12 @@ async fn speak_oneshot(
                 Ok(Ok(())) => tracing::info!("TTS playback complete"),
                 Ok(Err(e)) => {
                     tracing::error!("TTS playback error: {}", e);
-                    let _ = shared.app_handle.emit(
+                    let _ = shared.app_handle.emit_throttled(
                         "voice-event",
-                        VoiceEvent::Error {
-                            message: format!("TTS playback error: {}", e),
-                        },
+                        VoiceEvent::turn_error(shared, AppError::AudioDevice(format!(
+                            "TTS playback error: {}",
+                            e
+                        ))),
                     );
                 }
                 Err(e) => tracing::error!("TTS playback task panicked: {}", e),
@@ -339,11 +599,9 @@ async fn speak_oneshot(
         Err(e) => {
             tracing::error!("TTS synthesis failed: {}", e);
             restore_tts_engine(shared, engine);
-            let _ = shared.app_handle.emit(
+            let _ = shared.app_handle.emit_throttled(
                 "voice-event",
-                VoiceEvent::Error {
-                    message: format!("TTS synthesis failed: {}", e),
-                },
+                VoiceEvent::turn_error(shared, AppError::from(e).context("TTS synthesis failed")),
             );
         }
     }
@@ -352,15 +610,96 @@ async fn speak_oneshot(
 }
 
 /// Restore the TTS engine into shared state after use.
-pub(crate) fn restore_tts_engine(shared: &Arc<PipelineShared>, engine: Box<dyn TtsEngine>) {
-    match shared.tts_engine.lock() {
-        Ok(mut guard) => {
-            *guard = Some(engine);
+///
+/// Voice/speed changes queued while the engine was checked out are applied
+/// here, under the same lock that [`set_tts_voice`] uses to queue them.
+pub(crate) fn restore_tts_engine(shared: &Arc<PipelineShared>, mut engine: Box<dyn TtsEngine>) {
+    let (mut guard, poisoned) = recovery::lock_slot(&shared.tts_engine);
+    if let Ok(mut pending) = shared.pending_tts.lock() {
+        if let Some(voice) = pending.voice.take() {
+            if let Err(e) = engine.set_voice(&voice) {
+                tracing::warn!(voice = %voice, "Queued TTS voice change failed: {}", e);
+            }
         }
-        Err(e) => {
-            tracing::error!("Failed to lock tts_engine to restore: {}", e);
+        if let Some(speed) = pending.speed.take() {
+            engine.set_speed(speed);
+        }
+    }
+    *guard = Some(engine);
+    drop(guard);
+    if poisoned {
+        recovery::report_recovery(shared, "tts", "poisoned");
+    }
+}
+
+/// Speak this utterance in `voice`, then go back to the selected voice: the
+/// switch back is queued like a voice change made mid-speech, so
+/// `restore_tts_engine` applies it whichever way `speak()` ends. A voice the
+/// engine doesn't know is logged and the selected one used.
+fn use_voice_once(shared: &Arc<PipelineShared>, engine: &mut dyn TtsEngine, voice: &str) {
+    let selected = shared
+        .tts_voice
+        .lock()
+        .map(|v| v.clone())
+        .unwrap_or_default();
+    if voice == selected {
+        return;
+    }
+    match engine.set_voice(voice) {
+        Ok(()) => {
+            if let Ok(mut pending) = shared.pending_tts.lock() {
+                // A voice picked meanwhile is newer than `selected`
+                pending.voice.get_or_insert(selected);
+            }
+        }
+        Err(e) => tracing::warn!(voice = %voice, "Sender's TTS voice unavailable: {}", e),
+    }
+}
+
+/// Voice/speed changes requested while `speak()` had the engine checked out.
+#[derive(Debug, Default)]
+pub(crate) struct PendingTtsSettings {
+    voice: Option<String>,
+    speed: Option<f32>,
+}
+
+/// Switch the pipeline's TTS voice.
+///
+/// Applies immediately when the engine is idle. While an utterance is being
+/// spoken the change is queued and takes effect from the next one; an
+/// invalid voice is then only logged, since the engine can't be asked.
+pub(crate) fn set_tts_voice(shared: &Arc<PipelineShared>, voice: &str) -> Result<(), String> {
+    let (mut guard, _) = recovery::lock_slot(&shared.tts_engine);
+    if let Ok(mut selected) = shared.tts_voice.lock() {
+        *selected = voice.to_string();
+    }
+    match guard.as_mut() {
+        Some(engine) => engine.set_voice(voice).map_err(|e| e.to_string()),
+        None => {
+            let mut pending = shared
+                .pending_tts
+                .lock()
+                .map_err(|e| format!("Failed to lock pending_tts: {}", e))?;
+            pending.voice = Some(voice.to_string());
+            Ok(())
+        }
+    }
+}
+
+/// Change the pipeline's TTS speed (same queuing rules as [`set_tts_voice`]).
+pub(crate) fn set_tts_speed(shared: &Arc<PipelineShared>, speed: f32) -> Result<(), String> {
+    let (mut guard, _) = recovery::lock_slot(&shared.tts_engine);
+    match guard.as_mut() {
+        Some(engine) => engine.set_speed(speed),
+        None => {
+            let mut pending = shared
+                .pending_tts
+                .lock()
+                .map_err(|e| format!("Failed to lock pending_tts: {}", e))?;
+            pending.speed = Some(speed);
         }
     }
+    Ok(())
 }
 
 /// Transition the pipeline out of Speaking state.
@@ -390,10 +729,13 @@ pub(crate) fn finish_speaking(shared: &Arc<PipelineShared>) {
     // Always emit SpeakingEnd so the frontend knows TTS is done
     let _ = shared
         .app_handle
-        .emit("voice-event", VoiceEvent::SpeakingEnd {});
+        .emit_throttled("voice-event", VoiceEvent::SpeakingEnd {});
+    let interrupted = shared.tts_cancel.load(Ordering::SeqCst);
+    timing::finish_reply(shared, interrupted);
+    shared.session.finish_speaking(&shared.app_handle, interrupted);
 
     if swapped.is_ok() {
-        let _ = shared.app_handle.emit(
+        let _ = shared.app_handle.emit_throttled(
             "voice-event",
             VoiceEvent::StateChange {
                 state: next_state.to_string(),
@@ -405,7 +747,7 @@ pub(crate) fn finish_speaking(shared: &Arc<PipelineShared>) {
 }
 
 /// Open the audio output stream for a named or default device.
-fn open_output_stream(
+pub(super) fn open_output_stream(
     output_device_name: Option<&str>,
 ) -> Result<(OutputStream, rodio::OutputStreamHandle), String> {
     if let Some(name) = output_device_name {
@@ -436,6 +778,149 @@ fn open_output_stream(
     }
 }
 
+/// Devices to play TTS on: the primary (`None` = system default) first,
+/// then each extra device that isn't blank or a repeat.
+fn output_targets(primary: Option<&str>, extra: &[String]) -> Vec<Option<String>> {
+    let mut targets = vec![primary.map(String::from)];
+    for name in extra.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        if !targets.iter().any(|t| t.as_deref() == Some(name)) {
+            targets.push(Some(name.to_string()));
+        }
+    }
+    targets
+}
+
+/// Where and how TTS audio is played.
+struct PlaybackOutput {
+    /// From `output_targets`.
+    targets: Vec<Option<String>>,
+    /// rodio volume: 1.0 = normal.
+    volume: f32,
+    resampler: ResamplerKind,
+    /// Voice effect specs (see `tts::effects`).
+    effects: Vec<String>,
+}
+
+/// Native sample rate of an output device (`None` = system default), if it
+/// can be queried.
+fn output_device_rate(name: Option<&str>) -> Option<u32> {
+    let host = cpal::default_host();
+    let device = match name {
+        Some(name) => host
+            .output_devices()
+            .ok()?
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+            .or_else(|| host.default_output_device()),
+        None => host.default_output_device(),
+    }?;
+    device
+        .default_output_config()
+        .ok()
+        .map(|config| config.sample_rate().0)
+}
+
+/// Keeps an output open while its sink plays.
+enum OutputHandle {
+    Device(OutputStream),
+    Remote(remote_output::Attachment),
+}
+
+/// One rodio Sink per output device, all fed the same audio.
+///
+/// The first device is the primary: failing to open it is an error, and word
+/// timings follow its playback. Extra devices that fail to open are skipped
+/// with a warning so a missing speaker never silences the headset. Network
+/// targets (see `remote_output`) get an idle Sink drained by their clock.
+struct MultiSink {
+    // Outputs must outlive their sinks.
+    _outputs: Vec<OutputHandle>,
+    sinks: Vec<Sink>,
+    /// Per sink: the device rate to pre-convert to (sinc resampler only).
+    device_rates: Vec<Option<u32>>,
+    resampler: ResamplerKind,
+    /// Runs across every buffer of the utterance, silence included, so
+    /// reverb rings on into the pauses.
+    effects: Mutex<Option<EffectChain>>,
+}
+
+impl MultiSink {
+    fn open(output: &PlaybackOutput, sample_rate: u32) -> Result<Self, String> {
+        let targets = &output.targets;
+        let mut outputs = Vec::with_capacity(targets.len());
+        let mut sinks = Vec::with_capacity(targets.len());
+        let mut device_rates = Vec::with_capacity(targets.len());
+        for (i, target) in targets.iter().enumerate() {
+            let sinc = output.resampler == ResamplerKind::Sinc;
+            let opened = match target.as_deref().and_then(RemoteTarget::parse) {
+                Some(Ok(remote)) => remote_output::attach(&remote).map(|(sink, attachment)| {
+                    (OutputHandle::Remote(attachment), sink, sinc.then_some(remote.rate))
+                }),
+                Some(Err(e)) => Err(e.to_string()),
+                None => open_output_stream(target.as_deref()).and_then(|(stream, handle)| {
+                    Sink::try_new(&handle)
+                        .map(|sink| {
+                            let rate = if sinc { output_device_rate(target.as_deref()) } else { None };
+                            (OutputHandle::Device(stream), sink, rate)
+                        })
+                        .map_err(|e| format!("Failed to create audio sink: {}", e))
+                }),
+            };
+            match opened {
+                Ok((handle, sink, device_rate)) => {
+                    // rodio volume: 1.0 = normal
+                    sink.set_volume(output.volume.clamp(0.0, 2.0));
+                    outputs.push(handle);
+                    sinks.push(sink);
+                    device_rates.push(device_rate);
+                }
+                Err(e) if i == 0 => return Err(e),
+                Err(e) => tracing::warn!(device = ?target, "Skipping mirrored output: {}", e),
+            }
+        }
+        Ok(Self {
+            _outputs: outputs,
+            sinks,
+            device_rates,
+            resampler: output.resampler,
+            effects: Mutex::new(EffectChain::new(&output.effects, sample_rate)),
+        })
+    }
+
+    /// The sink word timings are scheduled on.
+    fn primary(&self) -> &Sink {
+        &self.sinks[0]
+    }
+
+    /// Queue the same mono samples on every device.
+    fn append_samples(&self, sample_rate: u32, mut samples: Vec<f32>) {
+        if let Some(chain) = self.effects.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            chain.process(&mut samples);
+        }
+        for (sink, device_rate) in self.sinks.iter().zip(&self.device_rates) {
+            let buffer = match *device_rate {
+                Some(rate) if rate != sample_rate => rodio::buffer::SamplesBuffer::new(
+                    1,
+                    rate,
+                    resample::resample(self.resampler, &samples, sample_rate, rate),
+                ),
+                _ => rodio::buffer::SamplesBuffer::new(1, sample_rate, samples.clone()),
+            };
+            sink.append(buffer);
+        }
+    }
+
+    /// True once every device has drained.
+    fn empty(&self) -> bool {
+        self.sinks.iter().all(Sink::empty)
+    }
+
+    fn stop(&self) {
+        for sink in &self.sinks {
+            sink.stop();
+        }
+    }
+}
+
 /// Check if cancellation has been requested (per-request token).
 #[inline]
 fn is_cancelled(cancel: &AtomicBool) -> bool {
@@ -444,34 +929,29 @@ fn is_cancelled(cancel: &AtomicBool) -> bool {
 
 /// Play f32 PCM samples through the audio output device using rodio.
 ///
-/// This runs on a blocking thread. It creates a rodio `OutputStream` and
-/// `Sink`, loads the samples as a buffer source, and blocks until playback
-/// finishes or cancellation is requested.
+/// This runs on a blocking thread. It opens a rodio Sink on each output
+/// device, loads the samples as a buffer source, and blocks until playback
+/// finishes everywhere or cancellation is requested.
 ///
 /// The `cancel` flag is a per-request token that stays true even if a new
 /// speak() call resets the shared tts_cancel flag.
 fn play_samples_rodio(
     samples: Vec<f32>,
+    words: Vec<WordMark>,
     sample_rate: u32,
-    volume: f32,
-    output_device_name: Option<&str>,
+    output: &PlaybackOutput,
     cancel: &AtomicBool,
+    scheduler: &WordScheduler,
 ) -> Result<(), String> {
-    let (_stream, stream_handle) = open_output_stream(output_device_name)?;
-
-    let sink = Sink::try_new(&stream_handle)
-        .map_err(|e| format!("Failed to create audio sink: {}", e))?;
-
-    // Set volume (rodio volume: 1.0 = normal)
-    sink.set_volume(volume.clamp(0.0, 2.0));
+    let sink = MultiSink::open(output, sample_rate)?;
 
     // Cap how long we'll wait for this known-length buffer to drain, so a
     // stalled audio device can't hang the Speaking state forever.
     let cap = playback_cap(samples.len(), sample_rate);
 
-    // Create a rodio source from the f32 samples (mono, engine sample rate)
-    let source = rodio::buffer::SamplesBuffer::new(1, sample_rate, samples);
-    sink.append(source);
+    // Queue the f32 samples (mono, engine sample rate)
+    scheduler.append_start(sink.primary(), words);
+    sink.append_samples(sample_rate, samples);
 
     // Poll for completion or cancellation
     let start = Instant::now();
@@ -498,24 +978,27 @@ fn play_samples_rodio(
 /// Play audio chunks received from an async channel via rodio Sink.
 ///
 /// This runs on a blocking thread. It receives synthesized audio chunks
-/// from the streaming TTS pipeline and appends each to the Sink for
-/// gapless playback. First audio plays as soon as the first chunk arrives.
+/// from the streaming TTS pipeline and appends each to the Sink. First audio
+/// plays as soon as the first chunk arrives.
+///
+/// The pause requested by a phrase is inserted as a silence buffer just
+/// before the NEXT phrase is appended, so the final phrase never gets
+/// trailing silence that would delay SpeakingEnd. Each phrase's word
+/// timings start when playback reaches it, after that silence.
 ///
 /// The `cancel` flag is a per-request token that stays true even if a new
 /// speak() call resets the shared tts_cancel flag.
+///
+/// Returns the index of the first phrase that wasn't played to the end (see
+/// `first_unplayed`), so a cancelled reply can be resumed from there.
 fn play_chunks_rodio(
-    rx: tokio::sync::mpsc::Receiver<Vec<f32>>,
+    rx: tokio::sync::mpsc::Receiver<PhraseAudio>,
     sample_rate: u32,
-    volume: f32,
-    output_device_name: Option<&str>,
+    output: &PlaybackOutput,
     cancel: &AtomicBool,
-) -> Result<(), String> {
-    let (_stream, stream_handle) = open_output_stream(output_device_name)?;
-
-    let sink = Sink::try_new(&stream_handle)
-        .map_err(|e| format!("Failed to create audio sink: {}", e))?;
-
-    sink.set_volume(volume.clamp(0.0, 2.0));
+    scheduler: &WordScheduler,
+) -> Result<usize, String> {
+    let sink = MultiSink::open(output, sample_rate)?;
 
     // Use the current tokio runtime handle to block_on channel receives
     let rt = tokio::runtime::Handle::current();
@@ -532,21 +1015,41 @@ fn play_chunks_rodio(
 
     let mut total_samples: usize = 0;
     let mut idle = Duration::ZERO;
+    // Silence owed before the next phrase (from the previous phrase's pause).
+    let mut pending_silence: usize = 0;
+    // Phrase index of each source on the primary sink (None: silence or a
+    // word timing marker), and the phrase after the last one received.
+    let mut queued: Vec<Option<usize>> = Vec::new();
+    let mut next_phrase: usize = 0;
+    let unplayed_now = |queued: &[Option<usize>], next_phrase: usize| {
+        first_unplayed(queued, sink.primary().len(), next_phrase)
+    };
 
     // Receive and play chunks as they arrive
     loop {
         if is_cancelled(cancel) {
             tracing::info!("Streaming TTS playback cancelled");
+            let unplayed = unplayed_now(&queued, next_phrase);
             sink.stop();
-            return Ok(());
+            return Ok(unplayed);
         }
 
         match rt.block_on(async { tokio::time::timeout(RECV_POLL, rx.recv()).await }) {
-            Ok(Some(samples)) => {
+            Ok(Some(chunk)) => {
                 idle = Duration::ZERO;
-                total_samples += samples.len();
-                let source = rodio::buffer::SamplesBuffer::new(1, sample_rate, samples);
-                sink.append(source);
+                if pending_silence > 0 {
+                    total_samples += pending_silence;
+                    sink.append_samples(sample_rate, vec![0.0f32; pending_silence]);
+                    queued.push(None);
+                }
+                pending_silence = silence_samples(chunk.pause_after_ms, sample_rate);
+                total_samples += chunk.samples.len();
+                if scheduler.append_start(sink.primary(), chunk.words) {
+                    queued.push(None);
+                }
+                sink.append_samples(sample_rate, chunk.samples);
+                queued.push(Some(chunk.index));
+                next_phrase = chunk.index + 1;
             }
             Ok(None) => {
                 // Channel closed — all chunks sent, wait for playback to finish
@@ -561,8 +1064,9 @@ fn play_chunks_rodio(
                         idle_secs = idle.as_secs(),
                         "Streaming TTS received no audio chunk, stopping (synthesis wedged?)"
                     );
+                    let unplayed = unplayed_now(&queued, next_phrase);
                     sink.stop();
-                    return Ok(());
+                    return Ok(unplayed);
                 }
             }
         }
@@ -575,19 +1079,157 @@ fn play_chunks_rodio(
     while !sink.empty() {
         if is_cancelled(cancel) {
             tracing::info!("Streaming TTS playback cancelled during drain");
+            let unplayed = unplayed_now(&queued, next_phrase);
             sink.stop();
-            return Ok(());
+            return Ok(unplayed);
         }
         if start.elapsed() > cap {
             tracing::warn!(
                 cap_secs = cap.as_secs(),
                 "Streaming TTS drain exceeded expected duration, stopping (audio device stalled?)"
             );
+            let unplayed = unplayed_now(&queued, next_phrase);
             sink.stop();
-            return Ok(());
+            return Ok(unplayed);
         }
         std::thread::sleep(Duration::from_millis(50));
     }
 
-    Ok(())
+    Ok(next_phrase)
+}
+
+/// The first phrase not played to the end. `queued` lists the phrase index
+/// of every source appended to the sink (None for silence and markers), of
+/// which the last `still_queued` haven't finished; the first phrase among
+/// those was cut off. With none left, it's `next_phrase`, the one after the
+/// last received.
+fn first_unplayed(queued: &[Option<usize>], still_queued: usize, next_phrase: usize) -> usize {
+    queued[queued.len().saturating_sub(still_queued)..]
+        .iter()
+        .flatten()
+        .next()
+        .copied()
+        .unwrap_or(next_phrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_pause_after_phrase() {
+        assert_eq!(pause_after_phrase("First item,", 80, 300), 80);
+        assert_eq!(pause_after_phrase("That's all.", 80, 300), 300);
+        assert_eq!(pause_after_phrase("Ready?", 0, 0), 0);
+        // Sentence pause never undercuts the phrase gap
+        assert_eq!(pause_after_phrase("Done!", 200, 100), 200);
+    }
+
+    #[test]
+    fn test_output_targets() {
+        assert_eq!(output_targets(None, &[]), vec![None]);
+        let extra = vec!["Speakers".to_string(), " ".to_string(), "Headset".to_string()];
+        assert_eq!(
+            output_targets(Some("Headset"), &extra),
+            vec![Some("Headset".to_string()), Some("Speakers".to_string())]
+        );
+        // Extras still play alongside the system default
+        assert_eq!(
+            output_targets(None, &["Speakers".to_string()]),
+            vec![None, Some("Speakers".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_silence_samples() {
+        assert_eq!(silence_samples(0, 24000), 0);
+        assert_eq!(silence_samples(250, 24000), 6000);
+        assert_eq!(silence_samples(100, 22050), 2205);
+    }
+
+    #[test]
+    fn test_first_unplayed() {
+        // phrase 0, silence, marker, phrase 1, silence, phrase 2
+        let queued = [Some(0), None, None, Some(1), None, Some(2)];
+        // Cut off during phrase 1
+        assert_eq!(first_unplayed(&queued, 3, 3), 1);
+        // During the silence before phrase 2
+        assert_eq!(first_unplayed(&queued, 2, 3), 2);
+        // Everything received has played
+        assert_eq!(first_unplayed(&queued, 0, 3), 3);
+        assert_eq!(first_unplayed(&[], 0, 0), 0);
+    }
+
+    /// Network-style engine: slower for shorter text, and keeps the timings
+    /// of its last synthesis like Edge does.
+    struct Delayed {
+        last_words: Mutex<Vec<WordBoundary>>,
+    }
+
+    impl TtsEngine for Delayed {
+        fn synthesize(
+            &self,
+            text: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, TtsError>> + Send + '_>> {
+            let text = text.to_string();
+            Box::pin(async move {
+                self.last_words.lock().unwrap().clear();
+                let delay = 80u64.saturating_sub(text.len() as u64 * 10);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                *self.last_words.lock().unwrap() = vec![WordBoundary {
+                    offset: Duration::ZERO,
+                    word: text.clone(),
+                }];
+                Ok(vec![0.0; text.len()])
+            })
+        }
+        fn take_word_boundaries(&self) -> Vec<WordBoundary> {
+            std::mem::take(&mut *self.last_words.lock().unwrap())
+        }
+        fn stop(&self) {}
+        fn set_voice(&mut self, _voice: &str) -> Result<(), TtsError> {
+            Ok(())
+        }
+        fn set_speed(&mut self, _speed: f32) {}
+        fn name(&self) -> String {
+            "Delayed".into()
+        }
+        fn sample_rate(&self) -> u32 {
+            16000
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lookahead_keeps_order_and_timings() {
+        let engine = Delayed {
+            last_words: Mutex::new(Vec::new()),
+        };
+        let phrases = ["a", "bb", "ccc", "dddd"];
+        let start = Instant::now();
+        let results: Vec<_> = futures_util::stream::iter(phrases)
+            .map(|phrase| synthesize_with_words(&engine, phrase))
+            .buffered(4)
+            .collect()
+            .await;
+        // Later phrases finish first, yet come out in order with their own words
+        for (phrase, result) in phrases.iter().zip(results) {
+            let (samples, words) = result.unwrap();
+            assert_eq!(samples.len(), phrase.len());
+            assert_eq!(words[0].word, *phrase);
+        }
+        // Overlapped: about the slowest phrase (70 ms), not the sum (200 ms)
+        assert!(start.elapsed() < Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_cancel() {
+        let flag = AtomicBool::new(false);
+        let pending = tokio::time::timeout(Duration::from_millis(120), wait_for_cancel(&flag));
+        assert!(pending.await.is_err());
+        flag.store(true, Ordering::SeqCst);
+        wait_for_cancel(&flag).await;
+    }
 }
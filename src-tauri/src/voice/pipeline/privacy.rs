@@ -0,0 +1,162 @@
+//! Microphone privacy events: when the mic is open, and whether it is muted.
+//!
+//! `MicrophoneOpen` is emitted when a capture stream on an input device is
+//! created (start, `resume()`), `MicrophoneClosed` when it is dropped
+//! (`pause()`, stop), so the UI's "mic live" indicator follows the device,
+//! not the voice state. Network sources and system-audio loopback aren't a
+//! microphone and emit neither.
+//!
+//! A muted mic records silence, which looks like a user who isn't talking.
+//! `MicrophoneMuted { muted, source }` reports it two ways:
+//!
+//! - `"os"`: the OS mute state of the default input device, polled every
+//!   `MUTE_POLL` (see `services::mic_mute`). Only when no input device is
+//!   picked, since that's the device the OS reports on.
+//! - `"signal"`: `SILENT_AFTER` of exact digital zeros from the device.
+//!   Real rooms are never that quiet; a hardware mute switch the OS doesn't
+//!   know about is.
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::format::AudioFormat;
+use super::{PipelineShared, VoiceEvent};
+use crate::services::event_throttle::EmitThrottled;
+use crate::services::mic_mute;
+
+/// How often the OS mute state is read.
+pub const MUTE_POLL: Duration = Duration::from_secs(3);
+
+/// Digital silence lasting this long counts as a muted mic.
+pub const SILENT_AFTER: Duration = Duration::from_secs(2);
+
+/// Emit `MicrophoneOpen` for the input device `device`.
+pub(super) fn microphone_open(shared: &PipelineShared, device: Option<String>) {
+    tracing::info!(device = ?device, "Microphone open");
+    let _ = shared
+        .app_handle
+        .emit_throttled("voice-event", VoiceEvent::MicrophoneOpen { device });
+}
+
+/// Emit `MicrophoneClosed`.
+pub(super) fn microphone_closed(shared: &PipelineShared) {
+    tracing::info!("Microphone closed");
+    let _ = shared
+        .app_handle
+        .emit_throttled("voice-event", VoiceEvent::MicrophoneClosed {});
+}
+
+fn emit_muted(shared: &PipelineShared, muted: bool, source: &'static str) {
+    if muted {
+        tracing::warn!(source, "Microphone is muted, recordings will be silent");
+    } else {
+        tracing::info!(source, "Microphone unmuted");
+    }
+    let _ = shared
+        .app_handle
+        .emit_throttled("voice-event", VoiceEvent::MicrophoneMuted { muted, source });
+}
+
+/// Poll the OS mute state until the pipeline stops, emitting each change.
+/// Skipped while paused (the mic is closed) and for a picked input device.
+pub(super) async fn poll_os_mute(shared: Arc<PipelineShared>) {
+    if shared.config.input_device.is_some()
+        || shared.config.capture_source == super::CAPTURE_SYSTEM
+    {
+        return;
+    }
+    let mut last: Option<bool> = None;
+    while shared.running.load(Ordering::Relaxed) {
+        if !shared.paused.load(Ordering::Acquire) {
+            let muted = tokio::task::spawn_blocking(mic_mute::default_input_muted)
+                .await
+                .ok()
+                .flatten();
+            // Not reporting "unmuted" for a mic we never saw muted
+            if let Some(muted) = muted.filter(|&m| last.is_some() || m) {
+                if last != Some(muted) {
+                    emit_muted(&shared, muted, "os");
+                }
+                last = Some(muted);
+            }
+        }
+        tokio::time::sleep(MUTE_POLL).await;
+    }
+}
+
+/// Watches captured audio for digital silence. Fed by the processing loop.
+pub(super) struct SilentInput {
+    /// Zero samples in a row.
+    zeros: usize,
+    limit: usize,
+    muted: bool,
+}
+
+impl SilentInput {
+    pub(super) fn new(format: &AudioFormat) -> Self {
+        Self {
+            zeros: 0,
+            limit: format.samples(SILENT_AFTER.as_secs_f64()),
+            muted: false,
+        }
+    }
+
+    /// Look at a chunk; `Some(muted)` when the verdict changed.
+    pub(super) fn observe(&mut self, chunk: &[f32]) -> Option<bool> {
+        match chunk.iter().rposition(|&s| s != 0.0) {
+            Some(last_sound) => self.zeros = chunk.len() - 1 - last_sound,
+            None => self.zeros += chunk.len(),
+        }
+        let muted = self.zeros >= self.limit;
+        (muted != self.muted).then(|| {
+            self.muted = muted;
+            muted
+        })
+    }
+
+    /// `observe` and emit the change, if any.
+    pub(super) fn check(&mut self, shared: &PipelineShared, chunk: &[f32]) {
+        if let Some(muted) = self.observe(chunk) {
+            emit_muted(shared, muted, "signal");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn silent_input(limit: usize) -> SilentInput {
+        SilentInput {
+            zeros: 0,
+            limit,
+            muted: false,
+        }
+    }
+
+    #[test]
+    fn test_silent_input_needs_a_full_stretch_of_zeros() {
+        let mut silent = silent_input(10);
+        assert_eq!(silent.observe(&[0.0; 6]), None);
+        assert_eq!(silent.observe(&[0.0; 6]), Some(true));
+        assert_eq!(silent.observe(&[0.0; 6]), None);
+    }
+
+    #[test]
+    fn test_silent_input_quiet_room_is_not_muted() {
+        let mut silent = silent_input(10);
+        for _ in 0..10 {
+            assert_eq!(silent.observe(&[0.0, 0.0, 0.0001, 0.0]), None);
+        }
+    }
+
+    #[test]
+    fn test_silent_input_unmutes_on_sound() {
+        let mut silent = silent_input(4);
+        assert_eq!(silent.observe(&[0.0; 4]), Some(true));
+        assert_eq!(silent.observe(&[0.0, 0.2, 0.0]), Some(false));
+        // The zeros after the sound count towards the next stretch
+        assert_eq!(silent.observe(&[0.0, 0.0, 0.0]), Some(true));
+    }
+}
@@ -0,0 +1,174 @@
+//! Recovery of a lost STT or TTS engine.
+//!
+//! Each engine lives in a `Mutex<Option<..>>` slot and is taken out for the
+//! length of a transcription or an utterance. A panic while a slot is locked
+//! poisons it, and an engine that can't be put back (or dies with a
+//! panicking task) leaves the slot empty; every later turn then failed with
+//! "No STT/TTS engine available" until the pipeline was restarted.
+//!
+//! A poisoned slot is now cleared and used anyway: it only holds an
+//! `Option`, which a panic can't leave half-written. An empty slot gets a
+//! fresh engine built from the pipeline config. Each recovery is logged and
+//! emitted as `VoiceEvent::EngineRecovered`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::{loading, PipelineShared, VoiceEvent};
+use crate::services::event_throttle::EmitThrottled;
+use crate::voice::stt::{self, SttAdapter, SttError};
+use crate::voice::tts::{self, TtsEngine, TtsError};
+use crate::voice::VoiceEngineConfig;
+
+/// Lock an engine slot, clearing poison. The flag says whether it was
+/// poisoned.
+pub(super) fn lock_slot<T>(slot: &Mutex<T>) -> (MutexGuard<'_, T>, bool) {
+    match slot.lock() {
+        Ok(guard) => (guard, false),
+        Err(poisoned) => {
+            slot.clear_poison();
+            (poisoned.into_inner(), true)
+        }
+    }
+}
+
+/// Log and emit a recovery. `reason` is "poisoned" (the slot's lock was
+/// poisoned) or "missing" (the slot was empty and the engine was rebuilt).
+pub(super) fn report_recovery(shared: &PipelineShared, engine: &str, reason: &str) {
+    tracing::warn!(engine, reason, "Recovered voice engine");
+    let _ = shared.app_handle.emit_throttled(
+        "voice-event",
+        VoiceEvent::EngineRecovered {
+            engine: engine.into(),
+            reason: reason.into(),
+        },
+    );
+}
+
+/// Build the STT engine the config asks for.
+pub(super) fn stt_engine_from_config(config: &VoiceEngineConfig) -> Result<SttAdapter, SttError> {
+    let engine = stt::create_stt_engine(
+        &config.stt_adapter,
+        &crate::services::platform::get_data_dir(),
+        Some(&config.stt_model_size),
+        config.stt_use_gpu,
+        &config.stt_language,
+    )?;
+    Ok(engine
+        .with_context_words(config.stt_context_words)
+        .with_prompt_bias(&config.stt_initial_prompt, &config.stt_vocabulary)
+        .with_task(config.stt_task))
+}
+
+/// Build the TTS engine the config asks for.
+pub(super) fn tts_engine_from_config(
+    config: &VoiceEngineConfig,
+) -> Result<Box<dyn TtsEngine>, TtsError> {
+    tts::create_tts_engine(
+        &config.tts_adapter,
+        Some(&config.tts_voice),
+        Some(config.tts_speed),
+    )
+}
+
+/// Take the STT engine for a transcription, rebuilding it when the slot is
+/// empty. Waits for the first load if it hasn't finished. Only the
+/// processing loop takes it, one turn at a time, so an empty slot after that
+/// always means the engine is gone.
+pub(super) async fn take_stt_engine(shared: &Arc<PipelineShared>) -> Option<SttAdapter> {
+    if !loading::ensure_stt(shared).await {
+        return None;
+    }
+    let (engine, poisoned) = {
+        let (mut guard, poisoned) = lock_slot(&shared.stt_engine);
+        (guard.take(), poisoned)
+    };
+    let reason = if poisoned { "poisoned" } else { "missing" };
+    if engine.is_some() {
+        if poisoned {
+            report_recovery(shared, "stt", reason);
+        }
+        return engine;
+    }
+
+    let config = shared.config.clone();
+    match tokio::task::spawn_blocking(move || stt_engine_from_config(&config)).await {
+        Ok(Ok(engine)) => {
+            report_recovery(shared, "stt", reason);
+            Some(engine)
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("Could not re-create STT engine: {}", e);
+            None
+        }
+        Err(e) => {
+            tracing::error!("STT engine re-creation panicked: {}", e);
+            None
+        }
+    }
+}
+
+/// Put the STT engine back after a transcription.
+pub(super) fn restore_stt_engine(shared: &PipelineShared, engine: SttAdapter) {
+    let (mut guard, poisoned) = lock_slot(&shared.stt_engine);
+    *guard = Some(engine);
+    drop(guard);
+    if poisoned {
+        report_recovery(shared, "stt", "poisoned");
+    }
+}
+
+/// Counts the TTS engine as checked out until dropped, so `take_tts_engine`
+/// can tell an engine another `speak()` is using from a lost one. Being a
+/// guard, it also releases the count when `speak()` panics.
+pub(crate) struct TtsCheckout<'a>(&'a AtomicUsize);
+
+impl<'a> TtsCheckout<'a> {
+    /// Count a checkout. Call with the slot locked, so the slot being empty
+    /// and the count can't be observed out of step.
+    pub(super) fn begin(count: &'a AtomicUsize) -> Self {
+        count.fetch_add(1, Ordering::AcqRel);
+        Self(count)
+    }
+}
+
+impl Drop for TtsCheckout<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_slot_clears_poison() {
+        let slot = Arc::new(Mutex::new(Some(7)));
+        let poisoner = Arc::clone(&slot);
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poison the slot");
+        })
+        .join();
+        assert!(slot.is_poisoned());
+
+        let (guard, poisoned) = lock_slot(&slot);
+        assert!(poisoned);
+        assert_eq!(*guard, Some(7));
+        drop(guard);
+        assert!(!slot.is_poisoned());
+        assert!(!lock_slot(&slot).1);
+    }
+
+    #[test]
+    fn test_checkout_count() {
+        let count = AtomicUsize::new(0);
+        let first = TtsCheckout::begin(&count);
+        let second = TtsCheckout::begin(&count);
+        drop(first);
+        assert_eq!(count.load(Ordering::Acquire), 1);
+        drop(second);
+        assert_eq!(count.load(Ordering::Acquire), 0);
+    }
+}
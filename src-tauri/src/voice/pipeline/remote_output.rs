@@ -0,0 +1,569 @@
+//! Remote TTS output: speech played on a network sink instead of (or as
+//! well as) a local device.
+//!
+//! Selected with an output device, primary or mirrored, of the form
+//! - `http://[<bind-host>]:<port>?token=<secret>`: serve a live WAV stream.
+//!   Players open `http://<this machine>:<port>/stream?token=<secret>`;
+//!   `/?token=<secret>` is a page with an audio player for a phone browser.
+//!   Like the control API, every request must carry the token. With no
+//!   bind host the stream is only served on 127.0.0.1; phones on the LAN
+//!   need an explicit one such as `0.0.0.0`.
+//! - `snapcast://<host>[:<port>]`: push raw s16le PCM into a Snapcast
+//!   server's TCP source (`source = tcp://...?mode=server`, port 4953 by
+//!   default), so every Snapcast client in the house speaks the reply.
+//!
+//! Both take `rate=<hz>&channels=<n>` options; the defaults are 24 kHz mono for HTTP
+//! and 48 kHz stereo (snapserver's default `sampleformat`) for Snapcast.
+//!
+//! Each target runs one clock thread while the pipeline runs. Speech reaches
+//! it through an idle rodio `Sink` whose queue the clock drains in real
+//! time, so volume, word timings and "finished playing" behave as they do on
+//! a local device (listeners hear it a little later, after their player's
+//! buffer). HTTP listeners get silence between replies so players stay
+//! connected; Snapcast only gets audio while something is being said.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use rodio::queue::SourcesQueueOutput;
+use rodio::source::UniformSourceIterator;
+use rodio::Sink;
+
+use crate::error::AppError;
+
+/// Output device prefix that serves an HTTP audio stream.
+pub(crate) const HTTP_SCHEME: &str = "http://";
+/// Output device prefix that feeds a Snapcast TCP source.
+pub(crate) const SNAPCAST_SCHEME: &str = "snapcast://";
+
+const SNAPCAST_PORT: u16 = 4953;
+/// Audio sent per clock tick.
+const TICK: Duration = Duration::from_millis(20);
+/// A listener that can't take a tick's audio this fast is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+/// How long a new HTTP connection has to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+/// Wait between attempts to reach an unreachable Snapcast server.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const MAX_LISTENERS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RemoteKind {
+    Http,
+    Snapcast,
+}
+
+/// A parsed remote output device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RemoteTarget {
+    pub kind: RemoteKind,
+    /// Bind address (HTTP) or server `host:port` (Snapcast).
+    pub addr: String,
+    pub rate: u32,
+    pub channels: u16,
+    /// Required on every HTTP request (`?token=`); `None` for Snapcast.
+    pub token: Option<String>,
+}
+
+impl RemoteTarget {
+    /// Parse an output device name. `None` if it isn't a remote target;
+    /// `Some(Err)` if it is but is malformed.
+    pub(crate) fn parse(device: &str) -> Option<Result<Self, AppError>> {
+        let (kind, rest) = if let Some(rest) = device.strip_prefix(HTTP_SCHEME) {
+            (RemoteKind::Http, rest)
+        } else {
+            (RemoteKind::Snapcast, device.strip_prefix(SNAPCAST_SCHEME)?)
+        };
+        Some(Self::parse_rest(kind, rest).map_err(|e| {
+            AppError::AudioDevice(format!("Invalid network output '{}': {}", device, e))
+        }))
+    }
+
+    fn parse_rest(kind: RemoteKind, rest: &str) -> Result<Self, String> {
+        let (host_port, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let host_port = host_port.trim_end_matches('/');
+        let (addr, rate, channels) = match kind {
+            RemoteKind::Http => {
+                // Only this machine, unless the user names an interface
+                let host_port = match host_port.strip_prefix(':') {
+                    Some(port) => format!("127.0.0.1:{}", port),
+                    None => host_port.to_string(),
+                };
+                host_port
+                    .parse::<SocketAddr>()
+                    .map_err(|_| "expected <ip>:<port> to listen on".to_string())?;
+                (host_port, 24_000, 1)
+            }
+            RemoteKind::Snapcast => {
+                if host_port.is_empty() {
+                    return Err("expected <host>[:<port>]".into());
+                }
+                let addr = if host_port.contains(':') {
+                    host_port.to_string()
+                } else {
+                    format!("{}:{}", host_port, SNAPCAST_PORT)
+                };
+                (addr, 48_000, 2)
+            }
+        };
+        let mut target = Self {
+            kind,
+            addr,
+            rate,
+            channels,
+            token: None,
+        };
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "rate" => target.rate = value.parse().map_err(|_| "rate must be a number")?,
+                "channels" => target.channels = value.parse().map_err(|_| "channels must be a number")?,
+                "token" if kind == RemoteKind::Http => {
+                    target.token = Some(value.to_string()).filter(|t| !t.is_empty())
+                }
+                other => return Err(format!("unknown option '{}'", other)),
+            }
+        }
+        if kind == RemoteKind::Http && target.token.is_none() {
+            return Err("add ?token=<secret>; players then open /stream?token=<secret>".into());
+        }
+        if !(8_000..=192_000).contains(&target.rate) || !(1..=2).contains(&target.channels) {
+            return Err("rate must be 8000-192000 and channels 1-2".into());
+        }
+        Ok(target)
+    }
+
+    /// Samples (all channels) per clock tick.
+    fn tick_samples(&self) -> usize {
+        (self.rate as u64 * TICK.as_millis() as u64 / 1000) as usize * self.channels as usize
+    }
+}
+
+/// Speech being played on a remote output.
+type Feed = UniformSourceIterator<SourcesQueueOutput<f32>, f32>;
+
+struct Attached {
+    id: u64,
+    feed: Feed,
+}
+
+/// A target's clock thread. Dropping it stops the thread.
+struct RemoteOutput {
+    target: RemoteTarget,
+    attached: Arc<Mutex<Option<Attached>>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for RemoteOutput {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+static OUTPUTS: Mutex<Vec<RemoteOutput>> = Mutex::new(Vec::new());
+static NEXT_ATTACHMENT: AtomicU64 = AtomicU64::new(1);
+
+/// Run exactly the remote outputs among `devices` (output device names),
+/// starting missing ones and stopping the rest. Called when the pipeline
+/// starts, so listeners can connect before anything is said, and with no
+/// devices when it stops.
+pub(crate) fn sync<'a>(devices: impl IntoIterator<Item = &'a str>) {
+    let wanted: Vec<RemoteTarget> = devices
+        .into_iter()
+        .filter_map(RemoteTarget::parse)
+        .filter_map(|parsed| parsed.map_err(|e| tracing::warn!("{}", e)).ok())
+        .collect();
+    let mut outputs = OUTPUTS.lock().unwrap_or_else(|e| e.into_inner());
+    // Dropping stops the clock threads
+    outputs.retain(|o| wanted.contains(&o.target));
+    for target in wanted {
+        if outputs.iter().any(|o| o.target == target) {
+            continue;
+        }
+        match start(target) {
+            Ok(output) => outputs.push(output),
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+}
+
+/// Keeps speech attached to a remote output; dropping it detaches, unless
+/// newer speech has already taken the output over.
+pub(crate) struct Attachment {
+    id: u64,
+    attached: Arc<Mutex<Option<Attached>>>,
+}
+
+impl Drop for Attachment {
+    fn drop(&mut self) {
+        let mut attached = self.attached.lock().unwrap_or_else(|e| e.into_inner());
+        if attached.as_ref().is_some_and(|a| a.id == self.id) {
+            *attached = None;
+        }
+    }
+}
+
+/// A sink that plays on `target`, starting the output if it isn't running
+/// (e.g. the pipeline hasn't synced it yet).
+pub(crate) fn attach(target: &RemoteTarget) -> Result<(Sink, Attachment), String> {
+    let attached = {
+        let mut outputs = OUTPUTS.lock().unwrap_or_else(|e| e.into_inner());
+        if !outputs.iter().any(|o| &o.target == target) {
+            outputs.push(start(target.clone()).map_err(|e| e.to_string())?);
+        }
+        let output = outputs.iter().find(|o| &o.target == target).expect("just started");
+        Arc::clone(&output.attached)
+    };
+    let (sink, queue) = Sink::new_idle();
+    let id = NEXT_ATTACHMENT.fetch_add(1, Ordering::Relaxed);
+    let feed = UniformSourceIterator::new(queue, target.channels, target.rate);
+    *attached.lock().unwrap_or_else(|e| e.into_inner()) = Some(Attached { id, feed });
+    Ok((sink, Attachment { id, attached }))
+}
+
+fn start(target: RemoteTarget) -> Result<RemoteOutput, AppError> {
+    let transport = match target.kind {
+        RemoteKind::Http => {
+            let listener = TcpListener::bind(&target.addr).map_err(|e| {
+                AppError::AudioDevice(format!("Failed to serve audio stream on {}: {}", target.addr, e))
+            })?;
+            listener
+                .set_nonblocking(true)
+                .map_err(|e| AppError::AudioDevice(format!("Failed to configure audio stream: {}", e)))?;
+            tracing::info!(addr = %target.addr, "Serving TTS audio stream over HTTP");
+            Transport::Http {
+                listener,
+                listeners: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+        RemoteKind::Snapcast => {
+            tracing::info!(server = %target.addr, "Sending TTS audio to Snapcast");
+            Transport::Snapcast {
+                stream: None,
+                retry_at: Instant::now(),
+            }
+        }
+    };
+
+    let attached = Arc::new(Mutex::new(None));
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_target = target.clone();
+    let thread_attached = Arc::clone(&attached);
+    let thread_stop = Arc::clone(&stop);
+    let thread = std::thread::Builder::new()
+        .name("voice-remote-output".into())
+        .spawn(move || clock_loop(thread_target, thread_attached, transport, thread_stop))
+        .map_err(|e| AppError::Internal(format!("Failed to spawn remote output thread: {}", e)))?;
+    Ok(RemoteOutput {
+        target,
+        attached,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+enum Transport {
+    Http {
+        listener: TcpListener,
+        /// Clients of `/stream`, after their WAV header went out.
+        listeners: Arc<Mutex<Vec<TcpStream>>>,
+    },
+    Snapcast {
+        stream: Option<TcpStream>,
+        retry_at: Instant,
+    },
+}
+
+/// Every tick, take a tick of audio from the attached speech (silence if
+/// none) and send it.
+fn clock_loop(
+    target: RemoteTarget,
+    attached: Arc<Mutex<Option<Attached>>>,
+    mut transport: Transport,
+    stop: Arc<AtomicBool>,
+) {
+    let mut block = vec![0.0f32; target.tick_samples()];
+    let mut pcm = Vec::with_capacity(block.len() * 2);
+    let mut next = Instant::now();
+    while !stop.load(Ordering::SeqCst) {
+        let speaking = fill(&attached, &mut block);
+        pcm.clear();
+        for s in &block {
+            pcm.extend_from_slice(&((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+        }
+        transport.send(&target, &pcm, speaking);
+
+        next += TICK;
+        let now = Instant::now();
+        match next.checked_duration_since(now) {
+            Some(wait) => std::thread::sleep(wait),
+            // Fell behind (suspend, slow listener): don't try to catch up
+            None if now - next > TICK * 10 => next = now,
+            None => {}
+        }
+    }
+    tracing::info!(addr = %target.addr, "Remote TTS output stopped");
+}
+
+/// Fill `block` from the attached speech, padding with silence. Returns
+/// whether speech was attached.
+fn fill(attached: &Mutex<Option<Attached>>, block: &mut [f32]) -> bool {
+    let mut attached = attached.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(current) = attached.as_mut() else {
+        block.fill(0.0);
+        return false;
+    };
+    for (i, slot) in block.iter_mut().enumerate() {
+        match current.feed.next() {
+            Some(s) => *slot = s,
+            None => {
+                // The sink was dropped and its queue has ended
+                block[i..].fill(0.0);
+                *attached = None;
+                break;
+            }
+        }
+    }
+    true
+}
+
+impl Transport {
+    fn send(&mut self, target: &RemoteTarget, pcm: &[u8], speaking: bool) {
+        match self {
+            Self::Http { listener, listeners } => {
+                while let Ok((stream, _)) = listener.accept() {
+                    let listeners = Arc::clone(listeners);
+                    let target = target.clone();
+                    std::thread::spawn(move || handle_request(stream, &target, &listeners));
+                }
+                let mut listeners = listeners.lock().unwrap_or_else(|e| e.into_inner());
+                if listeners.is_empty() {
+                    return;
+                }
+                let mut chunk = format!("{:x}\r\n", pcm.len()).into_bytes();
+                chunk.extend_from_slice(pcm);
+                chunk.extend_from_slice(b"\r\n");
+                listeners.retain_mut(|client| client.write_all(&chunk).is_ok());
+            }
+            Self::Snapcast { stream, retry_at } => {
+                if !speaking {
+                    return;
+                }
+                if stream.is_none() && Instant::now() >= *retry_at {
+                    match connect(&target.addr) {
+                        Ok(s) => {
+                            tracing::info!(server = %target.addr, "Connected to Snapcast");
+                            *stream = Some(s);
+                        }
+                        Err(e) => {
+                            tracing::warn!(server = %target.addr, "Snapcast unreachable: {}", e);
+                            *retry_at = Instant::now() + RECONNECT_DELAY;
+                        }
+                    }
+                }
+                if let Some(s) = stream {
+                    if let Err(e) = s.write_all(pcm) {
+                        tracing::warn!(server = %target.addr, "Snapcast connection lost: {}", e);
+                        *stream = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn connect(addr: &str) -> std::io::Result<TcpStream> {
+    let resolved = addr.to_socket_addrs()?.next().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "no address")
+    })?;
+    let stream = TcpStream::connect_timeout(&resolved, Duration::from_secs(1))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    stream.set_nodelay(true)?;
+    Ok(stream)
+}
+
+/// Answer one HTTP request: `/stream` joins the listeners, anything else
+/// gets the player page. Both need the target's token.
+fn handle_request(mut stream: TcpStream, target: &RemoteTarget, listeners: &Mutex<Vec<TcpStream>>) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+    let Ok(reader_stream) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(reader_stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // Drain the remaining headers
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let request_target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let (path, query) = request_target.split_once('?').unwrap_or((request_target, ""));
+    let token = target.token.as_deref().unwrap_or_default();
+    if !is_authorized(query, token) {
+        tracing::warn!(peer = ?stream.peer_addr().ok(), "TTS stream request without a valid token");
+        let _ = stream.write_all(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        return;
+    }
+    if !path.starts_with("/stream") {
+        let query: String = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("token", token)
+            .finish();
+        let html = format!(
+            r#"<!doctype html>
+<html>
+<head><meta name="viewport" content="width=device-width, initial-scale=1"><title>Voice Mirror</title></head>
+<body style="margin:0;display:flex;height:100vh;align-items:center;justify-content:center;background:#111">
+  <audio src="/stream?{}" controls autoplay></audio>
+</body>
+</html>"#,
+            query
+        );
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            html.len(),
+            html
+        );
+        let _ = stream.write_all(response.as_bytes());
+        return;
+    }
+
+    let mut listeners = listeners.lock().unwrap_or_else(|e| e.into_inner());
+    if listeners.len() >= MAX_LISTENERS {
+        let _ = stream.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        return;
+    }
+    let header = "HTTP/1.1 200 OK\r\nContent-Type: audio/wav\r\nCache-Control: no-cache\r\nTransfer-Encoding: chunked\r\nConnection: keep-alive\r\n\r\n";
+    let wav_header = stream_wav_header(target.rate, target.channels);
+    let mut first = format!("{:x}\r\n", wav_header.len()).into_bytes();
+    first.extend_from_slice(&wav_header);
+    first.extend_from_slice(b"\r\n");
+    if stream.write_all(header.as_bytes()).is_ok() && stream.write_all(&first).is_ok() {
+        tracing::info!(peer = ?stream.peer_addr().ok(), "TTS stream listener connected");
+        listeners.push(stream);
+    }
+}
+
+/// Whether the request's `token` query parameter matches `token`.
+fn is_authorized(query: &str, token: &str) -> bool {
+    use crate::services::control_api::constant_time_eq;
+    !token.is_empty()
+        && url::form_urlencoded::parse(query.as_bytes())
+            .any(|(k, v)| k == "token" && constant_time_eq(v.as_bytes(), token.as_bytes()))
+}
+
+/// 16-bit PCM WAV header for a stream of unknown length.
+fn stream_wav_header(rate: u32, channels: u16) -> Vec<u8> {
+    let block_align = channels * 2;
+    let mut out = Vec::with_capacity(44);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&u32::MAX.to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&rate.to_le_bytes());
+    out.extend_from_slice(&(rate * block_align as u32).to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&u32::MAX.to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_targets() {
+        assert!(RemoteTarget::parse("Speakers (Realtek)").is_none());
+        assert_eq!(
+            RemoteTarget::parse("http://:8765?token=s3cret").unwrap().unwrap(),
+            RemoteTarget {
+                kind: RemoteKind::Http,
+                addr: "127.0.0.1:8765".into(),
+                rate: 24_000,
+                channels: 1,
+                token: Some("s3cret".into()),
+            }
+        );
+        let lan = RemoteTarget::parse("http://0.0.0.0:8765/?token=s3cret").unwrap().unwrap();
+        assert_eq!(lan.addr, "0.0.0.0:8765");
+        assert_eq!(
+            RemoteTarget::parse("snapcast://nas.local").unwrap().unwrap(),
+            RemoteTarget {
+                kind: RemoteKind::Snapcast,
+                addr: "nas.local:4953".into(),
+                rate: 48_000,
+                channels: 2,
+                token: None,
+            }
+        );
+        let custom = RemoteTarget::parse("snapcast://10.0.0.2:4000?rate=44100&channels=1").unwrap().unwrap();
+        assert_eq!((custom.addr.as_str(), custom.rate, custom.channels), ("10.0.0.2:4000", 44_100, 1));
+        assert!(RemoteTarget::parse("http://phone:8765?token=s3cret").unwrap().is_err());
+        // The stream always needs a token
+        assert!(RemoteTarget::parse("http://:8765").unwrap().is_err());
+        assert!(RemoteTarget::parse("http://:8765?token=").unwrap().is_err());
+        assert!(RemoteTarget::parse("snapcast://nas?token=s3cret").unwrap().is_err());
+        assert!(RemoteTarget::parse("snapcast://nas?channels=6").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_requests_need_the_token() {
+        assert!(is_authorized("token=s3cret", "s3cret"));
+        assert!(is_authorized("x=1&token=s3cret", "s3cret"));
+        assert!(!is_authorized("token=nope", "s3cret"));
+        assert!(!is_authorized("", "s3cret"));
+        assert!(!is_authorized("token=", ""));
+    }
+
+    #[test]
+    fn test_tick_samples() {
+        let target = RemoteTarget::parse("snapcast://nas").unwrap().unwrap();
+        assert_eq!(target.tick_samples(), 960 * 2);
+    }
+
+    #[test]
+    fn test_stream_wav_header() {
+        let header = stream_wav_header(48_000, 2);
+        assert_eq!(header.len(), 44);
+        assert_eq!(&header[22..24], &2u16.to_le_bytes());
+        assert_eq!(&header[28..32], &192_000u32.to_le_bytes());
+        assert_eq!(&header[40..44], &u32::MAX.to_le_bytes());
+    }
+
+    #[test]
+    fn test_fill_drains_sink_then_pads_silence() {
+        let (sink, queue) = Sink::new_idle();
+        sink.append(rodio::buffer::SamplesBuffer::new(1, 24_000, vec![0.5f32; 480]));
+        let attached = Mutex::new(Some(Attached {
+            id: 1,
+            feed: UniformSourceIterator::new(queue, 1, 24_000),
+        }));
+        let mut block = vec![0.0f32; 480];
+        assert!(fill(&attached, &mut block));
+        assert!(block.iter().all(|s| (s - 0.5).abs() < 1e-3));
+        assert!(fill(&attached, &mut block));
+        assert!(block.iter().all(|s| s.abs() < 1e-3), "idle sink plays silence");
+
+        *attached.lock().unwrap() = None;
+        assert!(!fill(&attached, &mut block));
+    }
+}
@@ -1,86 +1,181 @@
-//! Lock-based ring buffer for audio samples.
+//! Lock-free single-producer/single-consumer ring buffer for audio samples.
 //!
-//! Uses a simple Vec-based circular buffer with mutex protection.
-//! Not lock-free like the voice-core ringbuf implementation, but
-//! sufficient for the Tauri integration where we have more flexibility
-//! in thread scheduling.
+//! The producer is the cpal capture callback, a real-time thread that must
+//! never block. It writes samples and publishes them with one atomic store.
+//! It doesn't wait for the consumer and never fails. When the consumer falls
+//! behind, new samples overwrite the oldest unread ones (drop-oldest).
+//!
+//! The consumer (the processing loop) notices an overrun from the write
+//! counter. It skips the overwritten samples and adds them to an overflow
+//! counter that diagnostics can read. A sample overwritten while the
+//! consumer was copying it is caught seqlock-style: the producer announces
+//! how far it is about to write (`reserved`) before touching any slot. After
+//! copying, the consumer throws away anything below `reserved - capacity`.
+//!
+//! Samples are stored as `f32` bits in `AtomicU32` slots, so a racing
+//! overwrite is never a data race, only a value the consumer discards.
+
+use std::sync::atomic::{fence, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
 
-use std::sync::{Arc, Mutex};
+use crate::services::metrics::{self, Counter};
 
-/// Simple ring buffer producer (wraps a Vec with write position).
+/// Producer half. Owned by the capture callback.
 pub(crate) struct RingProducer {
-    pub(crate) buffer: Arc<Mutex<RingBuffer>>,
+    buffer: Arc<RingBuffer>,
 }
 
-/// Simple ring buffer consumer (reads from shared buffer).
+/// Consumer half. Only the processing loop may read from it.
 pub(crate) struct RingConsumer {
-    pub(crate) buffer: Arc<Mutex<RingBuffer>>,
+    buffer: Arc<RingBuffer>,
 }
 
-/// Lock-based ring buffer for audio samples.
+/// Lock-free ring buffer for audio samples.
 pub(crate) struct RingBuffer {
-    data: Vec<f32>,
-    write_pos: usize,
-    read_pos: usize,
-    count: usize,
-    capacity: usize,
+    slots: Box<[AtomicU32]>,
+    capacity: u64,
+    /// Total samples ever published. Written only by the producer.
+    head: AtomicU64,
+    /// Where the producer is about to write up to. Stored before the slots
+    /// are touched, so the consumer can tell which reads may be torn.
+    reserved: AtomicU64,
+    /// Total samples read or skipped. Written only by the consumer.
+    tail: AtomicU64,
+    /// Samples overwritten before the consumer got to them.
+    overflowed: AtomicU64,
 }
 
 impl RingBuffer {
-    pub(crate) fn new(capacity: usize) -> Self {
+    fn new(capacity: usize) -> Self {
         Self {
-            data: vec![0.0; capacity],
-            write_pos: 0,
-            read_pos: 0,
-            count: 0,
-            capacity,
+            slots: (0..capacity.max(1)).map(|_| AtomicU32::new(0)).collect(),
+            capacity: capacity.max(1) as u64,
+            head: AtomicU64::new(0),
+            reserved: AtomicU64::new(0),
+            tail: AtomicU64::new(0),
+            overflowed: AtomicU64::new(0),
         }
     }
 
-    pub(crate) fn push_slice(&mut self, samples: &[f32]) -> usize {
-        let mut written = 0;
-        for &sample in samples {
-            if self.count >= self.capacity {
-                // Overwrite oldest data
-                self.read_pos = (self.read_pos + 1) % self.capacity;
-                self.count -= 1;
-            }
-            self.data[self.write_pos] = sample;
-            self.write_pos = (self.write_pos + 1) % self.capacity;
-            self.count += 1;
-            written += 1;
+    fn slot(&self, index: u64) -> &AtomicU32 {
+        &self.slots[(index % self.capacity) as usize]
+    }
+
+    /// Tail moved past anything the producer has overwritten, given a write
+    /// position `written` (head or reserved). Counts the skipped samples.
+    fn skip_overwritten(&self, tail: u64, written: u64) -> u64 {
+        let oldest = written.saturating_sub(self.capacity);
+        if tail < oldest {
+            self.overflowed.fetch_add(oldest - tail, Ordering::Relaxed);
+            metrics::add(Counter::RingOverflowSamples, oldest - tail);
+            oldest
+        } else {
+            tail
+        }
+    }
+}
+
+impl RingProducer {
+    /// Write samples, overwriting the oldest unread ones if the buffer is
+    /// full. Never blocks.
+    pub(crate) fn push_slice(&self, samples: &[f32]) {
+        let rb = &*self.buffer;
+        let head = rb.head.load(Ordering::Relaxed);
+        let end = head + samples.len() as u64;
+        rb.reserved.store(end, Ordering::Relaxed);
+        fence(Ordering::Release);
+        // Only the newest `capacity` samples can survive anyway
+        let skip = samples.len().saturating_sub(rb.slots.len());
+        for (i, &sample) in samples.iter().enumerate().skip(skip) {
+            rb.slot(head + i as u64)
+                .store(sample.to_bits(), Ordering::Relaxed);
         }
-        written
+        rb.head.store(end, Ordering::Release);
     }
+}
+
+impl RingConsumer {
+    /// Read up to `buf.len()` of the oldest samples. Returns how many were
+    /// read.
+    pub(crate) fn pop_slice(&self, buf: &mut [f32]) -> usize {
+        let rb = &*self.buffer;
+        let head = rb.head.load(Ordering::Acquire);
+        let tail = rb.skip_overwritten(rb.tail.load(Ordering::Relaxed), head);
+        let n = buf.len().min(head.saturating_sub(tail) as usize);
+        for (i, out) in buf.iter_mut().take(n).enumerate() {
+            *out = f32::from_bits(rb.slot(tail + i as u64).load(Ordering::Relaxed));
+        }
 
-    pub(crate) fn pop_slice(&mut self, buf: &mut [f32]) -> usize {
-        let to_read = buf.len().min(self.count);
-        for item in buf.iter_mut().take(to_read) {
-            *item = self.data[self.read_pos];
-            self.read_pos = (self.read_pos + 1) % self.capacity;
-            self.count -= 1;
+        // Drop whatever the producer may have overwritten during the copy
+        fence(Ordering::Acquire);
+        let reserved = rb.reserved.load(Ordering::Relaxed);
+        let valid_from = rb.skip_overwritten(tail, reserved);
+        let torn = ((valid_from - tail) as usize).min(n);
+        if torn > 0 {
+            buf.copy_within(torn..n, 0);
         }
-        to_read
+        let read = n - torn;
+        rb.tail
+            .store(valid_from.max(tail + n as u64), Ordering::Relaxed);
+        read
     }
 
-    #[allow(dead_code)]
+    /// Samples waiting to be read.
     pub(crate) fn available(&self) -> usize {
-        self.count
+        let rb = &*self.buffer;
+        let head = rb.head.load(Ordering::Acquire);
+        let tail = rb.tail.load(Ordering::Relaxed);
+        head.saturating_sub(tail).min(rb.capacity) as usize
+    }
+
+    /// Drop up to `n` of the oldest samples without reading them.
+    pub(crate) fn discard(&self, n: usize) -> usize {
+        let rb = &*self.buffer;
+        let head = rb.head.load(Ordering::Acquire);
+        let tail = rb.skip_overwritten(rb.tail.load(Ordering::Relaxed), head);
+        let n = n.min(head.saturating_sub(tail) as usize);
+        rb.tail.store(tail + n as u64, Ordering::Relaxed);
+        n
     }
 
-    pub(crate) fn drain_all(&mut self) -> Vec<f32> {
-        let n = self.count;
+    pub(crate) fn drain_all(&self) -> Vec<f32> {
+        let n = self.available();
         if n == 0 {
             return Vec::new();
         }
         let mut buf = vec![0.0f32; n];
-        self.pop_slice(&mut buf);
+        let read = self.pop_slice(&mut buf);
+        buf.truncate(read);
         buf
     }
+
+    /// Samples lost to overruns since the buffer was created.
+    pub(crate) fn overflowed(&self) -> u64 {
+        self.buffer.overflowed.load(Ordering::Relaxed)
+    }
+
+    /// Samples read or skipped since the buffer was created. Only grows
+    /// while the consumer keeps reading (see `watchdog`).
+    pub(crate) fn consumed(&self) -> u64 {
+        self.buffer.tail.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.buffer.slots.len()
+    }
+
+    /// A new producer for this buffer, for a capture stream reopened after
+    /// the previous one (and its producer) was dropped. Never call it while
+    /// another producer is alive: the buffer has a single writer.
+    pub(crate) fn reattach_producer(&self) -> RingProducer {
+        RingProducer {
+            buffer: Arc::clone(&self.buffer),
+        }
+    }
 }
 
 pub(crate) fn create_ring_buffer(capacity: usize) -> (RingProducer, RingConsumer) {
-    let buffer = Arc::new(Mutex::new(RingBuffer::new(capacity)));
+    let buffer = Arc::new(RingBuffer::new(capacity));
     (
         RingProducer {
             buffer: Arc::clone(&buffer),
@@ -95,44 +190,100 @@ mod tests {
 
     #[test]
     fn test_ring_buffer_basic() {
-        let mut rb = RingBuffer::new(10);
-        assert_eq!(rb.available(), 0);
+        let (tx, rx) = create_ring_buffer(10);
+        assert_eq!(rx.available(), 0);
 
-        rb.push_slice(&[1.0, 2.0, 3.0]);
-        assert_eq!(rb.available(), 3);
+        tx.push_slice(&[1.0, 2.0, 3.0]);
+        assert_eq!(rx.available(), 3);
 
         let mut buf = [0.0f32; 2];
-        let read = rb.pop_slice(&mut buf);
+        let read = rx.pop_slice(&mut buf);
         assert_eq!(read, 2);
         assert_eq!(buf, [1.0, 2.0]);
-        assert_eq!(rb.available(), 1);
+        assert_eq!(rx.available(), 1);
+    }
+
+    #[test]
+    fn test_ring_buffer_reattached_producer_continues() {
+        let (tx, rx) = create_ring_buffer(10);
+        tx.push_slice(&[1.0, 2.0]);
+        drop(tx);
+        let tx = rx.reattach_producer();
+        tx.push_slice(&[3.0]);
+
+        let mut buf = [0.0f32; 4];
+        assert_eq!(rx.pop_slice(&mut buf), 3);
+        assert_eq!(buf[..3], [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_ring_buffer_discard() {
+        let (tx, rx) = create_ring_buffer(4);
+        tx.push_slice(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(rx.discard(2), 2);
+        assert_eq!(rx.drain_all(), vec![4.0, 5.0]);
+        assert_eq!(rx.discard(10), 0);
     }
 
     #[test]
     fn test_ring_buffer_overflow() {
-        let mut rb = RingBuffer::new(4);
+        let (tx, rx) = create_ring_buffer(4);
         // Write 6 samples into a buffer of size 4
-        rb.push_slice(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
-        assert_eq!(rb.available(), 4);
+        tx.push_slice(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(rx.available(), 4);
 
         // Should have the last 4 samples (overflow drops oldest)
-        let all = rb.drain_all();
+        let all = rx.drain_all();
         assert_eq!(all, vec![3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(rx.overflowed(), 2);
+
+        // Wrapping around several times keeps counting
+        for i in 0..3 {
+            tx.push_slice(&[i as f32; 3]);
+        }
+        assert_eq!(rx.drain_all(), vec![1.0, 2.0, 2.0, 2.0]);
+        assert_eq!(rx.overflowed(), 7);
     }
 
     #[test]
     fn test_ring_buffer_drain_all() {
-        let mut rb = RingBuffer::new(100);
-        rb.push_slice(&[1.0, 2.0, 3.0, 4.0]);
-        let all = rb.drain_all();
+        let (tx, rx) = create_ring_buffer(100);
+        tx.push_slice(&[1.0, 2.0, 3.0, 4.0]);
+        let all = rx.drain_all();
         assert_eq!(all, vec![1.0, 2.0, 3.0, 4.0]);
-        assert_eq!(rb.available(), 0);
+        assert_eq!(rx.available(), 0);
+        assert!(rx.drain_all().is_empty());
     }
 
     #[test]
-    fn test_ring_buffer_empty_drain() {
-        let mut rb = RingBuffer::new(100);
-        let all = rb.drain_all();
-        assert!(all.is_empty());
+    fn test_ring_buffer_concurrent_reads_stay_in_order() {
+        const TOTAL: usize = 200_000;
+        let (tx, rx) = create_ring_buffer(256);
+        let producer = std::thread::spawn(move || {
+            for chunk in (0..TOTAL).collect::<Vec<_>>().chunks(37) {
+                let samples: Vec<f32> = chunk.iter().map(|&i| i as f32).collect();
+                tx.push_slice(&samples);
+            }
+        });
+
+        // Whatever survives must be strictly increasing, and everything
+        // missing must be accounted for as overflow
+        let mut last = -1.0f32;
+        let mut received = 0u64;
+        let mut buf = [0.0f32; 64];
+        loop {
+            let n = rx.pop_slice(&mut buf);
+            for &s in &buf[..n] {
+                assert!(s > last, "{} after {}", s, last);
+                last = s;
+            }
+            received += n as u64;
+            if n == 0 && producer.is_finished() && rx.available() == 0 {
+                break;
+            }
+        }
+        producer.join().unwrap();
+        assert_eq!(last, (TOTAL - 1) as f32);
+        assert_eq!(received + rx.overflowed(), TOTAL as u64);
     }
 }
@@ -0,0 +1,332 @@
+//! Voice self-test: check the setup without starting the pipeline.
+//!
+//! `voice_self_test` runs four checks against the saved voice settings and
+//! returns a report the first-run wizard can show:
+//!
+//! - `inputDevice`: the configured (or default) microphone opens and
+//!   delivers audio. With `captureSource: "system"`, the loopback device
+//!   opens instead.
+//! - `outputDevice`: the configured (or default) output device opens.
+//!   Nothing is played.
+//! - `tts`: the TTS engine loads and synthesizes `TEST_PHRASE`.
+//! - `stt`: the STT engine loads and transcribes the TTS audio. No recording
+//!   is bundled; the synthesized phrase is the test clip, so a pass also
+//!   means the two engines understand each other. Without TTS audio the
+//!   model only runs once on silence.
+//!
+//! Like speech export, the checks build their own engines and streams, so
+//! they work whether or not the pipeline is running and never touch it.
+//!
+//! [`play_clip`] plays a recording back on an output device, for the
+//! onboarding record-and-playback test (see `services::onboarding`).
+
+use std::time::{Duration, Instant};
+
+use cpal::traits::DeviceTrait;
+use serde::Serialize;
+
+use super::network_input::RTP_SCHEME;
+use super::playback::{open_output_stream, SYNTH_TIMEOUT};
+use super::remote_output::RemoteTarget;
+use super::{capture_device, recovery, CAPTURE_SYSTEM, TARGET_SAMPLE_RATE};
+use crate::voice::calibration;
+use crate::voice::resample::{self, ResamplerKind};
+use crate::voice::VoiceEngineConfig;
+
+/// Spoken by the TTS check and expected back from the STT check. No
+/// numbers or names, which engines are free to spell their own way.
+pub const TEST_PHRASE: &str = "The quick brown fox jumps over the lazy dog.";
+/// Microphone capture length.
+const INPUT_SECS: f64 = 0.5;
+/// Share of the phrase's words the transcription must contain.
+const MIN_WORD_MATCH: f32 = 0.6;
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// Not applicable to this setup (e.g. a network audio source).
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestCheck {
+    /// `inputDevice`, `outputDevice`, `tts` or `stt`.
+    pub name: &'static str,
+    pub status: CheckStatus,
+    /// What was checked, or what went wrong.
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    /// No check failed.
+    pub ok: bool,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    fn new(checks: Vec<SelfTestCheck>) -> Self {
+        Self {
+            ok: checks.iter().all(|c| c.status != CheckStatus::Fail),
+            checks,
+        }
+    }
+}
+
+fn check(
+    name: &'static str,
+    started: Instant,
+    (status, detail): (CheckStatus, String),
+) -> SelfTestCheck {
+    if status == CheckStatus::Fail {
+        tracing::warn!(check = name, "Voice self-test failed: {}", detail);
+    }
+    SelfTestCheck {
+        name,
+        status,
+        detail,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Run every check, in order. Takes a few seconds (model loads).
+pub async fn run(config: VoiceEngineConfig) -> SelfTestReport {
+    let mut checks = Vec::with_capacity(4);
+
+    let started = Instant::now();
+    let cfg = config.clone();
+    let result = tokio::task::spawn_blocking(move || check_input(&cfg))
+        .await
+        .unwrap_or_else(|e| (CheckStatus::Fail, format!("Input check failed: {}", e)));
+    checks.push(check("inputDevice", started, result));
+
+    let started = Instant::now();
+    let cfg = config.clone();
+    let result = tokio::task::spawn_blocking(move || check_output(&cfg))
+        .await
+        .unwrap_or_else(|e| (CheckStatus::Fail, format!("Output check failed: {}", e)));
+    checks.push(check("outputDevice", started, result));
+
+    let started = Instant::now();
+    let (result, clip) = check_tts(&config).await;
+    checks.push(check("tts", started, result));
+
+    let started = Instant::now();
+    let result = tokio::task::spawn_blocking(move || check_stt(&config, clip))
+        .await
+        .unwrap_or_else(|e| (CheckStatus::Fail, format!("STT check failed: {}", e)));
+    checks.push(check("stt", started, result));
+
+    SelfTestReport::new(checks)
+}
+
+fn check_input(config: &VoiceEngineConfig) -> (CheckStatus, String) {
+    if config.capture_source == CAPTURE_SYSTEM {
+        return match capture_device(config) {
+            Ok((device, _)) => (
+                CheckStatus::Pass,
+                format!(
+                    "System audio from {}",
+                    device.name().unwrap_or_else(|_| "the default output".into())
+                ),
+            ),
+            Err(e) => (CheckStatus::Fail, e.to_string()),
+        };
+    }
+    let device = config.input_device.as_deref();
+    if device.is_some_and(|d| d.starts_with(RTP_SCHEME)) {
+        return (CheckStatus::Skipped, "Network audio source, not a local microphone".into());
+    }
+    let name = device.unwrap_or("System default");
+    let resampler = ResamplerKind::from_config(&config.resampler);
+    match calibration::record(device, INPUT_SECS, resampler) {
+        Ok(samples) if samples.is_empty() => (
+            CheckStatus::Fail,
+            format!("{} opened but delivered no audio", name),
+        ),
+        Ok(samples) => {
+            let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            (
+                CheckStatus::Pass,
+                format!("{} (peak level {:.3})", name, peak),
+            )
+        }
+        Err(e) => (CheckStatus::Fail, e.to_string()),
+    }
+}
+
+fn check_output(config: &VoiceEngineConfig) -> (CheckStatus, String) {
+    let device = config.output_device.as_deref();
+    if device.and_then(RemoteTarget::parse).is_some() {
+        return (CheckStatus::Skipped, "Network target, not a local device".into());
+    }
+    match open_output_stream(device) {
+        Ok(_stream) => (
+            CheckStatus::Pass,
+            device.unwrap_or("System default").to_string(),
+        ),
+        Err(e) => (CheckStatus::Fail, e),
+    }
+}
+
+/// Play mono `samples` at `sample_rate` on `device` (system default for
+/// `None`) and block until they finish. Network targets are refused: the
+/// point is to hear the clip on this machine.
+pub fn play_clip(device: Option<&str>, samples: Vec<f32>, sample_rate: u32) -> Result<(), String> {
+    if device.and_then(RemoteTarget::parse).is_some() {
+        return Err("The output is a network target, not a local device".into());
+    }
+    let secs = samples.len() as f64 / sample_rate.max(1) as f64;
+    let (_stream, handle) = open_output_stream(device)?;
+    let sink = rodio::Sink::try_new(&handle)
+        .map_err(|e| format!("Failed to create audio sink: {}", e))?;
+    sink.append(rodio::buffer::SamplesBuffer::new(1, sample_rate, samples));
+    // Same guard as TTS playback: a stalled device must not hang the caller
+    let deadline = Instant::now() + Duration::from_secs_f64(secs + 2.0);
+    while !sink.empty() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Ok(())
+}
+
+/// The TTS result plus the synthesized audio and its rate, for the STT check.
+async fn check_tts(
+    config: &VoiceEngineConfig,
+) -> ((CheckStatus, String), Option<(Vec<f32>, u32)>) {
+    let cfg = config.clone();
+    let loaded = tokio::task::spawn_blocking(move || recovery::tts_engine_from_config(&cfg)).await;
+    let engine = match loaded {
+        Ok(Ok(engine)) => engine,
+        Ok(Err(e)) => {
+            let detail = format!("Failed to load {}: {}", config.tts_adapter, e);
+            return ((CheckStatus::Fail, detail), None);
+        }
+        Err(e) => {
+            let detail = format!("TTS engine creation panicked: {}", e);
+            return ((CheckStatus::Fail, detail), None);
+        }
+    };
+    let sample_rate = engine.sample_rate();
+    match tokio::time::timeout(SYNTH_TIMEOUT, engine.synthesize(TEST_PHRASE)).await {
+        Ok(Ok(samples)) if !samples.is_empty() => {
+            let secs = samples.len() as f64 / sample_rate.max(1) as f64;
+            let detail = format!(
+                "{}: {:.1} s of audio at {} Hz",
+                config.tts_adapter, secs, sample_rate
+            );
+            ((CheckStatus::Pass, detail), Some((samples, sample_rate)))
+        }
+        Ok(Ok(_)) => {
+            let detail = format!("{} produced no audio", config.tts_adapter);
+            ((CheckStatus::Fail, detail), None)
+        }
+        Ok(Err(e)) => ((CheckStatus::Fail, e.to_string()), None),
+        Err(_) => ((CheckStatus::Fail, "Synthesis timed out".into()), None),
+    }
+}
+
+fn check_stt(config: &VoiceEngineConfig, clip: Option<(Vec<f32>, u32)>) -> (CheckStatus, String) {
+    let engine = match recovery::stt_engine_from_config(config) {
+        Ok(engine) => engine,
+        Err(e) => {
+            return (
+                CheckStatus::Fail,
+                format!("Failed to load {}: {}", config.stt_adapter, e),
+            )
+        }
+    };
+    let Some((samples, rate)) = clip else {
+        let silence = vec![0.0f32; TARGET_SAMPLE_RATE as usize];
+        return match engine.transcribe(&silence) {
+            Ok(_) => (
+                CheckStatus::Pass,
+                format!("{} loaded (no TTS audio to transcribe)", config.stt_adapter),
+            ),
+            Err(e) => (CheckStatus::Fail, e.to_string()),
+        };
+    };
+    let audio = resample::resample(
+        ResamplerKind::from_config(&config.resampler),
+        &samples,
+        rate,
+        TARGET_SAMPLE_RATE,
+    );
+    match engine.transcribe(&audio) {
+        Ok(text) => {
+            let heard = text.trim();
+            if word_match(TEST_PHRASE, heard) >= MIN_WORD_MATCH {
+                (CheckStatus::Pass, format!("Heard \"{}\"", heard))
+            } else {
+                (
+                    CheckStatus::Fail,
+                    format!("Heard \"{}\" instead of \"{}\"", heard, TEST_PHRASE),
+                )
+            }
+        }
+        Err(e) => (CheckStatus::Fail, e.to_string()),
+    }
+}
+
+/// Lowercase words without punctuation.
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Share of `expected`'s words that appear in `heard` (0.0 - 1.0).
+fn word_match(expected: &str, heard: &str) -> f32 {
+    let expected = words(expected);
+    let heard = words(heard);
+    if expected.is_empty() {
+        return 0.0;
+    }
+    let found = expected.iter().filter(|w| heard.contains(w)).count();
+    found as f32 / expected.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_match() {
+        assert_eq!(word_match(TEST_PHRASE, " the quick brown fox jumps over the lazy dog"), 1.0);
+        let close = word_match(TEST_PHRASE, "The quick brown fox jumped over a lazy log.");
+        assert!(close >= MIN_WORD_MATCH);
+        assert!(word_match(TEST_PHRASE, "Thank you.") < MIN_WORD_MATCH);
+        assert_eq!(word_match(TEST_PHRASE, ""), 0.0);
+    }
+
+    #[test]
+    fn test_report_ok_ignores_skipped() {
+        let result = |name, status| SelfTestCheck {
+            name,
+            status,
+            detail: String::new(),
+            duration_ms: 0,
+        };
+        let report = SelfTestReport::new(vec![
+            result("inputDevice", CheckStatus::Skipped),
+            result("tts", CheckStatus::Pass),
+        ]);
+        assert!(report.ok);
+        let report = SelfTestReport::new(vec![
+            result("tts", CheckStatus::Pass),
+            result("stt", CheckStatus::Fail),
+        ]);
+        assert!(!report.ok);
+    }
+}
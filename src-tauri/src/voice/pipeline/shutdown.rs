@@ -0,0 +1,79 @@
+//! Cooperative pipeline shutdown (`VoicePipeline::stop`).
+//!
+//! Stopping used to abort the processing task, which could cut a
+//! transcription off mid-STT or leave a rodio Sink playing into a dropped
+//! stream. Now `stop()` clears `running` and wakes the loop, cancels TTS, and
+//! waits for the work in flight to wind down before anything is dropped:
+//!
+//! 1. speech playback: `speak()` returns once its Sink drain loop sees the
+//!    cancel flag and stops the Sink (`SINK_TIMEOUT`)
+//! 2. the processing loop: a transcription in flight is finished and
+//!    emitted, then the loop sees `running` cleared and exits
+//!    (`DRAIN_TIMEOUT`)
+//! 3. the capture streams are dropped last, so the mic stays open until
+//!    nothing reads from it
+//!
+//! A loop that doesn't exit in time is aborted as before. A stalled
+//! pipeline (see `watchdog`) is aborted without waiting.
+
+use std::time::{Duration, Instant};
+
+/// How long `stop()` waits for the processing loop, including a
+/// transcription in flight. Longer than a healthy STT run.
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long `stop()` waits for cancelled speech to stop its Sink.
+pub const SINK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often the waits look.
+const POLL: Duration = Duration::from_millis(10);
+
+/// Block until `done` returns true or `timeout` passes. Returns whether it
+/// got there in time.
+///
+/// Blocks the calling thread: `stop()` is synchronous and runs under the
+/// engine lock, and the tasks it waits for run on other runtime threads.
+pub(super) fn wait_until(timeout: Duration, mut done: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if done() {
+            return true;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        std::thread::sleep(POLL.min(deadline - now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_wait_until_done_immediately() {
+        assert!(wait_until(Duration::ZERO, || true));
+    }
+
+    #[test]
+    fn test_wait_until_times_out() {
+        let start = Instant::now();
+        assert!(!wait_until(Duration::from_millis(30), || false));
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn test_wait_until_sees_other_thread() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let setter = Arc::clone(&flag);
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            setter.store(true, Ordering::SeqCst);
+        });
+        assert!(wait_until(Duration::from_secs(5), || flag.load(Ordering::SeqCst)));
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,206 @@
+//! Speech queue: how a new `speak()` request treats speech in progress.
+//!
+//! Replies interrupt (the default, and the old behaviour). Status messages
+//! and notifications can instead wait their turn ([`SpeechPriority::Queue`])
+//! or be skipped when something is already being said
+//! ([`SpeechPriority::DropIfBusy`]), so they don't clobber a long answer.
+//!
+//! Whoever is speaking owns the queue and, when its utterance ends, speaks
+//! the queued texts in order. An interrupting request takes ownership, so
+//! the speech it cut off stops without draining and the new owner drains
+//! the queue after itself. Stop / barge-in leave the owner without
+//! draining; an explicit stop also clears the queue.
+//!
+//! A queued text keeps the voice it was asked for (an agent's own voice,
+//! see `services::instances`), so it isn't spoken in whoever's voice is
+//! current when its turn comes.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// Most texts waiting at once; further `Queue` requests are dropped.
+const MAX_PENDING: usize = 16;
+
+/// How a speech request treats speech already in progress.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeechPriority {
+    /// Cut off current speech and speak now.
+    #[default]
+    Interrupt,
+    /// Speak once current and already-queued speech has finished.
+    Queue,
+    /// Speak only if nothing is being said; otherwise discard.
+    DropIfBusy,
+}
+
+impl SpeechPriority {
+    /// Parse a priority name (`"interrupt"`, `"queue"`, `"drop_if_busy"`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "interrupt" => Some(Self::Interrupt),
+            "queue" => Some(Self::Queue),
+            "drop_if_busy" => Some(Self::DropIfBusy),
+            _ => None,
+        }
+    }
+}
+
+/// A text waiting to be spoken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Utterance {
+    pub(crate) text: String,
+    /// TTS voice for this text; the selected one when `None`.
+    pub(crate) voice: Option<String>,
+}
+
+/// What to do with a submitted request.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Submitted {
+    /// Speak it now, holding this ticket.
+    Speak(u64),
+    Queued,
+    Dropped,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SpeechQueue {
+    pending: VecDeque<Utterance>,
+    /// Ticket of the request currently speaking (and draining).
+    owner: Option<u64>,
+    next_ticket: u64,
+}
+
+impl SpeechQueue {
+    pub(crate) fn submit(
+        &mut self,
+        text: &str,
+        voice: Option<&str>,
+        priority: SpeechPriority,
+    ) -> Submitted {
+        let busy = self.owner.is_some();
+        match priority {
+            SpeechPriority::Queue if busy => {
+                if self.pending.len() >= MAX_PENDING {
+                    return Submitted::Dropped;
+                }
+                self.pending.push_back(Utterance {
+                    text: text.to_string(),
+                    voice: voice.map(String::from),
+                });
+                Submitted::Queued
+            }
+            SpeechPriority::DropIfBusy if busy => Submitted::Dropped,
+            _ => {
+                self.next_ticket += 1;
+                self.owner = Some(self.next_ticket);
+                Submitted::Speak(self.next_ticket)
+            }
+        }
+    }
+
+    /// The speaker holding `ticket` is done. Returns the next text for it to
+    /// speak, if it still owns the queue and `may_continue` (not stopped or
+    /// barged in on).
+    pub(crate) fn finished(&mut self, ticket: u64, may_continue: bool) -> Option<Utterance> {
+        if self.owner != Some(ticket) {
+            return None;
+        }
+        let next = if may_continue { self.pending.pop_front() } else { None };
+        if next.is_none() {
+            self.owner = None;
+        }
+        next
+    }
+
+    /// Forget the current speaker (e.g. it wedged and was recovered), so
+    /// new requests aren't queued behind it.
+    pub(crate) fn abandon(&mut self) {
+        self.owner = None;
+    }
+
+    /// Drop everything waiting. Returns how many texts were discarded.
+    pub(crate) fn clear(&mut self) -> usize {
+        let n = self.pending.len();
+        self.pending.clear();
+        n
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(utterance: Option<Utterance>) -> Option<String> {
+        utterance.map(|u| u.text)
+    }
+
+    #[test]
+    fn test_queue_waits_for_speaker_and_drains_in_order() {
+        let mut q = SpeechQueue::default();
+        let Submitted::Speak(ticket) = q.submit("answer", None, SpeechPriority::Interrupt) else {
+            panic!("idle queue should speak now");
+        };
+        assert_eq!(q.submit("status 1", None, SpeechPriority::Queue), Submitted::Queued);
+        assert_eq!(q.submit("status 2", None, SpeechPriority::Queue), Submitted::Queued);
+        assert_eq!(q.submit("ping", None, SpeechPriority::DropIfBusy), Submitted::Dropped);
+
+        assert_eq!(text(q.finished(ticket, true)).as_deref(), Some("status 1"));
+        assert_eq!(text(q.finished(ticket, true)).as_deref(), Some("status 2"));
+        assert_eq!(q.finished(ticket, true), None);
+        assert!(matches!(q.submit("ping", None, SpeechPriority::DropIfBusy), Submitted::Speak(_)));
+    }
+
+    #[test]
+    fn test_interrupt_takes_over_draining() {
+        let mut q = SpeechQueue::default();
+        let Submitted::Speak(first) = q.submit("long answer", None, SpeechPriority::Interrupt) else {
+            panic!();
+        };
+        q.submit("status", None, SpeechPriority::Queue);
+        let Submitted::Speak(second) = q.submit("new answer", None, SpeechPriority::Interrupt) else {
+            panic!("interrupt always speaks");
+        };
+        assert_eq!(q.finished(first, true), None, "cut-off speaker must not drain");
+        assert_eq!(text(q.finished(second, true)).as_deref(), Some("status"));
+    }
+
+    #[test]
+    fn test_stopped_speaker_releases_queue() {
+        let mut q = SpeechQueue::default();
+        let Submitted::Speak(ticket) = q.submit("answer", None, SpeechPriority::Interrupt) else {
+            panic!();
+        };
+        q.submit("status", None, SpeechPriority::Queue);
+        assert_eq!(q.finished(ticket, false), None);
+        assert_eq!(q.len(), 1, "barge-in keeps queued text for later");
+        assert!(matches!(q.submit("next", None, SpeechPriority::Queue), Submitted::Speak(_)));
+        assert_eq!(q.clear(), 1);
+    }
+
+    #[test]
+    fn test_queued_text_keeps_its_voice() {
+        let mut q = SpeechQueue::default();
+        let Submitted::Speak(ticket) = q.submit("answer", None, SpeechPriority::Interrupt) else {
+            panic!();
+        };
+        q.submit("from the coder", Some("am_adam"), SpeechPriority::Queue);
+        let next = q.finished(ticket, true).unwrap();
+        assert_eq!(next.voice.as_deref(), Some("am_adam"));
+    }
+
+    #[test]
+    fn test_priority_names() {
+        assert_eq!(SpeechPriority::from_name("drop_if_busy"), Some(SpeechPriority::DropIfBusy));
+        assert_eq!(SpeechPriority::from_name("loud"), None);
+        assert_eq!(
+            serde_json::to_string(&SpeechPriority::Queue).unwrap(),
+            "\"queue\""
+        );
+    }
+}
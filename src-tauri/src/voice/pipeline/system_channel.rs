@@ -0,0 +1,326 @@
+//! Second capture channel for `captureSource: "both"`.
+//!
+//! The microphone drives the pipeline as usual. Alongside it, a loopback
+//! stream records what the output device is playing (a meeting, a video)
+//! into its own ring buffer. The processing loop drains that buffer each
+//! pass, cuts it into utterances by energy and silence, and transcribes
+//! them whenever the mic side is idle, using the same STT engine one turn
+//! at a time. The results are `Transcription` events with
+//! `source: "system"`; the frontend keeps them as notes instead of sending
+//! them to the AI.
+//!
+//! Audio that arrives while the assistant is speaking is dropped, so its
+//! own replies are never transcribed back.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use cpal::traits::DeviceTrait;
+
+use super::format::AudioFormat;
+use super::recovery;
+use super::ring_buffer::{create_ring_buffer, RingConsumer};
+use super::{
+    build_capture_stream, find_loopback_device, PipelineShared, SendStream, TranscriptSource,
+    VoiceEvent, VAD_FRAME_SAMPLES,
+};
+use crate::error::AppError;
+use crate::services::event_throttle::EmitThrottled;
+use crate::services::transcript_log::{self, Speaker};
+use crate::services::turn_archive;
+use crate::voice::resample::ResamplerKind;
+use crate::voice::vad;
+use crate::voice::{VoiceEngineConfig, VoiceState};
+
+/// `captureSource` value that records the mic and system audio side by side.
+pub(crate) const CAPTURE_BOTH: &str = "both";
+/// Audio kept from before an utterance's first loud frame.
+const PRE_ROLL_SECS: f64 = 0.3;
+/// Longest utterance; whisper works on 30 s windows.
+const MAX_SEGMENT_SECS: f64 = 30.0;
+/// Utterances waiting for STT; the oldest is dropped beyond this.
+const MAX_PENDING: usize = 8;
+
+/// Open the loopback stream for `captureSource: "both"` and hand its ring
+/// buffer to the processing loop. `None` for other sources.
+pub(super) fn start(shared: &PipelineShared) -> Result<Option<SendStream>, AppError> {
+    if shared.config.capture_source != CAPTURE_BOTH {
+        return Ok(None);
+    }
+    let device = find_loopback_device(shared.config.output_device.as_deref())?;
+    let config = device
+        .default_output_config()
+        .map_err(|e| AppError::AudioDevice(format!("Failed to get loopback config: {}", e)))?;
+    let (producer, consumer) = create_ring_buffer(shared.format.ring_capacity());
+    let stream = build_capture_stream(
+        &device,
+        &config,
+        ResamplerKind::from_config(&shared.config.resampler),
+        shared.format,
+        producer,
+    )?;
+    if let Ok(mut slot) = shared.system_audio.lock() {
+        *slot = Some(consumer);
+    }
+    tracing::info!("System audio capture started alongside the microphone");
+    Ok(Some(SendStream(stream)))
+}
+
+/// Cuts a stream into utterances: from the first frame above the
+/// threshold (plus a little pre-roll) until `silence_limit` samples of
+/// quiet, or `max_len` samples.
+pub(super) struct Segmenter {
+    threshold: f32,
+    frame: usize,
+    pre_roll: usize,
+    silence_limit: usize,
+    max_len: usize,
+    /// Pre-roll while quiet, the utterance while in speech.
+    buf: Vec<f32>,
+    /// Leftover samples shorter than a frame.
+    partial: Vec<f32>,
+    in_speech: bool,
+    silent_run: usize,
+}
+
+impl Segmenter {
+    pub(super) fn new(
+        format: &AudioFormat,
+        threshold: f32,
+        silence_limit: usize,
+        max_len: usize,
+    ) -> Self {
+        let frame = format.vad_frame();
+        Self {
+            threshold,
+            frame,
+            pre_roll: format.samples(PRE_ROLL_SECS),
+            silence_limit,
+            max_len,
+            buf: Vec::new(),
+            partial: Vec::with_capacity(frame),
+            in_speech: false,
+            silent_run: 0,
+        }
+    }
+
+    /// Feed samples; returns the utterances they completed.
+    pub(super) fn push(&mut self, samples: &[f32]) -> Vec<Vec<f32>> {
+        let mut done = Vec::new();
+        self.partial.extend_from_slice(samples);
+        let frames = self.partial.len() / self.frame;
+        let frame_audio: Vec<f32> = self.partial.drain(..frames * self.frame).collect();
+        for frame in frame_audio.chunks_exact(self.frame) {
+            let loud = vad::compute_energy(frame) > self.threshold;
+            self.buf.extend_from_slice(frame);
+            if !self.in_speech {
+                if loud {
+                    self.in_speech = true;
+                    self.silent_run = 0;
+                } else if self.buf.len() > self.pre_roll {
+                    let excess = self.buf.len() - self.pre_roll;
+                    self.buf.drain(..excess);
+                }
+                continue;
+            }
+            self.silent_run = if loud {
+                0
+            } else {
+                self.silent_run + frame.len()
+            };
+            if self.silent_run >= self.silence_limit || self.buf.len() >= self.max_len {
+                done.push(std::mem::take(&mut self.buf));
+                self.in_speech = false;
+                self.silent_run = 0;
+            }
+        }
+        done
+    }
+
+    /// Drop any utterance in progress.
+    pub(super) fn reset(&mut self) {
+        self.buf.clear();
+        self.partial.clear();
+        self.in_speech = false;
+        self.silent_run = 0;
+    }
+}
+
+/// The processing loop's side of the system channel.
+pub(super) struct SystemChannel {
+    ring: RingConsumer,
+    segmenter: Segmenter,
+    read_buf: Vec<f32>,
+    pending: VecDeque<Vec<f32>>,
+}
+
+impl SystemChannel {
+    pub(super) fn new(
+        ring: RingConsumer,
+        config: &VoiceEngineConfig,
+        format: &AudioFormat,
+    ) -> Self {
+        let segmenter = Segmenter::new(
+            format,
+            config.vad_threshold,
+            format.samples(config.silence_timeout_secs),
+            format.samples(MAX_SEGMENT_SECS),
+        );
+        Self {
+            ring,
+            segmenter,
+            read_buf: vec![0.0; format.chunk_samples],
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Drain captured audio into utterances. While the assistant speaks the
+    /// audio is its own voice, so it's dropped.
+    pub(super) fn poll(&mut self, state: VoiceState) {
+        loop {
+            let n = self.ring.pop_slice(&mut self.read_buf);
+            if n == 0 {
+                break;
+            }
+            if state == VoiceState::Speaking {
+                self.segmenter.reset();
+                continue;
+            }
+            for segment in self.segmenter.push(&self.read_buf[..n]) {
+                if self.pending.len() >= MAX_PENDING {
+                    tracing::warn!(
+                        "System audio transcription is falling behind, dropping an utterance"
+                    );
+                    self.pending.pop_front();
+                }
+                self.pending.push_back(segment);
+            }
+        }
+    }
+
+    /// Transcribe the finished utterances. Call only while the mic side
+    /// isn't recording, so the user's own turn never waits behind them.
+    pub(super) async fn transcribe_pending(&mut self, shared: &Arc<PipelineShared>) {
+        while let Some(audio) = self.pending.pop_front() {
+            run_system_stt(shared, audio).await;
+        }
+    }
+}
+
+/// Transcribe one system-audio utterance and emit it. Unlike a user turn it
+/// isn't timed, archived, matched against local commands, or counted as a
+/// conversation turn, and it doesn't feed the STT context.
+async fn run_system_stt(shared: &Arc<PipelineShared>, audio: Vec<f32>) {
+    let audio = shared
+        .format
+        .to_stt_rate(ResamplerKind::from_config(&shared.config.resampler), audio);
+    let coverage = vad::speech_coverage(&audio, shared.config.vad_threshold, VAD_FRAME_SAMPLES);
+    if coverage < shared.config.min_speech_coverage {
+        return;
+    }
+    let Some(engine) = recovery::take_stt_engine(shared).await else {
+        return;
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        let result = engine.transcribe(&audio);
+        (engine, result)
+    })
+    .await;
+
+    match result {
+        Ok((engine, Ok(text))) => {
+            let language = engine.detected_language();
+            recovery::restore_stt_engine(shared, engine);
+            let text = text.trim().to_string();
+            let text = match shared.transcript_rules.as_ref() {
+                Some(rules) if !text.is_empty() => rules.apply(&text),
+                _ => text,
+            };
+            if text.is_empty() {
+                return;
+            }
+            tracing::info!(text = %text, "System audio transcription");
+            if shared.config.transcript_log {
+                let (text, language) = (text.clone(), language.clone());
+                tokio::task::spawn_blocking(move || {
+                    transcript_log::append(Speaker::System, &text, language.as_deref())
+                });
+            }
+            let _ = shared.app_handle.emit_throttled(
+                "voice-event",
+                VoiceEvent::Transcription {
+                    id: turn_archive::next_id(),
+                    text,
+                    language,
+                    source: TranscriptSource::System,
+                    speaker: None,
+                    similarity: None,
+                    session_id: None,
+                    turn: None,
+                    routes: None,
+                },
+            );
+        }
+        Ok((engine, Err(e))) => {
+            tracing::warn!("System audio STT failed: {}", e);
+            recovery::restore_stt_engine(shared, engine);
+        }
+        Err(e) => {
+            tracing::error!("System audio STT task panicked: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: usize = VAD_FRAME_SAMPLES;
+    /// `PRE_ROLL_SECS` at 16kHz.
+    const PRE_ROLL_SAMPLES: usize = 4_800;
+
+    fn segmenter(silence_limit: usize, max_len: usize) -> Segmenter {
+        Segmenter::new(&AudioFormat::default(), 0.01, silence_limit, max_len)
+    }
+
+    fn tone(frames: usize) -> Vec<f32> {
+        vec![0.2; frames * FRAME]
+    }
+
+    fn quiet(frames: usize) -> Vec<f32> {
+        vec![0.0; frames * FRAME]
+    }
+
+    #[test]
+    fn test_segmenter_cuts_on_silence() {
+        let mut seg = segmenter(4 * FRAME, 1000 * FRAME);
+        assert!(seg.push(&quiet(50)).is_empty());
+        assert!(seg.push(&tone(10)).is_empty());
+        let done = seg.push(&quiet(6));
+        assert_eq!(done.len(), 1);
+        // Pre-roll, the tone, and the silence that ended it
+        assert_eq!(done[0].len(), PRE_ROLL_SAMPLES + 14 * FRAME);
+        assert!(seg.push(&quiet(20)).is_empty());
+    }
+
+    #[test]
+    fn test_segmenter_handles_partial_frames() {
+        let mut seg = segmenter(2 * FRAME, 1000 * FRAME);
+        let mut audio = tone(3);
+        audio.extend(quiet(3));
+        let mut done = Vec::new();
+        for chunk in audio.chunks(100) {
+            done.extend(seg.push(chunk));
+        }
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[0].len(), 5 * FRAME);
+    }
+
+    #[test]
+    fn test_segmenter_caps_length() {
+        let mut seg = segmenter(4 * FRAME, 10 * FRAME);
+        let done = seg.push(&tone(25));
+        assert_eq!(done.len(), 2);
+        assert!(done.iter().all(|s| s.len() == 10 * FRAME));
+    }
+}
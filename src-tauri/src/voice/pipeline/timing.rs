@@ -0,0 +1,374 @@
+//! Per-turn latency tracing.
+//!
+//! A voice turn runs recording stop -> STT start -> STT done -> provider
+//! response -> TTS first audio -> playback end. Each stage is timestamped as
+//! the pipeline passes it, and the finished turn is logged, emitted as
+//! `VoiceEvent::PipelineTiming`, and kept in a short history for the
+//! diagnostics UI (`get_pipeline_timings`).
+//!
+//! "Provider response" is when the reply reaches TTS (`speak()`), so it
+//! covers the frontend hop and the provider's full response time. Speech that
+//! isn't a reply to a recording (announcements, manual `speak_text`) has no
+//! open turn and isn't traced.
+//!
+//! Interrupted turns are also counted in the daily voice stats
+//! (`services::voice_stats`), and spoken ones against the latency budget
+//! (`voice::latency_budget`). STT and TTS latencies also feed the metrics
+//! histograms (`services::metrics`).
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use super::{PipelineShared, VoiceEvent};
+use crate::services::event_throttle::EmitThrottled;
+use crate::services::metrics::{self, Counter, Histogram};
+use crate::services::voice_stats;
+use crate::voice::latency_budget;
+
+/// Finished turns kept for `get_pipeline_timings`.
+const HISTORY_LEN: usize = 50;
+
+/// Survives pipeline restarts, so a restart doesn't wipe the numbers being
+/// compared.
+static HISTORY: Mutex<VecDeque<PipelineTiming>> = Mutex::new(VecDeque::new());
+
+/// A point a turn passes after recording stops.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Stage {
+    SttStart,
+    SttDone,
+    Response,
+    FirstAudio,
+}
+
+/// How a turn ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnOutcome {
+    /// The reply played to the end.
+    Spoken,
+    /// Playback was cut short (barge-in or stop).
+    Interrupted,
+    /// The recording held no speech, or STT returned nothing.
+    NoSpeech,
+    SttFailed,
+    /// The next recording started before a reply was spoken.
+    NoResponse,
+    /// The transcription was a local voice command (see `voice::intents`).
+    LocalCommand,
+    /// Dropped: the voice matched no enrolled speaker (see `voice::speaker`).
+    UnknownSpeaker,
+    /// Routed only to consumers that don't reply (see `voice::routing`).
+    Routed,
+    /// The transcription answered an AI's spoken question (see `voice::ask`).
+    Answered,
+}
+
+/// Stage durations of one turn, in milliseconds. A stage the turn never
+/// reached is `None`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineTiming {
+    /// Increments per turn within a pipeline run.
+    pub turn: u64,
+    /// Unix time (ms) the turn finished.
+    pub finished_at: u64,
+    pub outcome: TurnOutcome,
+    /// Recording stop -> STT start (buffer drain, speech coverage check).
+    pub stt_wait_ms: Option<u64>,
+    /// STT inference.
+    pub stt_ms: Option<u64>,
+    /// STT done -> reply handed to TTS.
+    pub response_ms: Option<u64>,
+    /// Reply -> first synthesized audio queued for playback.
+    pub tts_first_audio_ms: Option<u64>,
+    /// First audio -> playback end.
+    pub playback_ms: Option<u64>,
+    /// Recording stop -> turn end.
+    pub total_ms: u64,
+}
+
+/// Timestamps of the turn in progress.
+#[derive(Debug)]
+struct OpenTurn {
+    id: u64,
+    stopped: Instant,
+    stt_start: Option<Instant>,
+    stt_done: Option<Instant>,
+    response: Option<Instant>,
+    first_audio: Option<Instant>,
+}
+
+/// Tracks the current turn. Lives in `PipelineShared`.
+#[derive(Debug, Default)]
+pub(crate) struct TurnTimer {
+    current: Option<OpenTurn>,
+    next_id: u64,
+}
+
+impl TurnTimer {
+    /// Recording stopped: start a new turn. Returns the previous turn if it
+    /// was still open.
+    fn begin(&mut self, now: Instant) -> Option<PipelineTiming> {
+        let previous = self.current.take().map(|turn| {
+            let outcome = if turn.first_audio.is_some() {
+                TurnOutcome::Interrupted
+            } else {
+                TurnOutcome::NoResponse
+            };
+            let end = turn
+                .first_audio
+                .or(turn.response)
+                .or(turn.stt_done)
+                .or(turn.stt_start)
+                .unwrap_or(turn.stopped);
+            summarize(&turn, outcome, end)
+        });
+        self.next_id += 1;
+        self.current = Some(OpenTurn {
+            id: self.next_id,
+            stopped: now,
+            stt_start: None,
+            stt_done: None,
+            response: None,
+            first_audio: None,
+        });
+        previous
+    }
+
+    /// Record `stage`. Stages only count once and in order, so a second
+    /// `speak()` for the same reply doesn't move the response mark.
+    fn mark(&mut self, stage: Stage, now: Instant) {
+        let Some(turn) = self.current.as_mut() else {
+            return;
+        };
+        let (slot, prerequisite) = match stage {
+            Stage::SttStart => (&mut turn.stt_start, true),
+            Stage::SttDone => (&mut turn.stt_done, turn.stt_start.is_some()),
+            Stage::Response => (&mut turn.response, turn.stt_done.is_some()),
+            Stage::FirstAudio => (&mut turn.first_audio, turn.response.is_some()),
+        };
+        if prerequisite && slot.is_none() {
+            *slot = Some(now);
+        }
+    }
+
+    /// End the current turn with `outcome`.
+    fn finish(&mut self, outcome: TurnOutcome, now: Instant) -> Option<PipelineTiming> {
+        self.current
+            .take()
+            .map(|turn| summarize(&turn, outcome, now))
+    }
+
+    /// End the turn when its reply finishes playing. Speech without a
+    /// response mark isn't a reply and leaves the turn open.
+    fn finish_reply(&mut self, interrupted: bool, now: Instant) -> Option<PipelineTiming> {
+        if self.current.as_ref()?.response.is_none() {
+            return None;
+        }
+        let outcome = if interrupted {
+            TurnOutcome::Interrupted
+        } else {
+            TurnOutcome::Spoken
+        };
+        self.finish(outcome, now)
+    }
+}
+
+fn summarize(turn: &OpenTurn, outcome: TurnOutcome, end: Instant) -> PipelineTiming {
+    let span = |from: Option<Instant>, to: Option<Instant>| match (from, to) {
+        (Some(from), Some(to)) => Some(to.saturating_duration_since(from).as_millis() as u64),
+        _ => None,
+    };
+    let ended = turn.first_audio.map(|_| end);
+    PipelineTiming {
+        turn: turn.id,
+        finished_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        outcome,
+        stt_wait_ms: span(Some(turn.stopped), turn.stt_start),
+        stt_ms: span(turn.stt_start, turn.stt_done),
+        response_ms: span(turn.stt_done, turn.response),
+        tts_first_audio_ms: span(turn.response, turn.first_audio),
+        playback_ms: span(turn.first_audio, ended),
+        total_ms: end.saturating_duration_since(turn.stopped).as_millis() as u64,
+    }
+}
+
+/// Recording stopped and its audio is headed for STT.
+pub(crate) fn begin(shared: &Arc<PipelineShared>) {
+    let previous = shared
+        .turn_timer
+        .lock()
+        .ok()
+        .and_then(|mut timer| timer.begin(Instant::now()));
+    if let Some(timing) = previous {
+        publish(shared, timing);
+    }
+}
+
+pub(crate) fn mark(shared: &Arc<PipelineShared>, stage: Stage) {
+    if let Ok(mut timer) = shared.turn_timer.lock() {
+        timer.mark(stage, Instant::now());
+    }
+}
+
+/// End the turn early (no speech, STT failure).
+pub(crate) fn finish(shared: &Arc<PipelineShared>, outcome: TurnOutcome) {
+    let timing = shared
+        .turn_timer
+        .lock()
+        .ok()
+        .and_then(|mut timer| timer.finish(outcome, Instant::now()));
+    if let Some(timing) = timing {
+        publish(shared, timing);
+    }
+}
+
+/// A `speak()` call finished playing (or was cut short).
+pub(crate) fn finish_reply(shared: &Arc<PipelineShared>, interrupted: bool) {
+    let timing = shared
+        .turn_timer
+        .lock()
+        .ok()
+        .and_then(|mut timer| timer.finish_reply(interrupted, Instant::now()));
+    if let Some(timing) = timing {
+        publish(shared, timing);
+    }
+}
+
+/// Id of the turn in progress, if any.
+pub(crate) fn current_turn(shared: &PipelineShared) -> Option<u64> {
+    shared
+        .turn_timer
+        .lock()
+        .ok()
+        .and_then(|timer| timer.current.as_ref().map(|turn| turn.id))
+}
+
+/// Recent turns, oldest first.
+pub fn history() -> Vec<PipelineTiming> {
+    HISTORY
+        .lock()
+        .map(|h| h.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn publish(shared: &Arc<PipelineShared>, timing: PipelineTiming) {
+    tracing::info!(
+        turn = timing.turn,
+        outcome = ?timing.outcome,
+        stt_wait_ms = ?timing.stt_wait_ms,
+        stt_ms = ?timing.stt_ms,
+        response_ms = ?timing.response_ms,
+        tts_first_audio_ms = ?timing.tts_first_audio_ms,
+        playback_ms = ?timing.playback_ms,
+        total_ms = timing.total_ms,
+        "Voice turn timing"
+    );
+    if timing.outcome == TurnOutcome::Interrupted {
+        voice_stats::record_interruption();
+    }
+    if let Some(ms) = timing.stt_ms {
+        metrics::inc(Counter::Utterances);
+        metrics::observe(Histogram::SttLatency, ms);
+    }
+    if let Some(ms) = timing.tts_first_audio_ms {
+        metrics::observe(Histogram::TtsLatency, ms);
+    }
+    if let Ok(mut history) = HISTORY.lock() {
+        if history.len() == HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(timing.clone());
+    }
+    let step = latency_budget::observe(&shared.config, &timing);
+    let latency_ms = latency_budget::voice_latency_ms(&timing);
+    let _ = shared
+        .app_handle
+        .emit_throttled("voice-event", VoiceEvent::PipelineTiming(timing));
+    if let Some(step) = step {
+        tracing::warn!(
+            level = step.level,
+            latency_ms = ?latency_ms,
+            budget_ms = shared.config.latency_budget_ms,
+            "Over the latency budget, stepping down: {}",
+            step.detail
+        );
+        let _ = shared.app_handle.emit_throttled(
+            "voice-event",
+            VoiceEvent::LatencyLadder {
+                level: step.level,
+                rung: Some(step.rung),
+                detail: step.detail,
+                latency_ms,
+                budget_ms: shared.config.latency_budget_ms,
+                manual: false,
+            },
+        );
+        latency_budget::restart_pipeline(shared.app_handle.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_full_turn() {
+        let t0 = Instant::now();
+        let mut timer = TurnTimer::default();
+        assert!(timer.begin(t0).is_none());
+        timer.mark(Stage::SttStart, t0 + ms(20));
+        timer.mark(Stage::SttDone, t0 + ms(520));
+        timer.mark(Stage::Response, t0 + ms(2520));
+        // A second speak() for the same reply doesn't move the mark
+        timer.mark(Stage::Response, t0 + ms(4000));
+        timer.mark(Stage::FirstAudio, t0 + ms(2920));
+
+        let timing = timer.finish_reply(false, t0 + ms(5920)).unwrap();
+        assert_eq!(timing.turn, 1);
+        assert_eq!(timing.outcome, TurnOutcome::Spoken);
+        assert_eq!(timing.stt_wait_ms, Some(20));
+        assert_eq!(timing.stt_ms, Some(500));
+        assert_eq!(timing.response_ms, Some(2000));
+        assert_eq!(timing.tts_first_audio_ms, Some(400));
+        assert_eq!(timing.playback_ms, Some(3000));
+        assert_eq!(timing.total_ms, 5920);
+        assert!(timer.finish_reply(false, t0 + ms(6000)).is_none());
+    }
+
+    #[test]
+    fn test_unanswered_turn_closed_by_next_recording() {
+        let t0 = Instant::now();
+        let mut timer = TurnTimer::default();
+        timer.begin(t0);
+        timer.mark(Stage::SttStart, t0 + ms(10));
+        timer.mark(Stage::SttDone, t0 + ms(310));
+        // Speech that isn't a reply (no response mark) leaves the turn open
+        timer.mark(Stage::FirstAudio, t0 + ms(400));
+        assert!(timer.finish_reply(false, t0 + ms(900)).is_none());
+
+        let previous = timer.begin(t0 + ms(10_000)).unwrap();
+        assert_eq!(previous.outcome, TurnOutcome::NoResponse);
+        assert_eq!(previous.response_ms, None);
+        assert_eq!(previous.playback_ms, None);
+        assert_eq!(previous.total_ms, 310);
+
+        let next = timer
+            .finish(TurnOutcome::NoSpeech, t0 + ms(10_050))
+            .unwrap();
+        assert_eq!(next.turn, 2);
+        assert_eq!(next.stt_wait_ms, None);
+        assert_eq!(next.total_ms, 50);
+    }
+}
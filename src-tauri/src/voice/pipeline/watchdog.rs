@@ -0,0 +1,347 @@
+//! Pipeline watchdog: notices a processing loop that stopped consuming
+//! audio, and restarts the pipeline.
+//!
+//! `stuck_watchdog` watches the voice *state*; this one watches the loop
+//! itself. If the processing task panics or a lock it needs is poisoned,
+//! the pipeline silently stops listening while `is_running()` still said
+//! true. Every `POLL` it looks for:
+//!
+//! - `loop_exited`: the processing task ended while the pipeline runs (a
+//!   panic unwinds through `LoopGuard`)
+//! - `lock_poisoned`: a panic poisoned one of the pipeline's mutexes
+//! - `no_heartbeat`: the loop hasn't come round in `STALL_AFTER`
+//! - `not_draining`: the ring buffer stayed full and unread for `STALL_AFTER`
+//!
+//! On the first one the pipeline is marked stalled (`is_running()` turns
+//! false), the failure goes to the error log and
+//! `VoiceEvent::PipelineStalled` is emitted. With `voice.watchdogAutoRestart`
+//! on (the default) the engine is then torn down and started again, at most
+//! `MAX_RESTARTS` times per `RESTART_WINDOW`, so a pipeline that breaks on
+//! every start doesn't restart forever.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use super::{PipelineShared, VoiceEvent};
+use crate::commands::voice::VoiceEngineState;
+use crate::error::AppError;
+use crate::services::event_throttle::EmitThrottled;
+
+/// How often the watchdog looks.
+const POLL: Duration = Duration::from_secs(2);
+
+/// How long the loop may go without a heartbeat, or the ring buffer stay
+/// full, before the pipeline counts as stalled. Longer than a healthy STT
+/// run (the loop waits for it), and than `stuck_watchdog`'s 30s warning.
+pub const STALL_AFTER: Duration = Duration::from_secs(60);
+
+/// Automatic restarts allowed per `RESTART_WINDOW`.
+pub const MAX_RESTARTS: usize = 3;
+pub const RESTART_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Why the pipeline was declared stalled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StallReason {
+    LoopExited,
+    LockPoisoned,
+    NoHeartbeat,
+    NotDraining,
+}
+
+impl StallReason {
+    fn describe(self) -> &'static str {
+        match self {
+            StallReason::LoopExited => "the audio processing task ended",
+            StallReason::LockPoisoned => "a pipeline lock was poisoned",
+            StallReason::NoHeartbeat => "the audio processing loop stopped responding",
+            StallReason::NotDraining => "captured audio is no longer being read",
+        }
+    }
+}
+
+/// Liveness of the processing loop.
+pub(crate) struct Heartbeat {
+    started: Instant,
+    /// Ms since `started` of the last beat.
+    last_ms: AtomicU64,
+    loop_alive: AtomicBool,
+}
+
+impl Heartbeat {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            last_ms: AtomicU64::new(0),
+            loop_alive: AtomicBool::new(true),
+        }
+    }
+
+    /// Called by the processing loop on every pass.
+    pub(crate) fn beat(&self) {
+        let ms = self.started.elapsed().as_millis() as u64;
+        self.last_ms.store(ms, Ordering::Relaxed);
+    }
+
+    /// Whether the processing task is still running.
+    pub(crate) fn loop_alive(&self) -> bool {
+        self.loop_alive.load(Ordering::Relaxed)
+    }
+
+    fn since_beat(&self) -> Duration {
+        let last = Duration::from_millis(self.last_ms.load(Ordering::Relaxed));
+        self.started.elapsed().saturating_sub(last)
+    }
+}
+
+/// Held by the processing task; marks the loop dead when the task ends,
+/// including by a panic.
+pub(crate) struct LoopGuard(pub(crate) Arc<PipelineShared>);
+
+impl Drop for LoopGuard {
+    fn drop(&mut self) {
+        self.0.heartbeat.loop_alive.store(false, Ordering::Relaxed);
+    }
+}
+
+/// What the watchdog sees on one poll.
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    loop_alive: bool,
+    poisoned: bool,
+    since_beat: Duration,
+    ring_full: bool,
+    /// Samples the loop has read from the ring buffer so far.
+    consumed: u64,
+}
+
+/// Turns samples into a verdict; only the ring buffer needs memory.
+#[derive(Debug, Default)]
+struct Detector {
+    /// When the ring buffer was first seen full, and how much had been
+    /// read by then.
+    full_since: Option<(Instant, u64)>,
+}
+
+impl Detector {
+    fn check(&mut self, now: Instant, sample: &Sample) -> Option<StallReason> {
+        if !sample.loop_alive {
+            return Some(StallReason::LoopExited);
+        }
+        if sample.poisoned {
+            return Some(StallReason::LockPoisoned);
+        }
+        if sample.since_beat >= STALL_AFTER {
+            return Some(StallReason::NoHeartbeat);
+        }
+        match self.full_since {
+            Some((since, consumed)) if sample.ring_full && sample.consumed == consumed => {
+                if now.duration_since(since) >= STALL_AFTER {
+                    return Some(StallReason::NotDraining);
+                }
+            }
+            _ => self.full_since = sample.ring_full.then_some((now, sample.consumed)),
+        }
+        None
+    }
+}
+
+/// Restarts done recently, shared across pipeline runs.
+struct RestartBudget {
+    recent: VecDeque<Instant>,
+}
+
+impl RestartBudget {
+    /// Use up one restart if fewer than `MAX_RESTARTS` were done in the
+    /// last `RESTART_WINDOW`.
+    fn take(&mut self, now: Instant) -> bool {
+        while let Some(&oldest) = self.recent.front() {
+            if now.duration_since(oldest) < RESTART_WINDOW {
+                break;
+            }
+            self.recent.pop_front();
+        }
+        if self.recent.len() >= MAX_RESTARTS {
+            return false;
+        }
+        self.recent.push_back(now);
+        true
+    }
+}
+
+static RESTARTS: Mutex<RestartBudget> = Mutex::new(RestartBudget {
+    recent: VecDeque::new(),
+});
+
+fn any_lock_poisoned(shared: &PipelineShared) -> bool {
+    shared.mode.is_poisoned()
+        || shared.recording_buf.is_poisoned()
+        || shared.turn_timer.is_poisoned()
+        || shared.speech_queue.is_poisoned()
+        || shared.active_playback_cancel.is_poisoned()
+        || shared.ring_producer.is_poisoned()
+}
+
+fn sample(shared: &PipelineShared) -> Sample {
+    let ring = &shared.ring_consumer;
+    Sample {
+        loop_alive: shared.heartbeat.loop_alive.load(Ordering::Relaxed),
+        poisoned: any_lock_poisoned(shared),
+        since_beat: shared.heartbeat.since_beat(),
+        ring_full: ring.available() >= ring.capacity(),
+        consumed: ring.consumed(),
+    }
+}
+
+/// Watch the pipeline until it stops or stalls.
+pub(super) async fn run(shared: Arc<PipelineShared>) {
+    let mut detector = Detector::default();
+    while shared.running.load(Ordering::Relaxed) {
+        tokio::time::sleep(POLL).await;
+        if !shared.running.load(Ordering::Relaxed) {
+            break;
+        }
+        if let Some(reason) = detector.check(Instant::now(), &sample(&shared)) {
+            stalled(&shared, reason);
+            break;
+        }
+    }
+}
+
+fn stalled(shared: &PipelineShared, reason: StallReason) {
+    shared.stalled.store(true, Ordering::SeqCst);
+    let restarting = shared.config.watchdog_auto_restart
+        && RESTARTS
+            .lock()
+            .map(|mut budget| budget.take(Instant::now()))
+            .unwrap_or(false);
+    tracing::error!(
+        ?reason,
+        restarting,
+        "Voice pipeline stalled: {}",
+        reason.describe()
+    );
+    crate::voice::errors::record(
+        &AppError::Internal(format!("Voice pipeline stalled: {}", reason.describe())),
+        super::timing::current_turn(shared),
+    );
+    let _ = shared.app_handle.emit_throttled(
+        "voice-event",
+        VoiceEvent::PipelineStalled {
+            reason,
+            detail: reason.describe().to_string(),
+            restarting,
+        },
+    );
+    if restarting {
+        restart(shared.app_handle.clone());
+    }
+}
+
+/// Tear the stalled pipeline down and start a new one. Runs on its own
+/// thread: the engine lock may be held by whoever is stuck.
+fn restart(app: AppHandle) {
+    std::thread::spawn(move || {
+        let state = app.state::<VoiceEngineState>();
+        let mut engine = state.lock().unwrap_or_else(|e| e.into_inner());
+        // Voice may have been stopped or restarted in the meantime
+        if !engine.is_stalled() {
+            return;
+        }
+        engine.stop();
+        match engine.start(app.clone()) {
+            Ok(()) => tracing::info!("Voice pipeline restarted after a stall"),
+            Err(e) => {
+                tracing::error!("Failed to restart the stalled voice pipeline: {}", e);
+                let _ = app.emit_throttled(
+                    "voice-event",
+                    VoiceEvent::error(e.context("Voice restart failed")),
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy() -> Sample {
+        Sample {
+            loop_alive: true,
+            poisoned: false,
+            since_beat: Duration::from_millis(100),
+            ring_full: false,
+            consumed: 0,
+        }
+    }
+
+    #[test]
+    fn test_detector_reasons() {
+        let now = Instant::now();
+        let mut detector = Detector::default();
+        assert_eq!(detector.check(now, &healthy()), None);
+
+        let exited = Sample {
+            loop_alive: false,
+            ..healthy()
+        };
+        assert_eq!(detector.check(now, &exited), Some(StallReason::LoopExited));
+
+        let poisoned = Sample {
+            poisoned: true,
+            ..healthy()
+        };
+        assert_eq!(
+            detector.check(now, &poisoned),
+            Some(StallReason::LockPoisoned)
+        );
+
+        let silent = Sample {
+            since_beat: STALL_AFTER,
+            ..healthy()
+        };
+        assert_eq!(detector.check(now, &silent), Some(StallReason::NoHeartbeat));
+    }
+
+    #[test]
+    fn test_detector_ring_must_stay_full_and_unread() {
+        let start = Instant::now();
+        let full = Sample {
+            ring_full: true,
+            consumed: 500,
+            ..healthy()
+        };
+        let mut detector = Detector::default();
+        assert_eq!(detector.check(start, &full), None);
+        // Reading anything resets the clock
+        let read = Sample {
+            consumed: 600,
+            ..full
+        };
+        assert_eq!(detector.check(start + STALL_AFTER, &read), None);
+        assert_eq!(detector.check(start + STALL_AFTER * 2 - POLL, &read), None);
+        assert_eq!(
+            detector.check(start + STALL_AFTER * 2, &read),
+            Some(StallReason::NotDraining)
+        );
+    }
+
+    #[test]
+    fn test_restart_budget() {
+        let start = Instant::now();
+        let mut budget = RestartBudget {
+            recent: VecDeque::new(),
+        };
+        for i in 0..MAX_RESTARTS {
+            assert!(budget.take(start + Duration::from_secs(i as u64)));
+        }
+        assert!(!budget.take(start + Duration::from_secs(60)));
+        assert!(budget.take(start + RESTART_WINDOW));
+    }
+}
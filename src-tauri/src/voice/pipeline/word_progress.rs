@@ -0,0 +1,237 @@
+//! Word-level speaking progress for TTS playback.
+//!
+//! Engines that report word boundaries (Edge TTS) give timings relative to
+//! each synthesized phrase. [`WordLocator`] maps those words to character
+//! offsets in the full utterance, a rodio `EmptyCallback` queued in front of
+//! each phrase starts its timeline when playback actually reaches it, and an
+//! emitter task turns due words into `VoiceEvent::SpeakingProgress`.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rodio::source::EmptyCallback;
+use rodio::Sink;
+use tauri::AppHandle;
+
+use super::VoiceEvent;
+use crate::services::event_throttle::EmitThrottled;
+use crate::voice::tts::WordBoundary;
+
+/// How often the emitter checks for words that have started playing.
+const EMIT_POLL: Duration = Duration::from_millis(25);
+
+/// A word located in the utterance, timed from the start of its phrase audio.
+pub(super) struct WordMark {
+    at: Duration,
+    char_offset: usize,
+    word: String,
+}
+
+/// Resolves engine-reported words to character offsets in the utterance.
+///
+/// Phrases are spoken in order, so a forward-only cursor is enough. Words
+/// that can't be found (e.g. masked by the output filter) are skipped.
+struct WordLocator {
+    text: String,
+    cursor_bytes: usize,
+    cursor_chars: usize,
+}
+
+impl WordLocator {
+    fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            cursor_bytes: 0,
+            cursor_chars: 0,
+        }
+    }
+
+    fn marks(&mut self, boundaries: Vec<WordBoundary>) -> Vec<WordMark> {
+        let mut marks = Vec::with_capacity(boundaries.len());
+        for b in boundaries {
+            if b.word.is_empty() {
+                continue;
+            }
+            let rest = &self.text[self.cursor_bytes..];
+            let Some(rel) = rest.find(&b.word) else {
+                continue;
+            };
+            let char_offset = self.cursor_chars + rest[..rel].chars().count();
+            self.cursor_bytes += rel + b.word.len();
+            self.cursor_chars = char_offset + b.word.chars().count();
+            marks.push(WordMark {
+                at: b.offset,
+                char_offset,
+                word: b.word,
+            });
+        }
+        marks
+    }
+}
+
+/// Per-utterance progress tracker. Dropping it stops the emitter task.
+pub(super) struct WordProgress {
+    locator: WordLocator,
+    queue: Arc<Mutex<VecDeque<(Instant, WordMark)>>>,
+    done: Arc<AtomicBool>,
+}
+
+impl WordProgress {
+    /// Start tracking `text`; events stop early if `cancel` is set.
+    pub(super) fn start(app: AppHandle, text: &str, cancel: Arc<AtomicBool>) -> Self {
+        let queue = Arc::new(Mutex::new(VecDeque::new()));
+        let done = Arc::new(AtomicBool::new(false));
+        tauri::async_runtime::spawn(run_emitter(
+            app,
+            Arc::clone(&queue),
+            Arc::clone(&done),
+            cancel,
+        ));
+        Self {
+            locator: WordLocator::new(text),
+            queue,
+            done,
+        }
+    }
+
+    /// Locate the words of the phrase that was just synthesized.
+    pub(super) fn marks(&mut self, boundaries: Vec<WordBoundary>) -> Vec<WordMark> {
+        self.locator.marks(boundaries)
+    }
+
+    /// Handle for scheduling marks from the playback thread.
+    pub(super) fn scheduler(&self) -> WordScheduler {
+        WordScheduler {
+            queue: Arc::clone(&self.queue),
+        }
+    }
+}
+
+impl Drop for WordProgress {
+    fn drop(&mut self) {
+        self.done.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Queues phrase timelines onto a rodio Sink (used on the playback thread).
+pub(super) struct WordScheduler {
+    queue: Arc<Mutex<VecDeque<(Instant, WordMark)>>>,
+}
+
+impl WordScheduler {
+    /// Append a zero-length source that starts `marks`' timeline when
+    /// playback reaches it. Call right before appending the phrase audio.
+    /// Returns whether a source was appended (not for empty `marks`).
+    pub(super) fn append_start(&self, sink: &Sink, marks: Vec<WordMark>) -> bool {
+        if marks.is_empty() {
+            return false;
+        }
+        let queue = Arc::clone(&self.queue);
+        let marks = Mutex::new(Some(marks));
+        sink.append(EmptyCallback::<f32>::new(Box::new(move || {
+            let Some(marks) = marks.lock().ok().and_then(|mut m| m.take()) else {
+                return;
+            };
+            let now = Instant::now();
+            if let Ok(mut q) = queue.lock() {
+                q.extend(marks.into_iter().map(|m| (now + m.at, m)));
+            }
+        })));
+        true
+    }
+}
+
+/// Pop marks whose start time has passed, in queue order.
+fn take_due(queue: &Mutex<VecDeque<(Instant, WordMark)>>, now: Instant) -> Vec<WordMark> {
+    let Ok(mut q) = queue.lock() else {
+        return Vec::new();
+    };
+    let mut due = Vec::new();
+    while q.front().is_some_and(|(at, _)| *at <= now) {
+        if let Some((_, mark)) = q.pop_front() {
+            due.push(mark);
+        }
+    }
+    due
+}
+
+async fn run_emitter(
+    app: AppHandle,
+    queue: Arc<Mutex<VecDeque<(Instant, WordMark)>>>,
+    done: Arc<AtomicBool>,
+    cancel: Arc<AtomicBool>,
+) {
+    while !done.load(Ordering::SeqCst) && !cancel.load(Ordering::SeqCst) {
+        for mark in take_due(&queue, Instant::now()) {
+            let _ = app.emit_throttled(
+                "voice-event",
+                VoiceEvent::SpeakingProgress {
+                    char_offset: mark.char_offset,
+                    word: mark.word,
+                },
+            );
+        }
+        tokio::time::sleep(EMIT_POLL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boundary(ms: u64, word: &str) -> WordBoundary {
+        WordBoundary {
+            offset: Duration::from_millis(ms),
+            word: word.into(),
+        }
+    }
+
+    #[test]
+    fn test_locator_offsets_span_phrases() {
+        let mut loc = WordLocator::new("Héllo there. How are you?");
+        let first = loc.marks(vec![boundary(0, "Héllo"), boundary(400, "there")]);
+        assert_eq!(first[0].char_offset, 0);
+        assert_eq!(first[1].char_offset, 6);
+        assert_eq!(first[1].at, Duration::from_millis(400));
+
+        let second = loc.marks(vec![boundary(0, "How"), boundary(200, "you")]);
+        assert_eq!(second[0].char_offset, 13);
+        assert_eq!(second[1].char_offset, 21);
+    }
+
+    #[test]
+    fn test_locator_skips_missing_words() {
+        let mut loc = WordLocator::new("say the word now");
+        let marks = loc.marks(vec![
+            boundary(0, "say"),
+            boundary(100, "***"),
+            boundary(300, "now"),
+        ]);
+        let words: Vec<&str> = marks.iter().map(|m| m.word.as_str()).collect();
+        assert_eq!(words, ["say", "now"]);
+        assert_eq!(marks[1].char_offset, 13);
+    }
+
+    #[test]
+    fn test_take_due_in_order() {
+        let queue = Mutex::new(VecDeque::new());
+        let t0 = Instant::now();
+        for (i, w) in ["a", "b", "c"].iter().enumerate() {
+            let mark = WordMark {
+                at: Duration::ZERO,
+                char_offset: i,
+                word: w.to_string(),
+            };
+            queue
+                .lock()
+                .unwrap()
+                .push_back((t0 + Duration::from_millis(i as u64 * 100), mark));
+        }
+        let due = take_due(&queue, t0 + Duration::from_millis(150));
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[1].word, "b");
+        assert_eq!(queue.lock().unwrap().len(), 1);
+    }
+}
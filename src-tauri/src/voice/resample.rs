@@ -0,0 +1,260 @@
+//! Sample rate conversion (`voice.resampler`).
+//!
+//! `linear` interpolates between neighbouring samples. It is cheap, but it
+//! doesn't filter: when a 44.1/48 kHz mic is brought down to whisper's
+//! 16 kHz, everything between 8 kHz and the mic's Nyquist frequency (hiss,
+//! sibilance, fan whine) folds back into the speech band as aliasing.
+//!
+//! `sinc` convolves with a Blackman-windowed sinc low-pass whose cutoff sits
+//! just below the lower of the two Nyquist frequencies, so nothing aliases.
+//! The kernel is tabulated once per rate pair. Capture runs it as a stream:
+//! history carries across callbacks, so block boundaries don't click, at the
+//! cost of about 1 ms of added latency.
+
+use super::pipeline::resample_linear;
+
+/// Zero crossings of the sinc on each side of the centre tap.
+const ZERO_CROSSINGS: f64 = 16.0;
+/// Cutoff as a fraction of the lower Nyquist frequency. The window's
+/// transition band fits in the remaining 8%.
+const ROLLOFF: f64 = 0.92;
+/// Kernel table entries per input sample (linearly interpolated).
+const TABLE_RESOLUTION: f64 = 256.0;
+
+/// Which converter to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResamplerKind {
+    #[default]
+    Linear,
+    Sinc,
+}
+
+impl ResamplerKind {
+    /// Parse "linear" / "sinc". Unknown values mean `Linear`.
+    pub fn from_config(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "sinc" => Self::Sinc,
+            _ => Self::Linear,
+        }
+    }
+}
+
+/// Convert a whole buffer from `from_rate` to `to_rate`.
+pub fn resample(kind: ResamplerKind, input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || from_rate == 0 || to_rate == 0 || input.is_empty() {
+        return input.to_vec();
+    }
+    match kind {
+        ResamplerKind::Linear => resample_linear(input, from_rate, to_rate),
+        ResamplerKind::Sinc => {
+            let mut sinc = SincResampler::new(from_rate, to_rate);
+            let expected = (input.len() as f64 / sinc.kernel.step).floor() as usize;
+            let mut out = Vec::with_capacity(expected + 1);
+            sinc.process(input, &mut out);
+            // Flush: the last outputs need the kernel's right half
+            let tail = vec![0.0; sinc.kernel.half_width.ceil() as usize + 1];
+            sinc.process(&tail, &mut out);
+            out.truncate(expected);
+            out
+        }
+    }
+}
+
+/// Converter for audio that arrives in blocks (the capture callback).
+pub enum StreamResampler {
+    Passthrough,
+    /// Each block converted on its own, as capture always did.
+    Linear {
+        from_rate: u32,
+        to_rate: u32,
+    },
+    Sinc(SincResampler),
+}
+
+impl StreamResampler {
+    pub fn new(kind: ResamplerKind, from_rate: u32, to_rate: u32) -> Self {
+        if from_rate == to_rate || from_rate == 0 || to_rate == 0 {
+            return Self::Passthrough;
+        }
+        match kind {
+            ResamplerKind::Linear => Self::Linear { from_rate, to_rate },
+            ResamplerKind::Sinc => Self::Sinc(SincResampler::new(from_rate, to_rate)),
+        }
+    }
+
+    /// Convert the next block.
+    pub fn process(&mut self, block: Vec<f32>) -> Vec<f32> {
+        match self {
+            Self::Passthrough => block,
+            Self::Linear { from_rate, to_rate } => resample_linear(&block, *from_rate, *to_rate),
+            Self::Sinc(sinc) => {
+                let mut out = Vec::with_capacity(block.len());
+                sinc.process(&block, &mut out);
+                out
+            }
+        }
+    }
+}
+
+/// Tabulated windowed-sinc low-pass for one rate pair.
+struct SincKernel {
+    /// Filter weights for offsets 0, 1/TABLE_RESOLUTION, ... input samples.
+    table: Vec<f32>,
+    /// Taps reach this many input samples either side of the centre.
+    half_width: f64,
+    /// Input samples per output sample.
+    step: f64,
+}
+
+impl SincKernel {
+    fn new(from_rate: u32, to_rate: u32) -> Self {
+        let step = from_rate as f64 / to_rate as f64;
+        // Cycles per input sample
+        let cutoff = ROLLOFF * 0.5 * (1.0 / step).min(1.0);
+        let half_width = ZERO_CROSSINGS / (2.0 * cutoff);
+        let len = (half_width * TABLE_RESOLUTION).ceil() as usize + 2;
+        let table = (0..len)
+            .map(|i| {
+                let t = i as f64 / TABLE_RESOLUTION;
+                if t >= half_width {
+                    return 0.0;
+                }
+                let x = 2.0 * cutoff * t;
+                let sinc = if x == 0.0 {
+                    1.0
+                } else {
+                    (std::f64::consts::PI * x).sin() / (std::f64::consts::PI * x)
+                };
+                let u = std::f64::consts::PI * t / half_width;
+                let blackman = 0.42 + 0.5 * u.cos() + 0.08 * (2.0 * u).cos();
+                (2.0 * cutoff * sinc * blackman) as f32
+            })
+            .collect();
+        Self {
+            table,
+            half_width,
+            step,
+        }
+    }
+
+    fn weight(&self, offset: f64) -> f32 {
+        let x = offset.abs() * TABLE_RESOLUTION;
+        let i = x as usize;
+        match (self.table.get(i), self.table.get(i + 1)) {
+            (Some(&a), Some(&b)) => a + (b - a) * (x - i as f64) as f32,
+            _ => 0.0,
+        }
+    }
+
+    /// Filtered value of `input` at fractional position `pos`.
+    fn sample(&self, input: &[f32], pos: f64) -> f32 {
+        let first = (pos - self.half_width).ceil().max(0.0) as usize;
+        let last = ((pos + self.half_width).floor() as usize).min(input.len() - 1);
+        (first..=last)
+            .map(|i| input[i] * self.weight(i as f64 - pos))
+            .sum()
+    }
+}
+
+/// Streaming windowed-sinc converter.
+pub struct SincResampler {
+    kernel: SincKernel,
+    /// Unconsumed input, plus the history the next output still needs.
+    buffered: Vec<f32>,
+    /// Position of the next output sample in `buffered`.
+    pos: f64,
+}
+
+impl SincResampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        let kernel = SincKernel::new(from_rate, to_rate);
+        // Silence before the stream starts, so the first output is centred
+        // on the first input sample.
+        let lead = kernel.half_width.ceil() as usize;
+        Self {
+            kernel,
+            buffered: vec![0.0; lead],
+            pos: lead as f64,
+        }
+    }
+
+    /// Append `input` and push every output sample it completes.
+    pub fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        self.buffered.extend_from_slice(input);
+        let half_width = self.kernel.half_width;
+        while self.pos + half_width <= (self.buffered.len() - 1) as f64 {
+            out.push(self.kernel.sample(&self.buffered, self.pos));
+            self.pos += self.kernel.step;
+        }
+        // Drop input no later output can reach
+        let consumed = ((self.pos - half_width).floor().max(0.0) as usize).min(self.buffered.len());
+        self.buffered.drain(..consumed);
+        self.pos -= consumed as f64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f64, rate: u32, secs: f64) -> Vec<f32> {
+        let n = (rate as f64 * secs) as usize;
+        (0..n)
+            .map(|i| (2.0 * std::f64::consts::PI * freq * i as f64 / rate as f64).sin() as f32)
+            .collect()
+    }
+
+    /// RMS of the middle half (skips edge effects).
+    fn rms(samples: &[f32]) -> f32 {
+        let mid = &samples[samples.len() / 4..samples.len() * 3 / 4];
+        (mid.iter().map(|s| s * s).sum::<f32>() / mid.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_sinc_passes_speech_and_blocks_aliases() {
+        // 1 kHz is speech band: kept
+        let speech = resample(ResamplerKind::Sinc, &sine(1000.0, 48000, 0.5), 48000, 16000);
+        assert_eq!(speech.len(), 8000);
+        assert!((rms(&speech) - 0.707).abs() < 0.01);
+
+        // 11 kHz is above 16 kHz's Nyquist: linear folds it to 5 kHz,
+        // sinc removes it
+        let hiss = sine(11000.0, 48000, 0.5);
+        let linear = resample(ResamplerKind::Linear, &hiss, 48000, 16000);
+        let sinc = resample(ResamplerKind::Sinc, &hiss, 48000, 16000);
+        assert!(rms(&linear) > 0.1);
+        assert!(rms(&sinc) < 0.01, "alias rms {}", rms(&sinc));
+    }
+
+    #[test]
+    fn test_sinc_upsamples_at_unity_gain() {
+        let out = resample(ResamplerKind::Sinc, &[0.5; 2205], 22050, 24000);
+        assert_eq!(out.len(), 2400);
+        assert!((out[1200] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_streaming_matches_whole_buffer() {
+        let input = sine(440.0, 44100, 0.2);
+        let whole = resample(ResamplerKind::Sinc, &input, 44100, 16000);
+
+        let mut stream = StreamResampler::new(ResamplerKind::Sinc, 44100, 16000);
+        let mut streamed = Vec::new();
+        for block in input.chunks(441) {
+            streamed.extend(stream.process(block.to_vec()));
+        }
+        // The stream holds back the last few outputs until more input arrives
+        assert!(whole.len() - streamed.len() < 32);
+        for (a, b) in whole.iter().zip(&streamed) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_kind_and_passthrough() {
+        assert_eq!(ResamplerKind::from_config(" Sinc "), ResamplerKind::Sinc);
+        assert_eq!(ResamplerKind::from_config("bogus"), ResamplerKind::Linear);
+        let mut stream = StreamResampler::new(ResamplerKind::Sinc, 16000, 16000);
+        assert_eq!(stream.process(vec![1.0, 2.0]), vec![1.0, 2.0]);
+    }
+}
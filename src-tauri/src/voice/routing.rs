@@ -0,0 +1,204 @@
+//! Transcription routing (`transcriptRoutes` config section).
+//!
+//! Without routes a transcription goes to one consumer: the AI provider,
+//! or the text injector while dictating. A routing table can fan one
+//! utterance out to several consumers, or pick them by the keyword the
+//! utterance opens with ("note: buy milk" only becomes a note). Routes are
+//! tried in order and the first match wins; a route without a prefix
+//! matches everything, so it belongs last. With no match, the default
+//! applies.
+//!
+//! Targets:
+//! - `provider`: sent to the active AI provider (by the frontend)
+//! - `dictation`: typed into the focused app (by the frontend)
+//! - `webhook`: delivered as the `transcription` webhook event
+//! - `notes`: saved as a memory in the `notes` tier (24 h), here
+//!
+//! The chosen targets ride in the `Transcription` event's `routes` field.
+//! Webhooks skip a routed transcription unless it lists `webhook`.
+
+use serde::Serialize;
+
+use crate::config::schema::TranscriptRouteConfig;
+
+/// A consumer of transcriptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteTarget {
+    Provider,
+    Dictation,
+    Webhook,
+    Notes,
+}
+
+impl RouteTarget {
+    /// Config names of every target.
+    pub const NAMES: [&'static str; 4] = ["provider", "dictation", "webhook", "notes"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "provider" => Some(Self::Provider),
+            "dictation" => Some(Self::Dictation),
+            "webhook" => Some(Self::Webhook),
+            "notes" => Some(Self::Notes),
+            _ => None,
+        }
+    }
+}
+
+/// Where one transcription goes, and the text to send there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub targets: Vec<RouteTarget>,
+    pub text: String,
+}
+
+#[derive(Debug)]
+struct CompiledRoute {
+    prefix: String,
+    targets: Vec<RouteTarget>,
+    strip_prefix: bool,
+}
+
+/// Compiled routing table.
+#[derive(Debug)]
+pub struct TranscriptRouter {
+    routes: Vec<CompiledRoute>,
+}
+
+impl TranscriptRouter {
+    /// Compile `config`, or `None` when it has no usable route. Unknown
+    /// targets are skipped with a warning.
+    pub fn new(config: &[TranscriptRouteConfig]) -> Option<Self> {
+        let routes: Vec<CompiledRoute> = config
+            .iter()
+            .filter_map(|route| {
+                let mut targets = Vec::new();
+                for name in &route.targets {
+                    match RouteTarget::parse(name) {
+                        Some(t) if !targets.contains(&t) => targets.push(t),
+                        Some(_) => {}
+                        None => {
+                            tracing::warn!(name = %name, "Ignoring unknown transcript route target")
+                        }
+                    }
+                }
+                (!targets.is_empty()).then(|| CompiledRoute {
+                    prefix: route.prefix.trim().to_string(),
+                    targets,
+                    strip_prefix: route.strip_prefix,
+                })
+            })
+            .collect();
+        (!routes.is_empty()).then_some(Self { routes })
+    }
+
+    /// The first route `text` matches, or `None` for the default.
+    pub fn route(&self, text: &str) -> Option<Route> {
+        self.routes.iter().find_map(|route| {
+            if route.prefix.is_empty() {
+                return Some(Route {
+                    targets: route.targets.clone(),
+                    text: text.to_string(),
+                });
+            }
+            let rest = after_prefix(text, &route.prefix)?;
+            Some(Route {
+                targets: route.targets.clone(),
+                text: if route.strip_prefix { rest } else { text }.to_string(),
+            })
+        })
+    }
+}
+
+/// What follows `prefix` at the start of `text`, ignoring case and the
+/// punctuation whisper puts after it ("Note: ...", "Note, ..."). `None`
+/// when `text` doesn't open with the whole keyword or nothing follows it.
+fn after_prefix<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let text = text.trim_start();
+    let head = text.get(..prefix.len())?;
+    if !head.eq_ignore_ascii_case(prefix) {
+        return None;
+    }
+    let tail = &text[prefix.len()..];
+    if tail.chars().next().is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    let rest = tail.trim_start_matches(|c: char| {
+        c.is_whitespace() || matches!(c, ':' | ',' | '.' | '-' | ';')
+    });
+    (!rest.is_empty()).then_some(rest)
+}
+
+/// Save `text` as a short-lived memory (`notes` tier) in the MCP data
+/// directory, where the `memory_*` tools find it.
+pub async fn save_note(text: &str) {
+    use crate::mcp::handlers::memory::handle_memory_remember;
+    use crate::providers::cli::mcp_config::get_mcp_data_dir_for_env;
+
+    let args = serde_json::json!({ "content": text, "tier": "notes" });
+    let result = handle_memory_remember(&args, &get_mcp_data_dir_for_env()).await;
+    if result.is_error {
+        tracing::warn!("Failed to save a routed transcription as a note");
+    } else {
+        tracing::info!(
+            chars = text.chars().count(),
+            "Routed transcription saved as a note"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(prefix: &str, targets: &[&str]) -> TranscriptRouteConfig {
+        TranscriptRouteConfig {
+            prefix: prefix.into(),
+            targets: targets.iter().map(|t| t.to_string()).collect(),
+            strip_prefix: true,
+        }
+    }
+
+    #[test]
+    fn test_prefix_routes_before_fan_out() {
+        let router = TranscriptRouter::new(&[
+            route("note", &["notes"]),
+            route("", &["provider", "webhook", "provider"]),
+        ])
+        .unwrap();
+
+        let note = router.route("Note: buy oat milk.").unwrap();
+        assert_eq!(note.targets, vec![RouteTarget::Notes]);
+        assert_eq!(note.text, "buy oat milk.");
+
+        let other = router.route("Notebooks are on sale").unwrap();
+        assert_eq!(
+            other.targets,
+            vec![RouteTarget::Provider, RouteTarget::Webhook]
+        );
+        assert_eq!(other.text, "Notebooks are on sale");
+    }
+
+    #[test]
+    fn test_no_match_uses_default() {
+        let router = TranscriptRouter::new(&[route("take a note", &["notes"])]).unwrap();
+        assert!(router.route("what's the weather").is_none());
+        // A bare keyword has nothing to route
+        assert!(router.route("Take a note.").is_none());
+        let mut keep = route("todo", &["notes", "provider"]);
+        keep.strip_prefix = false;
+        let router = TranscriptRouter::new(&[keep]).unwrap();
+        assert_eq!(
+            router.route("TODO, call Sam").unwrap().text,
+            "TODO, call Sam"
+        );
+    }
+
+    #[test]
+    fn test_unusable_routes_are_dropped() {
+        assert!(TranscriptRouter::new(&[]).is_none());
+        assert!(TranscriptRouter::new(&[route("x", &["printer"])]).is_none());
+        assert_eq!(RouteTarget::parse(" Notes "), Some(RouteTarget::Notes));
+    }
+}
@@ -0,0 +1,409 @@
+//! Multi-turn conversation sessions.
+//!
+//! A session groups the voice turns of one conversation. The first
+//! transcription opens it (`session_start`), each turn links its
+//! transcription to the provider reply and the TTS playback of that reply
+//! (`turn_complete`), and it ends after `voice.sessionTimeoutSecs` without a
+//! turn, reply or playback, or when the pipeline stops (`session_end`).
+//!
+//! A turn opens when its `Transcription` is emitted, which carries the
+//! session id and turn number. The reply is marked when the provider's
+//! response arrives (`ai-response` / `mcp-inbox-message`). The turn completes
+//! when that reply finishes playing, right away if the reply isn't spoken,
+//! or with the next transcription if no reply came.
+//!
+//! The tracker is owned by the `VoiceEngine` and shared with its pipeline.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use super::pipeline::VoiceEvent;
+use crate::services::event_throttle::EmitThrottled;
+
+/// How often the pipeline checks for an inactive session.
+pub const EXPIRY_POLL: Duration = Duration::from_secs(1);
+
+/// Where a transcription landed: its session and turn number (from 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnRef {
+    pub session_id: u64,
+    pub turn: u32,
+}
+
+/// The session in progress, for `get_voice_status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    pub id: u64,
+    /// Turns opened so far.
+    pub turns: u32,
+    /// Seconds since the last turn, reply or playback.
+    pub idle_secs: u64,
+}
+
+/// The turn waiting for its reply to be given or played.
+#[derive(Debug)]
+struct OpenTurn {
+    turn: u32,
+    transcription_id: u64,
+    replied: bool,
+    spoken: bool,
+}
+
+#[derive(Debug)]
+struct Session {
+    id: u64,
+    turns: u32,
+    started: Instant,
+    last_activity: Instant,
+    open: Option<OpenTurn>,
+}
+
+/// Session state machine. All transitions take `now` and return the events
+/// they produce, so they can be tested without a pipeline.
+#[derive(Debug, Default)]
+struct Tracker {
+    current: Option<Session>,
+    last_id: u64,
+}
+
+impl Tracker {
+    /// A transcription arrived: open a turn, and a session if none is open.
+    /// A turn still waiting for its reply is completed first.
+    fn begin_turn(
+        &mut self,
+        transcription_id: u64,
+        unix_ms: u64,
+        now: Instant,
+    ) -> (TurnRef, Vec<VoiceEvent>) {
+        let mut events = Vec::new();
+        let mut session = match self.current.take() {
+            Some(session) => session,
+            None => {
+                // Unix ms of the start, so ids stay unique across app runs
+                let id = unix_ms.max(self.last_id + 1);
+                self.last_id = id;
+                events.push(VoiceEvent::SessionStart { session_id: id });
+                Session {
+                    id,
+                    turns: 0,
+                    started: now,
+                    last_activity: now,
+                    open: None,
+                }
+            }
+        };
+        events.extend(complete_turn(&mut session, false));
+        session.turns += 1;
+        session.last_activity = now;
+        session.open = Some(OpenTurn {
+            turn: session.turns,
+            transcription_id,
+            replied: false,
+            spoken: false,
+        });
+        let turn = TurnRef {
+            session_id: session.id,
+            turn: session.turns,
+        };
+        self.current = Some(session);
+        (turn, events)
+    }
+
+    /// The provider replied to the open turn. A reply that won't be spoken
+    /// completes it.
+    fn reply(&mut self, speak: bool, now: Instant) -> Vec<VoiceEvent> {
+        let Some(session) = self.current.as_mut() else {
+            return Vec::new();
+        };
+        session.last_activity = now;
+        let Some(turn) = session.open.as_mut() else {
+            return Vec::new();
+        };
+        turn.replied = true;
+        if speak {
+            Vec::new()
+        } else {
+            complete_turn(session, false)
+        }
+    }
+
+    /// Playback started. Only a replied turn counts it as its reply;
+    /// other speech just keeps the session alive.
+    fn speaking(&mut self, now: Instant) {
+        let Some(session) = self.current.as_mut() else {
+            return;
+        };
+        session.last_activity = now;
+        if let Some(turn) = session.open.as_mut().filter(|t| t.replied) {
+            turn.spoken = true;
+        }
+    }
+
+    /// Playback ended (or was cut short). Completes the turn whose reply it
+    /// was.
+    fn finish_speaking(&mut self, interrupted: bool, now: Instant) -> Vec<VoiceEvent> {
+        let Some(session) = self.current.as_mut() else {
+            return Vec::new();
+        };
+        session.last_activity = now;
+        if !session.open.as_ref().is_some_and(|t| t.spoken) {
+            return Vec::new();
+        }
+        complete_turn(session, interrupted)
+    }
+
+    /// End the session if nothing happened for `timeout`. Zero never ends it.
+    fn expire(&mut self, timeout: Duration, now: Instant) -> Vec<VoiceEvent> {
+        let idle = match self.current.as_ref() {
+            Some(session) => now.saturating_duration_since(session.last_activity),
+            None => return Vec::new(),
+        };
+        if timeout.is_zero() || idle < timeout {
+            return Vec::new();
+        }
+        self.end("inactivity", now)
+    }
+
+    /// End the session now. `reason` is "inactivity" or "stopped".
+    fn end(&mut self, reason: &str, now: Instant) -> Vec<VoiceEvent> {
+        let Some(mut session) = self.current.take() else {
+            return Vec::new();
+        };
+        let mut events = complete_turn(&mut session, false);
+        events.push(VoiceEvent::SessionEnd {
+            session_id: session.id,
+            turns: session.turns,
+            duration_secs: now.saturating_duration_since(session.started).as_secs(),
+            reason: reason.into(),
+        });
+        events
+    }
+
+    fn info(&self, now: Instant) -> Option<SessionInfo> {
+        let session = self.current.as_ref()?;
+        Some(SessionInfo {
+            id: session.id,
+            turns: session.turns,
+            idle_secs: now.saturating_duration_since(session.last_activity).as_secs(),
+        })
+    }
+}
+
+/// Close the open turn, if any.
+fn complete_turn(session: &mut Session, interrupted: bool) -> Vec<VoiceEvent> {
+    let Some(turn) = session.open.take() else {
+        return Vec::new();
+    };
+    vec![VoiceEvent::TurnComplete {
+        session_id: session.id,
+        turn: turn.turn,
+        transcription_id: turn.transcription_id,
+        replied: turn.replied,
+        spoken: turn.spoken,
+        interrupted: interrupted && turn.spoken,
+    }]
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Conversation sessions of a `VoiceEngine`, shared with its pipeline.
+#[derive(Debug, Default)]
+pub struct SessionTracker {
+    inner: Mutex<Tracker>,
+}
+
+impl SessionTracker {
+    /// Open a turn for the transcription `transcription_id`.
+    pub fn begin_turn(&self, app: &AppHandle, transcription_id: u64) -> Option<TurnRef> {
+        let (turn, events) = self
+            .inner
+            .lock()
+            .ok()?
+            .begin_turn(transcription_id, unix_millis(), Instant::now());
+        emit(app, events);
+        Some(turn)
+    }
+
+    /// The provider replied; `speak` is whether the reply goes to TTS.
+    pub fn reply(&self, app: &AppHandle, speak: bool) {
+        self.update(app, |t, now| t.reply(speak, now));
+    }
+
+    /// TTS playback started.
+    pub fn speaking(&self) {
+        if let Ok(mut tracker) = self.inner.lock() {
+            tracker.speaking(Instant::now());
+        }
+    }
+
+    /// TTS playback ended.
+    pub fn finish_speaking(&self, app: &AppHandle, interrupted: bool) {
+        self.update(app, |t, now| t.finish_speaking(interrupted, now));
+    }
+
+    /// End the session after `timeout` of inactivity.
+    pub fn expire(&self, app: &AppHandle, timeout: Duration) {
+        self.update(app, |t, now| t.expire(timeout, now));
+    }
+
+    /// End the session (pipeline stopping).
+    pub fn end(&self, app: &AppHandle, reason: &str) {
+        self.update(app, |t, now| t.end(reason, now));
+    }
+
+    /// The session in progress, if any.
+    pub fn current(&self) -> Option<SessionInfo> {
+        self.inner.lock().ok()?.info(Instant::now())
+    }
+
+    fn update(&self, app: &AppHandle, f: impl FnOnce(&mut Tracker, Instant) -> Vec<VoiceEvent>) {
+        let events = match self.inner.lock() {
+            Ok(mut tracker) => f(&mut tracker, Instant::now()),
+            Err(_) => return,
+        };
+        emit(app, events);
+    }
+}
+
+fn emit(app: &AppHandle, events: Vec<VoiceEvent>) {
+    for event in events {
+        match &event {
+            VoiceEvent::SessionStart { session_id } => {
+                tracing::info!(session_id, "Conversation session started");
+            }
+            VoiceEvent::SessionEnd {
+                session_id,
+                turns,
+                reason,
+                ..
+            } => {
+                tracing::info!(session_id, turns, reason = %reason, "Conversation session ended");
+            }
+            _ => {}
+        }
+        let _ = app.emit_throttled("voice-event", event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    fn names(events: &[VoiceEvent]) -> Vec<&'static str> {
+        events
+            .iter()
+            .map(|e| match e {
+                VoiceEvent::SessionStart { .. } => "session_start",
+                VoiceEvent::TurnComplete { .. } => "turn_complete",
+                VoiceEvent::SessionEnd { .. } => "session_end",
+                _ => "other",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_spoken_turn() {
+        let t0 = Instant::now();
+        let mut tracker = Tracker::default();
+        let (turn, events) = tracker.begin_turn(7, 1_000, t0);
+        assert_eq!(turn, TurnRef { session_id: 1_000, turn: 1 });
+        assert_eq!(names(&events), ["session_start"]);
+
+        assert!(tracker.reply(true, t0 + secs(2)).is_empty());
+        tracker.speaking(t0 + secs(2));
+        let events = tracker.finish_speaking(false, t0 + secs(5));
+        match &events[..] {
+            [VoiceEvent::TurnComplete {
+                session_id,
+                turn,
+                transcription_id,
+                replied,
+                spoken,
+                interrupted,
+            }] => {
+                assert_eq!((*session_id, *turn, *transcription_id), (1_000, 1, 7));
+                assert!(*replied && *spoken && !*interrupted);
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+
+        let (turn, events) = tracker.begin_turn(8, 9_000, t0 + secs(10));
+        assert_eq!(turn, TurnRef { session_id: 1_000, turn: 2 });
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_speech_outside_a_reply_leaves_the_turn_open() {
+        let t0 = Instant::now();
+        let mut tracker = Tracker::default();
+        tracker.begin_turn(1, 1_000, t0);
+        tracker.speaking(t0 + secs(1));
+        assert!(tracker.finish_speaking(false, t0 + secs(2)).is_empty());
+
+        // Unspoken reply completes the turn immediately
+        assert_eq!(names(&tracker.reply(false, t0 + secs(3))), ["turn_complete"]);
+
+        // An unanswered turn is completed by the next transcription
+        tracker.begin_turn(2, 1_000, t0 + secs(4));
+        let (turn, events) = tracker.begin_turn(3, 1_000, t0 + secs(5));
+        assert_eq!(turn.turn, 3);
+        assert!(matches!(
+            &events[..],
+            [VoiceEvent::TurnComplete { turn: 2, replied: false, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_session_ends_after_inactivity() {
+        let t0 = Instant::now();
+        let mut tracker = Tracker::default();
+        tracker.begin_turn(1, 5_000, t0);
+        tracker.reply(true, t0 + secs(1));
+        tracker.speaking(t0 + secs(1));
+        assert!(tracker.expire(secs(0), t0 + secs(1_000)).is_empty());
+        assert!(tracker.expire(secs(60), t0 + secs(30)).is_empty());
+        // Playback counts as activity
+        tracker.finish_speaking(true, t0 + secs(50));
+        assert!(tracker.expire(secs(60), t0 + secs(100)).is_empty());
+
+        let events = tracker.expire(secs(60), t0 + secs(110));
+        assert!(matches!(
+            &events[..],
+            [VoiceEvent::SessionEnd { session_id: 5_000, turns: 1, duration_secs: 110, reason }]
+                if reason == "inactivity"
+        ));
+        assert!(tracker.info(t0 + secs(110)).is_none());
+
+        // A new session never reuses an id, even within the same millisecond
+        let (turn, events) = tracker.begin_turn(2, 5_000, t0 + secs(120));
+        assert_eq!(turn, TurnRef { session_id: 5_001, turn: 1 });
+        assert_eq!(names(&events), ["session_start"]);
+    }
+
+    #[test]
+    fn test_end_completes_open_turn() {
+        let t0 = Instant::now();
+        let mut tracker = Tracker::default();
+        assert!(tracker.end("stopped", t0).is_empty());
+        tracker.begin_turn(1, 1_000, t0);
+        assert_eq!(
+            tracker.info(t0 + secs(3)),
+            Some(SessionInfo { id: 1_000, turns: 1, idle_secs: 3 })
+        );
+        assert_eq!(names(&tracker.end("stopped", t0 + secs(4))), ["turn_complete", "session_end"]);
+    }
+}
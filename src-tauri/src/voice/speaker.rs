@@ -0,0 +1,456 @@
+//! Speaker identification ("is that the enrolled user talking?").
+//!
+//! An ECAPA-TDNN speaker model (ONNX, 3D-Speaker VoxCeleb export) turns an
+//! utterance into a 192-dim voice embedding. Enrolling a speaker averages
+//! the embeddings of a few of their recordings into a profile stored in
+//! `{data_dir}/speakers.json`. While `voice.speakerId` is on, each turn's
+//! recording is compared against the profiles (cosine similarity) and the
+//! `Transcription` event carries the best match as `speaker` (when it
+//! reaches `voice.speakerThreshold`) and `similarity`.
+//!
+//! In wake-word mode, with `voice.ignoreUnknownSpeakers` on and at least one
+//! profile enrolled, turns that match no profile are dropped, so a TV or
+//! other people in the room don't start conversations.
+//!
+//! The model is loaded with ONNX Runtime and needs the `onnx` build feature;
+//! without it [`SpeakerEncoder::load`] fails and turns go untagged.
+
+use std::f32::consts::PI;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// Model file, stored in `models/speaker`.
+pub const SPEAKER_MODEL_FILE: &str = "3dspeaker_speech_ecapa_tdnn_sv_en_voxceleb_16k.onnx";
+/// Download URL of [`SPEAKER_MODEL_FILE`] (sherpa-onnx release assets).
+pub const SPEAKER_MODEL_URL: &str = "https://github.com/k2-fsa/sherpa-onnx/releases/download/speaker-recongition-models/3dspeaker_speech_ecapa_tdnn_sv_en_voxceleb_16k.onnx";
+
+const PROFILES_FILE: &str = "speakers.json";
+const SAMPLE_RATE: f32 = 16_000.0;
+/// Kaldi fbank framing: 25ms windows every 10ms.
+const FRAME_LEN: usize = 400;
+const FRAME_SHIFT: usize = 160;
+const FFT_SIZE: usize = 512;
+const MEL_BINS: usize = 80;
+const PREEMPHASIS: f32 = 0.97;
+/// Recordings shorter than this give embeddings too noisy to compare.
+pub const MIN_SAMPLES: usize = 8_000;
+
+/// Serializes profile writes.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Directory the speaker model is loaded from.
+pub fn model_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("models").join("speaker")
+}
+
+// ── Features ────────────────────────────────────────────────────────
+
+/// In-place radix-2 FFT of `re` + i·`im` (length a power of two).
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+fn mel(hz: f32) -> f32 {
+    1127.0 * (1.0 + hz / 700.0).ln()
+}
+
+/// Triangular mel filters over the FFT power bins, 20 Hz to Nyquist.
+fn mel_filters() -> Vec<Vec<f32>> {
+    let (low, high) = (mel(20.0), mel(SAMPLE_RATE / 2.0));
+    let step = (high - low) / (MEL_BINS + 1) as f32;
+    (0..MEL_BINS)
+        .map(|m| {
+            let (left, center, right) = (
+                low + m as f32 * step,
+                low + (m + 1) as f32 * step,
+                low + (m + 2) as f32 * step,
+            );
+            (0..FFT_SIZE / 2)
+                .map(|bin| {
+                    let f = mel(bin as f32 * SAMPLE_RATE / FFT_SIZE as f32);
+                    if f <= left || f >= right {
+                        0.0
+                    } else if f <= center {
+                        (f - left) / (center - left)
+                    } else {
+                        (right - f) / (right - center)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// 80-bin log-mel filterbank features (Kaldi-style, mean-normalized per
+/// bin), one row per 10ms frame. Returns the frame count and the features
+/// row by row.
+pub fn fbank(audio: &[f32]) -> (usize, Vec<f32>) {
+    if audio.len() < FRAME_LEN {
+        return (0, Vec::new());
+    }
+    let frames = 1 + (audio.len() - FRAME_LEN) / FRAME_SHIFT;
+    let filters = mel_filters();
+    // Povey window
+    let window: Vec<f32> = (0..FRAME_LEN)
+        .map(|i| (0.5 - 0.5 * (2.0 * PI * i as f32 / (FRAME_LEN - 1) as f32).cos()).powf(0.85))
+        .collect();
+
+    let mut feats = Vec::with_capacity(frames * MEL_BINS);
+    let (mut re, mut im) = (vec![0.0f32; FFT_SIZE], vec![0.0f32; FFT_SIZE]);
+    for f in 0..frames {
+        // Kaldi works on 16-bit sample values
+        let frame: Vec<f32> = audio[f * FRAME_SHIFT..f * FRAME_SHIFT + FRAME_LEN]
+            .iter()
+            .map(|s| s * 32768.0)
+            .collect();
+        let mean = frame.iter().sum::<f32>() / FRAME_LEN as f32;
+        re.fill(0.0);
+        im.fill(0.0);
+        for i in 0..FRAME_LEN {
+            let prev = if i == 0 { frame[0] } else { frame[i - 1] };
+            re[i] = ((frame[i] - mean) - PREEMPHASIS * (prev - mean)) * window[i];
+        }
+        fft(&mut re, &mut im);
+        let power: Vec<f32> = (0..FFT_SIZE / 2)
+            .map(|k| re[k] * re[k] + im[k] * im[k])
+            .collect();
+        for filter in &filters {
+            let energy: f32 = filter.iter().zip(&power).map(|(w, p)| w * p).sum();
+            feats.push(energy.max(f32::EPSILON).ln());
+        }
+    }
+
+    for bin in 0..MEL_BINS {
+        let mean = (0..frames).map(|f| feats[f * MEL_BINS + bin]).sum::<f32>() / frames as f32;
+        for f in 0..frames {
+            feats[f * MEL_BINS + bin] -= mean;
+        }
+    }
+    (frames, feats)
+}
+
+/// Cosine similarity of two embeddings (0.0 when either is all zeros or
+/// their lengths differ).
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom > 0.0 {
+        dot / denom
+    } else {
+        0.0
+    }
+}
+
+// ── Encoder ─────────────────────────────────────────────────────────
+
+#[cfg(feature = "onnx")]
+mod encoder {
+    use super::*;
+
+    use ort::session::Session;
+    use ort::value::Tensor;
+
+    fn infer_err(e: impl std::fmt::Display) -> AppError {
+        AppError::Internal(format!("Speaker model failed: {}", e))
+    }
+
+    /// The speaker embedding model.
+    pub struct SpeakerEncoder {
+        session: Mutex<Session>,
+    }
+
+    impl SpeakerEncoder {
+        /// Load the model from `models/speaker`.
+        pub fn load(data_dir: &Path) -> Result<Self, AppError> {
+            let path = model_dir(data_dir).join(SPEAKER_MODEL_FILE);
+            if !path.exists() {
+                return Err(AppError::ModelMissing(format!(
+                    "Speaker model not found: {}",
+                    path.display()
+                )));
+            }
+            let session = Session::builder()
+                .map_err(|e| AppError::Internal(format!("ONNX session builder failed: {}", e)))?
+                .commit_from_file(&path)
+                .map_err(|e| AppError::Internal(format!("Failed to load speaker model: {}", e)))?;
+            tracing::info!(path = %path.display(), "Speaker model loaded (ONNX)");
+            Ok(Self {
+                session: Mutex::new(session),
+            })
+        }
+
+        /// Voice embedding of a 16 kHz recording.
+        pub fn embed(&self, audio: &[f32]) -> Result<Vec<f32>, AppError> {
+            if audio.len() < MIN_SAMPLES {
+                return Err(AppError::Stt(
+                    "Recording too short to identify the speaker".into(),
+                ));
+            }
+            let (frames, feats) = fbank(audio);
+            let input = Tensor::from_array((vec![1i64, frames as i64, MEL_BINS as i64], feats))
+                .map_err(infer_err)?;
+            let mut session = self
+                .session
+                .lock()
+                .map_err(|e| AppError::Internal(format!("Speaker model lock poisoned: {}", e)))?;
+            let outputs = session
+                .run(ort::inputs! { "x" => input })
+                .map_err(infer_err)?;
+            let (_, embedding) = outputs[0].try_extract_tensor::<f32>().map_err(infer_err)?;
+            Ok(embedding.to_vec())
+        }
+    }
+}
+
+#[cfg(not(feature = "onnx"))]
+mod encoder {
+    use super::*;
+
+    /// Stand-in for builds without ONNX Runtime; never loads.
+    pub struct SpeakerEncoder;
+
+    impl SpeakerEncoder {
+        pub fn load(_data_dir: &Path) -> Result<Self, AppError> {
+            Err(AppError::Config(
+                "Speaker identification needs a build with the onnx feature".into(),
+            ))
+        }
+
+        pub fn embed(&self, _audio: &[f32]) -> Result<Vec<f32>, AppError> {
+            Err(AppError::Config(
+                "Speaker identification needs a build with the onnx feature".into(),
+            ))
+        }
+    }
+}
+
+pub use encoder::SpeakerEncoder;
+
+// ── Profiles ────────────────────────────────────────────────────────
+
+/// An enrolled speaker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerProfile {
+    pub name: String,
+    /// Mean embedding of the enrollment recordings.
+    pub embedding: Vec<f32>,
+    /// How many recordings the mean is over.
+    pub samples: u32,
+}
+
+/// Who a recording sounds like.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeakerMatch {
+    /// The best-matching profile, if its similarity reaches the threshold.
+    pub speaker: Option<String>,
+    /// Similarity to the best-matching profile (-1.0..1.0).
+    pub similarity: f32,
+}
+
+fn profiles_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(PROFILES_FILE)
+}
+
+/// The enrolled speakers. A missing or unreadable file means none.
+pub fn load_profiles(data_dir: &Path) -> Vec<SpeakerProfile> {
+    fs::read_to_string(profiles_path(data_dir))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_profiles(data_dir: &Path, profiles: &[SpeakerProfile]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(profiles).map_err(|e| e.to_string())?;
+    fs::write(profiles_path(data_dir), json)
+        .map_err(|e| format!("Failed to save speaker profiles: {}", e))
+}
+
+/// Add `embeddings` to `name`'s profile, creating it if needed. Re-enrolling
+/// refines the existing mean rather than replacing it.
+pub fn enroll(
+    data_dir: &Path,
+    name: &str,
+    embeddings: &[Vec<f32>],
+) -> Result<SpeakerProfile, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Speaker name is empty".into());
+    }
+    let Some(dim) = embeddings.first().map(Vec::len) else {
+        return Err("No recordings to enroll".into());
+    };
+    if embeddings.iter().any(|e| e.len() != dim) {
+        return Err("Embeddings have different sizes".into());
+    }
+
+    let _guard = WRITE_LOCK.lock();
+    let mut profiles = load_profiles(data_dir);
+    let index = match profiles.iter().position(|p| p.name == name) {
+        Some(i) if profiles[i].embedding.len() == dim => i,
+        Some(i) => {
+            // Enrolled with a different model; start over
+            profiles[i].embedding = vec![0.0; dim];
+            profiles[i].samples = 0;
+            i
+        }
+        None => {
+            profiles.push(SpeakerProfile {
+                name: name.to_string(),
+                embedding: vec![0.0; dim],
+                samples: 0,
+            });
+            profiles.len() - 1
+        }
+    };
+
+    let profile = &mut profiles[index];
+    let old = profile.samples as f32;
+    let total = old + embeddings.len() as f32;
+    for (d, value) in profile.embedding.iter_mut().enumerate() {
+        let sum: f32 = embeddings.iter().map(|e| e[d]).sum();
+        *value = (*value * old + sum) / total;
+    }
+    profile.samples += embeddings.len() as u32;
+    let profile = profile.clone();
+    save_profiles(data_dir, &profiles)?;
+    Ok(profile)
+}
+
+/// Delete `name`'s profile. Returns whether it existed.
+pub fn delete(data_dir: &Path, name: &str) -> Result<bool, String> {
+    let _guard = WRITE_LOCK.lock();
+    let mut profiles = load_profiles(data_dir);
+    let before = profiles.len();
+    profiles.retain(|p| p.name != name);
+    if profiles.len() == before {
+        return Ok(false);
+    }
+    save_profiles(data_dir, &profiles)?;
+    Ok(true)
+}
+
+/// The profile `embedding` is closest to. `None` when nobody is enrolled.
+pub fn best_match(
+    embedding: &[f32],
+    profiles: &[SpeakerProfile],
+    threshold: f32,
+) -> Option<SpeakerMatch> {
+    profiles
+        .iter()
+        .map(|p| (p, cosine(embedding, &p.embedding)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(profile, similarity)| SpeakerMatch {
+            speaker: (similarity >= threshold).then(|| profile.name.clone()),
+            similarity,
+        })
+}
+
+/// Who `audio` sounds like, or `None` when nobody is enrolled or the
+/// recording can't be embedded (logged).
+pub fn identify(
+    encoder: &SpeakerEncoder,
+    audio: &[f32],
+    data_dir: &Path,
+    threshold: f32,
+) -> Option<SpeakerMatch> {
+    let profiles = load_profiles(data_dir);
+    if profiles.is_empty() || audio.len() < MIN_SAMPLES {
+        return None;
+    }
+    match encoder.embed(audio) {
+        Ok(embedding) => best_match(&embedding, &profiles, threshold),
+        Err(e) => {
+            tracing::warn!("Speaker identification failed: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fbank_shape_and_tone() {
+        // 1 kHz tone: energy peaks in the filter around 1 kHz
+        let audio: Vec<f32> = (0..16_000)
+            .map(|i| 0.5 * (2.0 * PI * 1000.0 * i as f32 / SAMPLE_RATE).sin())
+            .collect();
+        let (frames, feats) = fbank(&audio);
+        assert_eq!(frames, 98);
+        assert_eq!(feats.len(), frames * MEL_BINS);
+        // Mean-normalized per bin
+        let bin_mean: f32 = (0..frames).map(|f| feats[f * MEL_BINS + 10]).sum::<f32>();
+        assert!(bin_mean.abs() < 1e-2);
+        assert_eq!(fbank(&audio[..100]).0, 0);
+    }
+
+    #[test]
+    fn test_enroll_and_match() {
+        let data_dir = std::env::temp_dir().join(format!(
+            "vm-speakers-{}",
+            crate::services::turn_archive::next_id()
+        ));
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let profile = enroll(&data_dir, " Ada ", &[vec![1.0, 0.0], vec![0.0, 1.0]]).unwrap();
+        assert_eq!(profile.name, "Ada");
+        assert_eq!(profile.embedding, vec![0.5, 0.5]);
+        let profile = enroll(&data_dir, "Ada", &[vec![1.0, 1.0]]).unwrap();
+        assert_eq!(profile.samples, 3);
+        assert!((profile.embedding[0] - 2.0 / 3.0).abs() < 1e-6);
+        enroll(&data_dir, "Tv", &[vec![-1.0, 0.2]]).unwrap();
+
+        let profiles = load_profiles(&data_dir);
+        let hit = best_match(&[0.9, 1.0], &profiles, 0.5).unwrap();
+        assert_eq!(hit.speaker.as_deref(), Some("Ada"));
+        let miss = best_match(&[1.0, -1.0], &profiles, 0.5).unwrap();
+        assert_eq!(miss.speaker, None);
+        assert!(best_match(&[1.0, 0.0], &[], 0.5).is_none());
+
+        assert!(delete(&data_dir, "Tv").unwrap());
+        assert!(!delete(&data_dir, "Tv").unwrap());
+        assert_eq!(load_profiles(&data_dir).len(), 1);
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+}
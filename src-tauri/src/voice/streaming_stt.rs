@@ -0,0 +1,323 @@
+//! Incremental (streaming) transcription over a rolling audio window.
+//!
+//! Whisper has no native streaming mode, so partial transcripts come from
+//! re-decoding the audio heard so far every time enough new audio arrives.
+//! Consecutive passes disagree on the trailing words (the model is still
+//! "hearing" them), so words are only committed once they have been stable
+//! across the last N passes (local agreement). Committed words are never
+//! revised.
+//!
+//! The window is bounded: once it reaches `max_window_samples`, the latest
+//! hypothesis is committed in full and the window restarts from a short
+//! audio overlap. Words re-decoded from that overlap are matched against
+//! the tail of the committed text and dropped, so nothing is emitted twice.
+
+use std::collections::VecDeque;
+
+use super::stt::SttError;
+
+/// Number of trailing committed words carried across a window trim and
+/// matched against the start of the next window's hypotheses.
+const CARRY_WORDS: usize = 8;
+
+/// Tuning for [`StreamingTranscriber`]. All sizes are 16kHz mono samples.
+#[derive(Debug, Clone)]
+pub struct StreamingConfig {
+    /// New audio required between decode passes.
+    pub step_samples: usize,
+    /// Minimum window length before the first decode pass.
+    pub min_samples: usize,
+    /// Window length at which the hypothesis is committed and the window trimmed.
+    pub max_window_samples: usize,
+    /// Audio kept from the end of a trimmed window so boundary words aren't cut.
+    pub overlap_samples: usize,
+    /// Consecutive passes a word must survive unchanged before it's committed.
+    pub stable_passes: usize,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            step_samples: 16_000,        // 1s
+            min_samples: 16_000,         // 1s
+            max_window_samples: 240_000, // 15s
+            overlap_samples: 8_000,      // 0.5s
+            stable_passes: 2,
+        }
+    }
+}
+
+/// Rolling-window streaming transcriber with local-agreement word commits.
+///
+/// Engine-agnostic: each call takes the decode function to run over the
+/// current window, so the same logic serves every `SttEngine`.
+pub struct StreamingTranscriber {
+    config: StreamingConfig,
+    /// Audio since the last window trim (plus the carried overlap).
+    window: Vec<f32>,
+    /// Samples added since the last decode pass.
+    since_decode: usize,
+    /// Recent hypotheses for the current window, newest last.
+    history: VecDeque<Vec<String>>,
+    /// Words of the current window's hypothesis that are already committed.
+    window_committed: usize,
+    /// Tail of the committed text that the overlap audio may re-decode.
+    carry: Vec<String>,
+    /// All words committed for the current utterance.
+    committed: Vec<String>,
+}
+
+impl StreamingTranscriber {
+    pub fn new(config: StreamingConfig) -> Self {
+        Self {
+            config,
+            window: Vec::new(),
+            since_decode: 0,
+            history: VecDeque::new(),
+            window_committed: 0,
+            carry: Vec::new(),
+            committed: Vec::new(),
+        }
+    }
+
+    /// Add an audio chunk, decoding the window if a step's worth of new
+    /// audio has accumulated.
+    ///
+    /// Returns `Ok(Some(words))` with the newly committed words, or
+    /// `Ok(None)` when nothing new became stable.
+    pub fn push<F>(&mut self, chunk: &[f32], decode: F) -> Result<Option<String>, SttError>
+    where
+        F: FnOnce(&[f32]) -> Result<String, SttError>,
+    {
+        self.window.extend_from_slice(chunk);
+        self.since_decode += chunk.len();
+
+        if self.window.len() < self.config.min_samples
+            || self.since_decode < self.config.step_samples
+        {
+            return Ok(None);
+        }
+        self.since_decode = 0;
+
+        let words = self.hypothesis(&decode(&self.window)?);
+        self.history.push_back(words);
+        while self.history.len() > self.config.stable_passes.max(1) {
+            self.history.pop_front();
+        }
+
+        let mut newly = Vec::new();
+        if self.history.len() >= self.config.stable_passes.max(1) {
+            let stable = common_prefix_len(&self.history);
+            if stable > self.window_committed {
+                let latest = &self.history[self.history.len() - 1];
+                newly.extend_from_slice(&latest[self.window_committed..stable]);
+                self.window_committed = stable;
+            }
+        }
+
+        if self.window.len() >= self.config.max_window_samples {
+            // Window full: commit everything the latest pass heard and
+            // restart from a short overlap.
+            if let Some(latest) = self.history.back() {
+                if latest.len() > self.window_committed {
+                    newly.extend_from_slice(&latest[self.window_committed..]);
+                }
+            }
+            self.commit(&newly);
+            self.trim_window();
+        } else {
+            self.commit(&newly);
+        }
+
+        if newly.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(newly.join(" ")))
+        }
+    }
+
+    /// Decode whatever audio remains and commit the rest of the utterance.
+    ///
+    /// Returns the words committed by this final pass and resets the
+    /// transcriber for the next utterance.
+    pub fn finish<F>(&mut self, decode: F) -> Result<String, SttError>
+    where
+        F: FnOnce(&[f32]) -> Result<String, SttError>,
+    {
+        let mut rest = Vec::new();
+        if !self.window.is_empty() {
+            let words = self.hypothesis(&decode(&self.window)?);
+            if words.len() > self.window_committed {
+                rest.extend_from_slice(&words[self.window_committed..]);
+            }
+        }
+        self.reset();
+        Ok(rest.join(" "))
+    }
+
+    /// Full text committed so far for the current utterance.
+    pub fn committed_text(&self) -> String {
+        self.committed.join(" ")
+    }
+
+    /// Drop all audio and transcript state.
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.since_decode = 0;
+        self.history.clear();
+        self.window_committed = 0;
+        self.carry.clear();
+        self.committed.clear();
+    }
+
+    /// Split a decoded window into words, dropping any leading words that
+    /// re-transcribe the overlap carried from the previous window.
+    fn hypothesis(&self, text: &str) -> Vec<String> {
+        let words: Vec<String> = text.split_whitespace().map(String::from).collect();
+        let skip = overlap_len(&self.carry, &words);
+        words[skip..].to_vec()
+    }
+
+    fn commit(&mut self, words: &[String]) {
+        self.committed.extend_from_slice(words);
+    }
+
+    fn trim_window(&mut self) {
+        let keep = self.config.overlap_samples.min(self.window.len());
+        self.window.drain(..self.window.len() - keep);
+        self.since_decode = 0;
+        self.history.clear();
+        self.window_committed = 0;
+        let start = self.committed.len().saturating_sub(CARRY_WORDS);
+        self.carry = self.committed[start..].to_vec();
+    }
+}
+
+/// Normalize a word for agreement checks: lowercase, punctuation stripped.
+fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '\'')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Length of the word prefix shared by every hypothesis.
+fn common_prefix_len(history: &VecDeque<Vec<String>>) -> usize {
+    let Some(first) = history.front() else {
+        return 0;
+    };
+    let mut len = first.len();
+    for hyp in history.iter().skip(1) {
+        len = len.min(
+            first
+                .iter()
+                .zip(hyp)
+                .take_while(|(a, b)| normalize(a) == normalize(b))
+                .count(),
+        );
+    }
+    len
+}
+
+/// Longest `k` such that the first `k` words of `words` repeat the last `k`
+/// words of `carry`.
+fn overlap_len(carry: &[String], words: &[String]) -> usize {
+    let max = carry.len().min(words.len());
+    (1..=max)
+        .rev()
+        .find(|&k| {
+            carry[carry.len() - k..]
+                .iter()
+                .zip(&words[..k])
+                .all(|(a, b)| normalize(a) == normalize(b))
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_config() -> StreamingConfig {
+        StreamingConfig {
+            step_samples: 10,
+            min_samples: 10,
+            max_window_samples: 1_000,
+            overlap_samples: 5,
+            stable_passes: 2,
+        }
+    }
+
+    fn ok(text: &str) -> impl FnOnce(&[f32]) -> Result<String, SttError> + '_ {
+        move |_| Ok(text.to_string())
+    }
+
+    #[test]
+    fn test_waits_for_step() {
+        let mut st = StreamingTranscriber::new(small_config());
+        let out = st
+            .push(&[0.0; 5], |_| panic!("should not decode yet"))
+            .unwrap();
+        assert_eq!(out, None);
+    }
+
+    #[test]
+    fn test_commits_only_stable_words() {
+        let mut st = StreamingTranscriber::new(small_config());
+        // First pass: nothing can be stable yet
+        assert_eq!(st.push(&[0.0; 10], ok("hello wor")).unwrap(), None);
+        // Second pass agrees on "hello" only
+        assert_eq!(
+            st.push(&[0.0; 10], ok("hello world how")).unwrap().as_deref(),
+            Some("hello")
+        );
+        // Third pass agrees with the second on "world how"
+        assert_eq!(
+            st.push(&[0.0; 10], ok("Hello world, how are")).unwrap().as_deref(),
+            Some("world, how")
+        );
+        assert_eq!(st.committed_text(), "hello world, how");
+
+        let rest = st.finish(ok("hello world how are you")).unwrap();
+        assert_eq!(rest, "are you");
+        assert_eq!(st.committed_text(), "");
+    }
+
+    #[test]
+    fn test_window_trim_dedupes_overlap() {
+        let mut config = small_config();
+        config.max_window_samples = 20;
+        let mut st = StreamingTranscriber::new(config);
+
+        assert_eq!(st.push(&[0.0; 10], ok("one two")).unwrap(), None);
+        // Window full: the whole latest hypothesis is committed
+        assert_eq!(
+            st.push(&[0.0; 10], ok("one two three")).unwrap().as_deref(),
+            Some("one two three")
+        );
+        // The overlap re-decodes "three"; it must not be emitted again
+        assert_eq!(st.push(&[0.0; 10], ok("three four")).unwrap(), None);
+        assert_eq!(
+            st.push(&[0.0; 10], ok("three four five")).unwrap().as_deref(),
+            Some("four five")
+        );
+    }
+
+    #[test]
+    fn test_decode_error_propagates() {
+        let mut st = StreamingTranscriber::new(small_config());
+        let result = st.push(&[0.0; 10], |_| {
+            Err(SttError::TranscriptionError("boom".into()))
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_overlap_len() {
+        let carry: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let words: Vec<String> = ["B", "c.", "d"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(overlap_len(&carry, &words), 2);
+        assert_eq!(overlap_len(&carry, &["x".to_string()]), 0);
+        assert_eq!(overlap_len(&[], &words), 0);
+    }
+}
@@ -15,6 +15,10 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 
+use super::streaming_stt::{StreamingConfig, StreamingTranscriber};
+use super::stt_context::TranscriptContext;
+use crate::services::model_manager;
+
 // ── STT Engine Trait ────────────────────────────────────────────────
 
 /// Common trait for all Speech-to-Text engines.
@@ -30,18 +34,37 @@ pub trait SttEngine: Send + Sync {
     /// uploads the audio and waits for the response.
     fn transcribe(&self, audio: &[f32]) -> Result<String, SttError>;
 
-    /// Process a streaming audio chunk and return a partial transcript
-    /// if enough audio has accumulated.
+    /// Process a streaming audio chunk and return newly committed words.
+    ///
+    /// The utterance is re-decoded over a rolling window and words are only
+    /// committed once they've been stable across several passes (see
+    /// [`StreamingTranscriber`](super::streaming_stt::StreamingTranscriber)).
     ///
-    /// Returns `Ok(Some(text))` when a partial transcript is available,
-    /// `Ok(None)` when more audio is needed, or an error on failure.
+    /// Returns `Ok(Some(words))` when new words became stable, `Ok(None)`
+    /// when nothing new was committed, or an error on failure.
     fn transcribe_streaming(&self, audio_chunk: &[f32]) -> Result<Option<String>, SttError>;
 
+    /// End the streaming utterance: decode the remaining audio, return the
+    /// words not yet committed, and reset streaming state.
+    fn finish_streaming(&self) -> Result<String, SttError>;
+
     /// Get the engine name for display/logging.
     fn name(&self) -> &str;
 
     /// Whether the engine is ready to process audio.
     fn is_ready(&self) -> bool;
+
+    /// Language of the most recent transcription (ISO 639-1, e.g. "en").
+    ///
+    /// For a fixed language this is the configured one; with `"auto"` it is
+    /// whatever the model detected. `None` when unknown.
+    fn detected_language(&self) -> Option<String>;
+
+    /// Record a final transcription as context for the next one.
+    ///
+    /// Only engines that support prompt conditioning use it; streaming
+    /// windows are never passed here, just finished utterances.
+    fn remember_transcript(&self, _text: &str) {}
 }
 
 // ── STT Error ───────────────────────────────────────────────────────
@@ -84,6 +107,9 @@ impl std::error::Error for SttError {}
 struct ModelDescriptor {
     /// Filename on disk (e.g., "ggml-base.en.bin").
     filename: &'static str,
+    /// Filename of the multilingual variant (e.g., "ggml-base.bin").
+    /// Empty when `filename` is already multilingual.
+    multilingual_filename: &'static str,
     /// HuggingFace repo path (e.g., "ggerganov/whisper.cpp").
     repo: &'static str,
 }
@@ -96,42 +122,170 @@ fn model_descriptor(size: &str) -> ModelDescriptor {
     match size {
         "tiny" => ModelDescriptor {
             filename: "ggml-tiny.en.bin",
+            multilingual_filename: "ggml-tiny.bin",
             repo: "ggerganov/whisper.cpp",
         },
         "base" => ModelDescriptor {
             filename: "ggml-base.en.bin",
+            multilingual_filename: "ggml-base.bin",
             repo: "ggerganov/whisper.cpp",
         },
         "small" => ModelDescriptor {
             filename: "ggml-small.en.bin",
+            multilingual_filename: "ggml-small.bin",
             repo: "ggerganov/whisper.cpp",
         },
+        // The large models only ship multilingual weights.
         "large-v3-turbo" => ModelDescriptor {
             filename: "ggml-large-v3-turbo-q5_0.bin",
+            multilingual_filename: "",
             repo: "ggerganov/whisper.cpp",
         },
         "large-v3" => ModelDescriptor {
             filename: "ggml-large-v3.bin",
+            multilingual_filename: "",
             repo: "ggerganov/whisper.cpp",
         },
         // Legacy / unknown — fall back to old naming convention
         _ => ModelDescriptor {
             filename: "",
+            multilingual_filename: "",
             repo: "ggerganov/whisper.cpp",
         },
     }
 }
 
-/// Get the model filename for a given size, using the descriptor registry.
+/// Whisper sizes offered for download, smallest first.
+pub const WHISPER_MODEL_SIZES: &[&str] = &["tiny", "base", "small", "large-v3-turbo", "large-v3"];
+
+/// HuggingFace download URL for the model file `size` + `language` resolve to.
+pub fn model_download_url(size: &str, language: &str) -> String {
+    format!(
+        "https://huggingface.co/{}/resolve/main/{}",
+        model_descriptor(size).repo,
+        model_filename_for_language(size, language)
+    )
+}
+
+/// Whether a configured STT language needs a multilingual model.
+///
+/// Only plain English can use the smaller `.en` weights; every other
+/// language (and `"auto"` detection) needs the multilingual variant.
+pub fn needs_multilingual_model(language: &str) -> bool {
+    !language.trim().eq_ignore_ascii_case("en")
+}
+
+/// Get the (English) model filename for a given size, using the descriptor registry.
 pub fn model_filename(size: &str) -> String {
+    model_filename_for_language(size, "en")
+}
+
+/// Get the model filename for a given size and STT language.
+///
+/// English resolves to the `.en` weights; any other language (or `"auto"`)
+/// resolves to the multilingual variant, e.g. `("base", "de")` ->
+/// `"ggml-base.bin"`. Unknown sizes fall back to the legacy naming pattern.
+pub fn model_filename_for_language(size: &str, language: &str) -> String {
     let desc = model_descriptor(size);
+    let multilingual = needs_multilingual_model(language);
     if desc.filename.is_empty() {
-        format!("ggml-{}.en.bin", size)
+        return if multilingual {
+            format!("ggml-{}.bin", size)
+        } else {
+            format!("ggml-{}.en.bin", size)
+        };
+    }
+    if multilingual && !desc.multilingual_filename.is_empty() {
+        desc.multilingual_filename.to_string()
     } else {
         desc.filename.to_string()
     }
 }
 
+/// Language codes whisper.cpp accepts (`whisper_lang_id`), in its own order.
+const WHISPER_LANGUAGES: &[&str] = &[
+    "en", "zh", "de", "es", "ru", "ko", "fr", "ja", "pt", "tr", "pl", "ca", "nl", "ar",
+    "sv", "it", "id", "hi", "fi", "vi", "he", "uk", "el", "ms", "cs", "ro", "da", "hu",
+    "ta", "no", "th", "ur", "hr", "bg", "lt", "la", "mi", "ml", "cy", "sk", "te", "fa",
+    "lv", "bn", "sr", "az", "sl", "kn", "et", "mk", "br", "eu", "is", "hy", "ne", "mn",
+    "bs", "kk", "sq", "sw", "gl", "mr", "pa", "si", "km", "sn", "yo", "so", "af", "oc",
+    "ka", "be", "tg", "sd", "gu", "am", "yi", "lo", "uz", "fo", "ht", "ps", "tk", "nn",
+    "mt", "sa", "lb", "my", "bo", "tl", "mg", "as", "tt", "haw", "ln", "ha", "ba", "jw",
+    "su", "yue",
+];
+
+/// Validate a configured STT language against the codes whisper supports.
+///
+/// Returns `"auto"` for auto-detection (`"auto"` or empty) or a lowercased
+/// code. Locale tags like `"en-US"` reduce to their primary code. Anything
+/// else (`"english"`, typos) falls back to `"en"` with a warning -- passing it
+/// through would make whisper.cpp fail on every inference and select a
+/// multilingual model that is probably not downloaded.
+pub fn normalize_stt_language(language: &str) -> String {
+    let lang = language.trim().to_lowercase();
+    if lang.is_empty() || lang == "auto" {
+        return "auto".into();
+    }
+    let primary = lang.split(['-', '_']).next().unwrap_or_default();
+    if WHISPER_LANGUAGES.contains(&primary) {
+        return primary.to_string();
+    }
+    tracing::warn!(language, "Unsupported STT language, falling back to English");
+    "en".into()
+}
+
+/// Normalize a configured language for whisper.
+///
+/// Returns `None` for auto-detection (`"auto"` or empty), otherwise the
+/// lowercased ISO 639-1 code.
+pub fn whisper_language(language: &str) -> Option<String> {
+    let lang = language.trim().to_lowercase();
+    if lang.is_empty() || lang == "auto" {
+        None
+    } else {
+        Some(lang)
+    }
+}
+
+/// What whisper does with the speech (`voice.sttTask`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SttTask {
+    /// Write down what was said, in the language it was said in.
+    #[default]
+    Transcribe,
+    /// Translate the speech to English text. Needs a multilingual model;
+    /// the source language is still reported (see `detected_language`).
+    Translate,
+}
+
+impl SttTask {
+    /// Parse a config value; unknown values fall back to `Transcribe`.
+    pub fn parse(s: &str) -> Self {
+        match s.trim() {
+            "translate" => Self::Translate,
+            _ => Self::Transcribe,
+        }
+    }
+}
+
+/// GPU backend compiled into whisper.cpp, if any.
+///
+/// Chosen at build time via the `cuda`, `metal` or `vulkan` cargo feature.
+/// With no backend compiled in, `stt_use_gpu` has no effect and inference
+/// runs on the CPU.
+pub fn whisper_gpu_backend() -> Option<&'static str> {
+    if cfg!(feature = "cuda") {
+        Some("cuda")
+    } else if cfg!(feature = "metal") {
+        Some("metal")
+    } else if cfg!(feature = "vulkan") {
+        Some("vulkan")
+    } else {
+        None
+    }
+}
+
 // ── Model Auto-Download ─────────────────────────────────────────────
 
 /// Progress event emitted during model download.
@@ -146,13 +300,17 @@ pub struct SttDownloadProgress {
 
 /// Ensure a whisper GGML model exists, downloading from HuggingFace if needed.
 ///
-/// Uses the model descriptor registry to resolve filenames. Downloads to a
-/// `.tmp` file first, then renames atomically to prevent corrupt partials.
-/// Emits `stt-download-progress` events via the AppHandle for UI feedback.
+/// Uses the model descriptor registry to resolve filenames and downloads via
+/// the model manager (`.tmp` + size and SHA-256 check + atomic rename,
+/// retrying corrupt downloads), which emits
+/// `model-download-progress`. Progress is also forwarded as the legacy
+/// `stt-download-progress` event for the settings and onboarding UI.
 ///
 /// # Arguments
 /// * `data_dir` - Application data directory
 /// * `model_size` - Model size identifier (e.g., "tiny", "base", "large-v3-turbo")
+/// * `language` - Configured STT language ("en", "de", "auto", ...). Anything
+///   other than English resolves to the multilingual weights.
 /// * `app_handle` - Optional Tauri AppHandle for emitting progress events
 ///
 /// # Returns
@@ -160,106 +318,46 @@ pub struct SttDownloadProgress {
 pub async fn ensure_model_exists(
     data_dir: &Path,
     model_size: &str,
+    language: &str,
     app_handle: Option<&AppHandle>,
 ) -> Result<PathBuf, SttError> {
-    let filename = model_filename(model_size);
-    let desc = model_descriptor(model_size);
+    let filename = model_filename_for_language(model_size, language);
     let models_dir = data_dir.join("models");
     let model_path = models_dir.join(&filename);
 
-    if model_path.exists() {
-        tracing::info!(path = %model_path.display(), "Whisper model already present");
-        return Ok(model_path);
-    }
+    // A present file still goes through the manager so it is checked
+    // against the bundled checksum manifest (and replaced if corrupt)
+    let asset = model_manager::whisper_asset(data_dir, model_size, language);
+    tracing::info!(model = %asset.id, dest = %model_path.display(), "Ensuring whisper model");
+    model_manager::download_asset(&asset, app_handle, |p| {
+        emit_legacy_progress(app_handle, model_size, p)
+    })
+    .await
+    .map_err(SttError::DownloadError)?;
 
-    // Create models directory
-    tokio::fs::create_dir_all(&models_dir)
-        .await
-        .map_err(|e| SttError::DownloadError(format!("Failed to create models dir: {}", e)))?;
-
-    let url = format!(
-        "https://huggingface.co/{}/resolve/main/{}",
-        desc.repo, filename
-    );
-
-    tracing::info!(url = %url, dest = %model_path.display(), "Downloading whisper model");
-
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| SttError::DownloadError(format!("HTTP request failed: {}", e)))?;
-
-    if !resp.status().is_success() {
-        return Err(SttError::DownloadError(format!(
-            "HTTP {} from {}",
-            resp.status(),
-            url
-        )));
-    }
-
-    let total_size = resp.content_length();
-
-    // Download to a temp file, then rename (atomic pattern)
-    let tmp_path = model_path.with_extension("bin.tmp");
-    let mut file = tokio::fs::File::create(&tmp_path)
-        .await
-        .map_err(|e| SttError::DownloadError(format!("Failed to create temp file: {}", e)))?;
-
-    use futures_util::StreamExt;
-    use tokio::io::AsyncWriteExt;
-
-    let mut downloaded: u64 = 0;
-    let mut last_progress: u8 = 0;
-    let mut stream = resp.bytes_stream();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk =
-            chunk.map_err(|e| SttError::DownloadError(format!("Download stream error: {}", e)))?;
-        file.write_all(&chunk)
-            .await
-            .map_err(|e| SttError::DownloadError(format!("Write error: {}", e)))?;
-        downloaded += chunk.len() as u64;
-
-        // Emit progress every ~5%
-        if let Some(total) = total_size {
-            let pct = ((downloaded as f64 / total as f64) * 100.0) as u8;
-            if pct >= last_progress + 5 {
-                last_progress = pct;
-                let downloaded_mb = downloaded as f64 / 1_048_576.0;
-                let total_mb = total as f64 / 1_048_576.0;
-                tracing::info!(
-                    "Downloading whisper {} model... {}% ({:.1} MB / {:.1} MB)",
-                    model_size, pct, downloaded_mb, total_mb
-                );
-                if let Some(handle) = app_handle {
-                    let _ = handle.emit("stt-download-progress", SttDownloadProgress {
-                        model_size: model_size.to_string(),
-                        percent: pct,
-                        downloaded_mb,
-                        total_mb,
-                    });
-                }
-            }
-        }
-    }
-
-    file.flush()
-        .await
-        .map_err(|e| SttError::DownloadError(format!("Flush error: {}", e)))?;
-    drop(file);
-
-    // Atomic rename from tmp to final path
-    tokio::fs::rename(&tmp_path, &model_path)
-        .await
-        .map_err(|e| SttError::DownloadError(format!("Rename failed: {}", e)))?;
-
-    tracing::info!(path = %model_path.display(), "Whisper model downloaded successfully");
+    tracing::info!(path = %model_path.display(), "Whisper model ready");
 
     Ok(model_path)
 }
 
+/// Re-emit a model manager progress event as `stt-download-progress`.
+fn emit_legacy_progress(
+    app_handle: Option<&AppHandle>,
+    model_size: &str,
+    progress: &model_manager::ModelDownloadProgress,
+) {
+    let Some(handle) = app_handle else {
+        return;
+    };
+    let total = progress.total_bytes.unwrap_or(0);
+    let _ = handle.emit("stt-download-progress", SttDownloadProgress {
+        model_size: model_size.to_string(),
+        percent: progress.percent,
+        downloaded_mb: progress.downloaded_bytes as f64 / 1_048_576.0,
+        total_mb: total as f64 / 1_048_576.0,
+    });
+}
+
 // ── Whisper STT (Real Implementation) ────────────────────────────────
 
 #[cfg(feature = "whisper")]
@@ -271,10 +369,6 @@ mod whisper_real {
     /// Minimum audio length in samples before processing (0.4s at 16kHz).
     const MIN_SAMPLES: usize = 6_400;
 
-    /// Minimum audio length for streaming before triggering transcription
-    /// (2 seconds at 16kHz).
-    const MIN_STREAMING_SAMPLES: usize = 32_000;
-
     /// Number of inference threads: half available cores, clamped to 1..=8.
     fn inference_threads() -> i32 {
         let cores = std::thread::available_parallelism()
@@ -283,6 +377,18 @@ mod whisper_real {
         (cores / 2).clamp(1, 8) as i32
     }
 
+    /// Load a whisper context, optionally on the compiled-in GPU backend.
+    fn load_context(model_path: &Path, use_gpu: bool) -> Result<WhisperContext, SttError> {
+        let mut ctx_params = WhisperContextParameters::default();
+        ctx_params.use_gpu = use_gpu;
+        // Flash attention gives extra speed on GPU (incompatible with DTW, which we don't use)
+        if use_gpu {
+            ctx_params.flash_attn = true;
+        }
+        WhisperContext::new_with_params(model_path.to_str().unwrap_or_default(), ctx_params)
+            .map_err(|e| SttError::ModelLoadError(format!("Failed to load whisper model: {}", e)))
+    }
+
     /// Holds the WhisperContext and a cached WhisperState.
     ///
     /// The state is lazily created on the first transcription and reused
@@ -306,8 +412,18 @@ mod whisper_real {
         inner: Arc<Mutex<WhisperInner>>,
         n_threads: i32,
         model_size: String,
+        /// Language passed to whisper. `None` = auto-detect.
+        language: Option<String>,
+        /// Transcribe, or translate to English.
+        task: SttTask,
+        /// Language of the most recent transcription (detected or configured).
+        last_language: Mutex<Option<String>>,
+        /// Whether the context was loaded on a GPU backend.
+        gpu_active: bool,
         ready: AtomicBool,
-        streaming_buffer: Mutex<Vec<f32>>,
+        streaming: Mutex<StreamingTranscriber>,
+        /// Recent transcriptions, passed to whisper as the initial prompt.
+        context: Mutex<TranscriptContext>,
     }
 
     impl WhisperStt {
@@ -315,12 +431,14 @@ mod whisper_real {
         ///
         /// # Arguments
         /// * `model_path` - Path to the GGML Whisper model file.
-        /// * `use_gpu` - Whether to use GPU acceleration (CUDA). Falls back to CPU if unavailable.
+        /// * `use_gpu` - Whether to use the compiled-in GPU backend (CUDA/Metal/Vulkan).
+        ///   Falls back to CPU if no backend is compiled in or GPU init fails.
+        /// * `language` - ISO 639-1 code, or `"auto"` to let whisper detect it.
         ///
         /// # Errors
         /// Returns `SttError::ModelNotFound` if the model file doesn't exist.
         /// Returns `SttError::ModelLoadError` if whisper-rs can't load the model.
-        pub fn new(model_path: &Path, use_gpu: bool) -> Result<Self, SttError> {
+        pub fn new(model_path: &Path, use_gpu: bool, language: &str) -> Result<Self, SttError> {
             if !model_path.exists() {
                 return Err(SttError::ModelNotFound(model_path.to_path_buf()));
             }
@@ -328,23 +446,44 @@ mod whisper_real {
             let model_size = guess_model_size(model_path);
             let n_threads = inference_threads();
 
-            let mut ctx_params = WhisperContextParameters::default();
-            ctx_params.use_gpu = use_gpu;
-            // Flash attention gives extra speed on GPU (incompatible with DTW, which we don't use)
-            if use_gpu {
-                ctx_params.flash_attn = true;
+            let gpu_backend = whisper_gpu_backend();
+            if use_gpu && gpu_backend.is_none() {
+                tracing::warn!(
+                    "GPU requested but no whisper GPU backend compiled in \
+                     (build with the cuda, metal or vulkan feature), using CPU"
+                );
+            }
+
+            let mut gpu_active = use_gpu && gpu_backend.is_some();
+            let ctx = match load_context(model_path, gpu_active) {
+                Ok(ctx) => ctx,
+                Err(e) if gpu_active => {
+                    tracing::warn!(
+                        backend = gpu_backend.unwrap_or_default(),
+                        error = %e,
+                        "Whisper GPU init failed, falling back to CPU"
+                    );
+                    gpu_active = false;
+                    load_context(model_path, false)?
+                }
+                Err(e) => return Err(e),
+            };
+
+            let language = whisper_language(language);
+            if language.as_deref() != Some("en") && !ctx.is_multilingual() {
+                tracing::warn!(
+                    language = ?language,
+                    "English-only whisper model loaded for a non-English language"
+                );
             }
-            let ctx = WhisperContext::new_with_params(
-                model_path.to_str().unwrap_or_default(),
-                ctx_params,
-            )
-            .map_err(|e| SttError::ModelLoadError(format!("Failed to load whisper model: {}", e)))?;
 
             tracing::info!(
                 model_path = %model_path.display(),
                 model_size = %model_size,
                 threads = n_threads,
                 use_gpu = use_gpu,
+                gpu_backend = if gpu_active { gpu_backend.unwrap_or("cpu") } else { "cpu" },
+                language = language.as_deref().unwrap_or("auto"),
                 "WhisperStt loaded (real whisper-rs)"
             );
 
@@ -355,19 +494,68 @@ mod whisper_real {
                 })),
                 n_threads,
                 model_size,
+                last_language: Mutex::new(language.clone()),
+                language,
+                task: SttTask::Transcribe,
+                gpu_active,
                 ready: AtomicBool::new(true),
-                streaming_buffer: Mutex::new(Vec::new()),
+                streaming: Mutex::new(StreamingTranscriber::new(StreamingConfig::default())),
+                context: Mutex::new(TranscriptContext::default()),
             })
         }
 
+        /// Carry up to `words` words of recent transcriptions into each
+        /// decode as whisper's initial prompt. 0 (the default) disables it.
+        pub fn with_context_words(self, words: usize) -> Self {
+            Self {
+                context: Mutex::new(TranscriptContext::new(words)),
+                ..self
+            }
+        }
+
+        /// Lead each decode's prompt with a fixed initial prompt and
+        /// vocabulary terms (see [`TranscriptContext::with_bias`]). Call
+        /// after `with_context_words`, which starts a fresh context.
+        pub fn with_prompt_bias(mut self, initial_prompt: &str, vocabulary: &[String]) -> Self {
+            let context = self.context.get_mut().unwrap_or_else(|e| e.into_inner());
+            *context = std::mem::take(context).with_bias(initial_prompt, vocabulary);
+            self
+        }
+
+        /// Set the whisper task. Translating from English is a no-op, and
+        /// an English-only model can't translate at all, so both are logged.
+        pub fn with_task(self, task: SttTask) -> Self {
+            if task == SttTask::Translate {
+                if self.language.as_deref() == Some("en") {
+                    tracing::info!("STT language is English; translate task has nothing to do");
+                } else if let Ok(inner) = self.inner.lock() {
+                    if !inner.ctx.is_multilingual() {
+                        tracing::warn!("English-only whisper model loaded; it cannot translate");
+                    }
+                }
+            }
+            Self { task, ..self }
+        }
+
         /// Create from a model size name, resolving the path in the data directory.
         ///
         /// Uses the model descriptor registry to resolve the correct filename
-        /// for each model size (e.g., "large-v3-turbo" -> "ggml-large-v3-turbo-q5_0.bin").
-        pub fn from_model_size(data_dir: &Path, size: &str, use_gpu: bool) -> Result<Self, SttError> {
-            let filename = model_filename(size);
+        /// for each model size and language (e.g., "large-v3-turbo" ->
+        /// "ggml-large-v3-turbo-q5_0.bin", ("base", "de") -> "ggml-base.bin").
+        pub fn from_model_size(
+            data_dir: &Path,
+            size: &str,
+            use_gpu: bool,
+            language: &str,
+        ) -> Result<Self, SttError> {
+            let filename = model_filename_for_language(size, language);
             let model_path = data_dir.join("models").join(filename);
-            Self::new(&model_path, use_gpu)
+            Self::new(&model_path, use_gpu, language)
+        }
+
+        /// Whether inference runs on a GPU backend (false after CPU fallback).
+        pub fn gpu_active(&self) -> bool {
+            self.gpu_active
         }
     }
 
@@ -421,7 +609,10 @@ mod whisper_real {
 
             // Configure inference parameters
             let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-            params.set_language(Some("en"));
+            // None = auto-detect (whisper picks the most likely language)
+            params.set_language(self.language.as_deref());
+            // Translate decodes English text; the source language is still detected
+            params.set_translate(self.task == SttTask::Translate);
             params.set_n_threads(self.n_threads);
             params.set_print_special(false);
             params.set_print_progress(false);
@@ -431,12 +622,30 @@ mod whisper_real {
             params.set_no_timestamps(true);
             // Suppress non-speech tokens to reduce hallucination on silence
             params.set_suppress_nst(true);
+            // Condition on what the user said last (names, unfinished sentences)
+            let prompt = self
+                .context
+                .lock()
+                .ok()
+                .and_then(|c| c.prompt(std::time::Instant::now()));
+            if let Some(ref prompt) = prompt {
+                params.set_initial_prompt(prompt);
+            }
 
             // Run inference
             state.full(params, audio).map_err(|e| {
                 SttError::TranscriptionError(format!("Whisper inference failed: {}", e))
             })?;
 
+            // Record the language whisper actually decoded in
+            let detected = match self.language {
+                Some(ref lang) => Some(lang.clone()),
+                None => whisper_rs::get_lang_str(state.full_lang_id_from_state()).map(String::from),
+            };
+            if let Ok(mut last) = self.last_language.lock() {
+                *last = detected.clone();
+            }
+
             // Collect transcribed text from all segments
             let num_segments = state.full_n_segments();
 
@@ -458,6 +667,7 @@ mod whisper_real {
             tracing::info!(
                 segments = num_segments,
                 text_len = text.len(),
+                language = detected.as_deref().unwrap_or("unknown"),
                 "Whisper transcription complete"
             );
 
@@ -469,25 +679,17 @@ mod whisper_real {
                 return Err(SttError::NotReady);
             }
 
-            let mut buffer = self.streaming_buffer.lock().map_err(|e| {
-                SttError::TranscriptionError(format!("Failed to lock streaming buffer: {}", e))
+            let mut streaming = self.streaming.lock().map_err(|e| {
+                SttError::TranscriptionError(format!("Failed to lock streaming state: {}", e))
             })?;
+            streaming.push(audio_chunk, |window| self.transcribe(window))
+        }
 
-            buffer.extend_from_slice(audio_chunk);
-
-            // Accumulate at least 2 seconds of audio before attempting transcription
-            if buffer.len() >= MIN_STREAMING_SAMPLES {
-                let audio = std::mem::take(&mut *buffer);
-                drop(buffer); // Release lock before transcription
-                let text = self.transcribe(&audio)?;
-                if text.is_empty() {
-                    Ok(None)
-                } else {
-                    Ok(Some(text))
-                }
-            } else {
-                Ok(None)
-            }
+        fn finish_streaming(&self) -> Result<String, SttError> {
+            let mut streaming = self.streaming.lock().map_err(|e| {
+                SttError::TranscriptionError(format!("Failed to lock streaming state: {}", e))
+            })?;
+            streaming.finish(|window| self.transcribe(window))
         }
 
         fn name(&self) -> &str {
@@ -497,6 +699,16 @@ mod whisper_real {
         fn is_ready(&self) -> bool {
             self.ready.load(Ordering::Relaxed)
         }
+
+        fn detected_language(&self) -> Option<String> {
+            self.last_language.lock().ok().and_then(|l| l.clone())
+        }
+
+        fn remember_transcript(&self, text: &str) {
+            if let Ok(mut context) = self.context.lock() {
+                context.push(text, std::time::Instant::now());
+            }
+        }
     }
 }
 
@@ -518,8 +730,10 @@ mod whisper_stub {
         ready: AtomicBool,
         /// Model size identifier.
         model_size: String,
-        /// Accumulated audio buffer for streaming transcription.
-        streaming_buffer: std::sync::Mutex<Vec<f32>>,
+        /// Configured language. `None` = auto-detect.
+        language: Option<String>,
+        /// Rolling-window state for streaming transcription.
+        streaming: std::sync::Mutex<StreamingTranscriber>,
     }
 
     impl WhisperStt {
@@ -527,7 +741,7 @@ mod whisper_stub {
         ///
         /// Always succeeds regardless of whether the model file exists.
         /// The `use_gpu` parameter is accepted for API compatibility but ignored in stub mode.
-        pub fn new(model_path: &Path, _use_gpu: bool) -> Result<Self, SttError> {
+        pub fn new(model_path: &Path, _use_gpu: bool, language: &str) -> Result<Self, SttError> {
             let model_size = guess_model_size(model_path);
 
             tracing::info!(
@@ -540,18 +754,47 @@ mod whisper_stub {
                 model_path: model_path.to_path_buf(),
                 ready: AtomicBool::new(true),
                 model_size,
-                streaming_buffer: std::sync::Mutex::new(Vec::new()),
+                language: whisper_language(language),
+                streaming: std::sync::Mutex::new(StreamingTranscriber::new(
+                    StreamingConfig::default(),
+                )),
             })
         }
 
         /// Create from a model size name, resolving the path in the data directory.
         ///
         /// Uses the model descriptor registry to resolve the correct filename
-        /// for each model size (e.g., "large-v3-turbo" -> "ggml-large-v3-turbo-q5_0.bin").
-        pub fn from_model_size(data_dir: &Path, size: &str, use_gpu: bool) -> Result<Self, SttError> {
-            let filename = model_filename(size);
+        /// for each model size and language (e.g., "large-v3-turbo" ->
+        /// "ggml-large-v3-turbo-q5_0.bin", ("base", "de") -> "ggml-base.bin").
+        pub fn from_model_size(
+            data_dir: &Path,
+            size: &str,
+            use_gpu: bool,
+            language: &str,
+        ) -> Result<Self, SttError> {
+            let filename = model_filename_for_language(size, language);
             let model_path = data_dir.join("models").join(filename);
-            Self::new(&model_path, use_gpu)
+            Self::new(&model_path, use_gpu, language)
+        }
+
+        /// Always false in stub mode.
+        pub fn gpu_active(&self) -> bool {
+            false
+        }
+
+        /// Accepted for API compatibility; the stub has no prompt to condition.
+        pub fn with_context_words(self, _words: usize) -> Self {
+            self
+        }
+
+        /// Accepted for API compatibility; the stub never decodes.
+        pub fn with_task(self, _task: SttTask) -> Self {
+            self
+        }
+
+        /// Accepted for API compatibility; the stub has no prompt to condition.
+        pub fn with_prompt_bias(self, _initial_prompt: &str, _vocabulary: &[String]) -> Self {
+            self
         }
     }
 
@@ -594,27 +837,17 @@ mod whisper_stub {
                 return Err(SttError::NotReady);
             }
 
-            let mut buffer = self.streaming_buffer.lock().map_err(|e| {
-                SttError::TranscriptionError(format!("Failed to lock streaming buffer: {}", e))
+            let mut streaming = self.streaming.lock().map_err(|e| {
+                SttError::TranscriptionError(format!("Failed to lock streaming state: {}", e))
             })?;
+            streaming.push(audio_chunk, |window| self.transcribe(window))
+        }
 
-            buffer.extend_from_slice(audio_chunk);
-
-            // Accumulate at least 2 seconds of audio (32000 samples at 16kHz)
-            const MIN_STREAMING_SAMPLES: usize = 32000;
-
-            if buffer.len() >= MIN_STREAMING_SAMPLES {
-                let audio = std::mem::take(&mut *buffer);
-                drop(buffer); // Release lock before transcription
-                let text = self.transcribe(&audio)?;
-                if text.is_empty() {
-                    Ok(None)
-                } else {
-                    Ok(Some(text))
-                }
-            } else {
-                Ok(None)
-            }
+        fn finish_streaming(&self) -> Result<String, SttError> {
+            let mut streaming = self.streaming.lock().map_err(|e| {
+                SttError::TranscriptionError(format!("Failed to lock streaming state: {}", e))
+            })?;
+            streaming.finish(|window| self.transcribe(window))
         }
 
         fn name(&self) -> &str {
@@ -624,6 +857,11 @@ mod whisper_stub {
         fn is_ready(&self) -> bool {
             self.ready.load(Ordering::Relaxed)
         }
+
+        fn detected_language(&self) -> Option<String> {
+            // No real inference, so nothing is ever detected in auto mode
+            self.language.clone()
+        }
     }
 }
 
@@ -635,11 +873,376 @@ pub use whisper_real::WhisperStt;
 #[cfg(not(feature = "whisper"))]
 pub use whisper_stub::WhisperStt;
 
+// ── Parakeet STT (ONNX) ──────────────────────────────────────────────
+
+/// HuggingFace repo with the ONNX export of NVIDIA Parakeet TDT 0.6B v2.
+const PARAKEET_REPO: &str = "istupakov/parakeet-tdt-0.6b-v2-onnx";
+
+/// Files Parakeet needs (int8 encoder/decoder, ~670MB total), stored in
+/// `models/parakeet`.
+pub const PARAKEET_FILES: &[&str] = &[
+    "nemo128.onnx",
+    "encoder-model.int8.onnx",
+    "decoder_joint-model.int8.onnx",
+    "vocab.txt",
+];
+
+/// HuggingFace download URL for one of the `PARAKEET_FILES`.
+pub fn parakeet_file_url(filename: &str) -> String {
+    format!(
+        "https://huggingface.co/{}/resolve/main/{}",
+        PARAKEET_REPO, filename
+    )
+}
+
+/// Directory Parakeet model files are loaded from.
+pub fn parakeet_model_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("models").join("parakeet")
+}
+
+/// Ensure all Parakeet ONNX files exist, downloading any that are missing.
+///
+/// Emits `stt-download-progress` events with `modelSize: "parakeet"`.
+/// Returns the model directory.
+pub async fn ensure_parakeet_model_exists(
+    data_dir: &Path,
+    app_handle: Option<&AppHandle>,
+) -> Result<PathBuf, SttError> {
+    let asset = model_manager::parakeet_asset(data_dir);
+    let model_dir = model_manager::download_asset(&asset, app_handle, |p| {
+        emit_legacy_progress(app_handle, "parakeet", p)
+    })
+    .await
+    .map_err(SttError::DownloadError)?;
+
+    tracing::info!(path = %model_dir.display(), "Parakeet model ready");
+    Ok(model_dir)
+}
+
+/// Parse a NeMo `vocab.txt` (`<token> <id>` per line) into an id-indexed table.
+#[cfg_attr(not(feature = "onnx"), allow(dead_code))]
+fn parse_parakeet_vocab(contents: &str) -> Result<Vec<String>, SttError> {
+    let mut entries = Vec::new();
+    for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+        let (token, id) = line
+            .rsplit_once(' ')
+            .ok_or_else(|| SttError::ModelLoadError(format!("Bad vocab line: {:?}", line)))?;
+        let id: usize = id
+            .trim()
+            .parse()
+            .map_err(|_| SttError::ModelLoadError(format!("Bad vocab id: {:?}", line)))?;
+        entries.push((id, token.to_string()));
+    }
+    let size = entries.iter().map(|(id, _)| id + 1).max().unwrap_or(0);
+    let mut vocab = vec![String::new(); size];
+    for (id, token) in entries {
+        vocab[id] = token;
+    }
+    Ok(vocab)
+}
+
+/// Join SentencePiece tokens into text (`▁` marks a word start).
+#[cfg_attr(not(feature = "onnx"), allow(dead_code))]
+fn decode_parakeet_tokens(vocab: &[String], tokens: &[usize]) -> String {
+    let mut text = String::new();
+    for &id in tokens {
+        match vocab.get(id) {
+            // Skip special tokens like <blk> / <unk>
+            Some(tok) if tok.starts_with('<') && tok.ends_with('>') => {}
+            Some(tok) => text.push_str(tok),
+            None => {}
+        }
+    }
+    text.replace('\u{2581}', " ").trim().to_string()
+}
+
+#[cfg(feature = "onnx")]
+mod parakeet_real {
+    use super::*;
+    use std::sync::Mutex;
+
+    use ort::session::Session;
+    use ort::value::Tensor;
+
+    /// Minimum audio length in samples before processing (0.4s at 16kHz).
+    const MIN_SAMPLES: usize = 6_400;
+    /// Prediction network shape (LSTM layers x hidden size).
+    const PRED_LAYERS: usize = 2;
+    const PRED_HIDDEN: usize = 640;
+    /// Frame advances the TDT duration head can predict.
+    const TDT_DURATIONS: [usize; 5] = [0, 1, 2, 3, 4];
+    /// Cap on tokens emitted without advancing a frame.
+    const MAX_TOKENS_PER_STEP: usize = 10;
+
+    struct Sessions {
+        preprocessor: Session,
+        encoder: Session,
+        decoder_joint: Session,
+    }
+
+    fn infer_err(e: impl std::fmt::Display) -> SttError {
+        SttError::TranscriptionError(format!("Parakeet inference failed: {}", e))
+    }
+
+    fn argmax(values: &[f32]) -> usize {
+        values
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    /// Local Parakeet TDT STT engine (English) using ONNX Runtime.
+    ///
+    /// Runs the NeMo mel preprocessor, the FastConformer encoder, and greedy
+    /// token-and-duration decoding over the joint network.
+    pub struct ParakeetStt {
+        sessions: Mutex<Sessions>,
+        vocab: Vec<String>,
+        blank_id: usize,
+        ready: AtomicBool,
+        streaming: Mutex<StreamingTranscriber>,
+    }
+
+    impl ParakeetStt {
+        /// Load the Parakeet ONNX models from `model_dir`.
+        ///
+        /// # Errors
+        /// Returns `SttError::ModelNotFound` if any file is missing, or
+        /// `SttError::ModelLoadError` if ONNX Runtime can't load a model.
+        pub fn new(model_dir: &Path) -> Result<Self, SttError> {
+            for filename in PARAKEET_FILES {
+                let path = model_dir.join(filename);
+                if !path.exists() {
+                    return Err(SttError::ModelNotFound(path));
+                }
+            }
+
+            let load = |name: &str| -> Result<Session, SttError> {
+                Session::builder()
+                    .map_err(|e| {
+                        SttError::ModelLoadError(format!("ONNX session builder failed: {}", e))
+                    })?
+                    .commit_from_file(model_dir.join(name))
+                    .map_err(|e| {
+                        SttError::ModelLoadError(format!("Failed to load {}: {}", name, e))
+                    })
+            };
+            let sessions = Sessions {
+                preprocessor: load("nemo128.onnx")?,
+                encoder: load("encoder-model.int8.onnx")?,
+                decoder_joint: load("decoder_joint-model.int8.onnx")?,
+            };
+
+            let vocab_text = std::fs::read_to_string(model_dir.join("vocab.txt"))
+                .map_err(|e| SttError::ModelLoadError(format!("Failed to read vocab: {}", e)))?;
+            let vocab = parse_parakeet_vocab(&vocab_text)?;
+            let blank_id = vocab
+                .iter()
+                .position(|t| t == "<blk>")
+                .unwrap_or(vocab.len().saturating_sub(1));
+
+            tracing::info!(
+                model_dir = %model_dir.display(),
+                vocab = vocab.len(),
+                "ParakeetStt loaded (ONNX)"
+            );
+
+            Ok(Self {
+                sessions: Mutex::new(sessions),
+                vocab,
+                blank_id,
+                ready: AtomicBool::new(true),
+                streaming: Mutex::new(StreamingTranscriber::new(StreamingConfig::default())),
+            })
+        }
+
+        fn run_inference(&self, audio: &[f32]) -> Result<String, SttError> {
+            let mut guard = self.sessions.lock().map_err(|e| {
+                SttError::TranscriptionError(format!("Failed to lock Parakeet sessions: {}", e))
+            })?;
+            let sessions = &mut *guard;
+
+            // 1. Waveform -> 128-bin log-mel features
+            let (feat_shape, feats, feat_len) = {
+                let outputs = sessions
+                    .preprocessor
+                    .run(ort::inputs! {
+                        "waveforms" => Tensor::from_array((vec![1i64, audio.len() as i64], audio.to_vec())).map_err(infer_err)?,
+                        "waveforms_lens" => Tensor::from_array((vec![1i64], vec![audio.len() as i64])).map_err(infer_err)?
+                    })
+                    .map_err(infer_err)?;
+                let (shape, data) = outputs["features"]
+                    .try_extract_tensor::<f32>()
+                    .map_err(infer_err)?;
+                let (_, lens) = outputs["features_lens"]
+                    .try_extract_tensor::<i64>()
+                    .map_err(infer_err)?;
+                (shape.to_vec(), data.to_vec(), lens.first().copied().unwrap_or(0))
+            };
+
+            // 2. Features -> encoder frames, shape [1, D, T]
+            let (enc_shape, encoded, enc_len) = {
+                let outputs = sessions
+                    .encoder
+                    .run(ort::inputs! {
+                        "audio_signal" => Tensor::from_array((feat_shape, feats)).map_err(infer_err)?,
+                        "length" => Tensor::from_array((vec![1i64], vec![feat_len])).map_err(infer_err)?
+                    })
+                    .map_err(infer_err)?;
+                let (shape, data) = outputs["outputs"]
+                    .try_extract_tensor::<f32>()
+                    .map_err(infer_err)?;
+                let (_, lens) = outputs["encoded_lengths"]
+                    .try_extract_tensor::<i64>()
+                    .map_err(infer_err)?;
+                (shape.to_vec(), data.to_vec(), lens.first().copied().unwrap_or(0))
+            };
+            if enc_shape.len() != 3 {
+                return Err(infer_err(format!("unexpected encoder shape {:?}", enc_shape)));
+            }
+            let dim = enc_shape[1] as usize;
+            let total_frames = enc_shape[2] as usize;
+            let frames = (enc_len.max(0) as usize).min(total_frames);
+
+            // 3. Greedy TDT decoding: each step predicts a token and how many
+            //    frames to advance.
+            let vocab_size = self.vocab.len();
+            let mut state1 = vec![0.0f32; PRED_LAYERS * PRED_HIDDEN];
+            let mut state2 = vec![0.0f32; PRED_LAYERS * PRED_HIDDEN];
+            let state_shape = vec![PRED_LAYERS as i64, 1, PRED_HIDDEN as i64];
+            let mut tokens: Vec<usize> = Vec::new();
+            let mut emitted = 0usize;
+            let mut t = 0usize;
+
+            while t < frames {
+                let frame: Vec<f32> = (0..dim).map(|d| encoded[d * total_frames + t]).collect();
+                let prev = tokens.last().copied().unwrap_or(self.blank_id) as i32;
+
+                let outputs = sessions
+                    .decoder_joint
+                    .run(ort::inputs! {
+                        "encoder_outputs" => Tensor::from_array((vec![1i64, dim as i64, 1], frame)).map_err(infer_err)?,
+                        "targets" => Tensor::from_array((vec![1i64, 1], vec![prev])).map_err(infer_err)?,
+                        "target_length" => Tensor::from_array((vec![1i64], vec![1i32])).map_err(infer_err)?,
+                        "input_states_1" => Tensor::from_array((state_shape.clone(), state1.clone())).map_err(infer_err)?,
+                        "input_states_2" => Tensor::from_array((state_shape.clone(), state2.clone())).map_err(infer_err)?
+                    })
+                    .map_err(infer_err)?;
+                let (_, logits) = outputs["outputs"]
+                    .try_extract_tensor::<f32>()
+                    .map_err(infer_err)?;
+                if logits.len() < vocab_size {
+                    return Err(infer_err("joint output smaller than vocabulary"));
+                }
+
+                let token = argmax(&logits[..vocab_size]);
+                let step = if logits.len() > vocab_size {
+                    TDT_DURATIONS
+                        .get(argmax(&logits[vocab_size..]))
+                        .copied()
+                        .unwrap_or(1)
+                } else {
+                    0
+                };
+
+                if token != self.blank_id {
+                    // Only a real token advances the prediction network
+                    let (_, s1) = outputs["output_states_1"]
+                        .try_extract_tensor::<f32>()
+                        .map_err(infer_err)?;
+                    let (_, s2) = outputs["output_states_2"]
+                        .try_extract_tensor::<f32>()
+                        .map_err(infer_err)?;
+                    state1 = s1.to_vec();
+                    state2 = s2.to_vec();
+                    tokens.push(token);
+                    emitted += 1;
+                }
+
+                if step > 0 {
+                    t += step;
+                    emitted = 0;
+                } else if token == self.blank_id || emitted >= MAX_TOKENS_PER_STEP {
+                    t += 1;
+                    emitted = 0;
+                }
+            }
+
+            Ok(decode_parakeet_tokens(&self.vocab, &tokens))
+        }
+    }
+
+    impl SttEngine for ParakeetStt {
+        fn transcribe(&self, audio: &[f32]) -> Result<String, SttError> {
+            if !self.is_ready() {
+                return Err(SttError::NotReady);
+            }
+
+            if audio.len() < MIN_SAMPLES {
+                tracing::debug!(
+                    samples = audio.len(),
+                    min = MIN_SAMPLES,
+                    "Audio too short for Parakeet, skipping"
+                );
+                return Ok(String::new());
+            }
+
+            let start = std::time::Instant::now();
+            let text = self.run_inference(audio)?;
+            tracing::info!(
+                duration_secs = format!("{:.2}", audio.len() as f64 / 16000.0),
+                elapsed_ms = start.elapsed().as_millis() as u64,
+                text_len = text.len(),
+                "Parakeet transcription complete"
+            );
+            Ok(text)
+        }
+
+        fn transcribe_streaming(&self, audio_chunk: &[f32]) -> Result<Option<String>, SttError> {
+            if !self.is_ready() {
+                return Err(SttError::NotReady);
+            }
+
+            let mut streaming = self.streaming.lock().map_err(|e| {
+                SttError::TranscriptionError(format!("Failed to lock streaming state: {}", e))
+            })?;
+            streaming.push(audio_chunk, |window| self.transcribe(window))
+        }
+
+        fn finish_streaming(&self) -> Result<String, SttError> {
+            let mut streaming = self.streaming.lock().map_err(|e| {
+                SttError::TranscriptionError(format!("Failed to lock streaming state: {}", e))
+            })?;
+            streaming.finish(|window| self.transcribe(window))
+        }
+
+        fn name(&self) -> &str {
+            "parakeet"
+        }
+
+        fn is_ready(&self) -> bool {
+            self.ready.load(Ordering::Relaxed)
+        }
+
+        fn detected_language(&self) -> Option<String> {
+            // Parakeet TDT 0.6B v2 is English-only
+            Some("en".into())
+        }
+    }
+}
+
+#[cfg(feature = "onnx")]
+pub use parakeet_real::ParakeetStt;
+
 // ── STT Engine Factory ──────────────────────────────────────────────
 
 /// Enum-dispatch wrapper to avoid dyn-trait issues with non-object-safe methods.
 pub enum SttAdapter {
     Whisper(WhisperStt),
+    #[cfg(feature = "onnx")]
+    Parakeet(ParakeetStt),
     // TODO: Add cloud adapters:
     // OpenAi(OpenAiStt),
     // Custom(CustomApiStt),
@@ -650,13 +1253,26 @@ impl SttAdapter {
     pub fn transcribe(&self, audio: &[f32]) -> Result<String, SttError> {
         match self {
             Self::Whisper(e) => e.transcribe(audio),
+            #[cfg(feature = "onnx")]
+            Self::Parakeet(e) => e.transcribe(audio),
         }
     }
 
-    /// Process a streaming audio chunk.
+    /// Process a streaming audio chunk, returning newly committed words.
     pub fn transcribe_streaming(&self, audio_chunk: &[f32]) -> Result<Option<String>, SttError> {
         match self {
             Self::Whisper(e) => e.transcribe_streaming(audio_chunk),
+            #[cfg(feature = "onnx")]
+            Self::Parakeet(e) => e.transcribe_streaming(audio_chunk),
+        }
+    }
+
+    /// Finish the streaming utterance, returning the remaining words.
+    pub fn finish_streaming(&self) -> Result<String, SttError> {
+        match self {
+            Self::Whisper(e) => e.finish_streaming(),
+            #[cfg(feature = "onnx")]
+            Self::Parakeet(e) => e.finish_streaming(),
         }
     }
 
@@ -664,6 +1280,8 @@ impl SttAdapter {
     pub fn name(&self) -> &str {
         match self {
             Self::Whisper(e) => e.name(),
+            #[cfg(feature = "onnx")]
+            Self::Parakeet(e) => e.name(),
         }
     }
 
@@ -671,6 +1289,55 @@ impl SttAdapter {
     pub fn is_ready(&self) -> bool {
         match self {
             Self::Whisper(e) => e.is_ready(),
+            #[cfg(feature = "onnx")]
+            Self::Parakeet(e) => e.is_ready(),
+        }
+    }
+
+    /// Language of the most recent transcription, if known.
+    pub fn detected_language(&self) -> Option<String> {
+        match self {
+            Self::Whisper(e) => e.detected_language(),
+            #[cfg(feature = "onnx")]
+            Self::Parakeet(e) => e.detected_language(),
+        }
+    }
+
+    /// Record a final transcription as context for the next one.
+    pub fn remember_transcript(&self, text: &str) {
+        match self {
+            Self::Whisper(e) => e.remember_transcript(text),
+            #[cfg(feature = "onnx")]
+            Self::Parakeet(e) => e.remember_transcript(text),
+        }
+    }
+
+    /// Set the transcript carry-over window (whisper only; see
+    /// [`TranscriptContext`]).
+    pub fn with_context_words(self, words: usize) -> Self {
+        match self {
+            Self::Whisper(e) => Self::Whisper(e.with_context_words(words)),
+            #[cfg(feature = "onnx")]
+            other => other,
+        }
+    }
+
+    /// Set the fixed initial prompt and vocabulary (whisper only).
+    pub fn with_prompt_bias(self, initial_prompt: &str, vocabulary: &[String]) -> Self {
+        match self {
+            Self::Whisper(e) => Self::Whisper(e.with_prompt_bias(initial_prompt, vocabulary)),
+            #[cfg(feature = "onnx")]
+            other => other,
+        }
+    }
+
+    /// Set the transcribe/translate task (whisper only; Parakeet is
+    /// English-only and always transcribes).
+    pub fn with_task(self, task: SttTask) -> Self {
+        match self {
+            Self::Whisper(e) => Self::Whisper(e.with_task(task)),
+            #[cfg(feature = "onnx")]
+            other => other,
         }
     }
 }
@@ -678,16 +1345,20 @@ impl SttAdapter {
 /// Create an STT engine from configuration.
 ///
 /// # Arguments
-/// * `adapter` - Adapter name: "whisper-local", "openai-cloud", "custom-cloud"
+/// * `adapter` - Adapter name: "whisper-local", "parakeet", "openai-cloud", "custom-cloud"
 /// * `data_dir` - Application data directory for model files
 /// * `model_size` - Model size for local whisper (e.g., "tiny", "base", "small")
 /// * `use_gpu` - Whether to use GPU acceleration (CUDA)
+/// * `language` - STT language ("en", "de", "auto", ...)
 pub fn create_stt_engine(
     adapter: &str,
     data_dir: &Path,
     model_size: Option<&str>,
     use_gpu: bool,
+    language: &str,
 ) -> Result<SttAdapter, SttError> {
+    let language = &normalize_stt_language(language);
+
     // Normalize legacy adapter names
     let adapter = match adapter {
         "whisper" | "faster-whisper" => "whisper-local",
@@ -698,19 +1369,48 @@ pub fn create_stt_engine(
     match adapter {
         "whisper-local" => {
             let size = model_size.unwrap_or("base");
-            let engine = WhisperStt::from_model_size(data_dir, size, use_gpu)?;
+            let engine = WhisperStt::from_model_size(data_dir, size, use_gpu, language)?;
+            Ok(SttAdapter::Whisper(engine))
+        }
+        // Parakeet falls back to local whisper (configured size/GPU), not to
+        // a cloud adapter as voice-core does: the cloud adapters below aren't
+        // implemented in this crate and would only yield a base whisper stub.
+        "parakeet" => {
+            #[cfg(feature = "onnx")]
+            {
+                match ParakeetStt::new(&parakeet_model_dir(data_dir)) {
+                    Ok(engine) => {
+                        if whisper_language(language).as_deref() != Some("en") {
+                            tracing::warn!(
+                                language,
+                                "Parakeet is English-only; configured language is ignored"
+                            );
+                        }
+                        return Ok(SttAdapter::Parakeet(engine));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Parakeet not available ({}), falling back to whisper-local", e);
+                    }
+                }
+            }
+            #[cfg(not(feature = "onnx"))]
+            {
+                tracing::warn!("Parakeet requires the onnx feature, falling back to whisper-local");
+            }
+            let size = model_size.unwrap_or("base");
+            let engine = WhisperStt::from_model_size(data_dir, size, use_gpu, language)?;
             Ok(SttAdapter::Whisper(engine))
         }
         "openai-cloud" => {
             // TODO: Implement OpenAI cloud STT adapter
             tracing::warn!("OpenAI cloud STT not yet implemented, falling back to whisper stub");
-            let engine = WhisperStt::from_model_size(data_dir, "base", false)?;
+            let engine = WhisperStt::from_model_size(data_dir, "base", false, language)?;
             Ok(SttAdapter::Whisper(engine))
         }
         "custom-cloud" => {
             // TODO: Implement custom cloud STT adapter
             tracing::warn!("Custom cloud STT not yet implemented, falling back to whisper stub");
-            let engine = WhisperStt::from_model_size(data_dir, "base", false)?;
+            let engine = WhisperStt::from_model_size(data_dir, "base", false, language)?;
             Ok(SttAdapter::Whisper(engine))
         }
         other => Err(SttError::ModelLoadError(format!(
@@ -765,7 +1465,7 @@ mod tests {
         #[test]
         fn test_whisper_stt_stub_creation() {
             let path = PathBuf::from("/tmp/models/ggml-base.en.bin");
-            let engine = WhisperStt::new(&path, false);
+            let engine = WhisperStt::new(&path, false, "en");
             assert!(engine.is_ok());
 
             let engine = engine.unwrap();
@@ -776,7 +1476,7 @@ mod tests {
         #[test]
         fn test_whisper_stt_stub_transcribe() {
             let path = PathBuf::from("/tmp/models/ggml-base.en.bin");
-            let engine = WhisperStt::new(&path, false).unwrap();
+            let engine = WhisperStt::new(&path, false, "en").unwrap();
 
             // Generate 1 second of fake audio
             let audio = vec![0.1f32; 16000];
@@ -788,7 +1488,7 @@ mod tests {
         #[test]
         fn test_whisper_stt_empty_audio() {
             let path = PathBuf::from("/tmp/models/ggml-base.en.bin");
-            let engine = WhisperStt::new(&path, false).unwrap();
+            let engine = WhisperStt::new(&path, false, "en").unwrap();
 
             let result = engine.transcribe(&[]);
             assert!(result.is_ok());
@@ -798,7 +1498,7 @@ mod tests {
         #[test]
         fn test_whisper_stt_short_audio() {
             let path = PathBuf::from("/tmp/models/ggml-base.en.bin");
-            let engine = WhisperStt::new(&path, false).unwrap();
+            let engine = WhisperStt::new(&path, false, "en").unwrap();
 
             // Audio too short (< 100ms)
             let audio = vec![0.1f32; 100];
@@ -809,17 +1509,53 @@ mod tests {
         #[test]
         fn test_create_stt_engine_whisper() {
             let data_dir = PathBuf::from("/tmp/voice-mirror-test");
-            let result = create_stt_engine("whisper-local", &data_dir, Some("tiny"), false);
+            let result = create_stt_engine("whisper-local", &data_dir, Some("tiny"), false, "en");
             assert!(result.is_ok());
         }
 
         #[test]
         fn test_stt_adapter_dispatch() {
             let data_dir = PathBuf::from("/tmp/voice-mirror-test");
-            let adapter = create_stt_engine("whisper-local", &data_dir, Some("base"), false).unwrap();
+            let adapter = create_stt_engine("whisper-local", &data_dir, Some("base"), false, "en").unwrap();
             assert!(adapter.is_ready());
             assert!(adapter.name().contains("stub"));
         }
+
+        #[test]
+        fn test_stub_detected_language() {
+            let path = PathBuf::from("/tmp/models/ggml-base.bin");
+            let engine = WhisperStt::new(&path, false, "DE").unwrap();
+            assert_eq!(engine.detected_language().as_deref(), Some("de"));
+
+            let engine = WhisperStt::new(&path, false, "auto").unwrap();
+            assert_eq!(engine.detected_language(), None);
+        }
+
+        #[test]
+        fn test_parakeet_falls_back_to_whisper() {
+            // No Parakeet files in this dir (and/or no onnx feature), so the
+            // adapter falls back to local whisper instead of failing.
+            let data_dir = PathBuf::from("/tmp/voice-mirror-test-no-parakeet");
+            let engine = create_stt_engine("parakeet", &data_dir, Some("base"), false, "en").unwrap();
+            assert_eq!(engine.name(), "whisper-local (stub)");
+        }
+
+        #[test]
+        fn test_stub_streaming_commits_stable_prefix() {
+            let path = PathBuf::from("/tmp/models/ggml-base.en.bin");
+            let engine = WhisperStt::new(&path, false, "en").unwrap();
+            let second = vec![0.0f32; 16_000];
+
+            // First pass has nothing to agree with yet
+            assert_eq!(engine.transcribe_streaming(&second).unwrap(), None);
+            // Stub text differs only in the duration, so the shared prefix commits
+            assert_eq!(
+                engine.transcribe_streaming(&second).unwrap().as_deref(),
+                Some("[STT stub: received")
+            );
+            let rest = engine.finish_streaming().unwrap();
+            assert_eq!(rest, "2.0s of audio, model=base]");
+        }
     }
 
     // ── Real whisper tests (only run with whisper feature) ──────────
@@ -832,7 +1568,7 @@ mod tests {
         fn test_whisper_stt_real_missing_model() {
             // Real implementation should fail when model file is missing
             let path = PathBuf::from("/tmp/nonexistent/ggml-base.en.bin");
-            let result = WhisperStt::new(&path, false);
+            let result = WhisperStt::new(&path, false, "en");
             assert!(result.is_err());
         }
 
@@ -844,7 +1580,7 @@ mod tests {
             // on a path that doesn't exist (which will error).
             // This test just verifies the error path reports correctly.
             let data_dir = PathBuf::from("/tmp/voice-mirror-test-real");
-            let result = create_stt_engine("whisper-local", &data_dir, Some("tiny"), false);
+            let result = create_stt_engine("whisper-local", &data_dir, Some("tiny"), false, "en");
             // Should fail because model file doesn't exist
             assert!(result.is_err());
         }
@@ -881,10 +1617,88 @@ mod tests {
         assert_eq!(model_filename("medium"), "ggml-medium.en.bin");
     }
 
+    #[test]
+    fn test_model_filename_for_language() {
+        assert_eq!(model_filename_for_language("base", "en"), "ggml-base.en.bin");
+        assert_eq!(model_filename_for_language("base", "de"), "ggml-base.bin");
+        assert_eq!(model_filename_for_language("tiny", "auto"), "ggml-tiny.bin");
+        assert_eq!(model_filename_for_language("small", "ja"), "ggml-small.bin");
+        // Large models are multilingual regardless of language
+        assert_eq!(
+            model_filename_for_language("large-v3-turbo", "fr"),
+            "ggml-large-v3-turbo-q5_0.bin"
+        );
+        assert_eq!(model_filename_for_language("large-v3", "en"), "ggml-large-v3.bin");
+        // Unknown sizes follow the legacy pattern per language
+        assert_eq!(model_filename_for_language("medium", "es"), "ggml-medium.bin");
+    }
+
+    #[test]
+    fn test_whisper_language() {
+        assert_eq!(whisper_language("en").as_deref(), Some("en"));
+        assert_eq!(whisper_language(" DE ").as_deref(), Some("de"));
+        assert_eq!(whisper_language("auto"), None);
+        assert_eq!(whisper_language(""), None);
+        assert!(!needs_multilingual_model("EN"));
+        assert!(needs_multilingual_model("auto"));
+    }
+
+    #[test]
+    fn test_normalize_stt_language() {
+        assert_eq!(normalize_stt_language("EN"), "en");
+        assert_eq!(normalize_stt_language("en-US"), "en");
+        assert_eq!(normalize_stt_language("pt_BR"), "pt");
+        assert_eq!(normalize_stt_language("haw"), "haw");
+        assert_eq!(normalize_stt_language(""), "auto");
+        assert_eq!(normalize_stt_language(" Auto "), "auto");
+        // Unknown values must not reach whisper.cpp
+        assert_eq!(normalize_stt_language("english"), "en");
+        assert_eq!(normalize_stt_language("xx"), "en");
+    }
+
+    #[test]
+    fn test_stt_task_parse() {
+        assert_eq!(SttTask::parse("translate"), SttTask::Translate);
+        assert_eq!(SttTask::parse(" translate "), SttTask::Translate);
+        assert_eq!(SttTask::parse("transcribe"), SttTask::Transcribe);
+        assert_eq!(SttTask::parse(""), SttTask::Transcribe);
+        assert_eq!(SttTask::parse("bogus"), SttTask::Transcribe);
+    }
+
+    #[test]
+    fn test_whisper_gpu_backend() {
+        let backend = whisper_gpu_backend();
+        let any_gpu_feature =
+            cfg!(any(feature = "cuda", feature = "metal", feature = "vulkan"));
+        assert_eq!(backend.is_some(), any_gpu_feature);
+
+        #[cfg(not(feature = "whisper"))]
+        {
+            let stub = WhisperStt::new(Path::new("/tmp/ggml-base.en.bin"), true, "en").unwrap();
+            assert!(!stub.gpu_active());
+        }
+    }
+
+    #[test]
+    fn test_parse_parakeet_vocab() {
+        let vocab = parse_parakeet_vocab("<unk> 0\n\u{2581}the 1\n\u{2581}cat 2\ns 3\n<blk> 4\n").unwrap();
+        assert_eq!(vocab.len(), 5);
+        assert_eq!(vocab[1], "\u{2581}the");
+        assert_eq!(vocab[4], "<blk>");
+        assert!(parse_parakeet_vocab("no-id-here").is_err());
+    }
+
+    #[test]
+    fn test_decode_parakeet_tokens() {
+        let vocab = parse_parakeet_vocab("<unk> 0\n\u{2581}the 1\n\u{2581}cat 2\ns 3\n<blk> 4\n").unwrap();
+        assert_eq!(decode_parakeet_tokens(&vocab, &[1, 2, 3]), "the cats");
+        assert_eq!(decode_parakeet_tokens(&vocab, &[0, 4, 99]), "");
+    }
+
     #[test]
     fn test_create_stt_engine_unknown() {
         let data_dir = PathBuf::from("/tmp/voice-mirror-test");
-        let result = create_stt_engine("nonexistent-adapter", &data_dir, None, false);
+        let result = create_stt_engine("nonexistent-adapter", &data_dir, None, false, "en");
         assert!(result.is_err());
     }
 
@@ -0,0 +1,177 @@
+//! Transcript carry-over between utterances.
+//!
+//! Whisper decodes each recording on its own, so a name spelled correctly in
+//! one sentence can come out differently in the next. A sentence split by a
+//! pause also loses its start. Whisper can be conditioned on a text prompt
+//! that acts as the "previous text". Passing the end of the recent
+//! transcriptions keeps spellings consistent and lets split sentences
+//! continue naturally.
+//!
+//! The window is capped in words (`voice.sttContextWords`, 0 = off), well
+//! under whisper's 224-token prompt limit. Context older than `MAX_AGE` is
+//! dropped, so a new conversation isn't biased by an old one.
+//!
+//! A fixed bias can lead the prompt: `voice.sttInitialPrompt` and the
+//! `voice.sttVocabulary` terms (names, project jargon like "Kokoro" or
+//! "n8n"). It never expires. Whisper keeps the end of an over-long prompt,
+//! so the bias goes first and the recent words last.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Context this old no longer belongs to the current conversation.
+const MAX_AGE: Duration = Duration::from_secs(180);
+
+/// The last few words the user said, for whisper's initial prompt.
+#[derive(Debug, Default)]
+pub struct TranscriptContext {
+    /// Initial prompt and vocabulary, always sent.
+    bias: Option<String>,
+    max_words: usize,
+    words: VecDeque<String>,
+    updated: Option<Instant>,
+}
+
+impl TranscriptContext {
+    /// Keep up to `max_words` words. 0 disables carry-over.
+    pub fn new(max_words: usize) -> Self {
+        Self {
+            bias: None,
+            max_words,
+            words: VecDeque::with_capacity(max_words),
+            updated: None,
+        }
+    }
+
+    /// Lead every prompt with `initial_prompt` followed by the `vocabulary`
+    /// terms. Both empty leaves the prompt to the recent words alone.
+    pub fn with_bias(self, initial_prompt: &str, vocabulary: &[String]) -> Self {
+        let terms: Vec<&str> = vocabulary
+            .iter()
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .collect();
+        let mut bias = initial_prompt.trim().to_string();
+        if !terms.is_empty() {
+            if !bias.is_empty() {
+                bias.push(' ');
+            }
+            bias.push_str(&terms.join(", "));
+            bias.push('.');
+        }
+        // The prompt goes to whisper.cpp as a C string
+        bias.retain(|c| c != '\0');
+        Self {
+            bias: (!bias.is_empty()).then_some(bias),
+            ..self
+        }
+    }
+
+    /// Append a final transcription, dropping the oldest words past the
+    /// window.
+    pub fn push(&mut self, text: &str, now: Instant) {
+        if self.max_words == 0 {
+            return;
+        }
+        if self.is_stale(now) {
+            self.words.clear();
+        }
+        // The prompt goes to whisper.cpp as a C string
+        let words = text.split_whitespace().filter(|w| !w.contains('\0'));
+        self.words.extend(words.map(String::from));
+        while self.words.len() > self.max_words {
+            self.words.pop_front();
+        }
+        self.updated = Some(now);
+    }
+
+    /// Prompt text for the next decode (bias, then recent words), or `None`
+    /// when there's neither.
+    pub fn prompt(&self, now: Instant) -> Option<String> {
+        let recent = (!self.words.is_empty() && !self.is_stale(now)).then(|| {
+            self.words
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        match (&self.bias, recent) {
+            (Some(bias), Some(recent)) => Some(format!("{} {}", bias, recent)),
+            (Some(bias), None) => Some(bias.clone()),
+            (None, recent) => recent,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.updated = None;
+    }
+
+    fn is_stale(&self, now: Instant) -> bool {
+        matches!(self.updated, Some(at) if now.duration_since(at) >= MAX_AGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_keeps_latest_words() {
+        let now = Instant::now();
+        let mut ctx = TranscriptContext::new(5);
+        assert_eq!(ctx.prompt(now), None);
+
+        ctx.push("Ask Siobhan about the", now);
+        ctx.push("quarterly report please", now);
+        assert_eq!(
+            ctx.prompt(now).as_deref(),
+            Some("about the quarterly report please")
+        );
+
+        ctx.clear();
+        assert_eq!(ctx.prompt(now), None);
+    }
+
+    #[test]
+    fn test_expires_and_disabled() {
+        let now = Instant::now();
+        let mut ctx = TranscriptContext::new(10);
+        ctx.push("hello Siobhan", now);
+        assert_eq!(ctx.prompt(now + MAX_AGE), None);
+
+        // A push after expiry starts fresh
+        ctx.push("new topic", now + MAX_AGE);
+        assert_eq!(ctx.prompt(now + MAX_AGE).as_deref(), Some("new topic"));
+
+        let mut off = TranscriptContext::new(0);
+        off.push("anything", now);
+        assert_eq!(off.prompt(now), None);
+    }
+
+    #[test]
+    fn test_bias_leads_and_never_expires() {
+        let now = Instant::now();
+        let vocab = vec!["Kokoro".to_string(), " ".to_string(), "n8n".to_string()];
+        let mut ctx = TranscriptContext::new(3).with_bias("Voice Mirror dev chat.", &vocab);
+        assert_eq!(
+            ctx.prompt(now).as_deref(),
+            Some("Voice Mirror dev chat. Kokoro, n8n.")
+        );
+
+        ctx.push("restart the workflow", now);
+        assert_eq!(
+            ctx.prompt(now).as_deref(),
+            Some("Voice Mirror dev chat. Kokoro, n8n. restart the workflow")
+        );
+        assert_eq!(
+            ctx.prompt(now + MAX_AGE).as_deref(),
+            Some("Voice Mirror dev chat. Kokoro, n8n.")
+        );
+
+        // Vocabulary alone, with carry-over off
+        let off = TranscriptContext::new(0).with_bias("", &["Siobhan".to_string()]);
+        assert_eq!(off.prompt(now).as_deref(), Some("Siobhan."));
+        assert_eq!(TranscriptContext::new(0).with_bias(" ", &[]).prompt(now), None);
+    }
+}
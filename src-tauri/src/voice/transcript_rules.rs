@@ -0,0 +1,430 @@
+//! Post-processing of STT text (`transcriptRules` config section).
+//!
+//! Runs on every final transcription before the `Transcription` event, so
+//! the chat, the AI provider, the transcript log and webhooks all see the
+//! processed text. Steps, in order:
+//!
+//! 1. Number normalization: spelled-out numbers become digits ("twenty
+//!    three" -> "23", "two thousand and five" -> "2005"). A lone number
+//!    below ten stays a word ("one of them").
+//! 2. User replacements: regexes applied in order (`$1` etc. in the
+//!    replacement; `(?i)` for case-insensitive).
+//! 3. Profanity masking: whole words from a small built-in list plus
+//!    `profanityWords` keep their first letter ("s***").
+//! 4. Auto-capitalization: the first letter of each sentence and a
+//!    standalone "i".
+//!
+//! The STT context prompt (`stt_context`) still remembers the raw text, so
+//! rules never feed back into what whisper is conditioned on. The frontend
+//! dictionary (`voice.dictionary`) runs after all of this.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::config::schema::TranscriptRulesConfig;
+
+/// Masked by default when `maskProfanity` is on. Matched as whole words,
+/// with common suffixes ("-s", "-ing", ...).
+const PROFANITY: &[&str] = &[
+    "fuck",
+    "motherfucker",
+    "shit",
+    "bullshit",
+    "bitch",
+    "bastard",
+    "asshole",
+    "dick",
+    "cunt",
+    "damn",
+    "crap",
+    "piss",
+];
+
+/// Compiled transcript rules.
+#[derive(Debug)]
+pub struct TranscriptRules {
+    replacements: Vec<(Regex, String)>,
+    profanity: Option<Regex>,
+    capitalize: bool,
+    numbers: bool,
+}
+
+impl TranscriptRules {
+    /// Compile `config`, or `None` when no rule is enabled. Invalid
+    /// replacement patterns are skipped with a warning.
+    pub fn new(config: &TranscriptRulesConfig) -> Option<Self> {
+        let replacements: Vec<(Regex, String)> = config
+            .replacements
+            .iter()
+            .filter(|r| !r.pattern.is_empty())
+            .filter_map(|r| match Regex::new(&r.pattern) {
+                Ok(re) => Some((re, r.replacement.clone())),
+                Err(e) => {
+                    tracing::warn!(pattern = %r.pattern, "Ignoring invalid transcript replacement: {}", e);
+                    None
+                }
+            })
+            .collect();
+        let profanity = config
+            .mask_profanity
+            .then(|| profanity_regex(&config.profanity_words))
+            .flatten();
+
+        let rules = Self {
+            replacements,
+            profanity,
+            capitalize: config.auto_capitalize,
+            numbers: config.normalize_numbers,
+        };
+        let active = !rules.replacements.is_empty()
+            || rules.profanity.is_some()
+            || rules.capitalize
+            || rules.numbers;
+        active.then_some(rules)
+    }
+
+    /// `text` with every rule applied, trimmed.
+    pub fn apply(&self, text: &str) -> String {
+        let mut out = if self.numbers {
+            normalize_numbers(text)
+        } else {
+            text.to_string()
+        };
+        for (re, replacement) in &self.replacements {
+            out = re.replace_all(&out, replacement.as_str()).into_owned();
+        }
+        if let Some(re) = &self.profanity {
+            out = re
+                .replace_all(&out, |caps: &regex::Captures| mask_word(&caps[0]))
+                .into_owned();
+        }
+        if self.capitalize {
+            out = capitalize_sentences(&out);
+        }
+        out.trim().to_string()
+    }
+}
+
+/// Whole-word, case-insensitive matcher for the built-in list plus `extra`.
+fn profanity_regex(extra: &[String]) -> Option<Regex> {
+    let words: Vec<String> = PROFANITY
+        .iter()
+        .map(|w| w.to_string())
+        .chain(extra.iter().map(|w| w.trim().to_lowercase()))
+        .filter(|w| !w.is_empty())
+        .map(|w| regex::escape(&w))
+        .collect();
+    let pattern = format!(r"\b(?:{})(?:s|es|ed|ing|er|ers|y)?\b", words.join("|"));
+    RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| tracing::warn!("Failed to build the profanity filter: {}", e))
+        .ok()
+}
+
+/// First letter kept, the rest starred.
+fn mask_word(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => std::iter::once(first)
+            .chain(chars.map(|_| '*'))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Uppercase the first letter of the text and of each sentence, and a
+/// standalone "i" ("i'm" -> "I'm").
+fn capitalize_sentences(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut sentence_start = true;
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_alphabetic() {
+            let prev_is_word = i > 0 && chars[i - 1].is_alphanumeric();
+            let next_is_word = chars.get(i + 1).is_some_and(|n| n.is_alphanumeric());
+            let lone_i = c == 'i' && !prev_is_word && !next_is_word;
+            if sentence_start || lone_i {
+                out.extend(c.to_uppercase());
+            } else {
+                out.push(c);
+            }
+            sentence_start = false;
+        } else {
+            if matches!(c, '.' | '!' | '?') {
+                sentence_start = true;
+            } else if c.is_numeric() {
+                sentence_start = false;
+            }
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn unit_value(word: &str) -> Option<u64> {
+    const UNITS: [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen",
+        "eighteen", "nineteen",
+    ];
+    UNITS.iter().position(|u| *u == word).map(|n| n as u64)
+}
+
+fn tens_value(word: &str) -> Option<u64> {
+    const TENS: [&str; 8] = [
+        "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+    TENS.iter()
+        .position(|t| *t == word)
+        .map(|n| (n as u64 + 2) * 10)
+}
+
+fn scale_value(word: &str) -> Option<u64> {
+    match word {
+        "thousand" => Some(1_000),
+        "million" => Some(1_000_000),
+        "billion" => Some(1_000_000_000),
+        _ => None,
+    }
+}
+
+/// "five", "twenty" or "twenty-five".
+fn is_number_word(word: &str) -> bool {
+    match word.split_once('-') {
+        Some((tens, unit)) => tens_value(tens).is_some() && unit_value(unit).is_some(),
+        None => unit_value(word).is_some() || tens_value(word).is_some(),
+    }
+}
+
+/// What the previous number word was, to reject sequences like "five six".
+#[derive(Clone, Copy, PartialEq)]
+enum Last {
+    Start,
+    /// 0 - 19
+    Unit,
+    /// 20, 30, ... 90
+    Tens,
+    Hundred,
+    Scale,
+}
+
+/// Parses number words from a running total.
+struct NumberParser {
+    total: u64,
+    current: u64,
+    last: Last,
+    last_scale: u64,
+}
+
+impl NumberParser {
+    fn new() -> Self {
+        Self {
+            total: 0,
+            current: 0,
+            last: Last::Start,
+            last_scale: u64::MAX,
+        }
+    }
+
+    /// Take one lowercase word ("twenty-three" included). `false` (and no
+    /// change) when it doesn't continue the number.
+    fn push(&mut self, word: &str) -> bool {
+        if let Some((tens, unit)) = word.split_once('-') {
+            return match (tens_value(tens), unit_value(unit)) {
+                (Some(_), Some(u)) if (1..10).contains(&u) => {
+                    let saved = (self.current, self.last);
+                    if self.push(tens) && self.push(unit) {
+                        true
+                    } else {
+                        (self.current, self.last) = saved;
+                        false
+                    }
+                }
+                _ => false,
+            };
+        }
+        if let Some(n) = unit_value(word) {
+            let ok = match self.last {
+                Last::Start | Last::Hundred | Last::Scale => true,
+                Last::Tens => (1..10).contains(&n),
+                Last::Unit => false,
+            };
+            if ok {
+                self.current += n;
+                self.last = Last::Unit;
+            }
+            ok
+        } else if let Some(n) = tens_value(word) {
+            let ok = matches!(self.last, Last::Start | Last::Hundred | Last::Scale);
+            if ok {
+                self.current += n;
+                self.last = Last::Tens;
+            }
+            ok
+        } else if word == "hundred" {
+            // "five hundred", "nineteen hundred"; not "twenty hundred"
+            let ok = self.last == Last::Unit && self.current % 100 != 0 && self.current < 100;
+            if ok {
+                self.current *= 100;
+                self.last = Last::Hundred;
+            }
+            ok
+        } else if let Some(scale) = scale_value(word) {
+            let ok = matches!(self.last, Last::Unit | Last::Tens | Last::Hundred)
+                && self.current > 0
+                && scale < self.last_scale;
+            if ok {
+                self.total += self.current * scale;
+                self.current = 0;
+                self.last = Last::Scale;
+                self.last_scale = scale;
+            }
+            ok
+        } else {
+            false
+        }
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+}
+
+/// A whitespace-separated word split into leading punctuation, the word
+/// (lowercased) and trailing punctuation.
+fn split_token(token: &str) -> (&str, String, &str) {
+    let start = token
+        .find(|c: char| c.is_alphanumeric())
+        .unwrap_or(token.len());
+    let end = token
+        .rfind(|c: char| c.is_alphanumeric())
+        .map(|i| i + token[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(start);
+    (&token[..start], token[start..end].to_lowercase(), &token[end..])
+}
+
+/// Replace runs of number words with digits.
+fn normalize_numbers(text: &str) -> String {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut out: Vec<String> = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let (prefix, _, _) = split_token(tokens[i]);
+        let mut parser = NumberParser::new();
+        let mut used = 0;
+        let mut j = i;
+        while j < tokens.len() {
+            let (lead, word, trail) = split_token(tokens[j]);
+            if j > i && !lead.is_empty() {
+                break;
+            }
+            // "and" only joins "hundred and five", "thousand and one"
+            if word == "and" && trail.is_empty() && j > i {
+                let next_is_number = tokens.get(j + 1).is_some_and(|t| {
+                    let (lead, next, _) = split_token(t);
+                    lead.is_empty() && is_number_word(&next)
+                });
+                if next_is_number && matches!(parser.last, Last::Hundred | Last::Scale) {
+                    j += 1;
+                    continue;
+                }
+                break;
+            }
+            if !parser.push(&word) {
+                break;
+            }
+            j += 1;
+            used = j - i;
+            if !trail.is_empty() {
+                break;
+            }
+        }
+        if used == 0 || (used == 1 && parser.value() < 10) {
+            out.push(tokens[i].to_string());
+            i += 1;
+            continue;
+        }
+        let (_, _, trail) = split_token(tokens[i + used - 1]);
+        out.push(format!("{}{}{}", prefix, parser.value(), trail));
+        i += used;
+    }
+    out.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::TranscriptReplacement;
+
+    fn rules(config: TranscriptRulesConfig) -> TranscriptRules {
+        TranscriptRules::new(&config).expect("rules enabled")
+    }
+
+    #[test]
+    fn test_nothing_enabled_means_no_rules() {
+        assert!(TranscriptRules::new(&TranscriptRulesConfig::default()).is_none());
+        let only_bad = TranscriptRulesConfig {
+            replacements: vec![TranscriptReplacement {
+                pattern: "(".into(),
+                replacement: "".into(),
+            }],
+            ..Default::default()
+        };
+        assert!(TranscriptRules::new(&only_bad).is_none());
+    }
+
+    #[test]
+    fn test_normalize_numbers() {
+        assert_eq!(normalize_numbers("set a timer for twenty three minutes"), "set a timer for 23 minutes");
+        assert_eq!(normalize_numbers("in two thousand and five"), "in 2005");
+        assert_eq!(normalize_numbers("about one hundred and twenty-five people."), "about 125 people.");
+        assert_eq!(normalize_numbers("nineteen hundred, roughly"), "1900, roughly");
+        assert_eq!(normalize_numbers("three million four hundred thousand"), "3400000");
+        // Small lone numbers stay words; digit strings aren't summed
+        assert_eq!(normalize_numbers("one of them"), "one of them");
+        assert_eq!(normalize_numbers("five six seven"), "five six seven");
+        assert_eq!(normalize_numbers("twenty, thirty"), "20, 30");
+        assert_eq!(normalize_numbers("rock and roll"), "rock and roll");
+    }
+
+    #[test]
+    fn test_replacements_in_order() {
+        let r = rules(TranscriptRulesConfig {
+            replacements: vec![
+                TranscriptReplacement {
+                    pattern: r"(?i)\bvoice mirror\b".into(),
+                    replacement: "Voice Mirror".into(),
+                },
+                TranscriptReplacement {
+                    pattern: r"(\d+) percent".into(),
+                    replacement: "$1%".into(),
+                },
+            ],
+            normalize_numbers: true,
+            ..Default::default()
+        });
+        assert_eq!(r.apply(" open VOICE mirror at fifty percent "), "open Voice Mirror at 50%");
+    }
+
+    #[test]
+    fn test_mask_profanity() {
+        let r = rules(TranscriptRulesConfig {
+            mask_profanity: true,
+            profanity_words: vec!["Heck".into()],
+            ..Default::default()
+        });
+        assert_eq!(r.apply("Well SHIT, heck, that's bullshit"), "Well S***, h***, that's b*******");
+        // Whole words only
+        assert_eq!(r.apply("Scrap the classic assessment"), "Scrap the classic assessment");
+    }
+
+    #[test]
+    fn test_auto_capitalize() {
+        let r = rules(TranscriptRulesConfig {
+            auto_capitalize: true,
+            ..Default::default()
+        });
+        assert_eq!(r.apply("hello there. i'm fine! and you? ok"), "Hello there. I'm fine! And you? Ok");
+        assert_eq!(r.apply("it's 3.5 inches"), "It's 3.5 inches");
+    }
+}
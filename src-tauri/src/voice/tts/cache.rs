@@ -0,0 +1,413 @@
+//! LRU cache of synthesized PCM, in memory and on disk.
+//!
+//! Short phrases the assistant repeats ("Okay", "Done", error messages) are
+//! served from the cache instead of being re-synthesized. Entries are keyed by
+//! a SHA-256 of (engine, voice, speed, text), so changing any of them misses.
+//!
+//! The memory tier holds recently used clips; the disk tier survives restarts.
+//! Both are size-capped and evict least-recently-used entries first.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
+
+use super::crypto::{hex_encode_lower, sha256};
+use super::{TtsEngine, TtsError, TtsStream, WordBoundary};
+
+/// Memory tier cap (~16 MB of f32 samples).
+const MEMORY_CAP_BYTES: usize = 16 * 1024 * 1024;
+
+/// Disk tier cap.
+const DISK_CAP_BYTES: u64 = 128 * 1024 * 1024;
+
+/// Longer texts are unlikely to repeat verbatim and are not cached.
+const MAX_CACHED_TEXT_CHARS: usize = 300;
+
+/// File extension for cached clips (raw little-endian f32 mono).
+const CLIP_EXT: &str = "pcm";
+
+static CACHE: LazyLock<TtsCache> = LazyLock::new(|| {
+    TtsCache::new(
+        Some(crate::services::platform::get_data_dir().join("tts-cache")),
+        MEMORY_CAP_BYTES,
+        DISK_CAP_BYTES,
+    )
+});
+
+/// Cache key for one synthesized clip.
+///
+/// `engine` is the engine display name, which already includes the voice
+/// (and reflects any fallback, e.g. Kokoro → Edge).
+pub fn cache_key(engine: &str, speed: f32, text: &str) -> String {
+    let material = format!("{engine}\n{speed:.3}\n{}", text.trim());
+    hex_encode_lower(&sha256(material.as_bytes()))[..32].to_string()
+}
+
+/// What `clear` removed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ClearStats {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Remove every cached clip from memory and disk.
+pub fn clear() -> ClearStats {
+    CACHE.clear()
+}
+
+struct MemoryEntry {
+    samples: Arc<Vec<f32>>,
+    last_used: u64,
+}
+
+/// In-memory LRU bounded by total sample bytes.
+struct MemoryLru {
+    entries: HashMap<String, MemoryEntry>,
+    bytes: usize,
+    cap: usize,
+    tick: u64,
+}
+
+impl MemoryLru {
+    fn new(cap: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            bytes: 0,
+            cap,
+            tick: 0,
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<Arc<Vec<f32>>> {
+        self.tick += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.tick;
+        Some(Arc::clone(&entry.samples))
+    }
+
+    fn put(&mut self, key: String, samples: Arc<Vec<f32>>) {
+        let size = samples.len() * 4;
+        if size > self.cap {
+            return;
+        }
+        self.tick += 1;
+        if let Some(old) = self.entries.insert(
+            key,
+            MemoryEntry {
+                samples,
+                last_used: self.tick,
+            },
+        ) {
+            self.bytes -= old.samples.len() * 4;
+        }
+        self.bytes += size;
+
+        while self.bytes > self.cap {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+            if let Some(evicted) = self.entries.remove(&oldest) {
+                self.bytes -= evicted.samples.len() * 4;
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+    }
+}
+
+/// Two-tier clip cache. `dir` is `None` when running memory-only.
+pub struct TtsCache {
+    memory: Mutex<MemoryLru>,
+    dir: Option<PathBuf>,
+    disk_cap: u64,
+}
+
+impl TtsCache {
+    fn new(dir: Option<PathBuf>, memory_cap: usize, disk_cap: u64) -> Self {
+        Self {
+            memory: Mutex::new(MemoryLru::new(memory_cap)),
+            dir,
+            disk_cap,
+        }
+    }
+
+    fn clip_path(&self, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|d| d.join(format!("{key}.{CLIP_EXT}")))
+    }
+
+    /// Look up a clip, promoting disk hits into memory.
+    fn get(&self, key: &str) -> Option<Vec<f32>> {
+        if let Some(samples) = self.memory.lock().ok()?.get(key) {
+            return Some(samples.as_ref().clone());
+        }
+
+        let path = self.clip_path(key)?;
+        let bytes = std::fs::read(&path).ok()?;
+        if bytes.is_empty() || bytes.len() % 4 != 0 {
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        let samples: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+
+        // Bump mtime so disk eviction sees this clip as recently used.
+        if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        if let Ok(mut memory) = self.memory.lock() {
+            memory.put(key.to_string(), Arc::new(samples.clone()));
+        }
+        Some(samples)
+    }
+
+    /// Store a clip in memory and (best effort) on disk.
+    fn put(&self, key: &str, samples: &[f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        if let Ok(mut memory) = self.memory.lock() {
+            memory.put(key.to_string(), Arc::new(samples.to_vec()));
+        }
+
+        let (Some(dir), Some(path)) = (self.dir.as_ref(), self.clip_path(key)) else {
+            return;
+        };
+        if let Err(e) = write_clip(dir, &path, samples) {
+            tracing::debug!("TTS cache write failed: {}", e);
+            return;
+        }
+        evict_disk(dir, self.disk_cap);
+    }
+
+    fn clear(&self) -> ClearStats {
+        if let Ok(mut memory) = self.memory.lock() {
+            memory.clear();
+        }
+        let mut stats = ClearStats::default();
+        let Some(dir) = self.dir.as_ref() else {
+            return stats;
+        };
+        for (path, size, _) in list_clips(dir) {
+            if std::fs::remove_file(&path).is_ok() {
+                stats.files += 1;
+                stats.bytes += size;
+            }
+        }
+        stats
+    }
+}
+
+fn write_clip(dir: &Path, path: &Path, samples: &[f32]) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    // Write-then-rename so a crash never leaves a truncated clip behind.
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Cached clips as (path, size, last modified).
+fn list_clips(dir: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == CLIP_EXT))
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((e.path(), meta.len(), modified))
+        })
+        .collect()
+}
+
+/// Delete the least recently used clips until the directory fits `cap`.
+fn evict_disk(dir: &Path, cap: u64) {
+    let mut clips = list_clips(dir);
+    let mut total: u64 = clips.iter().map(|(_, size, _)| size).sum();
+    if total <= cap {
+        return;
+    }
+    clips.sort_by_key(|(_, _, modified)| *modified);
+    for (path, size, _) in clips {
+        if total <= cap {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total -= size;
+        }
+    }
+}
+
+// ── Caching engine wrapper ──────────────────────────────────────────
+
+/// Wraps any engine so `synthesize()` is served from the shared cache.
+pub struct CachedTts {
+    inner: Box<dyn TtsEngine>,
+    speed: f32,
+}
+
+impl CachedTts {
+    pub fn new(inner: Box<dyn TtsEngine>, speed: f32) -> Self {
+        Self { inner, speed }
+    }
+}
+
+impl TtsEngine for CachedTts {
+    fn synthesize(
+        &self,
+        text: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, TtsError>> + Send + '_>> {
+        let text = text.to_string();
+        Box::pin(async move {
+            let cacheable = text.chars().count() <= MAX_CACHED_TEXT_CHARS;
+            let key = cache_key(&self.inner.name(), self.speed, &text);
+            if cacheable {
+                if let Some(samples) = CACHE.get(&key) {
+                    tracing::debug!(key = %key, "TTS cache hit");
+                    // Timings aren't cached; don't let a stale set describe this audio.
+                    let _ = self.inner.take_word_boundaries();
+                    return Ok(samples);
+                }
+            }
+
+            let samples = self.inner.synthesize(&text).await?;
+            if cacheable && !samples.is_empty() {
+                let to_store = samples.clone();
+                // Disk write + eviction scan stay off the async runtime.
+                let _ = tokio::task::spawn_blocking(move || CACHE.put(&key, &to_store));
+            }
+            Ok(samples)
+        })
+    }
+
+    fn synthesize_streaming(
+        &self,
+        text: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<TtsStream, TtsError>> + Send + '_>> {
+        self.inner.synthesize_streaming(text)
+    }
+
+    fn stop(&self) {
+        self.inner.stop();
+    }
+
+    fn take_word_boundaries(&self) -> Vec<WordBoundary> {
+        self.inner.take_word_boundaries()
+    }
+
+    fn set_voice(&mut self, voice: &str) -> Result<(), TtsError> {
+        // The voice is part of `name()`, so new audio gets fresh cache keys.
+        self.inner.set_voice(voice)
+    }
+
+    fn set_speed(&mut self, speed: f32) {
+        self.inner.set_speed(speed);
+        self.speed = speed;
+    }
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vm-tts-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_key_depends_on_engine_speed_and_text() {
+        let base = cache_key("Edge TTS (en-US-AriaNeural)", 1.0, "Okay");
+        assert_eq!(base, cache_key("Edge TTS (en-US-AriaNeural)", 1.0, " Okay "));
+        assert_ne!(base, cache_key("Edge TTS (en-US-GuyNeural)", 1.0, "Okay"));
+        assert_ne!(base, cache_key("Edge TTS (en-US-AriaNeural)", 1.2, "Okay"));
+        assert_ne!(base, cache_key("Edge TTS (en-US-AriaNeural)", 1.0, "Done"));
+        assert_eq!(base.len(), 32);
+    }
+
+    #[test]
+    fn test_memory_lru_evicts_least_recently_used() {
+        // Room for two 4-sample clips (16 bytes each).
+        let mut lru = MemoryLru::new(32);
+        lru.put("a".into(), Arc::new(vec![0.0; 4]));
+        lru.put("b".into(), Arc::new(vec![0.0; 4]));
+        assert!(lru.get("a").is_some());
+        lru.put("c".into(), Arc::new(vec![0.0; 4]));
+        assert!(lru.get("a").is_some());
+        assert!(lru.get("b").is_none());
+        assert!(lru.get("c").is_some());
+        assert_eq!(lru.bytes, 32);
+
+        // Oversized clips are skipped rather than flushing everything.
+        lru.put("huge".into(), Arc::new(vec![0.0; 100]));
+        assert!(lru.get("huge").is_none());
+        assert_eq!(lru.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_disk_round_trip_and_clear() {
+        let dir = temp_dir("roundtrip");
+        let cache = TtsCache::new(Some(dir.clone()), 1024, 1024 * 1024);
+        let clip = vec![0.25f32, -0.5, 1.0];
+        cache.put("k1", &clip);
+
+        // A fresh cache on the same dir (i.e. after restart) hits on disk.
+        let reopened = TtsCache::new(Some(dir.clone()), 1024, 1024 * 1024);
+        assert_eq!(reopened.get("k1"), Some(clip));
+        assert_eq!(reopened.get("missing"), None);
+
+        let stats = reopened.clear();
+        assert_eq!(stats, ClearStats { files: 1, bytes: 12 });
+        assert_eq!(reopened.get("k1"), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_disk_eviction_respects_cap() {
+        let dir = temp_dir("evict");
+        // Each clip is 40 bytes; cap fits two.
+        let cache = TtsCache::new(Some(dir.clone()), 0, 80);
+        cache.put("old", &[0.0; 10]);
+        let past = SystemTime::now() - std::time::Duration::from_secs(60);
+        let old_path = cache.clip_path("old").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&old_path)
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+        cache.put("mid", &[0.0; 10]);
+        cache.put("new", &[0.0; 10]);
+
+        assert!(!old_path.exists());
+        assert!(cache.clip_path("mid").unwrap().exists());
+        assert!(cache.clip_path("new").unwrap().exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,7 +1,9 @@
-//! SHA-256, hex encoding, and base64 utilities for Edge TTS DRM.
+//! SHA-256, HMAC-SHA256, SHA-1, hex encoding, and base64 utilities.
 //!
-//! Minimal inline implementations to avoid adding `sha2` as a dependency
-//! just for the DRM token generation.
+//! Minimal inline implementations to avoid adding `sha2` as a dependency.
+//! Used for the Edge TTS DRM token, for verifying downloaded models, and
+//! for signing outbound webhooks. SHA-1 is only there to match the git blob
+//! ids GitHub publishes for repository files.
 
 /// SHA-256 initial hash values (first 32 bits of fractional parts of
 /// square roots of the first 8 primes).
@@ -33,77 +35,255 @@ const SHA256_K: [u32; 64] = [
 
 /// Compute SHA-256 digest of input bytes, returning 32-byte hash.
 pub(crate) fn sha256(input: &[u8]) -> [u8; 32] {
-    let mut h = SHA256_H;
-
-    // Pre-processing: pad message to 512-bit (64-byte) blocks.
-    // Append bit '1' (0x80), then zeros, then 64-bit big-endian length.
-    let bit_len = (input.len() as u64) * 8;
-    let mut padded = input.to_vec();
-    padded.push(0x80);
-    while (padded.len() % 64) != 56 {
-        padded.push(0);
-    }
-    padded.extend_from_slice(&bit_len.to_be_bytes());
-
-    // Process each 512-bit block
-    for block in padded.chunks_exact(64) {
-        let mut w = [0u32; 64];
-        for i in 0..16 {
-            w[i] = u32::from_be_bytes([
-                block[i * 4],
-                block[i * 4 + 1],
-                block[i * 4 + 2],
-                block[i * 4 + 3],
-            ]);
+    let mut hasher = Sha256::new();
+    hasher.update(input);
+    hasher.finalize()
+}
+
+/// HMAC-SHA256 (RFC 2104) of `message` under `key`.
+pub(crate) fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block_key = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block_key[..32].copy_from_slice(&sha256(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(&block_key.map(|b| b ^ 0x36));
+    inner.update(message);
+    let inner_hash = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(&block_key.map(|b| b ^ 0x5c));
+    outer.update(&inner_hash);
+    outer.finalize()
+}
+
+/// Incremental SHA-256 for inputs too large to hold in memory (model files).
+pub(crate) struct Sha256 {
+    h: [u32; 8],
+    /// Partial block waiting for more input.
+    buf: [u8; 64],
+    buf_len: usize,
+    total_len: u64,
+}
+
+impl Sha256 {
+    pub(crate) fn new() -> Self {
+        Self {
+            h: SHA256_H,
+            buf: [0; 64],
+            buf_len: 0,
+            total_len: 0,
         }
-        for i in 16..64 {
-            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
-            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
-            w[i] = w[i - 16]
-                .wrapping_add(s0)
-                .wrapping_add(w[i - 7])
-                .wrapping_add(s1);
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+
+        if self.buf_len > 0 {
+            let take = (64 - self.buf_len).min(data.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+            self.buf_len += take;
+            data = &data[take..];
+            if self.buf_len < 64 {
+                return;
+            }
+            let block = self.buf;
+            compress(&mut self.h, &block);
+            self.buf_len = 0;
         }
 
-        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
-
-        for i in 0..64 {
-            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
-            let ch = (e & f) ^ ((!e) & g);
-            let temp1 = hh
-                .wrapping_add(s1)
-                .wrapping_add(ch)
-                .wrapping_add(SHA256_K[i])
-                .wrapping_add(w[i]);
-            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
-            let maj = (a & b) ^ (a & c) ^ (b & c);
-            let temp2 = s0.wrapping_add(maj);
-
-            hh = g;
-            g = f;
-            f = e;
-            e = d.wrapping_add(temp1);
-            d = c;
-            c = b;
-            b = a;
-            a = temp1.wrapping_add(temp2);
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            compress(&mut self.h, block);
         }
+        let rest = blocks.remainder();
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.buf_len = rest.len();
+    }
 
-        h[0] = h[0].wrapping_add(a);
-        h[1] = h[1].wrapping_add(b);
-        h[2] = h[2].wrapping_add(c);
-        h[3] = h[3].wrapping_add(d);
-        h[4] = h[4].wrapping_add(e);
-        h[5] = h[5].wrapping_add(f);
-        h[6] = h[6].wrapping_add(g);
-        h[7] = h[7].wrapping_add(hh);
+    pub(crate) fn finalize(mut self) -> [u8; 32] {
+        // Pad: bit '1' (0x80), zeros up to 56 mod 64, then 64-bit big-endian length
+        let bit_len = self.total_len * 8;
+        let mut tail = Vec::with_capacity(128);
+        tail.extend_from_slice(&self.buf[..self.buf_len]);
+        tail.push(0x80);
+        while (tail.len() % 64) != 56 {
+            tail.push(0);
+        }
+        tail.extend_from_slice(&bit_len.to_be_bytes());
+        for block in tail.chunks_exact(64) {
+            compress(&mut self.h, block);
+        }
+
+        let mut result = [0u8; 32];
+        for (i, val) in self.h.iter().enumerate() {
+            result[i * 4..i * 4 + 4].copy_from_slice(&val.to_be_bytes());
+        }
+        result
     }
+}
 
-    let mut result = [0u8; 32];
-    for (i, val) in h.iter().enumerate() {
-        result[i * 4..i * 4 + 4].copy_from_slice(&val.to_be_bytes());
+/// Process one 512-bit (64-byte) block.
+fn compress(h: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes([
+            block[i * 4],
+            block[i * 4 + 1],
+            block[i * 4 + 2],
+            block[i * 4 + 3],
+        ]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
     }
-    result
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ ((!e) & g);
+        let temp1 = hh
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(maj);
+
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    h[0] = h[0].wrapping_add(a);
+    h[1] = h[1].wrapping_add(b);
+    h[2] = h[2].wrapping_add(c);
+    h[3] = h[3].wrapping_add(d);
+    h[4] = h[4].wrapping_add(e);
+    h[5] = h[5].wrapping_add(f);
+    h[6] = h[6].wrapping_add(g);
+    h[7] = h[7].wrapping_add(hh);
+}
+
+/// Incremental SHA-1 (the hash git names blobs by).
+pub(crate) struct Sha1 {
+    h: [u32; 5],
+    /// Partial block waiting for more input.
+    buf: [u8; 64],
+    buf_len: usize,
+    total_len: u64,
+}
+
+impl Sha1 {
+    pub(crate) fn new() -> Self {
+        Self {
+            h: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0],
+            buf: [0; 64],
+            buf_len: 0,
+            total_len: 0,
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+
+        if self.buf_len > 0 {
+            let take = (64 - self.buf_len).min(data.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+            self.buf_len += take;
+            data = &data[take..];
+            if self.buf_len < 64 {
+                return;
+            }
+            let block = self.buf;
+            sha1_compress(&mut self.h, &block);
+            self.buf_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            sha1_compress(&mut self.h, block);
+        }
+        let rest = blocks.remainder();
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.buf_len = rest.len();
+    }
+
+    pub(crate) fn finalize(mut self) -> [u8; 20] {
+        // Same padding as SHA-256
+        let bit_len = self.total_len * 8;
+        let mut tail = Vec::with_capacity(128);
+        tail.extend_from_slice(&self.buf[..self.buf_len]);
+        tail.push(0x80);
+        while (tail.len() % 64) != 56 {
+            tail.push(0);
+        }
+        tail.extend_from_slice(&bit_len.to_be_bytes());
+        for block in tail.chunks_exact(64) {
+            sha1_compress(&mut self.h, block);
+        }
+
+        let mut result = [0u8; 20];
+        for (i, val) in self.h.iter().enumerate() {
+            result[i * 4..i * 4 + 4].copy_from_slice(&val.to_be_bytes());
+        }
+        result
+    }
+}
+
+/// Process one 512-bit (64-byte) SHA-1 block.
+fn sha1_compress(h: &mut [u32; 5], block: &[u8]) {
+    let mut w = [0u32; 80];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *h;
+
+    for (i, wi) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | ((!b) & d), 0x5a827999),
+            20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+            _ => (b ^ c ^ d, 0xca62c1d6),
+        };
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(*wi);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    h[0] = h[0].wrapping_add(a);
+    h[1] = h[1].wrapping_add(b);
+    h[2] = h[2].wrapping_add(c);
+    h[3] = h[3].wrapping_add(d);
+    h[4] = h[4].wrapping_add(e);
 }
 
 const HEX_UPPER: [char; 16] = [
@@ -111,6 +291,11 @@ const HEX_UPPER: [char; 16] = [
     '8', '9', 'A', 'B', 'C', 'D', 'E', 'F',
 ];
 
+/// Convert bytes to lowercase hex string (checksum manifests use lowercase).
+pub(crate) fn hex_encode_lower(bytes: &[u8]) -> String {
+    hex_encode_upper(bytes).to_ascii_lowercase()
+}
+
 /// Convert bytes to uppercase hex string.
 pub(crate) fn hex_encode_upper(bytes: &[u8]) -> String {
     let mut s = String::with_capacity(bytes.len() * 2);
@@ -160,6 +345,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sha256_streaming_matches_oneshot() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7) as u8).collect();
+        // Chunk sizes that straddle block boundaries
+        for chunk in [1, 3, 63, 64, 65, 200] {
+            let mut hasher = Sha256::new();
+            for part in data.chunks(chunk) {
+                hasher.update(part);
+            }
+            assert_eq!(hasher.finalize(), sha256(&data), "chunk size {}", chunk);
+        }
+        assert_eq!(
+            hex_encode_lower(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_sha1_known_vectors() {
+        let digest = |data: &[u8], chunk: usize| {
+            let mut hasher = Sha1::new();
+            for part in data.chunks(chunk) {
+                hasher.update(part);
+            }
+            hex_encode_lower(&hasher.finalize())
+        };
+        assert_eq!(
+            digest(b"abc", 1),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        let two_blocks = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        for chunk in [1, 7, 64] {
+            assert_eq!(
+                digest(two_blocks, chunk),
+                "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+            );
+        }
+        // git's id for an empty blob: SHA-1 of "blob 0\0"
+        assert_eq!(
+            digest(b"blob 0\0", 64),
+            "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        // Test case 2
+        assert_eq!(
+            hex_encode_lower(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Test case 6: key longer than the block size is hashed first
+        assert_eq!(
+            hex_encode_lower(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
     #[test]
     fn test_base64_encode() {
         assert_eq!(base64_encode(b""), "");
@@ -7,19 +7,21 @@
 //! 3. Receive MP3 audio chunks in binary frames
 //! 4. Decode MP3 to f32 PCM via Symphonia
 //!
-//! Since this crate does not include a WebSocket client, we use reqwest's
-//! HTTP upgrade mechanism to get a raw byte stream, then implement
-//! minimal WebSocket framing on top. This avoids adding `tokio-tungstenite`.
+//! The WebSocket client is the minimal one in [`super::ws`], on top of
+//! reqwest's HTTP upgrade (so it shares reqwest's TLS). Dropped or stalled
+//! connections are retried with exponential backoff.
 
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use super::crypto::{base64_encode, hex_encode_upper, sha256};
+use super::crypto::{hex_encode_upper, sha256};
 use super::mp3_decode::decode_mp3_to_f32;
-use super::{TtsEngine, TtsError};
+use super::ws::{self, Message, WsConnection, WsError};
+use super::{TtsEngine, TtsError, WordBoundary};
+use crate::services::metrics::{self, Counter};
 
 // ── Edge TTS DRM Token ──────────────────────────────────────────────
 
@@ -27,6 +29,16 @@ const TRUSTED_CLIENT_TOKEN: &str = "6A5AA1D4EAFF4E9FB37E23D68491D6F4";
 /// Windows epoch offset: seconds between 1601-01-01 and 1970-01-01.
 const WIN_EPOCH: u64 = 11_644_473_600;
 
+/// Attempts per synthesis when the connection fails or drops mid-stream.
+const MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubles after each failed attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+/// Time limit for one attempt (connect + full audio stream). All attempts
+/// plus backoff fit inside the pipeline's 60s synthesis timeout.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// Max gap between messages before the stream counts as stalled.
+const FRAME_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Generate the Sec-MS-GEC security token for Edge TTS.
 ///
 /// Replicates the Python `edge-tts` DRM logic:
@@ -65,6 +77,70 @@ fn xml_escape(s: &str) -> String {
     out
 }
 
+/// Parse `WordBoundary` entries from an `audio.metadata` text message.
+///
+/// The body after the header block is JSON like
+/// `{"Metadata":[{"Type":"WordBoundary","Data":{"Offset":1000000,"text":{"Text":"Hello"}}}]}`
+/// with `Offset` in 100-nanosecond ticks from the start of the audio.
+fn parse_word_boundaries(message: &str) -> Vec<WordBoundary> {
+    let Some((_, body)) = message.split_once("\r\n\r\n") else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(body) else {
+        return Vec::new();
+    };
+    json["Metadata"]
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|e| e["Type"] == "WordBoundary")
+                .filter_map(|e| {
+                    let ticks = e["Data"]["Offset"].as_u64()?;
+                    let word = e["Data"]["text"]["Text"].as_str()?;
+                    Some(WordBoundary {
+                        offset: Duration::from_nanos(ticks * 100),
+                        word: word.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Backoff before retry number `attempt` (1-based): 250ms, 500ms, 1s, ...
+fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY * 2u32.pow(attempt.saturating_sub(1).min(6))
+}
+
+/// The MP3 bytes of a binary `Path:audio` message, if it is one.
+///
+/// Binary messages start with a 2-byte big-endian header length, then the
+/// text headers, then the payload.
+fn audio_payload(data: &[u8]) -> Option<&[u8]> {
+    if data.len() < 2 {
+        return None;
+    }
+    let header_len = u16::from_be_bytes([data[0], data[1]]) as usize;
+    let header = data.get(2..2 + header_len)?;
+    let is_audio = header
+        .windows(b"Path:audio".len())
+        .any(|w| w == b"Path:audio");
+    let audio = &data[2 + header_len..];
+    (is_audio && !audio.is_empty()).then_some(audio)
+}
+
+fn network_error(e: WsError) -> TtsError {
+    TtsError::NetworkError(format!("Edge TTS: {}", e))
+}
+
+/// Finish the close handshake without holding up synthesis.
+fn close_in_background(mut conn: WsConnection<reqwest::Upgraded>) {
+    tokio::spawn(async move {
+        let _ = conn.close().await;
+    });
+}
+
 // ── Edge TTS ────────────────────────────────────────────────────────
 
 /// Microsoft Edge TTS engine using the free cloud API.
@@ -77,6 +153,8 @@ pub struct EdgeTts {
     cancelled: Arc<AtomicBool>,
     /// HTTP client (reused across requests).
     client: reqwest::Client,
+    /// Word timings from the last synthesis (drained by `take_word_boundaries`).
+    word_boundaries: Mutex<Vec<WordBoundary>>,
 }
 
 impl EdgeTts {
@@ -91,6 +169,7 @@ impl EdgeTts {
                 .connect_timeout(std::time::Duration::from_secs(10))
                 .build()
                 .unwrap_or_else(|_| reqwest::Client::new()),
+            word_boundaries: Mutex::new(Vec::new()),
         }
     }
 
@@ -107,6 +186,7 @@ impl EdgeTts {
                 .connect_timeout(std::time::Duration::from_secs(10))
                 .build()
                 .unwrap_or_else(|_| reqwest::Client::new()),
+            word_boundaries: Mutex::new(Vec::new()),
         }
     }
 
@@ -129,16 +209,66 @@ impl EdgeTts {
         )
     }
 
-    /// Perform TTS synthesis via WebSocket using reqwest HTTP upgrade.
+    /// Perform TTS synthesis over a WebSocket (see [`super::ws`]).
     ///
-    /// Uses reqwest to perform the WebSocket upgrade handshake (HTTP 101),
-    /// then speaks the minimal WebSocket framing protocol on the upgraded
-    /// raw byte stream. This avoids adding tokio-tungstenite while
-    /// leveraging reqwest's existing TLS support.
+    /// If the connection fails, stalls, or drops before `turn.end`, the whole
+    /// request is retried with exponential backoff: Edge can't resume a
+    /// stream, so partial audio is discarded.
     async fn synthesize_ws(&self, text: &str) -> Result<Vec<f32>, TtsError> {
+        if let Ok(mut guard) = self.word_boundaries.lock() {
+            guard.clear();
+        }
+
+        let mut attempt = 1;
+        let (mp3_data, boundaries) = loop {
+            let result = match tokio::time::timeout(REQUEST_TIMEOUT, self.stream_audio(text)).await
+            {
+                Ok(result) => result,
+                Err(_) => Err(TtsError::NetworkError(format!(
+                    "Edge TTS request timed out after {}s",
+                    REQUEST_TIMEOUT.as_secs()
+                ))),
+            };
+            match result {
+                Ok(audio) => break audio,
+                Err(TtsError::NetworkError(e)) if attempt < MAX_ATTEMPTS => {
+                    let delay = retry_delay(attempt);
+                    tracing::warn!(
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        "{} — retrying",
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    if self.cancelled.load(Ordering::SeqCst) {
+                        return Err(TtsError::Cancelled);
+                    }
+                    metrics::inc(Counter::WsReconnects);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        // Decode MP3 to f32 PCM
+        let samples = decode_mp3_to_f32(&mp3_data)?;
+        if let Ok(mut guard) = self.word_boundaries.lock() {
+            *guard = boundaries;
+        }
+        tracing::info!(
+            mp3_bytes = mp3_data.len(),
+            pcm_samples = samples.len(),
+            attempt,
+            "Edge TTS synthesis complete"
+        );
+        Ok(samples)
+    }
+
+    /// One synthesis attempt: connect, send the request, and collect MP3
+    /// audio plus word boundaries until `turn.end`.
+    async fn stream_audio(&self, text: &str) -> Result<(Vec<u8>, Vec<WordBoundary>), TtsError> {
         let connection_id = uuid::Uuid::new_v4().as_simple().to_string();
         let sec_ms_gec = generate_sec_ms_gec();
-        let ws_key = base64_encode(&uuid::Uuid::new_v4().as_bytes()[..16]);
 
         let url = format!(
             "https://speech.platform.bing.com/consumer/speech/synthesize/readaloud/edge/v1\
@@ -149,14 +279,9 @@ impl EdgeTts {
             TRUSTED_CLIENT_TOKEN, connection_id, sec_ms_gec,
         );
 
-        // Send WebSocket upgrade via reqwest
-        let response = self
+        let request = self
             .client
             .get(&url)
-            .header("Upgrade", "websocket")
-            .header("Connection", "Upgrade")
-            .header("Sec-WebSocket-Key", &ws_key)
-            .header("Sec-WebSocket-Version", "13")
             .header(
                 "Origin",
                 "chrome-extension://jdiccldimpdaibmpdkjnbmckianbfold",
@@ -167,24 +292,8 @@ impl EdgeTts {
                  (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36 Edg/143.0.0.0",
             )
             .header("Pragma", "no-cache")
-            .header("Cache-Control", "no-cache")
-            .send()
-            .await
-            .map_err(|e| TtsError::NetworkError(format!("Edge TTS request failed: {}", e)))?;
-
-        let status = response.status();
-        if status != reqwest::StatusCode::SWITCHING_PROTOCOLS {
-            return Err(TtsError::NetworkError(format!(
-                "Edge TTS WebSocket upgrade failed: HTTP {}",
-                status
-            )));
-        }
-
-        // Get the upgraded raw stream
-        let mut upgraded = response
-            .upgrade()
-            .await
-            .map_err(|e| TtsError::NetworkError(format!("Edge TTS stream upgrade failed: {}", e)))?;
+            .header("Cache-Control", "no-cache");
+        let mut conn = ws::connect(request).await.map_err(network_error)?;
 
         // Send speech.config message
         let request_id = uuid::Uuid::new_v4().as_simple().to_string();
@@ -193,9 +302,9 @@ impl EdgeTts {
              Content-Type:application/json; charset=utf-8\r\n\
              Path:speech.config\r\n\r\n\
              {\"context\":{\"synthesis\":{\"audio\":{\"metadataoptions\":\
-             {\"sentenceBoundaryEnabled\":\"false\",\"wordBoundaryEnabled\":\"false\"},\
+             {\"sentenceBoundaryEnabled\":\"false\",\"wordBoundaryEnabled\":\"true\"},\
              \"outputFormat\":\"audio-24khz-48kbitrate-mono-mp3\"}}}}".to_string();
-        ws_send_text(&mut upgraded, &config_msg).await?;
+        conn.send_text(&config_msg).await.map_err(network_error)?;
 
         // Send SSML request
         let ssml = self.build_ssml(text);
@@ -207,85 +316,59 @@ impl EdgeTts {
              {}",
             request_id, ssml
         );
-        ws_send_text(&mut upgraded, &ssml_msg).await?;
+        conn.send_text(&ssml_msg).await.map_err(network_error)?;
 
-        // Receive audio frames
+        // Receive audio (binary) and word-boundary metadata (text) messages
         let mut mp3_data = Vec::new();
+        let mut boundaries = Vec::new();
         loop {
             if self.cancelled.load(Ordering::SeqCst) {
                 tracing::debug!("Edge TTS interrupted by user");
-                break;
+                close_in_background(conn);
+                return Err(TtsError::Cancelled);
             }
 
-            // Bound the read so a network stall mid-frame can't wedge the pipeline
-            // in the Speaking state forever (cancel is only checked between frames).
-            let frame = match tokio::time::timeout(
-                std::time::Duration::from_secs(10),
-                ws_read_frame(&mut upgraded),
-            )
-            .await
-            {
-                Ok(Ok(f)) => f,
-                Ok(Err(_)) => break, // connection closed or error
+            // Bound each read so a network stall mid-stream can't wedge the
+            // pipeline (cancel is only checked between messages).
+            let message = match tokio::time::timeout(FRAME_TIMEOUT, conn.recv()).await {
+                Ok(result) => result.map_err(network_error)?,
                 Err(_) => {
-                    tracing::warn!("Edge TTS: frame read timed out — ending synthesis");
-                    break;
+                    return Err(TtsError::NetworkError(
+                        "Edge TTS: stream stalled mid-synthesis".into(),
+                    ));
                 }
             };
 
-            match frame {
-                WsFrame::Text(txt) => {
+            match message {
+                Some(Message::Text(txt)) => {
                     if txt.contains("Path:turn.end") {
                         tracing::debug!("Edge TTS: turn.end received");
                         break;
                     }
-                }
-                WsFrame::Binary(data) => {
-                    if data.len() < 2 {
-                        continue;
-                    }
-                    // First 2 bytes: header length (big-endian)
-                    let header_len = u16::from_be_bytes([data[0], data[1]]) as usize;
-                    if header_len + 2 > data.len() {
-                        continue;
-                    }
-                    // Check if this is an audio frame
-                    let header_bytes = &data[2..2 + header_len];
-                    let is_audio = header_bytes
-                        .windows(b"Path:audio".len())
-                        .any(|w| w == b"Path:audio");
-                    if !is_audio {
-                        continue;
-                    }
-                    let audio_start = 2 + header_len;
-                    if audio_start < data.len() {
-                        mp3_data.extend_from_slice(&data[audio_start..]);
+                    if txt.contains("Path:audio.metadata") {
+                        boundaries.extend(parse_word_boundaries(&txt));
                     }
                 }
-                WsFrame::Close => {
-                    tracing::debug!("Edge TTS: WebSocket closed");
-                    break;
+                Some(Message::Binary(data)) => {
+                    if let Some(audio) = audio_payload(&data) {
+                        mp3_data.extend_from_slice(audio);
+                    }
                 }
-                WsFrame::Ping(payload) => {
-                    let _ = ws_send_pong(&mut upgraded, &payload).await;
+                None => {
+                    return Err(TtsError::NetworkError(
+                        "Edge TTS: connection closed before turn.end".into(),
+                    ));
                 }
             }
         }
+        close_in_background(conn);
 
         if mp3_data.is_empty() {
             return Err(TtsError::NetworkError(
                 "Edge TTS: no audio data received".into(),
             ));
         }
-
-        // Decode MP3 to f32 PCM
-        let samples = decode_mp3_to_f32(&mp3_data)?;
-        tracing::info!(
-            mp3_bytes = mp3_data.len(),
-            pcm_samples = samples.len(),
-            "Edge TTS synthesis complete"
-        );
-        Ok(samples)
+        Ok((mp3_data, boundaries))
     }
 }
 
@@ -320,163 +403,37 @@ impl TtsEngine for EdgeTts {
         self.cancelled.store(true, Ordering::SeqCst);
     }
 
-    fn name(&self) -> String {
-        format!("Edge TTS ({})", self.voice)
+    fn take_word_boundaries(&self) -> Vec<WordBoundary> {
+        self.word_boundaries
+            .lock()
+            .map(|mut g| std::mem::take(&mut *g))
+            .unwrap_or_default()
     }
 
-    fn sample_rate(&self) -> u32 {
-        24000
-    }
-}
-
-// ── Minimal WebSocket Helpers ───────────────────────────────────────
-//
-// These implement just enough of RFC 6455 to talk to the Edge TTS
-// endpoint. No extensions, no fragmentation, client-to-server masking
-// only. This keeps us free of a full WebSocket crate dependency.
-
-/// Parsed WebSocket frame.
-enum WsFrame {
-    Text(String),
-    Binary(Vec<u8>),
-    Close,
-    Ping(Vec<u8>),
-}
-
-/// Send a text frame (opcode 0x1) with client masking.
-async fn ws_send_text<W: tokio::io::AsyncWrite + Unpin>(
-    writer: &mut W,
-    text: &str,
-) -> Result<(), TtsError> {
-    ws_send_frame(writer, 0x01, text.as_bytes()).await
-}
-
-/// Send a pong frame (opcode 0xA) with client masking.
-async fn ws_send_pong<W: tokio::io::AsyncWrite + Unpin>(
-    writer: &mut W,
-    payload: &[u8],
-) -> Result<(), TtsError> {
-    ws_send_frame(writer, 0x0A, payload).await
-}
-
-/// Send a WebSocket frame with the given opcode and payload.
-/// All client-to-server frames are masked per RFC 6455.
-async fn ws_send_frame<W: tokio::io::AsyncWrite + Unpin>(
-    writer: &mut W,
-    opcode: u8,
-    payload: &[u8],
-) -> Result<(), TtsError> {
-    use tokio::io::AsyncWriteExt;
-
-    let len = payload.len();
-    let mut header = Vec::with_capacity(14);
-
-    // FIN bit + opcode
-    header.push(0x80 | opcode);
-
-    // Payload length with mask bit set
-    if len < 126 {
-        header.push(0x80 | len as u8);
-    } else if len <= 65535 {
-        header.push(0x80 | 126);
-        header.extend_from_slice(&(len as u16).to_be_bytes());
-    } else {
-        header.push(0x80 | 127);
-        header.extend_from_slice(&(len as u64).to_be_bytes());
-    }
-
-    // Masking key (use a simple deterministic key -- Edge doesn't check)
-    let mask_key: [u8; 4] = [0x37, 0xfa, 0x21, 0x3d];
-    header.extend_from_slice(&mask_key);
-
-    // Write header
-    writer
-        .write_all(&header)
-        .await
-        .map_err(|e| TtsError::NetworkError(format!("WS write header failed: {}", e)))?;
-
-    // Write masked payload
-    let mut masked = Vec::with_capacity(len);
-    for (i, &b) in payload.iter().enumerate() {
-        masked.push(b ^ mask_key[i % 4]);
+    fn set_voice(&mut self, voice: &str) -> Result<(), TtsError> {
+        let voice = voice.trim();
+        // Short names look like "en-US-AriaNeural"; anything else would be
+        // spliced into the SSML verbatim.
+        if voice.is_empty() || !voice.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(TtsError::SynthesisError(format!(
+                "Invalid Edge TTS voice name: '{}'",
+                voice
+            )));
+        }
+        self.voice = voice.to_string();
+        Ok(())
     }
-    writer
-        .write_all(&masked)
-        .await
-        .map_err(|e| TtsError::NetworkError(format!("WS write payload failed: {}", e)))?;
-
-    Ok(())
-}
 
-/// Read a single WebSocket frame from the stream.
-async fn ws_read_frame<R: tokio::io::AsyncRead + Unpin>(
-    reader: &mut R,
-) -> Result<WsFrame, TtsError> {
-    use tokio::io::AsyncReadExt;
-
-    let mut hdr = [0u8; 2];
-    reader
-        .read_exact(&mut hdr)
-        .await
-        .map_err(|e| TtsError::NetworkError(format!("WS read header failed: {}", e)))?;
-
-    let opcode = hdr[0] & 0x0f;
-    let masked = (hdr[1] & 0x80) != 0;
-    let mut payload_len = (hdr[1] & 0x7f) as u64;
-
-    if payload_len == 126 {
-        let mut buf = [0u8; 2];
-        reader
-            .read_exact(&mut buf)
-            .await
-            .map_err(|e| TtsError::NetworkError(format!("WS read len16 failed: {}", e)))?;
-        payload_len = u16::from_be_bytes(buf) as u64;
-    } else if payload_len == 127 {
-        let mut buf = [0u8; 8];
-        reader
-            .read_exact(&mut buf)
-            .await
-            .map_err(|e| TtsError::NetworkError(format!("WS read len64 failed: {}", e)))?;
-        payload_len = u64::from_be_bytes(buf);
+    fn set_speed(&mut self, speed: f32) {
+        self.rate = ((speed - 1.0) * 100.0) as i32;
     }
 
-    // Server-to-client frames should NOT be masked, but handle it
-    let mask_key = if masked {
-        let mut key = [0u8; 4];
-        reader
-            .read_exact(&mut key)
-            .await
-            .map_err(|e| TtsError::NetworkError(format!("WS read mask failed: {}", e)))?;
-        Some(key)
-    } else {
-        None
-    };
-
-    // Read payload (cap at 10MB to prevent OOM)
-    let len = payload_len.min(10 * 1024 * 1024) as usize;
-    let mut payload = vec![0u8; len];
-    reader
-        .read_exact(&mut payload)
-        .await
-        .map_err(|e| TtsError::NetworkError(format!("WS read payload failed: {}", e)))?;
-
-    // Unmask if needed
-    if let Some(key) = mask_key {
-        for (i, b) in payload.iter_mut().enumerate() {
-            *b ^= key[i % 4];
-        }
+    fn name(&self) -> String {
+        format!("Edge TTS ({})", self.voice)
     }
 
-    match opcode {
-        0x01 => {
-            let text = String::from_utf8_lossy(&payload).into_owned();
-            Ok(WsFrame::Text(text))
-        }
-        0x02 => Ok(WsFrame::Binary(payload)),
-        0x08 => Ok(WsFrame::Close),
-        0x09 => Ok(WsFrame::Ping(payload)),
-        0x0A => Ok(WsFrame::Ping(Vec::new())), // Pong -- treat as no-op ping
-        _ => Ok(WsFrame::Text(String::new())), // Unknown opcode -- ignore
+    fn sample_rate(&self) -> u32 {
+        24000
     }
 }
 
@@ -515,6 +472,63 @@ mod tests {
         assert!(ssml_fast.contains("Test &amp; &lt;escape&gt;"));
     }
 
+    #[test]
+    fn test_parse_word_boundaries() {
+        let msg = "X-RequestId:abc\r\n\
+                   Content-Type:application/json; charset=utf-8\r\n\
+                   Path:audio.metadata\r\n\r\n\
+                   {\"Metadata\":[\
+                   {\"Type\":\"WordBoundary\",\"Data\":{\"Offset\":1000000,\"Duration\":3000000,\
+                   \"text\":{\"Text\":\"Hello\",\"Length\":5,\"BoundaryType\":\"WordBoundary\"}}},\
+                   {\"Type\":\"SessionEnd\",\"Data\":{\"Offset\":9000000}}]}";
+        let words = parse_word_boundaries(msg);
+        assert_eq!(
+            words,
+            vec![WordBoundary {
+                offset: Duration::from_millis(100),
+                word: "Hello".into(),
+            }]
+        );
+        assert!(parse_word_boundaries("Path:audio.metadata\r\n\r\nnot json").is_empty());
+    }
+
+    #[test]
+    fn test_edge_tts_runtime_voice_and_speed() {
+        let mut engine = EdgeTts::new("en-US-AriaNeural");
+        engine.set_voice("en-GB-SoniaNeural").unwrap();
+        engine.set_speed(0.75);
+        let ssml = engine.build_ssml("Hi");
+        assert!(ssml.contains("en-GB-SoniaNeural"));
+        assert!(ssml.contains("rate='-25%'"));
+
+        assert!(engine.set_voice("en-US-Aria'/><x").is_err());
+        assert!(engine.name().contains("SoniaNeural"));
+    }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        assert_eq!(retry_delay(1), Duration::from_millis(250));
+        assert_eq!(retry_delay(2), Duration::from_millis(500));
+        assert_eq!(retry_delay(3), Duration::from_secs(1));
+        let total: Duration = (1..MAX_ATTEMPTS).map(retry_delay).sum();
+        assert!(REQUEST_TIMEOUT * MAX_ATTEMPTS + total < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_audio_payload() {
+        let header = b"Path:audio\r\n";
+        let mut msg = (header.len() as u16).to_be_bytes().to_vec();
+        msg.extend_from_slice(header);
+        msg.extend_from_slice(&[0xff, 0xfb]);
+        assert_eq!(audio_payload(&msg), Some(&[0xff, 0xfb][..]));
+
+        let mut other = 8u16.to_be_bytes().to_vec();
+        other.extend_from_slice(b"Path:xyz");
+        other.push(1);
+        assert_eq!(audio_payload(&other), None);
+        assert_eq!(audio_payload(&[0, 50, 1]), None);
+    }
+
     #[test]
     fn test_xml_escape() {
         assert_eq!(xml_escape("hello"), "hello");
@@ -0,0 +1,399 @@
+//! Voice effects on TTS audio (fun filters).
+//!
+//! `voice.ttsEffects` is a chain of DSP operators run over the synthesized
+//! PCM, in order, before it reaches the output devices:
+//!
+//! - `"pitch:<semitones>"`: shift the pitch without changing the tempo
+//!   (e.g. `"pitch:7"` chipmunk, `"pitch:-5"` deep).
+//! - `"robot"`: ring modulation plus a short metallic comb.
+//! - `"reverb"` / `"reverb:<mix>"`: small-hall Schroeder reverb, `mix`
+//!   0.0 - 1.0 (default 0.3).
+//!
+//! Operators keep their state between buffers, so phrases and the pauses
+//! between them flow through one continuous chain and the reverb tail rings
+//! into the pause. The buffer length never changes, so word timings still
+//! line up. Presets (`PRESETS`) name common chains for the "use the robot
+//! voice" style commands and the settings UI.
+
+use std::f32::consts::PI;
+
+/// Named effect chains. `"normal"` is no effects.
+pub const PRESETS: &[(&str, &[&str])] = &[
+    ("normal", &[]),
+    ("robot", &["robot"]),
+    ("chipmunk", &["pitch:7"]),
+    ("deep", &["pitch:-5"]),
+    ("echo", &["reverb:0.45"]),
+];
+
+/// The effect chain for preset `name`, if it exists.
+pub fn preset(name: &str) -> Option<Vec<String>> {
+    PRESETS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, specs)| specs.iter().map(|s| s.to_string()).collect())
+}
+
+/// Pitch shifts are kept within two octaves either way.
+const MAX_SEMITONES: f32 = 24.0;
+/// Pitch shifter grain length.
+const PITCH_WINDOW_SECS: f32 = 0.05;
+/// Robot ring-modulator carrier.
+const ROBOT_CARRIER_HZ: f32 = 50.0;
+const ROBOT_COMB_SECS: f32 = 0.006;
+const ROBOT_COMB_FEEDBACK: f32 = 0.55;
+const DEFAULT_REVERB_MIX: f32 = 0.3;
+/// Schroeder comb delays (mutually prime-ish, 30-45 ms) and feedback.
+const REVERB_COMB_SECS: [f32; 4] = [0.0297, 0.0371, 0.0411, 0.0437];
+const REVERB_COMB_FEEDBACK: f32 = 0.8;
+/// High-frequency damping inside the combs (0 = none).
+const REVERB_DAMPING: f32 = 0.25;
+const REVERB_ALLPASS_SECS: [f32; 2] = [0.005, 0.0017];
+const REVERB_ALLPASS_GAIN: f32 = 0.7;
+
+/// One parsed operator from `voice.ttsEffects`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EffectSpec {
+    Pitch { semitones: f32 },
+    Robot,
+    Reverb { mix: f32 },
+}
+
+impl EffectSpec {
+    /// Parse `"pitch:<n>"`, `"robot"`, `"reverb"` or `"reverb:<mix>"`
+    /// (case-insensitive). Out-of-range values are clamped.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim().to_ascii_lowercase();
+        let (name, arg) = match spec.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg.trim())),
+            None => (spec.as_str(), None),
+        };
+        let number = |arg: Option<&str>| arg.and_then(|a| a.parse::<f32>().ok()).filter(|v| v.is_finite());
+        match name {
+            "pitch" => Some(Self::Pitch {
+                semitones: number(arg)?.clamp(-MAX_SEMITONES, MAX_SEMITONES),
+            }),
+            "robot" if arg.is_none() => Some(Self::Robot),
+            "reverb" => {
+                let mix = match arg {
+                    Some(_) => number(arg)?,
+                    None => DEFAULT_REVERB_MIX,
+                };
+                Some(Self::Reverb {
+                    mix: mix.clamp(0.0, 1.0),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn build(self, sample_rate: u32) -> Box<dyn Effect> {
+        let rate = sample_rate.max(1) as f32;
+        match self {
+            Self::Pitch { semitones } => Box::new(PitchShift::new(semitones, rate)),
+            Self::Robot => Box::new(Robot::new(rate)),
+            Self::Reverb { mix } => Box::new(Reverb::new(mix, rate)),
+        }
+    }
+}
+
+/// A DSP operator over mono f32 PCM, processed in place.
+trait Effect: Send {
+    fn process(&mut self, samples: &mut [f32]);
+}
+
+/// The configured operators, built for one sample rate.
+pub struct EffectChain {
+    effects: Vec<Box<dyn Effect>>,
+}
+
+impl EffectChain {
+    /// Build the chain for `specs`, or `None` when there is nothing to do.
+    /// Unknown specs are skipped with a warning.
+    pub fn new(specs: &[String], sample_rate: u32) -> Option<Self> {
+        let effects: Vec<Box<dyn Effect>> = specs
+            .iter()
+            .filter_map(|s| {
+                let parsed = EffectSpec::parse(s);
+                if parsed.is_none() {
+                    tracing::warn!(effect = %s, "Ignoring unknown TTS effect");
+                }
+                parsed
+            })
+            .map(|spec| spec.build(sample_rate))
+            .collect();
+        (!effects.is_empty()).then_some(Self { effects })
+    }
+
+    /// Run every operator over `samples`, in order.
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for effect in &mut self.effects {
+            effect.process(samples);
+        }
+        for s in samples.iter_mut() {
+            *s = s.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// A delay line read at fractional positions.
+struct DelayLine {
+    buf: Vec<f32>,
+    pos: usize,
+}
+
+impl DelayLine {
+    fn new(len: usize) -> Self {
+        Self {
+            buf: vec![0.0; len.max(2)],
+            pos: 0,
+        }
+    }
+
+    fn push(&mut self, sample: f32) {
+        self.buf[self.pos] = sample;
+        self.pos = (self.pos + 1) % self.buf.len();
+    }
+
+    /// The sample written `delay` samples ago (1.0 = the latest).
+    fn read(&self, delay: f32) -> f32 {
+        let len = self.buf.len();
+        let delay = delay.clamp(1.0, (len - 1) as f32);
+        let whole = delay.floor() as usize;
+        let frac = delay - whole as f32;
+        let a = self.buf[(self.pos + len - whole) % len];
+        let b = self.buf[(self.pos + len - whole - 1) % len];
+        a + (b - a) * frac
+    }
+
+    /// The oldest sample (a fixed delay of the full length).
+    fn oldest(&self) -> f32 {
+        self.buf[self.pos]
+    }
+}
+
+/// Delay-line pitch shifter: two taps sweep through a grain window half a
+/// period apart, crossfaded so their gains always sum to one. Changes the
+/// pitch by `ratio` while the output keeps the input's length.
+struct PitchShift {
+    line: DelayLine,
+    window: f32,
+    /// Position of the first tap within the window, 0.0 - 1.0.
+    phase: f32,
+    /// Phase change per sample.
+    step: f32,
+}
+
+impl PitchShift {
+    fn new(semitones: f32, rate: f32) -> Self {
+        let ratio = 2f32.powf(semitones / 12.0);
+        let window = (PITCH_WINDOW_SECS * rate).max(8.0);
+        Self {
+            line: DelayLine::new(window as usize + 4),
+            window,
+            phase: 0.0,
+            step: (1.0 - ratio) / window,
+        }
+    }
+}
+
+impl Effect for PitchShift {
+    fn process(&mut self, samples: &mut [f32]) {
+        if self.step == 0.0 {
+            return;
+        }
+        for s in samples.iter_mut() {
+            self.line.push(*s);
+            let p1 = self.phase;
+            let p2 = (p1 + 0.5).fract();
+            // sin² crossfade: sin²(πp) + sin²(π(p + ½)) = 1
+            let g1 = (PI * p1).sin().powi(2);
+            let g2 = (PI * p2).sin().powi(2);
+            *s = g1 * self.line.read(1.0 + p1 * self.window)
+                + g2 * self.line.read(1.0 + p2 * self.window);
+            self.phase = (self.phase + self.step).rem_euclid(1.0);
+        }
+    }
+}
+
+/// Ring modulation with a low carrier, through a short feedback comb that
+/// adds the metallic buzz.
+struct Robot {
+    carrier_phase: f32,
+    carrier_step: f32,
+    comb: DelayLine,
+}
+
+impl Robot {
+    fn new(rate: f32) -> Self {
+        Self {
+            carrier_phase: 0.0,
+            carrier_step: 2.0 * PI * ROBOT_CARRIER_HZ / rate,
+            comb: DelayLine::new((ROBOT_COMB_SECS * rate) as usize),
+        }
+    }
+}
+
+impl Effect for Robot {
+    fn process(&mut self, samples: &mut [f32]) {
+        for s in samples.iter_mut() {
+            let modulated = *s * self.carrier_phase.sin();
+            self.carrier_phase = (self.carrier_phase + self.carrier_step) % (2.0 * PI);
+            let y = modulated + ROBOT_COMB_FEEDBACK * self.comb.oldest();
+            self.comb.push(y);
+            // Undo most of the comb's resonant gain
+            *s = y * (1.0 - ROBOT_COMB_FEEDBACK) * 2.0;
+        }
+    }
+}
+
+/// Damped feedback comb filter.
+struct Comb {
+    line: DelayLine,
+    filtered: f32,
+}
+
+impl Comb {
+    fn tick(&mut self, input: f32) -> f32 {
+        let out = self.line.oldest();
+        self.filtered = out * (1.0 - REVERB_DAMPING) + self.filtered * REVERB_DAMPING;
+        self.line.push(input + self.filtered * REVERB_COMB_FEEDBACK);
+        out
+    }
+}
+
+/// Schroeder allpass filter.
+struct Allpass {
+    line: DelayLine,
+}
+
+impl Allpass {
+    fn tick(&mut self, input: f32) -> f32 {
+        let delayed = self.line.oldest();
+        let y = -REVERB_ALLPASS_GAIN * input + delayed;
+        self.line.push(input + REVERB_ALLPASS_GAIN * y);
+        y
+    }
+}
+
+/// Four parallel combs into two allpasses, mixed with the dry signal.
+struct Reverb {
+    mix: f32,
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl Reverb {
+    fn new(mix: f32, rate: f32) -> Self {
+        let line = |secs: f32| DelayLine::new((secs * rate) as usize);
+        Self {
+            mix,
+            combs: REVERB_COMB_SECS
+                .iter()
+                .map(|&secs| Comb {
+                    line: line(secs),
+                    filtered: 0.0,
+                })
+                .collect(),
+            allpasses: REVERB_ALLPASS_SECS
+                .iter()
+                .map(|&secs| Allpass { line: line(secs) })
+                .collect(),
+        }
+    }
+}
+
+impl Effect for Reverb {
+    fn process(&mut self, samples: &mut [f32]) {
+        let comb_gain = 1.0 / self.combs.len() as f32;
+        for s in samples.iter_mut() {
+            let input = *s;
+            let mut wet: f32 = self.combs.iter_mut().map(|c| c.tick(input)).sum::<f32>() * comb_gain;
+            for allpass in &mut self.allpasses {
+                wet = allpass.tick(wet);
+            }
+            *s = input * (1.0 - self.mix) + wet * self.mix;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, rate: u32, secs: f32) -> Vec<f32> {
+        (0..(rate as f32 * secs) as usize)
+            .map(|i| 0.5 * (2.0 * PI * freq * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    fn zero_crossings(samples: &[f32]) -> usize {
+        samples
+            .windows(2)
+            .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+            .count()
+    }
+
+    #[test]
+    fn test_parse_specs() {
+        assert_eq!(EffectSpec::parse("pitch:7"), Some(EffectSpec::Pitch { semitones: 7.0 }));
+        assert_eq!(EffectSpec::parse(" Pitch: -5 "), Some(EffectSpec::Pitch { semitones: -5.0 }));
+        assert_eq!(EffectSpec::parse("pitch:99"), Some(EffectSpec::Pitch { semitones: 24.0 }));
+        assert_eq!(EffectSpec::parse("robot"), Some(EffectSpec::Robot));
+        assert_eq!(EffectSpec::parse("reverb"), Some(EffectSpec::Reverb { mix: 0.3 }));
+        assert_eq!(EffectSpec::parse("reverb:2"), Some(EffectSpec::Reverb { mix: 1.0 }));
+        assert_eq!(EffectSpec::parse("pitch"), None);
+        assert_eq!(EffectSpec::parse("pitch:high"), None);
+        assert_eq!(EffectSpec::parse("robot:3"), None);
+        assert_eq!(EffectSpec::parse("flanger"), None);
+    }
+
+    #[test]
+    fn test_chain_and_presets() {
+        assert!(EffectChain::new(&[], 24_000).is_none());
+        assert!(EffectChain::new(&["flanger".into()], 24_000).is_none());
+        for (name, _) in PRESETS {
+            let specs = preset(name).unwrap();
+            assert!(specs.iter().all(|s| EffectSpec::parse(s).is_some()), "{}", name);
+        }
+        assert_eq!(preset("normal"), Some(vec![]));
+        assert_eq!(preset("pirate"), None);
+    }
+
+    #[test]
+    fn test_pitch_shift_octave_up() {
+        let rate = 24_000;
+        let mut samples = sine(200.0, rate, 1.0);
+        let before = zero_crossings(&samples[rate as usize / 4..]);
+        let mut chain = EffectChain::new(&["pitch:12".into()], rate).unwrap();
+        chain.process(&mut samples);
+        assert_eq!(samples.len(), rate as usize);
+        // Skip the window it takes to fill the delay line
+        let after = zero_crossings(&samples[rate as usize / 4..]);
+        let ratio = after as f32 / before as f32;
+        assert!((1.8..2.2).contains(&ratio), "ratio {}", ratio);
+    }
+
+    #[test]
+    fn test_reverb_rings_into_silence() {
+        let rate = 24_000;
+        let mut chain = EffectChain::new(&["reverb".into()], rate).unwrap();
+        let mut burst = sine(300.0, rate, 0.1);
+        chain.process(&mut burst);
+        // The pause after a phrase goes through the same chain
+        let mut pause = vec![0.0; rate as usize / 5];
+        chain.process(&mut pause);
+        assert!(pause.iter().any(|s| s.abs() > 0.001));
+        assert!(pause.iter().all(|s| s.abs() <= 1.0));
+    }
+
+    #[test]
+    fn test_robot_stays_bounded() {
+        let rate = 24_000;
+        let mut samples = sine(150.0, rate, 0.5);
+        let mut chain = EffectChain::new(&["robot".into(), "reverb:0.2".into()], rate).unwrap();
+        chain.process(&mut samples);
+        assert!(samples.iter().all(|s| s.is_finite() && s.abs() <= 1.0));
+        assert!(samples.iter().any(|s| s.abs() > 0.05));
+    }
+}
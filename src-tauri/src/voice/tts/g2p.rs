@@ -0,0 +1,911 @@
+//! Built-in English grapheme-to-phoneme conversion for Kokoro.
+//!
+//! Avoids spawning an `espeak-ng` process per synthesis call. Words are
+//! looked up in a small embedded lexicon first; anything else goes through
+//! the NRL letter-to-sound rules (Elovitz et al., 1976). Output uses the same
+//! IPA symbols espeak-ng produces, so it feeds the existing Kokoro vocab.
+//!
+//! Only English voices are handled; callers fall back to the espeak-ng CLI
+//! when [`phonemize`] returns `None`.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Embedded lexicon: `WORD PH PH PH` per line, CMUdict-style stress digits.
+static LEXICON: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
+    include_str!("g2p_lexicon.txt")
+        .lines()
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| l.split_once(' '))
+        .collect()
+});
+
+/// Punctuation passed through to Kokoro (all present in its vocab).
+const PUNCTUATION: &[char] = &['.', ',', '!', '?', ';', ':'];
+
+/// Convert English text to IPA phonemes.
+///
+/// `lang` is the espeak language code of the voice ("en-us", "en-gb").
+/// Returns `None` for other languages or text with nothing to pronounce.
+pub fn phonemize(text: &str, lang: &str) -> Option<String> {
+    let british = match lang {
+        "en-us" | "en" => false,
+        "en-gb" => true,
+        _ => return None,
+    };
+
+    let mut out = String::new();
+    for token in tokenize(text) {
+        match token {
+            Token::Word(word) => {
+                if !out.is_empty() && !out.ends_with(' ') {
+                    out.push(' ');
+                }
+                out.push_str(&word_to_ipa(&word, british));
+            }
+            Token::Punct(c) => {
+                // Attach to the preceding word, like espeak's clause marks.
+                let trimmed = out.trim_end().len();
+                out.truncate(trimmed);
+                out.push(c);
+                out.push(' ');
+            }
+        }
+    }
+
+    let out = out.trim().to_string();
+    if out.chars().any(|c| c.is_alphabetic()) {
+        Some(out)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// Uppercased word (letters and apostrophes only).
+    Word(String),
+    Punct(char),
+}
+
+/// Split text into words and punctuation, spelling out numbers.
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_alphabetic() {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphabetic() || is_inner_apostrophe(&chars, i))
+            {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(Token::Word(
+                word.replace('\u{2019}', "'").to_ascii_uppercase(),
+            ));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            // Digits, thousands separators, and one decimal point.
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || ((chars[i] == ',' || chars[i] == '.')
+                        && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())))
+            {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().filter(|c| **c != ',').collect();
+            for word in number_to_words(&number).split_whitespace() {
+                tokens.push(Token::Word(word.to_ascii_uppercase()));
+            }
+        } else {
+            if PUNCTUATION.contains(&c) && !matches!(tokens.last(), Some(Token::Punct(_)) | None) {
+                tokens.push(Token::Punct(c));
+            }
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// An apostrophe between two letters ("don't", "it's").
+fn is_inner_apostrophe(chars: &[char], i: usize) -> bool {
+    matches!(chars[i], '\'' | '\u{2019}')
+        && i > 0
+        && chars[i - 1].is_ascii_alphabetic()
+        && chars.get(i + 1).is_some_and(|c| c.is_ascii_alphabetic())
+}
+
+// ── Numbers ────────────────────────────────────────────────────────
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Spell out a digit string ("1200", "3.5"). Very long integers are read
+/// digit by digit.
+fn number_to_words(number: &str) -> String {
+    let (int_part, frac_part) = match number.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (number, None),
+    };
+
+    let mut words = match int_part.parse::<u64>() {
+        Ok(n) if int_part.len() <= 12 => cardinal(n),
+        _ => digits(int_part),
+    };
+    if let Some(frac) = frac_part {
+        words.push_str(" point ");
+        words.push_str(&digits(frac));
+    }
+    words
+}
+
+fn digits(s: &str) -> String {
+    s.chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| ONES[d as usize])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn cardinal(n: u64) -> String {
+    if n < 20 {
+        return ONES[n as usize].to_string();
+    }
+    if n < 100 {
+        let tens = TENS[(n / 10) as usize];
+        return match n % 10 {
+            0 => tens.to_string(),
+            r => format!("{tens} {}", ONES[r as usize]),
+        };
+    }
+    if n < 1000 {
+        let head = format!("{} hundred", ONES[(n / 100) as usize]);
+        return match n % 100 {
+            0 => head,
+            r => format!("{head} {}", cardinal(r)),
+        };
+    }
+    for (scale, name) in [
+        (1_000_000_000, "billion"),
+        (1_000_000, "million"),
+        (1_000, "thousand"),
+    ] {
+        if n >= scale {
+            let head = format!("{} {name}", cardinal(n / scale));
+            return match n % scale {
+                0 => head,
+                r => format!("{head} {}", cardinal(r)),
+            };
+        }
+    }
+    unreachable!("all n >= 1000 handled by scales")
+}
+
+// ── Words ──────────────────────────────────────────────────────────
+
+/// Pronounce one uppercased word.
+fn word_to_ipa(word: &str, british: bool) -> String {
+    if let Some(phones) = LEXICON.get(word) {
+        return arpabet_to_ipa(phones, british);
+    }
+    let phones = apply_rules(word);
+    arpabet_to_ipa(&mark_default_stress(&phones), british)
+}
+
+/// Rule output carries no stress; put primary stress on the first full
+/// vowel so Kokoro gets a stressed syllable per word.
+fn mark_default_stress(phones: &[&str]) -> String {
+    let mut stressed = false;
+    phones
+        .iter()
+        .map(|p| {
+            if is_vowel_phone(p) {
+                if !stressed && *p != "AX" {
+                    stressed = true;
+                    format!("{p}1")
+                } else {
+                    format!("{p}0")
+                }
+            } else {
+                p.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_vowel_phone(p: &str) -> bool {
+    matches!(
+        p,
+        "AA" | "AE"
+            | "AH"
+            | "AO"
+            | "AW"
+            | "AX"
+            | "AY"
+            | "EH"
+            | "ER"
+            | "EY"
+            | "IH"
+            | "IY"
+            | "OW"
+            | "OY"
+            | "UH"
+            | "UW"
+    )
+}
+
+/// Map ARPAbet phones (optionally with stress digits) to espeak-style IPA.
+fn arpabet_to_ipa(phones: &str, british: bool) -> String {
+    let mut out = String::new();
+    for phone in phones.split_whitespace() {
+        let (base, stress) = match phone.as_bytes().last() {
+            Some(d @ b'0'..=b'2') => (&phone[..phone.len() - 1], Some(d - b'0')),
+            _ => (phone, None),
+        };
+        let unstressed = stress == Some(0);
+        match stress {
+            Some(1) => out.push('ˈ'),
+            Some(2) => out.push('ˌ'),
+            _ => {}
+        }
+        let ipa = match base {
+            "AA" => "ɑː",
+            "AE" => "æ",
+            "AH" | "AX" if unstressed || base == "AX" => "ə",
+            "AH" => "ʌ",
+            "AO" => "ɔː",
+            "AW" => "aʊ",
+            "AY" => "aɪ",
+            "EH" => "ɛ",
+            "ER" if unstressed && british => "ə",
+            "ER" if unstressed => "ɚ",
+            "ER" => "ɜː",
+            "EY" => "eɪ",
+            "IH" => "ɪ",
+            "IY" if unstressed => "i",
+            "IY" => "iː",
+            "OW" if british => "əʊ",
+            "OW" => "oʊ",
+            "OY" => "ɔɪ",
+            "UH" => "ʊ",
+            "UW" => "uː",
+            "B" => "b",
+            "CH" => "tʃ",
+            "D" => "d",
+            "DH" => "ð",
+            "F" => "f",
+            "G" => "ɡ",
+            "HH" => "h",
+            "JH" => "dʒ",
+            "K" => "k",
+            "L" => "l",
+            "M" => "m",
+            "N" => "n",
+            "NG" => "ŋ",
+            "P" => "p",
+            "R" => "ɹ",
+            "S" => "s",
+            "SH" => "ʃ",
+            "T" => "t",
+            "TH" => "θ",
+            "V" => "v",
+            "W" | "WH" => "w",
+            "Y" => "j",
+            "Z" => "z",
+            "ZH" => "ʒ",
+            _ => "",
+        };
+        out.push_str(ipa);
+    }
+    out
+}
+
+// ── Letter-to-sound rules ──────────────────────────────────────────
+//
+// Each rule is (left context, match, right context, phones). Rules are tried
+// in order and the first match wins. Context symbols:
+//   ' '  word boundary          '#'  one or more vowels
+//   ':'  zero or more consonants '^'  one consonant
+//   '.'  a voiced consonant     '+'  a front vowel (E, I, Y)
+//   '%'  a suffix (E, ER, ES, ED, ING, ELY) — right context only
+//   '@'  a consonant that makes a following U long (T S R D L Z N J TH CH SH)
+
+type Rule = (&'static str, &'static str, &'static str, &'static str);
+
+#[rustfmt::skip]
+const RULES: &[Rule] = &[
+    // A
+    ("", "A", " ", "AX"),
+    (" ", "ARE", " ", "AA R"),
+    (" ", "AR", "O", "AX R"),
+    ("", "AR", "#", "EH R"),
+    ("^", "AS", "#", "EY S"),
+    ("", "A", "WA", "AX"),
+    ("", "AW", "", "AO"),
+    (" :", "ANY", "", "EH N IY"),
+    ("", "A", "^+#", "EY"),
+    ("#:", "ALLY", "", "AX L IY"),
+    (" ", "AL", "#", "AX L"),
+    ("", "AGAIN", "", "AX G EH N"),
+    ("#:", "AG", "E", "IH JH"),
+    ("", "A", "^+:#", "AE"),
+    (" :", "A", "^+ ", "EY"),
+    ("", "A", "^%", "EY"),
+    (" ", "ARR", "", "AX R"),
+    ("", "ARR", "", "AE R"),
+    (" :", "AR", " ", "AA R"),
+    ("", "AR", " ", "ER"),
+    ("", "AR", "", "AA R"),
+    ("", "AIR", "", "EH R"),
+    ("", "AI", "", "EY"),
+    ("", "AY", "", "EY"),
+    ("", "AU", "", "AO"),
+    ("#:", "AL", " ", "AX L"),
+    ("#:", "ALS", " ", "AX L Z"),
+    ("", "ALK", "", "AO K"),
+    ("", "AL", "^", "AO L"),
+    (" :", "ABLE", "", "EY B AX L"),
+    ("", "ABLE", "", "AX B AX L"),
+    ("", "ANG", "+", "EY N JH"),
+    ("", "A", "", "AE"),
+    // B
+    (" ", "BE", "^#", "B IH"),
+    ("", "BEING", "", "B IY IH NG"),
+    (" ", "BOTH", " ", "B OW TH"),
+    (" ", "BUS", "#", "B IH Z"),
+    ("", "BUIL", "", "B IH L"),
+    ("", "B", "", "B"),
+    // C
+    (" ", "CH", "^", "K"),
+    ("^E", "CH", "", "K"),
+    ("", "CH", "", "CH"),
+    (" S", "CI", "#", "S AY"),
+    ("", "CI", "A", "SH"),
+    ("", "CI", "O", "SH"),
+    ("", "CI", "EN", "SH"),
+    ("", "C", "+", "S"),
+    ("", "CK", "", "K"),
+    ("", "COM", "%", "K AH M"),
+    ("", "C", "", "K"),
+    // D
+    ("#:", "DED", " ", "D IH D"),
+    (".E", "D", " ", "D"),
+    ("#:^E", "D", " ", "T"),
+    (" ", "DE", "^#", "D IH"),
+    (" ", "DO", " ", "D UW"),
+    (" ", "DOES", "", "D AH Z"),
+    (" ", "DOING", "", "D UW IH NG"),
+    (" ", "DOW", "", "D AW"),
+    ("", "DU", "A", "JH UW"),
+    ("", "D", "", "D"),
+    // E
+    ("#:", "E", " ", ""),
+    ("':^", "E", " ", ""),
+    (" :", "E", " ", "IY"),
+    ("#", "ED", " ", "D"),
+    ("#:", "E", "D ", ""),
+    ("", "EV", "ER", "EH V"),
+    ("", "E", "^%", "IY"),
+    ("", "ERI", "#", "IY R IY"),
+    ("", "ERI", "", "EH R IH"),
+    ("#:", "ER", "#", "ER"),
+    ("", "ER", "#", "EH R"),
+    ("", "ER", "", "ER"),
+    (" ", "EVEN", "", "IY V EH N"),
+    ("#:", "E", "W", ""),
+    ("@", "EW", "", "UW"),
+    ("", "EW", "", "Y UW"),
+    ("", "E", "O", "IY"),
+    ("#:&", "ES", " ", "IH Z"),
+    ("#:", "E", "S ", ""),
+    ("#:", "ELY", " ", "L IY"),
+    ("#:", "EMENT", "", "M EH N T"),
+    ("", "EFUL", "", "F UH L"),
+    ("", "EE", "", "IY"),
+    ("", "EARN", "", "ER N"),
+    (" ", "EAR", "^", "ER"),
+    ("", "EAD", "", "EH D"),
+    ("#:", "EA", " ", "IY AX"),
+    ("", "EA", "SU", "EH"),
+    ("", "EA", "", "IY"),
+    ("", "EIGH", "", "EY"),
+    ("", "EI", "", "IY"),
+    (" ", "EYE", "", "AY"),
+    ("", "EY", "", "IY"),
+    ("", "EU", "", "Y UW"),
+    ("", "E", "", "EH"),
+    // F
+    ("", "FUL", "", "F UH L"),
+    ("", "F", "", "F"),
+    // G
+    ("", "GIV", "", "G IH V"),
+    (" ", "G", "I^", "G"),
+    ("", "GE", "T", "G EH"),
+    ("SU", "GGES", "", "G JH EH S"),
+    ("", "GG", "", "G"),
+    (" B#", "G", "", "G"),
+    ("", "G", "+", "JH"),
+    ("", "GREAT", "", "G R EY T"),
+    ("#", "GH", "", ""),
+    ("", "G", "", "G"),
+    // H
+    (" ", "HAV", "", "HH AE V"),
+    (" ", "HERE", "", "HH IY R"),
+    (" ", "HOUR", "", "AW ER"),
+    ("", "HOW", "", "HH AW"),
+    ("", "H", "#", "HH"),
+    ("", "H", "", ""),
+    // I
+    (" ", "IN", "", "IH N"),
+    (" ", "I", " ", "AY"),
+    ("", "IN", "D", "AY N"),
+    ("", "IER", "", "IY ER"),
+    ("#:R", "IED", "", "IY D"),
+    ("", "IED", " ", "AY D"),
+    ("", "IEN", "", "IY EH N"),
+    ("", "IE", "T", "AY EH"),
+    (" :", "I", "%", "AY"),
+    ("", "I", "%", "IY"),
+    ("", "IE", "", "IY"),
+    ("", "I", "^+:#", "IH"),
+    ("", "IR", "#", "AY R"),
+    ("", "IZ", "%", "AY Z"),
+    ("", "IS", "%", "AY Z"),
+    ("", "I", "D%", "AY"),
+    ("+^", "I", "^+", "IH"),
+    ("", "I", "T%", "AY"),
+    ("#:^", "I", "^+", "IH"),
+    ("", "I", "^+", "AY"),
+    ("", "IR", "", "ER"),
+    ("", "IGH", "", "AY"),
+    ("", "ILD", "", "AY L D"),
+    ("", "IGN", " ", "AY N"),
+    ("", "IGN", "^", "AY N"),
+    ("", "IGN", "%", "AY N"),
+    ("", "IQUE", "", "IY K"),
+    ("", "I", "", "IH"),
+    // J
+    ("", "J", "", "JH"),
+    // K
+    (" ", "K", "N", ""),
+    ("", "K", "", "K"),
+    // L
+    ("", "LO", "C#", "L OW"),
+    ("L", "L", "", ""),
+    ("#:^", "L", "%", "AX L"),
+    ("", "LEAD", "", "L IY D"),
+    ("", "L", "", "L"),
+    // M
+    ("", "MOV", "", "M UW V"),
+    ("", "M", "", "M"),
+    // N
+    ("E", "NG", "+", "N JH"),
+    ("", "NG", "R", "NG G"),
+    ("", "NG", "#", "NG G"),
+    ("", "NGL", "%", "NG G AX L"),
+    ("", "NG", "", "NG"),
+    ("", "NK", "", "NG K"),
+    (" ", "NOW", " ", "N AW"),
+    ("", "N", "", "N"),
+    // O
+    ("", "OF", " ", "AX V"),
+    ("", "OROUGH", "", "ER OW"),
+    ("#:", "OR", " ", "ER"),
+    ("#:", "ORS", " ", "ER Z"),
+    ("", "OR", "", "AO R"),
+    (" ", "ONE", "", "W AH N"),
+    ("", "OW", "", "OW"),
+    (" ", "OVER", "", "OW V ER"),
+    ("", "OV", "", "AH V"),
+    ("", "O", "^%", "OW"),
+    ("", "O", "^EN", "OW"),
+    ("", "O", "^I#", "OW"),
+    ("", "OL", "D", "OW L"),
+    ("", "OUGHT", "", "AO T"),
+    ("", "OUGH", "", "AH F"),
+    (" ", "OU", "", "AW"),
+    ("H", "OU", "S#", "AW"),
+    ("", "OUS", "", "AX S"),
+    ("", "OUR", "", "AO R"),
+    ("", "OULD", "", "UH D"),
+    ("^", "OU", "^L", "AH"),
+    ("", "OUP", "", "UW P"),
+    ("", "OU", "", "AW"),
+    ("", "OY", "", "OY"),
+    ("", "OING", "", "OW IH NG"),
+    ("", "OI", "", "OY"),
+    ("", "OOR", "", "AO R"),
+    ("", "OOK", "", "UH K"),
+    ("", "OOD", "", "UH D"),
+    ("", "OO", "", "UW"),
+    ("", "O", "E", "OW"),
+    ("", "O", " ", "OW"),
+    ("", "OA", "", "OW"),
+    (" ", "ONLY", "", "OW N L IY"),
+    (" ", "ONCE", "", "W AH N S"),
+    ("", "ON'T", "", "OW N T"),
+    ("C", "O", "N", "AA"),
+    ("", "O", "NG", "AO"),
+    (" :^", "O", "N", "AH"),
+    ("I", "ON", "", "AX N"),
+    ("#:", "ON", " ", "AX N"),
+    ("#^", "ON", "", "AX N"),
+    ("", "O", "ST ", "OW"),
+    ("", "OF", "^", "AO F"),
+    ("", "OTHER", "", "AH DH ER"),
+    ("", "OSS", " ", "AO S"),
+    ("#:^", "OM", "", "AH M"),
+    ("", "O", "", "AA"),
+    // P
+    ("", "PH", "", "F"),
+    ("", "PEOP", "", "P IY P"),
+    ("", "POW", "", "P AW"),
+    ("", "PUT", " ", "P UH T"),
+    ("", "P", "", "P"),
+    // Q
+    ("", "QUAR", "", "K W AO R"),
+    ("", "QU", "", "K W"),
+    ("", "Q", "", "K"),
+    // R
+    (" ", "RE", "^#", "R IY"),
+    ("", "R", "", "R"),
+    // S
+    ("", "SH", "", "SH"),
+    ("#", "SION", "", "ZH AX N"),
+    ("", "SOME", "", "S AH M"),
+    ("#", "SUR", "#", "ZH ER"),
+    ("", "SUR", "#", "SH ER"),
+    ("#", "SU", "#", "ZH UW"),
+    ("#", "SSU", "#", "SH UW"),
+    ("#", "SED", " ", "Z D"),
+    ("#", "S", "#", "Z"),
+    ("", "SAID", "", "S EH D"),
+    ("^", "SION", "", "SH AX N"),
+    ("", "S", "S", ""),
+    (".", "S", " ", "Z"),
+    ("#:.E", "S", " ", "Z"),
+    ("#:^##", "S", " ", "Z"),
+    ("#:^#", "S", " ", "S"),
+    ("U", "S", " ", "S"),
+    (" :#", "S", " ", "Z"),
+    (" ", "SCH", "", "S K"),
+    ("", "S", "C+", ""),
+    ("#", "SM", "", "Z M"),
+    ("#", "SN", "'", "Z AX N"),
+    ("", "S", "", "S"),
+    // T
+    (" ", "THE", " ", "DH AX"),
+    ("", "TO", " ", "T UW"),
+    ("", "THAT", " ", "DH AE T"),
+    (" ", "THIS", " ", "DH IH S"),
+    (" ", "THEY", "", "DH EY"),
+    (" ", "THERE", "", "DH EH R"),
+    ("", "THER", "", "DH ER"),
+    ("", "THEIR", "", "DH EH R"),
+    (" ", "THAN", " ", "DH AE N"),
+    (" ", "THEM", " ", "DH EH M"),
+    ("", "THESE", " ", "DH IY Z"),
+    (" ", "THEN", "", "DH EH N"),
+    ("", "THROUGH", "", "TH R UW"),
+    ("", "THOSE", "", "DH OW Z"),
+    ("", "THOUGH", " ", "DH OW"),
+    (" ", "THUS", "", "DH AH S"),
+    ("", "TH", "", "TH"),
+    ("#:", "TED", " ", "T IH D"),
+    ("S", "TI", "#N", "CH"),
+    ("", "TI", "O", "SH"),
+    ("", "TI", "A", "SH"),
+    ("", "TIEN", "", "SH AX N"),
+    ("", "TUR", "#", "CH ER"),
+    ("", "TU", "A", "CH UW"),
+    (" ", "TWO", "", "T UW"),
+    ("", "T", "", "T"),
+    // U
+    (" ", "UN", "I", "Y UW N"),
+    (" ", "UN", "", "AH N"),
+    (" ", "UPON", "", "AX P AO N"),
+    ("@", "UR", "#", "UH R"),
+    ("", "UR", "#", "Y UH R"),
+    ("", "UR", "", "ER"),
+    ("", "U", "^ ", "AH"),
+    ("", "U", "^^", "AH"),
+    ("", "UY", "", "AY"),
+    (" G", "U", "#", ""),
+    ("G", "U", "%", ""),
+    ("G", "U", "#", "W"),
+    ("#N", "U", "", "Y UW"),
+    ("@", "U", "", "UW"),
+    ("", "U", "", "Y UW"),
+    // V
+    ("", "VIEW", "", "V Y UW"),
+    ("", "V", "", "V"),
+    // W
+    (" ", "WERE", "", "W ER"),
+    ("", "WA", "S", "W AA"),
+    ("", "WA", "T", "W AA"),
+    ("", "WHERE", "", "WH EH R"),
+    ("", "WHAT", "", "WH AA T"),
+    ("", "WHOL", "", "HH OW L"),
+    ("", "WHO", "", "HH UW"),
+    ("", "WH", "", "WH"),
+    ("", "WAR", "", "W AO R"),
+    ("", "WOR", "^", "W ER"),
+    ("", "WR", "", "R"),
+    ("", "W", "", "W"),
+    // X
+    ("", "X", "", "K S"),
+    // Y
+    ("", "YOUNG", "", "Y AH NG"),
+    (" ", "YOU", "", "Y UW"),
+    (" ", "YES", "", "Y EH S"),
+    (" ", "Y", "", "Y"),
+    ("#:^", "Y", " ", "IY"),
+    ("#:^", "Y", "I", "IY"),
+    (" :", "Y", " ", "AY"),
+    (" :", "Y", "#", "AY"),
+    (" :", "Y", "^+:#", "IH"),
+    (" :", "Y", "^#", "AY"),
+    ("", "Y", "", "IH"),
+    // Z
+    ("", "Z", "", "Z"),
+];
+
+fn is_vowel(c: u8) -> bool {
+    matches!(c, b'A' | b'E' | b'I' | b'O' | b'U')
+}
+
+fn is_consonant(c: u8) -> bool {
+    c.is_ascii_uppercase() && !is_vowel(c)
+}
+
+/// Run the letter-to-sound rules over an uppercased word.
+fn apply_rules(word: &str) -> Vec<&'static str> {
+    // Pad with boundaries so context checks never run off the ends.
+    let padded = format!(" {word} ");
+    let text = padded.as_bytes();
+    let end = text.len() - 1;
+
+    let mut phones = Vec::new();
+    let mut i = 1;
+    while i < end {
+        let rule = RULES.iter().find(|(left, matched, right, _)| {
+            text[i..end].starts_with(matched.as_bytes())
+                && left_matches(left.as_bytes(), text, i)
+                && right_matches(right.as_bytes(), text, i + matched.len())
+        });
+        match rule {
+            Some((_, matched, _, out)) => {
+                phones.extend(out.split_whitespace());
+                i += matched.len();
+            }
+            // Apostrophes and anything else without a rule are silent.
+            None => i += 1,
+        }
+    }
+    phones
+}
+
+/// Match a left-context pattern ending just before `pos`, scanning leftwards.
+fn left_matches(pattern: &[u8], text: &[u8], pos: usize) -> bool {
+    let at = |i: isize| if i < 0 { b' ' } else { text[i as usize] };
+    let mut i = pos as isize - 1;
+    for &p in pattern.iter().rev() {
+        match p {
+            b'#' => {
+                if !is_vowel(at(i)) {
+                    return false;
+                }
+                while is_vowel(at(i)) {
+                    i -= 1;
+                }
+            }
+            b':' => {
+                while is_consonant(at(i)) {
+                    i -= 1;
+                }
+            }
+            b'^' => {
+                if !is_consonant(at(i)) {
+                    return false;
+                }
+                i -= 1;
+            }
+            b'.' => {
+                if !b"BDVGJLMNRWZ".contains(&at(i)) {
+                    return false;
+                }
+                i -= 1;
+            }
+            b'+' => {
+                if !b"EIY".contains(&at(i)) {
+                    return false;
+                }
+                i -= 1;
+            }
+            b'@' => {
+                // TH, CH and SH read right-to-left start with the H.
+                if at(i) == b'H' && b"TCS".contains(&at(i - 1)) {
+                    i -= 1;
+                } else if !b"TSRDLZNJ".contains(&at(i)) {
+                    return false;
+                }
+                i -= 1;
+            }
+            b'&' => {
+                if at(i) == b'H' && b"CS".contains(&at(i - 1)) {
+                    i -= 1;
+                } else if !b"SCGZXJ".contains(&at(i)) {
+                    return false;
+                }
+                i -= 1;
+            }
+            b' ' => {
+                if at(i).is_ascii_uppercase() {
+                    return false;
+                }
+                i -= 1;
+            }
+            literal => {
+                if at(i) != literal {
+                    return false;
+                }
+                i -= 1;
+            }
+        }
+    }
+    true
+}
+
+/// Match a right-context pattern starting at `pos`, scanning rightwards.
+fn right_matches(pattern: &[u8], text: &[u8], pos: usize) -> bool {
+    let at = |i: usize| text.get(i).copied().unwrap_or(b' ');
+    let mut i = pos;
+    for &p in pattern {
+        match p {
+            b'#' => {
+                if !is_vowel(at(i)) {
+                    return false;
+                }
+                while is_vowel(at(i)) {
+                    i += 1;
+                }
+            }
+            b':' => {
+                while is_consonant(at(i)) {
+                    i += 1;
+                }
+            }
+            b'^' => {
+                if !is_consonant(at(i)) {
+                    return false;
+                }
+                i += 1;
+            }
+            b'.' => {
+                if !b"BDVGJLMNRWZ".contains(&at(i)) {
+                    return false;
+                }
+                i += 1;
+            }
+            b'+' => {
+                if !b"EIY".contains(&at(i)) {
+                    return false;
+                }
+                i += 1;
+            }
+            b'%' => {
+                // Suffixes: E, ER, ES, ED, ELY, ING.
+                if at(i) == b'E' {
+                    i += 1;
+                    if at(i) == b'L' && at(i + 1) == b'Y' {
+                        i += 2;
+                    } else if b"RSD".contains(&at(i)) {
+                        i += 1;
+                    }
+                } else if at(i) == b'I' && at(i + 1) == b'N' && at(i + 2) == b'G' {
+                    i += 3;
+                } else {
+                    return false;
+                }
+            }
+            b' ' => {
+                if at(i).is_ascii_uppercase() {
+                    return false;
+                }
+                i += 1;
+            }
+            literal => {
+                if at(i) != literal {
+                    return false;
+                }
+                i += 1;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lexicon_words_use_stress_from_entries() {
+        assert_eq!(phonemize("hello", "en-us").unwrap(), "həlˈoʊ");
+        assert_eq!(phonemize("the", "en-us").unwrap(), "ðə");
+        assert_eq!(phonemize("don't", "en-us").unwrap(), "dˈoʊnt");
+    }
+
+    #[test]
+    fn test_rules_cover_unknown_words() {
+        assert_eq!(word_to_ipa("CAT", false), "kˈæt");
+        assert_eq!(word_to_ipa("SHIP", false), "ʃˈɪp");
+        assert_eq!(word_to_ipa("MAKE", false), "mˈeɪk");
+        assert_eq!(word_to_ipa("PHONE", false), "fˈoʊn");
+        assert_eq!(word_to_ipa("NATION", false), "nˈeɪʃən");
+    }
+
+    #[test]
+    fn test_suffix_contexts() {
+        // Final silent E and the -ED/-ES endings.
+        assert_eq!(apply_rules("HOPED"), vec!["HH", "OW", "P", "T"]);
+        assert_eq!(apply_rules("KISSES"), vec!["K", "IH", "S", "IH", "Z"]);
+    }
+
+    #[test]
+    fn test_british_variants() {
+        assert_eq!(phonemize("no", "en-gb").unwrap(), "nˈəʊ");
+        assert_eq!(phonemize("no", "en-us").unwrap(), "nˈoʊ");
+    }
+
+    #[test]
+    fn test_punctuation_attaches_to_words() {
+        assert_eq!(phonemize("Yes, no.", "en-us").unwrap(), "jˈɛs, nˈoʊ.");
+        assert_eq!(phonemize("  ...hi!!  ", "en-us").unwrap(), "hˈaɪ!");
+    }
+
+    #[test]
+    fn test_numbers_are_spelled_out() {
+        assert_eq!(number_to_words("0"), "zero");
+        assert_eq!(number_to_words("42"), "forty two");
+        assert_eq!(number_to_words("1200"), "one thousand two hundred");
+        assert_eq!(number_to_words("3.14"), "three point one four");
+        assert_eq!(
+            tokenize("1,000"),
+            vec![Token::Word("ONE".into()), Token::Word("THOUSAND".into())]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_language_or_empty_text() {
+        assert!(phonemize("bonjour", "fr-fr").is_none());
+        assert!(phonemize("?!", "en-us").is_none());
+    }
+}
@@ -3,6 +3,92 @@
 //! Two variants: real ONNX inference behind `#[cfg(feature = "onnx")]`,
 //! and a simple stub when the feature is disabled. The stub preserves
 //! the existing test-compatible API (new(voice, speed) -> Self).
+//!
+//! The voice may be a blend of several voices, e.g. `af_bella:0.5+af_sky:0.5`;
+//! their style embeddings are mixed by weight before inference.
+//!
+//! Text longer than the model context is inferred in chunks, split at
+//! sentence (then clause, then word) boundaries and joined with a short
+//! crossfade so long answers don't click or jump at the seams.
+
+/// Parse a Kokoro voice spec into `(voice, weight)` pairs whose weights sum to 1.
+///
+/// Accepts a single voice (`af_bella`) or a `+`-separated blend with optional
+/// `:weight` suffixes. Omitted weights count as 1 and all weights are
+/// normalized, so `af_bella+af_sky` is an even mix. Repeated voices merge.
+pub fn parse_voice_blend(spec: &str) -> Result<Vec<(String, f32)>, String> {
+    let mut parts: Vec<(String, f32)> = Vec::new();
+    for part in spec.split('+') {
+        let (name, weight) = match part.split_once(':') {
+            Some((name, w)) => {
+                let weight: f32 = w
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid weight '{}' in voice blend", w.trim()))?;
+                (name.trim(), weight)
+            }
+            None => (part.trim(), 1.0),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("Invalid voice name '{}' in voice blend", name));
+        }
+        if !weight.is_finite() || weight <= 0.0 {
+            return Err(format!("Weight for '{}' must be a positive number", name));
+        }
+        match parts.iter_mut().find(|(n, _)| n == name) {
+            Some((_, w)) => *w += weight,
+            None => parts.push((name.to_string(), weight)),
+        }
+    }
+    let total: f32 = parts.iter().map(|(_, w)| w).sum();
+    for (_, w) in &mut parts {
+        *w /= total;
+    }
+    Ok(parts)
+}
+
+/// Samples (~20 ms at 22.05 kHz) blended across each chunk boundary.
+#[cfg_attr(not(feature = "onnx"), allow(dead_code))]
+const CHUNK_CROSSFADE_SAMPLES: usize = 441;
+
+/// Pick where to cut a token stream that is longer than `max` tokens.
+///
+/// Prefers the last sentence end (`. ! ? … ;` followed by a space) within
+/// the window, then the last clause break (`, : —`), then the last space,
+/// and only cuts mid-word when there is no space at all. Returns the
+/// exclusive end of the first chunk, always in `1..=max`.
+#[cfg_attr(not(feature = "onnx"), allow(dead_code))]
+fn chunk_split_point(tokens: &[i64], max: usize) -> usize {
+    // Token IDs from the Kokoro vocabulary (see `build_vocab`).
+    const SPACE: i64 = 16;
+    const SENTENCE_ENDS: &[i64] = &[4, 5, 6, 10, 1];
+    const CLAUSE_BREAKS: &[i64] = &[3, 2, 9];
+
+    let window = &tokens[..max.min(tokens.len())];
+    let after_space_following = |marks: &[i64]| {
+        window
+            .windows(2)
+            .rposition(|w| marks.contains(&w[0]) && w[1] == SPACE)
+            .map(|p| p + 2)
+    };
+    after_space_following(SENTENCE_ENDS)
+        .or_else(|| after_space_following(CLAUSE_BREAKS))
+        .or_else(|| window.iter().rposition(|&t| t == SPACE).map(|p| p + 1))
+        .unwrap_or(window.len())
+}
+
+/// Append `next` to `out`, overlapping up to `overlap` samples with an
+/// equal-power crossfade instead of butting the two chunks together.
+#[cfg_attr(not(feature = "onnx"), allow(dead_code))]
+fn append_crossfaded(out: &mut Vec<f32>, next: &[f32], overlap: usize) {
+    let n = overlap.min(out.len()).min(next.len());
+    let start = out.len() - n;
+    for (i, &incoming) in next[..n].iter().enumerate() {
+        let t = (i as f32 + 0.5) / n as f32 * std::f32::consts::FRAC_PI_2;
+        out[start + i] = out[start + i] * t.cos() + incoming * t.sin();
+    }
+    out.extend_from_slice(&next[n..]);
+}
 
 // ── Kokoro TTS (real ONNX implementation) ───────────────────────────
 #[cfg(feature = "onnx")]
@@ -12,7 +98,7 @@ mod inner {
     use std::path::{Path, PathBuf};
     use std::process::Command;
     use std::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::{Arc, Mutex};
+    use std::sync::{Arc, Mutex, OnceLock};
 
     use byteorder::{LittleEndian, ReadBytesExt};
     use tracing::{debug, info, warn};
@@ -47,6 +133,25 @@ mod inner {
         }
     }
 
+    /// One or more voices with normalized weights (see [`super::parse_voice_blend`]).
+    struct VoiceBlend<'a> {
+        parts: Vec<(&'a VoiceData, f32)>,
+    }
+
+    impl VoiceBlend<'_> {
+        /// Weighted sum of each voice's style vector for the token count.
+        fn style_for_len(&self, token_count: usize) -> Result<Vec<f32>, TtsError> {
+            let mut style = vec![0.0f32; STYLE_DIM];
+            for (voice, weight) in &self.parts {
+                let part = voice.style_for_len(token_count)?;
+                for (out, v) in style.iter_mut().zip(part) {
+                    *out += weight * v;
+                }
+            }
+            Ok(style)
+        }
+    }
+
     /// Local Kokoro ONNX TTS engine.
     ///
     /// Loads an ONNX model and voice embeddings from disk, then runs
@@ -75,16 +180,10 @@ mod inner {
             let voices_path = model_dir.join("voices-v1.0.bin");
 
             if !model_path.exists() {
-                return Err(TtsError::SynthesisError(format!(
-                    "Kokoro model not found: {}. Download from HuggingFace.",
-                    model_path.display()
-                )));
+                return Err(TtsError::ModelNotFound(model_path));
             }
             if !voices_path.exists() {
-                return Err(TtsError::SynthesisError(format!(
-                    "Kokoro voices not found: {}. Download from HuggingFace.",
-                    voices_path.display()
-                )));
+                return Err(TtsError::ModelNotFound(voices_path));
             }
 
             let session = ort::session::Session::builder()
@@ -115,21 +214,13 @@ mod inner {
             })
         }
 
-        /// Change the active voice.
-        pub fn set_voice(&mut self, voice: &str) {
-            match self.voice.lock() {
-                Ok(mut g) => *g = voice.to_string(),
-                Err(e) => warn!("voice mutex poisoned in set_voice: {e}"),
-            }
-        }
-
-        /// Change the playback speed.
-        pub fn set_speed(&mut self, speed: f32) {
-            self.speed = speed;
+        /// Find espeak-ng executable (probed once per process).
+        fn find_espeak_ng() -> Option<(PathBuf, Option<PathBuf>)> {
+            static ESPEAK: OnceLock<Option<(PathBuf, Option<PathBuf>)>> = OnceLock::new();
+            ESPEAK.get_or_init(Self::locate_espeak_ng).clone()
         }
 
-        /// Find espeak-ng executable.
-        fn find_espeak_ng() -> Option<(PathBuf, Option<PathBuf>)> {
+        fn locate_espeak_ng() -> Option<(PathBuf, Option<PathBuf>)> {
             // 1. Check if espeak-ng is on PATH
             let mut version_cmd = Command::new("espeak-ng");
             version_cmd.arg("--version");
@@ -169,8 +260,16 @@ mod inner {
             None
         }
 
-        /// Convert text to IPA phonemes using espeak-ng CLI.
+        /// Convert text to IPA phonemes.
+        ///
+        /// With the `builtin-g2p` feature, English goes through the embedded
+        /// G2P; the espeak-ng CLI handles everything else.
         fn phonemize(text: &str, lang: &str) -> Result<String, TtsError> {
+            #[cfg(feature = "builtin-g2p")]
+            if let Some(phonemes) = crate::voice::tts::g2p::phonemize(text, lang) {
+                return Ok(phonemes);
+            }
+
             let (espeak_bin, data_path) = Self::find_espeak_ng().ok_or_else(|| {
                 TtsError::SynthesisError(
                     "espeak-ng not found. Install espeak-ng or place it in tools/espeak-ng/"
@@ -221,10 +320,10 @@ mod inner {
         fn infer_chunk(
             &self,
             tokens: &[i64],
-            voice_data: &VoiceData,
+            voice: &VoiceBlend<'_>,
         ) -> Result<Vec<f32>, TtsError> {
             let token_count = tokens.len();
-            let style = voice_data.style_for_len(token_count)?;
+            let style = voice.style_for_len(token_count)?;
 
             // Pad with 0 at start and end: [0, ...tokens, 0]
             let mut padded = Vec::with_capacity(token_count + 2);
@@ -303,12 +402,20 @@ mod inner {
                     .map_err(|e| TtsError::SynthesisError(format!("voice mutex poisoned: {e}")))?
                     .clone();
 
-                let voice_data = self.voices.get(&voice_name).ok_or_else(|| {
-                    TtsError::SynthesisError(format!("Unknown Kokoro voice: {}", voice_name))
-                })?;
+                let blend = super::parse_voice_blend(&voice_name)
+                    .map_err(TtsError::SynthesisError)?;
+                let parts = blend
+                    .iter()
+                    .map(|(name, weight)| {
+                        self.voices.get(name).map(|v| (v, *weight)).ok_or_else(|| {
+                            TtsError::SynthesisError(format!("Unknown Kokoro voice: {}", name))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let voice = VoiceBlend { parts };
 
-                // Detect language from voice prefix
-                let lang = match voice_name.chars().next() {
+                // Detect language from the (first) voice's prefix
+                let lang = match blend[0].0.chars().next() {
                     Some('a') => "en-us",
                     Some('b') => "en-gb",
                     _ => "en-us",
@@ -330,7 +437,6 @@ mod inner {
                 );
 
                 let mut all_audio = Vec::new();
-                const SPACE_TOKEN: i64 = 16;
 
                 while !tokens.is_empty() {
                     if self.cancelled.load(Ordering::SeqCst) {
@@ -341,17 +447,16 @@ mod inner {
                     let chunk = if tokens.len() <= MAX_PHONEME_TOKENS {
                         std::mem::take(&mut tokens)
                     } else {
-                        let search_end = MAX_PHONEME_TOKENS;
-                        let split_at = tokens[..search_end]
-                            .iter()
-                            .rposition(|&t| t == SPACE_TOKEN)
-                            .map(|p| p + 1)
-                            .unwrap_or(search_end);
+                        let split_at = super::chunk_split_point(&tokens, MAX_PHONEME_TOKENS);
                         tokens.drain(..split_at).collect()
                     };
 
-                    let audio = self.infer_chunk(&chunk, voice_data)?;
-                    all_audio.extend_from_slice(&audio);
+                    let audio = self.infer_chunk(&chunk, &voice)?;
+                    super::append_crossfaded(
+                        &mut all_audio,
+                        &audio,
+                        super::CHUNK_CROSSFADE_SAMPLES,
+                    );
                 }
 
                 if all_audio.is_empty() {
@@ -374,6 +479,28 @@ mod inner {
             self.cancelled.store(true, Ordering::SeqCst);
         }
 
+        fn set_voice(&mut self, voice: &str) -> Result<(), TtsError> {
+            let blend = super::parse_voice_blend(voice).map_err(TtsError::SynthesisError)?;
+            if let Some((name, _)) = blend
+                .iter()
+                .find(|(name, _)| !self.voices.contains_key(name))
+            {
+                return Err(TtsError::SynthesisError(format!(
+                    "Unknown Kokoro voice: {}",
+                    name
+                )));
+            }
+            match self.voice.get_mut() {
+                Ok(g) => *g = voice.to_string(),
+                Err(e) => warn!("voice mutex poisoned in set_voice: {e}"),
+            }
+            Ok(())
+        }
+
+        fn set_speed(&mut self, speed: f32) {
+            self.speed = speed;
+        }
+
         fn name(&self) -> String {
             let voice = match self.voice.lock() {
                 Ok(g) => g.clone(),
@@ -649,15 +776,6 @@ mod inner {
             }
         }
 
-        /// Change the active voice.
-        pub fn set_voice(&mut self, voice: &str) {
-            self.voice = voice.to_string();
-        }
-
-        /// Change the playback speed.
-        pub fn set_speed(&mut self, speed: f32) {
-            self.speed = speed;
-        }
     }
 
     impl TtsEngine for KokoroTts {
@@ -704,6 +822,16 @@ mod inner {
             self.cancelled.store(true, Ordering::SeqCst);
         }
 
+        fn set_voice(&mut self, voice: &str) -> Result<(), TtsError> {
+            super::parse_voice_blend(voice).map_err(TtsError::SynthesisError)?;
+            self.voice = voice.to_string();
+            Ok(())
+        }
+
+        fn set_speed(&mut self, speed: f32) {
+            self.speed = speed;
+        }
+
         fn name(&self) -> String {
             format!("Kokoro ({}) [stub]", self.voice)
         }
@@ -715,3 +843,73 @@ mod inner {
 }
 
 pub use inner::KokoroTts;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_voice() {
+        assert_eq!(parse_voice_blend("af_bella").unwrap(), vec![("af_bella".to_string(), 1.0)]);
+    }
+
+    #[test]
+    fn test_parse_blend_normalizes_weights() {
+        let blend = parse_voice_blend("af_bella:0.3 + af_sky:0.9").unwrap();
+        assert_eq!(blend[0].0, "af_bella");
+        assert!((blend[0].1 - 0.25).abs() < 1e-6);
+        assert!((blend[1].1 - 0.75).abs() < 1e-6);
+
+        let even = parse_voice_blend("af_bella+af_sky").unwrap();
+        assert_eq!(even[0].1, 0.5);
+        assert_eq!(even[1].1, 0.5);
+    }
+
+    #[test]
+    fn test_parse_blend_merges_repeats() {
+        let blend = parse_voice_blend("af_bella:1+af_sky:2+af_bella:1").unwrap();
+        assert_eq!(blend.len(), 2);
+        assert_eq!(blend[0].1, 0.5);
+    }
+
+    #[test]
+    fn test_parse_blend_rejects_bad_specs() {
+        assert!(parse_voice_blend("").is_err());
+        assert!(parse_voice_blend("af_bella+").is_err());
+        assert!(parse_voice_blend("af_bella:abc").is_err());
+        assert!(parse_voice_blend("af_bella:0").is_err());
+        assert!(parse_voice_blend("af_bella:-1+af_sky:2").is_err());
+        assert!(parse_voice_blend("../af_bella").is_err());
+    }
+
+    #[test]
+    fn test_chunk_split_prefers_sentence_then_clause_then_space() {
+        // "ab. cd, ef gh" as token IDs: letters 40+, '.'=4, ','=3, ' '=16
+        let tokens = [40, 41, 4, 16, 42, 43, 3, 16, 44, 45, 16, 46, 47];
+        assert_eq!(chunk_split_point(&tokens, 12), 4);
+        // Window ends before the sentence break: fall back to the comma
+        let tokens = [40, 41, 16, 42, 43, 3, 16, 44, 45, 16, 46, 47];
+        assert_eq!(chunk_split_point(&tokens, 11), 7);
+        // No punctuation: last space in the window
+        let tokens = [40, 16, 41, 16, 42, 43];
+        assert_eq!(chunk_split_point(&tokens, 5), 4);
+        // No space at all: hard cut
+        assert_eq!(chunk_split_point(&[40; 20], 8), 8);
+    }
+
+    #[test]
+    fn test_append_crossfaded_overlaps_chunks() {
+        let mut out = Vec::new();
+        append_crossfaded(&mut out, &[1.0; 10], 4);
+        assert_eq!(out, vec![1.0; 10]);
+
+        append_crossfaded(&mut out, &[1.0; 10], 4);
+        assert_eq!(out.len(), 16);
+        // Equal-power blend of two equal signals stays close to 1
+        assert!(out.iter().all(|&s| (0.99..=1.42).contains(&s)));
+
+        // Overlap never exceeds the shorter side
+        append_crossfaded(&mut out, &[0.0; 2], 4);
+        assert_eq!(out.len(), 16);
+    }
+}
@@ -1,23 +1,41 @@
 //! Text-to-Speech (TTS) engine.
 //!
 //! Provides a trait-based abstraction for TTS with implementations for:
-//! - Edge TTS (Microsoft free cloud voices via HTTP REST)
+//! - Edge TTS (Microsoft free cloud voices over WebSocket)
 //! - Kokoro TTS (local ONNX inference, feature-gated behind `onnx`)
+//! - System TTS (the OS speech synthesizer; no download, no network)
 //!
 //! Audio output is f32 PCM samples suitable for playback via rodio.
+//! Engines from [`create_tts_engine`] cache repeated phrases (see [`cache`]),
+//! keep a warm Edge standby behind local engines (see [`standby`]) and fall
+//! back to the system voice when their model or network is missing.
+//! Playback can run the audio through voice effects (see [`effects`]).
 
+pub mod cache;
 pub(crate) mod crypto;
+pub mod effects;
 mod edge_tts;
+#[cfg(feature = "builtin-g2p")]
+mod g2p;
 mod kokoro_impl;
 mod mp3_decode;
+mod output_filter;
 mod phrase_split;
+mod standby;
+mod system_tts;
+pub mod voices;
+mod ws;
 
 use std::future::Future;
 use std::pin::Pin;
 
+pub use cache::CachedTts;
 pub use edge_tts::EdgeTts;
 pub use kokoro_impl::KokoroTts;
-pub use phrase_split::split_into_phrases;
+pub use output_filter::{FilterAction, OutputFilter};
+pub use phrase_split::{ends_with_sentence_punctuation, split_into_phrases};
+pub use standby::WarmStandby;
+pub use system_tts::{SystemFallback, SystemTts};
 
 // ── TTS Engine Trait ────────────────────────────────────────────────
 
@@ -53,9 +71,24 @@ pub trait TtsEngine: Send + Sync {
         })
     }
 
+    /// Word timings for the audio returned by the most recent `synthesize`
+    /// call, drained on read. Engines without boundary metadata return none.
+    fn take_word_boundaries(&self) -> Vec<WordBoundary> {
+        Vec::new()
+    }
+
     /// Interrupt any in-progress synthesis.
     fn stop(&self);
 
+    /// Switch the voice used by subsequent synthesis calls.
+    ///
+    /// Returns an error (and keeps the current voice) if the engine does not
+    /// recognize `voice`.
+    fn set_voice(&mut self, voice: &str) -> Result<(), TtsError>;
+
+    /// Set the speaking-rate multiplier (1.0 = normal) for subsequent synthesis.
+    fn set_speed(&mut self, speed: f32);
+
     /// Get the engine display name (e.g., "Edge TTS (en-US-AriaNeural)").
     fn name(&self) -> String;
 
@@ -63,6 +96,18 @@ pub trait TtsEngine: Send + Sync {
     fn sample_rate(&self) -> u32;
 }
 
+// ── Word Boundaries ─────────────────────────────────────────────────
+
+/// When a word starts within synthesized audio (e.g. Edge TTS `WordBoundary`
+/// metadata). Used to emit `SpeakingProgress` events during playback.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordBoundary {
+    /// Start of the word, relative to the start of the audio.
+    pub offset: std::time::Duration,
+    /// The word as the engine spoke it.
+    pub word: String,
+}
+
 // ── TTS Stream ──────────────────────────────────────────────────────
 
 /// A stream of phrases for incremental TTS synthesis.
@@ -106,6 +151,8 @@ impl TtsStream {
 pub enum TtsError {
     /// TTS synthesis failed.
     SynthesisError(String),
+    /// Model or voice file not found at the expected path.
+    ModelNotFound(std::path::PathBuf),
     /// Network error (for cloud TTS).
     NetworkError(String),
     /// Engine not initialized.
@@ -120,6 +167,7 @@ impl std::fmt::Display for TtsError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::SynthesisError(msg) => write!(f, "TTS synthesis error: {}", msg),
+            Self::ModelNotFound(path) => write!(f, "TTS model not found: {}", path.display()),
             Self::NetworkError(msg) => write!(f, "TTS network error: {}", msg),
             Self::NotReady => write!(f, "TTS engine not ready"),
             Self::Cancelled => write!(f, "TTS synthesis cancelled"),
@@ -135,21 +183,71 @@ impl std::error::Error for TtsError {}
 /// Create a TTS engine from configuration.
 ///
 /// # Arguments
-/// * `adapter` - Adapter name: "edge", "kokoro", "openai-tts", "elevenlabs"
-/// * `voice` - Voice name (engine-specific)
+/// * `adapter` - Adapter name: "edge", "kokoro", "system", "openai-tts", "elevenlabs"
+/// * `voice` - Voice name (engine-specific; Kokoro also accepts blends like `af_bella:0.5+af_sky:0.5`)
 /// * `speed` - Playback speed multiplier
+///
+/// The engine is wrapped in [`CachedTts`], so repeated phrases are served
+/// from the synthesis cache. Kokoro also gets a pre-built Edge engine as a
+/// [`WarmStandby`], so a mid-conversation failure switches voices instantly.
+/// Unless the adapter already is `system`, the result is wrapped in
+/// [`SystemFallback`] when the OS has a synthesizer, so a missing model or
+/// network degrades to the OS voice instead of silence.
 pub fn create_tts_engine(
     adapter: &str,
     voice: Option<&str>,
     speed: Option<f32>,
 ) -> Result<Box<dyn TtsEngine>, TtsError> {
     let speed = speed.unwrap_or(1.0);
+    let engine = create_uncached_engine(adapter, voice, speed)?;
+    let mut cached: Box<dyn TtsEngine> = Box::new(CachedTts::new(engine, speed));
+    if adapter == "kokoro" {
+        let rate = ((speed - 1.0) * 100.0) as i32;
+        let standby = EdgeTts::with_rate(standby_edge_voice(voice.unwrap_or("af_bella")), rate);
+        cached = Box::new(WarmStandby::new(cached, Box::new(standby)));
+    }
+    if adapter == "system" {
+        return Ok(cached);
+    }
+    match SystemTts::new(None, speed) {
+        Ok(system) => Ok(Box::new(SystemFallback::new(cached, system))),
+        Err(_) => {
+            tracing::debug!("No system speech synthesizer; TTS has no offline fallback");
+            Ok(cached)
+        }
+    }
+}
+
+/// Edge voice closest to a Kokoro voice: accent from the first letter
+/// (`a` American, `b` British), gender from the second (`f` / `m`).
+fn standby_edge_voice(kokoro_voice: &str) -> &'static str {
+    let mut chars = kokoro_voice.trim().chars();
+    match (chars.next(), chars.next()) {
+        (Some('b'), Some('m')) => "en-GB-RyanNeural",
+        (Some('b'), _) => "en-GB-SoniaNeural",
+        (_, Some('m')) => "en-US-GuyNeural",
+        _ => "en-US-AriaNeural",
+    }
+}
+
+fn create_uncached_engine(
+    adapter: &str,
+    voice: Option<&str>,
+    speed: f32,
+) -> Result<Box<dyn TtsEngine>, TtsError> {
 
     match adapter {
         "kokoro" => {
+            // Reject malformed blends up front rather than on every phrase.
+            let v = match voice.map(kokoro_impl::parse_voice_blend) {
+                Some(Err(e)) => {
+                    tracing::warn!("Invalid Kokoro voice ({}), using af_bella", e);
+                    "af_bella"
+                }
+                _ => voice.unwrap_or("af_bella"),
+            };
             #[cfg(feature = "onnx")]
             {
-                let v = voice.unwrap_or("af_bella");
                 let data_dir = crate::services::platform::get_data_dir()
                     .join("models")
                     .join("kokoro");
@@ -171,7 +269,6 @@ pub fn create_tts_engine(
             }
             #[cfg(not(feature = "onnx"))]
             {
-                let v = voice.unwrap_or("af_bella");
                 tracing::info!("Creating Kokoro TTS (stub) with voice: {}", v);
                 Ok(Box::new(KokoroTts::new(v, speed)))
             }
@@ -181,6 +278,11 @@ pub fn create_tts_engine(
             let rate = ((speed - 1.0) * 100.0) as i32;
             Ok(Box::new(EdgeTts::with_rate(v, rate)))
         }
+        "system" => {
+            let engine = SystemTts::new(voice, speed)?;
+            tracing::info!("Created {}", engine.name());
+            Ok(Box::new(engine))
+        }
         "openai-tts" => {
             // TODO: Implement OpenAI TTS adapter
             tracing::warn!("OpenAI TTS not yet implemented, falling back to Edge TTS");
@@ -219,7 +321,7 @@ pub struct KokoroDownloadProgress {
 /// The two files Kokoro loads from `model_dir`, with their verified
 /// (HEAD-checked, HTTP 200) download URLs. Kept in sync with
 /// `kokoro_impl::KokoroTts::new`, which joins these exact filenames.
-const KOKORO_FILES: &[(&str, &str)] = &[
+pub const KOKORO_FILES: &[(&str, &str)] = &[
     (
         "kokoro-v1.0.onnx",
         "https://github.com/thewh1teagle/kokoro-onnx/releases/download/model-files-v1.0/kokoro-v1.0.onnx",
@@ -235,113 +337,38 @@ const KOKORO_FILES: &[(&str, &str)] = &[
 ///
 /// `model_dir` MUST be the directory `KokoroTts::new` reads from — i.e.
 /// `get_data_dir()/models/kokoro` — so the files land where inference loads
-/// them. Downloads each file to a `.tmp` sibling first, then renames
-/// atomically (mirrors the STT `ensure_model_exists` pattern). Emits
-/// `kokoro-download-progress` events (per file) every ~5%.
+/// them. Downloads go through the model manager (`.tmp` + size and SHA-256
+/// check + atomic rename, `model-download-progress` events); progress is also
+/// forwarded per file as the legacy `kokoro-download-progress` event the
+/// onboarding wizard listens to.
 pub async fn ensure_kokoro_model_exists(
     model_dir: &std::path::Path,
     app_handle: Option<&tauri::AppHandle>,
 ) -> Result<std::path::PathBuf, TtsError> {
-    use futures_util::StreamExt;
+    use crate::services::model_manager;
     use tauri::Emitter;
-    use tokio::io::AsyncWriteExt;
-
-    tokio::fs::create_dir_all(model_dir).await.map_err(|e| {
-        TtsError::NetworkError(format!("Failed to create Kokoro model dir: {}", e))
-    })?;
-
-    for (filename, url) in KOKORO_FILES {
-        let dest = model_dir.join(filename);
-        if dest.exists() {
-            tracing::info!(path = %dest.display(), "Kokoro file already present");
-            continue;
-        }
-
-        tracing::info!(url = %url, dest = %dest.display(), "Downloading Kokoro file");
-
-        let client = reqwest::Client::new();
-        let resp = client.get(*url).send().await.map_err(|e| {
-            TtsError::NetworkError(format!("HTTP request failed for {}: {}", filename, e))
-        })?;
-        if !resp.status().is_success() {
-            return Err(TtsError::NetworkError(format!(
-                "HTTP {} from {}",
-                resp.status(),
-                url
-            )));
-        }
-
-        let total_size = resp.content_length();
-        let tmp_path = dest.with_extension("tmp");
-        let mut file = tokio::fs::File::create(&tmp_path).await.map_err(|e| {
-            TtsError::NetworkError(format!("Failed to create temp file: {}", e))
-        })?;
-
-        let mut downloaded: u64 = 0;
-        let mut last_progress: u8 = 0;
-        let mut stream = resp.bytes_stream();
-
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|e| {
-                TtsError::NetworkError(format!("Download stream error: {}", e))
-            })?;
-            file.write_all(&chunk).await.map_err(|e| {
-                TtsError::NetworkError(format!("Write error: {}", e))
-            })?;
-            downloaded += chunk.len() as u64;
-
-            if let Some(total) = total_size {
-                let pct = ((downloaded as f64 / total as f64) * 100.0) as u8;
-                if pct >= last_progress + 5 {
-                    last_progress = pct;
-                    let downloaded_mb = downloaded as f64 / 1_048_576.0;
-                    let total_mb = total as f64 / 1_048_576.0;
-                    tracing::info!(
-                        "Downloading Kokoro {}... {}% ({:.1} MB / {:.1} MB)",
-                        filename, pct, downloaded_mb, total_mb
-                    );
-                    if let Some(handle) = app_handle {
-                        let _ = handle.emit(
-                            "kokoro-download-progress",
-                            KokoroDownloadProgress {
-                                model: filename.to_string(),
-                                percent: pct,
-                                downloaded_mb,
-                                total_mb,
-                            },
-                        );
-                    }
-                }
-            }
-        }
-
-        file.flush().await.map_err(|e| {
-            TtsError::NetworkError(format!("Flush error: {}", e))
-        })?;
-        drop(file);
-
-        tokio::fs::rename(&tmp_path, &dest).await.map_err(|e| {
-            TtsError::NetworkError(format!("Rename failed: {}", e))
-        })?;
 
-        // Emit a final 100% for this file so the UI settles.
+    // Present files are still checked against the bundled checksum manifest
+    let asset = model_manager::kokoro_asset(model_dir);
+    let dir = model_manager::download_asset(&asset, app_handle, |p| {
         if let Some(handle) = app_handle {
-            let total_mb = total_size.map(|t| t as f64 / 1_048_576.0).unwrap_or(0.0);
+            let total_mb = p.total_bytes.unwrap_or(0) as f64 / 1_048_576.0;
             let _ = handle.emit(
                 "kokoro-download-progress",
                 KokoroDownloadProgress {
-                    model: filename.to_string(),
-                    percent: 100,
-                    downloaded_mb: total_mb,
+                    model: p.file.clone(),
+                    percent: p.percent,
+                    downloaded_mb: p.downloaded_bytes as f64 / 1_048_576.0,
                     total_mb,
                 },
             );
         }
+    })
+    .await
+    .map_err(TtsError::NetworkError)?;
 
-        tracing::info!(path = %dest.display(), "Kokoro file downloaded");
-    }
-
-    Ok(model_dir.to_path_buf())
+    tracing::info!(path = %dir.display(), "Kokoro model ready");
+    Ok(dir)
 }
 
 // ── Tests ───────────────────────────────────────────────────────────
@@ -375,6 +402,23 @@ mod tests {
         assert!(engine.is_ok());
     }
 
+    #[test]
+    fn test_set_voice_through_cache_wrapper() {
+        let mut engine = create_tts_engine("edge", Some("en-US-AriaNeural"), None).unwrap();
+        engine.set_voice("en-US-GuyNeural").unwrap();
+        assert!(engine.name().contains("Guy"));
+        assert!(engine.set_voice("").is_err());
+        assert!(engine.name().contains("Guy"));
+    }
+
+    #[test]
+    fn test_standby_edge_voice() {
+        assert_eq!(standby_edge_voice("af_bella"), "en-US-AriaNeural");
+        assert_eq!(standby_edge_voice("am_michael"), "en-US-GuyNeural");
+        assert_eq!(standby_edge_voice("bf_emma"), "en-GB-SoniaNeural");
+        assert_eq!(standby_edge_voice("bm_george"), "en-GB-RyanNeural");
+    }
+
     #[test]
     fn test_create_tts_engine_unknown() {
         let engine = create_tts_engine("nonexistent", None, None);
@@ -0,0 +1,162 @@
+//! Blocked-term filter applied to text before it is spoken.
+//!
+//! Only speech is filtered; the chat UI still shows the full response.
+//! Useful when the assistant is audible in an office or around kids.
+
+/// What to do when a phrase contains a blocked term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterAction {
+    /// Drop the blocked words and keep speaking the rest.
+    Mask,
+    /// Stop speaking at the first phrase containing a blocked term.
+    Stop,
+}
+
+impl FilterAction {
+    /// Parse a config value ("mask" | "stop"). Unknown values mask.
+    pub fn from_str_flexible(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "stop" => Self::Stop,
+            _ => Self::Mask,
+        }
+    }
+}
+
+/// Case-insensitive, whole-word matcher for blocked terms.
+///
+/// Terms may span several words ("shut up"); punctuation attached to the
+/// spoken words is ignored when matching.
+#[derive(Debug, Clone)]
+pub struct OutputFilter {
+    /// Each term as a sequence of normalized words.
+    terms: Vec<Vec<String>>,
+    action: FilterAction,
+}
+
+impl OutputFilter {
+    /// Build a filter, or `None` if no usable terms are configured.
+    pub fn new(terms: &[String], action: &str) -> Option<Self> {
+        let terms: Vec<Vec<String>> = terms
+            .iter()
+            .map(|t| {
+                t.split_whitespace()
+                    .map(normalize)
+                    .filter(|w| !w.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|words| !words.is_empty())
+            .collect();
+        if terms.is_empty() {
+            return None;
+        }
+        Some(Self {
+            terms,
+            action: FilterAction::from_str_flexible(action),
+        })
+    }
+
+    pub fn action(&self) -> FilterAction {
+        self.action
+    }
+
+    /// Apply the filter to phrases about to be synthesized.
+    ///
+    /// Mask drops matched words (and phrases left with nothing to say);
+    /// Stop truncates the list before the first phrase with a match.
+    pub fn filter_phrases(&self, phrases: Vec<String>) -> Vec<String> {
+        let mut out = Vec::with_capacity(phrases.len());
+        for phrase in phrases {
+            match self.mask_phrase(&phrase) {
+                None => out.push(phrase),
+                Some(_) if self.action == FilterAction::Stop => break,
+                Some(masked) => {
+                    if masked.chars().any(|c| c.is_alphanumeric()) {
+                        out.push(masked);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Return the phrase with blocked words removed, or `None` if nothing matched.
+    fn mask_phrase(&self, phrase: &str) -> Option<String> {
+        let words: Vec<&str> = phrase.split_whitespace().collect();
+        let normalized: Vec<String> = words.iter().map(|w| normalize(w)).collect();
+
+        let mut keep = vec![true; words.len()];
+        let mut matched = false;
+        let mut i = 0;
+        while i < words.len() {
+            let hit = self.terms.iter().find_map(|term| {
+                let end = i + term.len();
+                (end <= words.len() && normalized[i..end] == term[..]).then_some(term.len())
+            });
+            match hit {
+                Some(len) => {
+                    keep[i..i + len].iter_mut().for_each(|k| *k = false);
+                    matched = true;
+                    i += len;
+                }
+                None => i += 1,
+            }
+        }
+
+        if !matched {
+            return None;
+        }
+        let kept: Vec<&str> = words
+            .iter()
+            .zip(&keep)
+            .filter_map(|(w, k)| k.then_some(*w))
+            .collect();
+        Some(kept.join(" "))
+    }
+}
+
+/// Lowercase a word and strip surrounding punctuation.
+fn normalize(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn phrases(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_no_terms_means_no_filter() {
+        assert!(OutputFilter::new(&[], "mask").is_none());
+        assert!(OutputFilter::new(&terms(&["  ", "!!"]), "mask").is_none());
+    }
+
+    #[test]
+    fn test_mask_removes_whole_words_case_insensitive() {
+        let filter = OutputFilter::new(&terms(&["darn"]), "mask").unwrap();
+        let out = filter.filter_phrases(phrases(&["Well, DARN, that broke.", "Darnell is fine."]));
+        assert_eq!(out, vec!["Well, that broke.", "Darnell is fine."]);
+    }
+
+    #[test]
+    fn test_mask_multi_word_term_and_drops_empty_phrase() {
+        let filter = OutputFilter::new(&terms(&["shut up"]), "mask").unwrap();
+        let out = filter.filter_phrases(phrases(&["Shut up!", "Please shut up now.", "Up next."]));
+        assert_eq!(out, vec!["Please now.", "Up next."]);
+    }
+
+    #[test]
+    fn test_stop_truncates_at_first_match() {
+        let filter = OutputFilter::new(&terms(&["darn"]), "stop").unwrap();
+        assert_eq!(filter.action(), FilterAction::Stop);
+        let out = filter.filter_phrases(phrases(&["First part.", "Oh darn.", "Never spoken."]));
+        assert_eq!(out, vec!["First part."]);
+    }
+}
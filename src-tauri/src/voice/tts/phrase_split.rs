@@ -93,6 +93,16 @@ pub fn split_into_phrases(text: &str) -> Vec<String> {
     }
 }
 
+/// Whether a phrase ends a sentence (`.`, `!`, `?`, or `…`), ignoring any
+/// trailing closing quotes/brackets. Used to give sentence boundaries a
+/// longer pause than mid-sentence phrase breaks during streaming playback.
+pub fn ends_with_sentence_punctuation(phrase: &str) -> bool {
+    phrase
+        .trim_end()
+        .trim_end_matches(['"', '\'', ')', ']', '\u{201D}', '\u{2019}'])
+        .ends_with(['.', '!', '?', '\u{2026}'])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,4 +141,15 @@ mod tests {
         assert!(joined.contains("Second"));
         assert!(joined.contains("Third"));
     }
+
+    #[test]
+    fn test_ends_with_sentence_punctuation() {
+        assert!(ends_with_sentence_punctuation("Done."));
+        assert!(ends_with_sentence_punctuation("Really?  "));
+        assert!(ends_with_sentence_punctuation("He said \"stop!\""));
+        assert!(ends_with_sentence_punctuation("(see above.)"));
+        assert!(!ends_with_sentence_punctuation("First item,"));
+        assert!(!ends_with_sentence_punctuation("no punctuation"));
+        assert!(!ends_with_sentence_punctuation(""));
+    }
 }
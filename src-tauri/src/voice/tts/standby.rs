@@ -0,0 +1,174 @@
+//! Warm standby engine for instant mid-conversation fallback.
+//!
+//! A local engine (Kokoro) can start failing after it loaded — a broken
+//! phonemizer, an ONNX error. Building a replacement at that point costs
+//! seconds, so a lightweight cloud engine (Edge) is constructed up front
+//! alongside it and takes over the phrase immediately.
+//!
+//! Only engine failures (`SynthesisError`) switch to the standby. Missing
+//! models and network outages are left to [`super::SystemFallback`], which
+//! wraps this one: a cloud standby wouldn't help when the network is down.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::system_tts::resample_linear;
+use super::{TtsEngine, TtsError, WordBoundary};
+
+/// Wraps a primary engine with a pre-built standby that speaks any phrase
+/// the primary fails to synthesize. Standby audio is resampled to the
+/// primary's sample rate so playback never has to reopen at a new rate.
+///
+/// Sits outside [`super::CachedTts`] so standby audio is never cached under
+/// the primary engine's key.
+pub struct WarmStandby {
+    primary: Box<dyn TtsEngine>,
+    standby: Box<dyn TtsEngine>,
+    /// Whether the last phrase used the standby (logged on transitions only).
+    on_standby: AtomicBool,
+}
+
+impl WarmStandby {
+    pub fn new(primary: Box<dyn TtsEngine>, standby: Box<dyn TtsEngine>) -> Self {
+        Self {
+            primary,
+            standby,
+            on_standby: AtomicBool::new(false),
+        }
+    }
+}
+
+impl TtsEngine for WarmStandby {
+    fn synthesize(
+        &self,
+        text: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, TtsError>> + Send + '_>> {
+        let text = text.to_string();
+        Box::pin(async move {
+            match self.primary.synthesize(&text).await {
+                Err(TtsError::SynthesisError(e)) => {
+                    if !self.on_standby.swap(true, Ordering::SeqCst) {
+                        tracing::warn!(
+                            "{} failed ({}), switching to standby {}",
+                            self.primary.name(),
+                            e,
+                            self.standby.name()
+                        );
+                    }
+                    let samples = self.standby.synthesize(&text).await?;
+                    Ok(resample_linear(
+                        &samples,
+                        self.standby.sample_rate(),
+                        self.primary.sample_rate(),
+                    ))
+                }
+                other => {
+                    if other.is_ok() && self.on_standby.swap(false, Ordering::SeqCst) {
+                        tracing::info!("{} recovered, leaving standby", self.primary.name());
+                    }
+                    other
+                }
+            }
+        })
+    }
+
+    fn take_word_boundaries(&self) -> Vec<WordBoundary> {
+        let primary = self.primary.take_word_boundaries();
+        let standby = self.standby.take_word_boundaries();
+        // Resampling keeps timing, so the standby's offsets still line up.
+        if self.on_standby.load(Ordering::SeqCst) {
+            standby
+        } else {
+            primary
+        }
+    }
+
+    fn stop(&self) {
+        self.primary.stop();
+        self.standby.stop();
+    }
+
+    fn set_voice(&mut self, voice: &str) -> Result<(), TtsError> {
+        // Voice names are engine-specific; the standby keeps its own.
+        self.primary.set_voice(voice)
+    }
+
+    fn set_speed(&mut self, speed: f32) {
+        self.primary.set_speed(speed);
+        self.standby.set_speed(speed);
+    }
+
+    fn name(&self) -> String {
+        self.primary.name()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.primary.sample_rate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns `result` for every phrase, at `rate`.
+    struct Fixed {
+        result: Result<Vec<f32>, TtsError>,
+        rate: u32,
+    }
+
+    impl TtsEngine for Fixed {
+        fn synthesize(
+            &self,
+            _text: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, TtsError>> + Send + '_>> {
+            let result = match &self.result {
+                Ok(samples) => Ok(samples.clone()),
+                Err(TtsError::SynthesisError(e)) => Err(TtsError::SynthesisError(e.clone())),
+                Err(TtsError::NetworkError(e)) => Err(TtsError::NetworkError(e.clone())),
+                Err(_) => Err(TtsError::NotReady),
+            };
+            Box::pin(async move { result })
+        }
+        fn stop(&self) {}
+        fn set_voice(&mut self, _voice: &str) -> Result<(), TtsError> {
+            Ok(())
+        }
+        fn set_speed(&mut self, _speed: f32) {}
+        fn name(&self) -> String {
+            "Fixed".into()
+        }
+        fn sample_rate(&self) -> u32 {
+            self.rate
+        }
+    }
+
+    fn engine(result: Result<Vec<f32>, TtsError>, rate: u32) -> Box<dyn TtsEngine> {
+        Box::new(Fixed { result, rate })
+    }
+
+    #[tokio::test]
+    async fn test_standby_covers_synthesis_errors() {
+        let tts = WarmStandby::new(
+            engine(Err(TtsError::SynthesisError("onnx".into())), 22050),
+            engine(Ok(vec![0.5; 24000]), 24000),
+        );
+        let samples = tts.synthesize("hello").await.unwrap();
+        // One second of standby audio, resampled to the primary's rate
+        assert!(samples.len().abs_diff(22050) <= 1);
+        assert_eq!(tts.sample_rate(), 22050);
+    }
+
+    #[tokio::test]
+    async fn test_standby_leaves_network_errors_to_outer_fallback() {
+        let tts = WarmStandby::new(
+            engine(Err(TtsError::NetworkError("offline".into())), 22050),
+            engine(Ok(vec![0.5; 10]), 24000),
+        );
+        assert!(matches!(
+            tts.synthesize("hello").await,
+            Err(TtsError::NetworkError(_))
+        ));
+    }
+}
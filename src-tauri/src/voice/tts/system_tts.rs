@@ -0,0 +1,558 @@
+//! System-native TTS: the OS speech synthesizer as a zero-download engine.
+//!
+//! - Windows: SAPI via `System.Speech` in PowerShell, rendered to a WAV file.
+//! - macOS: `say` (the AVSpeechSynthesizer voices), rendered to a WAV file.
+//! - Linux: `espeak-ng` / `espeak` (speech-dispatcher's default synthesizer),
+//!   rendered to WAV on stdout. If only `spd-say` is installed, speech goes
+//!   straight to speech-dispatcher and `synthesize` returns no samples once
+//!   it has been spoken.
+//!
+//! Selected explicitly as the `system` adapter, and used by [`SystemFallback`]
+//! when the configured engine has no model or no network.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Notify;
+
+use super::{TtsEngine, TtsError, TtsStream, WordBoundary};
+
+/// Rate the engine reports; rendered audio is resampled to this.
+const DEFAULT_SAMPLE_RATE: u32 = 24000;
+
+/// Words per minute at speed 1.0 (`say` and espeak defaults).
+const BASE_WPM: f32 = 175.0;
+
+/// Voice name meaning "whatever the OS default is".
+const DEFAULT_VOICE: &str = "default";
+
+/// Windows renderer. Text arrives on stdin; output path and voice come via
+/// environment variables so nothing user-controlled is spliced into the script.
+const SAPI_SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Speech
+$s = New-Object System.Speech.Synthesis.SpeechSynthesizer
+if ($env:VM_TTS_VOICE) { $s.SelectVoice($env:VM_TTS_VOICE) }
+$s.Rate = [int]$env:VM_TTS_RATE
+$fmt = New-Object System.Speech.AudioFormat.SpeechAudioFormatInfo(24000, [System.Speech.AudioFormat.AudioBitsPerSample]::Sixteen, [System.Speech.AudioFormat.AudioChannel]::Mono)
+$s.SetOutputToWaveFile($env:VM_TTS_OUT, $fmt)
+$s.Speak([Console]::In.ReadToEnd())
+$s.Dispose()
+"#;
+
+/// Which OS synthesizer is driven.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Sapi,
+    Say,
+    Espeak(&'static str),
+    SpeechDispatcher,
+}
+
+impl Backend {
+    /// The synthesizer available on this machine, if any.
+    fn detect() -> Option<Self> {
+        if cfg!(windows) {
+            return Some(Self::Sapi);
+        }
+        if cfg!(target_os = "macos") {
+            return Some(Self::Say);
+        }
+        for bin in ["espeak-ng", "espeak"] {
+            if which::which(bin).is_ok() {
+                return Some(Self::Espeak(bin));
+            }
+        }
+        which::which("spd-say").ok().map(|_| Self::SpeechDispatcher)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Sapi => "SAPI",
+            Self::Say => "macOS",
+            Self::Espeak(bin) => bin,
+            Self::SpeechDispatcher => "speech-dispatcher",
+        }
+    }
+}
+
+/// The OS speech synthesizer as a [`TtsEngine`].
+pub struct SystemTts {
+    backend: Backend,
+    voice: Option<String>,
+    speed: f32,
+    sample_rate: u32,
+    cancelled: Arc<AtomicBool>,
+    cancel_notify: Arc<Notify>,
+}
+
+impl SystemTts {
+    /// Create an engine for this OS, or `NotReady` if no synthesizer is
+    /// installed (Linux without espeak or speech-dispatcher).
+    pub fn new(voice: Option<&str>, speed: f32) -> Result<Self, TtsError> {
+        let backend = Backend::detect().ok_or(TtsError::NotReady)?;
+        Ok(Self {
+            backend,
+            voice: voice
+                .filter(|v| !v.is_empty() && *v != DEFAULT_VOICE)
+                .map(String::from),
+            speed,
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            cancel_notify: Arc::new(Notify::new()),
+        })
+    }
+
+    /// Resample output to `rate` (used to stand in for another engine).
+    pub fn with_sample_rate(mut self, rate: u32) -> Self {
+        self.sample_rate = rate;
+        self
+    }
+
+    fn command(&self, out_path: &std::path::Path) -> tokio::process::Command {
+        let wpm = (BASE_WPM * self.speed).round().clamp(80.0, 450.0) as u32;
+        let mut cmd;
+        match self.backend {
+            Backend::Sapi => {
+                // The script is passed encoded so stdin carries only the text.
+                cmd = tokio::process::Command::new("powershell");
+                cmd.args(["-NoProfile", "-NonInteractive", "-EncodedCommand"])
+                    .arg(encode_powershell(SAPI_SCRIPT));
+                #[cfg(windows)]
+                cmd.creation_flags(crate::util::CREATE_NO_WINDOW);
+                cmd.env("VM_TTS_OUT", out_path)
+                    .env("VM_TTS_VOICE", self.voice.as_deref().unwrap_or(""))
+                    .env("VM_TTS_RATE", sapi_rate(self.speed).to_string());
+            }
+            Backend::Say => {
+                cmd = tokio::process::Command::new("say");
+                cmd.arg("-o")
+                    .arg(out_path)
+                    .args(["--file-format=WAVE", "--data-format=LEI16@24000"])
+                    .args(["-r", &wpm.to_string()]);
+                if let Some(v) = &self.voice {
+                    cmd.args(["-v", v]);
+                }
+                cmd.args(["-f", "-"]);
+            }
+            Backend::Espeak(bin) => {
+                cmd = tokio::process::Command::new(bin);
+                cmd.args(["--stdout", "--stdin", "-s", &wpm.to_string()]);
+                if let Some(v) = &self.voice {
+                    cmd.args(["-v", v]);
+                }
+            }
+            Backend::SpeechDispatcher => {
+                // spd-say rate is -100..100 around the user's default.
+                let rate = ((self.speed - 1.0) * 100.0).round().clamp(-100.0, 100.0) as i32;
+                cmd = tokio::process::Command::new("spd-say");
+                cmd.args(["--wait", "--pipe-mode", "-r", &rate.to_string()]);
+                if let Some(v) = &self.voice {
+                    cmd.args(["-y", v]);
+                }
+            }
+        }
+        cmd
+    }
+
+    async fn render(&self, text: &str) -> Result<Vec<f32>, TtsError> {
+        let out_path = std::env::temp_dir().join(format!(
+            "vm-system-tts-{}-{}.wav",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        let mut child = self
+            .command(&out_path)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                TtsError::SynthesisError(format!(
+                    "{} TTS failed to start: {}",
+                    self.backend.label(),
+                    e
+                ))
+            })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .await
+                .map_err(|e| TtsError::SynthesisError(format!("System TTS stdin: {}", e)))?;
+        }
+
+        // Dropping the child on cancel kills the synthesizer (kill_on_drop).
+        let output = tokio::select! {
+            out = child.wait_with_output() => out
+                .map_err(|e| TtsError::SynthesisError(format!("System TTS: {}", e)))?,
+            _ = self.cancel_notify.notified() => {
+                let _ = std::fs::remove_file(&out_path);
+                return Err(TtsError::Cancelled);
+            }
+        };
+
+        let wav = match self.backend {
+            Backend::Espeak(_) => Ok(output.stdout),
+            Backend::SpeechDispatcher => Ok(Vec::new()),
+            Backend::Sapi | Backend::Say => std::fs::read(&out_path),
+        };
+        let _ = std::fs::remove_file(&out_path);
+
+        if !output.status.success() {
+            return Err(TtsError::SynthesisError(format!(
+                "{} TTS exited with {}: {}",
+                self.backend.label(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        if self.backend == Backend::SpeechDispatcher {
+            // Already spoken by speech-dispatcher.
+            return Ok(Vec::new());
+        }
+
+        let wav = wav.map_err(|e| TtsError::SynthesisError(format!("System TTS output: {}", e)))?;
+        let (samples, rate) = decode_wav(&wav).map_err(TtsError::SynthesisError)?;
+        Ok(resample_linear(&samples, rate, self.sample_rate))
+    }
+}
+
+impl TtsEngine for SystemTts {
+    fn synthesize(
+        &self,
+        text: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, TtsError>> + Send + '_>> {
+        let text = text.to_string();
+        self.cancelled.store(false, Ordering::SeqCst);
+        Box::pin(async move {
+            if text.trim().is_empty() {
+                return Ok(Vec::new());
+            }
+            let samples = self.render(&text).await?;
+            if self.cancelled.load(Ordering::SeqCst) {
+                return Err(TtsError::Cancelled);
+            }
+            Ok(samples)
+        })
+    }
+
+    fn stop(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.cancel_notify.notify_waiters();
+    }
+
+    fn set_voice(&mut self, voice: &str) -> Result<(), TtsError> {
+        let voice = voice.trim();
+        if voice.is_empty() {
+            return Err(TtsError::SynthesisError("Voice name is empty".into()));
+        }
+        self.voice = (voice != DEFAULT_VOICE).then(|| voice.to_string());
+        Ok(())
+    }
+
+    fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    fn name(&self) -> String {
+        format!(
+            "System TTS ({}, {})",
+            self.backend.label(),
+            self.voice.as_deref().unwrap_or(DEFAULT_VOICE)
+        )
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+// ── Fallback wrapper ────────────────────────────────────────────────
+
+/// Wraps an engine so that when it can't run — no model (`NotReady`) or no
+/// network (`NetworkError`) — the phrase is spoken by the OS voice instead
+/// of failing. Fallback audio is resampled to the primary's sample rate.
+///
+/// Sits outside [`super::CachedTts`] so fallback audio is never cached
+/// under the primary engine's key.
+pub struct SystemFallback {
+    primary: Box<dyn TtsEngine>,
+    fallback: SystemTts,
+    /// Whether the last phrase used the fallback (logged on transitions only).
+    falling_back: AtomicBool,
+}
+
+impl SystemFallback {
+    pub fn new(primary: Box<dyn TtsEngine>, fallback: SystemTts) -> Self {
+        let fallback = fallback.with_sample_rate(primary.sample_rate());
+        Self {
+            primary,
+            fallback,
+            falling_back: AtomicBool::new(false),
+        }
+    }
+}
+
+impl TtsEngine for SystemFallback {
+    fn synthesize(
+        &self,
+        text: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, TtsError>> + Send + '_>> {
+        let text = text.to_string();
+        Box::pin(async move {
+            match self.primary.synthesize(&text).await {
+                Err(e @ (TtsError::NotReady | TtsError::NetworkError(_))) => {
+                    if !self.falling_back.swap(true, Ordering::SeqCst) {
+                        tracing::warn!(
+                            "{} unavailable ({}), speaking with {}",
+                            self.primary.name(),
+                            e,
+                            self.fallback.name()
+                        );
+                    }
+                    self.fallback.synthesize(&text).await
+                }
+                other => {
+                    if other.is_ok() && self.falling_back.swap(false, Ordering::SeqCst) {
+                        tracing::info!("{} available again", self.primary.name());
+                    }
+                    other
+                }
+            }
+        })
+    }
+
+    fn synthesize_streaming(
+        &self,
+        text: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<TtsStream, TtsError>> + Send + '_>> {
+        self.primary.synthesize_streaming(text)
+    }
+
+    fn take_word_boundaries(&self) -> Vec<WordBoundary> {
+        let boundaries = self.primary.take_word_boundaries();
+        if self.falling_back.load(Ordering::SeqCst) {
+            // Timings from the primary don't describe the fallback audio.
+            return Vec::new();
+        }
+        boundaries
+    }
+
+    fn stop(&self) {
+        self.primary.stop();
+        self.fallback.stop();
+    }
+
+    fn set_voice(&mut self, voice: &str) -> Result<(), TtsError> {
+        // Voice names are engine-specific; the fallback keeps the OS default.
+        self.primary.set_voice(voice)
+    }
+
+    fn set_speed(&mut self, speed: f32) {
+        self.primary.set_speed(speed);
+        self.fallback.set_speed(speed);
+    }
+
+    fn name(&self) -> String {
+        self.primary.name()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.primary.sample_rate()
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────
+
+/// SAPI `Rate` (-10..10) for a speed multiplier.
+fn sapi_rate(speed: f32) -> i32 {
+    ((speed - 1.0) * 10.0).round().clamp(-10.0, 10.0) as i32
+}
+
+/// Base64 of the UTF-16LE script, as `powershell -EncodedCommand` expects.
+fn encode_powershell(script: &str) -> String {
+    use base64::Engine;
+    let utf16: Vec<u8> = script
+        .encode_utf16()
+        .flat_map(|u| u.to_le_bytes())
+        .collect();
+    base64::engine::general_purpose::STANDARD.encode(utf16)
+}
+
+/// Decode a PCM WAV (8/16/24/32-bit int or 32-bit float) to mono f32.
+///
+/// Tolerates the `0xFFFFFFFF` data length espeak writes when streaming.
+fn decode_wav(bytes: &[u8]) -> Result<(Vec<f32>, u32), String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("System TTS output is not a WAV file".into());
+    }
+    let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+    let u32_at =
+        |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+
+    let mut pos = 12;
+    let mut format: Option<(u16, u16, u32, u16)> = None;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let len = u32_at(pos + 4) as usize;
+        let body = pos + 8;
+        if id == b"fmt " && body + 16 <= bytes.len() {
+            format = Some((
+                u16_at(body),
+                u16_at(body + 2),
+                u32_at(body + 4),
+                u16_at(body + 14),
+            ));
+        } else if id == b"data" {
+            let (tag, channels, rate, bits) = format.ok_or("WAV data before fmt chunk")?;
+            let end = body.saturating_add(len).min(bytes.len());
+            let samples = pcm_to_mono(&bytes[body..end], tag, channels.max(1), bits)?;
+            return Ok((samples, rate));
+        }
+        pos = body.saturating_add(len + (len & 1));
+    }
+    Err("WAV file has no data chunk".into())
+}
+
+fn pcm_to_mono(data: &[u8], tag: u16, channels: u16, bits: u16) -> Result<Vec<f32>, String> {
+    const PCM: u16 = 1;
+    const IEEE_FLOAT: u16 = 3;
+    const EXTENSIBLE: u16 = 0xFFFE;
+
+    let width = (bits / 8) as usize;
+    let sample = |b: &[u8]| -> f32 {
+        match (tag, bits) {
+            (IEEE_FLOAT, 32) => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            (_, 8) => (b[0] as f32 - 128.0) / 128.0,
+            (_, 16) => i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            (_, 24) => (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0,
+            _ => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+        }
+    };
+    if !matches!(tag, PCM | IEEE_FLOAT | EXTENSIBLE) || !matches!(bits, 8 | 16 | 24 | 32) {
+        return Err(format!(
+            "Unsupported WAV format (tag {}, {} bits)",
+            tag, bits
+        ));
+    }
+
+    let frame = width * channels as usize;
+    Ok(data
+        .chunks_exact(frame)
+        .map(|f| f.chunks_exact(width).map(sample).sum::<f32>() / channels as f32)
+        .collect())
+}
+
+/// Linear resampler (fine for speech; the OS voices are band-limited).
+pub(super) fn resample_linear(input: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || from_rate == 0 || input.is_empty() {
+        return input.to_vec();
+    }
+    let ratio = from_rate as f64 / to_rate as f64;
+    let out_len = (input.len() as f64 / ratio).floor() as usize;
+    (0..out_len)
+        .map(|i| {
+            let src = i as f64 * ratio;
+            let idx = src.floor() as usize;
+            let frac = (src - idx as f64) as f32;
+            let s0 = input.get(idx).copied().unwrap_or(0.0);
+            let s1 = input.get(idx + 1).copied().unwrap_or(s0);
+            s0 + frac * (s1 - s0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(rate: u32, channels: u16, samples: &[i16]) -> Vec<u8> {
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&channels.to_le_bytes());
+        out.extend_from_slice(&rate.to_le_bytes());
+        out.extend_from_slice(&(rate * channels as u32 * 2).to_le_bytes());
+        out.extend_from_slice(&(channels * 2).to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(&data);
+        out
+    }
+
+    #[test]
+    fn test_decode_wav_mono_and_stereo() {
+        let (samples, rate) = decode_wav(&wav(22050, 1, &[0, 16384, -32768])).unwrap();
+        assert_eq!(rate, 22050);
+        assert_eq!(samples, vec![0.0, 0.5, -1.0]);
+
+        let (samples, _) = decode_wav(&wav(24000, 2, &[16384, 0, -16384, -16384])).unwrap();
+        assert_eq!(samples, vec![0.25, -0.5]);
+    }
+
+    #[test]
+    fn test_decode_wav_streaming_length() {
+        // espeak --stdout writes 0xFFFFFFFF as the data length.
+        let mut bytes = wav(22050, 1, &[100, 200]);
+        let len = bytes.len();
+        bytes[len - 8..len - 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let (samples, _) = decode_wav(&bytes).unwrap();
+        assert_eq!(samples.len(), 2);
+
+        assert!(decode_wav(b"not a wav file").is_err());
+    }
+
+    #[test]
+    fn test_resample_and_rate_mapping() {
+        let input: Vec<f32> = (0..22050).map(|i| i as f32).collect();
+        assert_eq!(resample_linear(&input, 22050, 24000).len(), 24000);
+        assert_eq!(resample_linear(&input, 22050, 22050).len(), 22050);
+
+        assert_eq!(sapi_rate(1.0), 0);
+        assert_eq!(sapi_rate(1.5), 5);
+        assert_eq!(sapi_rate(5.0), 10);
+    }
+
+    struct Offline;
+
+    impl TtsEngine for Offline {
+        fn synthesize(
+            &self,
+            _text: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<f32>, TtsError>> + Send + '_>> {
+            Box::pin(async { Err(TtsError::NetworkError("offline".into())) })
+        }
+        fn stop(&self) {}
+        fn set_voice(&mut self, _voice: &str) -> Result<(), TtsError> {
+            Ok(())
+        }
+        fn set_speed(&mut self, _speed: f32) {}
+        fn name(&self) -> String {
+            "Offline".into()
+        }
+        fn sample_rate(&self) -> u32 {
+            22050
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_keeps_primary_identity() {
+        // Only meaningful where an OS synthesizer exists.
+        let Ok(system) = SystemTts::new(None, 1.0) else {
+            return;
+        };
+        let engine = SystemFallback::new(Box::new(Offline), system);
+        assert_eq!(engine.name(), "Offline");
+        assert_eq!(engine.sample_rate(), 22050);
+        assert_eq!(engine.fallback.sample_rate(), 22050);
+    }
+}
@@ -0,0 +1,97 @@
+//! Voices offered for each TTS adapter (the MCP `list_voices` tool).
+//!
+//! Mirrors the `voices` lists of `ADAPTER_REGISTRY` in
+//! `src/lib/voice-adapters.js`, which the settings UI shows. Engines may
+//! accept more than these: Kokoro also takes blends such as
+//! `af_bella:0.6+am_adam:0.4`, and cloud engines any voice their service has.
+
+/// A voice an adapter offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoiceOption {
+    /// Name passed to the engine, e.g. `af_bella`.
+    pub value: &'static str,
+    pub label: &'static str,
+}
+
+const fn voice(value: &'static str, label: &'static str) -> VoiceOption {
+    VoiceOption { value, label }
+}
+
+const KOKORO: &[VoiceOption] = &[
+    voice("af_bella", "Bella (Female)"),
+    voice("af_nicole", "Nicole (Female)"),
+    voice("af_sarah", "Sarah (Female)"),
+    voice("af_sky", "Sky (Female)"),
+    voice("am_adam", "Adam (Male)"),
+    voice("am_michael", "Michael (Male)"),
+    voice("bf_emma", "Emma (British Female)"),
+    voice("bf_isabella", "Isabella (British Female)"),
+    voice("bm_george", "George (British Male)"),
+    voice("bm_lewis", "Lewis (British Male)"),
+];
+
+const QWEN: &[VoiceOption] = &[
+    voice("Ryan", "Ryan (Male)"),
+    voice("Vivian", "Vivian (Female)"),
+    voice("Serena", "Serena (Female)"),
+    voice("Dylan", "Dylan (Male)"),
+    voice("Eric", "Eric (Male)"),
+    voice("Aiden", "Aiden (Male)"),
+    voice("Uncle_Fu", "Uncle Fu (Male)"),
+    voice("Ono_Anna", "Ono Anna (Female, Japanese)"),
+    voice("Sohee", "Sohee (Female, Korean)"),
+];
+
+const PIPER: &[VoiceOption] = &[
+    voice("en_US-amy-medium", "Amy (US Female)"),
+    voice("en_US-lessac-medium", "Lessac (US Male)"),
+    voice("en_US-libritts_r-medium", "LibriTTS (US)"),
+    voice("en_GB-cori-medium", "Cori (British Female)"),
+    voice("en_GB-alan-medium", "Alan (British Male)"),
+];
+
+const EDGE: &[VoiceOption] = &[
+    voice("en-US-AriaNeural", "Aria (US Female)"),
+    voice("en-US-GuyNeural", "Guy (US Male)"),
+    voice("en-US-JennyNeural", "Jenny (US Female)"),
+    voice("en-GB-SoniaNeural", "Sonia (British Female)"),
+    voice("en-GB-RyanNeural", "Ryan (British Male)"),
+    voice("en-AU-NatashaNeural", "Natasha (Australian Female)"),
+];
+
+const SYSTEM: &[VoiceOption] = &[voice("default", "System default")];
+
+const OPENAI: &[VoiceOption] = &[
+    voice("alloy", "Alloy"),
+    voice("echo", "Echo"),
+    voice("fable", "Fable"),
+    voice("onyx", "Onyx"),
+    voice("nova", "Nova"),
+    voice("shimmer", "Shimmer"),
+];
+
+const ELEVENLABS: &[VoiceOption] = &[
+    voice("Rachel", "Rachel"),
+    voice("Domi", "Domi"),
+    voice("Bella", "Bella"),
+    voice("Antoni", "Antoni"),
+    voice("Josh", "Josh"),
+    voice("Adam", "Adam"),
+];
+
+const CUSTOM_API: &[VoiceOption] = &[voice("default", "Default")];
+
+/// The voices `adapter` offers; empty for an unknown adapter.
+pub fn for_adapter(adapter: &str) -> &'static [VoiceOption] {
+    match adapter {
+        "kokoro" => KOKORO,
+        "qwen" => QWEN,
+        "piper" => PIPER,
+        "edge" => EDGE,
+        "system" => SYSTEM,
+        "openai-tts" => OPENAI,
+        "elevenlabs" => ELEVENLABS,
+        "custom-api" => CUSTOM_API,
+        _ => &[],
+    }
+}
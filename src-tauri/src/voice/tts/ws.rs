@@ -0,0 +1,408 @@
+//! Minimal WebSocket client (RFC 6455) over a reqwest-upgraded stream.
+//!
+//! Implements just what cloud TTS endpoints need: the HTTP upgrade via
+//! reqwest (reusing its TLS), masked client frames, reassembly of fragmented
+//! messages, automatic pong replies, and the close handshake. No extensions
+//! or subprotocols. This keeps us free of a full WebSocket client stack on
+//! top of reqwest's connection handling.
+
+use std::fmt;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::crypto::base64_encode;
+
+/// Largest single frame we accept.
+const MAX_FRAME_BYTES: u64 = 10 * 1024 * 1024;
+/// Largest reassembled message we accept.
+const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+/// How long to wait for the server's close reply before giving up.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// Normal closure status code.
+const CLOSE_NORMAL: u16 = 1000;
+
+/// WebSocket error.
+#[derive(Debug)]
+pub(crate) enum WsError {
+    /// The upgrade request failed or the server refused to switch protocols.
+    Handshake(String),
+    /// Reading or writing the stream failed (connection dropped).
+    Io(String),
+    /// The server sent something that violates the protocol.
+    Protocol(String),
+}
+
+impl fmt::Display for WsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Handshake(msg) => write!(f, "WebSocket handshake failed: {}", msg),
+            Self::Io(msg) => write!(f, "WebSocket I/O error: {}", msg),
+            Self::Protocol(msg) => write!(f, "WebSocket protocol error: {}", msg),
+        }
+    }
+}
+
+/// A complete (reassembled) data message.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+/// A single frame off the wire.
+struct Frame {
+    fin: bool,
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+/// Send the upgrade request built by `request` and wrap the upgraded stream.
+///
+/// The WebSocket handshake headers are added here; callers add their own
+/// (Origin, User-Agent, ...) to `request`.
+pub(crate) async fn connect(
+    request: reqwest::RequestBuilder,
+) -> Result<WsConnection<reqwest::Upgraded>, WsError> {
+    let key = base64_encode(&uuid::Uuid::new_v4().as_bytes()[..16]);
+    let response = request
+        .header("Upgrade", "websocket")
+        .header("Connection", "Upgrade")
+        .header("Sec-WebSocket-Key", &key)
+        .header("Sec-WebSocket-Version", "13")
+        .send()
+        .await
+        .map_err(|e| WsError::Handshake(format!("request failed: {}", e)))?;
+
+    let status = response.status();
+    if status != reqwest::StatusCode::SWITCHING_PROTOCOLS {
+        return Err(WsError::Handshake(format!("HTTP {}", status)));
+    }
+
+    let stream = response
+        .upgrade()
+        .await
+        .map_err(|e| WsError::Handshake(format!("stream upgrade failed: {}", e)))?;
+    Ok(WsConnection::new(stream))
+}
+
+/// An open WebSocket connection.
+pub(crate) struct WsConnection<S> {
+    stream: S,
+    /// Opcode and payload of a fragmented message being reassembled.
+    partial: Option<(u8, Vec<u8>)>,
+    /// A close frame has been sent or received.
+    closed: bool,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> WsConnection<S> {
+    pub(crate) fn new(stream: S) -> Self {
+        Self {
+            stream,
+            partial: None,
+            closed: false,
+        }
+    }
+
+    /// Send a text message.
+    pub(crate) async fn send_text(&mut self, text: &str) -> Result<(), WsError> {
+        self.send_frame(OP_TEXT, text.as_bytes()).await
+    }
+
+    /// Receive the next data message.
+    ///
+    /// Pings are answered and pongs skipped transparently. Returns `Ok(None)`
+    /// once the server closes the connection (the close is echoed back).
+    pub(crate) async fn recv(&mut self) -> Result<Option<Message>, WsError> {
+        loop {
+            if self.closed {
+                return Ok(None);
+            }
+            let frame = read_frame(&mut self.stream).await?;
+            match frame.opcode {
+                OP_PING => self.send_frame(OP_PONG, &frame.payload).await?,
+                OP_PONG => {}
+                OP_CLOSE => {
+                    let code = close_code(&frame.payload).unwrap_or(CLOSE_NORMAL);
+                    tracing::debug!(code, "WebSocket closed by server");
+                    // Echo the close; the peer may already be gone.
+                    let _ = self.send_frame(OP_CLOSE, &code.to_be_bytes()).await;
+                    self.closed = true;
+                    return Ok(None);
+                }
+                _ => {
+                    if let Some(message) = self.reassemble(frame)? {
+                        return Ok(Some(message));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Start the close handshake and wait briefly for the server's reply.
+    pub(crate) async fn close(&mut self) -> Result<(), WsError> {
+        if self.closed {
+            return Ok(());
+        }
+        self.send_frame(OP_CLOSE, &CLOSE_NORMAL.to_be_bytes())
+            .await?;
+        self.closed = true;
+        let _ = tokio::time::timeout(CLOSE_TIMEOUT, async {
+            while let Ok(frame) = read_frame(&mut self.stream).await {
+                if frame.opcode == OP_CLOSE {
+                    break;
+                }
+            }
+        })
+        .await;
+        Ok(())
+    }
+
+    /// Feed a data frame into reassembly. Returns a message when one completes.
+    fn reassemble(&mut self, frame: Frame) -> Result<Option<Message>, WsError> {
+        let (opcode, payload) = match (frame.opcode, self.partial.take()) {
+            (OP_CONTINUATION, Some((opcode, mut buf))) => {
+                if buf.len() + frame.payload.len() > MAX_MESSAGE_BYTES {
+                    return Err(WsError::Protocol("message too large".into()));
+                }
+                buf.extend_from_slice(&frame.payload);
+                (opcode, buf)
+            }
+            (OP_CONTINUATION, None) => {
+                return Err(WsError::Protocol("continuation without a message".into()));
+            }
+            (OP_TEXT | OP_BINARY, None) => (frame.opcode, frame.payload),
+            (OP_TEXT | OP_BINARY, Some(_)) => {
+                return Err(WsError::Protocol(
+                    "new message inside a fragmented one".into(),
+                ));
+            }
+            (other, _) => {
+                return Err(WsError::Protocol(format!("unknown opcode {:#x}", other)));
+            }
+        };
+
+        if !frame.fin {
+            self.partial = Some((opcode, payload));
+            return Ok(None);
+        }
+        Ok(Some(if opcode == OP_TEXT {
+            Message::Text(String::from_utf8_lossy(&payload).into_owned())
+        } else {
+            Message::Binary(payload)
+        }))
+    }
+
+    /// Write one unfragmented, masked frame.
+    async fn send_frame(&mut self, opcode: u8, payload: &[u8]) -> Result<(), WsError> {
+        let mut mask = [0u8; 4];
+        mask.copy_from_slice(&uuid::Uuid::new_v4().as_bytes()[..4]);
+        let bytes = encode_frame(opcode, payload, mask);
+        self.stream
+            .write_all(&bytes)
+            .await
+            .map_err(|e| WsError::Io(format!("write failed: {}", e)))?;
+        self.stream
+            .flush()
+            .await
+            .map_err(|e| WsError::Io(format!("flush failed: {}", e)))
+    }
+}
+
+/// Encode a final (FIN) client frame; client frames are always masked.
+fn encode_frame(opcode: u8, payload: &[u8], mask: [u8; 4]) -> Vec<u8> {
+    let len = payload.len();
+    let mut out = Vec::with_capacity(14 + len);
+    out.push(0x80 | opcode);
+    if len < 126 {
+        out.push(0x80 | len as u8);
+    } else if len <= 65535 {
+        out.push(0x80 | 126);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(0x80 | 127);
+        out.extend_from_slice(&(len as u64).to_be_bytes());
+    }
+    out.extend_from_slice(&mask);
+    out.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    out
+}
+
+/// Status code from a close frame payload, if present.
+fn close_code(payload: &[u8]) -> Option<u16> {
+    (payload.len() >= 2).then(|| u16::from_be_bytes([payload[0], payload[1]]))
+}
+
+/// Read a single frame from the stream.
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Frame, WsError> {
+    let io = |what: &str, e: std::io::Error| WsError::Io(format!("read {} failed: {}", what, e));
+
+    let mut hdr = [0u8; 2];
+    reader
+        .read_exact(&mut hdr)
+        .await
+        .map_err(|e| io("header", e))?;
+
+    let fin = hdr[0] & 0x80 != 0;
+    let opcode = hdr[0] & 0x0f;
+    let masked = hdr[1] & 0x80 != 0;
+    let mut len = (hdr[1] & 0x7f) as u64;
+
+    if len == 126 {
+        let mut buf = [0u8; 2];
+        reader
+            .read_exact(&mut buf)
+            .await
+            .map_err(|e| io("length", e))?;
+        len = u16::from_be_bytes(buf) as u64;
+    } else if len == 127 {
+        let mut buf = [0u8; 8];
+        reader
+            .read_exact(&mut buf)
+            .await
+            .map_err(|e| io("length", e))?;
+        len = u64::from_be_bytes(buf);
+    }
+    if len > MAX_FRAME_BYTES {
+        return Err(WsError::Protocol(format!(
+            "frame of {} bytes is too large",
+            len
+        )));
+    }
+    if opcode >= OP_CLOSE && (!fin || len > 125) {
+        return Err(WsError::Protocol("malformed control frame".into()));
+    }
+
+    // Server-to-client frames should NOT be masked, but handle it
+    let mask = if masked {
+        let mut key = [0u8; 4];
+        reader
+            .read_exact(&mut key)
+            .await
+            .map_err(|e| io("mask", e))?;
+        Some(key)
+    } else {
+        None
+    };
+
+    let mut payload = vec![0u8; len as usize];
+    reader
+        .read_exact(&mut payload)
+        .await
+        .map_err(|e| io("payload", e))?;
+    if let Some(key) = mask {
+        for (i, b) in payload.iter_mut().enumerate() {
+            *b ^= key[i % 4];
+        }
+    }
+
+    Ok(Frame {
+        fin,
+        opcode,
+        payload,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An unmasked server frame.
+    fn server_frame(fin: bool, opcode: u8, payload: &[u8]) -> Vec<u8> {
+        let mut out = vec![if fin { 0x80 } else { 0 } | opcode];
+        if payload.len() < 126 {
+            out.push(payload.len() as u8);
+        } else {
+            out.push(126);
+            out.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+        }
+        out.extend_from_slice(payload);
+        out
+    }
+
+    /// Connection whose server side has already written `incoming`.
+    async fn conn_with(
+        incoming: &[u8],
+    ) -> (
+        WsConnection<tokio::io::DuplexStream>,
+        tokio::io::DuplexStream,
+    ) {
+        let (client, mut server) = tokio::io::duplex(1 << 16);
+        server.write_all(incoming).await.unwrap();
+        (WsConnection::new(client), server)
+    }
+
+    #[test]
+    fn test_encode_frame_masks_payload() {
+        let frame = encode_frame(OP_TEXT, b"hi", [1, 2, 3, 4]);
+        assert_eq!(frame, vec![0x81, 0x82, 1, 2, 3, 4, b'h' ^ 1, b'i' ^ 2]);
+
+        let long = encode_frame(OP_BINARY, &[0u8; 300], [0; 4]);
+        assert_eq!(&long[..4], &[0x82, 0xfe, 0x01, 0x2c]);
+        assert_eq!(long.len(), 4 + 4 + 300);
+    }
+
+    #[tokio::test]
+    async fn test_reassembles_fragments_around_ping() {
+        let mut wire = server_frame(false, OP_TEXT, b"Path:");
+        wire.extend(server_frame(true, OP_PING, b"p"));
+        wire.extend(server_frame(false, OP_CONTINUATION, b"turn"));
+        wire.extend(server_frame(true, OP_CONTINUATION, b".end"));
+        let (mut conn, mut server) = conn_with(&wire).await;
+
+        let msg = conn.recv().await.unwrap();
+        assert_eq!(msg, Some(Message::Text("Path:turn.end".into())));
+
+        // The ping was answered with a masked pong carrying the same payload.
+        let mut pong = [0u8; 7];
+        server.read_exact(&mut pong).await.unwrap();
+        assert_eq!(pong[0], 0x80 | OP_PONG);
+        assert_eq!(pong[1], 0x80 | 1);
+        assert_eq!(pong[6] ^ pong[2], b'p');
+    }
+
+    #[tokio::test]
+    async fn test_close_is_echoed_and_ends_stream() {
+        let mut wire = server_frame(true, OP_BINARY, &[1, 2, 3]);
+        wire.extend(server_frame(true, OP_CLOSE, &1001u16.to_be_bytes()));
+        let (mut conn, mut server) = conn_with(&wire).await;
+
+        assert_eq!(
+            conn.recv().await.unwrap(),
+            Some(Message::Binary(vec![1, 2, 3]))
+        );
+        assert_eq!(conn.recv().await.unwrap(), None);
+        assert_eq!(conn.recv().await.unwrap(), None);
+
+        let mut echo = [0u8; 8];
+        server.read_exact(&mut echo).await.unwrap();
+        assert_eq!(echo[0], 0x80 | OP_CLOSE);
+        assert_eq!(
+            [echo[6] ^ echo[2], echo[7] ^ echo[3]],
+            1001u16.to_be_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dropped_connection_is_io_error() {
+        let wire = server_frame(false, OP_BINARY, &[9; 10]);
+        let (mut conn, server) = conn_with(&wire).await;
+        drop(server);
+        assert!(matches!(conn.recv().await, Err(WsError::Io(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_stray_continuation() {
+        let wire = server_frame(true, OP_CONTINUATION, b"x");
+        let (mut conn, _server) = conn_with(&wire).await;
+        assert!(matches!(conn.recv().await, Err(WsError::Protocol(_))));
+    }
+}
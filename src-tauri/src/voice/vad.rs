@@ -34,6 +34,28 @@ pub fn compute_energy_i16(samples: &[i16]) -> f32 {
     sum / samples.len() as f32
 }
 
+/// Fraction of `frame_len`-sample frames in `samples` whose energy exceeds
+/// `threshold` (0.0 - 1.0).
+///
+/// Used to check a whole recorded buffer before STT: an accidental PTT press
+/// captures mostly silence and isn't worth running inference on.
+pub fn speech_coverage(samples: &[f32], threshold: f32, frame_len: usize) -> f32 {
+    let frame_len = frame_len.max(1);
+    let mut frames = 0usize;
+    let mut speech = 0usize;
+    for frame in samples.chunks(frame_len) {
+        frames += 1;
+        if compute_energy(frame) > threshold {
+            speech += 1;
+        }
+    }
+    if frames == 0 {
+        0.0
+    } else {
+        speech as f32 / frames as f32
+    }
+}
+
 // ── VAD Processor ───────────────────────────────────────────────────
 
 /// Voice Activity Detection processor.
@@ -78,8 +100,14 @@ impl VadProcessor {
     ///
     /// Returns `true` if speech is detected in this frame.
     pub fn process_frame(&mut self, audio: &[f32]) -> bool {
+        self.process_frame_at(audio, Instant::now())
+    }
+
+    /// [`process_frame`](Self::process_frame) on a caller-supplied clock,
+    /// for replaying recorded audio faster than real time.
+    pub fn process_frame_at(&mut self, audio: &[f32], now: Instant) -> bool {
         let energy = compute_energy(audio);
-        self.update_state(energy)
+        self.update_state(energy, now)
     }
 
     /// Process an audio frame of i16 samples.
@@ -87,11 +115,11 @@ impl VadProcessor {
     /// Returns `true` if speech is detected in this frame.
     pub fn process_frame_i16(&mut self, audio: &[i16]) -> bool {
         let energy = compute_energy_i16(audio);
-        self.update_state(energy)
+        self.update_state(energy, Instant::now())
     }
 
     /// Update internal state based on computed energy level.
-    fn update_state(&mut self, energy: f32) -> bool {
+    fn update_state(&mut self, energy: f32, now: Instant) -> bool {
         // Update running average
         self.frame_count += 1;
         let alpha = 0.01_f32;
@@ -105,7 +133,7 @@ impl VadProcessor {
             self.silence_start = None;
         } else if self.silence_start.is_none() {
             // Silence just started
-            self.silence_start = Some(Instant::now());
+            self.silence_start = Some(now);
         }
 
         self.is_speech
@@ -128,6 +156,14 @@ impl VadProcessor {
             .unwrap_or(false)
     }
 
+    /// [`silence_exceeded`](Self::silence_exceeded) on the clock given to
+    /// [`process_frame_at`](Self::process_frame_at).
+    pub fn silence_exceeded_at(&self, timeout: Duration, now: Instant) -> bool {
+        self.silence_start
+            .map(|start| now.saturating_duration_since(start) >= timeout)
+            .unwrap_or(false)
+    }
+
     /// Whether speech was detected in the most recent frame.
     pub fn is_speech(&self) -> bool {
         self.is_speech
@@ -188,6 +224,21 @@ mod tests {
         assert!((energy - 0.5).abs() < 0.001);
     }
 
+    #[test]
+    fn test_speech_coverage() {
+        assert_eq!(speech_coverage(&[], 0.01, 480), 0.0);
+
+        let silence = vec![0.0f32; 4800];
+        assert_eq!(speech_coverage(&silence, 0.01, 480), 0.0);
+
+        // 3 of 10 frames loud
+        let mut mixed = vec![0.0f32; 4800];
+        for s in mixed.iter_mut().take(1440) {
+            *s = 0.3;
+        }
+        assert!((speech_coverage(&mixed, 0.01, 480) - 0.3).abs() < 1e-6);
+    }
+
     #[test]
     fn test_compute_energy_i16() {
         let silence = vec![0i16; 1280];
@@ -240,6 +291,17 @@ mod tests {
         assert!(vad.silence_duration().is_none());
     }
 
+    #[test]
+    fn test_vad_replay_clock() {
+        let mut vad = VadProcessor::new(0.01);
+        let start = Instant::now();
+        let chunk = Duration::from_millis(80);
+        vad.process_frame_at(&[0.5f32; 1280], start);
+        vad.process_frame_at(&[0.0f32; 1280], start + chunk);
+        assert!(!vad.silence_exceeded_at(Duration::from_secs(1), start + chunk * 12));
+        assert!(vad.silence_exceeded_at(Duration::from_secs(1), start + chunk * 14));
+    }
+
     #[test]
     fn test_vad_reset() {
         let mut vad = VadProcessor::new(0.01);
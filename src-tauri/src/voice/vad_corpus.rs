@@ -0,0 +1,460 @@
+//! Regression corpus for VAD and endpointing.
+//!
+//! Replays a directory of labeled WAV clips through [`VadProcessor`] and the
+//! same start/stop rules the pipeline uses in wake-word mode (speech starts
+//! a recording, `silenceTimeoutSecs` of silence or `maxRecordingSecs` ends
+//! it, the `minSpeechCoverage` gate decides whether STT would run), on a
+//! simulated clock so a corpus runs in seconds. Tuning changes can then be
+//! checked against real-world samples instead of by ear.
+//!
+//! Layout: clips live under `speech/`, `noise/` or `overlap/` (speech over
+//! background talk or media), at any depth. An optional sidecar
+//! `<clip>.json` can set the label and mark where speech is:
+//!
+//! ```json
+//! { "label": "speech", "segments": [[350, 2100], [2900, 4200]] }
+//! ```
+//!
+//! Segments are in milliseconds. The report gives:
+//! - false-trigger rate: noise clips with a recording that would reach STT
+//! - miss rate: speech/overlap clips with none
+//! - clipping rate: marked segments not inside a single such recording
+//!   (onset cut, or the recording ended or split before the segment did)
+//!
+//! Duty cycling, noise suppression and the time spent in STT (when the live
+//! pipeline ignores audio) aren't simulated.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::pipeline::{CHUNK_SAMPLES, TARGET_SAMPLE_RATE, VAD_FRAME_SAMPLES};
+use super::resample::{self, ResamplerKind};
+use super::vad::{self, VadProcessor};
+
+/// A segment counts as captured if the recording starts at most this long
+/// after it.
+const ONSET_TOLERANCE_MS: u64 = 50;
+/// Directory depth searched below the corpus root.
+const MAX_DEPTH: usize = 4;
+
+/// Endpointing settings a corpus run uses (from config, optionally
+/// overridden).
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointParams {
+    pub vad_threshold: f32,
+    pub silence_timeout_secs: f64,
+    /// 0 = unlimited.
+    pub max_recording_secs: f64,
+    pub min_speech_coverage: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipLabel {
+    Speech,
+    Noise,
+    Overlap,
+}
+
+impl ClipLabel {
+    fn from_dir(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "speech" => Some(Self::Speech),
+            "noise" => Some(Self::Noise),
+            "overlap" => Some(Self::Overlap),
+            _ => None,
+        }
+    }
+}
+
+/// Sidecar `<clip>.json`.
+#[derive(Debug, Default, Deserialize)]
+struct ClipMeta {
+    label: Option<ClipLabel>,
+    #[serde(default)]
+    segments: Vec<(u64, u64)>,
+}
+
+/// A simulated recording.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recording {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// `silence`, `max_duration` or `end_of_clip`.
+    pub reason: &'static str,
+    /// Passed the speech-coverage gate, so STT would have run.
+    pub reaches_stt: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipResult {
+    pub path: String,
+    pub label: ClipLabel,
+    pub duration_ms: u64,
+    pub recordings: Vec<Recording>,
+    pub false_trigger: bool,
+    pub missed: bool,
+    pub segments: usize,
+    pub clipped_segments: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorpusReport {
+    pub params: EndpointParams,
+    pub clips: Vec<ClipResult>,
+    /// Clips that couldn't be used, with why.
+    pub skipped: Vec<String>,
+    pub noise_clips: usize,
+    pub speech_clips: usize,
+    pub false_triggers: usize,
+    pub false_trigger_rate: f32,
+    pub missed: usize,
+    pub miss_rate: f32,
+    pub segments: usize,
+    pub clipped_segments: usize,
+    pub clipping_rate: f32,
+}
+
+/// Run every clip under `dir`. Blocks; call from a blocking task.
+pub fn run(dir: &Path, params: EndpointParams, resampler: ResamplerKind) -> Result<CorpusReport, String> {
+    if !dir.is_dir() {
+        return Err(format!("Corpus directory not found: {}", dir.display()));
+    }
+    let mut wavs = Vec::new();
+    collect_wavs(dir, 0, &mut wavs);
+    wavs.sort();
+
+    let mut clips = Vec::new();
+    let mut skipped = Vec::new();
+    for path in wavs {
+        let shown = path.strip_prefix(dir).unwrap_or(&path).display().to_string();
+        match run_clip(&path, dir, params, resampler) {
+            Ok(mut clip) => {
+                clip.path = shown;
+                clips.push(clip);
+            }
+            Err(e) => skipped.push(format!("{}: {}", shown, e)),
+        }
+    }
+    if clips.is_empty() {
+        return Err(format!(
+            "No usable clips in {} (expected WAVs under speech/, noise/ or overlap/)",
+            dir.display()
+        ));
+    }
+    let report = summarize(params, clips, skipped);
+    tracing::info!(
+        clips = report.clips.len(),
+        false_trigger_rate = report.false_trigger_rate,
+        miss_rate = report.miss_rate,
+        clipping_rate = report.clipping_rate,
+        "VAD corpus run complete"
+    );
+    Ok(report)
+}
+
+fn collect_wavs(dir: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_DEPTH {
+                collect_wavs(&path, depth + 1, out);
+            }
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("wav"))
+        {
+            out.push(path);
+        }
+    }
+}
+
+fn run_clip(
+    path: &Path,
+    root: &Path,
+    params: EndpointParams,
+    resampler: ResamplerKind,
+) -> Result<ClipResult, String> {
+    let meta = match std::fs::read_to_string(path.with_extension("json")) {
+        Ok(json) => serde_json::from_str::<ClipMeta>(&json).map_err(|e| format!("bad sidecar: {}", e))?,
+        Err(_) => ClipMeta::default(),
+    };
+    let label = meta
+        .label
+        .or_else(|| {
+            path.strip_prefix(root)
+                .ok()?
+                .ancestors()
+                .filter_map(|a| a.file_name()?.to_str())
+                .find_map(ClipLabel::from_dir)
+        })
+        .ok_or("no label (put it under speech/, noise/ or overlap/)")?;
+
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let (samples, rate) = read_wav(&bytes)?;
+    let audio = resample::resample(resampler, &samples, rate, TARGET_SAMPLE_RATE);
+    let recordings = simulate(&audio, params);
+
+    let heard: Vec<&Recording> = recordings.iter().filter(|r| r.reaches_stt).collect();
+    let clipped_segments = meta
+        .segments
+        .iter()
+        .filter(|&&(start, end)| {
+            !heard
+                .iter()
+                .any(|r| r.start_ms <= start + ONSET_TOLERANCE_MS && r.end_ms >= end)
+        })
+        .count();
+    Ok(ClipResult {
+        path: String::new(),
+        label,
+        duration_ms: samples_to_ms(audio.len()),
+        false_trigger: label == ClipLabel::Noise && !heard.is_empty(),
+        missed: label != ClipLabel::Noise && heard.is_empty(),
+        segments: meta.segments.len(),
+        clipped_segments,
+        recordings,
+    })
+}
+
+/// Replay 16 kHz mono `audio` chunk by chunk through the wake-word
+/// start/stop rules.
+pub fn simulate(audio: &[f32], params: EndpointParams) -> Vec<Recording> {
+    let base = Instant::now();
+    let silence_timeout = Duration::from_secs_f64(params.silence_timeout_secs.max(0.0));
+    let max_samples = (params.max_recording_secs > 0.0)
+        .then(|| (params.max_recording_secs * TARGET_SAMPLE_RATE as f64) as usize);
+    let mut vad = VadProcessor::new(params.vad_threshold);
+    let mut recordings = Vec::new();
+    let mut open: Option<usize> = None;
+
+    let mut finish = |start: usize, end: usize, reason: &'static str| {
+        let coverage = vad::speech_coverage(&audio[start..end], params.vad_threshold, VAD_FRAME_SAMPLES);
+        recordings.push(Recording {
+            start_ms: samples_to_ms(start),
+            end_ms: samples_to_ms(end),
+            reason,
+            reaches_stt: coverage >= params.min_speech_coverage,
+        });
+    };
+
+    for (i, chunk) in audio.chunks(CHUNK_SAMPLES).enumerate() {
+        let pos = i * CHUNK_SAMPLES;
+        let now = base + Duration::from_secs_f64(pos as f64 / TARGET_SAMPLE_RATE as f64);
+        let is_speech = vad.process_frame_at(chunk, now);
+        match open {
+            // The triggering chunk seeds the recording
+            None if is_speech => open = Some(pos),
+            None => {}
+            Some(start) => {
+                let end = pos + chunk.len();
+                let reason = if max_samples.is_some_and(|max| end - start >= max) {
+                    Some("max_duration")
+                } else if vad.silence_exceeded_at(silence_timeout, now) {
+                    Some("silence")
+                } else {
+                    None
+                };
+                if let Some(reason) = reason {
+                    finish(start, end, reason);
+                    open = None;
+                    vad.reset();
+                }
+            }
+        }
+    }
+    if let Some(start) = open {
+        finish(start, audio.len(), "end_of_clip");
+    }
+    recordings
+}
+
+fn summarize(params: EndpointParams, clips: Vec<ClipResult>, skipped: Vec<String>) -> CorpusReport {
+    let rate = |n: usize, of: usize| if of == 0 { 0.0 } else { n as f32 / of as f32 };
+    let noise_clips = clips.iter().filter(|c| c.label == ClipLabel::Noise).count();
+    let speech_clips = clips.len() - noise_clips;
+    let false_triggers = clips.iter().filter(|c| c.false_trigger).count();
+    let missed = clips.iter().filter(|c| c.missed).count();
+    let segments = clips.iter().map(|c| c.segments).sum();
+    let clipped_segments = clips.iter().map(|c| c.clipped_segments).sum();
+    CorpusReport {
+        params,
+        skipped,
+        noise_clips,
+        speech_clips,
+        false_triggers,
+        false_trigger_rate: rate(false_triggers, noise_clips),
+        missed,
+        miss_rate: rate(missed, speech_clips),
+        segments,
+        clipped_segments,
+        clipping_rate: rate(clipped_segments, segments),
+        clips,
+    }
+}
+
+fn samples_to_ms(samples: usize) -> u64 {
+    samples as u64 * 1000 / TARGET_SAMPLE_RATE as u64
+}
+
+/// Decode a PCM (8/16/24/32-bit) or 32-bit float WAV to mono samples and
+/// its sample rate.
+fn read_wav(bytes: &[u8]) -> Result<(Vec<f32>, u32), String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a WAV file".into());
+    }
+    let u16_at = |b: &[u8], i: usize| u16::from_le_bytes([b[i], b[i + 1]]);
+    let u32_at = |b: &[u8], i: usize| u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]]);
+
+    let mut format = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let len = u32_at(bytes, pos + 4) as usize;
+        let body = &bytes[pos + 8..(pos + 8).saturating_add(len).min(bytes.len())];
+        if id == b"fmt " && body.len() >= 16 {
+            let mut tag = u16_at(body, 0);
+            // WAVE_FORMAT_EXTENSIBLE: the real tag starts the sub-format GUID
+            if tag == 0xFFFE && body.len() >= 26 {
+                tag = u16_at(body, 24);
+            }
+            format = Some((tag, u16_at(body, 2), u32_at(body, 4), u16_at(body, 14)));
+        } else if id == b"data" {
+            let (tag, channels, rate, bits) = format.ok_or("data before fmt chunk")?;
+            let channels = channels.max(1) as usize;
+            let width = (bits as usize).div_ceil(8);
+            let sample = |s: &[u8]| -> Option<f32> {
+                Some(match (tag, bits) {
+                    (1, 8) => (s[0] as f32 - 128.0) / 128.0,
+                    (1, 16) => i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0,
+                    (1, 24) => (i32::from_le_bytes([0, s[0], s[1], s[2]]) >> 8) as f32 / 8_388_608.0,
+                    (1, 32) => i32::from_le_bytes([s[0], s[1], s[2], s[3]]) as f32 / 2_147_483_648.0,
+                    (3, 32) => f32::from_le_bytes([s[0], s[1], s[2], s[3]]),
+                    _ => return None,
+                })
+            };
+            if width == 0 || sample(&vec![0u8; width]).is_none() {
+                return Err(format!("unsupported WAV format (tag {}, {} bits)", tag, bits));
+            }
+            let mono = body
+                .chunks_exact(width * channels)
+                .map(|frame| {
+                    frame.chunks_exact(width).filter_map(sample).sum::<f32>() / channels as f32
+                })
+                .collect();
+            return Ok((mono, rate));
+        }
+        // Chunks are padded to an even length
+        pos += 8 + len + (len & 1);
+    }
+    Err("no data chunk".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::turn_archive::encode_wav;
+
+    const PARAMS: EndpointParams = EndpointParams {
+        vad_threshold: 0.01,
+        silence_timeout_secs: 1.0,
+        max_recording_secs: 0.0,
+        min_speech_coverage: 0.1,
+    };
+
+    /// `(ms, loud)` spans of 16 kHz audio.
+    fn clip(spans: &[(u64, bool)]) -> Vec<f32> {
+        spans
+            .iter()
+            .flat_map(|&(ms, loud)| vec![if loud { 0.3 } else { 0.0 }; ms as usize * 16])
+            .collect()
+    }
+
+    #[test]
+    fn test_simulate_ends_on_silence() {
+        let audio = clip(&[(400, false), (1200, true), (300, false), (800, true), (2000, false)]);
+        let recordings = simulate(&audio, PARAMS);
+        assert_eq!(recordings.len(), 1, "a 300 ms pause must not split the utterance");
+        let r = &recordings[0];
+        assert_eq!(r.reason, "silence");
+        assert_eq!(r.start_ms, 400);
+        // Stops about a second after the speech ends at 2700 ms
+        assert!((3600..=3900).contains(&r.end_ms), "ended at {}", r.end_ms);
+        assert!(r.reaches_stt);
+    }
+
+    #[test]
+    fn test_simulate_gate_and_limit() {
+        // A lone click triggers a recording that the coverage gate drops
+        let click = clip(&[(500, false), (80, true), (3000, false)]);
+        let recordings = simulate(&click, PARAMS);
+        assert_eq!(recordings.len(), 1);
+        assert!(!recordings[0].reaches_stt);
+
+        let drone = clip(&[(5000, true)]);
+        let limited = EndpointParams { max_recording_secs: 2.0, ..PARAMS };
+        let recordings = simulate(&drone, limited);
+        assert_eq!(recordings[0].reason, "max_duration");
+        assert_eq!(recordings.last().unwrap().reason, "end_of_clip");
+    }
+
+    #[test]
+    fn test_read_wav_downmixes_and_reports_rate() {
+        let (samples, rate) = read_wav(&encode_wav(&[0.5, -0.5], 22_050)).unwrap();
+        assert_eq!(rate, 22_050);
+        assert!((samples[0] - 0.5).abs() < 1e-3);
+
+        // 16-bit stereo: left 0.5, right 0.0 -> 0.25
+        let mut stereo = encode_wav(&[0.0, 0.0], 8_000);
+        stereo[22] = 2;
+        stereo[40..44].copy_from_slice(&4u32.to_le_bytes());
+        stereo[44..46].copy_from_slice(&16384i16.to_le_bytes());
+        stereo[46..48].copy_from_slice(&0i16.to_le_bytes());
+        let (samples, _) = read_wav(&stereo).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert!((samples[0] - 0.25).abs() < 1e-3);
+
+        assert!(read_wav(b"RIFF....WAVEjunk").is_err());
+    }
+
+    #[test]
+    fn test_run_reports_rates() {
+        let dir = std::env::temp_dir().join(format!("vm-vad-corpus-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("speech")).unwrap();
+        std::fs::create_dir_all(dir.join("noise")).unwrap();
+        let write = |rel: &str, audio: &[f32]| std::fs::write(dir.join(rel), encode_wav(audio, 16_000)).unwrap();
+
+        write("speech/hello.wav", &clip(&[(300, false), (1000, true), (1500, false)]));
+        std::fs::write(dir.join("speech/hello.json"), r#"{"segments": [[300, 1300]]}"#).unwrap();
+        // Speech that starts too quietly for the threshold: onset clipped
+        let mut soft = clip(&[(300, false), (1000, true), (1500, false)]);
+        soft[300 * 16..600 * 16].iter_mut().for_each(|s| *s = 0.005);
+        write("speech/soft.wav", &soft);
+        std::fs::write(dir.join("speech/soft.json"), r#"{"segments": [[300, 1300]]}"#).unwrap();
+        write("noise/fan.wav", &clip(&[(3000, false)]));
+        write("noise/door.wav", &clip(&[(200, false), (600, true), (2000, false)]));
+        std::fs::write(dir.join("unlabeled.wav"), encode_wav(&[0.0; 160], 16_000)).unwrap();
+
+        let report = run(&dir, PARAMS, ResamplerKind::Linear).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!((report.speech_clips, report.noise_clips), (2, 2));
+        assert_eq!(report.false_triggers, 1);
+        assert_eq!(report.false_trigger_rate, 0.5);
+        assert_eq!(report.missed, 0);
+        assert_eq!((report.segments, report.clipped_segments), (2, 1));
+        assert_eq!(report.skipped.len(), 1);
+        assert!(report.skipped[0].starts_with("unlabeled.wav"));
+    }
+}
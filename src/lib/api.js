@@ -93,8 +93,68 @@ export async function restartVoice() {
   return invoke('restart_voice');
 }
 
-export async function ensureSttModel(modelSize) {
-  return invoke('ensure_stt_model', { modelSize });
+/** Release the microphone without unloading the STT/TTS engines (privacy mute). */
+export async function pauseVoice() {
+  return invoke('pause_voice');
+}
+
+/** Reopen the microphone released by pauseVoice(). */
+export async function resumeVoice() {
+  return invoke('resume_voice');
+}
+
+/**
+ * Check the saved voice settings the way start_voice does.
+ * @returns {Promise<{success: boolean, data?: {issues: Array<{field: string, message: string}>}}>}
+ */
+export async function validateVoiceConfig() {
+  return invoke('validate_voice_config');
+}
+
+/**
+ * List the saved voice profiles.
+ * @returns {Promise<{success: boolean, data?: {profiles: Array<{name: string, mode: string, inputDevice: string|null, outputDevice: string|null, ttsVoice: string}>, active: string|null}}>}
+ */
+export async function listVoiceProfiles() {
+  return invoke('list_voice_profiles');
+}
+
+/**
+ * Save the current voice settings as a named profile.
+ * @param {string} name - Profile name (letters, digits, '-', '_', spaces)
+ */
+export async function saveVoiceProfile(name) {
+  return invoke('save_voice_profile', { name });
+}
+
+/**
+ * Switch to a voice profile, restarting the pipeline if it is running.
+ * @param {string|null} name - Profile name, or null for the voice settings
+ * @returns {Promise<{success: boolean, data?: {active: string|null, restarted: boolean}}>}
+ */
+export async function activateVoiceProfile(name) {
+  return invoke('activate_voice_profile', { name: name ?? null });
+}
+
+/**
+ * Delete a voice profile.
+ * @param {string} name - Profile name
+ */
+export async function deleteVoiceProfile(name) {
+  return invoke('delete_voice_profile', { name });
+}
+
+/**
+ * Download a Whisper model. `language` picks the English-only or multilingual
+ * weights (defaults to the saved voice.sttLanguage).
+ */
+export async function ensureSttModel(modelSize, language) {
+  return invoke('ensure_stt_model', { modelSize, language: language || null });
+}
+
+/** Download the local Parakeet STT model (~670 MB) with stt-download-progress events. */
+export async function ensureParakeetModel() {
+  return invoke('ensure_parakeet_model');
 }
 
 /** Download the local Kokoro TTS voice model (~350 MB) with progress events. */
@@ -102,6 +162,23 @@ export async function ensureKokoroModel() {
   return invoke('ensure_kokoro_model');
 }
 
+// ============ Model Manager ============
+
+/** List all downloadable local models (Whisper, Parakeet, Kokoro, Silero VAD) with install status. */
+export async function listModelAssets() {
+  return invoke('list_model_assets');
+}
+
+/** Download a managed model by id; emits model-download-progress events. */
+export async function downloadModel(id) {
+  return invoke('download_model', { id });
+}
+
+/** Delete a downloaded model by id. */
+export async function deleteModel(id) {
+  return invoke('delete_model', { id });
+}
+
 export async function getVoiceStatus() {
   return invoke('get_voice_status');
 }
@@ -110,18 +187,176 @@ export async function setVoiceMode(mode) {
   return invoke('set_voice_mode', { mode });
 }
 
+/**
+ * Record 3s of silence then 3s of speech and return the recommended
+ * { vadThreshold, silenceTimeoutSecs } plus measured levels. Emits
+ * `voice-calibration` ({ phase, secs }) as each take starts.
+ */
+export async function calibrateVoice() {
+  return invoke('voice_calibrate');
+}
+
+/**
+ * Check the voice setup without starting the pipeline: microphone, output
+ * device, TTS (speaks a test phrase) and STT (transcribes it back). Returns
+ * { ok, checks: [{ name, status: 'pass'|'fail'|'skipped', detail, durationMs }] }.
+ */
+export async function voiceSelfTest() {
+  return invoke('voice_self_test');
+}
+
+/**
+ * Development builds only: replay a labeled clip corpus (speech/, noise/,
+ * overlap/ WAVs) through VAD and endpointing and return false-trigger, miss
+ * and clipping rates. Overrides default to the saved settings.
+ * @param {string} dir
+ * @param {{ vadThreshold?: number, silenceTimeoutSecs?: number }} [overrides]
+ */
+export async function runVadCorpus(dir, overrides = {}) {
+  return invoke('run_vad_corpus', {
+    dir,
+    vadThreshold: overrides.vadThreshold ?? null,
+    silenceTimeoutSecs: overrides.silenceTimeoutSecs ?? null,
+  });
+}
+
+/**
+ * Stage timings of recent voice turns, oldest first. Each entry:
+ * { turn, finished_at, outcome, stt_wait_ms, stt_ms, response_ms,
+ *   tts_first_audio_ms, playback_ms, total_ms } (null = stage not reached).
+ */
+export async function getPipelineTimings() {
+  return invoke('get_pipeline_timings');
+}
+
+/**
+ * Counters and latency histograms since the app started.
+ * @returns {Promise<{success: boolean, data?: {counters: Object<string, number>, histograms: Object<string, {count: number, sumMs: number, buckets: Array<[number, number]>}>}}>}
+ */
+export async function getMetrics() {
+  return invoke('get_metrics');
+}
+
+/**
+ * Recent voice errors, oldest first.
+ * @param {number} [limit] - Keep only the newest N
+ * @returns {Promise<{success: boolean, data?: Array<{id: number, at: number, code: string, message: string, turn: number|null}>}>}
+ */
+export async function voiceGetErrors(limit) {
+  return invoke('voice_get_errors', { limit: limit ?? null });
+}
+
+/** Empty the voice error log. */
+export async function voiceClearErrors() {
+  return invoke('voice_clear_errors');
+}
+
+/**
+ * Where the latency ladder stands.
+ * @returns {Promise<{success: boolean, data?: {budgetMs: number, level: number, pinned: boolean, strikes: number, steps: Array<{level: number, rung: string, detail: string}>}}>}
+ */
+export async function getLatencyLadder() {
+  return invoke('get_latency_ladder');
+}
+
+/**
+ * Pin the latency ladder at a level (0 = full quality), or pass null to
+ * go back to automatic stepping. Restarts a running pipeline.
+ * @param {number|null} level
+ */
+export async function setLatencyLadder(level) {
+  return invoke('set_latency_ladder', { level: level ?? null });
+}
+
+/**
+ * Which voice features this build/machine supports. Each entry is
+ * { available, reason? }: whisper, whisperModel, parakeetModel, kokoroModel,
+ * onnx, noiseSuppression, builtinG2p, wakeWordModel, echoCancellation,
+ * audioDucking, sttCloudConfigured, ttsCloudConfigured, plus per-adapter maps
+ * sttAdapters / ttsAdapters and whisperGpuBackend (string or null).
+ */
+export async function getCapabilities() {
+  return invoke('get_capabilities');
+}
+
+/**
+ * Daily voice activity for the last `days` days (default 7), oldest first:
+ * { days: [{ date, talkMs, utterances, averageUtteranceMs, interruptions }],
+ *   today, total } (same shape as each day). Dates are UTC.
+ */
+export async function getVoiceStats(days) {
+  return invoke('get_voice_stats', { days });
+}
+
+/** Enrolled speakers for speaker identification: [{ name, samples }]. */
+export async function listSpeakers() {
+  return invoke('list_speakers');
+}
+
+/**
+ * Enroll (or refine) `name`'s voice from turn-archive recordings.
+ * Returns { name, samples, used }.
+ */
+export async function enrollSpeaker(name, turnIds) {
+  return invoke('enroll_speaker', { name, turnIds });
+}
+
+export async function deleteSpeaker(name) {
+  return invoke('delete_speaker', { name });
+}
+
 export async function listAudioDevices() {
   return invoke('list_audio_devices');
 }
 
-export async function speakText(text) {
-  return invoke('speak_text', { text });
+/**
+ * Speak text through the voice pipeline's TTS.
+ * @param {string} text
+ * @param {'interrupt'|'queue'|'drop_if_busy'} [priority] - What to do if
+ *   something is already being spoken (default: interrupt it)
+ * @param {string} [voice] - TTS voice for this text only (default: the selected one)
+ */
+export async function speakText(text, priority, voice) {
+  return invoke('speak_text', { text, priority: priority ?? null, voice: voice ?? null });
+}
+
+/**
+ * Synthesize text with the configured TTS engine into a file instead of
+ * playing it. Works with the voice engine stopped.
+ * @param {string} text
+ * @param {string} path - Destination ending in .wav or .mp3 (MP3 needs ffmpeg)
+ */
+export async function speakToFile(text, path) {
+  return invoke('speak_to_file', { text, path });
 }
 
 export async function stopSpeaking() {
   return invoke('stop_speaking');
 }
 
+/** Switch the running pipeline's TTS voice (applies from the next utterance). */
+export async function setTtsVoice(voice) {
+  return invoke('voice_set_tts_voice', { voice });
+}
+
+/** Set the running pipeline's TTS speed multiplier (0.5 - 2.0). */
+export async function setTtsSpeed(speed) {
+  return invoke('voice_set_tts_speed', { speed });
+}
+
+/** Set the running pipeline's TTS volume (0.0 - 2.0, 1.0 = normal). */
+export async function setTtsVolume(volume) {
+  return invoke('voice_set_tts_volume', { volume });
+}
+
+/**
+ * Set the running pipeline's voice effect chain.
+ * @param {string[]} effects - e.g. ['robot'] or ['pitch:-5', 'reverb:0.3']; [] for none
+ */
+export async function setTtsEffects(effects) {
+  return invoke('voice_set_tts_effects', { effects });
+}
+
 export async function pttPress() {
   return invoke('ptt_press');
 }
@@ -202,6 +437,39 @@ export async function interruptAI() {
   return invoke('interrupt_ai');
 }
 
+/**
+ * Set a temporary system-prompt addendum for the current AI session
+ * ("act as a code reviewer"). Applies from the next API provider turn and is
+ * cleared when the provider stops. Pass null to clear it.
+ * Resolves to { prompt, applied } (applied is false for CLI providers).
+ */
+export async function setSessionPrompt(prompt) {
+  return invoke('set_session_prompt', { prompt: prompt ?? null });
+}
+
+export async function getSessionPrompt() {
+  return invoke('get_session_prompt');
+}
+
+/**
+ * Save a session snapshot (provider history, session prompt, pending timers,
+ * active tool profile). Without a path it goes to the app data `snapshots/`
+ * folder. Resolves to { path, messages, timers }.
+ * @param {string} [path] - Absolute .json path
+ */
+export async function snapshotSession(path) {
+  return invoke('snapshot_session', { path: path ?? null });
+}
+
+/**
+ * Restore a snapshot saved by snapshotSession.
+ * Resolves to { createdAt, provider, historyRestored, messages, toolProfile, timers }.
+ * @param {string} path - Absolute .json path
+ */
+export async function restoreSession(path) {
+  return invoke('restore_session', { path });
+}
+
 export async function getProvider() {
   return invoke('get_provider');
 }
@@ -214,6 +482,24 @@ export async function aiPtyInput(data, imagePath, imageDataUrl) {
   return invoke('ai_pty_input', { data, imagePath: imagePath || null, imageDataUrl: imageDataUrl || null });
 }
 
+/**
+ * Send a user message (typed or transcribed) to the active AI provider.
+ * The backend routes it to the API provider or the MCP inbox, so chat and
+ * voice share one conversation.
+ *
+ * @param {string} text - The message text
+ * @param {Object} [options]
+ * @param {string} [options.imagePath] - Screenshot file path
+ * @param {string} [options.imageDataUrl] - Pre-encoded image data URL
+ * @param {boolean} [options.speak=true] - Speak the reply via TTS
+ * @param {boolean} [options.voice=false] - A voice turn: follows voice.turnPolicy
+ *   while an API provider is still answering (typed messages always queue)
+ * @returns {Promise<Object>} data.turn: 'sent' | 'queued' | 'replaced' | 'rejected'
+ */
+export async function sendTextMessage(text, { imagePath = null, imageDataUrl = null, speak = true, voice = false } = {}) {
+  return invoke('send_text_message', { text, imagePath: imagePath || null, imageDataUrl: imageDataUrl || null, speak, voice });
+}
+
 export async function aiRawInput(data) {
   return invoke('ai_raw_input', { data });
 }
@@ -279,10 +565,10 @@ export async function listModels(providerType, baseUrl) {
 // ============ Inbox / Messaging ============
 
 /**
- * Write a user message to the MCP inbox file.
+ * Write a user message to the MCP inbox.
  *
  * This is how chat input reaches the AI provider — the message goes into
- * inbox.json, and the AI reads it via `voice_listen`.
+ * the inbox (inbox.db), and the AI reads it via `voice_listen`.
  *
  * @param {string} message - The message text
  * @param {string} [from] - Sender name (defaults to config user name)
@@ -319,6 +605,43 @@ export async function exportChatToFile(path, content) {
   return invoke('export_chat_to_file', { path, content });
 }
 
+// ============ Transcript log ============
+
+/** Transcript entries between two `YYYY-MM-DD` dates (inclusive, either optional). */
+export async function transcriptQuery(from, to) {
+  return invoke('transcript_query', { from: from || null, to: to || null });
+}
+
+export async function transcriptExportMarkdown(from, to) {
+  return invoke('transcript_export_markdown', { from: from || null, to: to || null });
+}
+
+/** Delete entries before `before` (`YYYY-MM-DD`), or everything when omitted. */
+export async function transcriptPurge(before) {
+  return invoke('transcript_purge', { before: before || null });
+}
+
+// ============ Turn audio archive ============
+
+/** Archived recordings of recent turns, newest first. */
+export async function turnArchiveList() {
+  return invoke('turn_archive_list');
+}
+
+/** One archived turn with its recording as base64 WAV. */
+export async function turnArchiveGet(id) {
+  return invoke('turn_archive_get', { id });
+}
+
+/** Re-transcribe an archived recording with cloud STT. */
+export async function turnArchiveRecheck(id) {
+  return invoke('turn_archive_recheck', { id });
+}
+
+export async function turnArchivePurge() {
+  return invoke('turn_archive_purge');
+}
+
 // ============ Screenshot ============
 
 export async function takeScreenshot() {
@@ -398,6 +721,17 @@ export async function listShortcuts() {
   return invoke('list_shortcuts');
 }
 
+/**
+ * Save and register the global voice hotkeys. The backend handles them
+ * directly, so they work while the window is unfocused.
+ * @param {{ pushToTalk: string, toggle: string, stopSpeaking: string, cycleMode: string }} hotkeys
+ *   Accelerators like "CommandOrControl+Shift+Space"; '' = unbound.
+ * @returns {Promise<{ success: boolean, data?: Array<{ action: string, keys: string, error: string|null }> }>}
+ */
+export async function configureHotkeys(hotkeys) {
+  return invoke('configure_hotkeys', { hotkeys });
+}
+
 // ============ Performance Stats ============
 
 export async function getProcessStats() {
@@ -708,12 +1042,82 @@ export async function detectGpu() {
   return invoke('detect_gpu');
 }
 
+/**
+ * Probe the hardware and recommend the largest Whisper model that meets the
+ * latency target (default voice.sttTargetLatencyMs).
+ * @param {{ apply?: boolean, targetLatencyMs?: number }} [options] - apply saves
+ *   voice.sttModelSize / voice.sttUseGpu.
+ * @returns {Promise<{success: boolean, data?: {hardware: Object, recommendation: {modelSize: string, useGpu: boolean, estimatedMs: number, targetMs: number, reason: string, estimates: Array}, applied: boolean}}>}
+ */
+export async function recommendSttModel({ apply = false, targetLatencyMs = null } = {}) {
+  return invoke('recommend_stt_model', { apply, targetLatencyMs });
+}
+
 export async function listSttModels() {
   return invoke('list_stt_models');
 }
 
-export async function deleteSttModel(modelSize) {
-  return invoke('delete_stt_model', { modelSize });
+export async function deleteSttModel(modelSize, filename) {
+  return invoke('delete_stt_model', { modelSize, filename: filename || null });
+}
+
+/**
+ * Delete all cached TTS audio (memory and disk).
+ * @returns {Promise<{success: boolean, data?: {files: number, bytes: number}}>}
+ */
+export async function clearTtsCache() {
+  return invoke('clear_tts_cache');
+}
+
+// ============ Control API ============
+
+/**
+ * Get the localhost control API settings (including the plaintext token).
+ * @returns {Promise<{success: boolean, data?: {enabled: boolean, port: number, token: string, running: boolean, url: string|null}}>}
+ */
+export async function controlApiStatus() {
+  return invoke('control_api_status');
+}
+
+/**
+ * Enable/disable the control API, change its port, or rotate its token.
+ * @param {boolean} enabled
+ * @param {number} [port]
+ * @param {boolean} [regenerateToken]
+ */
+export async function configureControlApi(enabled, port, regenerateToken) {
+  return invoke('configure_control_api', {
+    enabled,
+    port: port ?? null,
+    regenerateToken: regenerateToken || false,
+  });
+}
+
+// ============ Quiet Hours ============
+
+/**
+ * Whether quiet hours are in effect (schedule plus any override).
+ * @returns {Promise<{success: boolean, data?: {active: boolean, scheduled: boolean, overrideMode: 'quiet'|'off'|null, overrideUntil: number|null, nextChange: number|null}}>}
+ */
+export async function getQuietHoursStatus() {
+  return invoke('get_quiet_hours_status');
+}
+
+/**
+ * Save the quiet hours schedule.
+ * @param {{enabled: boolean, schedule: Array<{days: string[], start: string, end: string}>, utcOffsetMinutes: number}} quietHours
+ */
+export async function configureQuietHours(quietHours) {
+  return invoke('configure_quiet_hours', { quietHours });
+}
+
+/**
+ * Override the schedule: 'quiet' (quiet now), 'off' (not now) or 'auto'.
+ * @param {'quiet'|'off'|'auto'} mode
+ * @param {number} [minutes] - Override length; default until the schedule next changes
+ */
+export async function setQuietHoursOverride(mode, minutes) {
+  return invoke('set_quiet_hours_override', { mode, minutes: minutes ?? null });
 }
 
 // ============ Files ============
@@ -1176,6 +1580,27 @@ export async function validateApiKey(provider, key) {
   return invoke('validate_api_key', { params: { provider, key } });
 }
 
+/**
+ * Setup step states: { steps: [{ step, status, detail, phase, percent, data }], complete }.
+ * Steps are 'models', 'audio', 'vad' and 'provider'.
+ */
+export async function getOnboardingStatus() {
+  return invoke('get_onboarding_status');
+}
+
+/**
+ * Run one setup step; progress arrives as `onboarding-step` events and the
+ * final state is returned. `download` fetches missing models (models step).
+ */
+export async function runOnboardingStep(step, download = false) {
+  return invoke('run_onboarding_step', { params: { step, download } });
+}
+
+/** Mark a setup step skipped. */
+export async function skipOnboardingStep(step) {
+  return invoke('skip_onboarding_step', { params: { step } });
+}
+
 export async function logFrontendError(params) {
   return invoke('log_frontend_error', { params });
 }
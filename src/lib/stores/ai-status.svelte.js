@@ -7,8 +7,13 @@
  * Also wires API provider streaming events (ai-stream-token, ai-stream-end,
  * ai-response) to the chat store so API responses appear in the chat UI.
  *
+ * Also keeps the tool plan trace: the calls and results of an API
+ * provider's current tool loop (ai-tool-plan-step), cleared when the
+ * reply arrives.
+ *
  * Listens to Tauri events: ai-status-change, ai-error, ai-output,
- * ai-stream-token, ai-stream-end, ai-response.
+ * ai-stream-token, ai-stream-end, ai-response, ai-tool-calls,
+ * ai-tool-plan-step.
  */
 import { listen } from '@tauri-apps/api/event';
 import { startAI, stopAI, getAIStatus, setProvider as apiSetProvider, speakText } from '../api.js';
@@ -29,6 +34,8 @@ function createAiStatusStore() {
   let displayName = $state('');
   let error = $state(null);
   let starting = $state(false);
+  /** @type {Array<{iteration: number, maxIterations: number, tool: string, toolCallId: string, phase: 'call'|'result', summary: string}>} */
+  let toolTrace = $state([]);
 
   return {
     get running() { return running; },
@@ -36,6 +43,7 @@ function createAiStatusStore() {
     get displayName() { return displayName; },
     get error() { return error; },
     get starting() { return starting; },
+    get toolTrace() { return toolTrace; },
 
     /** Whether the current provider is a CLI/PTY provider. */
     get isCliProvider() { return CLI_PROVIDERS.includes(providerType); },
@@ -62,6 +70,22 @@ function createAiStatusStore() {
       starting = false;
     },
 
+    /** Add a tool plan step; a result replaces its call's entry. */
+    _addToolStep(step) {
+      const i = step.phase === 'result'
+        ? toolTrace.findIndex((s) => s.toolCallId === step.toolCallId)
+        : -1;
+      if (i >= 0) {
+        toolTrace[i] = step;
+      } else {
+        toolTrace = [...toolTrace, step];
+      }
+    },
+
+    _clearToolTrace() {
+      if (toolTrace.length) toolTrace = [];
+    },
+
     _setStarting() {
       starting = true;
       error = null;
@@ -213,6 +237,7 @@ export async function initAiStatusListeners() {
   await listen('ai-error', (event) => {
     const data = event.payload;
     aiStatusStore._setError(data?.error || 'Unknown error');
+    aiStatusStore._clearToolTrace();
 
     // If we were streaming an API response, finalize and show the error
     if (_apiStreamingMsgId) {
@@ -252,11 +277,12 @@ export async function initAiStatusListeners() {
       chatStore.finalizeStreamingMessage();
       _apiStreamingMsgId = null;
     }
+    aiStatusStore._clearToolTrace();
 
     // Speak the response via TTS (API providers only — CLI providers
     // speak via the MCP voice_send path in voice.svelte.js).
     const text = event.payload?.text;
-    if (text) {
+    if (text && event.payload?.speak !== false) {
       speakText(text).catch((err) => {
         console.warn('[ai-status] Failed to speak API response:', err);
       });
@@ -278,6 +304,11 @@ export async function initAiStatusListeners() {
     }
   });
 
+  // Live "working on it" trace while the model runs tools
+  await listen('ai-tool-plan-step', (event) => {
+    if (event.payload?.tool) aiStatusStore._addToolStep(event.payload);
+  });
+
   // Initial status poll
   await refreshStatus();
 }
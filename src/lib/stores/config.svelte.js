@@ -21,6 +21,12 @@ const DEFAULT_CONFIG = {
     ttsModelSize: '0.6B',
     ttsSpeed: 1.0,
     ttsVolume: 1.0,
+    ttsEffects: [],
+    ttsPhraseGapMs: 80,
+    ttsSentencePauseMs: 250,
+    ttsLookahead: 2,
+    ttsBlockedTerms: [],
+    ttsFilterAction: 'mask',
     ttsApiKey: null,
     ttsEndpoint: null,
     ttsModelPath: null,
@@ -28,14 +34,51 @@ const DEFAULT_CONFIG = {
     sttAdapter: 'whisper-local',
     sttModelSize: 'base',
     sttUseGpu: false,
+    sttTargetLatencyMs: 1500,
+    sttLanguage: 'en',
+    sttTask: 'transcribe',
+    sttContextWords: 32,
+    sttInitialPrompt: '',
+    sttVocabulary: [],
+    minSpeechCoverage: 0.05,
+    vadThreshold: 0.01,
+    silenceTimeoutSecs: 2,
+    maxRecordingSecs: 120,
+    toggleTimeoutSecs: 60,
     sttApiKey: null,
     sttEndpoint: null,
     sttModelName: null,
     inputDevice: null,
+    captureSource: 'microphone',
     outputDevice: null,
+    outputDevices: [],
+    duckWhileRecording: false,
+    duckLevel: 0.2,
+    listenDutyCycle: 'battery',
+    resampler: 'linear',
+    audioSession: 'default',
+    sampleRate: 16000,
+    chunkMs: 80,
+    transcriptLog: true,
+    turnArchiveSize: 20,
+    turnArchiveFormat: 'wav',
+    noiseSuppression: false,
     announceStartup: true,
     announceProviderSwitch: true,
     dictionary: [],
+    localCommands: true,
+    spokenConfirmation: true,
+    sttLoad: 'background',
+    ttsLoad: 'background',
+    speakerId: false,
+    speakerThreshold: 0.5,
+    ignoreUnknownSpeakers: true,
+    sessionTimeoutSecs: 300,
+    turnPolicy: 'queue',
+    activeProfile: null,
+    latencyBudgetMs: 0,
+    watchdogAutoRestart: true,
+    commands: [],
   },
   appearance: {
     orbSize: 80,
@@ -60,6 +103,7 @@ const DEFAULT_CONFIG = {
     statsHotkey: 'CommandOrControl+Shift+M',
     dictationKey: 'MouseButton5',
     showToasts: true,
+    speakChatReplies: true,
   },
   window: {
     orbX: null,
@@ -75,6 +119,12 @@ const DEFAULT_CONFIG = {
   advanced: {
     debugMode: false,
     showDependencies: false,
+    eventThrottle: {
+      enabled: true,
+      stateCoalesceMs: 16,
+      tokenBatchMs: 30,
+      levelIntervalMs: 0,
+    },
   },
   sidebar: {
     collapsed: false,
@@ -98,6 +148,21 @@ const DEFAULT_CONFIG = {
   browser: {
     downloadAskLocation: false,
     downloadPath: '',
+    searchBackend: 'auto',
+    searchFallback: true,
+    searxngUrl: null,
+    braveApiKey: null,
+  },
+  controlApi: {
+    enabled: false,
+    port: 47823,
+    token: null,
+  },
+  hotkeys: {
+    pushToTalk: '',
+    toggle: '',
+    stopSpeaking: '',
+    cycleMode: '',
   },
   workspace: {
     showChat: false,
@@ -105,6 +170,26 @@ const DEFAULT_CONFIG = {
     chatRatio: 0.3,
     terminalRatio: 0.7,
   },
+  network: {
+    devices: [],
+  },
+  files: {
+    roots: [],
+  },
+  webhooks: [],
+  quietHours: {
+    enabled: false,
+    schedule: [{ days: [], start: '22:00', end: '07:00' }],
+    utcOffsetMinutes: 0,
+  },
+  transcriptRules: {
+    replacements: [],
+    maskProfanity: false,
+    profanityWords: [],
+    autoCapitalize: false,
+    normalizeNumbers: false,
+  },
+  transcriptRoutes: [],
   user: {
     name: null,
   },
@@ -124,11 +209,20 @@ const DEFAULT_CONFIG = {
     contextLength: 32768,
     autoDetect: true,
     systemPrompt: null,
+    responseStyle: 'auto',
+    narrateToolSteps: false,
     toolProfile: 'voice-assistant',
     toolProfiles: {
       'voice-assistant': { groups: ['core', 'memory', 'browser'] },
       'full-toolbox': { groups: ['core', 'memory', 'browser', 'n8n'] },
     },
+    toolResultMaxChars: 24000,
+    toolResultLimits: {
+      browser_action: 16000,
+      sandbox_snapshot: 16000,
+      n8n_get_execution: 8000,
+      n8n_get_executions: 8000,
+    },
     endpoints: {
       ollama: 'http://127.0.0.1:11434',
       lmstudio: 'http://127.0.0.1:1234',
@@ -17,10 +17,13 @@ const VALID_VIEWS = ['chat', 'terminal', 'lens', 'settings'];
 function createNavigationStore() {
   let activeView = $state('lens');
   let sidebarCollapsed = $state(false);
+  /** Last settings tab requested via openSettings() ({ tab }), for SettingsPanel. */
+  let settingsRequest = $state(null);
 
   return {
     get activeView() { return activeView; },
     get sidebarCollapsed() { return sidebarCollapsed; },
+    get settingsRequest() { return settingsRequest; },
 
     /**
      * Switch to a different view.
@@ -34,6 +37,15 @@ function createNavigationStore() {
       activeView = view;
     },
 
+    /**
+     * Show the settings view on a specific tab (e.g. from a toast action).
+     * @param {string} tab - SettingsPanel tab id ('voice', 'ai', ...)
+     */
+    openSettings(tab) {
+      settingsRequest = { tab };
+      activeView = 'settings';
+    },
+
     /**
      * Toggle sidebar collapsed state and persist to config.
      */
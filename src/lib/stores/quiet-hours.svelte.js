@@ -0,0 +1,54 @@
+/**
+ * quiet-hours.js -- Svelte 5 reactive store for scheduled quiet hours.
+ *
+ * Mirrors the backend quiet-hours status (schedule plus override) so the UI
+ * can show it and the toast store can keep non-error toasts silent while
+ * quiet hours are active.
+ */
+
+import { listen } from '@tauri-apps/api/event';
+import { getQuietHoursStatus, setQuietHoursOverride } from '../api.js';
+import { unwrapResult } from '../utils.js';
+
+function createQuietHoursStore() {
+  /** @type {{ active: boolean, scheduled: boolean, overrideMode: 'quiet'|'off'|null, overrideUntil: number|null, nextChange: number|null }|null} */
+  let status = $state(null);
+
+  /**
+   * Override the schedule ('quiet', 'off' or 'auto').
+   * @param {'quiet'|'off'|'auto'} mode
+   * @param {number} [minutes]
+   */
+  async function override(mode, minutes) {
+    const result = await setQuietHoursOverride(mode, minutes);
+    if (!result?.success) throw new Error(result?.error || 'Override failed');
+    status = result.data;
+    return status;
+  }
+
+  return {
+    get status() { return status; },
+    get active() { return status?.active === true; },
+    override,
+    /** @param {object|null} next */
+    _setStatus(next) { status = next; },
+  };
+}
+
+export const quietHoursStore = createQuietHoursStore();
+
+/**
+ * Load the current status and follow `quiet-hours-changed` events.
+ */
+export async function initQuietHoursListeners() {
+  try {
+    const result = unwrapResult(await getQuietHoursStatus());
+    if (result) quietHoursStore._setStatus(result);
+  } catch (err) {
+    console.warn('[quiet-hours] Failed to load status:', err);
+  }
+
+  await listen('quiet-hours-changed', (event) => {
+    quietHoursStore._setStatus(event.payload);
+  });
+}
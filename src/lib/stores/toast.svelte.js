@@ -8,6 +8,7 @@
 import { uid } from '../utils.js';
 import { configStore } from './config.svelte.js';
 import { statusBarStore } from './status-bar.svelte.js';
+import { quietHoursStore } from './quiet-hours.svelte.js';
 
 /**
  * @typedef {Object} Toast
@@ -64,6 +65,13 @@ function createToastStore() {
     // Respect the showToasts config setting (errors always shown)
     if (severity !== 'error' && configStore.value?.behavior?.showToasts === false) return null;
 
+    // Quiet hours: keep non-error toasts silent, but still record them in
+    // the notification center.
+    if (severity !== 'error' && quietHoursStore.active) {
+      statusBarStore.addNotification({ message, severity, source: 'toast' });
+      return null;
+    }
+
     // Deduplicate by key — dismiss existing toast with same key
     if (key) {
       const existing = toasts.find(t => t.key === key);
@@ -5,17 +5,48 @@
  * and exposes reactive state for the Sidebar, ChatInput, Overlay, etc.
  */
 import { listen } from '@tauri-apps/api/event';
-import { startVoice, stopVoice, getVoiceStatus, speakText, setVoiceMode, aiPtyInput, writeUserMessage, injectText } from '../api.js';
-import { configStore } from './config.svelte.js';
+import { startVoice, stopVoice, restartVoice, getVoiceStatus, speakText, setVoiceMode, sendTextMessage, injectText } from '../api.js';
+import { configStore, loadConfig } from './config.svelte.js';
 import { chatStore } from './chat.svelte.js';
 import { aiStatusStore } from './ai-status.svelte.js';
 import { attachmentsStore } from './attachments.svelte.js';
 import { toastStore } from './toast.svelte.js';
+import { navigationStore } from './navigation.svelte.js';
 import { unwrapResult } from '../utils.js';
 
 /** Dedup window (ms) — ignore duplicate transcription text within this period. */
 const TRANSCRIPTION_DEDUP_MS = 3000;
 
+/**
+ * Fixes offered for backend error codes (see src-tauri/src/error.rs).
+ * Codes without an entry just show the message.
+ */
+export const VOICE_ERROR_REMEDIES = {
+  mic_unavailable: 'No microphone available. Connect one or allow microphone access, then pick it in Voice settings.',
+  audio_device: 'The audio device could not be opened. Try another device in Voice settings.',
+  model_missing: 'A speech model has not been downloaded yet. Download it in Voice settings.',
+  network: 'Could not reach the network. Check your connection or switch to a local engine in Voice settings.',
+  config: 'Check the setting in Voice settings.',
+};
+
+/**
+ * Show a voice error toast, with a "Voice settings" action when the code
+ * has a known fix. Repeats of the same code replace the previous toast.
+ * @param {string} message
+ * @param {string} [code]
+ */
+export function showVoiceError(message, code) {
+  const remedy = code ? VOICE_ERROR_REMEDIES[code] : null;
+  toastStore.addToast({
+    message: remedy ? `${message}. ${remedy}` : message,
+    severity: 'error',
+    key: code ? `voice-error-${code}` : null,
+    action: remedy
+      ? { label: 'Voice settings', callback: () => navigationStore.openSettings('voice') }
+      : null,
+  });
+}
+
 /**
  * Apply user dictionary corrections to a transcription.
  *
@@ -49,21 +80,43 @@ export function applyDictionary(text, dictionary) {
 function createVoiceStore() {
   let state = $state('idle');           // idle | listening | recording | processing | speaking
   let running = $state(false);
+  let paused = $state(false);           // mic released by pauseVoice(); engines stay loaded
+  let micOpen = $state(false);          // a capture stream holds the input device (privacy indicator)
+  let micMuted = $state(null);          // { source } while the OS or a hardware switch mutes the mic, else null
   let lastTranscription = $state('');
   let error = $state(null);
+  let errorCode = $state(null);         // stable backend error code of `error`, if any
   let isDictating = $state(false);     // true when recording for dictation (not AI)
   let stuck = $state(null);            // { state, elapsedSecs } when pipeline is wedged, else null
+  let lastTiming = $state(null);       // stage timings of the last finished turn (pipeline_timing event)
+  let loadingEngines = $state([]);     // engines ('stt' | 'tts' | 'speaker') still on their first load
+  let lastSpeaker = $state(null);      // enrolled speaker of the last transcription, if identified
+  let session = $state(null);          // { id, turns } of the open conversation session, else null
+  let pendingConfirm = $state(null);   // { tool, prompt } while a destructive tool waits for a spoken yes/no
+  let pendingQuestion = $state(null);  // question an AI asked aloud (voice_ask) until it is answered
+  /** Speech heard on system audio (captureSource "both"): [{ id, text, at }], oldest first. */
+  let systemTranscripts = $state([]);
+  const MAX_SYSTEM_TRANSCRIPTS = 200;
   /** Rolling waveform amplitudes (0..1), newest at the end — for the recording bar. */
   let levels = $state([]);
   const MAX_LEVELS = 72;
+  /** Seconds recorded so far and when the pipeline will auto-stop ({ secs, limitSecs }), or null. */
+  let recordingElapsed = $state(null);
+  /** Text being spoken and the word currently playing ({ charOffset, word }), for highlighting. */
+  let speakingText = $state('');
+  let speakingProgress = $state(null);
   let lastRoutedText = '';
   let lastRoutedTime = 0;
 
   return {
     get state() { return state; },
     get running() { return running; },
+    get paused() { return paused; },
+    get micOpen() { return micOpen; },
+    get micMuted() { return micMuted; },
     get lastTranscription() { return lastTranscription; },
     get error() { return error; },
+    get errorCode() { return errorCode; },
 
     // Derived convenience getters
     get isRecording() { return state === 'recording'; },
@@ -72,8 +125,27 @@ function createVoiceStore() {
     get isProcessing() { return state === 'processing'; },
     get isDictating() { return isDictating; },
     get stuck() { return stuck; },
+    get lastTiming() { return lastTiming; },
+    /** Engines ('stt', 'tts') whose model is still loading; the first turn waits on them. */
+    get loadingEngines() { return loadingEngines; },
+    /** Enrolled speaker the last transcription was identified as, or null. */
+    get lastSpeaker() { return lastSpeaker; },
+    /** Open conversation session ({ id, turns }), or null between sessions. */
+    get session() { return session; },
+    /** Destructive tool waiting for a spoken yes or no ({ tool, prompt }), or null. */
+    get pendingConfirm() { return pendingConfirm; },
+    /** Question an AI asked with voice_ask, or null. The next utterance answers it. */
+    get pendingQuestion() { return pendingQuestion; },
+    /** System-audio transcriptions (meeting notes), oldest first. Never sent to the AI. */
+    get systemTranscripts() { return systemTranscripts; },
     /** Live waveform amplitudes (0..1) for the recording bar. */
     get levels() { return levels; },
+    /** Elapsed time of the current recording ({ secs, limitSecs | null }), or null. */
+    get recordingElapsed() { return recordingElapsed; },
+    /** Text of the current TTS utterance ('' when not speaking). */
+    get speakingText() { return speakingText; },
+    /** Word currently being spoken, or null. `charOffset` indexes speakingText by code point. */
+    get speakingProgress() { return speakingProgress; },
 
     /** Update state from voice-event payload */
     _handleVoiceEvent(payload) {
@@ -91,7 +163,10 @@ function createVoiceStore() {
           // watchdog will re-emit a fresh 'stuck' event.
           stuck = null;
           // Clear the waveform once we leave the recording state.
-          if (state !== 'recording') levels = [];
+          if (state !== 'recording') {
+            levels = [];
+            recordingElapsed = null;
+          }
           // A dictation session ends when we return to idle. Clear isDictating here
           // (not only on a successful transcription, line ~140) so a silent/empty
           // recording can't leave it stuck true — which would wedge the next toggle
@@ -108,29 +183,191 @@ function createVoiceStore() {
           }
           break;
         }
+        case 'recording_elapsed':
+          recordingElapsed = {
+            secs: data.secs ?? 0,
+            limitSecs: data.limit_secs ?? null,
+          };
+          break;
+        case 'recording_stop':
+          if (data.reason === 'max_duration') {
+            toastStore.addToast({
+              message: 'Recording hit the maximum length and was stopped',
+              severity: 'warning',
+            });
+          } else if (data.reason === 'toggle_timeout') {
+            toastStore.addToast({
+              message: 'Mic was left on, so the recording was stopped and sent',
+              severity: 'info',
+            });
+          }
+          break;
+        case 'no_speech_detected':
+          // Recording was (nearly) all silence, so STT was skipped. The
+          // pipeline returns to idle/listening on its own; nothing to route.
+          break;
+        case 'pipeline_timing':
+          lastTiming = data;
+          break;
+        case 'latency_ladder':
+          // Turns kept going over voice.latencyBudgetMs, so the pipeline
+          // restarted with cheaper settings (manual overrides come from Settings).
+          if (!data.manual) {
+            toastStore.addToast({
+              message: `Voice was slow, switched to faster settings: ${data.detail}`,
+              severity: 'info',
+            });
+          }
+          break;
         case 'stuck':
           // Watchdog detected the pipeline wedged in a non-idle state.
           stuck = { state: data.state, elapsedSecs: data.elapsed_secs ?? 0 };
           break;
+        case 'pipeline_stalled':
+          // The audio loop stopped consuming audio; the backend restarts it
+          // unless voice.watchdogAutoRestart is off or it keeps stalling.
+          if (data.restarting) {
+            toastStore.addToast({ message: `Voice stopped responding (${data.detail}), restarting`, severity: 'warning' });
+          } else {
+            running = false;
+            toastStore.addToast({
+              message: `Voice stopped responding: ${data.detail}`,
+              severity: 'error',
+              key: 'voice-pipeline-stalled',
+              action: { label: 'Restart voice', callback: () => restartVoice() },
+            });
+          }
+          break;
+        case 'local_command':
+          // A spoken command ("stop talking", "louder", ...) the backend handled
+          // itself; it is not forwarded to the AI. Mode switches announce
+          // themselves via voice-mode-changed.
+          if (data.intent === 'louder' || data.intent === 'quieter') {
+            loadConfig().then(() => {
+              const volume = configStore.value?.voice?.ttsVolume;
+              if (volume != null) {
+                toastStore.addToast({ message: `Voice volume: ${Math.round(volume * 100)}%`, severity: 'info' });
+              }
+            });
+          }
+          break;
+        case 'confirm_prompt':
+          // A destructive tool is being confirmed aloud; the next utterance
+          // is the answer and is not sent to the AI.
+          pendingConfirm = { tool: data.tool, prompt: data.prompt };
+          break;
+        case 'confirm_answer':
+          pendingConfirm = null;
+          if (data.answer === 'no') {
+            toastStore.addToast({ message: `Cancelled ${data.tool}`, severity: 'info' });
+          } else if (data.answer !== 'yes') {
+            toastStore.addToast({
+              message: `No spoken answer for ${data.tool}; the AI will ask instead`,
+              severity: 'info',
+            });
+          }
+          break;
+        case 'ask_question':
+          // An AI asked the user something aloud; recording starts once the
+          // question has been spoken and the reply goes back to that AI.
+          pendingQuestion = data.question;
+          break;
+        case 'ask_answer':
+          pendingQuestion = null;
+          if (data.answer == null) {
+            toastStore.addToast({ message: 'No answer heard; the AI will carry on without one', severity: 'info' });
+          }
+          break;
+        case 'engine_load':
+          // First load of an engine started in the background or lazily.
+          // Failures also arrive as an 'error' event.
+          loadingEngines = data.status === 'loading'
+            ? [...loadingEngines.filter((e) => e !== data.engine), data.engine]
+            : loadingEngines.filter((e) => e !== data.engine);
+          break;
+        case 'session_start':
+          session = { id: data.session_id, turns: 0 };
+          break;
+        case 'turn_complete':
+          // The reply to a turn was given and played (or skipped); the chat
+          // message already carries its session and turn.
+          break;
+        case 'session_end':
+          if (session?.id === data.session_id) session = null;
+          break;
+        case 'engine_recovered':
+          // A lost/poisoned STT or TTS engine was rebuilt; voice keeps working.
+          toastStore.addToast({
+            message: data.engine === 'tts'
+              ? 'Speech output engine was restarted after an error'
+              : 'Speech recognition engine was restarted after an error',
+            severity: 'info',
+          });
+          break;
         case 'ready':
           running = true;
           error = null;
+          errorCode = null;
           applyVoiceModeFromConfig();
           break;
         case 'starting':
           running = false;
           error = null;
+          errorCode = null;
           break;
         case 'stopping':
           running = false;
+          paused = false;
           state = 'idle';
+          loadingEngines = [];
+          session = null;
+          break;
+        case 'paused':
+          paused = true;
+          break;
+        case 'resumed':
+          paused = false;
+          break;
+        case 'microphone_open':
+          micOpen = true;
+          micMuted = null;
+          break;
+        case 'microphone_closed':
+          micOpen = false;
+          micMuted = null;
+          break;
+        case 'microphone_muted':
+          micMuted = data.muted ? { source: data.source } : null;
+          if (data.muted) {
+            toastStore.addToast({
+              message: data.source === 'os'
+                ? 'Your microphone is muted in the system settings — Voice Mirror hears silence'
+                : 'Your microphone is sending silence — check its mute switch',
+              severity: 'warning',
+              key: 'voice-mic-muted',
+            });
+          }
           break;
         case 'transcription':
+          // System audio (a meeting, a video) is kept as notes, not a prompt
+          if (data.source === 'system') {
+            if (data.text) {
+              systemTranscripts = [
+                ...systemTranscripts.slice(-(MAX_SYSTEM_TRANSCRIPTS - 1)),
+                { id: data.id, text: data.text, at: Date.now() },
+              ];
+            }
+            break;
+          }
           if (data.text) {
             // Apply user dictionary corrections before anything consumes the
             // text (dedup, injection, AI routing all use the corrected form).
             const text = applyDictionary(data.text, configStore.value?.voice?.dictionary);
             lastTranscription = text;
+            lastSpeaker = data.speaker ?? null;
+            if (data.session_id != null && session?.id === data.session_id) {
+              session = { ...session, turns: data.turn };
+            }
 
             // Dedup: the voice pipeline can fire multiple transcription events
             // for the same audio segment. Skip if same text within the dedup window.
@@ -141,20 +378,38 @@ function createVoiceStore() {
             lastRoutedText = text;
             lastRoutedTime = now;
 
-            if (isDictating || aiStatusStore.isDictationProvider) {
+            if (Array.isArray(data.routes)) {
+              // A transcriptRoutes entry picked the consumers; the backend
+              // already handled 'webhook' and 'notes'.
+              if (data.routes.includes('dictation')) {
+                injectText(text).catch((err) => {
+                  console.warn('[voice] Failed to inject routed text:', err);
+                });
+              }
+              if (data.routes.includes('provider')) {
+                routeTranscriptionToAI(text, data.session_id, data.turn);
+              }
+            } else if (isDictating || aiStatusStore.isDictationProvider) {
               if (isDictating) isDictating = false;
               injectText(text).catch((err) => {
                 console.warn('[voice] Failed to inject dictation text:', err);
               });
             } else {
-              routeTranscriptionToAI(text);
+              routeTranscriptionToAI(text, data.session_id, data.turn);
             }
           }
           break;
         case 'speaking_start':
           state = 'speaking';
+          speakingText = data.text || '';
+          speakingProgress = null;
+          break;
+        case 'speaking_progress':
+          speakingProgress = { charOffset: data.char_offset, word: data.word };
           break;
         case 'speaking_end':
+          speakingText = '';
+          speakingProgress = null;
           // Don't override if pipeline already set to listening
           if (state === 'speaking') {
             state = 'idle';
@@ -162,6 +417,8 @@ function createVoiceStore() {
           break;
         case 'error':
           error = data.message || 'Unknown voice error';
+          errorCode = data.code || null;
+          if (VOICE_ERROR_REMEDIES[errorCode]) showVoiceError(error, errorCode);
           break;
         case 'audio_devices':
           // Ignore — handled by settings panel if needed
@@ -174,8 +431,9 @@ function createVoiceStore() {
       if (!value) state = 'idle';
     },
 
-    _setError(msg) {
+    _setError(msg, code = null) {
       error = msg;
+      errorCode = code;
     },
 
     startDictation() {
@@ -190,25 +448,38 @@ function createVoiceStore() {
     clearStuck() {
       stuck = null;
     },
+
+    /** Forget the collected system-audio transcriptions (e.g. after the meeting). */
+    clearSystemTranscripts() {
+      systemTranscripts = [];
+    },
   };
 }
 
 export const voiceStore = createVoiceStore();
 
+/** Chat message of the voice turn waiting for the provider, if any. */
+let waitingVoiceMessageId = null;
+
 /**
  * Route a transcription from the voice pipeline to the active AI provider.
  * Adds the text as a user chat message and sends it via the appropriate channel.
  */
-function routeTranscriptionToAI(text) {
+function routeTranscriptionToAI(text, sessionId = null, turn = null) {
   // Take any pending attachments (screenshot thumbnails queued from the picker)
   const attachments = attachmentsStore.take();
   const meta = { source: 'voice' };
+  // Voice session/turn, so the chat and memory can group the conversation
+  if (sessionId != null) {
+    meta.sessionId = sessionId;
+    meta.turn = turn;
+  }
   if (attachments.length > 0) {
     meta.attachments = attachments;
   }
 
   // Add as user message in chat (with attachments if any)
-  chatStore.addMessage('user', text, meta);
+  const messageId = chatStore.addMessage('user', text, meta);
 
   // Extract image path AND data URL for the AI provider. Screenshot-picker
   // attachments have a real path; drag-and-dropped images only have a data
@@ -218,16 +489,25 @@ function routeTranscriptionToAI(text) {
   const imagePath = attachments.length > 0 ? (attachments[0].path || null) : null;
   const imageDataUrl = attachments.length > 0 ? (attachments[0].dataUrl || null) : null;
 
-  // Route to appropriate provider
-  if (aiStatusStore.isApiProvider) {
-    aiPtyInput(text, imagePath, imageDataUrl).catch((err) => {
-      console.warn('[voice] Failed to send transcription to API provider:', err);
-    });
-  } else {
-    writeUserMessage(text, null, null, imagePath, imageDataUrl).catch((err) => {
-      console.warn('[voice] Failed to send transcription to MCP inbox:', err);
+  // Same entry point as the chat box — the backend picks the provider channel
+  sendTextMessage(text, { imagePath, imageDataUrl, speak: true, voice: true })
+    .then((result) => {
+      // The provider was still answering (voice.turnPolicy). A rejected turn
+      // is never sent (the backend says so out loud) and a replaced one is
+      // superseded by the newer turn, so neither stays in the chat.
+      const turn = result?.data?.turn;
+      if (turn === 'rejected') {
+        chatStore.removeMessage(messageId);
+        return;
+      }
+      if (turn === 'replaced' && waitingVoiceMessageId) {
+        chatStore.removeMessage(waitingVoiceMessageId);
+      }
+      waitingVoiceMessageId = turn === 'queued' || turn === 'replaced' ? messageId : null;
+    })
+    .catch((err) => {
+      console.warn('[voice] Failed to send transcription to AI provider:', err);
     });
-  }
 }
 
 /**
@@ -250,8 +530,8 @@ export async function startVoiceEngine() {
     const result = await startVoice();
     if (result?.success === false) {
       const msg = result.error || 'Failed to start voice engine';
-      voiceStore._setError(msg);
-      toastStore.addToast({ message: `Voice engine failed to start: ${msg}`, severity: 'error' });
+      voiceStore._setError(msg, result.code);
+      showVoiceError(msg, result.code);
     }
     // Running state will be confirmed by the voice-event Ready event
   } catch (err) {
@@ -299,7 +579,7 @@ export async function initVoiceListeners() {
   const seenMessageIds = new Set();
 
   // When a new Claude session starts, reset dedup tracking.
-  // The backend clears inbox.json on session start, so old IDs are irrelevant.
+  // The backend clears the inbox on session start, so old IDs are irrelevant.
   voiceUnlisteners.push(await listen('mcp-session-start', () => {
     seenMessageIds.clear();
   }));
@@ -326,9 +606,13 @@ export async function initVoiceListeners() {
         inboxId: payload.id,
       });
 
-      // Speak the response via TTS (unless voice engine is off)
-      if (voiceStore.running) {
-        speakText(payload.text).catch((err) => {
+      // Speak the response via TTS (unless voice engine is off or the
+      // message it answers asked for a silent reply)
+      if (voiceStore.running && payload.speak !== false) {
+        // Several agents connected: speak in the sender's own voice, and say
+        // its name first when another agent sounds the same
+        const spoken = payload.announce ? `${payload.announce}: ${payload.text}` : payload.text;
+        speakText(spoken, payload.priority || undefined, payload.voice || undefined).catch((err) => {
           console.warn('[voice] Failed to speak inbox message:', err);
         });
       }
@@ -6,6 +6,8 @@
  */
 
 // ---- TTS Adapter Registry ----
+// The voice lists are mirrored in src-tauri/src/voice/tts/voices.rs for the
+// MCP list_voices tool (test/lib/voice-catalog.test.cjs keeps them in step).
 
 export const ADAPTER_REGISTRY = {
   kokoro: {
@@ -23,6 +25,7 @@ export const ADAPTER_REGISTRY = {
       { value: 'bm_george', label: 'George (British Male)' },
       { value: 'bm_lewis', label: 'Lewis (British Male)' },
     ],
+    supportsVoiceBlend: true,
     showModelSize: false,
     showApiKey: false,
     showEndpoint: false,
@@ -82,6 +85,17 @@ export const ADAPTER_REGISTRY = {
     showEndpoint: false,
     showModelPath: false,
   },
+  system: {
+    label: 'System voice (Built-in, no download)',
+    category: 'local',
+    voices: [
+      { value: 'default', label: 'System default' },
+    ],
+    showModelSize: false,
+    showApiKey: false,
+    showEndpoint: false,
+    showModelPath: false,
+  },
   'openai-tts': {
     label: 'OpenAI TTS (Cloud, API key required)',
     category: 'cloud-paid',
@@ -141,6 +155,15 @@ export const STT_REGISTRY = {
       { value: 'large-v3-turbo', label: 'Turbo (~574MB, best value)' },
       { value: 'large-v3', label: 'Large v3 (~3.1GB, max accuracy)' },
     ],
+    showLanguage: true,
+    showModelName: false,
+    showApiKey: false,
+    showEndpoint: false,
+  },
+  parakeet: {
+    label: 'Parakeet (Local ONNX, English)',
+    requiresOnnx: true,
+    showModelSize: false,
     showModelName: false,
     showApiKey: false,
     showEndpoint: false,
@@ -161,6 +184,34 @@ export const STT_REGISTRY = {
   },
 };
 
+// ---- STT Languages ----
+
+/**
+ * Languages offered for local Whisper. English uses the smaller `.en`
+ * weights; every other choice (including auto-detect) downloads the
+ * multilingual model.
+ */
+export const STT_LANGUAGES = [
+  { value: 'en', label: 'English' },
+  { value: 'auto', label: 'Auto-detect' },
+  { value: 'de', label: 'German' },
+  { value: 'es', label: 'Spanish' },
+  { value: 'fr', label: 'French' },
+  { value: 'it', label: 'Italian' },
+  { value: 'pt', label: 'Portuguese' },
+  { value: 'nl', label: 'Dutch' },
+  { value: 'pl', label: 'Polish' },
+  { value: 'sv', label: 'Swedish' },
+  { value: 'ru', label: 'Russian' },
+  { value: 'uk', label: 'Ukrainian' },
+  { value: 'tr', label: 'Turkish' },
+  { value: 'ar', label: 'Arabic' },
+  { value: 'hi', label: 'Hindi' },
+  { value: 'ja', label: 'Japanese' },
+  { value: 'ko', label: 'Korean' },
+  { value: 'zh', label: 'Chinese' },
+];
+
 // ---- Keybind display helpers ----
 
 // Virtual key code -> display name (matches Windows VK_ codes)
@@ -185,6 +236,14 @@ export const VKEY_NAMES = {
 
 export const MOUSE_BUTTON_NAMES = { 3: 'Mouse Middle', 4: 'Mouse Back', 5: 'Mouse Forward' };
 
+/** Standard gamepad button index (browser Gamepad API) → display name. */
+export const GAMEPAD_BUTTON_NAMES = {
+  0: 'Pad A', 1: 'Pad B', 2: 'Pad X', 3: 'Pad Y',
+  4: 'Pad LB', 5: 'Pad RB', 6: 'Pad LT', 7: 'Pad RT',
+  8: 'Pad Back', 9: 'Pad Start', 10: 'Pad L-Stick', 11: 'Pad R-Stick',
+  12: 'Pad Up', 13: 'Pad Down', 14: 'Pad Left', 15: 'Pad Right',
+};
+
 // Legacy names (for old configs that haven't been re-saved yet)
 const LEGACY_MOUSE_NAMES = {
   MouseButton3: 'Mouse Middle',
@@ -206,6 +265,12 @@ export function formatKeybind(keybind) {
     const id = parseInt(mouseMatch[1], 10);
     return MOUSE_BUTTON_NAMES[id] || `Mouse Button ${id}`;
   }
+  // "pad:ID" (gamepad button, polled by the native input hook)
+  const padMatch = keybind.match(/^pad:(\d+)$/);
+  if (padMatch) {
+    const id = parseInt(padMatch[1], 10);
+    return GAMEPAD_BUTTON_NAMES[id] || `Pad Button ${id}`;
+  }
   // Legacy format: "MouseButtonN"
   if (LEGACY_MOUSE_NAMES[keybind]) return LEGACY_MOUSE_NAMES[keybind];
   const m = keybind.match(/^MouseButton(\d+)$/);
@@ -32,7 +32,8 @@ export async function initStartupGreeting() {
       if (announceStartup) {
         // Small delay to let pipeline fully settle
         setTimeout(() => {
-          speakText('Voice Mirror is Online').catch((err) => {
+          // Queued so it never cuts off a reply that is already playing
+          speakText('Voice Mirror is Online', 'queue').catch((err) => {
             console.warn('[greeting] Failed to speak startup greeting:', err);
           });
         }, 500);
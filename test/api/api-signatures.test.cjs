@@ -58,6 +58,8 @@ describe('api.js -- critical Tauri command names', () => {
     // Voice
     'start_voice',
     'stop_voice',
+    'pause_voice',
+    'resume_voice',
     'get_voice_status',
     'set_voice_mode',
     'list_audio_devices',
@@ -233,13 +235,43 @@ describe('api.js -- exported async functions', () => {
     'startVoice',
     'stopVoice',
     'restartVoice',
+    'pauseVoice',
+    'resumeVoice',
+    'validateVoiceConfig',
+    'listVoiceProfiles',
+    'saveVoiceProfile',
+    'activateVoiceProfile',
+    'deleteVoiceProfile',
     'ensureSttModel',
     'ensureKokoroModel',
+    'ensureParakeetModel',
+    'listModelAssets',
+    'downloadModel',
+    'deleteModel',
     'getVoiceStatus',
     'setVoiceMode',
+    'calibrateVoice',
+    'voiceSelfTest',
+    'runVadCorpus',
+    'getPipelineTimings',
+    'getMetrics',
+    'voiceGetErrors',
+    'voiceClearErrors',
+    'getLatencyLadder',
+    'setLatencyLadder',
+    'getVoiceStats',
+    'listSpeakers',
+    'enrollSpeaker',
+    'deleteSpeaker',
+    'getCapabilities',
     'listAudioDevices',
     'speakText',
+    'speakToFile',
     'stopSpeaking',
+    'setTtsVoice',
+    'setTtsSpeed',
+    'setTtsVolume',
+    'setTtsEffects',
     'pttPress',
     'pttRelease',
     'cancelRecording',
@@ -252,8 +284,13 @@ describe('api.js -- exported async functions', () => {
     'stopAI',
     'interruptAI',
     'getProvider',
+    'setSessionPrompt',
+    'getSessionPrompt',
+    'snapshotSession',
+    'restoreSession',
     'getAIStatus',
     'aiPtyInput',
+    'sendTextMessage',
     'aiRawInput',
     'aiPtyResize',
     'sendVoiceLoop',
@@ -269,6 +306,14 @@ describe('api.js -- exported async functions', () => {
     'chatDelete',
     'chatRename',
     'exportChatToFile',
+    // Transcript log
+    'transcriptQuery',
+    'transcriptExportMarkdown',
+    'transcriptPurge',
+    'turnArchiveList',
+    'turnArchiveGet',
+    'turnArchiveRecheck',
+    'turnArchivePurge',
     // Screenshot
     'takeScreenshot',
     'saveImageToTemp',
@@ -286,6 +331,7 @@ describe('api.js -- exported async functions', () => {
     'unregisterShortcut',
     'unregisterAllShortcuts',
     'listShortcuts',
+    'configureHotkeys',
     // Performance Stats
     'getProcessStats',
     // Migration
@@ -342,8 +388,17 @@ describe('api.js -- exported async functions', () => {
     // GPU / Model Management
     'detectEspeak',
     'detectGpu',
+    'recommendSttModel',
     'listSttModels',
     'deleteSttModel',
+    'clearTtsCache',
+    // Control API
+    'controlApiStatus',
+    'configureControlApi',
+    // Quiet Hours
+    'getQuietHoursStatus',
+    'configureQuietHours',
+    'setQuietHoursOverride',
     // Dev Server
     'detectDevServers',
     'probePort',
@@ -450,6 +505,9 @@ describe('api.js -- exported async functions', () => {
     'installProvider',
     'probeProviderAuth',
     'validateApiKey',
+    'getOnboardingStatus',
+    'runOnboardingStep',
+    'skipOnboardingStep',
     'logFrontendError',
     // Browser History
     'lensAddHistoryEntry',
@@ -25,12 +25,11 @@ describe('App.svelte — handleChatSend', () => {
     assert.ok(fn.includes('[/Element Context]'), 'Should close context delimiter');
   });
 
-  it('passes imageDataUrl to aiPtyInput', () => {
-    assert.ok(fn.includes('imageDataUrl'), 'Should pass imageDataUrl to provider calls');
-  });
-
-  it('passes imageDataUrl to writeUserMessage', () => {
-    // Both provider paths should use the dataUrl
-    assert.ok(fn.includes('writeUserMessage(fullText'), 'Should pass fullText to writeUserMessage');
+  it('sends through sendTextMessage with the image and speak flag', () => {
+    assert.ok(
+      fn.includes('sendTextMessage(fullText, { imagePath, imageDataUrl, speak })'),
+      'Should share the voice transcription path'
+    );
+    assert.ok(fn.includes('speakChatReplies'), 'Should read behavior.speakChatReplies');
   });
 });
@@ -264,6 +264,11 @@ describe('ChatPanel.svelte', () => {
     assert.ok(src.includes('messages-container'), 'Should have messages container');
   });
 
+  it('shows the tool plan trace from aiStatusStore', () => {
+    assert.ok(src.includes('aiStatusStore.toolTrace'), 'Should render the tool trace');
+    assert.ok(src.includes('{step.iteration}/{step.maxIterations}'), 'Should show the round out of the max');
+  });
+
   it('has empty state', () => {
     assert.ok(src.includes('empty-state'), 'Should have empty state');
   });
@@ -0,0 +1,65 @@
+/**
+ * control-api.test.cjs
+ *
+ * Covers the localhost control API:
+ * - Backend server binds to 127.0.0.1 and requires the token
+ * - Tauri commands to query/configure it are registered
+ * - Settings exposes the toggle, port, and token
+ */
+const { describe, it } = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs');
+const path = require('path');
+
+const root = path.join(__dirname, '..', '..');
+const read = (p) => fs.readFileSync(path.join(root, p), 'utf-8');
+
+describe('backend: control API service', () => {
+  const src = read('src-tauri/src/services/control_api.rs');
+
+  it('binds to localhost only', () => {
+    assert.ok(src.includes('TcpListener::bind(("127.0.0.1", port))'), 'Should bind 127.0.0.1');
+  });
+
+  it('checks the bearer token or token query parameter', () => {
+    assert.ok(src.includes('strip_prefix("Bearer ")'), 'Should accept Authorization: Bearer');
+    assert.ok(src.includes('query.get("token")'), 'Should accept ?token=');
+  });
+
+  it('streams voice events over WebSocket', () => {
+    assert.ok(src.includes('listen_any("voice-event"'), 'Should subscribe to voice-event');
+    assert.ok(src.includes('derive_accept_key('), 'Should upgrade /api/events to WebSocket');
+    assert.ok(src.includes('from_partially_read('), 'Should keep bytes read with the head');
+  });
+
+  it('caps concurrent connections', () => {
+    assert.ok(src.includes('Semaphore::new(MAX_CONNECTIONS)'), 'Should limit connections');
+  });
+
+  it('is started at launch and exposed as commands', () => {
+    const lib = read('src-tauri/src/lib.rs');
+    assert.ok(lib.includes('control_api::start_from_config'), 'Should start from config in setup');
+    assert.ok(lib.includes('voice_cmds::control_api_status'), 'Should register control_api_status');
+    assert.ok(lib.includes('voice_cmds::configure_control_api'), 'Should register configure_control_api');
+  });
+
+  it('encrypts and masks the token', () => {
+    assert.ok(read('src-tauri/src/config/persistence.rs').includes('config.control_api.token'), 'Should encrypt on disk');
+    assert.ok(read('src-tauri/src/commands/config.rs').includes('/controlApi/token'), 'Should mask in get_config');
+  });
+});
+
+describe('frontend: control API settings', () => {
+  const comp = read('src/components/settings/ControlApiSettings.svelte');
+
+  it('toggles the API and rotates the token', () => {
+    assert.ok(comp.includes('label="Enable Control API"'), 'Should have enable toggle');
+    assert.ok(comp.includes('configureControlApi('), 'Should call configureControlApi');
+    assert.ok(comp.includes('regenerateToken: true'), 'Should offer token regeneration');
+  });
+
+  it('is rendered in the General tab', () => {
+    const panel = read('src/components/settings/SettingsPanel.svelte');
+    assert.ok(panel.includes('<ControlApiSettings'), 'Should render ControlApiSettings');
+  });
+});
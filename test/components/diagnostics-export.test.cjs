@@ -87,3 +87,28 @@ describe('frontend: diagnostics export', () => {
     assert.ok(panel.includes('<DiagnosticsSettings'), 'Should render it');
   });
 });
+
+describe('frontend: voice latency', () => {
+  it('DiagnosticsSettings lists recent turn timings', () => {
+    const comp = read('src/components/settings/DiagnosticsSettings.svelte');
+    assert.ok(comp.includes('getPipelineTimings('), 'Should fetch the timing history');
+    assert.ok(comp.includes('voiceStore.lastTiming'), 'Should refresh when a turn finishes');
+  });
+
+  it('voice store keeps the last pipeline_timing event', () => {
+    const store = read('src/lib/stores/voice.svelte.js');
+    assert.ok(store.includes("case 'pipeline_timing':"), 'Should handle pipeline_timing');
+    assert.ok(store.includes('get lastTiming()'), 'Should expose lastTiming');
+  });
+});
+
+describe('frontend: recent recordings', () => {
+  it('DiagnosticsSettings replays and re-checks archived turns', () => {
+    const comp = read('src/components/settings/DiagnosticsSettings.svelte');
+    assert.ok(comp.includes('turnArchiveList('), 'Should list archived turns');
+    assert.ok(comp.includes('turnArchiveGet(id)'), 'Should fetch the recording to play');
+    assert.ok(comp.includes('new Audio(`data:${data.mimeType};base64,'), 'Should play the WAV in the webview');
+    assert.ok(comp.includes('turnArchiveRecheck(id)'), 'Should re-check with cloud STT');
+    assert.ok(comp.includes('rec.corrected'), 'Should show the cloud transcription next to the original');
+  });
+});
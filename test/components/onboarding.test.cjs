@@ -243,6 +243,13 @@ describe('Phase 3: multi-step setup checklist', () => {
     assert.ok(wiz.includes('vramMb'), 'Suggestion should consider VRAM');
   });
 
+  it('STT suggestion comes from the hardware probe and the download is selected', () => {
+    assert.ok(wiz.includes('recommendSttModel'), 'Should ask the backend for a recommendation');
+    assert.ok(wiz.includes('sttRecommendation.modelSize'), 'Probe result should drive the suggestion');
+    assert.ok(wiz.includes('sttRecommendation.reason'), 'Should explain the suggestion');
+    assert.ok(wiz.includes('updateConfig({ voice: { sttModelSize: size } })'), 'Downloaded model should become the active one');
+  });
+
   it('TTS step verifies espeak-ng and warns (skippable) when missing', () => {
     assert.ok(wiz.includes('detectEspeak'), 'Should verify espeak-ng');
     assert.ok(/espeak-ng ready/i.test(wiz), 'Should show the ready state');
@@ -259,6 +266,14 @@ describe('Phase 3: multi-step setup checklist', () => {
     assert.ok(/gpu:\s*'done'/.test(wiz), 'GPU step should always be complete');
   });
 
+  it('Voice Check step runs the self-test on demand and is skippable', () => {
+    assert.ok(wiz.includes("id: 'check'"), 'Should have a voice check step');
+    assert.ok(wiz.includes('voiceSelfTest'), 'Should call voiceSelfTest');
+    assert.ok(wiz.includes("selfTest?.ok ? 'done'"), 'Step should complete when every check passes');
+    assert.ok(wiz.includes('checkSkipped'), 'Voice check should be skippable');
+    assert.ok(wiz.includes('{#each selfTest.checks as c'), 'Should list each check');
+  });
+
   it('re-check re-runs every step\'s detection', () => {
     assert.ok(wiz.includes('recheckAll'), 'Should expose a recheckAll');
     assert.ok(wiz.includes('detectStt') && wiz.includes('detectTts') && wiz.includes('detectGpuStep'), 'Should re-detect each step');
@@ -286,7 +301,10 @@ describe('Phase 4: provisioning polish', () => {
       'Should use the verified ONNX URL'
     );
     assert.ok(tts.includes('kokoro-download-progress'), 'Should emit a kokoro-download-progress event');
-    assert.ok(tts.includes('.with_extension("tmp")') || tts.includes('tmp_path'), 'Should download atomically via a temp file');
+    // The shared model manager downloader does the temp-file + rename dance
+    assert.ok(tts.includes('model_manager::download_asset'), 'Should download via the model manager');
+    const mgr = read('src-tauri/src/services/model_manager.rs');
+    assert.ok(mgr.includes('.with_extension("tmp")') || mgr.includes('tmp_path'), 'Should download atomically via a temp file');
 
     const voice = read('src-tauri/src/commands/voice.rs');
     assert.ok(voice.includes('pub async fn ensure_kokoro_model'), 'Should define the ensure_kokoro_model command');
@@ -367,3 +385,36 @@ describe('App.svelte: first-run gating', () => {
     assert.ok(app.includes('{:else if showWelcome}'), 'Should branch on showWelcome before the app shell');
   });
 });
+
+describe('backend: onboarding setup steps', () => {
+  const svc = read('src-tauri/src/services/onboarding.rs');
+  const cmds = read('src-tauri/src/commands/onboarding.rs');
+
+  it('covers models, the audio loop, VAD calibration and the provider', () => {
+    for (const step of ['"models"', '"audio"', '"vad"', '"provider"']) {
+      assert.ok(svc.includes(step), `Should parse the ${step} step`);
+    }
+  });
+
+  it('emits every step change as onboarding-step', () => {
+    assert.ok(svc.includes('"onboarding-step"'), 'Should name the onboarding-step event');
+    assert.ok(svc.includes('app.emit(EVENT'), 'Should emit step states');
+  });
+
+  it('plays the microphone take back on the output device', () => {
+    assert.ok(svc.includes('calibration::record'), 'Should record from the microphone');
+    assert.ok(svc.includes('self_test::play_clip'), 'Should play the take back');
+  });
+
+  it('saves the VAD calibration', () => {
+    assert.ok(svc.includes('"vadThreshold"') && svc.includes('"silenceTimeoutSecs"'));
+  });
+
+  it('registers the step commands', () => {
+    const lib = read('src-tauri/src/lib.rs');
+    for (const cmd of ['get_onboarding_status', 'run_onboarding_step', 'skip_onboarding_step']) {
+      assert.ok(cmds.includes(`pub fn ${cmd}`) || cmds.includes(`pub async fn ${cmd}`), `Should define ${cmd}`);
+      assert.ok(lib.includes(`onboarding_cmds::${cmd}`), `Should register ${cmd}`);
+    }
+  });
+});
@@ -0,0 +1,58 @@
+/**
+ * quiet-hours.test.cjs
+ *
+ * Covers scheduled quiet hours:
+ * - Backend service is started at launch and gates TTS and wake word
+ * - Tauri commands to query/configure/override it are registered
+ * - Toasts stay silent while active; settings expose schedule and override
+ */
+const { describe, it } = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs');
+const path = require('path');
+
+const root = path.join(__dirname, '..', '..');
+const read = (p) => fs.readFileSync(path.join(root, p), 'utf-8');
+
+describe('backend: quiet hours service', () => {
+  it('is started at launch and exposed as commands', () => {
+    const lib = read('src-tauri/src/lib.rs');
+    assert.ok(lib.includes('services::quiet_hours::start'), 'Should start the ticker in setup');
+    assert.ok(lib.includes('voice_cmds::get_quiet_hours_status'), 'Should register get_quiet_hours_status');
+    assert.ok(lib.includes('voice_cmds::configure_quiet_hours'), 'Should register configure_quiet_hours');
+    assert.ok(lib.includes('voice_cmds::set_quiet_hours_override'), 'Should register set_quiet_hours_override');
+  });
+
+  it('emits quiet-hours-changed on transitions', () => {
+    const src = read('src-tauri/src/services/quiet_hours.rs');
+    assert.ok(src.includes('"quiet-hours-changed"'), 'Should emit quiet-hours-changed');
+  });
+
+  it('suppresses TTS and wake word recordings while active', () => {
+    assert.ok(read('src-tauri/src/voice/pipeline/playback.rs').includes('quiet_hours::active()'), 'speak should check quiet hours');
+    assert.ok(read('src-tauri/src/voice/pipeline/mod.rs').includes('quiet_hours::active()'), 'wake word should check quiet hours');
+    assert.ok(read('src-tauri/src/commands/ai.rs').includes('quiet_hours::active()'), 'replies should be text-only');
+  });
+});
+
+describe('frontend: quiet hours', () => {
+  it('keeps non-error toasts silent but records them', () => {
+    const toast = read('src/lib/stores/toast.svelte.js');
+    assert.ok(toast.includes("severity !== 'error' && quietHoursStore.active"), 'Should check quiet hours');
+  });
+
+  it('follows quiet-hours-changed events', () => {
+    const store = read('src/lib/stores/quiet-hours.svelte.js');
+    assert.ok(store.includes("listen('quiet-hours-changed'"), 'Should listen for changes');
+    assert.ok(read('src/App.svelte').includes('initQuietHoursListeners()'), 'App should init listeners');
+  });
+
+  it('settings save the schedule with the local UTC offset and offer overrides', () => {
+    const comp = read('src/components/settings/QuietHoursSettings.svelte');
+    assert.ok(comp.includes('configureQuietHours('), 'Should call configureQuietHours');
+    assert.ok(comp.includes('-new Date().getTimezoneOffset()'), 'Should send the UTC offset');
+    assert.ok(comp.includes("override('quiet'"), 'Should offer quiet now');
+    assert.ok(comp.includes("override('auto')"), 'Should offer follow schedule');
+    assert.ok(read('src/components/settings/SettingsPanel.svelte').includes('<QuietHoursSettings'), 'Should render in General tab');
+  });
+});
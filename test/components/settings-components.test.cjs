@@ -130,6 +130,13 @@ describe('AISettings.svelte', () => {
     assert.ok(src.includes("import Select from '../shared/Select.svelte'"), 'Should import Select');
   });
 
+  it('has a response style preset selector saved to config', () => {
+    assert.ok(src.includes('RESPONSE_STYLE_OPTIONS'), 'Should define response style options');
+    assert.ok(src.includes("value: 'concise'"), 'Should offer concise preset');
+    assert.ok(src.includes("value: 'detailed'"), 'Should offer detailed preset');
+    assert.ok(src.includes('responseStyle,'), 'Should save responseStyle in the ai patch');
+  });
+
   it('imports Toggle component', () => {
     assert.ok(src.includes("import Toggle from '../shared/Toggle.svelte'"), 'Should import Toggle');
   });
@@ -313,6 +320,19 @@ describe('VoiceSettings.svelte', () => {
     assert.ok(src.includes('Output Device'), 'Should have output device selection');
   });
 
+  it('offers a network (RTP) input source', () => {
+    assert.ok(src.includes("'rtp://:5004'"), 'Should default the network source to local UDP port 5004');
+    assert.ok(src.includes('?from=&lt;phone IP&gt;'), 'Should explain the sender allow-list');
+    assert.ok(src.includes('label="Network Source"'), 'Should let the user edit the network source');
+  });
+
+  it('offers network (HTTP / Snapcast) outputs', () => {
+    assert.ok(src.includes("'http://0.0.0.0:8765'"), 'Should default the HTTP stream to port 8765');
+    assert.ok(src.includes('?token=${streamToken()}'), 'Should suggest a token for the HTTP stream');
+    assert.ok(src.includes("'snapcast://127.0.0.1:4953'"), 'Should default Snapcast to its TCP source port');
+    assert.ok(src.includes('label="Network Output"'), 'Should let the user edit the network output');
+  });
+
   it('has announcement toggles', () => {
     assert.ok(src.includes('announceStartup'), 'Should have announceStartup toggle');
     assert.ok(src.includes('announceProvider'), 'Should have announceProvider toggle');
@@ -328,6 +348,14 @@ describe('VoiceSettings.svelte', () => {
     assert.ok(src.includes('getVoiceStatus'), 'Should import getVoiceStatus');
   });
 
+  it('applies TTS voice, speed and volume live when the adapter is unchanged', () => {
+    assert.ok(src.includes('setTtsVoice(ttsVoice)'), 'Should apply voice to running pipeline');
+    assert.ok(src.includes('setTtsSpeed(ttsSpeed)'), 'Should apply speed to running pipeline');
+    assert.ok(src.includes('setTtsVolume(ttsVolume)'), 'Should apply volume to running pipeline');
+    assert.ok(src.includes('setTtsEffects(ttsEffects)'), 'Should apply voice effects to running pipeline');
+    assert.ok(src.includes('ttsAdapter === prevTtsAdapter'), 'Should skip live apply on adapter switch');
+  });
+
   it('imports GPU and model management API functions', () => {
     assert.ok(src.includes('detectGpu'), 'Should import detectGpu');
     assert.ok(src.includes('listSttModels'), 'Should import listSttModels');
@@ -345,6 +373,53 @@ describe('VoiceSettings.svelte', () => {
     assert.ok(src.includes('refreshInstalledModels'), 'Should have refreshInstalledModels function');
   });
 
+  it('has STT language selector wired into model download', () => {
+    assert.ok(src.includes('STT_LANGUAGES'), 'Should use STT_LANGUAGES options');
+    assert.ok(src.includes('currentSTTAdapter.showLanguage'), 'Should check showLanguage flag');
+    assert.ok(src.includes('ensureSttModel(sttModelSize, sttLanguage)'), 'Should download the model for the chosen language');
+  });
+
+  it('has a turn policy selector for speaking mid-reply', () => {
+    assert.ok(src.includes('label="Speaking While the AI Answers"'), 'Should have the turn policy select');
+    assert.ok(src.includes("value: 'reject'"), 'Should offer the reject policy');
+    assert.ok(src.includes('turnPolicy,'), 'Should save turnPolicy in the voice patch');
+  });
+
+  it('has initial prompt and vocabulary inputs for whisper', () => {
+    assert.ok(src.includes('label="Initial Prompt"'), 'Should have an initial prompt input');
+    assert.ok(src.includes('label="Vocabulary"'), 'Should have a vocabulary input');
+    assert.ok(src.includes("sttVocabulary: sttVocabulary.split(',')"), 'Should save vocabulary as a list');
+  });
+
+  it('has transcript rule toggles saved to transcriptRules', () => {
+    assert.ok(src.includes('label="Numbers as Digits"'), 'Should have a number normalization toggle');
+    assert.ok(src.includes('label="Mask Profanity"'), 'Should have a profanity toggle');
+    assert.ok(src.includes('label="Auto-Capitalize"'), 'Should have an auto-capitalize toggle');
+    assert.ok(src.includes('transcriptRules: {'), 'Should save the toggles in the transcriptRules section');
+    assert.ok(src.includes('rulesChanged'), 'Should restart the pipeline when the rules change');
+  });
+
+  it('has a capture source select for recording system audio', () => {
+    assert.ok(src.includes('label="Capture"'), 'Should have a capture source select');
+    assert.ok(src.includes("value: 'system'"), 'Should offer system audio');
+    assert.ok(src.includes('captureSource,'), 'Should save captureSource');
+    assert.ok(src.includes('captureChanged'), 'Should restart the pipeline when the source changes');
+  });
+
+  it('has pipeline sample rate and chunk size selects', () => {
+    assert.ok(src.includes('label="Pipeline Sample Rate"'), 'Should have a sample rate select');
+    assert.ok(src.includes("value: '8000'"), 'Should offer 8 kHz for telephony sources');
+    assert.ok(src.includes('label="Audio Chunk Size"'), 'Should have a chunk size select');
+    assert.ok(src.includes('sampleRate,') && src.includes('chunkMs,'), 'Should save both in the voice patch');
+    assert.ok(src.includes('formatChanged'), 'Should restart the pipeline when the format changes');
+  });
+
+  it('has a translate-to-English option for non-English whisper', () => {
+    assert.ok(src.includes("value: 'translate'"), 'Should offer the translate task');
+    assert.ok(src.includes("sttLanguage !== 'en'"), 'Should only offer translation for non-English speech');
+    assert.ok(src.includes('sttTask,'), 'Should save sttTask in the voice patch');
+  });
+
   it('renders GPU toggle conditional on whisper-local', () => {
     assert.ok(src.includes('GPU Acceleration (CUDA)'), 'Should have GPU toggle label');
     assert.ok(src.includes('currentSTTAdapter.showGpu'), 'Should check showGpu flag');
@@ -370,10 +445,22 @@ describe('VoiceSettings.svelte', () => {
     assert.ok(src.includes('progress: 0'), 'Should start progress bar at 0');
   });
 
+  it('reports settings the voice engine would reject after saving', () => {
+    assert.ok(src.includes('validateVoiceConfig()'), 'Should call validateVoiceConfig');
+    assert.ok(src.includes('validation?.data?.issues'), 'Should read the issues');
+  });
+
   it('restarts voice pipeline when STT model changes', () => {
     assert.ok(src.includes('restartVoice()'), 'Should call restartVoice');
     assert.ok(src.includes('sttChanged'), 'Should track whether STT changed');
   });
+
+  it('enrolls speakers from recent turn-archive recordings', () => {
+    assert.ok(src.includes('Speaker Identification'), 'Should have a speaker identification section');
+    assert.ok(src.includes('turnArchiveList()'), 'Should read the turn archive');
+    assert.ok(src.includes('enrollSpeaker(name'), 'Should call enrollSpeaker');
+    assert.ok(src.includes('speakerChanged'), 'Should restart voice when speaker ID settings change');
+  });
 });
 
 // ---- KeybindRecorder.svelte ----
@@ -435,6 +522,12 @@ describe('TTSConfig.svelte', () => {
     assert.ok(src.includes("label=\"Volume\""), 'Should have Volume label');
   });
 
+  it('has a voice effect preset selector', () => {
+    assert.ok(src.includes('ttsEffects = $bindable([])'), 'Should have bindable ttsEffects');
+    assert.ok(src.includes("label=\"Voice Effect\""), 'Should have Voice Effect label');
+    assert.ok(src.includes("robot: ['robot']"), 'Should offer the robot preset');
+  });
+
   it('has TTS engine selector', () => {
     assert.ok(src.includes("label=\"TTS Engine\""), 'Should have TTS Engine label');
   });
@@ -443,6 +536,16 @@ describe('TTSConfig.svelte', () => {
     assert.ok(src.includes("label=\"Voice\""), 'Should have Voice label');
   });
 
+  it('has voice blend input for adapters that support it', () => {
+    assert.ok(src.includes('supportsVoiceBlend'), 'Should gate on supportsVoiceBlend');
+    assert.ok(src.includes("label=\"Voice Blend\""), 'Should have Voice Blend label');
+  });
+
+  it('has a button to clear the TTS cache', () => {
+    assert.ok(src.includes('clearTtsCache'), 'Should call clearTtsCache');
+    assert.ok(src.includes('Clear voice cache'), 'Should have Clear voice cache button');
+  });
+
   it('uses $props for bindable TTS values', () => {
     assert.ok(src.includes('$props()'), 'Should use $props');
     assert.ok(src.includes('$bindable'), 'Should use $bindable for two-way binding');
@@ -470,6 +573,10 @@ describe('voice-adapters.js', () => {
     assert.ok(src.includes("piper:"), 'Should support piper TTS');
   });
 
+  it('supports system TTS', () => {
+    assert.ok(src.includes("system:"), 'Should support system TTS');
+  });
+
   it('exports STT_REGISTRY', () => {
     assert.ok(src.includes('export const STT_REGISTRY'), 'Should export STT_REGISTRY');
   });
@@ -486,6 +593,11 @@ describe('voice-adapters.js', () => {
     assert.ok(src.includes('export function formatKeybind'), 'Should export formatKeybind');
   });
 
+  it('formats gamepad keybinds', () => {
+    assert.ok(src.includes('export const GAMEPAD_BUTTON_NAMES'), 'Should export GAMEPAD_BUTTON_NAMES');
+    assert.ok(src.includes('pad:'), 'Should handle pad:ID keybinds');
+  });
+
   it('has large-v3-turbo model size option', () => {
     assert.ok(src.includes("'large-v3-turbo'"), 'Should have large-v3-turbo model size');
     assert.ok(src.includes('Turbo'), 'Should have Turbo label');
@@ -675,6 +787,13 @@ describe('BehaviorSettings.svelte', () => {
     assert.ok(src.includes("import Button from '../shared/Button.svelte'"), 'Should import Button');
   });
 
+  it('has web search backend settings', () => {
+    assert.ok(src.includes("label=\"Web Search Engine\""), 'Should have search engine selector');
+    assert.ok(src.includes('searchFallback'), 'Should have fallback toggle');
+    assert.ok(src.includes('searxngUrl'), 'Should have SearxNG URL');
+    assert.ok(src.includes('braveApiKey'), 'Should have Brave API key');
+  });
+
   it('has user name input', () => {
     assert.ok(src.includes('userName'), 'Should have userName state');
     assert.ok(src.includes("label=\"Name\""), 'Should have Name label');
@@ -0,0 +1,37 @@
+/**
+ * voice-catalog.test.cjs
+ *
+ * The MCP list_voices tool answers from src-tauri/src/voice/tts/voices.rs,
+ * which mirrors the voice lists of ADAPTER_REGISTRY in voice-adapters.js.
+ * These checks keep the two in step.
+ */
+const { describe, it } = require('node:test');
+const assert = require('node:assert/strict');
+const fs = require('fs');
+const path = require('path');
+
+const root = path.join(__dirname, '..', '..');
+const read = (p) => fs.readFileSync(path.join(root, p), 'utf-8');
+
+const js = read('src/lib/voice-adapters.js');
+const rust = read('src-tauri/src/voice/tts/voices.rs');
+const ttsSection = js.slice(js.indexOf('ADAPTER_REGISTRY'), js.indexOf('STT_REGISTRY'));
+
+describe('voice catalog: Rust mirrors voice-adapters.js', () => {
+  it('lists every adapter', () => {
+    const adapters = [...ttsSection.matchAll(/^ {2}'?([\w-]+)'?: \{$/gm)].map((m) => m[1]);
+    assert.ok(adapters.length >= 8, 'Should find the TTS adapters');
+    for (const adapter of adapters) {
+      assert.ok(rust.includes(`"${adapter}" =>`), `voices.rs should map adapter ${adapter}`);
+    }
+  });
+
+  it('lists every voice with the same label', () => {
+    const lists = [...ttsSection.matchAll(/voices: \[([^\]]*)\]/g)].map((m) => m[1]).join('\n');
+    const voices = [...lists.matchAll(/\{ value: '([^']+)', label: '([^']+)' \}/g)];
+    assert.ok(voices.length >= 40, 'Should find the TTS voices');
+    for (const [, value, label] of voices) {
+      assert.ok(rust.includes(`voice("${value}", "${label}")`), `voices.rs should list ${value} (${label})`);
+    }
+  });
+});
@@ -52,10 +52,10 @@ describe('voice-greeting.js -- TTS call', () => {
     );
   });
 
-  it('calls speakText with greeting message', () => {
+  it('calls speakText with greeting message, queued behind any reply', () => {
     assert.ok(
-      src.includes("speakText('Voice Mirror is Online')"),
-      'Should speak Voice Mirror is Online'
+      src.includes("speakText('Voice Mirror is Online', 'queue')"),
+      'Should queue Voice Mirror is Online'
     );
   });
 
@@ -77,7 +77,7 @@ describe('ai-status: exports', () => {
 // ============ Getters ============
 
 describe('ai-status: store getters', () => {
-  const expectedGetters = ['running', 'providerType', 'displayName', 'error', 'starting', 'isCliProvider', 'isApiProvider'];
+  const expectedGetters = ['running', 'providerType', 'displayName', 'error', 'starting', 'toolTrace', 'isCliProvider', 'isApiProvider'];
 
   for (const getter of expectedGetters) {
     it(`has getter "${getter}"`, () => {
@@ -106,7 +106,7 @@ describe('ai-status: store getters', () => {
 // ============ $state reactivity ============
 
 describe('ai-status: $state reactivity', () => {
-  const stateVars = ['running', 'providerType', 'displayName', 'error', 'starting'];
+  const stateVars = ['running', 'providerType', 'displayName', 'error', 'starting', 'toolTrace'];
 
   for (const varName of stateVars) {
     it(`uses $state for "${varName}"`, () => {
@@ -158,6 +158,7 @@ describe('ai-status: event listeners', () => {
     'ai-stream-end',
     'ai-response',
     'ai-tool-calls',
+    'ai-tool-plan-step',
   ];
 
   for (const eventName of expectedEvents) {
@@ -215,3 +216,20 @@ describe('ai-status: API streaming integration', () => {
     assert.ok(src.includes('speakText'), 'Should call speakText for TTS on API responses');
   });
 });
+
+// ============ Tool plan trace ============
+
+describe('ai-status: tool plan trace', () => {
+  it('adds plan steps from ai-tool-plan-step', () => {
+    assert.ok(src.includes('aiStatusStore._addToolStep(event.payload)'));
+  });
+
+  it('replaces a call with its result by toolCallId', () => {
+    assert.ok(src.includes('s.toolCallId === step.toolCallId'));
+  });
+
+  it('clears the trace when the reply or an error arrives', () => {
+    const clears = src.match(/aiStatusStore\._clearToolTrace\(\)/g) || [];
+    assert.equal(clears.length, 2);
+  });
+});
@@ -73,6 +73,11 @@ describe('navigation: store methods', () => {
   it('has initSidebarState method', () => {
     assert.ok(src.includes('initSidebarState('), 'Store should have initSidebarState method');
   });
+
+  it('has openSettings method that requests a settings tab', () => {
+    assert.ok(src.includes('openSettings(tab)'), 'Store should have openSettings method');
+    assert.ok(src.includes('settingsRequest = { tab }'), 'Should record the requested tab');
+  });
 });
 
 // ============ Store getters ============
@@ -130,11 +130,22 @@ describe('voice: _handleVoiceEvent switch cases', () => {
     'ready',
     'starting',
     'stopping',
+    'paused',
+    'resumed',
+    'microphone_open',
+    'microphone_closed',
+    'microphone_muted',
     'transcription',
     'speaking_start',
+    'speaking_progress',
     'speaking_end',
     'error',
     'audio_devices',
+    'recording_stop',
+    'recording_elapsed',
+    'engine_recovered',
+    'latency_ladder',
+    'pipeline_stalled',
   ];
 
   for (const eventType of eventTypes) {
@@ -159,11 +170,23 @@ describe('voice: transcription routing to AI', () => {
 
   it('calls routeTranscriptionToAI when transcription arrives', () => {
     assert.ok(
-      src.includes('routeTranscriptionToAI(text)'),
-      'Should route (dictionary-corrected) transcription text to AI'
+      src.includes('routeTranscriptionToAI(text, data.session_id, data.turn)'),
+      'Should route (dictionary-corrected) transcription text to AI with its turn'
     );
   });
 
+  it('follows the consumers a transcriptRoutes entry picked', () => {
+    assert.ok(src.includes('Array.isArray(data.routes)'), 'Should check for backend routes');
+    assert.ok(src.includes("data.routes.includes('dictation')"), 'Should inject text routed to dictation');
+    assert.ok(src.includes("data.routes.includes('provider')"), 'Should forward text routed to the provider');
+  });
+
+  it('keeps system-audio transcriptions as notes instead of routing them', () => {
+    assert.ok(src.includes("data.source === 'system'"), 'Should check the transcription source');
+    assert.ok(src.includes('get systemTranscripts()'), 'Should expose the system transcripts');
+    assert.ok(src.includes('clearSystemTranscripts()'), 'Should allow clearing them');
+  });
+
   it('adds transcription as user message in chat', () => {
     assert.ok(
       src.includes("chatStore.addMessage('user'") || src.includes('chatStore.addMessage("user"'),
@@ -171,25 +194,30 @@ describe('voice: transcription routing to AI', () => {
     );
   });
 
-  it('checks isApiProvider to decide routing path', () => {
+  it('sends transcriptions through sendTextMessage with imagePath and imageDataUrl', () => {
     assert.ok(
-      src.includes('aiStatusStore.isApiProvider'),
-      'Should check aiStatusStore.isApiProvider for routing'
+      src.includes('sendTextMessage(text, { imagePath, imageDataUrl, speak: true, voice: true })'),
+      'Should route transcriptions through the shared sendTextMessage path'
     );
   });
 
-  it('uses aiPtyInput with imagePath and imageDataUrl for API providers', () => {
-    assert.ok(
-      src.includes('aiPtyInput(text, imagePath, imageDataUrl)'),
-      'Should call aiPtyInput with imagePath and imageDataUrl for API providers'
-    );
+  it('drops rejected and replaced voice turns from the chat', () => {
+    assert.ok(src.includes("turn === 'rejected'"), 'Should handle a rejected turn');
+    assert.ok(src.includes('chatStore.removeMessage(messageId)'), 'Should remove the rejected message');
+    assert.ok(src.includes('chatStore.removeMessage(waitingVoiceMessageId)'), 'Should remove the replaced waiting message');
   });
 
-  it('uses writeUserMessage with imagePath and imageDataUrl for CLI providers', () => {
-    assert.ok(
-      src.includes('writeUserMessage(text, null, null, imagePath, imageDataUrl)'),
-      'Should call writeUserMessage with imagePath and imageDataUrl for CLI/MCP providers'
-    );
+  it('skips TTS for inbox replies marked speak: false', () => {
+    assert.ok(src.includes('payload.speak !== false'), 'Should honor the reply speak flag');
+  });
+
+  it('passes the voice_send speech priority to speakText', () => {
+    assert.ok(src.includes('speakText(spoken, payload.priority'), 'Should forward payload.priority');
+  });
+
+  it("speaks inbox replies in the sender's voice", () => {
+    assert.ok(src.includes('payload.voice'), 'Should forward the sender voice');
+    assert.ok(src.includes('payload.announce'), 'Should say the sender name when announced');
   });
 
   it('imports attachmentsStore for pending attachments', () => {
@@ -451,3 +479,108 @@ describe('voice: initVoiceListeners idempotency', () => {
     );
   });
 });
+
+// ============ Word-level speaking progress ============
+
+describe('voice: speaking progress', () => {
+  it('exposes speakingText and speakingProgress getters', () => {
+    assert.ok(src.includes('get speakingText()'), 'Should expose speakingText');
+    assert.ok(src.includes('get speakingProgress()'), 'Should expose speakingProgress');
+  });
+
+  it('maps char_offset from speaking_progress events', () => {
+    assert.ok(
+      src.includes('charOffset: data.char_offset'),
+      'Should store the char offset of the word being spoken'
+    );
+  });
+
+  it('clears progress on speaking_end', () => {
+    const endCase = src.slice(src.indexOf("case 'speaking_end':"));
+    assert.ok(
+      endCase.slice(0, 200).includes('speakingProgress = null'),
+      'Should clear progress when speech ends'
+    );
+  });
+});
+
+// ============ Error codes ============
+
+describe('voice: error codes', () => {
+  it('keeps the backend error code of the last error', () => {
+    assert.ok(src.includes('get errorCode()'), 'Should expose errorCode');
+    assert.ok(src.includes('errorCode = data.code'), 'Should read the code from error events');
+  });
+
+  it('offers remediation for known codes', () => {
+    for (const code of ['mic_unavailable', 'audio_device', 'model_missing', 'network', 'config']) {
+      assert.ok(src.includes(`${code}:`), `Should have a remedy for ${code}`);
+    }
+    assert.ok(src.includes("navigationStore.openSettings('voice')"), 'Remedy action should open Voice settings');
+  });
+
+  it('shows coded start failures through showVoiceError', () => {
+    assert.ok(src.includes('showVoiceError(msg, result.code)'), 'startVoiceEngine should pass the code');
+  });
+});
+
+describe('voice: local commands', () => {
+  it('handles local_command events without routing to the AI', () => {
+    const start = src.indexOf("case 'local_command':");
+    assert.ok(start > 0, 'Should handle local_command');
+    const body = src.slice(start, src.indexOf('break;', start));
+    assert.ok(!body.includes('routeTranscriptionToAI'), 'Should not forward the command');
+    assert.ok(body.includes('loadConfig()'), 'Should reload config after a volume change');
+  });
+
+  it('tracks engines still on their first load', () => {
+    assert.ok(src.includes('get loadingEngines()'), 'Should expose loadingEngines');
+    const start = src.indexOf("case 'engine_load':");
+    assert.ok(start > 0, 'Should handle engine_load');
+    const body = src.slice(start, src.indexOf('break;', start));
+    assert.ok(body.includes("data.status === 'loading'"), 'Should add an engine while it loads');
+    const stopping = src.slice(src.indexOf("case 'stopping':"));
+    assert.ok(stopping.slice(0, stopping.indexOf('break;')).includes('loadingEngines = []'), 'Should clear on stop');
+  });
+});
+
+describe('voice: spoken confirmation', () => {
+  it('tracks a destructive tool waiting for a spoken answer', () => {
+    assert.ok(src.includes('get pendingConfirm()'), 'Should expose pendingConfirm');
+    const start = src.indexOf("case 'confirm_prompt':");
+    assert.ok(start > 0, 'Should handle confirm_prompt');
+    assert.ok(src.slice(start, src.indexOf('break;', start)).includes('pendingConfirm = {'), 'Should store the question');
+    const answer = src.indexOf("case 'confirm_answer':");
+    assert.ok(answer > 0, 'Should handle confirm_answer');
+    const body = src.slice(answer, src.indexOf("case 'engine_load':", answer));
+    assert.ok(body.includes('pendingConfirm = null'), 'Should clear the question once answered');
+    assert.ok(!body.includes('routeTranscriptionToAI'), 'Should not forward the answer');
+  });
+});
+
+describe('voice: spoken questions', () => {
+  it('tracks a voice_ask question until it is answered', () => {
+    assert.ok(src.includes('get pendingQuestion()'), 'Should expose pendingQuestion');
+    const start = src.indexOf("case 'ask_question':");
+    assert.ok(start > 0, 'Should handle ask_question');
+    assert.ok(src.slice(start, src.indexOf('break;', start)).includes('pendingQuestion = data.question'), 'Should store the question');
+    const answer = src.indexOf("case 'ask_answer':");
+    assert.ok(answer > 0, 'Should handle ask_answer');
+    assert.ok(src.slice(answer, src.indexOf("case 'engine_load':", answer)).includes('pendingQuestion = null'), 'Should clear it');
+  });
+});
+
+describe('voice: conversation sessions', () => {
+  it('tracks the open session from lifecycle events', () => {
+    assert.ok(src.includes('get session()'), 'Should expose session');
+    for (const event of ['session_start', 'turn_complete', 'session_end']) {
+      assert.ok(src.includes(`case '${event}':`), `Should handle ${event}`);
+    }
+    const stopping = src.slice(src.indexOf("case 'stopping':"));
+    assert.ok(stopping.slice(0, stopping.indexOf('break;')).includes('session = null'), 'Should clear on stop');
+  });
+
+  it('tags routed transcriptions with their session and turn', () => {
+    assert.ok(src.includes('meta.sessionId = sessionId'), 'Should put the session on the chat message');
+  });
+});